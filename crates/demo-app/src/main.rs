@@ -495,14 +495,16 @@ fn main() -> Result<()> {
 
                                     // Add each glyph with its position, color, and z-index
                                     for glyph in glyphs {
+                                        let color = glyph.color.unwrap_or(text_draw.run.color);
                                         glyph_draws.push((
                                             [
                                                 transformed_pos[0] + glyph.offset[0],
                                                 transformed_pos[1] + glyph.offset[1],
                                             ],
                                             glyph,
-                                            text_draw.run.color,
+                                            color,
                                             text_draw.z,
+                                            text_draw.clip,
                                         ));
                                     }
                                 }
@@ -512,46 +514,56 @@ fn main() -> Result<()> {
                             // eprintln!("🔍 Total glyph_draws: {}", glyph_draws.len());
 
                             // Convert image and SVG draws to the format expected by render_unified
-                            let image_draws: Vec<(std::path::PathBuf, [f32; 2], [f32; 2], i32)> =
-                                unified_scene
-                                    .image_draws
-                                    .iter()
-                                    .map(|d| (d.path.clone(), d.origin, d.size, d.z))
-                                    .collect();
+                            let image_draws: Vec<engine_core::ImageDraw> = unified_scene
+                                .image_draws
+                                .iter()
+                                .map(|d| (d.path.clone(), d.origin, d.size, d.z, d.clip))
+                                .collect();
 
-                            let svg_draws: Vec<(
-                                std::path::PathBuf,
-                                [f32; 2],
-                                [f32; 2],
-                                Option<engine_core::SvgStyle>,
-                                i32,
-                                engine_core::Transform2D,
-                            )> = unified_scene
+                            let svg_draws: Vec<engine_core::SvgDraw> = unified_scene
                                 .svg_draws
                                 .iter()
-                                .map(|d| (d.path.clone(), d.origin, d.size, None, d.z, d.transform))
+                                .map(|d| {
+                                    (
+                                        d.path.clone(),
+                                        d.origin,
+                                        d.size,
+                                        None,
+                                        d.z,
+                                        d.transform,
+                                        d.clip,
+                                    )
+                                })
                                 .collect();
 
                             // Use unified rendering
-                            passes.render_unified(
-                                &mut encoder,
-                                engine.allocator_mut(),
-                                &view,
-                                size.width,
-                                size.height,
-                                &unified_scene.gpu_scene,
-                                &glyph_draws,
-                                &svg_draws,
-                                &image_draws,
-                                wgpu::Color {
+                            passes.set_clips(&unified_scene.clips, &queue);
+                            let target = engine_core::UnifiedTarget {
+                                view: &view,
+                                width: size.width,
+                                height: size.height,
+                                clear: wgpu::Color {
                                     r: 30.0 / 255.0,
                                     g: 30.0 / 255.0,
                                     b: 40.0 / 255.0,
                                     a: 1.0,
                                 },
-                                !use_intermediate, // direct rendering if not using intermediate
+                                direct: !use_intermediate, // direct rendering if not using intermediate
+                                preserve_surface: false,
+                            };
+                            let draws = engine_core::UnifiedDraws {
+                                glyph_draws: &glyph_draws,
+                                svg_draws: &svg_draws,
+                                image_draws: &image_draws,
+                                pattern_draws: &unified_scene.pattern_draws,
+                                ..engine_core::UnifiedDraws::scene(&unified_scene.gpu_scene)
+                            };
+                            passes.render_unified(
+                                &mut encoder,
+                                engine.allocator_mut(),
+                                target,
+                                draws,
                                 &queue,
-                                false, // preserve_surface
                             );
                         }
                     }
@@ -92,12 +92,7 @@ impl CenteredRectScene {
                     w: rect_width,
                     h: rect_height,
                 },
-                radii: RoundedRadii {
-                    tl: 16.0,
-                    tr: 16.0,
-                    br: 16.0,
-                    bl: 16.0,
-                },
+                radii: RoundedRadii::uniform(16.0),
             },
             Brush::Solid(white_fill),
             2,
@@ -113,14 +108,9 @@ impl CenteredRectScene {
                     w: rect_width,
                     h: rect_height,
                 },
-                radii: RoundedRadii {
-                    tl: 16.0,
-                    tr: 16.0,
-                    br: 16.0,
-                    bl: 16.0,
-                },
+                radii: RoundedRadii::uniform(16.0),
             },
-            engine_core::Stroke { width: 2.0 },
+            engine_core::Stroke::round(2.0),
             Brush::Solid(border_color),
             3,
         );
@@ -130,18 +120,18 @@ impl CenteredRectScene {
             let highlight = Brush::Solid(ColorLinPremul::from_srgba(0, 255, 255, 0.85));
             match shape {
                 HitShape::Rect(r) => {
-                    painter.stroke_rect(*r, Stroke { width: 2.0 }, highlight.clone(), 10);
+                    painter.stroke_rect(*r, Stroke::new(2.0), highlight.clone(), 10);
                 }
                 HitShape::RoundedRect(rr) => {
-                    painter.stroke_rounded_rect(*rr, Stroke { width: 2.0 }, highlight.clone(), 10);
+                    painter.stroke_rounded_rect(*rr, Stroke::round(2.0), highlight.clone(), 10);
                 }
                 HitShape::StrokeRect { rect, .. } => {
-                    painter.stroke_rect(*rect, Stroke { width: 2.0 }, highlight.clone(), 10);
+                    painter.stroke_rect(*rect, Stroke::new(2.0), highlight.clone(), 10);
                 }
-                HitShape::StrokeRoundedRect { rrect, .. } => {
+                HitShape::StrokeRoundedRect { rrect, .. } | HitShape::Border { rrect, .. } => {
                     painter.stroke_rounded_rect(
                         *rrect,
-                        Stroke { width: 2.0 },
+                        Stroke::round(2.0),
                         highlight.clone(),
                         10,
                     );
@@ -155,12 +145,12 @@ impl CenteredRectScene {
                     );
                 }
                 HitShape::PathBBox { rect } => {
-                    painter.stroke_rect(*rect, Stroke { width: 2.0 }, highlight.clone(), 10);
+                    painter.stroke_rect(*rect, Stroke::new(2.0), highlight.clone(), 10);
                 }
                 HitShape::Hyperlink { rect, .. } => {
-                    painter.stroke_rect(*rect, Stroke { width: 2.0 }, highlight.clone(), 10);
+                    painter.stroke_rect(*rect, Stroke::new(2.0), highlight.clone(), 10);
                 }
-                HitShape::Text | HitShape::BoxShadow { .. } => {}
+                HitShape::Text { .. } | HitShape::BoxShadow { .. } => {}
             }
         }
 
@@ -1,4 +1,4 @@
-use engine_core::{Brush, ColorLinPremul};
+use engine_core::{Brush, ColorLinPremul, SpreadMode};
 use engine_core::{DisplayList, Painter, PassManager, Viewport};
 
 use super::{Scene, SceneKind};
@@ -96,6 +96,7 @@ fn build_circle_dl(viewport: Viewport) -> DisplayList {
                 ),
                 (1.0, ColorLinPremul::from_srgba_u8([0x10, 0xb9, 0x81, 0xff])),
             ],
+            spread: SpreadMode::Pad,
         },
         0,
     );
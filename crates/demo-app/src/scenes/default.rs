@@ -1,4 +1,4 @@
-use engine_core::{Brush, ColorLinPremul, Rect};
+use engine_core::{Brush, ColorLinPremul, Rect, SpreadMode};
 use engine_core::{DisplayList, Painter, PassManager, Viewport};
 
 use super::{Scene, SceneKind};
@@ -25,6 +25,7 @@ impl Scene for DefaultScene {
                 pos: [60.0, 36.0],
                 size: 28.0,
                 color: ColorLinPremul::from_srgba_u8([255, 255, 255, 255]),
+                spans: Vec::new(),
             },
             1,
         );
@@ -55,6 +56,7 @@ impl Scene for DefaultScene {
                     (0.5, ColorLinPremul::from_srgba_u8([140, 180, 255, 255])),
                     (1.0, ColorLinPremul::from_srgba_u8([140, 255, 180, 255])),
                 ],
+                spread: SpreadMode::Pad,
             },
             0,
         );
@@ -67,12 +69,7 @@ impl Scene for DefaultScene {
                     w: 300.0,
                     h: 180.0,
                 },
-                radii: engine_core::RoundedRadii {
-                    tl: 24.0,
-                    tr: 32.0,
-                    br: 24.0,
-                    bl: 24.0,
-                },
+                radii: engine_core::RoundedRadii::new(24.0, 32.0, 24.0, 24.0),
             },
             Brush::Solid(ColorLinPremul::from_srgba_u8([238, 154, 106, 255])),
             0,
@@ -95,6 +92,7 @@ impl Scene for DefaultScene {
                     (0.0, ColorLinPremul::from_srgba_u8([255, 255, 190, 255])),
                     (1.0, ColorLinPremul::from_srgba_u8([220, 220, 80, 255])),
                 ],
+                spread: SpreadMode::Pad,
             },
             0,
         );
@@ -67,12 +67,7 @@ impl OverlayScene {
                     w: panel_w,
                     h: panel_h,
                 },
-                radii: RoundedRadii {
-                    tl: 14.0,
-                    tr: 14.0,
-                    br: 14.0,
-                    bl: 14.0,
-                },
+                radii: RoundedRadii::uniform(14.0),
             };
             let panel_fill = ColorLinPremul::from_srgba_u8([0xff, 0xff, 0xff, 0xff]);
             // A close-zone hit region above scrim and below panel
@@ -103,12 +98,7 @@ impl OverlayScene {
                     w: bw,
                     h: bh,
                 },
-                radii: RoundedRadii {
-                    tl: 10.0,
-                    tr: 10.0,
-                    br: 10.0,
-                    bl: 10.0,
-                },
+                radii: RoundedRadii::uniform(10.0),
             };
             let fill = ColorLinPremul::from_srgba_u8([0xee, 0xee, 0xee, 0xff]);
             p.rounded_rect(r, Brush::Solid(fill), 2);
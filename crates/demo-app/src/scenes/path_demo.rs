@@ -1,5 +1,5 @@
 use super::{Scene, SceneKind};
-use engine_core::{ColorLinPremul, DisplayList, FillRule, Painter, Path, PathCmd, Viewport};
+use engine_core::{Brush, ColorLinPremul, DisplayList, FillRule, Painter, Path, PathCmd, Viewport};
 
 pub struct PathDemoScene;
 
@@ -40,7 +40,7 @@ impl Scene for PathDemoScene {
             fill_rule: FillRule::NonZero,
         };
         let yellow = ColorLinPremul::from_srgba(255, 220, 30, 1.0);
-        p.fill_path(path, yellow, 0);
+        p.fill_path(path, Brush::Solid(yellow), 0);
         Some(p.finish())
     }
 
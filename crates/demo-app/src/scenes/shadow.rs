@@ -50,12 +50,7 @@ impl Scene for ShadowScene {
                 w: width as f32,
                 h: height as f32,
             },
-            radii: RoundedRadii {
-                tl: 0.0,
-                tr: 0.0,
-                br: 0.0,
-                bl: 0.0,
-            },
+            radii: RoundedRadii::uniform(0.0),
         };
         let bg_color = ColorLinPremul::from_srgba_u8([0xf4, 0xf6, 0xfa, 0xff]);
         passes.draw_filled_rounded_rect(
@@ -81,12 +76,7 @@ impl Scene for ShadowScene {
                 w: rect_w,
                 h: rect_h,
             },
-            radii: RoundedRadii {
-                tl: 22.0,
-                tr: 22.0,
-                br: 22.0,
-                bl: 22.0,
-            },
+            radii: RoundedRadii::uniform(22.0),
         };
         let spec = BoxShadowSpec {
             // Match CSS: box-shadow: 0px 18px 12px 8px rgba(0,0,0,0.3)
@@ -57,6 +57,7 @@ impl UiElementsScene {
                 pos: [col1_x, y],
                 size: 24.0,
                 color: Color::from_srgba_u8([235, 240, 255, 255]),
+                spans: Vec::new(),
             },
             2,
         );
@@ -70,12 +71,7 @@ impl UiElementsScene {
                 w: 160.0,
                 h: 36.0,
             },
-            radii: RoundedRadii {
-                tl: 8.0,
-                tr: 8.0,
-                br: 8.0,
-                bl: 8.0,
-            },
+            radii: RoundedRadii::uniform(8.0),
         };
         p.rounded_rect(
             btn_r,
@@ -88,6 +84,7 @@ impl UiElementsScene {
                 pos: [col1_x + 12.0, y + 24.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([255, 255, 255, 255]),
+                spans: Vec::new(),
             },
             4,
         );
@@ -98,12 +95,7 @@ impl UiElementsScene {
                 w: 180.0,
                 h: 36.0,
             },
-            radii: RoundedRadii {
-                tl: 8.0,
-                tr: 8.0,
-                br: 8.0,
-                bl: 8.0,
-            },
+            radii: RoundedRadii::uniform(8.0),
         };
         p.rounded_rect(
             btn2_r,
@@ -116,6 +108,7 @@ impl UiElementsScene {
                 pos: [col1_x + 188.0, y + 24.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([255, 255, 255, 255]),
+                spans: Vec::new(),
             },
             4,
         );
@@ -156,12 +149,7 @@ impl UiElementsScene {
                 w: (cb0.w - 2.0 * inset).max(0.0),
                 h: (cb0.h - 2.0 * inset).max(0.0),
             };
-            let inner_r = RoundedRadii {
-                tl: 1.5,
-                tr: 1.5,
-                br: 1.5,
-                bl: 1.5,
-            };
+            let inner_r = RoundedRadii::uniform(1.5);
             p.rounded_rect(
                 RoundedRect {
                     rect: inner,
@@ -178,6 +166,7 @@ impl UiElementsScene {
                 pos: [col1_x + 28.0, y + 16.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([240, 240, 240, 255]),
+                spans: Vec::new(),
             },
             4,
         );
@@ -192,12 +181,7 @@ impl UiElementsScene {
         // Base box with small rounded corners to match focus border
         let cb1_base = RoundedRect {
             rect: cb1,
-            radii: RoundedRadii {
-                tl: 2.0,
-                tr: 2.0,
-                br: 2.0,
-                bl: 2.0,
-            },
+            radii: RoundedRadii::uniform(2.0),
         };
         p.rounded_rect(
             cb1_base,
@@ -207,16 +191,11 @@ impl UiElementsScene {
         // Focus border
         let cb1_rr = RoundedRect {
             rect: cb1,
-            radii: RoundedRadii {
-                tl: 2.0,
-                tr: 2.0,
-                br: 2.0,
-                bl: 2.0,
-            },
+            radii: RoundedRadii::uniform(2.0),
         };
         p.stroke_rounded_rect(
             cb1_rr,
-            Stroke { width: 2.0 },
+            Stroke::round(2.0),
             Brush::Solid(Color::from_srgba_u8([63, 130, 246, 255])),
             4,
         );
@@ -232,12 +211,7 @@ impl UiElementsScene {
                 w: (cb1.w - 2.0 * inset).max(0.0),
                 h: (cb1.h - 2.0 * inset).max(0.0),
             };
-            let inner_r = RoundedRadii {
-                tl: 1.5,
-                tr: 1.5,
-                br: 1.5,
-                bl: 1.5,
-            };
+            let inner_r = RoundedRadii::uniform(1.5);
             p.rounded_rect(
                 RoundedRect {
                     rect: inner,
@@ -254,6 +228,7 @@ impl UiElementsScene {
                 pos: [col1_x + 188.0, y + 16.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([240, 240, 240, 255]),
+                spans: Vec::new(),
             },
             4,
         );
@@ -273,6 +248,7 @@ impl UiElementsScene {
                 pos: [col1_x + 28.0, y + 16.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([240, 240, 240, 255]),
+                spans: Vec::new(),
             },
             4,
         );
@@ -295,6 +271,7 @@ impl UiElementsScene {
                 pos: [col1_x + 168.0, y + 16.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([240, 240, 240, 255]),
+                spans: Vec::new(),
             },
             4,
         );
@@ -314,7 +291,7 @@ impl UiElementsScene {
         );
         p.stroke_rect(
             ib,
-            Stroke { width: 1.0 },
+            Stroke::new(1.0),
             Brush::Solid(Color::from_srgba_u8([200, 200, 200, 255])),
             4,
         );
@@ -324,6 +301,7 @@ impl UiElementsScene {
                 pos: [ib.x + 10.0, y + 22.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([16, 16, 16, 255]),
+                spans: Vec::new(),
             },
             5,
         );
@@ -343,7 +321,7 @@ impl UiElementsScene {
         );
         p.stroke_rect(
             ta,
-            Stroke { width: 1.0 },
+            Stroke::new(1.0),
             Brush::Solid(Color::from_srgba_u8([200, 200, 200, 255])),
             4,
         );
@@ -353,6 +331,7 @@ impl UiElementsScene {
                 pos: [ta.x + 8.0, y + 20.0],
                 size: 15.0,
                 color: Color::from_srgba_u8([16, 16, 16, 255]),
+                spans: Vec::new(),
             },
             5,
         );
@@ -362,6 +341,7 @@ impl UiElementsScene {
                 pos: [ta.x + 8.0, y + 40.0],
                 size: 15.0,
                 color: Color::from_srgba_u8([16, 16, 16, 255]),
+                spans: Vec::new(),
             },
             5,
         );
@@ -381,7 +361,7 @@ impl UiElementsScene {
         );
         p.stroke_rect(
             sel1,
-            Stroke { width: 1.0 },
+            Stroke::new(1.0),
             Brush::Solid(Color::from_srgba_u8([200, 200, 200, 255])),
             4,
         );
@@ -391,6 +371,7 @@ impl UiElementsScene {
                 pos: [sel1.x + 10.0, y + 22.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([16, 16, 16, 255]),
+                spans: Vec::new(),
             },
             5,
         );
@@ -405,7 +386,11 @@ impl UiElementsScene {
         tri.cmds.push(PathCmd::LineTo([ax, ay + 3.0]));
         tri.cmds.push(PathCmd::LineTo([ax + 6.0, ay - 3.0]));
         tri.cmds.push(PathCmd::Close);
-        p.fill_path(tri, Color::from_srgba_u8([80, 80, 80, 255]), 6);
+        p.fill_path(
+            tri,
+            Brush::Solid(Color::from_srgba_u8([80, 80, 80, 255])),
+            6,
+        );
 
         let sel2 = Rect {
             x: col1_x + 240.0,
@@ -420,7 +405,7 @@ impl UiElementsScene {
         );
         p.stroke_rect(
             sel2,
-            Stroke { width: 2.0 },
+            Stroke::new(2.0),
             Brush::Solid(Color::from_srgba_u8([63, 130, 246, 255])),
             4,
         );
@@ -430,6 +415,7 @@ impl UiElementsScene {
                 pos: [sel2.x + 10.0, y + 22.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([16, 16, 16, 255]),
+                spans: Vec::new(),
             },
             5,
         );
@@ -443,7 +429,11 @@ impl UiElementsScene {
         tri2.cmds.push(PathCmd::LineTo([ax2, ay2 - 3.0]));
         tri2.cmds.push(PathCmd::LineTo([ax2 + 6.0, ay2 + 3.0]));
         tri2.cmds.push(PathCmd::Close);
-        p.fill_path(tri2, Color::from_srgba_u8([80, 80, 80, 255]), 6);
+        p.fill_path(
+            tri2,
+            Brush::Solid(Color::from_srgba_u8([80, 80, 80, 255])),
+            6,
+        );
         // Right column label + text
         p.text(
             engine_core::TextRun {
@@ -451,6 +441,7 @@ impl UiElementsScene {
                 pos: [col2_x, 40.0],
                 size: 20.0,
                 color: Color::from_srgba_u8([220, 230, 250, 255]),
+                spans: Vec::new(),
             },
             2,
         );
@@ -460,6 +451,7 @@ impl UiElementsScene {
                 pos: [col2_x, 40.0 + 36.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([230, 230, 235, 255]),
+                spans: Vec::new(),
             },
             3,
         );
@@ -469,6 +461,7 @@ impl UiElementsScene {
                 pos: [col2_x, 40.0 + 36.0 + 28.0],
                 size: 16.0,
                 color: Color::from_srgba_u8([210, 245, 210, 255]),
+                spans: Vec::new(),
             },
             3,
         );
@@ -491,6 +484,7 @@ impl UiElementsScene {
                 pos: [img.x, img.y + img.h + 20.0],
                 size: 14.0,
                 color: Color::from_srgba_u8([235, 240, 255, 200]),
+                spans: Vec::new(),
             },
             4,
         );
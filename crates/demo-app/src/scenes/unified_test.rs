@@ -122,6 +122,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
             pos: [50.0, 50.0],
             size: 32.0,
             color: ColorLinPremul::from_srgba_u8([255, 255, 255, 255]),
+            spans: Vec::new(),
         },
         100,
     );
@@ -132,6 +133,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
             pos: [110.0, 160.0],
             size: 20.0,
             color: ColorLinPremul::from_srgba_u8([255, 255, 255, 255]),
+            spans: Vec::new(),
         },
         15, // z=15 - should appear on top of red, below green
     );
@@ -142,6 +144,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
             pos: [210.0, 210.0],
             size: 20.0,
             color: ColorLinPremul::from_srgba_u8([255, 255, 255, 255]), // Changed to white for visibility
+            spans: Vec::new(),
         },
         25, // z=25 - should appear on top of green, below blue
     );
@@ -152,6 +155,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
             pos: [310.0, 260.0],
             size: 20.0,
             color: ColorLinPremul::from_srgba_u8([255, 255, 255, 255]),
+            spans: Vec::new(),
         },
         35, // z=35 - should appear on top of blue
     );
@@ -174,6 +178,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
             pos: [img_origin[0] + 10.0, img_origin[1] + img_size[1] + 24.0],
             size: 18.0,
             color: ColorLinPremul::from_srgba_u8([255, 255, 0, 255]),
+            spans: Vec::new(),
         },
         40,
     );
@@ -197,6 +202,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
             pos: [svg_origin[0] + 10.0, svg_origin[1] - 10.0],
             size: 18.0,
             color: ColorLinPremul::from_srgba_u8([180, 255, 180, 255]),
+            spans: Vec::new(),
         },
         45,
     );
@@ -212,6 +218,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
             pos: [50.0, icon_test_y - 20.0],
             size: 16.0,
             color: ColorLinPremul::from_srgba_u8([80, 80, 80, 255]),
+            spans: Vec::new(),
         },
         50,
     );
@@ -236,6 +243,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
                 pos: [col_x, icon_test_y],
                 size: 12.0,
                 color: ColorLinPremul::from_srgba_u8([100, 100, 100, 255]),
+                spans: Vec::new(),
             },
             50,
         );
@@ -257,6 +265,7 @@ fn build_test_dl(viewport: Viewport, test_level: u32) -> DisplayList {
                 pos: [50.0, row_y + 8.0],
                 size: 10.0,
                 color: ColorLinPremul::from_srgba_u8([120, 120, 120, 255]),
+                spans: Vec::new(),
             },
             50,
         );
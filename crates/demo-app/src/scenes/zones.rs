@@ -135,17 +135,12 @@ impl ZonesScene {
             };
             let rr = RoundedRect {
                 rect,
-                radii: RoundedRadii {
-                    tl: 10.0,
-                    tr: 10.0,
-                    br: 10.0,
-                    bl: 10.0,
-                },
+                radii: RoundedRadii::uniform(10.0),
             };
             p.rounded_rect(rr, Brush::Solid(fill), 3);
             p.stroke_rounded_rect(
                 rr,
-                Stroke { width: 2.0 },
+                Stroke::round(2.0),
                 Brush::Solid(ColorLinPremul::from_srgba(255, 255, 255, 0.6)),
                 4,
             );
@@ -0,0 +1,341 @@
+//! Shelf-packed texture atlas allocation with least-recently-used eviction.
+//!
+//! Entries are packed left to right into horizontal shelves stacked top to
+//! bottom; each shelf is as tall as the first entry that opened it (rounded
+//! up), so entries of similar height share shelves. When the atlas is full,
+//! the entries that have gone unused the longest are evicted until the new
+//! one fits. Entries used in the current frame are never evicted, so a frame
+//! never overwrites atlas texels it has already referenced.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Empty texels kept around every entry so filtering at its edges doesn't
+/// pick up its neighbors.
+pub const ATLAS_PADDING: u32 = 1;
+
+/// Shelf heights are rounded up to a multiple of this, so entries of nearly
+/// the same height can share a shelf.
+const SHELF_ROUNDING: u32 = 4;
+
+/// Region of an atlas, in texels, excluding padding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasRegion {
+    /// Normalized `[u0, v0, u1, v1]` texture coordinates in an atlas of
+    /// `size` texels.
+    pub fn uv(&self, size: [u32; 2]) -> [f32; 4] {
+        let (w, h) = (size[0].max(1) as f32, size[1].max(1) as f32);
+        [
+            self.x as f32 / w,
+            self.y as f32 / h,
+            (self.x + self.width) as f32 / w,
+            (self.y + self.height) as f32 / h,
+        ]
+    }
+
+    /// The region grown by [`ATLAS_PADDING`] on every side: the texels the
+    /// entry owns.
+    pub fn padded(&self) -> AtlasRegion {
+        AtlasRegion {
+            x: self.x - ATLAS_PADDING,
+            y: self.y - ATLAS_PADDING,
+            width: self.width + ATLAS_PADDING * 2,
+            height: self.height + ATLAS_PADDING * 2,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// Spans covering the shelf's width left to right; `true` when free.
+    spans: Vec<(u32, u32, bool)>,
+}
+
+impl Shelf {
+    fn new(y: u32, height: u32, width: u32) -> Self {
+        Self {
+            y,
+            height,
+            spans: vec![(0, width, true)],
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.spans.len() == 1 && self.spans[0].2
+    }
+
+    /// Narrowest free span at least `width` wide.
+    fn fit(&self, width: u32) -> Option<usize> {
+        self.spans
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.2 && s.1 >= width)
+            .min_by_key(|(_, s)| s.1)
+            .map(|(i, _)| i)
+    }
+
+    /// Take `width` from the start of free span `index`; returns its x.
+    fn take(&mut self, index: usize, width: u32) -> u32 {
+        let (x, w, _) = self.spans[index];
+        self.spans[index] = (x, width, false);
+        if w > width {
+            self.spans.insert(index + 1, (x + width, w - width, true));
+        }
+        x
+    }
+
+    /// Free the span starting at `x`, merging it with free neighbors.
+    fn release(&mut self, x: u32) {
+        let Some(i) = self.spans.iter().position(|s| s.0 == x) else {
+            return;
+        };
+        self.spans[i].2 = true;
+        if i + 1 < self.spans.len() && self.spans[i + 1].2 {
+            self.spans[i].1 += self.spans[i + 1].1;
+            self.spans.remove(i + 1);
+        }
+        if i > 0 && self.spans[i - 1].2 {
+            self.spans[i - 1].1 += self.spans[i].1;
+            self.spans.remove(i);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    region: AtlasRegion,
+    shelf: usize,
+    last_used: u64,
+}
+
+/// Space allocator for a texture atlas shared by entries keyed by `K`
+/// (glyph masks, icons, ...). It only does the bookkeeping; callers upload
+/// texels into the regions it hands out.
+#[derive(Debug)]
+pub struct AtlasAllocator<K> {
+    width: u32,
+    height: u32,
+    shelves: Vec<Shelf>,
+    entries: HashMap<K, Entry>,
+    frame: u64,
+}
+
+impl<K: Clone + Eq + Hash> AtlasAllocator<K> {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            shelves: Vec::new(),
+            entries: HashMap::new(),
+            frame: 0,
+        }
+    }
+
+    /// Atlas size in texels.
+    pub fn size(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Start a new frame. Entries used before it become eligible for
+    /// eviction.
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Region of `key`, marking it used in the current frame.
+    pub fn get(&mut self, key: &K) -> Option<AtlasRegion> {
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.frame;
+        Some(entry.region)
+    }
+
+    /// Allocate a `width` x `height` region for `key`, replacing any
+    /// existing one and evicting least recently used entries as needed.
+    /// `None` if it can't fit even after evicting everything not used in
+    /// the current frame.
+    pub fn allocate(&mut self, key: K, width: u32, height: u32) -> Option<AtlasRegion> {
+        self.remove(&key);
+        let (w, h) = (width + ATLAS_PADDING * 2, height + ATLAS_PADDING * 2);
+        if w > self.width || h > self.height {
+            return None;
+        }
+        let mut stale: Option<Vec<K>> = None;
+        loop {
+            if let Some((shelf, x)) = self.place(w, h) {
+                let region = AtlasRegion {
+                    x: x + ATLAS_PADDING,
+                    y: self.shelves[shelf].y + ATLAS_PADDING,
+                    width,
+                    height,
+                };
+                self.entries.insert(
+                    key,
+                    Entry {
+                        region,
+                        shelf,
+                        last_used: self.frame,
+                    },
+                );
+                return Some(region);
+            }
+            // Evict the least recently used entry and retry.
+            let stale = stale.get_or_insert_with(|| {
+                let mut keys: Vec<(u64, K)> = self
+                    .entries
+                    .iter()
+                    .filter(|(_, e)| e.last_used < self.frame)
+                    .map(|(k, e)| (e.last_used, k.clone()))
+                    .collect();
+                keys.sort_by_key(|(used, _)| std::cmp::Reverse(*used));
+                keys.into_iter().map(|(_, k)| k).collect()
+            });
+            let victim = stale.pop()?;
+            self.remove(&victim);
+        }
+    }
+
+    /// Free `key`'s region. Returns whether it was present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        let Some(entry) = self.entries.remove(key) else {
+            return false;
+        };
+        self.shelves[entry.shelf].release(entry.region.x - ATLAS_PADDING);
+        // Give the space of empty trailing shelves back to new shelves.
+        while self.shelves.last().is_some_and(Shelf::is_empty) {
+            self.shelves.pop();
+        }
+        true
+    }
+
+    /// Free every region.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.shelves.clear();
+    }
+
+    /// Find room for a padded `w` x `h` entry: the best fitting shelf of a
+    /// similar height, else a new shelf, else the shortest empty shelf tall
+    /// enough.
+    fn place(&mut self, w: u32, h: u32) -> Option<(usize, u32)> {
+        let similar =
+            |shelf: &Shelf| shelf.height >= h && shelf.height <= h + h / 2 + SHELF_ROUNDING;
+        let best = self
+            .shelves
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| similar(s))
+            .filter_map(|(i, s)| s.fit(w).map(|span| (i, span, s.height)))
+            .min_by_key(|&(_, _, height)| height);
+        if let Some((i, span, _)) = best {
+            return Some((i, self.shelves[i].take(span, w)));
+        }
+
+        let bottom = self.shelves.last().map_or(0, |s| s.y + s.height);
+        let height = h.div_ceil(SHELF_ROUNDING) * SHELF_ROUNDING;
+        let height = height.min(self.height - bottom);
+        if height >= h {
+            self.shelves.push(Shelf::new(bottom, height, self.width));
+            let i = self.shelves.len() - 1;
+            return Some((i, self.shelves[i].take(0, w)));
+        }
+
+        let (i, _) = self
+            .shelves
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.height >= h && s.is_empty())
+            .min_by_key(|(_, s)| s.height)?;
+        Some((i, self.shelves[i].take(0, w)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: AtlasRegion, b: AtlasRegion) -> bool {
+        let (a, b) = (a.padded(), b.padded());
+        a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+    }
+
+    #[test]
+    fn packs_entries_without_overlap() {
+        let mut atlas = AtlasAllocator::new(64, 64);
+        let regions: Vec<AtlasRegion> = (0..12)
+            .map(|i| atlas.allocate(i, 10 + i % 3, 8 + i % 5).unwrap())
+            .collect();
+        for (i, a) in regions.iter().enumerate() {
+            assert!(a.x + a.width < 64 && a.y + a.height < 64);
+            for b in &regions[i + 1..] {
+                assert!(!overlaps(*a, *b), "{a:?} overlaps {b:?}");
+            }
+        }
+        assert_eq!(atlas.get(&3), Some(regions[3]));
+        assert_eq!(regions[0].uv([64, 64])[0], 1.0 / 64.0);
+    }
+
+    #[test]
+    fn full_atlases_evict_least_recently_used_entries() {
+        let mut atlas = AtlasAllocator::new(32, 32);
+        // Four 14x14 entries (16x16 padded) fill the atlas.
+        for key in 0..4 {
+            atlas.allocate(key, 14, 14).unwrap();
+        }
+        atlas.begin_frame();
+        for key in [0, 2, 3] {
+            atlas.get(&key).unwrap();
+        }
+        let region = atlas.allocate(4, 14, 14).unwrap();
+        assert_eq!(atlas.get(&1), None);
+        assert_eq!(atlas.len(), 4);
+        assert!([0, 2, 3].iter().all(|k| atlas.get(k).is_some()));
+        assert!(!overlaps(region, atlas.get(&0).unwrap()));
+    }
+
+    #[test]
+    fn entries_used_this_frame_are_never_evicted() {
+        let mut atlas = AtlasAllocator::new(32, 32);
+        for key in 0..4 {
+            atlas.allocate(key, 14, 14).unwrap();
+        }
+        assert_eq!(atlas.allocate(4, 14, 14), None);
+        assert_eq!(atlas.len(), 4);
+        assert_eq!(atlas.allocate(5, 40, 4), None, "larger than the atlas");
+    }
+
+    #[test]
+    fn freed_space_is_reused() {
+        let mut atlas = AtlasAllocator::new(64, 16);
+        let a = atlas.allocate("a", 20, 10).unwrap();
+        atlas.allocate("b", 20, 10).unwrap();
+        let c = atlas.allocate("c", 18, 10).unwrap();
+        assert!(atlas.remove(&"a"));
+        assert!(atlas.remove(&"b"));
+        // The two freed spans merged into one wide enough for this.
+        let wide = atlas.allocate("wide", 40, 10).unwrap();
+        assert_eq!((wide.x, wide.y), (a.x, a.y));
+        assert!(wide.x + wide.width <= c.x);
+        assert!(!overlaps(wide, c));
+        // Removing everything returns the shelves too.
+        atlas.remove(&"wide");
+        atlas.remove(&"c");
+        assert!(atlas.allocate("tall", 60, 14).is_some());
+    }
+}
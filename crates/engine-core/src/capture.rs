@@ -0,0 +1,170 @@
+//! Encoding for captured frames, so a rendering bug can be reproduced from a
+//! single file.
+//!
+//! Display lists (and the capture types built on them) are written either as
+//! pretty JSON, which is easy to read and edit by hand, or as a compact
+//! binary form prefixed with a small header. Decoding accepts both.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::display_list::DisplayList;
+
+/// Leading bytes of the binary form.
+const BINARY_MAGIC: &[u8; 4] = b"RCAP";
+
+/// Version of the binary form, stored after [`BINARY_MAGIC`]. Bump it when
+/// a serialized type changes shape.
+const BINARY_VERSION: u8 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaptureFormat {
+    Json,
+    Binary,
+}
+
+impl CaptureFormat {
+    /// `Json` for paths with a `.json` extension, `Binary` otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => CaptureFormat::Json,
+            _ => CaptureFormat::Binary,
+        }
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            CaptureFormat::Json => {
+                serde_json::to_vec_pretty(value).context("failed to encode capture as JSON")
+            }
+            CaptureFormat::Binary => {
+                let mut out = BINARY_MAGIC.to_vec();
+                out.push(BINARY_VERSION);
+                bincode::serialize_into(&mut out, value)
+                    .context("failed to encode binary capture")?;
+                Ok(out)
+            }
+        }
+    }
+
+    /// Decode a value written in either format, told apart by the binary
+    /// header.
+    pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        let Some(rest) = bytes.strip_prefix(BINARY_MAGIC) else {
+            return serde_json::from_slice(bytes).context("invalid JSON capture");
+        };
+        match rest.split_first() {
+            Some((&BINARY_VERSION, body)) => {
+                bincode::deserialize(body).context("invalid binary capture")
+            }
+            Some((version, _)) => bail!("unsupported binary capture version {version}"),
+            None => bail!("truncated binary capture"),
+        }
+    }
+}
+
+impl DisplayList {
+    /// Serialize the list, see [`CaptureFormat`].
+    pub fn encode(&self, format: CaptureFormat) -> Result<Vec<u8>> {
+        format.encode(self)
+    }
+
+    /// Deserialize a list encoded in either format.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        CaptureFormat::decode(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display_list::Viewport;
+    use crate::painter::Painter;
+    use crate::scene::*;
+
+    fn sample_list() -> DisplayList {
+        let mut p = Painter::begin_frame(Viewport {
+            width: 320,
+            height: 200,
+        });
+        let red = ColorLinPremul::from_srgba_u8([255, 0, 0, 255]);
+        p.push_transform(Transform2D::translate(4.0, 8.0));
+        p.push_clip_rounded_rect(RoundedRect {
+            rect: Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 100.0,
+                h: 50.0,
+            },
+            radii: RoundedRadii::uniform(6.0),
+        });
+        p.rect(
+            Rect {
+                x: 1.0,
+                y: 2.0,
+                w: 3.0,
+                h: 4.0,
+            },
+            Brush::LinearGradient {
+                start: [0.0, 0.0],
+                end: [1.0, 0.0],
+                stops: vec![(0.0, red), (1.0, ColorLinPremul::rgba(0, 0, 0, 0))],
+                spread: SpreadMode::Reflect,
+            },
+            3,
+        );
+        p.push_filter_layer(vec![Filter::Blur { radius: 2.5 }]);
+        p.text(
+            TextRun::new("héllo", [10.0, 20.0], 14.0, red).with_span(
+                0..2,
+                TextAttrs {
+                    weight: FontWeight(700),
+                    ..Default::default()
+                },
+            ),
+            5,
+        );
+        p.pop_layer();
+        p.image("assets/icon.png", [0.0, 0.0], [16.0, 16.0], 7);
+        p.pop_clip();
+        p.pop_transform();
+        p.finish()
+    }
+
+    #[test]
+    fn display_lists_round_trip_in_both_formats() {
+        let list = sample_list();
+        for format in [CaptureFormat::Json, CaptureFormat::Binary] {
+            let bytes = list.encode(format).unwrap();
+            let decoded = DisplayList::decode(&bytes).unwrap();
+            assert_eq!(format!("{decoded:?}"), format!("{list:?}"), "{format:?}");
+        }
+        let json = list.encode(CaptureFormat::Json).unwrap();
+        let binary = list.encode(CaptureFormat::Binary).unwrap();
+        assert!(binary.len() < json.len());
+    }
+
+    #[test]
+    fn format_follows_the_extension() {
+        assert_eq!(
+            CaptureFormat::from_path(std::path::Path::new("frame.JSON")),
+            CaptureFormat::Json
+        );
+        assert_eq!(
+            CaptureFormat::from_path(std::path::Path::new("frame.rcap")),
+            CaptureFormat::Binary
+        );
+    }
+
+    #[test]
+    fn unknown_binary_versions_are_rejected() {
+        let mut bytes = sample_list().encode(CaptureFormat::Binary).unwrap();
+        bytes[BINARY_MAGIC.len()] = BINARY_VERSION + 1;
+        let err = DisplayList::decode(&bytes).unwrap_err();
+        assert!(err.to_string().contains("version"), "{err}");
+        assert!(DisplayList::decode(BINARY_MAGIC).is_err());
+    }
+}
@@ -0,0 +1,2294 @@
+//! CPU reference rasterizer for [`DisplayList`].
+//!
+//! [`CpuRenderer`] renders a display list into an in-memory framebuffer with
+//! no GPU device, so scenes can be snapshot-tested headlessly in CI. Coverage
+//! for geometry is computed with `tiny-skia`; shading and compositing happen
+//! in premultiplied linear space, like the wgpu pipeline renders into an sRGB
+//! target. sRGB encoding only happens when pixels are read back.
+//!
+//! The renderer mirrors the GPU path rather than an idealized one:
+//! - draws are ordered by z-index; within one z-index, solids go first, then
+//!   text, images and SVGs (the pass order of `PassManager::render_unified`);
+//! - linear gradients on rects run horizontally across the rect and radial
+//!   gradients on ellipses follow the normalized ellipse radius; brushes the
+//!   GPU upload skips (e.g. gradients on rounded rects) are skipped here too;
+//! - path brushes place gradients and image patterns in the path's local
+//!   space, and patterns wrap like the GPU's repeating pattern sampler;
+//! - `StrokeRect` strokes inside the rect, every other stroke is centered;
+//!   dashes are cut from the flattened path before stroking, as in
+//!   `upload.rs`;
+//! - glyph masks are sampled nearest and composited like `TEXT_WGSL`;
+//! - box shadows use the calibration of `PassManager::draw_box_shadow`;
+//! - layers are composited as one unit at the lowest z-index of their
+//!   content, with the blend formulas of `LAYER_COMPOSITE_WGSL`.
+//!
+//! Clips (rects, rounded rects and paths) are honored for every command, as in
+//! [`HitIndex`](crate::HitIndex), and for side draws through the clip stacks
+//! set with [`CpuRenderer::set_clips`].
+
+use std::collections::HashMap;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use tiny_skia::{Mask, PathBuilder};
+
+use crate::display_list::{Command, DisplayList};
+use crate::scene::{
+    BackdropFilter, BlendMode, BoxShadowSpec, Brush, Clip, ClipShape, ClipTable, ColorLinPremul,
+    FillRule, Filter, ImagePattern, LayerMask, LineCap, LineJoin, Path, PathCmd, Rect, RoundedRect,
+    SpreadMode, Stroke, TextRun, Transform2D, apply_color_matrix,
+};
+use crate::svg::SvgStyle;
+use crate::text::{MaskFormat, RasterizedGlyph, TextProvider};
+use crate::upload::{ramp_stops, sample_spread};
+
+/// Glyph draw supplied next to a display list:
+/// `(origin, glyph, color, z, clip)`.
+///
+/// Same layout as the `glyph_draws` accepted by `PassManager::render_unified`,
+/// so text rasterized up front by a canvas can be replayed on the CPU. `clip`
+/// indexes the table passed to [`CpuRenderer::set_clips`].
+pub type CpuGlyphDraw = ([f32; 2], RasterizedGlyph, ColorLinPremul, i32, u32);
+
+/// SVG draw supplied next to a display list:
+/// `(path, origin, max_size, style, z, transform, clip)`.
+pub type CpuSvgDraw = (
+    PathBuf,
+    [f32; 2],
+    [f32; 2],
+    Option<SvgStyle>,
+    i32,
+    Transform2D,
+    u32,
+);
+
+/// Image draw with an already transformed origin and fitted size:
+/// `(path, origin, size, z, clip)`.
+pub type CpuImageDraw = (PathBuf, [f32; 2], [f32; 2], i32, u32);
+
+/// Headless renderer producing a premultiplied linear RGBA framebuffer.
+pub struct CpuRenderer {
+    width: u32,
+    height: u32,
+    scale_factor: f32,
+    pixels: Vec<[f32; 4]>,
+    text_provider: Option<Arc<dyn TextProvider>>,
+    images: HashMap<PathBuf, Option<Arc<LinearImage>>>,
+    clips: ClipTable,
+}
+
+/// Decoded raster image in straight-alpha linear RGBA.
+struct LinearImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+/// Pass buckets of the GPU unified renderer, in draw order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Pass {
+    Solid,
+    Text,
+    Image,
+    Svg,
+}
+
+/// Side draws a canvas collects for the root of a frame or for one layer.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuSideDraws<'a> {
+    pub glyph_draws: &'a [CpuGlyphDraw],
+    pub svg_draws: &'a [CpuSvgDraw],
+    pub image_draws: &'a [CpuImageDraw],
+}
+
+enum ItemKind<'a> {
+    Command(&'a Command),
+    Glyph(&'a CpuGlyphDraw),
+    Svg(&'a CpuSvgDraw),
+    Image(&'a CpuImageDraw),
+    Layer(LayerGroup<'a>),
+}
+
+/// Content of a `PushLayer` block, rendered offscreen and composited as one.
+struct LayerGroup<'a> {
+    opacity: f32,
+    blend_mode: BlendMode,
+    mask: Option<&'a LayerMask>,
+    filters: &'a [Filter],
+    backdrop: Option<&'a BackdropFilter>,
+    /// Clip active at `PushLayer`, applied to the backdrop filter.
+    clip: Option<Arc<Mask>>,
+    items: Vec<Item<'a>>,
+}
+
+struct Item<'a> {
+    z: i32,
+    pass: Pass,
+    kind: ItemKind<'a>,
+    clip: Option<Arc<Mask>>,
+}
+
+/// Per-pixel color source evaluated at device pixel centers.
+enum Paint {
+    Solid([f32; 4]),
+    /// Ramp from `start` to `end` in local space.
+    Linear {
+        device: Transform2D,
+        start: [f32; 2],
+        end: [f32; 2],
+        stops: Vec<(f32, [f32; 4])>,
+        spread: SpreadMode,
+    },
+    /// Ramp over the normalized ellipse radius in local space.
+    Radial {
+        device: Transform2D,
+        center: [f32; 2],
+        radii: [f32; 2],
+        stops: Vec<(f32, [f32; 4])>,
+        spread: SpreadMode,
+    },
+    /// Ramp over the turn around `center`, starting at `angle` radians.
+    Conic {
+        device: Transform2D,
+        center: [f32; 2],
+        angle: f32,
+        stops: Vec<(f32, [f32; 4])>,
+        spread: SpreadMode,
+    },
+    /// Image tiled in local space.
+    Pattern {
+        device: Transform2D,
+        pattern: ImagePattern,
+        image: Arc<LinearImage>,
+    },
+}
+
+impl Paint {
+    fn color_at(&self, x: f32, y: f32) -> [f32; 4] {
+        match self {
+            Paint::Solid(c) => *c,
+            Paint::Linear {
+                device,
+                start,
+                end,
+                stops,
+                spread,
+            } => {
+                let Some(p) = device.inverse_apply([x, y]) else {
+                    return [0.0; 4];
+                };
+                let d = [end[0] - start[0], end[1] - start[1]];
+                let len2 = d[0] * d[0] + d[1] * d[1];
+                let t = if len2 > 1e-12 {
+                    ((p[0] - start[0]) * d[0] + (p[1] - start[1]) * d[1]) / len2
+                } else {
+                    0.0
+                };
+                sample_spread(stops, *spread, t)
+            }
+            Paint::Radial {
+                device,
+                center,
+                radii,
+                stops,
+                spread,
+            } => {
+                let Some(p) = device.inverse_apply([x, y]) else {
+                    return [0.0; 4];
+                };
+                let dx = (p[0] - center[0]) / radii[0].max(1e-6);
+                let dy = (p[1] - center[1]) / radii[1].max(1e-6);
+                sample_spread(stops, *spread, (dx * dx + dy * dy).sqrt())
+            }
+            Paint::Conic {
+                device,
+                center,
+                angle,
+                stops,
+                spread,
+            } => {
+                let Some(p) = device.inverse_apply([x, y]) else {
+                    return [0.0; 4];
+                };
+                let tau = std::f32::consts::TAU;
+                let a = (p[1] - center[1]).atan2(p[0] - center[0]) - angle;
+                sample_spread(stops, *spread, a.rem_euclid(tau) / tau)
+            }
+            Paint::Pattern {
+                device,
+                pattern,
+                image,
+            } => {
+                let Some(uv) = device.inverse_apply([x, y]).and_then(|p| pattern.uv_at(p)) else {
+                    return [0.0; 4];
+                };
+                let outside = |t: f32| !(0.0..=1.0).contains(&t);
+                if (!pattern.repeat.repeats_x() && outside(uv[0]))
+                    || (!pattern.repeat.repeats_y() && outside(uv[1]))
+                {
+                    return [0.0; 4];
+                }
+                let c = image.sample_bilinear_repeat(
+                    uv[0] * image.width as f32,
+                    uv[1] * image.height as f32,
+                );
+                [c[0] * c[3], c[1] * c[3], c[2] * c[3], c[3]]
+            }
+        }
+    }
+}
+
+impl CpuRenderer {
+    /// Create a renderer with a transparent framebuffer of `width`×`height`
+    /// device pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        Self {
+            width,
+            height,
+            scale_factor: 1.0,
+            pixels: vec![[0.0; 4]; (width as usize) * (height as usize)],
+            text_provider: None,
+            images: HashMap::new(),
+            clips: ClipTable::default(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Logical→device multiplier applied to all geometry, like
+    /// `PassManager::set_scale_factor` with logical pixels enabled.
+    pub fn set_scale_factor(&mut self, sf: f32) {
+        self.scale_factor = if sf.is_finite() && sf > 0.0 { sf } else { 1.0 };
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Provider used to rasterize `DrawText` and `DrawHyperlink` runs. Without
+    /// one, display-list text is skipped.
+    pub fn set_text_provider(&mut self, provider: Arc<dyn TextProvider>) {
+        self.text_provider = Some(provider);
+    }
+
+    /// Fill the whole framebuffer with `color`.
+    pub fn clear(&mut self, color: ColorLinPremul) {
+        let c = [color.r, color.g, color.b, color.a];
+        self.pixels.iter_mut().for_each(|p| *p = c);
+    }
+
+    /// Premultiplied linear color of a device pixel.
+    pub fn pixel(&self, x: u32, y: u32) -> ColorLinPremul {
+        let p = self.pixels[(y * self.width + x) as usize];
+        ColorLinPremul {
+            r: p[0],
+            g: p[1],
+            b: p[2],
+            a: p[3],
+        }
+    }
+
+    /// Read back the framebuffer as straight-alpha sRGB RGBA8, row-major.
+    pub fn to_srgba8(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.pixels.len() * 4);
+        for p in &self.pixels {
+            let c = ColorLinPremul {
+                r: p[0],
+                g: p[1],
+                b: p[2],
+                a: p[3],
+            };
+            out.extend_from_slice(&c.to_srgba_u8());
+        }
+        out
+    }
+
+    /// Read back the framebuffer as an sRGB image.
+    pub fn to_image(&self) -> image::RgbaImage {
+        image::RgbaImage::from_raw(self.width, self.height, self.to_srgba8())
+            .expect("framebuffer size matches dimensions")
+    }
+
+    /// Encode the framebuffer as an sRGB PNG.
+    pub fn save_png(&self, path: impl AsRef<FsPath>) -> Result<()> {
+        let path = path.as_ref();
+        self.to_image()
+            .save_with_format(path, image::ImageFormat::Png)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Clip stacks that the `clip` ids of side draws index, like
+    /// `PassManager::set_clips`. Ids without a stack draw unclipped.
+    pub fn set_clips(&mut self, clips: ClipTable) {
+        self.clips = clips;
+    }
+
+    /// Render a display list over the current framebuffer contents.
+    pub fn render(&mut self, list: &DisplayList) {
+        self.render_unified(list, &[], &[], &[]);
+    }
+
+    /// Render a display list together with the side draws a canvas collects
+    /// (pre-rasterized glyphs, styled SVGs and fitted images). Everything is
+    /// interleaved by z-index, taking the same inputs as
+    /// `PassManager::render_unified`.
+    ///
+    /// Clip scopes are resolved while walking the list, so it may be in
+    /// submission order or sorted with [`DisplayList::sort_by_z`], which
+    /// keeps them around the commands they apply to.
+    pub fn render_unified(
+        &mut self,
+        list: &DisplayList,
+        glyph_draws: &[CpuGlyphDraw],
+        svg_draws: &[CpuSvgDraw],
+        image_draws: &[CpuImageDraw],
+    ) {
+        let root = CpuSideDraws {
+            glyph_draws,
+            svg_draws,
+            image_draws,
+        };
+        self.render_layered(list, root, &[]);
+    }
+
+    /// [`CpuRenderer::render_unified`] with side draws for layers as well:
+    /// `layers[i]` is drawn inside the list's `i`-th `PushLayer` block, in
+    /// submission order.
+    ///
+    /// Layers render into a transparent buffer that is composited with the
+    /// layer's opacity, mask and blend mode at the lowest z-index of its
+    /// content, like `PassManager::render_layers`.
+    pub fn render_layered(
+        &mut self,
+        list: &DisplayList,
+        root: CpuSideDraws,
+        layers: &[CpuSideDraws],
+    ) {
+        // Open groups: the root and every layer not popped yet.
+        let mut groups: Vec<(Option<LayerGroup>, usize)> = vec![(None, 0)];
+        let mut items: Vec<Vec<Item>> = vec![Vec::new()];
+        let mut next_layer = 0;
+        let mut clips: Vec<Option<Arc<Mask>>> = vec![None];
+        let mut tstack: Vec<Transform2D> = vec![Transform2D::identity()];
+        // Masks of the clip stacks side draws refer to, by id.
+        let mut side_masks: HashMap<u32, Option<Arc<Mask>>> = HashMap::new();
+        let mut side_clip = |id: u32| {
+            side_masks
+                .entry(id)
+                .or_insert_with(|| self.stack_mask(self.clips.get(id)))
+                .clone()
+        };
+
+        for cmd in &list.commands {
+            let pass = match cmd {
+                Command::PushClip(clip) => {
+                    let parent = clips.last().cloned().flatten();
+                    let t = *tstack.last().unwrap();
+                    clips.push(self.clip_mask(parent.as_deref(), clip, t).map(Arc::new));
+                    continue;
+                }
+                Command::PopClip => {
+                    if clips.len() > 1 {
+                        clips.pop();
+                    }
+                    continue;
+                }
+                Command::PushTransform(t) => {
+                    tstack.push(*t);
+                    continue;
+                }
+                Command::PopTransform => {
+                    if tstack.len() > 1 {
+                        tstack.pop();
+                    }
+                    continue;
+                }
+                Command::PushLayer {
+                    opacity,
+                    blend_mode,
+                    mask,
+                    filters,
+                    backdrop,
+                } => {
+                    let group = LayerGroup {
+                        opacity: *opacity,
+                        blend_mode: *blend_mode,
+                        mask: mask.as_ref(),
+                        filters,
+                        backdrop: backdrop.as_ref(),
+                        clip: clips.last().cloned().flatten(),
+                        items: Vec::new(),
+                    };
+                    groups.push((Some(group), next_layer));
+                    items.push(Vec::new());
+                    next_layer += 1;
+                    continue;
+                }
+                Command::PopLayer => {
+                    if groups.len() > 1 {
+                        close_layer(&mut groups, &mut items, layers, &mut side_clip);
+                    }
+                    continue;
+                }
+                Command::HitRegionRect { .. }
+                | Command::HitRegionRoundedRect { .. }
+                | Command::HitRegionEllipse { .. } => continue,
+                Command::DrawText { .. } | Command::DrawHyperlink { .. } => Pass::Text,
+                Command::DrawImage { .. } => Pass::Image,
+                Command::DrawSvg { .. } => Pass::Svg,
+                _ => Pass::Solid,
+            };
+            items.last_mut().unwrap().push(Item {
+                z: cmd.z_index().unwrap_or(0),
+                pass,
+                kind: ItemKind::Command(cmd),
+                clip: clips.last().cloned().flatten(),
+            });
+        }
+        while groups.len() > 1 {
+            close_layer(&mut groups, &mut items, layers, &mut side_clip);
+        }
+        let mut items = items.pop().unwrap_or_default();
+        push_side_draws(&mut items, root, &mut side_clip);
+        self.draw_items(items);
+    }
+
+    fn draw_items(&mut self, mut items: Vec<Item>) {
+        // Stable: equal keys keep submission order.
+        items.sort_by_key(|it| (it.z, it.pass));
+
+        for it in items {
+            let clip = it.clip.as_deref();
+            match it.kind {
+                ItemKind::Command(cmd) => self.draw_command(cmd, clip),
+                ItemKind::Glyph((origin, glyph, color, _, _)) => {
+                    self.draw_glyph(*origin, glyph, *color, clip)
+                }
+                ItemKind::Svg((path, origin, max_size, style, _, transform, _)) => {
+                    self.draw_svg(path, *origin, *max_size, *style, *transform, clip)
+                }
+                ItemKind::Image((path, origin, size, _, _)) => {
+                    if let Some(img) = self.load_image(path) {
+                        self.draw_linear_image(&img, *origin, *size, Transform2D::identity(), clip);
+                    }
+                }
+                ItemKind::Layer(layer) => self.draw_layer(layer),
+            }
+        }
+    }
+
+    /// Render a layer's items into a transparent buffer, then composite it
+    /// onto the current one.
+    fn draw_layer(&mut self, layer: LayerGroup) {
+        if let Some(backdrop) = layer.backdrop {
+            self.filter_backdrop(backdrop, layer.clip.as_deref());
+        }
+        let blank = vec![[0.0; 4]; self.pixels.len()];
+        let backdrop = std::mem::replace(&mut self.pixels, blank);
+        self.draw_items(layer.items);
+        let mask = layer.mask.map(|mask| self.mask_alpha(mask));
+        let mut content = std::mem::replace(&mut self.pixels, backdrop);
+        for filter in layer.filters {
+            content = self.apply_filter(&content, filter, false);
+        }
+        for (i, src) in content.iter().enumerate() {
+            let mut k = layer.opacity;
+            if let Some(mask) = &mask {
+                k *= mask[i];
+            }
+            if src[3] * k <= 0.0 {
+                continue;
+            }
+            let src = [src[0] * k, src[1] * k, src[2] * k, src[3] * k];
+            self.pixels[i] = layer.blend_mode.composite(self.pixels[i], src);
+        }
+    }
+
+    /// Replace the pixels inside the backdrop's rounded rect with their
+    /// filtered copy.
+    fn filter_backdrop(&mut self, backdrop: &BackdropFilter, clip: Option<&Mask>) {
+        let Some(mut region) = Mask::new(self.width, self.height) else {
+            return;
+        };
+        if let Some(path) = to_sk_path(&backdrop.rrect.to_path()) {
+            let ts = sk_transform(self.device_transform(backdrop.transform));
+            region.fill_path(&path, tiny_skia::FillRule::Winding, true, ts);
+        }
+        let mut filtered = self.pixels.clone();
+        for filter in &backdrop.filters {
+            filtered = self.apply_filter(&filtered, filter, true);
+        }
+        for (i, px) in self.pixels.iter_mut().enumerate() {
+            let mut k = region.data()[i] as f32 / 255.0;
+            if let Some(clip) = clip {
+                k *= clip.data()[i] as f32 / 255.0;
+            }
+            if k > 0.0 {
+                let f = filtered[i];
+                *px = std::array::from_fn(|c| px[c] + (f[c] - px[c]) * k);
+            }
+        }
+    }
+
+    /// Run one filter over a framebuffer-sized buffer. Blurs read clamped
+    /// edge pixels for backdrops and transparency outside layer content.
+    fn apply_filter(&self, src: &[[f32; 4]], filter: &Filter, clamp_edges: bool) -> Vec<[f32; 4]> {
+        let s = self.scale_factor;
+        match filter {
+            Filter::Blur { radius } => self.blur(src, radius * s, clamp_edges),
+            Filter::DropShadow {
+                offset,
+                blur,
+                color,
+            } => {
+                let (w, h) = (self.width as i64, self.height as i64);
+                let (dx, dy) = (
+                    (offset[0] * s).round() as i64,
+                    (offset[1] * s).round() as i64,
+                );
+                let tint = lin(*color);
+                let mut shadow = vec![[0.0f32; 4]; src.len()];
+                for y in 0..h {
+                    for x in 0..w {
+                        let (sx, sy) = (x - dx, y - dy);
+                        if sx < 0 || sy < 0 || sx >= w || sy >= h {
+                            continue;
+                        }
+                        let a = src[(sy * w + sx) as usize][3];
+                        shadow[(y * w + x) as usize] = tint.map(|c| c * a);
+                    }
+                }
+                let mut out = self.blur(&shadow, blur * 0.5 * s, false);
+                for (o, c) in out.iter_mut().zip(src) {
+                    *o = BlendMode::Normal.composite(*o, *c);
+                }
+                out
+            }
+            Filter::ColorMatrix(m) => src.iter().map(|c| apply_color_matrix(m, *c)).collect(),
+        }
+    }
+
+    /// Separable Gaussian blur with standard deviation `sigma` device pixels.
+    fn blur(&self, src: &[[f32; 4]], sigma: f32, clamp_edges: bool) -> Vec<[f32; 4]> {
+        if sigma <= 0.0 || !sigma.is_finite() {
+            return src.to_vec();
+        }
+        let r = (sigma * 3.0).ceil() as i64;
+        let weights: Vec<f32> = (-r..=r)
+            .map(|i| (-0.5 * (i * i) as f32 / (sigma * sigma)).exp())
+            .collect();
+        let norm: f32 = weights.iter().sum();
+        let (w, h) = (self.width as i64, self.height as i64);
+        let pass = |src: &[[f32; 4]], horizontal: bool| -> Vec<[f32; 4]> {
+            let mut out = vec![[0.0f32; 4]; src.len()];
+            for y in 0..h {
+                for x in 0..w {
+                    let mut acc = [0.0f32; 4];
+                    for (k, wt) in (-r..=r).zip(&weights) {
+                        let (mut sx, mut sy) = if horizontal { (x + k, y) } else { (x, y + k) };
+                        if clamp_edges {
+                            sx = sx.clamp(0, w - 1);
+                            sy = sy.clamp(0, h - 1);
+                        } else if sx < 0 || sy < 0 || sx >= w || sy >= h {
+                            continue;
+                        }
+                        let c = src[(sy * w + sx) as usize];
+                        for ch in 0..4 {
+                            acc[ch] += c[ch] * wt;
+                        }
+                    }
+                    out[(y * w + x) as usize] = acc.map(|v| v / norm);
+                }
+            }
+            out
+        };
+        pass(&pass(src, true), false)
+    }
+
+    /// Per-pixel alpha of a layer mask.
+    fn mask_alpha(&mut self, mask: &LayerMask) -> Vec<f32> {
+        let blank = vec![[0.0; 4]; self.pixels.len()];
+        let saved = std::mem::replace(&mut self.pixels, blank);
+        if let (Some(paint), Some(path)) = (
+            self.path_paint(&mask.brush, mask.transform),
+            rect_path(mask.rect),
+        ) {
+            self.fill(&path, FillRule::NonZero, mask.transform, None, &paint);
+        }
+        let painted = std::mem::replace(&mut self.pixels, saved);
+        painted.iter().map(|p| p[3]).collect()
+    }
+    /// Blend a solid rect over everything drawn so far, ignoring z-order, like
+    /// overlay and scrim rects on the GPU. `rect` is in logical pixels.
+    pub fn fill_overlay_rect(&mut self, rect: Rect, color: ColorLinPremul) {
+        if let Some(path) = rect_path(rect) {
+            let paint = Paint::Solid(lin(color));
+            self.fill(
+                &path,
+                FillRule::NonZero,
+                Transform2D::identity(),
+                None,
+                &paint,
+            );
+        }
+    }
+
+    /// Blend a viewport-sized scrim that leaves `hole` uncovered.
+    pub fn fill_scrim_with_cutout(&mut self, hole: RoundedRect, color: ColorLinPremul) {
+        let s = self.scale_factor;
+        let mut pb = PathBuilder::new();
+        push_rect(
+            &mut pb,
+            0.0,
+            0.0,
+            self.width as f32 / s,
+            self.height as f32 / s,
+        );
+        let Some(outer) = pb.finish() else {
+            return;
+        };
+        let Some(inner) = to_sk_path(&hole.to_path()) else {
+            return;
+        };
+        let mut pb = PathBuilder::new();
+        pb.push_path(&outer);
+        pb.push_path(&inner);
+        if let Some(path) = pb.finish() {
+            let paint = Paint::Solid(lin(color));
+            self.fill(
+                &path,
+                FillRule::EvenOdd,
+                Transform2D::identity(),
+                None,
+                &paint,
+            );
+        }
+    }
+
+    /// Pixel size of an image file, decoding it into the renderer's cache.
+    pub fn image_size(&mut self, path: &FsPath) -> Option<(u32, u32)> {
+        self.load_image(path).map(|img| (img.width, img.height))
+    }
+
+    fn device_transform(&self, t: Transform2D) -> Transform2D {
+        Transform2D::scale(self.scale_factor, self.scale_factor).concat(t)
+    }
+
+    /// Mask of everything inside all clips of `stack`; `None` for no clips.
+    fn stack_mask(&self, stack: &[Clip]) -> Option<Arc<Mask>> {
+        stack.iter().fold(None, |parent, clip| {
+            self.clip_mask(parent.as_deref(), &clip.shape, clip.transform)
+                .map(Arc::new)
+        })
+    }
+
+    fn clip_mask(&self, parent: Option<&Mask>, clip: &ClipShape, t: Transform2D) -> Option<Mask> {
+        let ts = sk_transform(self.device_transform(t));
+        let (path, rule) = match clip {
+            ClipShape::Rect(rect) => (rect_path(*rect), tiny_skia::FillRule::Winding),
+            ClipShape::RoundedRect(rrect) => {
+                (to_sk_path(&rrect.to_path()), tiny_skia::FillRule::Winding)
+            }
+            ClipShape::Path(path) => (
+                to_sk_path(path),
+                match path.fill_rule {
+                    FillRule::NonZero => tiny_skia::FillRule::Winding,
+                    FillRule::EvenOdd => tiny_skia::FillRule::EvenOdd,
+                },
+            ),
+        };
+        let mut mask = match parent {
+            Some(parent) => parent.clone(),
+            None => {
+                let mut mask = Mask::new(self.width, self.height)?;
+                mask.data_mut().fill(255);
+                mask
+            }
+        };
+        match path {
+            Some(path) => mask.intersect_path(&path, rule, true, ts),
+            // An empty clip shape clips everything.
+            None => mask.data_mut().fill(0),
+        }
+        Some(mask)
+    }
+
+    fn draw_command(&mut self, cmd: &Command, clip: Option<&Mask>) {
+        match cmd {
+            Command::DrawRect {
+                rect,
+                brush,
+                transform,
+                ..
+            } => {
+                let paint = match brush {
+                    Brush::Solid(c) => Paint::Solid(lin(*c)),
+                    // Pad linear gradients on rects run left to right, as
+                    // in the GPU upload.
+                    Brush::LinearGradient {
+                        stops,
+                        spread: SpreadMode::Pad,
+                        ..
+                    } => {
+                        let Some(stops) = ramp_stops(stops, SpreadMode::Pad) else {
+                            return;
+                        };
+                        Paint::Linear {
+                            device: self.device_transform(*transform),
+                            start: [rect.x, rect.y],
+                            end: [rect.x + rect.w, rect.y],
+                            stops,
+                            spread: SpreadMode::Pad,
+                        }
+                    }
+                    Brush::Image(_) => return,
+                    _ => match self.path_paint(brush, *transform) {
+                        Some(paint) => paint,
+                        None => return,
+                    },
+                };
+                if let Some(path) = rect_path(*rect) {
+                    self.fill(&path, FillRule::NonZero, *transform, clip, &paint);
+                }
+            }
+            Command::DrawRoundedRect {
+                rrect,
+                brush: Brush::Solid(c),
+                transform,
+                ..
+            } => {
+                if let Some(path) = to_sk_path(&rrect.to_path()) {
+                    self.fill(
+                        &path,
+                        FillRule::NonZero,
+                        *transform,
+                        clip,
+                        &Paint::Solid(lin(*c)),
+                    );
+                }
+            }
+            Command::StrokeRect {
+                rect,
+                stroke,
+                brush: Brush::Solid(c),
+                transform,
+                ..
+            } => {
+                // Inside stroke: outer rect minus the rect inset by the width.
+                let w = stroke.width.max(0.0);
+                if w <= 0.0001 {
+                    return;
+                }
+                if !stroke.has_square_corners() {
+                    let path = crate::upload::rect_stroke_centerline(*rect, w);
+                    self.stroke(&path, stroke, *transform, clip, &Paint::Solid(lin(*c)));
+                    return;
+                }
+                let ix0 = rect.x + w;
+                let iy0 = rect.y + w;
+                let ix1 = (rect.x + rect.w - w).max(ix0);
+                let iy1 = (rect.y + rect.h - w).max(iy0);
+                let mut pb = PathBuilder::new();
+                push_rect(&mut pb, rect.x, rect.y, rect.x + rect.w, rect.y + rect.h);
+                push_rect(&mut pb, ix0, iy0, ix1, iy1);
+                if let Some(path) = pb.finish() {
+                    self.fill(
+                        &path,
+                        FillRule::EvenOdd,
+                        *transform,
+                        clip,
+                        &Paint::Solid(lin(*c)),
+                    );
+                }
+            }
+            Command::StrokeRoundedRect {
+                rrect,
+                stroke,
+                brush: Brush::Solid(c),
+                transform,
+                ..
+            } => {
+                if stroke.width <= 0.0001 {
+                    return;
+                }
+                let path = rrect.to_path();
+                self.stroke(&path, stroke, *transform, clip, &Paint::Solid(lin(*c)));
+            }
+            Command::DrawBorder {
+                rrect,
+                border,
+                transform,
+                ..
+            } => {
+                let Some(ring) = to_sk_path(&border.ring(rrect)) else {
+                    return;
+                };
+                if border.is_single_color() {
+                    let paint = Paint::Solid(lin(border.colors[0]));
+                    self.fill(&ring, FillRule::EvenOdd, *transform, clip, &paint);
+                    return;
+                }
+                // Paint each side through a mask of its region so the
+                // colors meet along the corner join lines.
+                for (side, quad) in border.side_regions(rrect).iter().enumerate() {
+                    if border.widths[side] <= 0.0 {
+                        continue;
+                    }
+                    let mut region = Path {
+                        cmds: quad.iter().map(|&p| PathCmd::LineTo(p)).collect(),
+                        fill_rule: FillRule::NonZero,
+                    };
+                    region.cmds[0] = PathCmd::MoveTo(quad[0]);
+                    region.cmds.push(PathCmd::Close);
+                    let Some(mask) = self.clip_mask(clip, &ClipShape::Path(region), *transform)
+                    else {
+                        continue;
+                    };
+                    let paint = Paint::Solid(lin(border.colors[side]));
+                    self.fill(&ring, FillRule::EvenOdd, *transform, Some(&mask), &paint);
+                }
+            }
+            Command::DrawEllipse {
+                center,
+                radii,
+                brush,
+                transform,
+                ..
+            } => {
+                let paint = match brush {
+                    Brush::Solid(c) => Paint::Solid(lin(*c)),
+                    // Pad radial gradients fill the ellipse itself, as in
+                    // the GPU upload.
+                    Brush::RadialGradient {
+                        stops,
+                        spread: SpreadMode::Pad,
+                        ..
+                    } => {
+                        let Some(stops) = ramp_stops(stops, SpreadMode::Pad) else {
+                            return;
+                        };
+                        Paint::Radial {
+                            device: self.device_transform(*transform),
+                            center: *center,
+                            radii: *radii,
+                            stops,
+                            spread: SpreadMode::Pad,
+                        }
+                    }
+                    Brush::Image(_) => return,
+                    _ => match self.path_paint(brush, *transform) {
+                        Some(paint) => paint,
+                        None => return,
+                    },
+                };
+                let oval = tiny_skia::Rect::from_xywh(
+                    center[0] - radii[0],
+                    center[1] - radii[1],
+                    radii[0] * 2.0,
+                    radii[1] * 2.0,
+                );
+                if let Some(path) = oval.and_then(PathBuilder::from_oval) {
+                    self.fill(&path, FillRule::NonZero, *transform, clip, &paint);
+                }
+            }
+            Command::FillPath {
+                path,
+                brush,
+                transform,
+                ..
+            } => {
+                let Some(paint) = self.path_paint(brush, *transform) else {
+                    return;
+                };
+                if let Some(sk) = to_sk_path(path) {
+                    self.fill(&sk, path.fill_rule, *transform, clip, &paint);
+                }
+            }
+            Command::StrokePath {
+                path,
+                stroke,
+                brush,
+                transform,
+                ..
+            } => {
+                let Some(paint) = self.path_paint(brush, *transform) else {
+                    return;
+                };
+                self.stroke(path, stroke, *transform, clip, &paint);
+            }
+            Command::BoxShadow {
+                rrect,
+                spec,
+                transform,
+                ..
+            } => self.draw_box_shadow(*rrect, *spec, *transform, clip),
+            Command::DrawText { run, transform, .. } => self.draw_text_run(run, *transform, clip),
+            Command::DrawHyperlink {
+                hyperlink,
+                transform,
+                ..
+            } => {
+                let run = TextRun {
+                    text: hyperlink.text.clone(),
+                    pos: hyperlink.pos,
+                    size: hyperlink.size,
+                    color: hyperlink.color,
+                    spans: Vec::new(),
+                };
+                self.draw_text_run(&run, *transform, clip);
+                if hyperlink.underline {
+                    // Same width heuristic as the unified upload path.
+                    let color = hyperlink.underline_color.unwrap_or(hyperlink.color);
+                    let char_count = hyperlink.text.trim_end().chars().count() as f32;
+                    let mut text_width = char_count * hyperlink.size * 0.50;
+                    let inset = hyperlink.size * 0.20;
+                    if text_width > inset * 2.0 {
+                        text_width -= inset * 2.0;
+                    }
+                    let underline = Rect {
+                        x: hyperlink.pos[0] + inset,
+                        y: hyperlink.pos[1] + hyperlink.size * 0.10,
+                        w: text_width,
+                        h: (hyperlink.size * 0.08).max(1.0),
+                    };
+                    if let Some(path) = rect_path(underline) {
+                        self.fill(
+                            &path,
+                            FillRule::NonZero,
+                            *transform,
+                            clip,
+                            &Paint::Solid(lin(color)),
+                        );
+                    }
+                }
+            }
+            Command::DrawImage {
+                path,
+                origin,
+                size,
+                transform,
+                ..
+            } => {
+                if let Some(img) = self.load_image(path) {
+                    self.draw_linear_image(&img, *origin, *size, *transform, clip);
+                }
+            }
+            Command::DrawSvg {
+                path,
+                origin,
+                max_size,
+                transform,
+                ..
+            } => self.draw_svg(path, *origin, *max_size, None, *transform, clip),
+            _ => {}
+        }
+    }
+
+    /// Paint for a path brush, with gradient and pattern coordinates in the
+    /// path's local space. `None` for an empty ramp or an unreadable image.
+    fn path_paint(&mut self, brush: &Brush, transform: Transform2D) -> Option<Paint> {
+        let device = self.device_transform(transform);
+        Some(match brush {
+            Brush::Solid(c) => Paint::Solid(lin(*c)),
+            Brush::LinearGradient {
+                start,
+                end,
+                stops,
+                spread,
+            } => Paint::Linear {
+                device,
+                start: *start,
+                end: *end,
+                stops: ramp_stops(stops, *spread)?,
+                spread: *spread,
+            },
+            Brush::RadialGradient {
+                center,
+                radius,
+                stops,
+                spread,
+            } => Paint::Radial {
+                device,
+                center: *center,
+                radii: [*radius; 2],
+                stops: ramp_stops(stops, *spread)?,
+                spread: *spread,
+            },
+            Brush::ConicGradient {
+                center,
+                angle,
+                stops,
+                spread,
+            } => Paint::Conic {
+                device,
+                center: *center,
+                angle: *angle,
+                stops: ramp_stops(stops, *spread)?,
+                spread: *spread,
+            },
+            Brush::Image(pattern) => Paint::Pattern {
+                device,
+                image: self.load_image(&pattern.path)?,
+                pattern: pattern.clone(),
+            },
+        })
+    }
+
+    fn fill(
+        &mut self,
+        path: &tiny_skia::Path,
+        rule: FillRule,
+        transform: Transform2D,
+        clip: Option<&Mask>,
+        paint: &Paint,
+    ) {
+        let ts = sk_transform(self.device_transform(transform));
+        let Some(device) = path.clone().transform(ts) else {
+            return;
+        };
+        let rule = match rule {
+            FillRule::NonZero => tiny_skia::FillRule::Winding,
+            FillRule::EvenOdd => tiny_skia::FillRule::EvenOdd,
+        };
+        let Some((x0, y0, coverage)) = self.coverage(&device, rule) else {
+            return;
+        };
+        let (mw, mh) = (coverage.width(), coverage.height());
+        let data = coverage.data();
+        for my in 0..mh {
+            for mx in 0..mw {
+                let cov = data[(my * mw + mx) as usize];
+                if cov == 0 {
+                    continue;
+                }
+                let (px, py) = (x0 + mx, y0 + my);
+                let src = paint.color_at(px as f32 + 0.5, py as f32 + 0.5);
+                self.blend(px, py, src, cov as f32 / 255.0, clip);
+            }
+        }
+    }
+
+    /// Stroke `path`, dashing it first like the GPU upload does.
+    fn stroke(
+        &mut self,
+        path: &Path,
+        stroke: &Stroke,
+        transform: Transform2D,
+        clip: Option<&Mask>,
+        paint: &Paint,
+    ) {
+        let width = stroke.width.max(0.0);
+        if width <= 0.0 {
+            return;
+        }
+        let path = if stroke.is_dashed() {
+            to_sk_path(&path.dashed(&stroke.dash, stroke.dash_offset))
+        } else {
+            to_sk_path(path)
+        };
+        let Some(path) = path else {
+            return;
+        };
+        let sk_stroke = tiny_skia::Stroke {
+            width,
+            miter_limit: stroke.miter_limit.max(1.0),
+            line_cap: match stroke.cap {
+                LineCap::Butt => tiny_skia::LineCap::Butt,
+                LineCap::Square => tiny_skia::LineCap::Square,
+                LineCap::Round => tiny_skia::LineCap::Round,
+            },
+            line_join: match stroke.join {
+                LineJoin::Miter => tiny_skia::LineJoin::Miter,
+                LineJoin::Bevel => tiny_skia::LineJoin::Bevel,
+                LineJoin::Round => tiny_skia::LineJoin::Round,
+            },
+            dash: None,
+        };
+        let ts = sk_transform(self.device_transform(transform));
+        let res_scale = tiny_skia::PathStroker::compute_resolution_scale(&ts);
+        if let Some(outline) = path.stroke(&sk_stroke, res_scale) {
+            self.fill(&outline, FillRule::NonZero, transform, clip, paint);
+        }
+    }
+
+    /// Rasterize a device-space path into a coverage mask cropped to its
+    /// bounds. Returns the mask and its top-left pixel.
+    fn coverage(
+        &self,
+        device: &tiny_skia::Path,
+        rule: tiny_skia::FillRule,
+    ) -> Option<(u32, u32, Mask)> {
+        let b = device.bounds();
+        let x0 = b.left().floor().max(0.0) as u32;
+        let y0 = b.top().floor().max(0.0) as u32;
+        let x1 = (b.right().ceil().max(0.0) as u32).min(self.width);
+        let y1 = (b.bottom().ceil().max(0.0) as u32).min(self.height);
+        if x1 <= x0 || y1 <= y0 {
+            return None;
+        }
+        let mut mask = Mask::new(x1 - x0, y1 - y0)?;
+        mask.fill_path(
+            device,
+            rule,
+            true,
+            tiny_skia::Transform::from_translate(-(x0 as f32), -(y0 as f32)),
+        );
+        Some((x0, y0, mask))
+    }
+
+    /// Source-over of a premultiplied color scaled by `coverage` and the clip.
+    #[inline]
+    fn blend(&mut self, x: u32, y: u32, src: [f32; 4], coverage: f32, clip: Option<&Mask>) {
+        let idx = (y * self.width + x) as usize;
+        let mut k = coverage;
+        if let Some(clip) = clip {
+            k *= clip.data()[idx] as f32 / 255.0;
+        }
+        if k <= 0.0 {
+            return;
+        }
+        let dst = &mut self.pixels[idx];
+        let inv = 1.0 - src[3] * k;
+        for i in 0..4 {
+            dst[i] = src[i] * k + dst[i] * inv;
+        }
+    }
+
+    fn draw_box_shadow(
+        &mut self,
+        rrect: RoundedRect,
+        spec: BoxShadowSpec,
+        transform: Transform2D,
+        clip: Option<&Mask>,
+    ) {
+        let blur = spec.blur_radius.max(0.0);
+        let sigma = if blur > 0.0 { blur } else { 0.5 };
+        let spread = spec.spread.max(0.0);
+        let expand = spread + 1.8 * sigma + 1.0;
+        let shape = RoundedRect {
+            rect: Rect {
+                x: rrect.rect.x + spec.offset[0] - expand,
+                y: rrect.rect.y + spec.offset[1] - expand,
+                w: (rrect.rect.w + 2.0 * expand).max(0.0),
+                h: (rrect.rect.h + 2.0 * expand).max(0.0),
+            },
+            radii: rrect.radii.inset([-spread; 4]),
+        };
+        let Some(path) = to_sk_path(&shape.to_path()) else {
+            return;
+        };
+        let ts = sk_transform(self.device_transform(transform));
+        let Some(device) = path.transform(ts) else {
+            return;
+        };
+
+        // Blur in device pixels over the shape bounds padded by the kernel.
+        let sigma_px = (sigma * self.scale_factor).max(0.25);
+        let radius = (6.0 * sigma_px).ceil().clamp(1.0, 64.0) as i32;
+        let b = device.bounds();
+        let x0 = ((b.left().floor() as i32) - radius).max(0);
+        let y0 = ((b.top().floor() as i32) - radius).max(0);
+        let x1 = ((b.right().ceil() as i32) + radius).min(self.width as i32);
+        let y1 = ((b.bottom().ceil() as i32) + radius).min(self.height as i32);
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        let (w, h) = ((x1 - x0) as usize, (y1 - y0) as usize);
+        let Some(mut mask) = Mask::new(w as u32, h as u32) else {
+            return;
+        };
+        mask.fill_path(
+            &device,
+            tiny_skia::FillRule::Winding,
+            true,
+            tiny_skia::Transform::from_translate(-(x0 as f32), -(y0 as f32)),
+        );
+
+        let weights: Vec<f32> = {
+            let raw: Vec<f32> = (-radius..=radius)
+                .map(|i| (-(i * i) as f32 / (2.0 * sigma_px * sigma_px)).exp())
+                .collect();
+            let sum: f32 = raw.iter().sum();
+            raw.into_iter().map(|v| v / sum).collect()
+        };
+        let src: Vec<f32> = mask.data().iter().map(|&v| v as f32 / 255.0).collect();
+        let mut tmp = vec![0.0f32; w * h];
+        for y in 0..h {
+            for x in 0..w {
+                let mut acc = 0.0;
+                for (k, wgt) in weights.iter().enumerate() {
+                    let sx = x as i32 + k as i32 - radius;
+                    if sx >= 0 && (sx as usize) < w {
+                        acc += src[y * w + sx as usize] * wgt;
+                    }
+                }
+                tmp[y * w + x] = acc;
+            }
+        }
+        let color = lin(spec.color);
+        for y in 0..h {
+            for x in 0..w {
+                let mut acc = 0.0;
+                for (k, wgt) in weights.iter().enumerate() {
+                    let sy = y as i32 + k as i32 - radius;
+                    if sy >= 0 && (sy as usize) < h {
+                        acc += tmp[sy as usize * w + x] * wgt;
+                    }
+                }
+                if acc > 0.0 {
+                    self.blend(
+                        x0 as u32 + x as u32,
+                        y0 as u32 + y as u32,
+                        color,
+                        acc.min(1.0),
+                        clip,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Rasterize a display-list text run with the text provider, following
+    /// the run placement and small-text snapping of `RuneSurface::end_frame`.
+    fn draw_text_run(&mut self, run: &TextRun, transform: Transform2D, clip: Option<&Mask>) {
+        let Some(provider) = self.text_provider.clone() else {
+            return;
+        };
+        let [a, b, c, d, e, f] = transform.m;
+        let origin_x = a * run.pos[0] + c * run.pos[1] + e;
+        let origin_y = b * run.pos[0] + d * run.pos[1] + f;
+        let sx = (a * a + b * b).sqrt();
+        let sy = (c * c + d * d).sqrt();
+        let s = if sx > 0.0 && sy > 0.0 {
+            (sx + sy) * 0.5
+        } else {
+            sx.max(sy).max(1.0)
+        };
+        let s = if s.is_finite() && s > 0.0 { s } else { 1.0 };
+        let scaled_size = (run.size * s).max(1.0);
+        let sized = TextRun {
+            text: run.text.clone(),
+            pos: [0.0, 0.0],
+            size: scaled_size,
+            color: run.color,
+            spans: run.scaled_spans(s),
+        };
+        let sf = self.scale_factor;
+        let glyphs = crate::text::rasterize_run_cached(provider.as_ref(), &sized);
+        for g in glyphs.iter() {
+            let mut origin = [origin_x + g.offset[0], origin_y + g.offset[1]];
+            if scaled_size <= 15.0 {
+                origin[0] = (origin[0] * sf).round() / sf;
+                origin[1] = (origin[1] * sf).round() / sf;
+            }
+            self.draw_glyph(origin, g, g.color.unwrap_or(run.color), clip);
+        }
+    }
+
+    /// Composite a subpixel glyph mask at a logical origin: color channels are
+    /// modulated per subpixel and alpha uses the max coverage, as `TEXT_WGSL`.
+    /// Color glyph images are composited as is, scaled by the color's alpha.
+    fn draw_glyph(
+        &mut self,
+        origin: [f32; 2],
+        glyph: &RasterizedGlyph,
+        color: ColorLinPremul,
+        clip: Option<&Mask>,
+    ) {
+        let mask = &glyph.mask;
+        if mask.width == 0 || mask.height == 0 {
+            return;
+        }
+        let s = self.scale_factor;
+        let gx = origin[0] * s;
+        let gy = origin[1] * s;
+        let gw = mask.width as f32 * s;
+        let gh = mask.height as f32 * s;
+        let px0 = gx.floor().max(0.0) as u32;
+        let py0 = gy.floor().max(0.0) as u32;
+        let px1 = ((gx + gw).ceil().max(0.0) as u32).min(self.width);
+        let py1 = ((gy + gh).ceil().max(0.0) as u32).min(self.height);
+        let bpp = mask.bytes_per_pixel();
+        for py in py0..py1 {
+            let v = ((py as f32 + 0.5) - gy) / s;
+            if v < 0.0 || v >= mask.height as f32 {
+                continue;
+            }
+            for px in px0..px1 {
+                let u = ((px as f32 + 0.5) - gx) / s;
+                if u < 0.0 || u >= mask.width as f32 {
+                    continue;
+                }
+                let i = ((v as u32 * mask.width + u as u32) as usize) * bpp;
+                if mask.format == MaskFormat::ColorRgba8 {
+                    // Premultiplied sRGB texel; only the text alpha applies.
+                    let a = mask.data[i + 3] as f32 / 255.0;
+                    if a <= 0.0 {
+                        continue;
+                    }
+                    let lin = |c: u8| srgb_to_linear((c as f32 / a).round().min(255.0) as u8) * a;
+                    let src = [
+                        lin(mask.data[i]) * color.a,
+                        lin(mask.data[i + 1]) * color.a,
+                        lin(mask.data[i + 2]) * color.a,
+                        a * color.a,
+                    ];
+                    self.blend(px, py, src, 1.0, clip);
+                    continue;
+                }
+                let m = match mask.format {
+                    MaskFormat::Rgba8 => [
+                        mask.data[i] as f32 / 255.0,
+                        mask.data[i + 1] as f32 / 255.0,
+                        mask.data[i + 2] as f32 / 255.0,
+                    ],
+                    MaskFormat::Rgba16 => {
+                        let ch = |o: usize| {
+                            u16::from_le_bytes([mask.data[i + o], mask.data[i + o + 1]]) as f32
+                                / 65535.0
+                        };
+                        [ch(0), ch(2), ch(4)]
+                    }
+                    MaskFormat::ColorRgba8 => unreachable!("color glyphs are drawn above"),
+                };
+                let cov = m[0].max(m[1]).max(m[2]);
+                if cov <= 0.0 {
+                    continue;
+                }
+                let src = [
+                    color.r * m[0],
+                    color.g * m[1],
+                    color.b * m[2],
+                    color.a * cov,
+                ];
+                self.blend(px, py, src, 1.0, clip);
+            }
+        }
+    }
+
+    fn load_image(&mut self, path: &FsPath) -> Option<Arc<LinearImage>> {
+        self.images
+            .entry(path.to_path_buf())
+            .or_insert_with(|| {
+                let rgba = image::open(path).ok()?.to_rgba8();
+                let pixels = rgba
+                    .pixels()
+                    .map(|p| {
+                        [
+                            srgb_to_linear(p[0]),
+                            srgb_to_linear(p[1]),
+                            srgb_to_linear(p[2]),
+                            p[3] as f32 / 255.0,
+                        ]
+                    })
+                    .collect();
+                Some(Arc::new(LinearImage {
+                    width: rgba.width(),
+                    height: rgba.height(),
+                    pixels,
+                }))
+            })
+            .clone()
+    }
+
+    fn draw_svg(
+        &mut self,
+        path: &FsPath,
+        origin: [f32; 2],
+        max_size: [f32; 2],
+        style: Option<SvgStyle>,
+        transform: Transform2D,
+        clip: Option<&Mask>,
+    ) {
+        let Some((w0, h0)) = crate::svg::svg_intrinsic_size(path) else {
+            return;
+        };
+        // Fit inside max_size, then rasterize at device resolution.
+        let fit = (max_size[0] / w0 as f32)
+            .min(max_size[1] / h0 as f32)
+            .max(0.0);
+        let size = [w0 as f32 * fit, h0 as f32 * fit];
+        let raster_scale = fit * self.scale_factor;
+        let Some(img) = rasterize_svg(path, raster_scale, style) else {
+            return;
+        };
+        self.draw_linear_image(&img, origin, size, transform, clip);
+    }
+
+    /// Draw an image stretched over `[origin, origin + size]` in local space,
+    /// sampled bilinearly and premultiplied after filtering like `IMAGE_WGSL`.
+    fn draw_linear_image(
+        &mut self,
+        img: &LinearImage,
+        origin: [f32; 2],
+        size: [f32; 2],
+        transform: Transform2D,
+        clip: Option<&Mask>,
+    ) {
+        if size[0] <= 0.0 || size[1] <= 0.0 || img.width == 0 || img.height == 0 {
+            return;
+        }
+        let rect = Rect {
+            x: origin[0],
+            y: origin[1],
+            w: size[0],
+            h: size[1],
+        };
+        let Some(path) = rect_path(rect) else {
+            return;
+        };
+        let device = self.device_transform(transform);
+        let Some(device_path) = path.transform(sk_transform(device)) else {
+            return;
+        };
+        let Some((x0, y0, coverage)) = self.coverage(&device_path, tiny_skia::FillRule::Winding)
+        else {
+            return;
+        };
+        let (mw, mh) = (coverage.width(), coverage.height());
+        for my in 0..mh {
+            for mx in 0..mw {
+                let cov = coverage.data()[(my * mw + mx) as usize];
+                if cov == 0 {
+                    continue;
+                }
+                let (px, py) = (x0 + mx, y0 + my);
+                let Some(p) = device.inverse_apply([px as f32 + 0.5, py as f32 + 0.5]) else {
+                    continue;
+                };
+                let u = (p[0] - origin[0]) / size[0] * img.width as f32;
+                let v = (p[1] - origin[1]) / size[1] * img.height as f32;
+                let c = img.sample_bilinear(u, v);
+                let src = [c[0] * c[3], c[1] * c[3], c[2] * c[3], c[3]];
+                self.blend(px, py, src, cov as f32 / 255.0, clip);
+            }
+        }
+    }
+}
+
+impl LinearImage {
+    /// Bilinear sample at texel-space `(u, v)` with clamp-to-edge addressing.
+    fn sample_bilinear(&self, u: f32, v: f32) -> [f32; 4] {
+        let fx = (u - 0.5).clamp(0.0, (self.width - 1) as f32);
+        let fy = (v - 0.5).clamp(0.0, (self.height - 1) as f32);
+        let x0 = fx.floor() as u32;
+        let y0 = fy.floor() as u32;
+        let x1 = (x0 + 1).min(self.width - 1);
+        let y1 = (y0 + 1).min(self.height - 1);
+        let tx = fx - x0 as f32;
+        let ty = fy - y0 as f32;
+        let at = |x: u32, y: u32| self.pixels[(y * self.width + x) as usize];
+        let (c00, c10, c01, c11) = (at(x0, y0), at(x1, y0), at(x0, y1), at(x1, y1));
+        let mut out = [0.0; 4];
+        for i in 0..4 {
+            let top = c00[i] + (c10[i] - c00[i]) * tx;
+            let bottom = c01[i] + (c11[i] - c01[i]) * tx;
+            out[i] = top + (bottom - top) * ty;
+        }
+        out
+    }
+
+    /// Bilinear sample at texel-space `(u, v)` with repeat addressing.
+    fn sample_bilinear_repeat(&self, u: f32, v: f32) -> [f32; 4] {
+        let fx = u - 0.5;
+        let fy = v - 0.5;
+        let (w, h) = (self.width as i64, self.height as i64);
+        let x0 = fx.floor() as i64;
+        let y0 = fy.floor() as i64;
+        let tx = fx - x0 as f32;
+        let ty = fy - y0 as f32;
+        let at = |x: i64, y: i64| self.pixels[(y.rem_euclid(h) * w + x.rem_euclid(w)) as usize];
+        let (c00, c10, c01, c11) = (
+            at(x0, y0),
+            at(x0 + 1, y0),
+            at(x0, y0 + 1),
+            at(x0 + 1, y0 + 1),
+        );
+        let mut out = [0.0; 4];
+        for i in 0..4 {
+            let top = c00[i] + (c10[i] - c00[i]) * tx;
+            let bottom = c01[i] + (c11[i] - c01[i]) * tx;
+            out[i] = top + (bottom - top) * ty;
+        }
+        out
+    }
+}
+
+/// Render an SVG with resvg at `scale` and convert it to straight linear RGBA.
+fn rasterize_svg(path: &FsPath, scale: f32, style: Option<SvgStyle>) -> Option<LinearImage> {
+    let mut data = std::fs::read(path).ok()?;
+    if let Some(style) = style
+        && (style.fill.is_some() || style.stroke.is_some() || style.stroke_width.is_some())
+    {
+        data = crate::svg::apply_style_overrides_to_xml(&data, style)?;
+    }
+    let opt = usvg::Options {
+        resources_dir: path.parent().map(|p| p.to_path_buf()),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_data(&data, &opt).ok()?;
+    let size = tree.size().to_int_size();
+    let w = ((size.width() as f32) * scale).round() as u32;
+    let h = ((size.height() as f32) * scale).round() as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(w, h)?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .map(|p| {
+            let c = p.demultiply();
+            [
+                srgb_to_linear(c.red()),
+                srgb_to_linear(c.green()),
+                srgb_to_linear(c.blue()),
+                c.alpha() as f32 / 255.0,
+            ]
+        })
+        .collect();
+    Some(LinearImage {
+        width: w,
+        height: h,
+        pixels,
+    })
+}
+
+fn srgb_to_linear(v: u8) -> f32 {
+    let c = v as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[inline]
+/// Pop the innermost open layer into an item of its parent group.
+fn close_layer<'a>(
+    groups: &mut Vec<(Option<LayerGroup<'a>>, usize)>,
+    items: &mut Vec<Vec<Item<'a>>>,
+    layers: &[CpuSideDraws<'a>],
+    side_clip: &mut impl FnMut(u32) -> Option<Arc<Mask>>,
+) {
+    let (Some((Some(mut group), index)), Some(mut content)) = (groups.pop(), items.pop()) else {
+        return;
+    };
+    if let Some(side) = layers.get(index) {
+        push_side_draws(&mut content, *side, side_clip);
+    }
+    // The layer takes the slot of its lowest item or its backdrop filter;
+    // empty layers draw nothing.
+    let backdrop = group.backdrop.map(|b| (b.z, Pass::Solid));
+    let Some((z, pass)) = content
+        .iter()
+        .map(|it| (it.z, it.pass))
+        .chain(backdrop)
+        .min()
+    else {
+        return;
+    };
+    group.items = content;
+    if let Some(parent) = items.last_mut() {
+        parent.push(Item {
+            z,
+            pass,
+            kind: ItemKind::Layer(group),
+            clip: None,
+        });
+    }
+}
+
+fn push_side_draws<'a>(
+    items: &mut Vec<Item<'a>>,
+    side: CpuSideDraws<'a>,
+    side_clip: &mut impl FnMut(u32) -> Option<Arc<Mask>>,
+) {
+    for g in side.glyph_draws {
+        items.push(Item {
+            z: g.3,
+            pass: Pass::Text,
+            kind: ItemKind::Glyph(g),
+            clip: side_clip(g.4),
+        });
+    }
+    for d in side.svg_draws {
+        items.push(Item {
+            z: d.4,
+            pass: Pass::Svg,
+            kind: ItemKind::Svg(d),
+            clip: side_clip(d.6),
+        });
+    }
+    for d in side.image_draws {
+        items.push(Item {
+            z: d.3,
+            pass: Pass::Image,
+            kind: ItemKind::Image(d),
+            clip: side_clip(d.4),
+        });
+    }
+}
+
+fn lin(c: ColorLinPremul) -> [f32; 4] {
+    [c.r, c.g, c.b, c.a]
+}
+
+fn sk_transform(t: Transform2D) -> tiny_skia::Transform {
+    let [a, b, c, d, e, f] = t.m;
+    tiny_skia::Transform::from_row(a, b, c, d, e, f)
+}
+
+fn push_rect(pb: &mut PathBuilder, x0: f32, y0: f32, x1: f32, y1: f32) {
+    pb.move_to(x0, y0);
+    pb.line_to(x1, y0);
+    pb.line_to(x1, y1);
+    pb.line_to(x0, y1);
+    pb.close();
+}
+
+fn rect_path(r: Rect) -> Option<tiny_skia::Path> {
+    let mut pb = PathBuilder::new();
+    push_rect(&mut pb, r.x, r.y, r.x + r.w, r.y + r.h);
+    pb.finish()
+}
+
+fn to_sk_path(path: &Path) -> Option<tiny_skia::Path> {
+    let mut pb = PathBuilder::new();
+    for cmd in &path.cmds {
+        match *cmd {
+            PathCmd::MoveTo(p) => pb.move_to(p[0], p[1]),
+            PathCmd::LineTo(p) => pb.line_to(p[0], p[1]),
+            PathCmd::QuadTo(c, p) => pb.quad_to(c[0], c[1], p[0], p[1]),
+            PathCmd::CubicTo(c1, c2, p) => pb.cubic_to(c1[0], c1[1], c2[0], c2[1], p[0], p[1]),
+            PathCmd::Close => pb.close(),
+        }
+    }
+    pb.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Painter;
+    use crate::display_list::Viewport;
+    use crate::scene::{Border, RoundedRadii};
+
+    fn painter(w: u32, h: u32) -> Painter {
+        Painter::begin_frame(Viewport {
+            width: w,
+            height: h,
+        })
+    }
+
+    #[test]
+    fn solid_rect_is_opaque_inside_and_empty_outside() {
+        let mut p = painter(8, 8);
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        p.rect(
+            Rect {
+                x: 2.0,
+                y: 2.0,
+                w: 4.0,
+                h: 4.0,
+            },
+            Brush::Solid(red),
+            0,
+        );
+        let mut cpu = CpuRenderer::new(8, 8);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(3, 3), red);
+        assert_eq!(cpu.pixel(0, 0), ColorLinPremul::default());
+    }
+
+    #[test]
+    fn higher_z_draws_on_top_regardless_of_order() {
+        let mut p = painter(4, 4);
+        let full = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 4.0,
+            h: 4.0,
+        };
+        let top = ColorLinPremul::rgba(0, 0, 255, 255);
+        p.rect(full, Brush::Solid(top), 5);
+        p.rect(full, Brush::Solid(ColorLinPremul::rgba(0, 255, 0, 255)), 1);
+        let mut cpu = CpuRenderer::new(4, 4);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(1, 1), top);
+    }
+
+    #[test]
+    fn blending_happens_in_linear_space() {
+        let mut p = painter(2, 2);
+        let full = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 2.0,
+            h: 2.0,
+        };
+        p.rect(
+            full,
+            Brush::Solid(ColorLinPremul::from_lin_rgba(1.0, 1.0, 1.0, 0.5)),
+            0,
+        );
+        let mut cpu = CpuRenderer::new(2, 2);
+        cpu.clear(ColorLinPremul::from_lin_rgba(0.0, 0.0, 0.0, 1.0));
+        cpu.render(&p.finish());
+        // 50% linear white over black encodes to ~188 in sRGB, not 128.
+        let px = &cpu.to_srgba8()[..4];
+        assert!((186..=189).contains(&px[0]), "got {px:?}");
+        assert_eq!(px[3], 255);
+    }
+
+    #[test]
+    fn clip_rect_limits_coverage() {
+        let mut p = painter(8, 8);
+        p.push_clip_rect(Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 4.0,
+            h: 8.0,
+        });
+        p.rect(
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 8.0,
+                h: 8.0,
+            },
+            Brush::Solid(ColorLinPremul::rgba(255, 255, 255, 255)),
+            0,
+        );
+        p.pop_clip();
+        let mut cpu = CpuRenderer::new(8, 8);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(2, 4).a, 1.0);
+        assert_eq!(cpu.pixel(6, 4).a, 0.0);
+    }
+
+    #[test]
+    fn strokes_honor_dashes_caps_and_joins() {
+        let white = Brush::Solid(ColorLinPremul::rgba(255, 255, 255, 255));
+        let line = |x0: f32, x1: f32| Path {
+            cmds: vec![PathCmd::MoveTo([x0, 4.0]), PathCmd::LineTo([x1, 4.0])],
+            fill_rule: FillRule::NonZero,
+        };
+        let mut p = painter(24, 16);
+        // Dashes cover x = 4..8 and 12..16, with gaps between.
+        p.stroke_path(
+            line(4.0, 20.0),
+            Stroke::new(4.0).with_dash(vec![4.0, 4.0], 0.0),
+            white.clone(),
+            0,
+        );
+        // A square cap extends the stroke by half its width; butt doesn't.
+        p.stroke_path(
+            Path {
+                cmds: vec![PathCmd::MoveTo([4.0, 12.0]), PathCmd::LineTo([8.0, 12.0])],
+                fill_rule: FillRule::NonZero,
+            },
+            Stroke::new(4.0).with_cap(LineCap::Square),
+            white.clone(),
+            0,
+        );
+        p.stroke_path(
+            Path {
+                cmds: vec![PathCmd::MoveTo([16.0, 12.0]), PathCmd::LineTo([20.0, 12.0])],
+                fill_rule: FillRule::NonZero,
+            },
+            Stroke::new(4.0),
+            white,
+            0,
+        );
+        let mut cpu = CpuRenderer::new(24, 16);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(5, 4).a, 1.0);
+        assert_eq!(cpu.pixel(9, 4).a, 0.0);
+        assert_eq!(cpu.pixel(13, 4).a, 1.0);
+        assert_eq!(cpu.pixel(18, 4).a, 0.0);
+        assert_eq!(cpu.pixel(2, 12).a, 1.0);
+        assert_eq!(cpu.pixel(14, 12).a, 0.0);
+        assert_eq!(cpu.pixel(17, 12).a, 1.0);
+
+        // Rect strokes stay inside the rect; round joins cut the corners.
+        let corner = |stroke: Stroke| {
+            let mut p = painter(16, 16);
+            p.stroke_rect(
+                Rect {
+                    x: 0.0,
+                    y: 0.0,
+                    w: 16.0,
+                    h: 16.0,
+                },
+                stroke,
+                Brush::Solid(ColorLinPremul::rgba(255, 255, 255, 255)),
+                0,
+            );
+            let mut cpu = CpuRenderer::new(16, 16);
+            cpu.render(&p.finish());
+            (cpu.pixel(0, 0).a, cpu.pixel(8, 0).a, cpu.pixel(8, 8).a)
+        };
+        assert_eq!(corner(Stroke::new(6.0)), (1.0, 1.0, 0.0));
+        let (round_corner, edge, center) = corner(Stroke::new(6.0).with_join(LineJoin::Round));
+        assert!(round_corner < 0.5, "got {round_corner}");
+        assert_eq!((edge, center), (1.0, 0.0));
+    }
+
+    fn rect_path_cmds(w: f32, h: f32) -> Path {
+        Path {
+            cmds: vec![
+                PathCmd::MoveTo([0.0, 0.0]),
+                PathCmd::LineTo([w, 0.0]),
+                PathCmd::LineTo([w, h]),
+                PathCmd::LineTo([0.0, h]),
+                PathCmd::Close,
+            ],
+            fill_rule: FillRule::NonZero,
+        }
+    }
+
+    #[test]
+    fn path_gradients_follow_local_start_and_end() {
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let mut p = painter(16, 16);
+        p.push_transform(Transform2D::translate(4.0, 0.0));
+        // Vertical ramp over the middle half of the path.
+        p.fill_path(
+            rect_path_cmds(8.0, 16.0),
+            Brush::LinearGradient {
+                start: [0.0, 4.0],
+                end: [0.0, 12.0],
+                stops: vec![(0.0, red), (1.0, blue)],
+                spread: SpreadMode::Pad,
+            },
+            0,
+        );
+        p.pop_transform();
+        let mut cpu = CpuRenderer::new(16, 16);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(5, 1), red);
+        assert_eq!(cpu.pixel(10, 14), blue);
+        let mid = cpu.pixel(6, 8);
+        assert!((mid.r - 0.5).abs() < 0.1 && (mid.b - 0.5).abs() < 0.1);
+        assert_eq!(cpu.pixel(6, 8), cpu.pixel(11, 8));
+        assert_eq!(cpu.pixel(2, 8).a, 0.0);
+    }
+
+    #[test]
+    fn gradients_repeat_reflect_and_sweep() {
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let render = |brush: Brush| {
+            let mut p = painter(16, 16);
+            p.rect(
+                Rect {
+                    x: 0.0,
+                    y: 0.0,
+                    w: 16.0,
+                    h: 16.0,
+                },
+                brush,
+                0,
+            );
+            let mut cpu = CpuRenderer::new(16, 16);
+            cpu.render(&p.finish());
+            cpu
+        };
+        let linear = |spread| Brush::LinearGradient {
+            start: [0.0, 0.0],
+            end: [16.0, 0.0],
+            stops: vec![(0.0, red), (0.25, blue)],
+            spread,
+        };
+        // Each 4px period restarts at red; reflected periods run back.
+        let cpu = render(linear(SpreadMode::Repeat));
+        assert!(cpu.pixel(3, 8).b > 0.8 && cpu.pixel(4, 8).r > 0.8);
+        let cpu = render(linear(SpreadMode::Reflect));
+        assert!(cpu.pixel(3, 8).b > 0.8 && cpu.pixel(4, 8).b > 0.8);
+        assert!(cpu.pixel(7, 8).r > 0.8);
+
+        // Hard stops split the turn: red for the first half clockwise from
+        // the start angle (pointing right), blue for the rest.
+        let cpu = render(Brush::ConicGradient {
+            center: [8.0, 8.0],
+            angle: 0.0,
+            stops: vec![(0.0, red), (0.5, red), (0.5, blue), (1.0, blue)],
+            spread: SpreadMode::Pad,
+        });
+        assert_eq!(cpu.pixel(12, 12), red);
+        assert_eq!(cpu.pixel(3, 12), red);
+        assert_eq!(cpu.pixel(12, 3), blue);
+        assert_eq!(cpu.pixel(3, 3), blue);
+    }
+
+    #[test]
+    fn image_patterns_repeat_only_along_enabled_axes() {
+        let file = std::env::temp_dir().join(format!("rune-pattern-{}.png", std::process::id()));
+        image::RgbaImage::from_pixel(2, 2, image::Rgba([0, 255, 0, 255]))
+            .save(&file)
+            .unwrap();
+        let pattern = ImagePattern::new(
+            &file,
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 4.0,
+                h: 4.0,
+            },
+        )
+        .with_repeat(crate::scene::PatternRepeat::RepeatX)
+        .with_transform(Transform2D::translate(0.0, 4.0));
+        let mut p = painter(16, 16);
+        p.fill_path(rect_path_cmds(16.0, 16.0), Brush::Image(pattern), 0);
+        let mut cpu = CpuRenderer::new(16, 16);
+        cpu.render(&p.finish());
+        std::fs::remove_file(&file).ok();
+
+        let green = ColorLinPremul::rgba(0, 255, 0, 255);
+        assert_eq!(cpu.pixel(1, 5), green);
+        assert_eq!(cpu.pixel(13, 6), green);
+        assert_eq!(cpu.pixel(13, 2).a, 0.0);
+        assert_eq!(cpu.pixel(1, 10).a, 0.0);
+    }
+
+    #[test]
+    fn layer_opacity_applies_to_the_group_not_each_shape() {
+        let mut p = painter(4, 4);
+        let red = Brush::Solid(ColorLinPremul::rgba(255, 0, 0, 255));
+        p.push_layer(0.5, BlendMode::Normal, None);
+        p.rect(
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 3.0,
+                h: 4.0,
+            },
+            red.clone(),
+            0,
+        );
+        p.rect(
+            Rect {
+                x: 1.0,
+                y: 0.0,
+                w: 3.0,
+                h: 4.0,
+            },
+            red,
+            1,
+        );
+        p.pop_layer();
+        let mut cpu = CpuRenderer::new(4, 4);
+        cpu.render(&p.finish());
+        // The overlap would reach 75% alpha if each rect blended separately.
+        assert!((cpu.pixel(0, 1).a - 0.5).abs() < 1e-3);
+        assert!((cpu.pixel(2, 1).a - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn layer_blend_modes_and_masks_composite_against_the_backdrop() {
+        let full = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 4.0,
+            h: 4.0,
+        };
+        let mut p = painter(4, 4);
+        p.rect(
+            full,
+            Brush::Solid(ColorLinPremul::rgba(255, 255, 0, 255)),
+            0,
+        );
+        let left_half = LayerMask::new(
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 2.0,
+                h: 4.0,
+            },
+            Brush::Solid(ColorLinPremul::rgba(0, 0, 0, 255)),
+        );
+        p.push_layer(1.0, BlendMode::Multiply, Some(left_half));
+        p.rect(
+            full,
+            Brush::Solid(ColorLinPremul::rgba(0, 255, 255, 255)),
+            1,
+        );
+        p.pop_layer();
+        let mut cpu = CpuRenderer::new(4, 4);
+        cpu.render(&p.finish());
+        // Yellow multiplied by cyan is green; the mask keeps the right half yellow.
+        assert_eq!(cpu.pixel(1, 1), ColorLinPremul::rgba(0, 255, 0, 255));
+        assert_eq!(cpu.pixel(3, 1), ColorLinPremul::rgba(255, 255, 0, 255));
+    }
+
+    #[test]
+    fn layers_stack_at_the_lowest_z_of_their_content() {
+        let full = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 2.0,
+            h: 2.0,
+        };
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let mut p = painter(2, 2);
+        p.push_layer(0.5, BlendMode::Normal, None);
+        p.rect(full, Brush::Solid(ColorLinPremul::rgba(255, 0, 0, 255)), 9);
+        p.pop_layer();
+        p.rect(full, Brush::Solid(blue), 5);
+        p.push_layer(1.0, BlendMode::Normal, None);
+        p.rect(full, Brush::Solid(ColorLinPremul::rgba(255, 0, 0, 255)), 2);
+        p.rect(full, Brush::Solid(ColorLinPremul::rgba(0, 255, 0, 255)), 8);
+        p.pop_layer();
+        let mut list = p.finish();
+        // The second layer (z 2..8) sorts under the blue rect at z 5, the
+        // first (z 9) over it.
+        assert_eq!(list.sort_by_z_tracking_layers(), vec![1, 0]);
+        let mut cpu = CpuRenderer::new(2, 2);
+        cpu.render(&list);
+        let px = cpu.pixel(0, 0);
+        assert!((px.r - 0.5).abs() < 1e-3 && (px.b - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn rounded_and_path_clips_mask_their_outside() {
+        let full = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 16.0,
+            h: 16.0,
+        };
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let mut p = painter(16, 16);
+        p.push_clip_rounded_rect(RoundedRect {
+            rect: full,
+            radii: RoundedRadii::uniform(8.0),
+        });
+        // Nested: the path clip applies on top of the rounded one.
+        p.push_transform(Transform2D::translate(8.0, 0.0));
+        p.push_clip_path(rect_path_cmds(8.0, 16.0));
+        p.rect(full, Brush::Solid(red), 0);
+        p.pop_clip();
+        p.pop_transform();
+        p.pop_clip();
+        let mut cpu = CpuRenderer::new(16, 16);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(12, 8), red);
+        assert_eq!(cpu.pixel(15, 0).a, 0.0);
+        assert_eq!(cpu.pixel(4, 8).a, 0.0);
+    }
+
+    #[test]
+    fn sorting_keeps_clips_on_their_commands() {
+        let full = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 16.0,
+            h: 16.0,
+        };
+        let mut p = painter(16, 16);
+        p.push_clip_rounded_rect(RoundedRect {
+            rect: full,
+            radii: RoundedRadii::uniform(8.0),
+        });
+        p.rect(full, Brush::Solid(ColorLinPremul::rgba(255, 0, 0, 255)), 4);
+        p.pop_clip();
+        p.rect(
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 4.0,
+                h: 4.0,
+            },
+            Brush::Solid(ColorLinPremul::rgba(0, 0, 255, 255)),
+            1,
+        );
+        let list = p.finish();
+        let mut sorted = list.clone();
+        sorted.sort_by_z();
+        let mut cpu = CpuRenderer::new(16, 16);
+        cpu.render(&list);
+        let mut cpu_sorted = CpuRenderer::new(16, 16);
+        cpu_sorted.render(&sorted);
+        assert_eq!(cpu.to_srgba8(), cpu_sorted.to_srgba8());
+        // The unclipped rect shows in the clipped-off corner.
+        assert_eq!(cpu_sorted.pixel(0, 0).b, 1.0);
+    }
+
+    #[test]
+    fn side_draws_follow_their_clip() {
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let mut p = painter(16, 16);
+        p.push_clip_rounded_rect(RoundedRect {
+            rect: Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 16.0,
+                h: 16.0,
+            },
+            radii: RoundedRadii::uniform(8.0),
+        });
+        let mut clips = ClipTable::default();
+        let clip = clips.intern(p.clips());
+        p.pop_clip();
+        let glyph = RasterizedGlyph {
+            offset: [0.0, 0.0],
+            color: None,
+            mask: crate::text::SubpixelMask {
+                width: 16,
+                height: 16,
+                format: MaskFormat::Rgba8,
+                data: vec![255; 16 * 16 * 4],
+            },
+            key: 0,
+        };
+        let mut cpu = CpuRenderer::new(16, 16);
+        cpu.set_clips(clips);
+        cpu.render_unified(&p.finish(), &[([0.0, 0.0], glyph, red, 0, clip)], &[], &[]);
+        assert!(cpu.pixel(8, 8).r > 0.99);
+        assert_eq!(cpu.pixel(0, 0).a, 0.0);
+    }
+
+    #[test]
+    fn filter_layers_blur_and_recolor_their_content() {
+        let left = Rect {
+            x: 0.0,
+            y: 2.0,
+            w: 8.0,
+            h: 12.0,
+        };
+        let red = Brush::Solid(ColorLinPremul::rgba(255, 0, 0, 255));
+        let mut p = painter(16, 16);
+        p.push_filter_layer(vec![Filter::blur(2.0), Filter::grayscale(1.0)]);
+        p.rect(left, red, 0);
+        p.pop_layer();
+        let mut cpu = CpuRenderer::new(16, 16);
+        cpu.render(&p.finish());
+        let inside = cpu.pixel(4, 8);
+        assert!(inside.a > 0.9);
+        assert!((inside.r - inside.g).abs() < 1e-3 && (inside.g - inside.b).abs() < 1e-3);
+        // The edge spreads about 1.5 sigma past the rect, and no further
+        // than the kernel.
+        let spread = cpu.pixel(9, 8).a;
+        assert!(spread > 0.05 && spread < 0.5, "spread {spread}");
+        assert_eq!(cpu.pixel(15, 8).a, 0.0);
+    }
+
+    #[test]
+    fn drop_shadows_follow_alpha_under_the_content() {
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let black = ColorLinPremul::rgba(0, 0, 0, 255);
+        let mut p = painter(16, 16);
+        p.push_filter_layer(vec![Filter::drop_shadow([6.0, 6.0], 0.0, black)]);
+        p.rect(
+            Rect {
+                x: 2.0,
+                y: 2.0,
+                w: 8.0,
+                h: 8.0,
+            },
+            Brush::Solid(blue),
+            0,
+        );
+        p.pop_layer();
+        let mut cpu = CpuRenderer::new(16, 16);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(6, 6), blue);
+        assert_eq!(cpu.pixel(12, 12), black);
+        assert_eq!(cpu.pixel(3, 12).a, 0.0);
+    }
+
+    #[test]
+    fn backdrop_filters_only_change_the_region_behind_the_layer() {
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let half = |x: f32| Rect {
+            x,
+            y: 0.0,
+            w: 8.0,
+            h: 8.0,
+        };
+        let mut p = painter(16, 8);
+        p.rect(half(0.0), Brush::Solid(red), 0);
+        p.rect(half(8.0), Brush::Solid(blue), 0);
+        let glass = RoundedRect {
+            rect: Rect {
+                x: 4.0,
+                y: 0.0,
+                w: 8.0,
+                h: 8.0,
+            },
+            radii: RoundedRadii::default(),
+        };
+        p.push_backdrop_layer(glass, vec![Filter::blur(2.0)], 1);
+        p.pop_layer();
+        let mut cpu = CpuRenderer::new(16, 8);
+        cpu.render(&p.finish());
+        let mixed = cpu.pixel(8, 4);
+        assert!(mixed.r > 0.2 && mixed.b > 0.2, "{mixed:?}");
+        // Edges are clamped, so the blur doesn't fade the backdrop out.
+        assert!((mixed.a - 1.0).abs() < 1e-3);
+        assert_eq!(cpu.pixel(3, 4), red);
+        assert_eq!(cpu.pixel(12, 4), blue);
+    }
+
+    #[test]
+    fn borders_paint_each_side_inside_elliptical_corners() {
+        let colors = [
+            ColorLinPremul::rgba(255, 0, 0, 255),
+            ColorLinPremul::rgba(0, 255, 0, 255),
+            ColorLinPremul::rgba(0, 0, 255, 255),
+            ColorLinPremul::rgba(255, 255, 0, 255),
+        ];
+        let rrect = RoundedRect {
+            rect: Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 40.0,
+                h: 20.0,
+            },
+            radii: RoundedRadii::default(),
+        };
+        let mut p = painter(40, 20);
+        p.border(
+            rrect,
+            Border {
+                widths: [2.0, 4.0, 2.0, 4.0],
+                colors,
+            },
+            0,
+        );
+        let mut cpu = CpuRenderer::new(40, 20);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(20, 0), colors[0]);
+        assert_eq!(cpu.pixel(38, 10), colors[1]);
+        assert_eq!(cpu.pixel(20, 19), colors[2]);
+        assert_eq!(cpu.pixel(1, 10), colors[3]);
+        assert_eq!(cpu.pixel(20, 10), ColorLinPremul::default());
+
+        // Radii of half the width and height make an ellipse, not a pill.
+        let mut p = painter(40, 20);
+        let oval = RoundedRect {
+            radii: RoundedRadii::elliptical(20.0, 10.0),
+            ..rrect
+        };
+        p.rounded_rect(oval, Brush::Solid(colors[0]), 0);
+        let mut cpu = CpuRenderer::new(40, 20);
+        cpu.render(&p.finish());
+        assert_eq!(cpu.pixel(10, 2), colors[0]);
+        // Inside a pill's circular corner but outside the ellipse.
+        assert_eq!(cpu.pixel(6, 1).a, 0.0);
+    }
+}
@@ -0,0 +1,546 @@
+//! Frame-to-frame damage tracking for partial redraws.
+//!
+//! Each drawable gets a stable id derived from its content and the clip and
+//! layer state it is drawn in, so an unchanged element keeps its id across
+//! frames however the display list around it was rebuilt. [`RetainedScene`]
+//! keeps the ids and bounds of the last frame and diffs the next one against
+//! them; the result is the set of rectangles that need repainting.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::display_list::{Command, DisplayList, Viewport};
+use crate::hit_test::{
+    inflate_rect, intersect_rect, rects_intersect, transform_bounds, union_rect,
+};
+use crate::scene::*;
+
+/// Bounds standing in for "anywhere", for content whose extent is unknown
+/// (e.g. a color matrix that makes transparent pixels opaque).
+const UNBOUNDED: Rect = Rect {
+    x: -1.0e7,
+    y: -1.0e7,
+    w: 2.0e7,
+    h: 2.0e7,
+};
+
+/// Padding for antialiased edges, which bleed past the geometry.
+const AA_PAD: f32 = 1.0;
+
+/// A drawable of one frame: its stable id and the world-space region it
+/// may paint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DamageItem {
+    pub id: u64,
+    pub bounds: Rect,
+    /// Region read from below, for backdrop filters: damage touching it also
+    /// damages `bounds`.
+    pub samples: Option<Rect>,
+}
+
+impl DamageItem {
+    pub fn new(id: u64, bounds: Rect) -> Self {
+        Self {
+            id,
+            bounds,
+            samples: None,
+        }
+    }
+}
+
+/// What changed between two frames.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Damage {
+    /// Nothing changed; the previous frame can be presented as is.
+    None,
+    /// Only these world-space rectangles need repainting.
+    Rects(Vec<Rect>),
+    /// The whole frame needs repainting.
+    Full,
+}
+
+impl Damage {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Damage::None)
+    }
+
+    pub fn is_full(&self) -> bool {
+        matches!(self, Damage::Full)
+    }
+
+    /// Bounding box of the damage within `viewport`: all of it for full
+    /// damage, `None` if nothing visible changed.
+    pub fn bounds(&self, viewport: Rect) -> Option<Rect> {
+        match self {
+            Damage::None => None,
+            Damage::Full => Some(viewport),
+            Damage::Rects(rects) => {
+                let union = rects.iter().copied().reduce(union_rect)?;
+                intersect_rect(union, viewport).filter(|r| r.w > 0.0 && r.h > 0.0)
+            }
+        }
+    }
+}
+
+/// The drawables of the last frame, for diffing against the next one.
+///
+/// Items are matched by id: new and removed ids damage their bounds, and an
+/// item that moved in the paint order damages its bounds too, as whatever it
+/// overlaps may now be above or below it. The first frame, and any frame
+/// after a viewport change or [`RetainedScene::invalidate`], is full damage.
+#[derive(Clone, Debug, Default)]
+pub struct RetainedScene {
+    viewport: Option<(u32, u32)>,
+    /// Paint order position and bounds by id.
+    items: HashMap<u64, (usize, Rect)>,
+}
+
+impl RetainedScene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the last frame so the next update reports full damage, e.g.
+    /// after something else drew over the retained target.
+    pub fn invalidate(&mut self) {
+        self.viewport = None;
+        self.items.clear();
+    }
+
+    /// Diff the display list's drawables against the last frame and retain
+    /// them for the next; see [`DisplayList::damage_items`].
+    pub fn update_list(&mut self, list: &DisplayList) -> Damage {
+        self.update(list.viewport, list.damage_items())
+    }
+
+    /// Diff `items`, in paint order, against the last frame and retain them
+    /// for the next. Repeated ids are told apart by their order.
+    pub fn update(
+        &mut self,
+        viewport: Viewport,
+        items: impl IntoIterator<Item = DamageItem>,
+    ) -> Damage {
+        let full = self.viewport != Some((viewport.width, viewport.height));
+        self.viewport = Some((viewport.width, viewport.height));
+        let mut previous = std::mem::take(&mut self.items);
+        let mut rects = Vec::new();
+        let mut samplers = Vec::new();
+        // Highest previous position among the retained items seen so far: an
+        // item behind it in the old order has been moved above it.
+        let mut max_previous = None;
+        for (index, item) in items.into_iter().enumerate() {
+            let mut id = item.id;
+            while self.items.contains_key(&id) {
+                id = mix(id, 1);
+            }
+            self.items.insert(id, (index, item.bounds));
+            match previous.remove(&id) {
+                Some((old, _)) => {
+                    if max_previous.is_some_and(|m| old < m) {
+                        rects.push(item.bounds);
+                    }
+                    max_previous = max_previous.max(Some(old));
+                }
+                None => rects.push(item.bounds),
+            }
+            if let Some(samples) = item.samples {
+                samplers.push((samples, item.bounds));
+            }
+        }
+        rects.extend(previous.into_values().map(|(_, bounds)| bounds));
+
+        // Backdrop filters repaint when anything they read from changed,
+        // which may in turn damage what other backdrops read.
+        let mut changed = !rects.is_empty();
+        while changed {
+            changed = false;
+            samplers.retain(|(samples, bounds)| {
+                if rects.iter().any(|r| rects_intersect(r, samples)) {
+                    rects.push(*bounds);
+                    changed = true;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        if full {
+            Damage::Full
+        } else if rects.is_empty() {
+            Damage::None
+        } else {
+            Damage::Rects(rects)
+        }
+    }
+}
+
+impl DisplayList {
+    /// Stable ids and world-space bounds of the list's drawables, in list
+    /// order; hit regions and fully clipped commands are left out.
+    ///
+    /// Bounds are conservative: strokes, shadows, antialiasing and layer
+    /// filters are allowed for, and text is assumed no wider than 1em per
+    /// character.
+    pub fn damage_items(&self) -> Vec<DamageItem> {
+        let mut items = Vec::new();
+        let mut transforms = vec![Transform2D::identity()];
+        // (state hash, world bounds) of the clip and layer stacks.
+        let mut clips: Vec<(u64, Rect)> = vec![(0, UNBOUNDED)];
+        let mut layers: Vec<(u64, f32)> = vec![(0, 0.0)];
+        for cmd in &self.commands {
+            let (clip_state, clip) = *clips.last().unwrap();
+            let (layer_state, outset) = *layers.last().unwrap();
+            let state = mix(clip_state, layer_state);
+            match cmd {
+                Command::PushTransform(t) => transforms.push(*t),
+                Command::PopTransform => {
+                    if transforms.len() > 1 {
+                        transforms.pop();
+                    }
+                }
+                Command::PushClip(shape) => {
+                    let t = *transforms.last().unwrap();
+                    let local = match shape {
+                        ClipShape::Rect(r) => *r,
+                        ClipShape::RoundedRect(rr) => rr.rect,
+                        ClipShape::Path(p) => p.bounds().unwrap_or_default(),
+                    };
+                    let bounds =
+                        intersect_rect(clip, transform_bounds(&t, local)).unwrap_or_default();
+                    clips.push((content_hash(clip_state, &(shape, t)), bounds));
+                }
+                Command::PopClip => {
+                    if clips.len() > 1 {
+                        clips.pop();
+                    }
+                }
+                Command::PushLayer {
+                    filters, backdrop, ..
+                } => {
+                    let layer = content_hash(layer_state, cmd);
+                    layers.push((layer, outset + filter_outset(filters)));
+                    if let Some(b) = backdrop {
+                        let world = transform_bounds(&b.transform, b.rrect.rect);
+                        if let Some(bounds) = intersect_rect(inflate_rect(world, AA_PAD), clip) {
+                            items.push(DamageItem {
+                                id: content_hash(state, b),
+                                bounds,
+                                samples: Some(outset_rect(bounds, filter_outset(&b.filters))),
+                            });
+                        }
+                    }
+                }
+                Command::PopLayer => {
+                    if layers.len() > 1 {
+                        layers.pop();
+                    }
+                }
+                _ => {
+                    let Some((local, transform)) = local_bounds(cmd) else {
+                        continue;
+                    };
+                    let world = inflate_rect(transform_bounds(&transform, local), AA_PAD);
+                    let Some(bounds) = intersect_rect(world, clip) else {
+                        continue;
+                    };
+                    items.push(DamageItem::new(
+                        content_hash(state, cmd),
+                        outset_rect(bounds, outset),
+                    ));
+                }
+            }
+        }
+        items
+    }
+}
+
+/// Local-space bounds of a drawable command and the transform placing it.
+fn local_bounds(cmd: &Command) -> Option<(Rect, Transform2D)> {
+    let bounds = match cmd {
+        Command::DrawRect {
+            rect, transform, ..
+        } => (*rect, *transform),
+        Command::DrawRoundedRect {
+            rrect, transform, ..
+        }
+        | Command::DrawBorder {
+            rrect, transform, ..
+        } => (rrect.rect, *transform),
+        Command::StrokeRect {
+            rect,
+            stroke,
+            transform,
+            ..
+        } => (inflate_rect(*rect, stroke_outset(stroke)), *transform),
+        Command::StrokeRoundedRect {
+            rrect,
+            stroke,
+            transform,
+            ..
+        } => (inflate_rect(rrect.rect, stroke_outset(stroke)), *transform),
+        Command::DrawText { run, transform, .. } => {
+            let size = run
+                .spans
+                .iter()
+                .filter_map(|s| s.attrs.size)
+                .fold(run.size, f32::max);
+            (text_bounds(&run.text, run.pos, size), *transform)
+        }
+        Command::DrawHyperlink {
+            hyperlink,
+            transform,
+            ..
+        } => (
+            text_bounds(&hyperlink.text, hyperlink.pos, hyperlink.size),
+            *transform,
+        ),
+        Command::DrawEllipse {
+            center,
+            radii,
+            transform,
+            ..
+        } => (
+            Rect {
+                x: center[0] - radii[0].abs(),
+                y: center[1] - radii[1].abs(),
+                w: radii[0].abs() * 2.0,
+                h: radii[1].abs() * 2.0,
+            },
+            *transform,
+        ),
+        Command::FillPath {
+            path, transform, ..
+        } => (path.bounds()?, *transform),
+        Command::StrokePath {
+            path,
+            stroke,
+            transform,
+            ..
+        } => (
+            inflate_rect(path.bounds()?, stroke_outset(stroke)),
+            *transform,
+        ),
+        Command::BoxShadow {
+            rrect,
+            spec,
+            transform,
+            ..
+        } => {
+            let shadow = Rect {
+                x: rrect.rect.x + spec.offset[0],
+                y: rrect.rect.y + spec.offset[1],
+                ..rrect.rect
+            };
+            let spread = spec.spread.max(0.0) + spec.blur_radius.max(0.0) * 3.0;
+            (inflate_rect(shadow, spread), *transform)
+        }
+        Command::DrawSvg {
+            origin,
+            max_size,
+            transform,
+            ..
+        } => (
+            Rect {
+                x: origin[0],
+                y: origin[1],
+                w: max_size[0],
+                h: max_size[1],
+            },
+            *transform,
+        ),
+        Command::DrawImage {
+            origin,
+            size,
+            transform,
+            ..
+        } => (
+            Rect {
+                x: origin[0],
+                y: origin[1],
+                w: size[0],
+                h: size[1],
+            },
+            *transform,
+        ),
+        _ => return None,
+    };
+    Some(bounds)
+}
+
+/// Generous box for a text run with its baseline at `pos`.
+fn text_bounds(text: &str, pos: [f32; 2], size: f32) -> Rect {
+    let chars = text.chars().count() as f32;
+    Rect {
+        x: pos[0] - size * 0.5,
+        y: pos[1] - size * 1.25,
+        w: (chars + 1.0) * size,
+        h: size * 1.75,
+    }
+}
+
+/// How far a stroke paints outside its path: half its width, times the
+/// longest miter (or the diagonal of a square cap).
+fn stroke_outset(stroke: &Stroke) -> f32 {
+    stroke.width.abs() * 0.5 * stroke.miter_limit.max(std::f32::consts::SQRT_2)
+}
+
+/// How far `filters` spread content, or infinity if they can paint
+/// anywhere.
+fn filter_outset(filters: &[Filter]) -> f32 {
+    filters
+        .iter()
+        .map(|f| match f {
+            Filter::Blur { radius } => radius * 3.0,
+            Filter::DropShadow { offset, blur, .. } => {
+                offset[0].abs().max(offset[1].abs()) + blur * 1.5
+            }
+            // A positive alpha offset turns transparent pixels opaque.
+            Filter::ColorMatrix(m) if m[19] > 0.0 => f32::INFINITY,
+            Filter::ColorMatrix(_) => 0.0,
+        })
+        .sum()
+}
+
+fn outset_rect(r: Rect, d: f32) -> Rect {
+    if d.is_finite() {
+        inflate_rect(r, d)
+    } else {
+        UNBOUNDED
+    }
+}
+
+/// Hash `value` into `state`. Float fields of display-list content hash by
+/// their bits.
+pub fn content_hash(state: u64, value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u64(state);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn mix(a: u64, b: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u64(a);
+    hasher.write_u64(b);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Painter;
+
+    const VIEWPORT: Viewport = Viewport {
+        width: 200,
+        height: 100,
+    };
+
+    fn frame(draw: impl FnOnce(&mut Painter)) -> DisplayList {
+        let mut painter = Painter::begin_frame(VIEWPORT);
+        draw(&mut painter);
+        painter.finish()
+    }
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    fn red() -> Brush {
+        Brush::Solid(ColorLinPremul::from_srgba_u8([255, 0, 0, 255]))
+    }
+
+    fn blue() -> Brush {
+        Brush::Solid(ColorLinPremul::from_srgba_u8([0, 0, 255, 255]))
+    }
+
+    #[test]
+    fn unchanged_frames_have_no_damage() {
+        let draw = |p: &mut Painter| {
+            p.rect(rect(10.0, 10.0, 20.0, 20.0), red(), 0);
+            p.rect(rect(50.0, 10.0, 20.0, 20.0), red(), 0);
+        };
+        let mut scene = RetainedScene::new();
+        assert!(scene.update_list(&frame(draw)).is_full());
+        assert!(scene.update_list(&frame(draw)).is_none());
+    }
+
+    #[test]
+    fn moved_commands_damage_old_and_new_bounds() {
+        let mut scene = RetainedScene::new();
+        scene.update_list(&frame(|p| {
+            p.rect(rect(10.0, 10.0, 20.0, 20.0), red(), 0);
+            p.rect(rect(150.0, 10.0, 20.0, 20.0), blue(), 0);
+        }));
+        let damage = scene.update_list(&frame(|p| {
+            p.rect(rect(40.0, 10.0, 20.0, 20.0), red(), 0);
+            p.rect(rect(150.0, 10.0, 20.0, 20.0), blue(), 0);
+        }));
+        let viewport = rect(0.0, 0.0, 200.0, 100.0);
+        let bounds = damage.bounds(viewport).unwrap();
+        assert!(bounds.x <= 10.0 && bounds.x + bounds.w >= 60.0);
+        assert!(bounds.x + bounds.w < 150.0, "untouched rect is not damaged");
+    }
+
+    #[test]
+    fn clip_and_paint_order_changes_are_damage() {
+        let mut scene = RetainedScene::new();
+        let a = rect(10.0, 10.0, 20.0, 20.0);
+        let b = rect(20.0, 20.0, 20.0, 20.0);
+        scene.update_list(&frame(|p| {
+            p.rect(a, red(), 0);
+            p.rect(b, blue(), 0);
+        }));
+        let swapped = scene.update_list(&frame(|p| {
+            p.rect(b, blue(), 0);
+            p.rect(a, red(), 0);
+        }));
+        assert!(matches!(swapped, Damage::Rects(ref r) if r.len() == 1));
+
+        let clipped = scene.update_list(&frame(|p| {
+            p.rect(b, blue(), 0);
+            p.push_clip_rect(rect(0.0, 0.0, 15.0, 15.0));
+            p.rect(a, red(), 0);
+            p.pop_clip();
+        }));
+        let bounds = clipped.bounds(rect(0.0, 0.0, 200.0, 100.0)).unwrap();
+        assert!(bounds.x <= 10.0 && bounds.x + bounds.w >= 30.0);
+    }
+
+    #[test]
+    fn resizes_and_invalidation_are_full_damage() {
+        let list = frame(|p| p.rect(rect(10.0, 10.0, 20.0, 20.0), red(), 0));
+        let mut scene = RetainedScene::new();
+        scene.update_list(&list);
+        scene.invalidate();
+        assert!(scene.update_list(&list).is_full());
+        let resized = DisplayList {
+            viewport: Viewport {
+                width: 300,
+                height: 100,
+            },
+            ..list
+        };
+        assert!(scene.update_list(&resized).is_full());
+    }
+
+    #[test]
+    fn backdrop_filters_repaint_when_what_they_sample_changes() {
+        let items = |x: f32| {
+            [
+                DamageItem::new(x as u64, rect(x, 0.0, 10.0, 10.0)),
+                DamageItem {
+                    id: 1000,
+                    bounds: rect(100.0, 0.0, 50.0, 50.0),
+                    samples: Some(rect(80.0, 0.0, 90.0, 70.0)),
+                },
+            ]
+        };
+        let mut scene = RetainedScene::new();
+        scene.update(VIEWPORT, items(0.0));
+        let far = scene.update(VIEWPORT, items(20.0));
+        assert!(matches!(far, Damage::Rects(ref r) if r.len() == 2));
+        let near = scene.update(VIEWPORT, items(85.0));
+        assert!(matches!(near, Damage::Rects(ref r) if r.len() == 3));
+    }
+}
@@ -1,13 +1,16 @@
 use crate::scene::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub struct Viewport {
     pub width: u32,
     pub height: u32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Command {
     DrawRect {
         rect: Rect,
@@ -35,6 +38,14 @@ pub enum Command {
         z: i32,
         transform: Transform2D,
     },
+    /// Border with per-side widths and colors, inside the outer edge
+    /// `rrect`; see [`Border`].
+    DrawBorder {
+        rrect: RoundedRect,
+        border: Border,
+        z: i32,
+        transform: Transform2D,
+    },
     DrawText {
         run: TextRun,
         z: i32,
@@ -49,18 +60,20 @@ pub enum Command {
         z: i32,
         transform: Transform2D,
     },
-    /// Filled path (solid color only for now)
+    /// Filled path. Gradient and pattern coordinates are in the path's local
+    /// space, before `transform`.
     FillPath {
         path: Path,
-        color: ColorLinPremul,
+        brush: Brush,
         z: i32,
         transform: Transform2D,
     },
-    /// Stroked path (width only; round join/cap for now)
+    /// Stroked path, dashed and capped/joined as `stroke` says, painted like
+    /// `FillPath`.
     StrokePath {
         path: Path,
         stroke: Stroke,
-        color: ColorLinPremul,
+        brush: Brush,
         z: i32,
         transform: Transform2D,
     },
@@ -118,10 +131,28 @@ pub enum Command {
         /// Unique ID for this hyperlink instance (for hit testing)
         id: u64,
     },
-    PushClip(ClipRect),
+    /// Intersect the clip with a shape in the local space of the innermost
+    /// `PushTransform` (identity outside any), until the matching `PopClip`.
+    PushClip(ClipShape),
     PopClip,
     PushTransform(Transform2D),
     PopTransform,
+    /// Start an isolated group: commands up to the matching `PopLayer` render
+    /// into an offscreen layer that is then composited onto what is below it
+    /// with `opacity`, `blend_mode` and an optional alpha `mask`. The layer
+    /// sits in the z-order as one unit, at the lowest z-index of its content
+    /// (or of its backdrop filter).
+    ///
+    /// `filters` apply to the layer's content in order before it is
+    /// composited; `backdrop` filters what is behind the layer first.
+    PushLayer {
+        opacity: f32,
+        blend_mode: BlendMode,
+        mask: Option<LayerMask>,
+        filters: Vec<Filter>,
+        backdrop: Option<BackdropFilter>,
+    },
+    PopLayer,
 }
 
 impl Command {
@@ -132,6 +163,7 @@ impl Command {
             Command::DrawRoundedRect { z, .. } => Some(*z),
             Command::StrokeRect { z, .. } => Some(*z),
             Command::StrokeRoundedRect { z, .. } => Some(*z),
+            Command::DrawBorder { z, .. } => Some(*z),
             Command::DrawText { z, .. } => Some(*z),
             Command::DrawEllipse { z, .. } => Some(*z),
             Command::FillPath { z, .. } => Some(*z),
@@ -148,30 +180,391 @@ impl Command {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+impl Hash for Command {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Command::DrawRect {
+                rect,
+                brush,
+                z,
+                transform,
+            } => (rect, brush, z, transform).hash(state),
+            Command::DrawRoundedRect {
+                rrect,
+                brush,
+                z,
+                transform,
+            } => (rrect, brush, z, transform).hash(state),
+            Command::StrokeRect {
+                rect,
+                stroke,
+                brush,
+                z,
+                transform,
+            } => (rect, stroke, brush, z, transform).hash(state),
+            Command::StrokeRoundedRect {
+                rrect,
+                stroke,
+                brush,
+                z,
+                transform,
+            } => (rrect, stroke, brush, z, transform).hash(state),
+            Command::DrawBorder {
+                rrect,
+                border,
+                z,
+                transform,
+            } => (rrect, border, z, transform).hash(state),
+            Command::DrawText {
+                run,
+                z,
+                transform,
+                id,
+                dynamic,
+            } => (run, z, transform, id, dynamic).hash(state),
+            Command::DrawEllipse {
+                center,
+                radii,
+                brush,
+                z,
+                transform,
+            } => {
+                hash_f32s(center, state);
+                hash_f32s(radii, state);
+                (brush, z, transform).hash(state);
+            }
+            Command::FillPath {
+                path,
+                brush,
+                z,
+                transform,
+            } => (path, brush, z, transform).hash(state),
+            Command::StrokePath {
+                path,
+                stroke,
+                brush,
+                z,
+                transform,
+            } => (path, stroke, brush, z, transform).hash(state),
+            Command::BoxShadow {
+                rrect,
+                spec,
+                z,
+                transform,
+            } => (rrect, spec, z, transform).hash(state),
+            Command::HitRegionRect {
+                id,
+                rect,
+                z,
+                transform,
+            } => (id, rect, z, transform).hash(state),
+            Command::HitRegionRoundedRect {
+                id,
+                rrect,
+                z,
+                transform,
+            } => (id, rrect, z, transform).hash(state),
+            Command::HitRegionEllipse {
+                id,
+                center,
+                radii,
+                z,
+                transform,
+            } => {
+                id.hash(state);
+                hash_f32s(center, state);
+                hash_f32s(radii, state);
+                (z, transform).hash(state);
+            }
+            Command::DrawSvg {
+                path,
+                origin,
+                max_size: size,
+                z,
+                transform,
+            }
+            | Command::DrawImage {
+                path,
+                origin,
+                size,
+                z,
+                transform,
+            } => {
+                path.hash(state);
+                hash_f32s(origin, state);
+                hash_f32s(size, state);
+                (z, transform).hash(state);
+            }
+            Command::DrawHyperlink {
+                hyperlink,
+                z,
+                transform,
+                id,
+            } => (hyperlink, z, transform, id).hash(state),
+            Command::PushClip(shape) => shape.hash(state),
+            Command::PushTransform(transform) => transform.hash(state),
+            Command::PushLayer {
+                opacity,
+                blend_mode,
+                mask,
+                filters,
+                backdrop,
+            } => {
+                opacity.to_bits().hash(state);
+                (blend_mode, mask, filters, backdrop).hash(state);
+            }
+            Command::PopClip | Command::PopTransform | Command::PopLayer => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct DisplayList {
     pub viewport: Viewport,
     pub commands: Vec<Command>,
 }
 
 impl DisplayList {
-    /// Sort commands by z-index while preserving clip/transform stack structure.
-    /// This is a simplified implementation that sorts drawable commands but keeps
-    /// transform/clip commands in their original order. For proper z-ordering with
-    /// transforms and clips, each drawable should store its full transform/clip state.
+    /// Sort commands by z-index. Drawables carry their own transforms; clips
+    /// are pushed again around the commands they applied to, each under the
+    /// transform it was pushed with, so every draw stays inside its clips.
+    ///
+    /// Layers are sorted as one unit at the lowest z-index of their content, and
+    /// their content is sorted within the layer. A layer's clips are also
+    /// pushed inside it, around its content.
     pub fn sort_by_z(&mut self) {
-        // Sort by z-index. Rust's sort_by is stable, preserving relative order of equal elements.
-        // This means transform/clip commands (which have no z-index) will stay in order,
-        // but drawable commands will be sorted by z-index.
-        self.commands.sort_by(|a, b| {
-            match (a.z_index(), b.z_index()) {
-                (Some(z_a), Some(z_b)) => z_a.cmp(&z_b),
-                (Some(_), None) => std::cmp::Ordering::Greater, // Drawables after non-drawables
-                (None, Some(_)) => std::cmp::Ordering::Less,    // Non-drawables before drawables
-                (None, None) => std::cmp::Ordering::Equal,      // Preserve order for non-drawables
+        let _ = self.sort_by_z_tracking_layers();
+    }
+
+    /// [`DisplayList::sort_by_z`], returning for each layer of the sorted list
+    /// (in `PushLayer` order) its position among the layers before sorting.
+    /// Lets data kept next to the list per layer follow the sort.
+    pub fn sort_by_z_tracking_layers(&mut self) -> Vec<usize> {
+        let commands = std::mem::take(&mut self.commands);
+        let mut next_layer = 0;
+        let (commands, layers) = sort_units(commands, &mut next_layer, Transform2D::identity());
+        self.commands = commands;
+        layers
+    }
+
+    /// Split the top-level `PushLayer`..`PopLayer` blocks off the list.
+    /// Returns the remaining commands and the layers in submission order; an
+    /// unbalanced `PushLayer` runs to the end of the list.
+    pub fn split_layers(&self) -> (DisplayList, Vec<LayerBlock>) {
+        let mut rest = Vec::new();
+        let mut layers = Vec::new();
+        let mut next_index = 0;
+        let mut iter = self.commands.iter();
+        while let Some(cmd) = iter.next() {
+            let Command::PushLayer {
+                opacity,
+                blend_mode,
+                mask,
+                filters,
+                backdrop,
+            } = cmd
+            else {
+                if !matches!(cmd, Command::PopLayer) {
+                    rest.push(cmd.clone());
+                }
+                continue;
+            };
+            let (body, _) = layer_body(&mut iter);
+            let index = next_index;
+            next_index += 1 + body.iter().filter(|c| is_push_layer(c)).count();
+            layers.push(LayerBlock {
+                opacity: *opacity,
+                blend_mode: *blend_mode,
+                mask: mask.clone(),
+                filters: filters.clone(),
+                backdrop: backdrop.clone(),
+                index,
+                list: DisplayList {
+                    viewport: self.viewport,
+                    commands: body.into_iter().cloned().collect(),
+                },
+            });
+        }
+        (
+            DisplayList {
+                viewport: self.viewport,
+                commands: rest,
+            },
+            layers,
+        )
+    }
+}
+
+/// Layer split off a display list by [`DisplayList::split_layers`].
+#[derive(Clone, Debug)]
+pub struct LayerBlock {
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    pub mask: Option<LayerMask>,
+    pub filters: Vec<Filter>,
+    pub backdrop: Option<BackdropFilter>,
+    /// Position of the layer among all layers of the list it was split from,
+    /// in `PushLayer` order. Layers nested in `list` follow it directly.
+    pub index: usize,
+    /// Layer content, which may contain nested layers.
+    pub list: DisplayList,
+}
+
+impl LayerBlock {
+    /// Z-index the layer is composited at: the lowest of its content and
+    /// backdrop filter. `None` if there is nothing to composite.
+    pub fn z(&self) -> Option<i32> {
+        let content = self.list.commands.iter().filter_map(Command::z_index);
+        content.chain(self.backdrop.as_ref().map(|b| b.z)).min()
+    }
+}
+
+fn is_push_layer(cmd: &Command) -> bool {
+    matches!(cmd, Command::PushLayer { .. })
+}
+
+/// Commands after a `PushLayer` up to its matching `PopLayer`, which is
+/// consumed. The flag tells whether the `PopLayer` was found.
+fn layer_body<C: Borrow<Command>>(iter: &mut impl Iterator<Item = C>) -> (Vec<C>, bool) {
+    let mut depth = 1;
+    let mut body = Vec::new();
+    for cmd in iter.by_ref() {
+        match cmd.borrow() {
+            Command::PushLayer { .. } => depth += 1,
+            Command::PopLayer => {
+                depth -= 1;
+                if depth == 0 {
+                    return (body, true);
+                }
+            }
+            _ => {}
+        }
+        body.push(cmd);
+    }
+    (body, false)
+}
+
+/// A command, or a whole layer block, moved as one by `sort_by_z`.
+struct SortUnit {
+    z: Option<i32>,
+    /// Clips the commands were made in, by index into the sort's table.
+    clip: u32,
+    commands: Vec<Command>,
+    /// Pre-sort indices of the layers in `commands`, in `PushLayer` order.
+    layers: Vec<usize>,
+}
+
+/// Sort `commands`, made under the world `transform`, as `sort_by_z` does.
+fn sort_units(
+    commands: Vec<Command>,
+    next_layer: &mut usize,
+    transform: Transform2D,
+) -> (Vec<Command>, Vec<usize>) {
+    let mut units = Vec::new();
+    let mut transforms = vec![transform];
+    let mut clips: Vec<Clip> = Vec::new();
+    let mut table = ClipTable::default();
+    let mut clip = 0;
+    let mut iter = commands.into_iter();
+    while let Some(cmd) = iter.next() {
+        match &cmd {
+            Command::PushClip(shape) => {
+                clips.push(Clip {
+                    shape: shape.clone(),
+                    transform: *transforms.last().unwrap(),
+                });
+                clip = table.intern(&clips);
+                continue;
+            }
+            Command::PopClip => {
+                clips.pop();
+                clip = table.intern(&clips);
+                continue;
             }
+            Command::PushTransform(t) => transforms.push(*t),
+            Command::PopTransform if transforms.len() > 1 => {
+                transforms.pop();
+            }
+            _ => {}
+        }
+        if !is_push_layer(&cmd) {
+            units.push(SortUnit {
+                z: cmd.z_index(),
+                clip,
+                commands: vec![cmd],
+                layers: Vec::new(),
+            });
+            continue;
+        }
+        let index = *next_layer;
+        *next_layer += 1;
+        let (body, closed) = layer_body(&mut iter);
+        let (body, nested) = sort_units(body, next_layer, *transforms.last().unwrap());
+        let backdrop_z = match &cmd {
+            Command::PushLayer {
+                backdrop: Some(backdrop),
+                ..
+            } => Some(backdrop.z),
+            _ => None,
+        };
+        let z = body
+            .iter()
+            .filter_map(Command::z_index)
+            .chain(backdrop_z)
+            .min();
+        let mut block = Vec::with_capacity(body.len() + 2);
+        block.push(cmd);
+        push_clips(&mut block, &clips);
+        block.extend(body);
+        block.extend(clips.iter().map(|_| Command::PopClip));
+        if closed {
+            block.push(Command::PopLayer);
+        }
+        let mut layers = vec![index];
+        layers.extend(nested);
+        units.push(SortUnit {
+            z,
+            clip,
+            commands: block,
+            layers,
         });
     }
+    // Sort by z-index. Rust's sort_by is stable, preserving relative order of equal elements.
+    // This means transform commands (which have no z-index) will stay in order,
+    // but drawable commands will be sorted by z-index.
+    units.sort_by(|a, b| {
+        match (a.z, b.z) {
+            (Some(z_a), Some(z_b)) => z_a.cmp(&z_b),
+            (Some(_), None) => std::cmp::Ordering::Greater, // Drawables after non-drawables
+            (None, Some(_)) => std::cmp::Ordering::Less,    // Non-drawables before drawables
+            (None, None) => std::cmp::Ordering::Equal,      // Preserve order for non-drawables
+        }
+    });
+    let mut sorted = Vec::new();
+    let mut layers = Vec::new();
+    let mut clip = 0;
+    for unit in units {
+        // Runs of units made in the same clips share one push of them.
+        if unit.clip != clip {
+            sorted.extend(table.get(clip).iter().map(|_| Command::PopClip));
+            push_clips(&mut sorted, table.get(unit.clip));
+            clip = unit.clip;
+        }
+        sorted.extend(unit.commands);
+        layers.extend(unit.layers);
+    }
+    sorted.extend(table.get(clip).iter().map(|_| Command::PopClip));
+    (sorted, layers)
+}
+
+/// Push `clips`, each under its own transform.
+fn push_clips(commands: &mut Vec<Command>, clips: &[Clip]) {
+    for clip in clips {
+        commands.push(Command::PushTransform(clip.transform));
+        commands.push(Command::PushClip(clip.shape.clone()));
+        commands.push(Command::PopTransform);
+    }
 }
 
 /// Convert z-index to depth value for GPU depth testing.
@@ -1,5 +1,9 @@
+use std::ops::Range;
+use std::sync::Arc;
+
 use crate::display_list::{Command, DisplayList};
 use crate::scene::*;
+use crate::text::{ClusterBounds, TextProvider};
 
 /// Result of a hit test for a single topmost element.
 #[derive(Clone, Debug)]
@@ -20,6 +24,20 @@ pub struct HitResult {
     pub local_pos: Option<[f32; 2]>,
     /// Normalized coordinates within the shape's bounding box when applicable ([0,1] range).
     pub local_uv: Option<[f32; 2]>,
+    /// Glyph under the point for text hits.
+    pub text: Option<TextHit>,
+}
+
+/// Glyph-level detail of a hit on a text run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextHit {
+    /// Byte range of the glyph cluster under the point.
+    pub cluster: Range<usize>,
+    /// Byte offset of the cluster edge nearest the point, for caret
+    /// placement. Honors right-to-left clusters.
+    pub caret_offset: usize,
+    /// Bounds of the cluster in the run's local space.
+    pub glyph_rect: Rect,
 }
 
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
@@ -30,6 +48,7 @@ pub enum HitKind {
     Text,
     StrokeRect,
     StrokeRoundedRect,
+    Border,
     Path,
     BoxShadow,
     HitRegion,
@@ -41,13 +60,36 @@ pub enum HitKind {
 pub enum HitShape {
     Rect(Rect),
     RoundedRect(RoundedRect),
-    Ellipse { center: [f32; 2], radii: [f32; 2] },
-    StrokeRect { rect: Rect, width: f32 },
-    StrokeRoundedRect { rrect: RoundedRect, width: f32 },
-    PathBBox { rect: Rect },
-    Text,
-    BoxShadow { rrect: RoundedRect },
-    Hyperlink { rect: Rect, url: String },
+    Ellipse {
+        center: [f32; 2],
+        radii: [f32; 2],
+    },
+    StrokeRect {
+        rect: Rect,
+        width: f32,
+    },
+    StrokeRoundedRect {
+        rrect: RoundedRect,
+        width: f32,
+    },
+    /// Border ring inside `rrect`, widths in CSS order.
+    Border {
+        rrect: RoundedRect,
+        widths: [f32; 4],
+    },
+    PathBBox {
+        rect: Rect,
+    },
+    Text {
+        rect: Rect,
+    },
+    BoxShadow {
+        rrect: RoundedRect,
+    },
+    Hyperlink {
+        rect: Rect,
+        url: String,
+    },
 }
 
 /// Preprocessed hit-test item built from a display list command.
@@ -66,32 +108,113 @@ struct HitItem {
 enum HitData {
     Rect(Rect),
     RoundedRect(RoundedRect),
-    Ellipse { center: [f32; 2], radii: [f32; 2] },
-    StrokeRect { rect: Rect, width: f32 },
-    StrokeRoundedRect { rrect: RoundedRect, width: f32 },
-    PathBBox(Rect),
-    Text(TextRun),
-    BoxShadow { rrect: RoundedRect },
-    Hyperlink { rect: Rect, url: String },
+    Ellipse {
+        center: [f32; 2],
+        radii: [f32; 2],
+    },
+    StrokeRect {
+        rect: Rect,
+        width: f32,
+    },
+    StrokeRoundedRect {
+        rrect: RoundedRect,
+        width: f32,
+    },
+    Border {
+        rrect: RoundedRect,
+        widths: [f32; 4],
+    },
+    Path {
+        bbox: Rect,
+        contours: Vec<Contour>,
+        fill_rule: FillRule,
+    },
+    StrokePath {
+        bbox: Rect,
+        contours: Vec<Contour>,
+        width: f32,
+    },
+    Text {
+        rect: Rect,
+        clusters: Vec<ClusterBounds>,
+    },
+    BoxShadow {
+        rrect: RoundedRect,
+    },
+    Hyperlink {
+        rect: Rect,
+        url: String,
+    },
 }
 
 #[derive(Clone, Debug)]
 struct ClipEntry {
-    rect: Rect,
+    shape: ClipData,
     transform: Transform2D,
 }
 
-/// Spatial index for hit testing. Currently implemented as a flat list while
-/// preserving z-order and clip stacks. Can be upgraded to a tree later.
+/// Clip region in local space, preprocessed like [`HitData`].
+#[derive(Clone, Debug)]
+enum ClipData {
+    Rect(Rect),
+    RoundedRect(RoundedRect),
+    Path {
+        bbox: Rect,
+        // Shared: every item under the clip keeps a copy of the clip stack.
+        contours: Arc<[Contour]>,
+        fill_rule: FillRule,
+    },
+}
+
+impl ClipData {
+    fn new(shape: &ClipShape) -> Self {
+        match shape {
+            ClipShape::Rect(rect) => ClipData::Rect(*rect),
+            ClipShape::RoundedRect(rrect) => ClipData::RoundedRect(*rrect),
+            ClipShape::Path(path) => ClipData::Path {
+                bbox: path.bounds().unwrap_or_default(),
+                contours: path.flatten().into(),
+                fill_rule: path.fill_rule,
+            },
+        }
+    }
+
+    fn bounds(&self) -> Rect {
+        match self {
+            ClipData::Rect(rect) | ClipData::Path { bbox: rect, .. } => *rect,
+            ClipData::RoundedRect(rrect) => rrect.rect,
+        }
+    }
+}
+
+/// Spatial index for hit testing.
+///
+/// Items keep their draw order, z, transform and clip stack; a uniform grid
+/// over their clipped world-space bounds limits each query to the items near
+/// the query point or rectangle.
 #[derive(Default)]
 pub struct HitIndex {
     items: Vec<HitItem>,
+    grid: HitGrid,
 }
 
 impl HitIndex {
     /// Build a hit-test index from the display list. Records each drawable
     /// element with its transform and the active clip stack at that point.
+    ///
+    /// Text glyph positions are estimated from the font size; use
+    /// [`HitIndex::build_with_text`] for exact glyph hits.
     pub fn build(list: &DisplayList) -> Self {
+        Self::build_inner(list, None)
+    }
+
+    /// Like [`HitIndex::build`], but shapes text runs with `provider` so text
+    /// hits resolve to the glyph cluster under the point.
+    pub fn build_with_text(list: &DisplayList, provider: &dyn TextProvider) -> Self {
+        Self::build_inner(list, Some(provider))
+    }
+
+    fn build_inner(list: &DisplayList, provider: Option<&dyn TextProvider>) -> Self {
         let mut items = Vec::new();
         let mut clips: Vec<ClipEntry> = Vec::new();
         let mut tstack: Vec<Transform2D> = vec![Transform2D::identity()];
@@ -99,9 +222,9 @@ impl HitIndex {
 
         for cmd in &list.commands {
             match cmd {
-                Command::PushClip(ClipRect(rect)) => {
+                Command::PushClip(shape) => {
                     clips.push(ClipEntry {
-                        rect: *rect,
+                        shape: ClipData::new(shape),
                         transform: *tstack.last().unwrap(),
                     });
                 }
@@ -114,6 +237,8 @@ impl HitIndex {
                 Command::PopTransform => {
                     let _ = tstack.pop();
                 }
+                // Layers only change how content is composited, not what is hit.
+                Command::PushLayer { .. } | Command::PopLayer => {}
                 Command::DrawRect {
                     rect, z, transform, ..
                 } => {
@@ -208,6 +333,26 @@ impl HitIndex {
                     });
                     next_id += 1;
                 }
+                Command::DrawBorder {
+                    rrect,
+                    border,
+                    z,
+                    transform,
+                } => {
+                    items.push(HitItem {
+                        id: next_id,
+                        z: *z,
+                        kind: HitKind::Border,
+                        transform: *transform,
+                        data: HitData::Border {
+                            rrect: *rrect,
+                            widths: border.widths,
+                        },
+                        clips: clips.clone(),
+                        region_id: None,
+                    });
+                    next_id += 1;
+                }
                 Command::DrawText {
                     run, z, transform, ..
                 } => {
@@ -216,7 +361,7 @@ impl HitIndex {
                         z: *z,
                         kind: HitKind::Text,
                         transform: *transform,
-                        data: HitData::Text(run.clone()),
+                        data: text_hit_data(run, provider),
                         clips: clips.clone(),
                         region_id: None,
                     });
@@ -255,44 +400,50 @@ impl HitIndex {
                     });
                     next_id += 1;
                 }
-                Command::FillPath { .. } => {
-                    // Coarse bbox hit: compute path bounding box (approximate using control/end points).
-                    if let Command::FillPath {
-                        path, z, transform, ..
-                    } = cmd
-                    {
-                        if let Some(rect) = bbox_for_path(path) {
-                            items.push(HitItem {
-                                id: next_id,
-                                z: *z,
-                                kind: HitKind::Path,
-                                transform: *transform,
-                                data: HitData::PathBBox(rect),
-                                clips: clips.clone(),
-                                region_id: None,
-                            });
-                            next_id += 1;
-                        }
+                Command::FillPath {
+                    path, z, transform, ..
+                } => {
+                    if let Some(bbox) = path.bounds() {
+                        items.push(HitItem {
+                            id: next_id,
+                            z: *z,
+                            kind: HitKind::Path,
+                            transform: *transform,
+                            data: HitData::Path {
+                                bbox,
+                                contours: path.flatten(),
+                                fill_rule: path.fill_rule,
+                            },
+                            clips: clips.clone(),
+                            region_id: None,
+                        });
+                        next_id += 1;
                     }
                 }
                 Command::StrokePath {
-                    path, z, transform, ..
+                    path,
+                    stroke,
+                    z,
+                    transform,
+                    ..
                 } => {
-                    if let Some(mut rect) = bbox_for_path(path) {
-                        // Expand bbox by half of stroke width conservatively
-                        if let Command::StrokePath { stroke, .. } = cmd {
-                            let w = stroke.width.max(0.0) * 0.5;
-                            rect.x -= w;
-                            rect.y -= w;
-                            rect.w += w * 2.0;
-                            rect.h += w * 2.0;
-                        }
+                    if let Some(rect) = path.bounds() {
+                        let width = stroke.width.max(0.0);
+                        let contours = if stroke.is_dashed() {
+                            path.dashed(&stroke.dash, stroke.dash_offset).flatten()
+                        } else {
+                            path.flatten()
+                        };
                         items.push(HitItem {
                             id: next_id,
                             z: *z,
                             kind: HitKind::Path,
                             transform: *transform,
-                            data: HitData::PathBBox(rect),
+                            data: HitData::StrokePath {
+                                bbox: inflate_rect(rect, width * 0.5),
+                                contours,
+                                width,
+                            },
                             clips: clips.clone(),
                             region_id: None,
                         });
@@ -395,122 +546,457 @@ impl HitIndex {
             region_id: Some(u32::MAX),
         });
 
-        Self { items }
+        let grid = HitGrid::build(&items);
+        Self { items, grid }
     }
 
     /// Return the topmost element at the given device-space position.
     pub fn topmost_at(&self, pos: [f32; 2]) -> Option<HitResult> {
-        let mut best: Option<HitItem> = None;
-        for it in &self.items {
-            if !passes_clip(it, pos) {
-                continue;
+        self.grid
+            .candidates_at(pos)
+            .map(|i| &self.items[i as usize])
+            .filter(|it| passes_clip(it, pos) && hit_item_contains(it, pos))
+            .max_by_key(|it| (it.z, it.id))
+            .map(|it| hit_result(it, Some(pos)))
+    }
+
+    /// Return every element at the given device-space position, topmost
+    /// first.
+    pub fn hits_at(&self, pos: [f32; 2]) -> Vec<HitResult> {
+        let mut hits: Vec<&HitItem> = self
+            .grid
+            .candidates_at(pos)
+            .map(|i| &self.items[i as usize])
+            .filter(|it| passes_clip(it, pos) && hit_item_contains(it, pos))
+            .collect();
+        hits.sort_by_key(|it| std::cmp::Reverse((it.z, it.id)));
+        hits.into_iter()
+            .map(|it| hit_result(it, Some(pos)))
+            .collect()
+    }
+
+    /// Return every element whose clipped device-space bounding box
+    /// intersects `rect`, topmost first. Intended for marquee selection;
+    /// results carry no local position.
+    pub fn hits_in_rect(&self, rect: Rect) -> Vec<HitResult> {
+        let mut hits: Vec<&HitItem> = self
+            .grid
+            .candidates_in(rect)
+            .into_iter()
+            .map(|i| &self.items[i as usize])
+            .collect();
+        hits.sort_by_key(|it| std::cmp::Reverse((it.z, it.id)));
+        hits.into_iter().map(|it| hit_result(it, None)).collect()
+    }
+}
+
+fn hit_result(it: &HitItem, pos: Option<[f32; 2]>) -> HitResult {
+    let (local_pos, local_uv) = pos.map_or((None, None), |pos| compute_locals(it, pos));
+    HitResult {
+        id: it.id,
+        z: it.z,
+        kind: it.kind,
+        shape: match &it.data {
+            HitData::Rect(r) => HitShape::Rect(*r),
+            HitData::RoundedRect(rr) => HitShape::RoundedRect(*rr),
+            HitData::Ellipse { center, radii } => HitShape::Ellipse {
+                center: *center,
+                radii: *radii,
+            },
+            HitData::StrokeRect { rect, width } => HitShape::StrokeRect {
+                rect: *rect,
+                width: *width,
+            },
+            HitData::StrokeRoundedRect { rrect, width } => HitShape::StrokeRoundedRect {
+                rrect: *rrect,
+                width: *width,
+            },
+            HitData::Border { rrect, widths } => HitShape::Border {
+                rrect: *rrect,
+                widths: *widths,
+            },
+            HitData::Path { bbox, .. } | HitData::StrokePath { bbox, .. } => {
+                HitShape::PathBBox { rect: *bbox }
             }
-            if hit_item_contains(it, pos) {
-                best = match best {
-                    None => Some(it.clone()),
-                    Some(ref cur) => {
-                        if it.z > cur.z || (it.z == cur.z && it.id > cur.id) {
-                            Some(it.clone())
-                        } else {
-                            Some(cur.clone())
-                        }
-                    }
-                };
+            HitData::Text { rect, .. } => HitShape::Text { rect: *rect },
+            HitData::BoxShadow { rrect } => HitShape::BoxShadow { rrect: *rrect },
+            HitData::Hyperlink { rect, url } => HitShape::Hyperlink {
+                rect: *rect,
+                url: url.clone(),
+            },
+        },
+        transform: it.transform,
+        region_id: it.region_id,
+        local_pos,
+        local_uv,
+        text: pos.and_then(|pos| text_hit(it, pos)),
+    }
+}
+
+fn text_hit(it: &HitItem, world: [f32; 2]) -> Option<TextHit> {
+    let HitData::Text { rect, clusters } = &it.data else {
+        return None;
+    };
+    let p = it.transform.inverse_apply(world)?;
+    let cluster = cluster_at(clusters, *rect, p)?;
+    let glyph_rect = Rect {
+        x: rect.x + cluster.x,
+        y: rect.y,
+        w: cluster.width,
+        h: rect.h,
+    };
+    // The cluster's logical start is on its left edge unless it's RTL.
+    let past_middle = p[0] > glyph_rect.x + glyph_rect.w * 0.5;
+    let caret_offset = if past_middle != cluster.rtl {
+        cluster.byte_range.end
+    } else {
+        cluster.byte_range.start
+    };
+    Some(TextHit {
+        cluster: cluster.byte_range.clone(),
+        caret_offset,
+        glyph_rect,
+    })
+}
+
+/// Uniform grid over the clipped world-space bounds of the hit items. Each
+/// cell lists the items overlapping it; items whose bounds aren't finite are
+/// tested by every query.
+#[derive(Default)]
+struct HitGrid {
+    origin: [f32; 2],
+    cell: [f32; 2],
+    cols: usize,
+    rows: usize,
+    cells: Vec<Vec<u32>>,
+    /// World-space bounds per item; `None` for items that can never be hit
+    /// (clipped away or with a non-invertible transform).
+    bounds: Vec<Option<Rect>>,
+    unbounded: Vec<u32>,
+}
+
+impl HitGrid {
+    /// Upper bound on cells per axis.
+    const MAX_CELLS: usize = 128;
+
+    fn build(items: &[HitItem]) -> Self {
+        let bounds: Vec<Option<Rect>> = items.iter().map(item_world_bounds).collect();
+        let mut unbounded = Vec::new();
+        let mut total: Option<Rect> = None;
+        for (i, b) in bounds.iter().enumerate() {
+            match b {
+                Some(b) if rect_is_finite(b) => {
+                    total = Some(total.map_or(*b, |t| union_rect(t, *b)));
+                }
+                Some(_) => unbounded.push(i as u32),
+                None => {}
             }
         }
-        best.map(|it| {
-            let (local_pos, local_uv) = compute_locals(&it, pos);
-            HitResult {
-                id: it.id,
-                z: it.z,
-                kind: it.kind,
-                shape: match &it.data {
-                    HitData::Rect(r) => HitShape::Rect(*r),
-                    HitData::RoundedRect(rr) => HitShape::RoundedRect(*rr),
-                    HitData::Ellipse { center, radii } => HitShape::Ellipse {
-                        center: *center,
-                        radii: *radii,
-                    },
-                    HitData::StrokeRect { rect, width } => HitShape::StrokeRect {
-                        rect: *rect,
-                        width: *width,
-                    },
-                    HitData::StrokeRoundedRect { rrect, width } => HitShape::StrokeRoundedRect {
-                        rrect: *rrect,
-                        width: *width,
-                    },
-                    HitData::PathBBox(r) => HitShape::PathBBox { rect: *r },
-                    HitData::Text(_) => HitShape::Text,
-                    HitData::BoxShadow { rrect } => HitShape::BoxShadow { rrect: *rrect },
-                    HitData::Hyperlink { rect, url } => HitShape::Hyperlink {
-                        rect: *rect,
-                        url: url.clone(),
-                    },
-                },
-                transform: it.transform,
-                region_id: it.region_id,
-                local_pos,
-                local_uv,
+        let Some(total) = total else {
+            return Self {
+                bounds,
+                unbounded,
+                ..Self::default()
+            };
+        };
+
+        // Aim for roughly one item per cell.
+        let side = ((items.len() as f32).sqrt().ceil() as usize).clamp(1, Self::MAX_CELLS);
+        let mut grid = Self {
+            origin: [total.x, total.y],
+            cell: [
+                (total.w / side as f32).max(1e-3),
+                (total.h / side as f32).max(1e-3),
+            ],
+            cols: side,
+            rows: side,
+            cells: vec![Vec::new(); side * side],
+            bounds,
+            unbounded,
+        };
+        for i in 0..grid.bounds.len() {
+            let Some(b) = grid.bounds[i].filter(rect_is_finite) else {
+                continue;
+            };
+            let (cols, rows) = grid.cell_span(b);
+            for row in rows {
+                for col in cols.clone() {
+                    grid.cells[row * grid.cols + col].push(i as u32);
+                }
             }
-        })
+        }
+        grid
     }
-}
 
-fn bbox_for_path(path: &Path) -> Option<Rect> {
-    let mut minx = f32::INFINITY;
-    let mut miny = f32::INFINITY;
-    let mut maxx = f32::NEG_INFINITY;
-    let mut maxy = f32::NEG_INFINITY;
-    let mut any = false;
-    for cmd in &path.cmds {
-        match *cmd {
-            PathCmd::MoveTo(p) | PathCmd::LineTo(p) => {
-                minx = minx.min(p[0]);
-                miny = miny.min(p[1]);
-                maxx = maxx.max(p[0]);
-                maxy = maxy.max(p[1]);
-                any = true;
-            }
-            PathCmd::QuadTo(c, p) => {
-                for q in [c, p] {
-                    minx = minx.min(q[0]);
-                    miny = miny.min(q[1]);
-                    maxx = maxx.max(q[0]);
-                    maxy = maxy.max(q[1]);
+    /// Column and row ranges of the cells overlapping `rect`, clamped to
+    /// the grid.
+    fn cell_span(
+        &self,
+        rect: Rect,
+    ) -> (
+        std::ops::RangeInclusive<usize>,
+        std::ops::RangeInclusive<usize>,
+    ) {
+        let cell = |v: f32, origin: f32, size: f32, n: usize| {
+            (((v - origin) / size).floor().max(0.0) as usize).min(n - 1)
+        };
+        let c0 = cell(rect.x, self.origin[0], self.cell[0], self.cols);
+        let c1 = cell(rect.x + rect.w, self.origin[0], self.cell[0], self.cols);
+        let r0 = cell(rect.y, self.origin[1], self.cell[1], self.rows);
+        let r1 = cell(rect.y + rect.h, self.origin[1], self.cell[1], self.rows);
+        (c0..=c1, r0..=r1)
+    }
+
+    /// Items whose bounds contain `pos`, in draw order.
+    fn candidates_at(&self, pos: [f32; 2]) -> impl Iterator<Item = u32> + '_ {
+        let point = Rect {
+            x: pos[0],
+            y: pos[1],
+            w: 0.0,
+            h: 0.0,
+        };
+        let cell: &[u32] = if self.cells.is_empty() {
+            &[]
+        } else {
+            let (cols, rows) = self.cell_span(point);
+            &self.cells[rows.start() * self.cols + cols.start()]
+        };
+        cell.iter()
+            .chain(&self.unbounded)
+            .copied()
+            .filter(move |&i| self.bounds[i as usize].is_some_and(|b| rect_contains(&b, pos)))
+    }
+
+    /// Items whose bounds intersect `rect`, in draw order.
+    fn candidates_in(&self, rect: Rect) -> Vec<u32> {
+        let mut out: Vec<u32> = self.unbounded.clone();
+        if !self.cells.is_empty() {
+            let (cols, rows) = self.cell_span(rect);
+            for row in rows {
+                for col in cols.clone() {
+                    out.extend_from_slice(&self.cells[row * self.cols + col]);
                 }
-                any = true;
             }
-            PathCmd::CubicTo(c1, c2, p) => {
-                for q in [c1, c2, p] {
-                    minx = minx.min(q[0]);
-                    miny = miny.min(q[1]);
-                    maxx = maxx.max(q[0]);
-                    maxy = maxy.max(q[1]);
+        }
+        out.sort_unstable();
+        out.dedup();
+        out.retain(|&i| self.bounds[i as usize].is_some_and(|b| rects_intersect(&b, &rect)));
+        out
+    }
+}
+
+/// World-space bounding box of an item's shape, intersected with its clip
+/// stack. `None` if the item can't be hit.
+fn item_world_bounds(item: &HitItem) -> Option<Rect> {
+    item.transform.inverse_apply([0.0, 0.0])?;
+    let local = match &item.data {
+        HitData::Rect(r)
+        | HitData::Path { bbox: r, .. }
+        | HitData::StrokePath { bbox: r, .. }
+        | HitData::Text { rect: r, .. } => *r,
+        HitData::RoundedRect(rr)
+        | HitData::BoxShadow { rrect: rr }
+        | HitData::Border { rrect: rr, .. } => rr.rect,
+        HitData::Ellipse { center, radii } => Rect {
+            x: center[0] - radii[0].abs(),
+            y: center[1] - radii[1].abs(),
+            w: radii[0].abs() * 2.0,
+            h: radii[1].abs() * 2.0,
+        },
+        HitData::StrokeRect { rect, width } => inflate_rect(*rect, width.abs() * 0.5),
+        HitData::StrokeRoundedRect { rrect, width } => inflate_rect(rrect.rect, width.abs() * 0.5),
+        HitData::Hyperlink { rect, .. } => *rect,
+    };
+    let mut bounds = transform_bounds(&item.transform, local);
+    for clip in &item.clips {
+        bounds = intersect_rect(
+            bounds,
+            transform_bounds(&clip.transform, clip.shape.bounds()),
+        )?;
+    }
+    Some(bounds)
+}
+
+/// Line box and glyph clusters of a text run. Without a provider, clusters
+/// are one per character at an estimated 0.6em advance.
+fn text_hit_data(run: &TextRun, provider: Option<&dyn TextProvider>) -> HitData {
+    let size = run
+        .spans
+        .iter()
+        .filter_map(|s| s.attrs.size)
+        .fold(run.size, f32::max);
+    let (ascent, descent) = provider
+        .and_then(|p| p.line_metrics(size))
+        .map_or((size * 0.8, size * 0.2), |m| (m.ascent, m.descent));
+    let clusters = provider
+        .and_then(|p| p.cluster_bounds(run))
+        .unwrap_or_else(|| {
+            let advance = size * 0.6;
+            run.text
+                .char_indices()
+                .enumerate()
+                .map(|(i, (offset, ch))| ClusterBounds {
+                    byte_range: offset..offset + ch.len_utf8(),
+                    x: i as f32 * advance,
+                    width: advance,
+                    rtl: false,
+                })
+                .collect()
+        });
+    let width = clusters
+        .iter()
+        .map(|c| c.x + c.width)
+        .fold(0.0f32, f32::max);
+    HitData::Text {
+        rect: Rect {
+            x: run.pos[0],
+            y: run.pos[1] - ascent,
+            w: width,
+            h: ascent + descent,
+        },
+        clusters,
+    }
+}
+
+/// Cluster whose horizontal extent contains local point `p`.
+fn cluster_at(clusters: &[ClusterBounds], rect: Rect, p: [f32; 2]) -> Option<&ClusterBounds> {
+    let x = p[0] - rect.x;
+    clusters.iter().find(|c| x >= c.x && x <= c.x + c.width)
+}
+
+/// Point-in-fill test. Every contour is implicitly closed, as when filling.
+fn point_in_contours(p: [f32; 2], contours: &[Contour], rule: FillRule) -> bool {
+    let mut winding = 0i32;
+    for contour in contours {
+        let pts = &contour.points;
+        for i in 0..pts.len() {
+            let a = pts[i];
+            let b = pts[(i + 1) % pts.len()];
+            if a[1] <= p[1] {
+                if b[1] > p[1] && cross(a, b, p) > 0.0 {
+                    winding += 1;
                 }
-                any = true;
+            } else if b[1] <= p[1] && cross(a, b, p) < 0.0 {
+                winding -= 1;
             }
-            PathCmd::Close => {}
         }
     }
-    if any {
-        Some(Rect {
-            x: minx,
-            y: miny,
-            w: (maxx - minx).max(0.0),
-            h: (maxy - miny).max(0.0),
-        })
+    match rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Whether `p` lies within `half_width` of any contour segment. Joins and
+/// caps are treated as round whatever the stroke's style, so miter tips and
+/// square caps are slightly under-reported; dashes are already split into
+/// separate contours.
+fn point_near_contours(p: [f32; 2], contours: &[Contour], half_width: f32) -> bool {
+    let r2 = half_width * half_width;
+    contours.iter().any(|contour| {
+        let pts = &contour.points;
+        let closing = contour.closed && pts.len() > 2;
+        let segs = pts.windows(2).map(|w| (w[0], w[1]));
+        let close = closing.then(|| (pts[pts.len() - 1], pts[0]));
+        segs.chain(close)
+            .any(|(a, b)| dist_sq_to_segment(p, a, b) <= r2)
+    })
+}
+
+/// Twice the signed area of triangle `a`, `b`, `p`; positive when `p` is
+/// left of `a -> b`.
+fn cross(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (p[0] - a[0]) * (b[1] - a[1])
+}
+
+fn dist_sq_to_segment(p: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let d = [b[0] - a[0], b[1] - a[1]];
+    let len2 = d[0] * d[0] + d[1] * d[1];
+    let t = if len2 > 0.0 {
+        (((p[0] - a[0]) * d[0] + (p[1] - a[1]) * d[1]) / len2).clamp(0.0, 1.0)
     } else {
-        None
+        0.0
+    };
+    let q = [a[0] + d[0] * t - p[0], a[1] + d[1] * t - p[1]];
+    q[0] * q[0] + q[1] * q[1]
+}
+
+pub(crate) fn transform_bounds(t: &Transform2D, r: Rect) -> Rect {
+    let corners = [
+        t.apply([r.x, r.y]),
+        t.apply([r.x + r.w, r.y]),
+        t.apply([r.x, r.y + r.h]),
+        t.apply([r.x + r.w, r.y + r.h]),
+    ];
+    let (mut minx, mut miny) = (f32::INFINITY, f32::INFINITY);
+    let (mut maxx, mut maxy) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for [x, y] in corners {
+        minx = minx.min(x);
+        miny = miny.min(y);
+        maxx = maxx.max(x);
+        maxy = maxy.max(y);
+    }
+    Rect {
+        x: minx,
+        y: miny,
+        w: maxx - minx,
+        h: maxy - miny,
     }
 }
 
-fn passes_clip(item: &HitItem, world: [f32; 2]) -> bool {
-    for c in &item.clips {
-        if !point_in_rect_local(world, &c.transform, c.rect) {
-            return false;
-        }
+pub(crate) fn inflate_rect(r: Rect, d: f32) -> Rect {
+    Rect {
+        x: r.x - d,
+        y: r.y - d,
+        w: r.w + d * 2.0,
+        h: r.h + d * 2.0,
+    }
+}
+
+pub(crate) fn union_rect(a: Rect, b: Rect) -> Rect {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    Rect {
+        x,
+        y,
+        w: (a.x + a.w).max(b.x + b.w) - x,
+        h: (a.y + a.h).max(b.y + b.h) - y,
     }
-    true
+}
+
+pub(crate) fn intersect_rect(a: Rect, b: Rect) -> Option<Rect> {
+    let x = a.x.max(b.x);
+    let y = a.y.max(b.y);
+    let w = (a.x + a.w).min(b.x + b.w) - x;
+    let h = (a.y + a.h).min(b.y + b.h) - y;
+    (w >= 0.0 && h >= 0.0).then_some(Rect { x, y, w, h })
+}
+
+pub(crate) fn rects_intersect(a: &Rect, b: &Rect) -> bool {
+    a.x <= b.x + b.w && b.x <= a.x + a.w && a.y <= b.y + b.h && b.y <= a.y + a.h
+}
+
+fn rect_contains(r: &Rect, p: [f32; 2]) -> bool {
+    p[0] >= r.x && p[1] >= r.y && p[0] <= r.x + r.w && p[1] <= r.y + r.h
+}
+
+fn rect_is_finite(r: &Rect) -> bool {
+    r.x.is_finite() && r.y.is_finite() && r.w.is_finite() && r.h.is_finite()
+}
+
+fn passes_clip(item: &HitItem, world: [f32; 2]) -> bool {
+    item.clips.iter().all(|c| match &c.shape {
+        ClipData::Rect(r) => point_in_rect_local(world, &c.transform, *r),
+        ClipData::RoundedRect(r) => point_in_rounded_rect_local(world, &c.transform, *r),
+        ClipData::Path {
+            contours,
+            fill_rule,
+            ..
+        } => c
+            .transform
+            .inverse_apply(world)
+            .is_some_and(|p| point_in_contours(p, contours, *fill_rule)),
+    })
 }
 
 fn hit_item_contains(item: &HitItem, world: [f32; 2]) -> bool {
@@ -526,8 +1012,27 @@ fn hit_item_contains(item: &HitItem, world: [f32; 2]) -> bool {
         HitData::StrokeRoundedRect { rrect, width } => {
             point_in_stroke_rounded_rect_local(world, &item.transform, *rrect, *width)
         }
-        HitData::PathBBox(rect) => point_in_rect_local(world, &item.transform, *rect),
-        HitData::Text(_run) => false, // Text hit testing not yet implemented
+        HitData::Border { rrect, widths } => item.transform.inverse_apply(world).is_some_and(|p| {
+            rrect.contains(p) && !rrect.inset(widths.map(|w| w.max(0.0))).contains(p)
+        }),
+        HitData::Path {
+            contours,
+            fill_rule,
+            ..
+        } => item
+            .transform
+            .inverse_apply(world)
+            .is_some_and(|p| point_in_contours(p, contours, *fill_rule)),
+        HitData::StrokePath {
+            contours, width, ..
+        } => item
+            .transform
+            .inverse_apply(world)
+            .is_some_and(|p| point_near_contours(p, contours, *width * 0.5)),
+        HitData::Text { rect, clusters } => item
+            .transform
+            .inverse_apply(world)
+            .is_some_and(|p| rect_contains(rect, p) && cluster_at(clusters, *rect, p).is_some()),
         HitData::BoxShadow { rrect } => point_in_rounded_rect_local(world, &item.transform, *rrect),
         HitData::Hyperlink { rect, .. } => point_in_rect_local(world, &item.transform, *rect),
     }
@@ -546,45 +1051,9 @@ fn point_in_rounded_rect_local(
     transform: &Transform2D,
     rrect: RoundedRect,
 ) -> bool {
-    if let Some(p) = transform.inverse_apply(world) {
-        let Rect { x, y, w, h } = rrect.rect;
-        let tl = rrect.radii.tl.min(w * 0.5).min(h * 0.5);
-        let tr = rrect.radii.tr.min(w * 0.5).min(h * 0.5);
-        let br = rrect.radii.br.min(w * 0.5).min(h * 0.5);
-        let bl = rrect.radii.bl.min(w * 0.5).min(h * 0.5);
-        let px = p[0] - x;
-        let py = p[1] - y;
-        if px < 0.0 || py < 0.0 || px > w || py > h {
-            return false;
-        }
-        // Top-left
-        if px < tl && py < tl {
-            let dx = tl - px;
-            let dy = tl - py;
-            return dx * dx + dy * dy <= tl * tl + 1e-5;
-        }
-        // Top-right
-        if px > w - tr && py < tr {
-            let dx = px - (w - tr);
-            let dy = tr - py;
-            return dx * dx + dy * dy <= tr * tr + 1e-5;
-        }
-        // Bottom-right
-        if px > w - br && py > h - br {
-            let dx = px - (w - br);
-            let dy = py - (h - br);
-            return dx * dx + dy * dy <= br * br + 1e-5;
-        }
-        // Bottom-left
-        if px < bl && py > h - bl {
-            let dx = bl - px;
-            let dy = py - (h - bl);
-            return dx * dx + dy * dy <= bl * bl + 1e-5;
-        }
-        true
-    } else {
-        false
-    }
+    transform
+        .inverse_apply(world)
+        .is_some_and(|p| rrect.contains(p))
 }
 
 fn point_in_ellipse_local(
@@ -644,66 +1113,19 @@ fn point_in_stroke_rounded_rect_local(
     // Approximate by testing ring between rrect and inset rrect.
     if let Some(p) = transform.inverse_apply(world) {
         // Outer check
-        let outer_hit = point_in_rounded_rect_untransformed(p, rrect);
+        let outer_hit = rrect.contains(p);
         if !outer_hit {
             return false;
         }
         // Inner check (inset by stroke width)
         let inset = width.max(0.0) * 0.5;
-        let inner = RoundedRect {
-            rect: Rect {
-                x: rrect.rect.x + inset,
-                y: rrect.rect.y + inset,
-                w: (rrect.rect.w - width).max(0.0),
-                h: (rrect.rect.h - width).max(0.0),
-            },
-            radii: RoundedRadii {
-                tl: (rrect.radii.tl - inset).max(0.0),
-                tr: (rrect.radii.tr - inset).max(0.0),
-                br: (rrect.radii.br - inset).max(0.0),
-                bl: (rrect.radii.bl - inset).max(0.0),
-            },
-        };
-        let inner_hit = point_in_rounded_rect_untransformed(p, inner);
+        let inner = rrect.inset([inset; 4]);
+        let inner_hit = inner.contains(p);
         return outer_hit && !inner_hit;
     }
     false
 }
 
-fn point_in_rounded_rect_untransformed(p: [f32; 2], rrect: RoundedRect) -> bool {
-    let Rect { x, y, w, h } = rrect.rect;
-    let tl = rrect.radii.tl.min(w * 0.5).min(h * 0.5);
-    let tr = rrect.radii.tr.min(w * 0.5).min(h * 0.5);
-    let br = rrect.radii.br.min(w * 0.5).min(h * 0.5);
-    let bl = rrect.radii.bl.min(w * 0.5).min(h * 0.5);
-    let px = p[0] - x;
-    let py = p[1] - y;
-    if px < 0.0 || py < 0.0 || px > w || py > h {
-        return false;
-    }
-    if px < tl && py < tl {
-        let dx = tl - px;
-        let dy = tl - py;
-        return dx * dx + dy * dy <= tl * tl + 1e-5;
-    }
-    if px > w - tr && py < tr {
-        let dx = px - (w - tr);
-        let dy = tr - py;
-        return dx * dx + dy * dy <= tr * tr + 1e-5;
-    }
-    if px > w - br && py > h - br {
-        let dx = px - (w - br);
-        let dy = py - (h - br);
-        return dx * dx + dy * dy <= br * br + 1e-5;
-    }
-    if px < bl && py > h - bl {
-        let dx = bl - px;
-        let dy = py - (h - bl);
-        return dx * dx + dy * dy <= bl * bl + 1e-5;
-    }
-    true
-}
-
 // --- Transform helpers ---
 impl Transform2D {
     /// Apply the transform to a point (x, y).
@@ -805,13 +1227,14 @@ fn compute_locals(item: &HitItem, world: [f32; 2]) -> (Option<[f32; 2]>, Option<
             let local = [p[0] - rect.x, p[1] - rect.y];
             (Some(local), None)
         }
-        HitData::StrokeRoundedRect { rrect, .. } => {
+        HitData::StrokeRoundedRect { rrect, .. } | HitData::Border { rrect, .. } => {
             let r = rrect.rect;
             let local = [p[0] - r.x, p[1] - r.y];
             (Some(local), None)
         }
-        HitData::Text(_) => (None, None),
-        HitData::PathBBox(r) => {
+        HitData::Text { rect: r, .. }
+        | HitData::Path { bbox: r, .. }
+        | HitData::StrokePath { bbox: r, .. } => {
             let local = [p[0] - r.x, p[1] - r.y];
             let uv = [
                 if r.w.abs() > 1e-6 {
@@ -862,3 +1285,339 @@ fn compute_locals(item: &HitItem, world: [f32; 2]) -> (Option<[f32; 2]>, Option<
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Painter;
+    use crate::display_list::Viewport;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    fn painter() -> Painter {
+        Painter::begin_frame(Viewport {
+            width: 400,
+            height: 400,
+        })
+    }
+
+    #[test]
+    fn topmost_prefers_higher_z_then_later_draws() {
+        let mut p = painter();
+        p.hit_region_rect(1, rect(0.0, 0.0, 100.0, 100.0), 5);
+        p.hit_region_rect(2, rect(50.0, 50.0, 100.0, 100.0), 1);
+        p.hit_region_rect(3, rect(50.0, 50.0, 100.0, 100.0), 1);
+        let index = HitIndex::build(&p.finish());
+
+        assert_eq!(index.topmost_at([75.0, 75.0]).unwrap().region_id, Some(1));
+        assert_eq!(index.topmost_at([120.0, 120.0]).unwrap().region_id, Some(3));
+        // Only the root viewport region remains outside every shape.
+        assert_eq!(
+            index.topmost_at([300.0, 300.0]).unwrap().region_id,
+            Some(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn hits_at_lists_every_hit_topmost_first() {
+        let mut p = painter();
+        p.hit_region_rect(1, rect(0.0, 0.0, 100.0, 100.0), 0);
+        p.hit_region_ellipse(2, [50.0, 50.0], [10.0, 10.0], 2);
+        p.hit_region_rect(3, rect(200.0, 200.0, 10.0, 10.0), 9);
+        let index = HitIndex::build(&p.finish());
+
+        let ids: Vec<_> = index
+            .hits_at([50.0, 50.0])
+            .iter()
+            .map(|h| h.region_id)
+            .collect();
+        assert_eq!(ids, vec![Some(2), Some(1), Some(u32::MAX)]);
+    }
+
+    #[test]
+    fn clips_and_transforms_are_respected() {
+        let mut p = painter();
+        p.push_clip_rect(rect(0.0, 0.0, 50.0, 50.0));
+        p.hit_region_rect(1, rect(0.0, 0.0, 100.0, 100.0), 1);
+        p.pop_clip();
+        p.push_transform(Transform2D {
+            m: [1.0, 0.0, 0.0, 1.0, 200.0, 0.0],
+        });
+        p.hit_region_rect(2, rect(0.0, 0.0, 20.0, 20.0), 1);
+        p.pop_transform();
+        let index = HitIndex::build(&p.finish());
+
+        assert_eq!(index.topmost_at([25.0, 25.0]).unwrap().region_id, Some(1));
+        assert_eq!(
+            index.topmost_at([75.0, 75.0]).unwrap().region_id,
+            Some(u32::MAX)
+        );
+        let hit = index.topmost_at([210.0, 5.0]).unwrap();
+        assert_eq!(hit.region_id, Some(2));
+        assert_eq!(hit.local_pos, Some([10.0, 5.0]));
+    }
+
+    #[test]
+    fn hits_in_rect_uses_clipped_bounds() {
+        let mut p = painter();
+        p.hit_region_rect(1, rect(10.0, 10.0, 20.0, 20.0), 0);
+        p.hit_region_rect(2, rect(100.0, 100.0, 20.0, 20.0), 3);
+        p.push_clip_rect(rect(300.0, 300.0, 10.0, 10.0));
+        p.hit_region_rect(3, rect(0.0, 0.0, 50.0, 50.0), 0);
+        p.pop_clip();
+        let index = HitIndex::build(&p.finish());
+
+        let ids: Vec<_> = index
+            .hits_in_rect(rect(0.0, 0.0, 150.0, 150.0))
+            .iter()
+            .map(|h| h.region_id)
+            .collect();
+        assert_eq!(ids, vec![Some(2), Some(1), Some(u32::MAX)]);
+    }
+
+    #[test]
+    fn rounded_and_path_clips_follow_their_shape() {
+        let mut p = painter();
+        p.push_transform(Transform2D::translate(100.0, 0.0));
+        p.push_clip_rounded_rect(RoundedRect {
+            rect: rect(0.0, 0.0, 100.0, 100.0),
+            radii: RoundedRadii::uniform(40.0),
+        });
+        p.hit_region_rect(1, rect(0.0, 0.0, 100.0, 100.0), 1);
+        p.pop_clip();
+        p.pop_transform();
+        let mut cmds = Vec::new();
+        square(&mut cmds, 0.0, 200.0, 100.0);
+        square(&mut cmds, 25.0, 225.0, 50.0);
+        p.push_clip_path(Path {
+            cmds,
+            fill_rule: FillRule::EvenOdd,
+        });
+        p.hit_region_rect(2, rect(0.0, 200.0, 100.0, 100.0), 1);
+        p.pop_clip();
+        let index = HitIndex::build(&p.finish());
+        let region_at = |pos| index.topmost_at(pos).and_then(|h| h.region_id);
+
+        assert_eq!(region_at([150.0, 50.0]), Some(1));
+        // Cut off by the rounded corner.
+        assert_eq!(region_at([103.0, 3.0]), Some(u32::MAX));
+        assert_eq!(region_at([10.0, 210.0]), Some(2));
+        // Inside the even-odd hole.
+        assert_eq!(region_at([50.0, 250.0]), Some(u32::MAX));
+    }
+
+    #[test]
+    fn grid_matches_linear_scan_on_many_items() {
+        let mut p = painter();
+        for i in 0..400u32 {
+            let (col, row) = ((i % 20) as f32, (i / 20) as f32);
+            p.hit_region_rect(i, rect(col * 20.0, row * 20.0, 25.0, 25.0), (i % 3) as i32);
+        }
+        let list = p.finish();
+        let index = HitIndex::build(&list);
+        for y in (0..400).step_by(7) {
+            for x in (0..400).step_by(7) {
+                let pos = [x as f32, y as f32];
+                let expected = index
+                    .items
+                    .iter()
+                    .filter(|it| passes_clip(it, pos) && hit_item_contains(it, pos))
+                    .max_by_key(|it| (it.z, it.id))
+                    .map(|it| it.id);
+                assert_eq!(index.topmost_at(pos).map(|h| h.id), expected);
+            }
+        }
+    }
+
+    fn square(path: &mut Vec<PathCmd>, x: f32, y: f32, size: f32) {
+        path.push(PathCmd::MoveTo([x, y]));
+        path.push(PathCmd::LineTo([x + size, y]));
+        path.push(PathCmd::LineTo([x + size, y + size]));
+        path.push(PathCmd::LineTo([x, y + size]));
+        path.push(PathCmd::Close);
+    }
+
+    #[test]
+    fn fill_paths_honor_fill_rule_and_concavity() {
+        let white = Brush::Solid(ColorLinPremul::rgba(255, 255, 255, 255));
+        let mut cmds = Vec::new();
+        square(&mut cmds, 0.0, 0.0, 100.0);
+        square(&mut cmds, 25.0, 25.0, 50.0);
+        let mut p = painter();
+        p.fill_path(
+            Path {
+                cmds: cmds.clone(),
+                fill_rule: FillRule::EvenOdd,
+            },
+            white.clone(),
+            1,
+        );
+        // Concave "L" made of a quadratic corner.
+        p.fill_path(
+            Path {
+                cmds: vec![
+                    PathCmd::MoveTo([200.0, 0.0]),
+                    PathCmd::LineTo([220.0, 0.0]),
+                    PathCmd::QuadTo([220.0, 80.0], [300.0, 80.0]),
+                    PathCmd::LineTo([300.0, 100.0]),
+                    PathCmd::LineTo([200.0, 100.0]),
+                    PathCmd::Close,
+                ],
+                fill_rule: FillRule::NonZero,
+            },
+            white.clone(),
+            1,
+        );
+        let index = HitIndex::build(&p.finish());
+        let kind_at = |pos| index.topmost_at(pos).map(|h| h.kind);
+
+        assert_eq!(kind_at([10.0, 10.0]), Some(HitKind::Path));
+        // The even-odd hole is empty.
+        assert_eq!(kind_at([50.0, 50.0]), Some(HitKind::HitRegion));
+        assert_eq!(kind_at([210.0, 50.0]), Some(HitKind::Path));
+        assert_eq!(kind_at([280.0, 20.0]), Some(HitKind::HitRegion));
+
+        let mut p = painter();
+        p.fill_path(
+            Path {
+                cmds,
+                fill_rule: FillRule::NonZero,
+            },
+            white,
+            1,
+        );
+        let index = HitIndex::build(&p.finish());
+        assert_eq!(
+            index.topmost_at([50.0, 50.0]).map(|h| h.kind),
+            Some(HitKind::Path)
+        );
+    }
+
+    #[test]
+    fn stroke_paths_hit_within_half_width() {
+        let mut p = painter();
+        p.stroke_path(
+            Path {
+                cmds: vec![
+                    PathCmd::MoveTo([0.0, 100.0]),
+                    PathCmd::LineTo([200.0, 100.0]),
+                ],
+                fill_rule: FillRule::NonZero,
+            },
+            Stroke::round(10.0),
+            Brush::Solid(ColorLinPremul::rgba(255, 255, 255, 255)),
+            1,
+        );
+        let index = HitIndex::build(&p.finish());
+        let kind_at = |pos| index.topmost_at(pos).map(|h| h.kind);
+
+        assert_eq!(kind_at([100.0, 104.0]), Some(HitKind::Path));
+        assert_eq!(kind_at([204.0, 100.0]), Some(HitKind::Path));
+        assert_eq!(kind_at([100.0, 107.0]), Some(HitKind::HitRegion));
+    }
+
+    #[test]
+    fn dashed_stroke_gaps_do_not_hit() {
+        let mut p = painter();
+        p.stroke_path(
+            Path {
+                cmds: vec![
+                    PathCmd::MoveTo([0.0, 100.0]),
+                    PathCmd::LineTo([200.0, 100.0]),
+                ],
+                fill_rule: FillRule::NonZero,
+            },
+            Stroke::new(4.0).with_dash(vec![20.0, 20.0], 0.0),
+            Brush::Solid(ColorLinPremul::rgba(255, 255, 255, 255)),
+            1,
+        );
+        let index = HitIndex::build(&p.finish());
+        let kind_at = |pos| index.topmost_at(pos).map(|h| h.kind);
+
+        assert_eq!(kind_at([10.0, 100.0]), Some(HitKind::Path));
+        assert_eq!(kind_at([30.0, 100.0]), Some(HitKind::HitRegion));
+        assert_eq!(kind_at([50.0, 100.0]), Some(HitKind::Path));
+    }
+
+    #[test]
+    fn text_hits_report_the_glyph_cluster() {
+        let mut p = painter();
+        p.text(
+            TextRun::new(
+                "héllo",
+                [10.0, 50.0],
+                20.0,
+                ColorLinPremul::rgba(0, 0, 0, 255),
+            ),
+            1,
+        );
+        let list = p.finish();
+
+        // Estimated 12px advances: "é" spans x = 22..34.
+        let hit = HitIndex::build(&list).topmost_at([25.0, 45.0]).unwrap();
+        assert_eq!(hit.kind, HitKind::Text);
+        let text = hit.text.unwrap();
+        assert_eq!(text.cluster, 1..3);
+        assert_eq!(text.caret_offset, 1);
+        assert!(
+            HitIndex::build(&list)
+                .topmost_at([25.0, 80.0])
+                .unwrap()
+                .text
+                .is_none()
+        );
+
+        let font = std::fs::read(format!(
+            "{}/../../fonts/Geist/static/Geist-Regular.ttf",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .expect("bundled Geist font");
+        let provider =
+            crate::RuneTextProvider::from_bytes(&font, crate::SubpixelOrientation::RGB).unwrap();
+        let index = HitIndex::build_with_text(&list, &provider);
+        let clusters = provider.cluster_bounds(&list_run(&list)).unwrap();
+        let last = clusters.last().unwrap();
+        let x = 10.0 + last.x + last.width * 0.75;
+        let text = index.topmost_at([x, 45.0]).unwrap().text.unwrap();
+        assert_eq!(text.cluster, 5..6);
+        assert_eq!(text.caret_offset, 6);
+    }
+
+    fn list_run(list: &DisplayList) -> TextRun {
+        list.commands
+            .iter()
+            .find_map(|c| match c {
+                Command::DrawText { run, .. } => Some(run.clone()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn borders_hit_only_their_ring() {
+        let mut p = painter();
+        let rrect = RoundedRect {
+            rect: rect(0.0, 0.0, 100.0, 50.0),
+            radii: RoundedRadii::elliptical(50.0, 25.0),
+        };
+        let mut border = Border::uniform(4.0, ColorLinPremul::rgba(0, 0, 0, 255));
+        border.widths[3] = 0.0;
+        p.border(rrect, border, 0);
+        let index = HitIndex::build(&p.finish());
+        let on_border = |pt| {
+            index
+                .topmost_at(pt)
+                .is_some_and(|h| h.kind == HitKind::Border)
+        };
+
+        assert!(on_border([50.0, 2.0]));
+        assert!(on_border([98.0, 25.0]));
+        assert!(!on_border([50.0, 25.0]));
+        // The ellipse's bounding-box corner is outside the shape.
+        assert!(!on_border([3.0, 3.0]));
+        // The left side has no width.
+        assert!(!on_border([1.0, 25.0]));
+    }
+}
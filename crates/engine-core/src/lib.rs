@@ -8,7 +8,9 @@ use anyhow::Result;
 pub use wgpu;
 
 mod allocator;
+mod atlas;
 pub use allocator::{OwnedBuffer, OwnedTexture, RenderAllocator};
+pub use atlas::{ATLAS_PADDING, AtlasAllocator, AtlasRegion};
 
 /// Top-level engine handle.
 pub struct GraphicsEngine {
@@ -103,7 +105,10 @@ pub fn make_surface_config(
 }
 
 // Scene and display list (Phase 2)
+mod capture;
 mod color; // sRGB conversion helpers for ColorLinPremul
+mod cpu_raster;
+mod damage;
 mod display_list;
 mod dpi;
 mod hit_test;
@@ -117,6 +122,9 @@ mod text;
 mod text_layout;
 mod upload;
 
+pub use capture::CaptureFormat;
+pub use cpu_raster::*;
+pub use damage::*;
 pub use display_list::*;
 pub use dpi::*;
 pub use hit_test::*;
@@ -126,6 +134,8 @@ pub use pass_manager::Background as RootBackground;
 pub use pass_manager::*;
 pub use pipeline::Blitter;
 pub use pipeline::*;
+pub use rune_text::layout::{EllipsisPosition, TextOverflow};
+pub use rune_text::{FontFeature, FontVariation};
 pub use scene::*;
 pub use svg::*;
 pub use text::*;
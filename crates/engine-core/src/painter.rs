@@ -5,7 +5,8 @@ use std::path::PathBuf;
 pub struct Painter {
     list: DisplayList,
     transform_stack: Vec<Transform2D>,
-    clip_depth: usize,
+    clip_stack: Vec<Clip>,
+    layer_depth: usize,
 }
 
 impl Painter {
@@ -16,7 +17,20 @@ impl Painter {
                 commands: Vec::new(),
             },
             transform_stack: vec![Transform2D::identity()],
-            clip_depth: 0,
+            clip_stack: Vec::new(),
+            layer_depth: 0,
+        }
+    }
+
+    /// Continue painting after the commands of a finished `list`, such as
+    /// one decoded from a capture. Its transform, clip and layer pushes must
+    /// be balanced.
+    pub fn from_display_list(list: DisplayList) -> Self {
+        Self {
+            list,
+            transform_stack: vec![Transform2D::identity()],
+            clip_stack: Vec::new(),
+            layer_depth: 0,
         }
     }
 
@@ -36,15 +50,92 @@ impl Painter {
     }
 
     pub fn push_clip_rect(&mut self, rect: Rect) {
-        self.clip_depth += 1;
-        self.list.commands.push(Command::PushClip(ClipRect(rect)));
+        self.push_clip(ClipShape::Rect(rect));
+    }
+    /// Clip to a rounded rectangle, e.g. a scroll container or card with
+    /// `corner_radius`.
+    pub fn push_clip_rounded_rect(&mut self, rrect: RoundedRect) {
+        self.push_clip(ClipShape::RoundedRect(rrect));
+    }
+    /// Clip to the inside of `path`, according to its fill rule.
+    pub fn push_clip_path(&mut self, path: Path) {
+        self.push_clip(ClipShape::Path(path));
+    }
+    /// Push a clip; it intersects any clip already active and is placed with
+    /// the current transform.
+    pub fn push_clip(&mut self, clip: ClipShape) {
+        self.clip_stack.push(Clip {
+            shape: clip.clone(),
+            transform: self.current_transform(),
+        });
+        self.list.commands.push(Command::PushClip(clip));
     }
     pub fn pop_clip(&mut self) {
-        if self.clip_depth > 0 {
-            self.clip_depth -= 1;
+        if self.clip_stack.pop().is_some() {
             self.list.commands.push(Command::PopClip);
         }
     }
+    /// Clips pushed and not popped yet, outermost first.
+    pub fn clips(&self) -> &[Clip] {
+        &self.clip_stack
+    }
+
+    /// Start an offscreen layer: everything drawn until the matching
+    /// `pop_layer` is composited as one group with `opacity` (`0.0..=1.0`),
+    /// `blend_mode` and an optional alpha mask. The mask is placed with the
+    /// current transform.
+    pub fn push_layer(&mut self, opacity: f32, blend_mode: BlendMode, mask: Option<LayerMask>) {
+        self.push_layer_with_filters(opacity, blend_mode, mask, Vec::new(), None);
+    }
+
+    /// Start a layer whose content is run through `filters` (blur, drop
+    /// shadow, color matrix) before it is composited.
+    pub fn push_filter_layer(&mut self, filters: Vec<Filter>) {
+        self.push_layer_with_filters(1.0, BlendMode::Normal, None, filters, None);
+    }
+
+    /// Start a layer drawn over a filtered copy of what is behind it inside
+    /// `rrect`, e.g. a frosted-glass panel. The backdrop is sampled at `z`.
+    pub fn push_backdrop_layer(&mut self, rrect: RoundedRect, filters: Vec<Filter>, z: i32) {
+        let backdrop = BackdropFilter::new(rrect, filters, z);
+        self.push_layer_with_filters(1.0, BlendMode::Normal, None, Vec::new(), Some(backdrop));
+    }
+
+    /// [`Painter::push_layer`] with content `filters` and an optional
+    /// backdrop filter. The mask and backdrop are placed with the current
+    /// transform.
+    pub fn push_layer_with_filters(
+        &mut self,
+        opacity: f32,
+        blend_mode: BlendMode,
+        mask: Option<LayerMask>,
+        filters: Vec<Filter>,
+        backdrop: Option<BackdropFilter>,
+    ) {
+        let t = self.current_transform();
+        let mask = mask.map(|mask| LayerMask {
+            transform: t.concat(mask.transform),
+            ..mask
+        });
+        let backdrop = backdrop.map(|backdrop| BackdropFilter {
+            transform: t.concat(backdrop.transform),
+            ..backdrop
+        });
+        self.layer_depth += 1;
+        self.list.commands.push(Command::PushLayer {
+            opacity: opacity.clamp(0.0, 1.0),
+            blend_mode,
+            mask,
+            filters,
+            backdrop,
+        });
+    }
+    pub fn pop_layer(&mut self) {
+        if self.layer_depth > 0 {
+            self.layer_depth -= 1;
+            self.list.commands.push(Command::PopLayer);
+        }
+    }
 
     pub fn rect(&mut self, rect: Rect, brush: Brush, z: i32) {
         let t = self.current_transform();
@@ -94,6 +185,17 @@ impl Painter {
         });
     }
 
+    /// Per-side border inside the outer edge `rrect`.
+    pub fn border(&mut self, rrect: RoundedRect, border: Border, z: i32) {
+        let t = self.current_transform();
+        self.list.commands.push(Command::DrawBorder {
+            rrect,
+            border,
+            z,
+            transform: t,
+        });
+    }
+
     /// Draw text with an explicit stable id and dynamic flag.
     /// Callers that don't care about ids can use `text`, which passes 0 / false.
     pub fn text_with_id(&mut self, run: TextRun, z: i32, id: u64, dynamic: bool) {
@@ -195,24 +297,25 @@ impl Painter {
         });
     }
 
-    /// Fill a path with a solid color. For now we only support solid color fills for paths.
-    pub fn fill_path(&mut self, path: Path, color: ColorLinPremul, z: i32) {
+    /// Fill a path with any brush; gradient and pattern coordinates are in the
+    /// path's coordinate space.
+    pub fn fill_path(&mut self, path: Path, brush: Brush, z: i32) {
         let t = self.current_transform();
         self.list.commands.push(Command::FillPath {
             path,
-            color,
+            brush,
             z,
             transform: t,
         });
     }
 
-    /// Stroke a path with uniform width and a solid color.
-    pub fn stroke_path(&mut self, path: Path, stroke: Stroke, color: ColorLinPremul, z: i32) {
+    /// Stroke a path with uniform width and any brush.
+    pub fn stroke_path(&mut self, path: Path, stroke: Stroke, brush: Brush, z: i32) {
         let t = self.current_transform();
         self.list.commands.push(Command::StrokePath {
             path,
             stroke,
-            color,
+            brush,
             z,
             transform: t,
         });
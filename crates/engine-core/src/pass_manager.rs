@@ -1,17 +1,40 @@
+use std::ops::{Bound, Range, RangeBounds};
 use std::sync::Arc;
 
 // use anyhow::Result;
 
 use crate::allocator::{RenderAllocator, TexKey};
+use crate::atlas::{AtlasAllocator, AtlasRegion};
 // use crate::display_list::{Command, DisplayList, Viewport};
 use crate::pipeline::{
-    BackgroundRenderer, BasicSolidRenderer, Blitter, BlurRenderer, Compositor,
-    OverlaySolidRenderer, ScrimSolidRenderer, ScrimStencilMaskRenderer, ScrimStencilRenderer,
-    ShadowCompositeRenderer, SmaaRenderer, TextRenderer,
+    BackgroundRenderer, BasicSolidRenderer, Blitter, BlurRenderer, ClipStencilRenderer, Compositor,
+    FilterRenderer, FilterUniform, OverlaySolidRenderer, ScrimSolidRenderer,
+    ScrimStencilMaskRenderer, ScrimStencilRenderer, ShadowCompositeRenderer, SmaaRenderer,
+    TextRenderer,
+};
+use crate::scene::{
+    BackdropFilter, BlendMode, BoxShadowSpec, ClipTable, Filter, LayerMask, RoundedRadii,
+    RoundedRect,
 };
-use crate::scene::{BoxShadowSpec, RoundedRadii, RoundedRect};
 use crate::upload::GpuScene;
 
+/// Copy `target` into `backdrop`, as far as both extend.
+fn copy_backdrop(
+    encoder: &mut wgpu::CommandEncoder,
+    target: &wgpu::Texture,
+    backdrop: &crate::OwnedTexture,
+) {
+    encoder.copy_texture_to_texture(
+        target.as_image_copy(),
+        backdrop.texture.as_image_copy(),
+        wgpu::Extent3d {
+            width: backdrop.key.width.min(target.width()),
+            height: backdrop.key.height.min(target.height()),
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
 /// Apply a 2D affine transform to a point
 fn apply_transform_to_point(point: [f32; 2], transform: crate::Transform2D) -> [f32; 2] {
     let [a, b, c, d, e, f] = transform.m;
@@ -24,6 +47,160 @@ pub struct PassTargets {
     pub color: crate::OwnedTexture,
 }
 
+/// A glyph drawn by [`PassManager::render_unified`]:
+/// `(origin, glyph, color, z, clip)`.
+pub type GlyphDraw = (
+    [f32; 2],
+    crate::text::RasterizedGlyph,
+    crate::ColorLinPremul,
+    i32,
+    u32,
+);
+
+/// An SVG drawn by [`PassManager::render_unified`]:
+/// `(path, origin, max_size, style, z, transform, clip)`.
+pub type SvgDraw = (
+    std::path::PathBuf,
+    [f32; 2],
+    [f32; 2],
+    Option<crate::SvgStyle>,
+    i32,
+    crate::Transform2D,
+    u32,
+);
+
+/// An image drawn by [`PassManager::render_unified`]:
+/// `(path, origin, size, z, clip)`.
+pub type ImageDraw = (std::path::PathBuf, [f32; 2], [f32; 2], i32, u32);
+
+/// Where [`PassManager::render_unified`] draws.
+pub struct UnifiedTarget<'a> {
+    pub view: &'a wgpu::TextureView,
+    pub width: u32,
+    pub height: u32,
+    /// Color the target is cleared to.
+    pub clear: wgpu::Color,
+    /// Draw straight into `view` instead of compositing an offscreen target.
+    pub direct: bool,
+    /// Keep the target's pixels and depth instead of clearing them.
+    pub preserve_surface: bool,
+}
+
+/// What [`PassManager::render_unified`] draws: a scene, the side draws made
+/// with it, and the z-indices to draw.
+#[derive(Clone, Copy)]
+pub struct UnifiedDraws<'a> {
+    pub scene: &'a GpuScene,
+    pub glyph_draws: &'a [GlyphDraw],
+    pub svg_draws: &'a [SvgDraw],
+    pub image_draws: &'a [ImageDraw],
+    pub pattern_draws: &'a [crate::ExtractedPatternDraw],
+    /// Draws outside this range are skipped, to draw a scene in the slices
+    /// returned by [`scene_slices`].
+    pub z_range: (Bound<i32>, Bound<i32>),
+}
+
+impl<'a> UnifiedDraws<'a> {
+    /// All of `scene`, with no side draws.
+    pub fn scene(scene: &'a GpuScene) -> Self {
+        Self {
+            scene,
+            glyph_draws: &[],
+            svg_draws: &[],
+            image_draws: &[],
+            pattern_draws: &[],
+            z_range: (Bound::Unbounded, Bound::Unbounded),
+        }
+    }
+}
+
+/// Everything drawn inside one layer, for [`PassManager::render_layers`]:
+/// an [`ExtractedLayer`](crate::ExtractedLayer) with its text rasterized and
+/// any side draws the caller keeps for the layer.
+pub struct LayerDraws<'a> {
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    pub mask: Option<&'a LayerMask>,
+    /// Filters applied to the layer's content before compositing.
+    pub filters: &'a [Filter],
+    pub backdrop: Option<&'a BackdropFilter>,
+    pub z: i32,
+    pub scene: &'a GpuScene,
+    pub glyph_draws: Vec<GlyphDraw>,
+    pub svg_draws: Vec<SvgDraw>,
+    pub image_draws: Vec<ImageDraw>,
+    pub pattern_draws: Vec<crate::ExtractedPatternDraw>,
+    /// Layers nested in this one.
+    pub children: Vec<LayerDraws<'a>>,
+}
+
+/// Layer content rendered into a pooled texture, waiting to be composited
+/// by [`PassManager::composite_layers`].
+pub struct RenderedLayer {
+    color: crate::OwnedTexture,
+    mask: Option<crate::OwnedTexture>,
+    /// Copy of the target under the layer, for blend modes other than normal.
+    backdrop: Option<crate::OwnedTexture>,
+    /// Filtered copy of what lies under the layer's backdrop region.
+    backdrop_filter: Option<RenderedBackdrop>,
+    opacity: f32,
+    blend_mode: BlendMode,
+    z: i32,
+}
+
+/// Part of a scene drawn between the composites of the layers that land in
+/// it; see [`scene_slices`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SceneSlice {
+    /// Z-indices of the scene content drawn in the slice.
+    pub z: (Bound<i32>, Bound<i32>),
+    /// Layers composited over the slice before the next one is drawn.
+    pub layers: Range<usize>,
+}
+
+/// Split the scene `layers` land in into slices that draw it in paint
+/// order. `layers` must be sorted by z-index, as
+/// [`PassManager::render_layers`] returns them.
+///
+/// Each slice draws the scene content below the z-index of the next layers,
+/// which are then composited over it; the following slice draws from their
+/// z-index up. A scene without layers is a single slice.
+pub fn scene_slices(layers: &[RenderedLayer]) -> Vec<SceneSlice> {
+    slices_at(layers.iter().map(|layer| layer.z))
+}
+
+/// [`scene_slices`] for layers at z-indices `layer_z`.
+fn slices_at(layer_z: impl IntoIterator<Item = i32>) -> Vec<SceneSlice> {
+    let mut slices: Vec<SceneSlice> = Vec::new();
+    let mut lower = Bound::Unbounded;
+    let mut count = 0;
+    for z in layer_z {
+        match slices.last_mut() {
+            Some(slice) if slice.z.1 == Bound::Excluded(z) => slice.layers.end += 1,
+            _ => slices.push(SceneSlice {
+                z: (lower, Bound::Excluded(z)),
+                layers: count..count + 1,
+            }),
+        }
+        lower = Bound::Included(z);
+        count += 1;
+    }
+    slices.push(SceneSlice {
+        z: (lower, Bound::Unbounded),
+        layers: count..count,
+    });
+    slices
+}
+
+/// Textures for a layer's [`BackdropFilter`], filled when compositing.
+struct RenderedBackdrop {
+    /// Coverage of the backdrop's rounded rect.
+    region: crate::OwnedTexture,
+    /// The backdrop copy and two scratch textures to filter it in.
+    targets: [crate::OwnedTexture; 3],
+    filters: Vec<Filter>,
+}
+
 pub enum Background {
     Solid(crate::scene::ColorLinPremul),
     LinearGradient {
@@ -42,10 +219,15 @@ pub struct PassManager {
     overlay_solid: OverlaySolidRenderer,
     scrim_solid: ScrimSolidRenderer,
     pub compositor: Compositor,
+    filter: FilterRenderer,
     pub blitter: Blitter,
     pub smaa: SmaaRenderer,
     scrim_mask: ScrimStencilMaskRenderer,
     scrim_stencil: ScrimStencilRenderer,
+    clip_stencil: ClipStencilRenderer,
+    clip_stencil_offscreen: ClipStencilRenderer,
+    // Clip stacks referenced by the draws of the next render_unified
+    clip_geometry: Option<ClipGeometry>,
     // Shadow/blur pipelines and helpers
     pub mask_renderer: BasicSolidRenderer,
     pub blur_r8: BlurRenderer,
@@ -70,6 +252,8 @@ pub struct PassManager {
     ui_scale: f32,
     // When true, treat positions as logical pixels and scale by `scale_factor` centrally
     logical_pixels: bool,
+    // Device-pixel scissor [x, y, w, h] for the next direct render_unified
+    scissor: Option<[u32; 4]>,
     // Intermediate texture for Vello-style smooth resizing
     pub intermediate_texture: Option<crate::OwnedTexture>,
     smaa_edges: Option<crate::OwnedTexture>,
@@ -84,9 +268,11 @@ pub struct PassManager {
     #[allow(dead_code)]
     text_mask_atlas_view: wgpu::TextureView,
     text_bind_group: wgpu::BindGroup,
-    // Track atlas region used in previous frame for efficient clearing
-    prev_atlas_max_x: u32,
-    prev_atlas_max_y: u32,
+    // sRGB view of the atlas, for sampling icons copied into it; `None`
+    // where view formats are unsupported, leaving icons out of the atlas
+    atlas_srgb_view: Option<wgpu::TextureView>,
+    // Space in the atlas shared by glyph masks and small images
+    atlas: AtlasAllocator<AtlasKey>,
     smaa_param_buffer: wgpu::Buffer,
 }
 
@@ -97,6 +283,8 @@ struct TextQuadVtx {
     pos: [f32; 2],
     uv: [f32; 2],
     color: [f32; 4],
+    /// 1.0 for color glyph images, 0.0 for coverage masks.
+    color_glyph: f32,
 }
 
 #[repr(C)]
@@ -106,6 +294,154 @@ struct ImageQuadVtx {
     uv: [f32; 2],
 }
 
+/// Images and SVGs no larger than this (in texels on both axes) are copied
+/// into the atlas and batched.
+const ATLAS_ICON_MAX: u32 = 128;
+
+/// Most quads drawn from one 16-bit index buffer.
+const MAX_BATCH_QUADS: usize = 16384;
+
+/// Identity of an atlas entry.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum AtlasKey {
+    /// Glyph mask, by its [`RasterizedGlyph::key`](crate::text::RasterizedGlyph::key)
+    /// or, for unkeyed glyphs, a hash of its size and coverage.
+    Glyph(u64),
+    Image(std::path::PathBuf),
+    /// Rasterized SVG by path, pixel size and style hash.
+    Svg(std::path::PathBuf, u32, u32, u64),
+}
+
+impl AtlasKey {
+    /// Atlas key for `glyph`. Keyed glyphs cost nothing here; only unkeyed
+    /// ones hash their mask bytes.
+    fn glyph(glyph: &crate::text::RasterizedGlyph) -> Self {
+        if glyph.key != 0 {
+            return AtlasKey::Glyph(glyph.key);
+        }
+        let mask = &glyph.mask;
+        AtlasKey::Glyph(crate::content_hash(
+            0,
+            &(mask.width, mask.height, mask.bytes_per_pixel(), &mask.data),
+        ))
+    }
+}
+
+/// An image or SVG draw resolved to its texture.
+struct ImageSource {
+    tex: Arc<wgpu::Texture>,
+    key: AtlasKey,
+    origin: [f32; 2],
+    size: [f32; 2],
+    z: i32,
+    clip: u32,
+}
+
+/// Buffers for one batch of glyph quads sampling the atlas, kept alive for
+/// the unified render pass.
+struct TextGroup {
+    vbuf: wgpu::Buffer,
+    ibuf: wgpu::Buffer,
+    z_bg: wgpu::BindGroup,
+    _z_buf: wgpu::Buffer,
+    index_count: u32,
+    clip: u32,
+}
+
+/// Buffers and bind groups for one textured mesh drawn with the image
+/// pipeline (patterns, images, SVGs), kept alive for the unified render pass.
+struct MeshResources {
+    vbuf: wgpu::Buffer,
+    ibuf: wgpu::Buffer,
+    vp_bg: wgpu::BindGroup,
+    z_bg: wgpu::BindGroup,
+    tex_bg: wgpu::BindGroup,
+    _z_buf: wgpu::Buffer,
+    index_count: u32,
+    clip: u32,
+}
+
+/// Stencil triangles of the clip stacks set with [`PassManager::set_clips`].
+struct ClipGeometry {
+    vbuf: wgpu::Buffer,
+    ibuf: wgpu::Buffer,
+    /// Per clip id, the index range and base vertex of each shape in the
+    /// stack, outermost first.
+    stacks: Vec<Vec<(Range<u32>, i32)>>,
+}
+
+/// Create the 4096x4096 atlas shared by glyph masks and icons, plus the
+/// sRGB view icons are sampled through. Devices without view format support
+/// (GL) get no sRGB view.
+fn create_atlas_texture(device: &wgpu::Device) -> (wgpu::Texture, Option<wgpu::TextureView>) {
+    let create = |view_formats: &[wgpu::TextureFormat]| {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("text-mask-atlas"),
+            size: wgpu::Extent3d {
+                width: 4096,
+                height: 4096,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            // Use RGBA8 so we can store RGB subpixel coverage masks directly.
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats,
+        })
+    };
+    // The device offers no downlevel query, so try the sRGB view format and
+    // check for a validation error. Native error scopes resolve immediately.
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let atlas = create(&[wgpu::TextureFormat::Rgba8UnormSrgb]);
+    let error = std::pin::pin!(device.pop_error_scope())
+        .poll(&mut std::task::Context::from_waker(std::task::Waker::noop()));
+    if !matches!(error, std::task::Poll::Ready(None)) {
+        return (create(&[]), None);
+    }
+    let srgb_view = atlas.create_view(&wgpu::TextureViewDescriptor {
+        format: Some(wgpu::TextureFormat::Rgba8UnormSrgb),
+        ..Default::default()
+    });
+    (atlas, Some(srgb_view))
+}
+
+/// Indices for `quads` quads of four vertices each.
+fn quad_indices(quads: usize) -> Vec<u16> {
+    let mut indices = Vec::with_capacity(quads * 6);
+    for i in 0..quads {
+        let base = (i * 4) as u16;
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+    indices
+}
+
+/// Quad covering `size` at `origin`, sampling `[u0, v0, u1, v1]`.
+fn image_quad(origin: [f32; 2], size: [f32; 2], uv: [f32; 4]) -> [ImageQuadVtx; 4] {
+    let [u0, v0, u1, v1] = uv;
+    [
+        ImageQuadVtx {
+            pos: [origin[0], origin[1]],
+            uv: [u0, v0],
+        },
+        ImageQuadVtx {
+            pos: [origin[0] + size[0], origin[1]],
+            uv: [u1, v0],
+        },
+        ImageQuadVtx {
+            pos: [origin[0] + size[0], origin[1] + size[1]],
+            uv: [u1, v1],
+        },
+        ImageQuadVtx {
+            pos: [origin[0], origin[1] + size[1]],
+            uv: [u0, v1],
+        },
+    ]
+}
+
 impl PassManager {
     /// Choose the best offscreen format based on scene color space.
     ///
@@ -166,10 +502,13 @@ impl PassManager {
         let overlay_solid = OverlaySolidRenderer::new(device.clone(), target_format);
         let scrim_solid = ScrimSolidRenderer::new(device.clone(), target_format);
         let compositor = Compositor::new(device.clone(), target_format);
+        let filter = FilterRenderer::new(device.clone(), target_format);
         let blitter = Blitter::new(device.clone(), target_format);
         let smaa = SmaaRenderer::new(device.clone(), target_format);
         let scrim_mask = ScrimStencilMaskRenderer::new(device.clone(), target_format);
         let scrim_stencil = ScrimStencilRenderer::new(device.clone(), target_format);
+        let clip_stencil = ClipStencilRenderer::new(device.clone(), target_format);
+        let clip_stencil_offscreen = ClipStencilRenderer::new(device.clone(), offscreen_format);
         // Shadow/blur pipelines
         let mask_renderer =
             BasicSolidRenderer::new(device.clone(), wgpu::TextureFormat::R8Unorm, 1);
@@ -214,23 +553,7 @@ impl PassManager {
             mapped_at_creation: false,
         });
         // Text pipeline GPU resources
-        let text_mask_atlas = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("text-mask-atlas"),
-            size: wgpu::Extent3d {
-                width: 4096,
-                height: 4096,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            // Use RGBA8 so we can store RGB subpixel coverage masks directly.
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING
-                | wgpu::TextureUsages::COPY_DST
-                | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        });
+        let (text_mask_atlas, atlas_srgb_view) = create_atlas_texture(&device);
         let text_mask_atlas_view =
             text_mask_atlas.create_view(&wgpu::TextureViewDescriptor::default());
         let text_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -258,10 +581,14 @@ impl PassManager {
             overlay_solid,
             scrim_solid,
             compositor,
+            filter,
             blitter,
             smaa,
             scrim_mask,
             scrim_stencil,
+            clip_stencil,
+            clip_stencil_offscreen,
+            clip_geometry: None,
             mask_renderer,
             blur_r8,
             shadow_comp,
@@ -281,6 +608,7 @@ impl PassManager {
             scale_factor: 1.0,
             ui_scale,
             logical_pixels: logical_default,
+            scissor: None,
             intermediate_texture: None,
             smaa_edges: None,
             smaa_weights: None,
@@ -288,8 +616,8 @@ impl PassManager {
             text_mask_atlas,
             text_mask_atlas_view,
             text_bind_group,
-            prev_atlas_max_x: 0,
-            prev_atlas_max_y: 0,
+            atlas_srgb_view,
+            atlas: AtlasAllocator::new(4096, 4096),
             smaa_param_buffer,
             scrim_stencil_tex: None,
         }
@@ -430,7 +758,7 @@ impl PassManager {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth32Float,
+            format: wgpu::TextureFormat::Depth24PlusStencil8,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             view_formats: &[],
         });
@@ -500,6 +828,432 @@ impl PassManager {
         Some((view, w, h))
     }
 
+    /// Load pattern textures and upload their meshes for the unified pass.
+    /// Patterns whose image fails to load are dropped.
+    fn prepare_pattern_resources(
+        &mut self,
+        pattern_draws: &[crate::ExtractedPatternDraw],
+        z_range: &(Bound<i32>, Bound<i32>),
+        offscreen: bool,
+        queue: &wgpu::Queue,
+    ) -> Vec<MeshResources> {
+        let mut out = Vec::new();
+        for draw in pattern_draws {
+            if draw.indices.is_empty() || !z_range.contains(&draw.z) {
+                continue;
+            }
+            let Some((tex_view, _, _)) = self.load_image_to_view(&draw.path, queue) else {
+                continue;
+            };
+            // Keep the index buffer a multiple of COPY_BUFFER_ALIGNMENT.
+            let mut indices = draw.indices.clone();
+            if indices.len() % 2 != 0 {
+                indices.push(0);
+            }
+            let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("pattern-vbuf-unified"),
+                size: std::mem::size_of_val(draw.vertices.as_slice()) as u64,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("pattern-ibuf-unified"),
+                size: std::mem::size_of_val(indices.as_slice()) as u64,
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&draw.vertices));
+            queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&indices));
+
+            let renderer = if offscreen {
+                &self.image_offscreen
+            } else {
+                &self.image
+            };
+            let vp_bg = renderer.vp_bind_group(&self.device, &self.vp_buffer);
+            let tex_bg = renderer.pattern_tex_bind_group(&self.device, &tex_view);
+            let (z_bg, z_buf) = self.create_group_z_bind_group(draw.z as f32, queue);
+            out.push(MeshResources {
+                vbuf,
+                ibuf,
+                vp_bg,
+                z_bg,
+                tex_bg,
+                _z_buf: z_buf,
+                index_count: draw.indices.len() as u32,
+                clip: draw.clip,
+            });
+        }
+        out
+    }
+
+    /// Write tightly packed RGBA8 `texels` covering `region` of the atlas.
+    fn write_atlas(&self, queue: &wgpu::Queue, region: AtlasRegion, texels: &[u8]) {
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.text_mask_atlas,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: region.x,
+                    y: region.y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            texels,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(region.width * 4),
+                rows_per_image: Some(region.height),
+            },
+            wgpu::Extent3d {
+                width: region.width,
+                height: region.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Atlas region holding `glyph`'s mask, uploading it (with a cleared
+    /// padding border) on a miss. `None` if the atlas has no room left this
+    /// frame.
+    fn glyph_region(
+        &mut self,
+        glyph: &crate::text::RasterizedGlyph,
+        queue: &wgpu::Queue,
+    ) -> Option<AtlasRegion> {
+        let mask = &glyph.mask;
+        let key = AtlasKey::glyph(glyph);
+        if let Some(region) = self.atlas.get(&key) {
+            return Some(region);
+        }
+        let region = self.atlas.allocate(key, mask.width, mask.height)?;
+        let padded = region.padded();
+        let bpp = mask.bytes_per_pixel();
+        let mut texels = vec![0u8; (padded.width * padded.height * 4) as usize];
+        for y in 0..mask.height as usize {
+            for x in 0..mask.width as usize {
+                let src = (y * mask.width as usize + x) * bpp;
+                let dst = ((y + 1) * padded.width as usize + x + 1) * 4;
+                for c in 0..4 {
+                    // 16-bit masks keep the high byte of each little-endian channel.
+                    texels[dst + c] = mask.data[src + c * (bpp / 4) + bpp / 4 - 1];
+                }
+            }
+        }
+        self.write_atlas(queue, padded, &texels);
+        Some(region)
+    }
+
+    /// Place glyphs in the atlas and build their quads, batched per clip and
+    /// z-index and sorted back to front. Glyphs the atlas can't fit are
+    /// skipped.
+    fn prepare_text_groups(
+        &mut self,
+        glyph_draws: &[GlyphDraw],
+        z_range: &(Bound<i32>, Bound<i32>),
+        queue: &wgpu::Queue,
+    ) -> Vec<TextGroup> {
+        let mut by_z: std::collections::BTreeMap<(u32, i32), Vec<TextQuadVtx>> =
+            std::collections::BTreeMap::new();
+        for (origin, glyph, color, z, clip) in glyph_draws {
+            let mask = &glyph.mask;
+            if mask.width == 0 || mask.height == 0 || !z_range.contains(z) {
+                continue;
+            }
+            let Some(region) = self.glyph_region(glyph, queue) else {
+                continue;
+            };
+            let [u0, v0, u1, v1] = region.uv(self.atlas.size());
+            let (w, h) = (mask.width as f32, mask.height as f32);
+            let color = [color.r, color.g, color.b, color.a];
+            let color_glyph = if mask.is_color() { 1.0 } else { 0.0 };
+            by_z.entry((*clip, *z)).or_default().extend_from_slice(&[
+                TextQuadVtx {
+                    pos: [origin[0], origin[1]],
+                    uv: [u0, v0],
+                    color,
+                    color_glyph,
+                },
+                TextQuadVtx {
+                    pos: [origin[0] + w, origin[1]],
+                    uv: [u1, v0],
+                    color,
+                    color_glyph,
+                },
+                TextQuadVtx {
+                    pos: [origin[0] + w, origin[1] + h],
+                    uv: [u1, v1],
+                    color,
+                    color_glyph,
+                },
+                TextQuadVtx {
+                    pos: [origin[0], origin[1] + h],
+                    uv: [u0, v1],
+                    color,
+                    color_glyph,
+                },
+            ]);
+        }
+
+        let mut groups = Vec::new();
+        for ((clip, z), vertices) in by_z {
+            for vertices in vertices.chunks(MAX_BATCH_QUADS * 4) {
+                let indices = quad_indices(vertices.len() / 4);
+                let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("text-vertex-buffer-group"),
+                    size: std::mem::size_of_val(vertices) as u64,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("text-index-buffer-group"),
+                    size: std::mem::size_of_val(indices.as_slice()) as u64,
+                    usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(vertices));
+                queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&indices));
+                // Pass z_index as float directly - shader will convert to depth
+                let (z_bg, z_buf) = self.create_group_z_bind_group(z as f32, queue);
+                groups.push(TextGroup {
+                    vbuf,
+                    ibuf,
+                    z_bg,
+                    _z_buf: z_buf,
+                    index_count: indices.len() as u32,
+                    clip,
+                });
+            }
+        }
+        groups
+    }
+
+    /// Resolve image and SVG draws to their (lazily loaded or rasterized)
+    /// textures, images first. Draws that fail to load are dropped.
+    fn collect_image_sources(
+        &mut self,
+        image_draws: &[ImageDraw],
+        svg_draws: &[SvgDraw],
+        z_range: &(Bound<i32>, Bound<i32>),
+        queue: &wgpu::Queue,
+    ) -> Vec<ImageSource> {
+        let mut out = Vec::new();
+        for (path, origin, size, z, clip) in image_draws {
+            if !z_range.contains(z) {
+                continue;
+            }
+            let loaded = self
+                .image_cache
+                .get(path)
+                .or_else(|| self.image_cache.get_or_load(path, queue));
+            if let Some((tex, _, _)) = loaded {
+                out.push(ImageSource {
+                    tex,
+                    key: AtlasKey::Image(path.clone()),
+                    origin: *origin,
+                    size: *size,
+                    z: *z,
+                    clip: *clip,
+                });
+            }
+        }
+        for (path, origin, max_size, style, z, transform, clip) in svg_draws {
+            if !z_range.contains(z) {
+                continue;
+            }
+            let style = style.unwrap_or_default();
+            let Some((_, w, h)) = self.svg_cache.get_or_rasterize(path, 1.0, style, queue) else {
+                continue;
+            };
+            let scale = (max_size[0] / w.max(1) as f32)
+                .min(max_size[1] / h.max(1) as f32)
+                .max(0.0);
+            let Some((tex, sw, sh)) = self.svg_cache.get_or_rasterize(path, scale, style, queue)
+            else {
+                continue;
+            };
+            out.push(ImageSource {
+                tex,
+                key: AtlasKey::Svg(path.clone(), sw, sh, crate::content_hash(0, &style)),
+                // Apply transform to origin for correct positioning
+                origin: apply_transform_to_point(*origin, *transform),
+                size: [sw as f32, sh as f32],
+                z: *z,
+                clip: *clip,
+            });
+        }
+        out
+    }
+
+    /// Atlas region holding a small image source, copying it in on a miss.
+    /// `None` for sources that are too large, can't be copied, or don't fit.
+    fn icon_region(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &ImageSource,
+        queue: &wgpu::Queue,
+    ) -> Option<AtlasRegion> {
+        let tex = &source.tex;
+        let (w, h) = (tex.width(), tex.height());
+        if self.atlas_srgb_view.is_none()
+            || w > ATLAS_ICON_MAX
+            || h > ATLAS_ICON_MAX
+            || !tex.usage().contains(wgpu::TextureUsages::COPY_SRC)
+            || tex.format().remove_srgb_suffix() != wgpu::TextureFormat::Rgba8Unorm
+        {
+            return None;
+        }
+        if let Some(region) = self.atlas.get(&source.key) {
+            return Some(region);
+        }
+        let region = self.atlas.allocate(source.key.clone(), w, h)?;
+        // Queue writes land before the encoder's copies, so clear the
+        // padding border first and copy the texels over it.
+        let padded = region.padded();
+        self.write_atlas(
+            queue,
+            padded,
+            &vec![0u8; (padded.width * padded.height * 4) as usize],
+        );
+        encoder.copy_texture_to_texture(
+            tex.as_image_copy(),
+            wgpu::ImageCopyTexture {
+                texture: &self.text_mask_atlas,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: region.x,
+                    y: region.y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width: w,
+                height: h,
+                depth_or_array_layers: 1,
+            },
+        );
+        Some(region)
+    }
+
+    /// Upload one mesh of image quads sampling `tex_view`.
+    fn upload_image_mesh(
+        &self,
+        vertices: &[ImageQuadVtx],
+        tex_view: &wgpu::TextureView,
+        z: i32,
+        clip: u32,
+        offscreen: bool,
+        queue: &wgpu::Queue,
+    ) -> MeshResources {
+        let indices = quad_indices(vertices.len() / 4);
+        let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("image-vbuf-unified"),
+            size: std::mem::size_of_val(vertices) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("image-ibuf-unified"),
+            size: std::mem::size_of_val(indices.as_slice()) as u64,
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(vertices));
+        queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&indices));
+
+        let renderer = if offscreen {
+            &self.image_offscreen
+        } else {
+            &self.image
+        };
+        let vp_bg = renderer.vp_bind_group(&self.device, &self.vp_buffer);
+        let tex_bg = renderer.tex_bind_group(&self.device, tex_view);
+        // Pass z_index as float directly - shader will convert to depth
+        let (z_bg, z_buf) = self.create_group_z_bind_group(z as f32, queue);
+        MeshResources {
+            vbuf,
+            ibuf,
+            vp_bg,
+            z_bg,
+            tex_bg,
+            _z_buf: z_buf,
+            index_count: indices.len() as u32,
+            clip,
+        }
+    }
+
+    /// Upload a batch of quads sampling icons in the atlas.
+    fn upload_icon_batch(
+        &self,
+        vertices: &[ImageQuadVtx],
+        z: i32,
+        clip: u32,
+        offscreen: bool,
+        queue: &wgpu::Queue,
+    ) -> MeshResources {
+        let view = self
+            .atlas_srgb_view
+            .as_ref()
+            .expect("icons are only placed in an atlas with an sRGB view");
+        self.upload_image_mesh(vertices, view, z, clip, offscreen, queue)
+    }
+
+    /// Build meshes for image and SVG draws, sorted by clip and z-index.
+    /// Small sources are drawn from the atlas, batching consecutive ones with
+    /// the same clip and z-index into one mesh; the rest get a quad of their
+    /// own.
+    fn prepare_image_meshes(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        mut sources: Vec<ImageSource>,
+        offscreen: bool,
+        queue: &wgpu::Queue,
+    ) -> Vec<MeshResources> {
+        // Stable, so draws with equal z keep their order.
+        sources.sort_by_key(|s| (s.clip, s.z));
+        let mut out = Vec::new();
+        let mut batch: Vec<ImageQuadVtx> = Vec::new();
+        let (mut batch_clip, mut batch_z) = (0, 0);
+        for source in &sources {
+            let region = self.icon_region(encoder, source, queue);
+            if !batch.is_empty()
+                && (region.is_none()
+                    || (source.clip, source.z) != (batch_clip, batch_z)
+                    || batch.len() >= MAX_BATCH_QUADS * 4)
+            {
+                out.push(self.upload_icon_batch(&batch, batch_z, batch_clip, offscreen, queue));
+                batch.clear();
+            }
+            let uv = match region {
+                Some(region) => region.uv(self.atlas.size()),
+                None => [0.0, 0.0, 1.0, 1.0],
+            };
+            let quad = image_quad(source.origin, source.size, uv);
+            if region.is_some() {
+                batch.extend_from_slice(&quad);
+                (batch_clip, batch_z) = (source.clip, source.z);
+            } else {
+                let view = source
+                    .tex
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+                out.push(self.upload_image_mesh(
+                    &quad,
+                    &view,
+                    source.z,
+                    source.clip,
+                    offscreen,
+                    queue,
+                ));
+            }
+        }
+        if !batch.is_empty() {
+            out.push(self.upload_icon_batch(&batch, batch_z, batch_clip, offscreen, queue));
+        }
+        out
+    }
+
     /// Try to get an image from cache without blocking. Returns None if not ready.
     pub fn try_get_image_view(
         &mut self,
@@ -528,15 +1282,18 @@ impl PassManager {
         width: u32,
         height: u32,
     ) {
+        self.atlas.remove(&AtlasKey::Image(path.to_path_buf()));
         self.image_cache.store_ready(path, tex, width, height);
     }
 
     /// Get a cached texture directly (for updating pixel data in-place).
-    /// Returns the Arc<Texture> and dimensions if found.
+    /// Returns the Arc<Texture> and dimensions if found. Its atlas copy, if
+    /// any, is dropped so the next draw picks up the new pixels.
     pub fn get_cached_texture(
         &mut self,
         path: &std::path::Path,
     ) -> Option<(Arc<wgpu::Texture>, u32, u32)> {
+        self.atlas.remove(&AtlasKey::Image(path.to_path_buf()));
         self.image_cache.get(path)
     }
 
@@ -561,6 +1318,65 @@ impl PassManager {
         self.logical_pixels = on;
     }
 
+    /// Scale from display list coordinates to device pixels.
+    pub fn logical_multiplier(&self) -> f32 {
+        crate::dpi::logical_multiplier(self.logical_pixels, self.scale_factor, self.ui_scale)
+    }
+
+    /// Limit the next direct `render_unified` to the device-pixel rectangle
+    /// `[x, y, w, h]` and keep the target's pixels outside it, for partial
+    /// redraws. The offscreen path ignores it.
+    pub fn set_scissor(&mut self, rect: Option<[u32; 4]>) {
+        self.scissor = rect;
+    }
+
+    /// Clip stacks that the `clip` ids of scenes and draws passed to
+    /// `render_unified` index, usually [`crate::UnifiedSceneData::clips`].
+    /// Kept until the next call; ids without a stack draw unclipped.
+    pub fn set_clips(&mut self, clips: &ClipTable, queue: &wgpu::Queue) {
+        let mut vertices = Vec::new();
+        let mut indices: Vec<u16> = Vec::new();
+        let stacks = clips
+            .iter()
+            .map(|stack| {
+                stack
+                    .iter()
+                    .map(|clip| {
+                        let (shape_vertices, shape_indices) = crate::upload::clip_triangles(clip);
+                        let base_vertex = vertices.len() as i32;
+                        let start = indices.len() as u32;
+                        vertices.extend(shape_vertices);
+                        indices.extend(shape_indices);
+                        (start..indices.len() as u32, base_vertex)
+                    })
+                    .collect()
+            })
+            .collect();
+        // Keep the index buffer a multiple of COPY_BUFFER_ALIGNMENT.
+        if !indices.len().is_multiple_of(2) {
+            indices.push(0);
+        }
+        let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("clip-vbuf"),
+            size: (std::mem::size_of_val(vertices.as_slice()) as u64).max(4),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("clip-ibuf"),
+            size: (std::mem::size_of_val(indices.as_slice()) as u64).max(4),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        if !vertices.is_empty() {
+            queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&vertices));
+        }
+        if !indices.is_empty() {
+            queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&indices));
+        }
+        self.clip_geometry = Some(ClipGeometry { vbuf, ibuf, stacks });
+    }
+
     pub fn alloc_targets(
         &self,
         allocator: &mut RenderAllocator,
@@ -697,7 +1513,7 @@ impl PassManager {
             let tex = allocator.allocate_texture(TexKey {
                 width,
                 height,
-                format: wgpu::TextureFormat::Depth32Float,
+                format: wgpu::TextureFormat::Depth24PlusStencil8,
                 usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             });
             self.depth_texture = Some(tex);
@@ -921,12 +1737,7 @@ impl PassManager {
         // debug log removed
         queue.write_buffer(&self.vp_buffer, 0, bytemuck::bytes_of(&vp_data));
 
-        let shadow_radii = RoundedRadii {
-            tl: (rrect.radii.tl + spread).max(0.0),
-            tr: (rrect.radii.tr + spread).max(0.0),
-            br: (rrect.radii.br + spread).max(0.0),
-            bl: (rrect.radii.bl + spread).max(0.0),
-        };
+        let shadow_radii = rrect.radii.inset([-spread; 4]);
         // Expand source to give blur room so the outer halo is broad enough.
         // Slightly higher multiplier works better with the wider blur support above.
         let expand = spread + 1.8 * sigma + 1.0;
@@ -950,87 +1761,8 @@ impl PassManager {
         let mut vertices: Vec<Vtx> = Vec::new();
         let mut indices: Vec<u16> = Vec::new();
         let rect = expanded.rect;
-        let tl = expanded.radii.tl.min(rect.w * 0.5).min(rect.h * 0.5);
-        let tr = expanded.radii.tr.min(rect.w * 0.5).min(rect.h * 0.5);
-        let br = expanded.radii.br.min(rect.w * 0.5).min(rect.h * 0.5);
-        let bl = expanded.radii.bl.min(rect.w * 0.5).min(rect.h * 0.5);
-        // Higher tessellation for smoother rounded corners (reduces polygonal artifacts before blur)
         let segs = 64u32;
-        let mut ring: Vec<[f32; 2]> = Vec::new();
-        fn arc_append(
-            ring: &mut Vec<[f32; 2]>,
-            c: [f32; 2],
-            r: f32,
-            start: f32,
-            end: f32,
-            segs: u32,
-            include_start: bool,
-        ) {
-            if r <= 0.0 {
-                return;
-            }
-            for i in 0..=segs {
-                if i == 0 && !include_start {
-                    continue;
-                }
-                let t = (i as f32) / (segs as f32);
-                let ang = start + t * (end - start);
-                let p = [c[0] + r * ang.cos(), c[1] - r * ang.sin()];
-                ring.push(p);
-            }
-        }
-        if tl > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + tl, rect.y + tl],
-                tl,
-                std::f32::consts::FRAC_PI_2,
-                std::f32::consts::PI,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + 0.0, rect.y + 0.0]);
-        }
-        if bl > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + bl, rect.y + rect.h - bl],
-                bl,
-                std::f32::consts::PI,
-                std::f32::consts::FRAC_PI_2 * 3.0,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + 0.0, rect.y + rect.h]);
-        }
-        if br > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + rect.w - br, rect.y + rect.h - br],
-                br,
-                std::f32::consts::FRAC_PI_2 * 3.0,
-                std::f32::consts::TAU,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + rect.w, rect.y + rect.h]);
-        }
-        if tr > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + rect.w - tr, rect.y + tr],
-                tr,
-                0.0,
-                std::f32::consts::FRAC_PI_2,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + rect.w, rect.y + 0.0]);
-        }
+        let ring = expanded.outline(segs);
         let center = [rect.x + rect.w * 0.5, rect.y + rect.h * 0.5];
         let white = [1.0, 1.0, 1.0, 1.0];
         let base = vertices.len() as u16;
@@ -1089,6 +1821,7 @@ impl PassManager {
             },
             vertices: vertices.len() as u32,
             indices: indices.len() as u32,
+            z_starts: Vec::new(),
         };
 
         // Bind groups for viewport
@@ -1195,63 +1928,7 @@ impl PassManager {
             let mut cutout_indices: Vec<u16> = Vec::new();
             // Use ORIGINAL rect (no spread/offset) in full target space
             let rect = rrect.rect;
-            let tl = rrect.radii.tl.min(rect.w * 0.5).min(rect.h * 0.5);
-            let tr = rrect.radii.tr.min(rect.w * 0.5).min(rect.h * 0.5);
-            let br = rrect.radii.br.min(rect.w * 0.5).min(rect.h * 0.5);
-            let bl = rrect.radii.bl.min(rect.w * 0.5).min(rect.h * 0.5);
-            let mut ring: Vec<[f32; 2]> = Vec::new();
-            if tl > 0.0 {
-                arc_append(
-                    &mut ring,
-                    [rect.x + tl, rect.y + tl],
-                    tl,
-                    std::f32::consts::FRAC_PI_2,
-                    std::f32::consts::PI,
-                    segs,
-                    true,
-                );
-            } else {
-                ring.push([rect.x, rect.y]);
-            }
-            if bl > 0.0 {
-                arc_append(
-                    &mut ring,
-                    [rect.x + bl, rect.y + rect.h - bl],
-                    bl,
-                    std::f32::consts::PI,
-                    std::f32::consts::FRAC_PI_2 * 3.0,
-                    segs,
-                    true,
-                );
-            } else {
-                ring.push([rect.x, rect.y + rect.h]);
-            }
-            if br > 0.0 {
-                arc_append(
-                    &mut ring,
-                    [rect.x + rect.w - br, rect.y + rect.h - br],
-                    br,
-                    std::f32::consts::FRAC_PI_2 * 3.0,
-                    std::f32::consts::TAU,
-                    segs,
-                    true,
-                );
-            } else {
-                ring.push([rect.x + rect.w, rect.y + rect.h]);
-            }
-            if tr > 0.0 {
-                arc_append(
-                    &mut ring,
-                    [rect.x + rect.w - tr, rect.y + tr],
-                    tr,
-                    0.0,
-                    std::f32::consts::FRAC_PI_2,
-                    segs,
-                    true,
-                );
-            } else {
-                ring.push([rect.x + rect.w, rect.y]);
-            }
+            let ring = rrect.outline(segs);
             let center = [rect.x + rect.w * 0.5, rect.y + rect.h * 0.5];
             // Use transparent (alpha=0) to clear the mask area
             // With premultiplied alpha: result = src * src.a + dst * (1 - src.a) = 0 * 0 + dst * 1 = dst
@@ -1314,6 +1991,7 @@ impl PassManager {
                 },
                 vertices: cutout_vertices.len() as u32,
                 indices: cutout_indices.len() as u32,
+                z_starts: Vec::new(),
             };
 
             let _z_bg_cutout = self.create_z_bind_group(0.0, queue);
@@ -1652,86 +2330,8 @@ impl PassManager {
         let mut vertices: Vec<Vtx> = Vec::new();
         let mut indices: Vec<u16> = Vec::new();
         let rect = hole.rect;
-        let tl = hole.radii.tl.min(rect.w * 0.5).min(rect.h * 0.5);
-        let tr = hole.radii.tr.min(rect.w * 0.5).min(rect.h * 0.5);
-        let br = hole.radii.br.min(rect.w * 0.5).min(rect.h * 0.5);
-        let bl = hole.radii.bl.min(rect.w * 0.5).min(rect.h * 0.5);
         let segs = 32u32;
-        let mut ring: Vec<[f32; 2]> = Vec::new();
-        fn arc_append(
-            ring: &mut Vec<[f32; 2]>,
-            c: [f32; 2],
-            r: f32,
-            start: f32,
-            end: f32,
-            segs: u32,
-            include_start: bool,
-        ) {
-            if r <= 0.0 {
-                return;
-            }
-            for i in 0..=segs {
-                if i == 0 && !include_start {
-                    continue;
-                }
-                let t = (i as f32) / (segs as f32);
-                let ang = start + t * (end - start);
-                let p = [c[0] + r * ang.cos(), c[1] - r * ang.sin()];
-                ring.push(p);
-            }
-        }
-        if tl > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + tl, rect.y + tl],
-                tl,
-                std::f32::consts::FRAC_PI_2,
-                std::f32::consts::PI,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + 0.0, rect.y + 0.0]);
-        }
-        if bl > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + bl, rect.y + rect.h - bl],
-                bl,
-                std::f32::consts::PI,
-                std::f32::consts::FRAC_PI_2 * 3.0,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + 0.0, rect.y + rect.h]);
-        }
-        if br > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + rect.w - br, rect.y + rect.h - br],
-                br,
-                std::f32::consts::FRAC_PI_2 * 3.0,
-                std::f32::consts::TAU,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + rect.w, rect.y + rect.h]);
-        }
-        if tr > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + rect.w - tr, rect.y + tr],
-                tr,
-                0.0,
-                std::f32::consts::FRAC_PI_2,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + rect.w, rect.y + 0.0]);
-        }
+        let ring = hole.outline(segs);
 
         // Triangulate fan
         let center = [rect.x + rect.w * 0.5, rect.y + rect.h * 0.5];
@@ -1934,86 +2534,8 @@ impl PassManager {
         let mut vertices: Vec<Vtx> = Vec::new();
         let mut indices: Vec<u16> = Vec::new();
         let rect = rrect.rect;
-        let tl = rrect.radii.tl.min(rect.w * 0.5).min(rect.h * 0.5);
-        let tr = rrect.radii.tr.min(rect.w * 0.5).min(rect.h * 0.5);
-        let br = rrect.radii.br.min(rect.w * 0.5).min(rect.h * 0.5);
-        let bl = rrect.radii.bl.min(rect.w * 0.5).min(rect.h * 0.5);
         let segs = 64u32;
-        let mut ring: Vec<[f32; 2]> = Vec::new();
-        fn arc_append(
-            ring: &mut Vec<[f32; 2]>,
-            c: [f32; 2],
-            r: f32,
-            start: f32,
-            end: f32,
-            segs: u32,
-            include_start: bool,
-        ) {
-            if r <= 0.0 {
-                return;
-            }
-            for i in 0..=segs {
-                if i == 0 && !include_start {
-                    continue;
-                }
-                let t = (i as f32) / (segs as f32);
-                let ang = start + t * (end - start);
-                let p = [c[0] + r * ang.cos(), c[1] - r * ang.sin()];
-                ring.push(p);
-            }
-        }
-        if tl > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + tl, rect.y + tl],
-                tl,
-                std::f32::consts::FRAC_PI_2,
-                std::f32::consts::PI,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + 0.0, rect.y + 0.0]);
-        }
-        if bl > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + bl, rect.y + rect.h - bl],
-                bl,
-                std::f32::consts::PI,
-                std::f32::consts::FRAC_PI_2 * 3.0,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + 0.0, rect.y + rect.h]);
-        }
-        if br > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + rect.w - br, rect.y + rect.h - br],
-                br,
-                std::f32::consts::FRAC_PI_2 * 3.0,
-                std::f32::consts::TAU,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + rect.w, rect.y + rect.h]);
-        }
-        if tr > 0.0 {
-            arc_append(
-                &mut ring,
-                [rect.x + rect.w - tr, rect.y + tr],
-                tr,
-                0.0,
-                std::f32::consts::FRAC_PI_2,
-                segs,
-                true,
-            );
-        } else {
-            ring.push([rect.x + rect.w, rect.y + 0.0]);
-        }
+        let ring = rrect.outline(segs);
         let center = [rect.x + rect.w * 0.5, rect.y + rect.h * 0.5];
         let col = [color.r, color.g, color.b, color.a];
         let base = vertices.len() as u16;
@@ -2073,6 +2595,7 @@ impl PassManager {
             },
             vertices: vertices.len() as u32,
             indices: indices.len() as u32,
+            z_starts: Vec::new(),
         };
 
         // Bind viewport
@@ -2097,7 +2620,11 @@ impl PassManager {
                     load: wgpu::LoadOp::Load, // Preserve existing depth
                     store: wgpu::StoreOp::Store,
                 }),
-                stencil_ops: None,
+                // Unclipped: the solid pipeline draws where the stencil is 0
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: wgpu::StoreOp::Store,
+                }),
             }
         });
 
@@ -2138,7 +2665,7 @@ impl PassManager {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth32Float,
+            format: wgpu::TextureFormat::Depth24PlusStencil8,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             view_formats: &[],
         });
@@ -2199,6 +2726,428 @@ impl PassManager {
         self.compositor.record(&mut pass, &bg);
     }
 
+    /// Render layer content into pooled textures, ready to be composited over
+    /// the scene they belong to with [`PassManager::composite_layers`].
+    /// The layers come back sorted by the z-index they are composited at.
+    ///
+    /// Each layer (with its nested layers composited in) and each mask is
+    /// rendered and submitted on its own encoder, since they share the depth
+    /// buffer and glyph atlas with the main pass. Call this before rendering
+    /// the scene the layers land in, and hand the result to
+    /// [`PassManager::release_layers`] once that frame has been submitted.
+    pub fn render_layers(
+        &mut self,
+        allocator: &mut RenderAllocator,
+        width: u32,
+        height: u32,
+        layers: &[LayerDraws],
+        queue: &wgpu::Queue,
+    ) -> Vec<RenderedLayer> {
+        if layers.is_empty() {
+            return Vec::new();
+        }
+        // Keep the scene's depth buffer out of reach of the layer passes.
+        let scene_depth = self.depth_texture.take();
+        let mut rendered: Vec<RenderedLayer> = layers
+            .iter()
+            .map(|layer| self.render_layer(allocator, width, height, layer, queue))
+            .collect();
+        rendered.sort_by_key(|layer| layer.z);
+        if let Some(layer_depth) = std::mem::replace(&mut self.depth_texture, scene_depth) {
+            allocator.release_texture(layer_depth);
+        }
+        rendered
+    }
+
+    fn render_layer(
+        &mut self,
+        allocator: &mut RenderAllocator,
+        width: u32,
+        height: u32,
+        layer: &LayerDraws,
+        queue: &wgpu::Queue,
+    ) -> RenderedLayer {
+        let mut children: Vec<RenderedLayer> = layer
+            .children
+            .iter()
+            .map(|child| self.render_layer(allocator, width, height, child, queue))
+            .collect();
+        children.sort_by_key(|child| child.z);
+
+        let mut color = self.alloc_layer_texture(allocator, width, height);
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("layer-encoder"),
+            });
+        for (i, slice) in scene_slices(&children).into_iter().enumerate() {
+            let target = UnifiedTarget {
+                view: &color.view,
+                width,
+                height,
+                clear: wgpu::Color::TRANSPARENT,
+                direct: true,
+                preserve_surface: i > 0,
+            };
+            let draws = UnifiedDraws {
+                scene: layer.scene,
+                glyph_draws: &layer.glyph_draws,
+                svg_draws: &layer.svg_draws,
+                image_draws: &layer.image_draws,
+                pattern_draws: &layer.pattern_draws,
+                z_range: slice.z,
+            };
+            self.render_unified(&mut encoder, allocator, target, draws, queue);
+            self.composite_layers(&mut encoder, &color.texture, &children[slice.layers], queue);
+        }
+        if layer.filters.is_empty() {
+            queue.submit(std::iter::once(encoder.finish()));
+        } else {
+            let mut scratch = [
+                self.alloc_layer_texture(allocator, width, height),
+                self.alloc_layer_texture(allocator, width, height),
+            ];
+            let textures = [&color, &scratch[0], &scratch[1]];
+            let result = self.apply_filters(&mut encoder, textures, layer.filters, false, queue);
+            queue.submit(std::iter::once(encoder.finish()));
+            if result > 0 {
+                std::mem::swap(&mut color, &mut scratch[result - 1]);
+            }
+            for texture in scratch {
+                allocator.release_texture(texture);
+            }
+        }
+        self.release_layers(allocator, children);
+
+        let mask = layer.mask.and_then(|mask| {
+            let rect = RoundedRect {
+                rect: mask.rect,
+                radii: RoundedRadii::default(),
+            }
+            .to_path();
+            self.render_layer_mask(
+                allocator,
+                width,
+                height,
+                rect,
+                mask.brush.clone(),
+                mask.transform,
+                queue,
+            )
+        });
+        let backdrop = (layer.blend_mode != BlendMode::Normal)
+            .then(|| self.alloc_layer_texture(allocator, width, height));
+        let backdrop_filter = layer.backdrop.and_then(|backdrop| {
+            let region = self.render_layer_mask(
+                allocator,
+                width,
+                height,
+                backdrop.rrect.to_path(),
+                crate::Brush::Solid(crate::ColorLinPremul::from_srgba_u8([255, 255, 255, 255])),
+                backdrop.transform,
+                queue,
+            )?;
+            Some(RenderedBackdrop {
+                region,
+                targets: [
+                    self.alloc_layer_texture(allocator, width, height),
+                    self.alloc_layer_texture(allocator, width, height),
+                    self.alloc_layer_texture(allocator, width, height),
+                ],
+                filters: backdrop.filters.clone(),
+            })
+        });
+        RenderedLayer {
+            color,
+            mask,
+            backdrop,
+            backdrop_filter,
+            opacity: layer.opacity,
+            blend_mode: layer.blend_mode,
+            z: layer.z,
+        }
+    }
+
+    /// Fill `path` with `brush` into a fresh texture, for layer masks and
+    /// backdrop regions; only its alpha is used.
+    #[allow(clippy::too_many_arguments)]
+    fn render_layer_mask(
+        &mut self,
+        allocator: &mut RenderAllocator,
+        width: u32,
+        height: u32,
+        path: crate::Path,
+        brush: crate::Brush,
+        transform: crate::Transform2D,
+        queue: &wgpu::Queue,
+    ) -> Option<crate::OwnedTexture> {
+        let list = crate::DisplayList {
+            viewport: crate::Viewport { width, height },
+            commands: vec![crate::Command::FillPath {
+                path,
+                brush,
+                z: 0,
+                transform,
+            }],
+        };
+        let scene = crate::upload_display_list_unified(allocator, queue, &list).ok()?;
+        let tex = self.alloc_layer_texture(allocator, width, height);
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("layer-mask-encoder"),
+            });
+        let target = UnifiedTarget {
+            view: &tex.view,
+            width,
+            height,
+            clear: wgpu::Color::TRANSPARENT,
+            direct: true,
+            preserve_surface: false,
+        };
+        let draws = UnifiedDraws {
+            pattern_draws: &scene.pattern_draws,
+            ..UnifiedDraws::scene(&scene.gpu_scene)
+        };
+        self.render_unified(&mut encoder, allocator, target, draws, queue);
+        queue.submit(std::iter::once(encoder.finish()));
+        Some(tex)
+    }
+
+    fn alloc_layer_texture(
+        &self,
+        allocator: &mut RenderAllocator,
+        width: u32,
+        height: u32,
+    ) -> crate::OwnedTexture {
+        allocator.allocate_texture(TexKey {
+            width: width.max(1),
+            height: height.max(1),
+            format: self.surface_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
+        })
+    }
+
+    /// Composite rendered layers over `target`, in order. Draw the scene
+    /// they land in slice by slice (see [`scene_slices`]) and composite each
+    /// slice's layers right after it, so the scene content below a layer is
+    /// in `target` and the content above it is drawn over it afterwards.
+    ///
+    /// `target` must have the surface format and the size the layers were
+    /// rendered at. Blend modes other than normal and backdrop filters copy
+    /// the backdrop out of `target` and need it to allow `COPY_SRC`; without
+    /// it blend modes fall back to normal blending and backdrop filters are
+    /// skipped.
+    pub fn composite_layers(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::Texture,
+        layers: &[RenderedLayer],
+        queue: &wgpu::Queue,
+    ) {
+        if layers.is_empty() {
+            return;
+        }
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let can_copy = target.usage().contains(wgpu::TextureUsages::COPY_SRC);
+        for layer in layers {
+            if let Some(filter) = layer.backdrop_filter.as_ref().filter(|_| can_copy) {
+                copy_backdrop(encoder, target, &filter.targets[0]);
+                let [out, a, b] = &filter.targets;
+                let result = self.apply_filters(encoder, [out, a, b], &filter.filters, true, queue);
+                let filtered = &filter.targets[result].view;
+                let uniform = crate::pipeline::LayerUniform {
+                    opacity: 1.0,
+                    mode: 0,
+                    has_mask: 1,
+                    _pad: 0,
+                };
+                let textures = [filtered, &filter.region.view, filtered];
+                self.composite_layer(encoder, &target_view, textures, uniform, queue);
+            }
+            let backdrop = layer.backdrop.as_ref().filter(|_| can_copy);
+            if let Some(backdrop) = backdrop {
+                copy_backdrop(encoder, target, backdrop);
+            }
+            let uniform = crate::pipeline::LayerUniform {
+                opacity: layer.opacity,
+                mode: if backdrop.is_some() {
+                    layer.blend_mode.shader_index()
+                } else {
+                    0
+                },
+                has_mask: layer.mask.is_some() as u32,
+                _pad: 0,
+            };
+            let textures = [
+                &layer.color.view,
+                layer.mask.as_ref().map_or(&layer.color.view, |m| &m.view),
+                backdrop.map_or(&layer.color.view, |b| &b.view),
+            ];
+            self.composite_layer(encoder, &target_view, textures, uniform, queue);
+        }
+    }
+
+    /// One layer composite pass; `textures` are the layer, mask and backdrop
+    /// bindings of `LAYER_COMPOSITE_WGSL`.
+    fn composite_layer(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target_view: &wgpu::TextureView,
+        textures: [&wgpu::TextureView; 3],
+        uniform: crate::pipeline::LayerUniform,
+        queue: &wgpu::Queue,
+    ) {
+        let ubuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("layer-composite-uniform"),
+            size: std::mem::size_of::<crate::pipeline::LayerUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(&ubuf, 0, bytemuck::bytes_of(&uniform));
+        let [layer, mask, backdrop] = textures;
+        let bg = self
+            .compositor
+            .layer_bind_group(&self.device, layer, mask, backdrop, &ubuf);
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("layer-composite-pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        self.compositor
+            .record_layer(&mut pass, &bg, uniform.mode != 0);
+    }
+
+    /// Apply `filters` in order to `textures[0]`, ping-ponging through the
+    /// other two, and return the index of the texture holding the result.
+    /// `clamp_edges` makes blurs read edge pixels instead of transparency
+    /// past the texture's borders, as backdrops need.
+    fn apply_filters(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        textures: [&crate::OwnedTexture; 3],
+        filters: &[Filter],
+        clamp_edges: bool,
+        queue: &wgpu::Queue,
+    ) -> usize {
+        let scale =
+            crate::dpi::logical_multiplier(self.logical_pixels, self.scale_factor, self.ui_scale);
+        let mut cur = 0;
+        for filter in filters {
+            let (t1, t2) = ((cur + 1) % 3, (cur + 2) % 3);
+            let [src, a, b] = [textures[cur], textures[t1], textures[t2]].map(|t| &t.view);
+            match filter {
+                Filter::Blur { radius } => {
+                    let sigma = radius * scale;
+                    if sigma <= 0.0 {
+                        continue;
+                    }
+                    let h = FilterUniform::blur(sigma, [1.0, 0.0], clamp_edges);
+                    let v = FilterUniform::blur(sigma, [0.0, 1.0], clamp_edges);
+                    self.filter_pass(encoder, src, a, h, false, queue);
+                    self.filter_pass(encoder, a, src, v, false, queue);
+                }
+                Filter::ColorMatrix(m) => {
+                    let uniform = FilterUniform::color_matrix(m);
+                    self.filter_pass(encoder, src, a, uniform, false, queue);
+                    cur = t1;
+                }
+                Filter::DropShadow {
+                    offset,
+                    blur,
+                    color,
+                } => {
+                    let tint = [color.r, color.g, color.b, color.a];
+                    let offset = [offset[0] * scale, offset[1] * scale];
+                    let shadow = FilterUniform::shadow(tint, offset);
+                    self.filter_pass(encoder, src, a, shadow, false, queue);
+                    let sigma = blur * 0.5 * scale;
+                    if sigma > 0.0 {
+                        let h = FilterUniform::blur(sigma, [1.0, 0.0], false);
+                        let v = FilterUniform::blur(sigma, [0.0, 1.0], false);
+                        self.filter_pass(encoder, a, b, h, false, queue);
+                        self.filter_pass(encoder, b, a, v, false, queue);
+                    }
+                    self.filter_pass(encoder, src, a, FilterUniform::copy(), true, queue);
+                    cur = t1;
+                }
+            }
+        }
+        cur
+    }
+
+    /// Draw `src` through the filter shader into `dst`, replacing it or, with
+    /// `over`, blending over it.
+    fn filter_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        src: &wgpu::TextureView,
+        dst: &wgpu::TextureView,
+        uniform: FilterUniform,
+        over: bool,
+        queue: &wgpu::Queue,
+    ) {
+        let ubuf = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("filter-uniform"),
+            size: std::mem::size_of::<FilterUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(&ubuf, 0, bytemuck::bytes_of(&uniform));
+        let bg = self.filter.bind_group(&self.device, src, &ubuf);
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("filter-pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: dst,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: if over {
+                        wgpu::LoadOp::Load
+                    } else {
+                        wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+                    },
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        self.filter.record(&mut pass, &bg, over);
+    }
+
+    /// Return layer textures to the pool once the frame compositing them
+    /// has been submitted.
+    pub fn release_layers(&self, allocator: &mut RenderAllocator, layers: Vec<RenderedLayer>) {
+        for layer in layers {
+            allocator.release_texture(layer.color);
+            if let Some(mask) = layer.mask {
+                allocator.release_texture(mask);
+            }
+            if let Some(backdrop) = layer.backdrop {
+                allocator.release_texture(backdrop);
+            }
+            if let Some(filter) = layer.backdrop_filter {
+                allocator.release_texture(filter.region);
+                for target in filter.targets {
+                    allocator.release_texture(target);
+                }
+            }
+        }
+    }
+
     /// Paint background to intermediate texture instead of directly to surface.
     /// This enables smooth resizing when combined with blit_to_surface.
     pub fn paint_root_to_intermediate(
@@ -2604,15 +3553,59 @@ impl PassManager {
         self.bg.record(&mut pass, &bg_bind);
     }
 
-    /// Convenience: paint a solid background color directly to the surface.
-    pub fn paint_root_color(
+    /// Paint a conic gradient to the intermediate texture.
+    #[allow(clippy::too_many_arguments)]
+    pub fn paint_root_conic_gradient_multi_to_intermediate(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        center_uv: [f32; 2],
+        angle: f32,
+        stops_in: &[(f32, crate::scene::ColorLinPremul)],
+        spread: crate::scene::SpreadMode,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+    ) {
+        let intermediate = self
+            .intermediate_texture
+            .as_ref()
+            .expect("intermediate texture must be allocated before painting");
+        self.paint_root_conic_gradient_multi(
+            encoder,
+            &intermediate.view,
+            center_uv,
+            angle,
+            stops_in,
+            spread,
+            queue,
+            width,
+            height,
+        );
+    }
+
+    /// Multi-stop conic gradient background sweeping clockwise around
+    /// `center_uv` from `angle` radians off the positive x axis. Stop
+    /// offsets are fractions of a turn; `spread` repeats them over their
+    /// span.
+    #[allow(clippy::too_many_arguments)]
+    pub fn paint_root_conic_gradient_multi(
         &self,
         encoder: &mut wgpu::CommandEncoder,
         surface_view: &wgpu::TextureView,
-        color: crate::scene::ColorLinPremul,
+        center_uv: [f32; 2],
+        angle: f32,
+        stops_in: &[(f32, crate::scene::ColorLinPremul)],
+        spread: crate::scene::SpreadMode,
         queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
     ) {
-        // Draw solid via the background fullscreen shader to avoid sRGB clear vs blit inconsistencies.
+        let mut sorted: Vec<(f32, crate::scene::ColorLinPremul)> = stops_in
+            .iter()
+            .map(|(p, c)| (p.clamp(0.0, 1.0), *c))
+            .collect();
+        sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        let count = sorted.len().clamp(2, 8) as u32;
         #[repr(C)]
         #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
         struct BgParams {
@@ -2627,21 +3620,34 @@ impl PassManager {
             _pad0: [f32; 3],
             color: [f32; 4],
         }
-        let params = BgParams {
-            start_end: [0.0, 0.0, 1.0, 1.0],
-            center_radius_stop: [0.5, 0.5, 1.0, 1.0],
-            flags: [0.0, 0.0, 0.0, 0.0], // mode = 0 => solid
-        };
-        let stops: [Stop; 1] = [Stop {
+        let mut stops: [Stop; 8] = [Stop {
             pos: 0.0,
             _pad0: [0.0; 3],
-            color: [color.r, color.g, color.b, color.a],
-        }];
-        // Write uniforms (only first stop used for solid mode)
+            color: [0.0; 4],
+        }; 8];
+        for (i, (p, c)) in sorted.iter().take(8).enumerate() {
+            stops[i] = Stop {
+                pos: *p,
+                _pad0: [0.0; 3],
+                color: [c.r, c.g, c.b, c.a],
+            };
+        }
+        // Matches SPREAD_* in GRADIENT_WGSL.
+        let spread = match spread {
+            crate::scene::SpreadMode::Pad => 0.0,
+            crate::scene::SpreadMode::Repeat => 1.0,
+            crate::scene::SpreadMode::Reflect => 2.0,
+        };
+        let aspect_ratio = (width.max(1) as f32) / (height.max(1) as f32);
+        let params = BgParams {
+            start_end: [angle, 0.0, 0.0, 0.0],
+            center_radius_stop: [center_uv[0], center_uv[1], 1.0, count as f32],
+            flags: [3.0, 0.0, aspect_ratio, spread],
+        };
         queue.write_buffer(&self.bg_param_buffer, 0, bytemuck::bytes_of(&params));
         queue.write_buffer(&self.bg_stops_buffer, 0, bytemuck::cast_slice(&stops));
         let bg_bind = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("bg-bind-solid"),
+            label: Some("bg-bind-conic"),
             layout: self.bg.bgl(),
             entries: &[
                 wgpu::BindGroupEntry {
@@ -2655,7 +3661,7 @@ impl PassManager {
             ],
         });
         let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("bg-solid-pass"),
+            label: Some("bg-conic-pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: surface_view,
                 resolve_target: None,
@@ -2671,32 +3677,99 @@ impl PassManager {
         self.bg.record(&mut pass, &bg_bind);
     }
 
-    /// Convenience: paint a simple 2-stop linear gradient to the surface.
-    pub fn paint_root_gradient(
+    /// Convenience: paint a solid background color directly to the surface.
+    pub fn paint_root_color(
         &self,
         encoder: &mut wgpu::CommandEncoder,
         surface_view: &wgpu::TextureView,
-        start_uv: [f32; 2],
-        end_uv: [f32; 2],
-        stop0: (f32, crate::scene::ColorLinPremul),
-        stop1: (f32, crate::scene::ColorLinPremul),
+        color: crate::scene::ColorLinPremul,
         queue: &wgpu::Queue,
     ) {
+        // Draw solid via the background fullscreen shader to avoid sRGB clear vs blit inconsistencies.
         #[repr(C)]
         #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
-        struct BgData {
+        struct BgParams {
             start_end: [f32; 4],
             center_radius_stop: [f32; 4],
             flags: [f32; 4],
         }
-        let c0 = stop0.1;
-        let c1 = stop1.1;
-        // Reuse the multi-stop layout by writing two stops into the stops buffer
-        let debug_flag = std::env::var("DEBUG_RADIAL")
-            .ok()
-            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-            .unwrap_or(false);
-        let params = BgData {
+        #[repr(C)]
+        #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+        struct Stop {
+            pos: f32,
+            _pad0: [f32; 3],
+            color: [f32; 4],
+        }
+        let params = BgParams {
+            start_end: [0.0, 0.0, 1.0, 1.0],
+            center_radius_stop: [0.5, 0.5, 1.0, 1.0],
+            flags: [0.0, 0.0, 0.0, 0.0], // mode = 0 => solid
+        };
+        let stops: [Stop; 1] = [Stop {
+            pos: 0.0,
+            _pad0: [0.0; 3],
+            color: [color.r, color.g, color.b, color.a],
+        }];
+        // Write uniforms (only first stop used for solid mode)
+        queue.write_buffer(&self.bg_param_buffer, 0, bytemuck::bytes_of(&params));
+        queue.write_buffer(&self.bg_stops_buffer, 0, bytemuck::cast_slice(&stops));
+        let bg_bind = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("bg-bind-solid"),
+            layout: self.bg.bgl(),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.bg_param_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.bg_stops_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("bg-solid-pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: surface_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        self.bg.record(&mut pass, &bg_bind);
+    }
+
+    /// Convenience: paint a simple 2-stop linear gradient to the surface.
+    pub fn paint_root_gradient(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        surface_view: &wgpu::TextureView,
+        start_uv: [f32; 2],
+        end_uv: [f32; 2],
+        stop0: (f32, crate::scene::ColorLinPremul),
+        stop1: (f32, crate::scene::ColorLinPremul),
+        queue: &wgpu::Queue,
+    ) {
+        #[repr(C)]
+        #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+        struct BgData {
+            start_end: [f32; 4],
+            center_radius_stop: [f32; 4],
+            flags: [f32; 4],
+        }
+        let c0 = stop0.1;
+        let c1 = stop1.1;
+        // Reuse the multi-stop layout by writing two stops into the stops buffer
+        let debug_flag = std::env::var("DEBUG_RADIAL")
+            .ok()
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let params = BgData {
             start_end: [start_uv[0], start_uv[1], end_uv[0], end_uv[1]],
             center_radius_stop: [0.5, 0.5, 1.0, 2.0],
             flags: [1.0, if debug_flag { 1.0 } else { 0.0 }, 0.0, 0.0],
@@ -2754,39 +3827,140 @@ impl PassManager {
         self.bg.record(&mut pass, &bg_bind);
     }
 
+    /// Record the solids, glyphs and textured meshes of a unified pass. The
+    /// unclipped ones come first, then each clip's, drawn where the stencil
+    /// written for its stack lets them through. `vp_bgs` are the viewport
+    /// bind groups of the solid, text and clip stencil pipelines.
+    #[allow(clippy::too_many_arguments)]
+    fn record_unified<'a>(
+        &'a self,
+        pass: &mut wgpu::RenderPass<'a>,
+        offscreen: bool,
+        vp_bgs: [&'a wgpu::BindGroup; 3],
+        scene: &'a GpuScene,
+        text_groups: &'a [TextGroup],
+        meshes: &'a [MeshResources],
+        z_range: (Bound<i32>, Bound<i32>),
+    ) {
+        let [vp_bg, vp_bg_text, vp_bg_clip] = vp_bgs;
+        let (solid, text, image, clip_stencil) = if offscreen {
+            (
+                &self.solid_offscreen,
+                &self.text_offscreen,
+                &self.image_offscreen,
+                &self.clip_stencil_offscreen,
+            )
+        } else {
+            (
+                &self.solid_direct,
+                &self.text,
+                &self.image,
+                &self.clip_stencil,
+            )
+        };
+        let mut clips: Vec<u32> = std::iter::once(0)
+            .chain(scene.z_starts.iter().map(|&(clip, _, _)| clip))
+            .chain(text_groups.iter().map(|group| group.clip))
+            .chain(meshes.iter().map(|mesh| mesh.clip))
+            .collect();
+        clips.sort_unstable();
+        clips.dedup();
+
+        for clip in clips {
+            let stack = self.clip_geometry.as_ref().and_then(|geometry| {
+                let shapes = geometry.stacks.get(clip as usize)?;
+                Some((geometry, shapes.as_slice()))
+            });
+            // Leave the stencil at the stack's depth inside all its shapes.
+            if let Some((geometry, shapes)) = stack {
+                for (depth, (indices, base_vertex)) in shapes.iter().enumerate() {
+                    clip_stencil.record_intersect(
+                        pass,
+                        vp_bg_clip,
+                        &geometry.vbuf,
+                        &geometry.ibuf,
+                        indices.clone(),
+                        *base_vertex,
+                        depth as u32,
+                    );
+                }
+            }
+            pass.set_stencil_reference(stack.map_or(0, |(_, shapes)| shapes.len() as u32));
+
+            // Solids are already sorted by clip and z-index in the scene
+            solid.record_range(pass, vp_bg, scene, scene.index_range(clip, z_range));
+
+            // Text glyphs, sorted by z-index
+            for group in text_groups.iter().filter(|group| group.clip == clip) {
+                pass.set_pipeline(&text.pipeline);
+                pass.set_bind_group(0, vp_bg_text, &[]);
+                pass.set_bind_group(1, &group.z_bg, &[]);
+                pass.set_bind_group(2, &self.text_bind_group, &[]);
+                pass.set_vertex_buffer(0, group.vbuf.slice(..));
+                pass.set_index_buffer(group.ibuf.slice(..), wgpu::IndexFormat::Uint16);
+                pass.draw_indexed(0..group.index_count, 0, 0..1);
+            }
+
+            // Image-pattern paths, images and SVGs with the image pipeline
+            for res in meshes.iter().filter(|mesh| mesh.clip == clip) {
+                image.record(
+                    pass,
+                    &res.vp_bg,
+                    &res.z_bg,
+                    &res.tex_bg,
+                    &res.vbuf,
+                    &res.ibuf,
+                    res.index_count,
+                );
+            }
+
+            // Everything the stack let through lies inside its outermost shape.
+            if let Some((geometry, [(indices, base_vertex), ..])) = stack {
+                clip_stencil.record_reset(
+                    pass,
+                    vp_bg_clip,
+                    &geometry.vbuf,
+                    &geometry.ibuf,
+                    indices.clone(),
+                    *base_vertex,
+                );
+            }
+        }
+    }
+
     /// Unified rendering: Render all draw types (solids, text, images, SVGs) in a single pass
     /// with proper z-ordering. This is Phase 3 of the depth buffer implementation.
     ///
     /// This method interleaves all draw calls based on z-index for optimal z-ordering performance.
     /// Draw calls are batched by material type when possible for efficiency.
+    ///
+    /// Draws are clipped to the stack their `clip` id indexes in the table
+    /// passed to [`PassManager::set_clips`], through the stencil buffer.
     pub fn render_unified(
         &mut self,
         encoder: &mut wgpu::CommandEncoder,
         allocator: &mut RenderAllocator,
-        surface_view: &wgpu::TextureView,
-        width: u32,
-        height: u32,
-        scene: &GpuScene,
-        glyph_draws: &[(
-            [f32; 2],
-            crate::text::RasterizedGlyph,
-            crate::ColorLinPremul,
-            i32,
-        )], // (origin, glyph, color, z)
-        svg_draws: &[(
-            std::path::PathBuf,
-            [f32; 2],
-            [f32; 2],
-            Option<crate::SvgStyle>,
-            i32,
-            crate::Transform2D,
-        )],
-        image_draws: &[(std::path::PathBuf, [f32; 2], [f32; 2], i32)], // (path, origin, size, z)
-        clear: wgpu::Color,
-        direct: bool,
+        target: UnifiedTarget,
+        draws: UnifiedDraws,
         queue: &wgpu::Queue,
-        preserve_surface: bool,
     ) {
+        let UnifiedTarget {
+            view: surface_view,
+            width,
+            height,
+            clear,
+            direct,
+            preserve_surface,
+        } = target;
+        let UnifiedDraws {
+            scene,
+            glyph_draws,
+            svg_draws,
+            image_draws,
+            pattern_draws,
+            z_range,
+        } = draws;
+
         // Update viewport uniform
         let logical =
             crate::dpi::logical_multiplier(self.logical_pixels, self.scale_factor, self.ui_scale);
@@ -2798,6 +3972,17 @@ impl PassManager {
         let vp_data = [scale[0], scale[1], translate[0], translate[1]];
         let data = bytemuck::bytes_of(&vp_data);
         queue.write_buffer(&self.vp_buffer, 0, data);
+        let scissor = self.scissor.take().and_then(|[x, y, w, h]| {
+            let (x, y) = (x.min(width), y.min(height));
+            let (w, h) = (w.min(width - x), h.min(height - y));
+            (w > 0 && h > 0).then_some([x, y, w, h])
+        });
+
+        // Atlas entries used by earlier frames may now be evicted. Later
+        // slices of a scene belong to the frame its first slice started.
+        if z_range.0 == Bound::Unbounded {
+            self.atlas.begin_frame();
+        }
 
         // Ensure depth buffer matches current render size (1x sample)
         self.ensure_depth_texture(allocator, width.max(1), height.max(1));
@@ -2824,370 +4009,24 @@ impl PassManager {
             // Create z-index bind group before render pass (must outlive the pass)
             let _z_bg = self.create_z_bind_group(0.0, queue);
 
-            // Pre-fetch (and lazily load) all image views before render pass (to avoid mutable borrow conflicts)
-            let mut image_views: Vec<(wgpu::TextureView, [f32; 2], [f32; 2], f32)> = Vec::new();
-            for (path, origin, size, z) in image_draws.iter() {
-                let tex_opt =
-                    if let Some(view) = self.try_get_image_view(std::path::Path::new(path)) {
-                        Some(view)
-                    } else {
-                        self.load_image_to_view(std::path::Path::new(path), queue)
-                    };
-                if let Some((tex_view, _w, _h)) = tex_opt {
-                    image_views.push((tex_view, *origin, *size, *z as f32));
-                }
-            }
-
-            // Pre-rasterize all SVGs before render pass (to avoid mutable borrow conflicts)
-            let mut svg_views: Vec<(wgpu::TextureView, [f32; 2], [f32; 2], f32)> = Vec::new();
-            for (path, origin, max_size, style, _z, transform) in svg_draws.iter() {
-                if let Some((_view, w, h)) =
-                    self.rasterize_svg_to_view(std::path::Path::new(path), 1.0, *style, queue)
-                {
-                    let base_w = w.max(1) as f32;
-                    let base_h = h.max(1) as f32;
-                    let scale = (max_size[0] / base_w).min(max_size[1] / base_h).max(0.0);
-
-                    if let Some((view_scaled, sw, sh)) =
-                        self.rasterize_svg_to_view(std::path::Path::new(path), scale, *style, queue)
-                    {
-                        // Apply transform to origin for correct positioning
-                        let transformed_origin = apply_transform_to_point(*origin, *transform);
-                        svg_views.push((
-                            view_scaled,
-                            transformed_origin,
-                            [sw as f32, sh as f32],
-                            *_z as f32,
-                        ));
-                    }
-                }
-            }
-
-            // Group text by z-index for proper depth rendering
-            // eprintln!("🎨 render_unified received {} glyph_draws", glyph_draws.len());
-            let mut text_by_z: std::collections::HashMap<
-                i32,
-                Vec<(
-                    usize,
-                    [f32; 2],
-                    &crate::text::RasterizedGlyph,
-                    &crate::ColorLinPremul,
-                )>,
-            > = std::collections::HashMap::new();
-            for (idx, (origin, glyph, color, z)) in glyph_draws.iter().enumerate() {
-                text_by_z
-                    .entry(*z)
-                    .or_insert_with(Vec::new)
-                    .push((idx, *origin, glyph, color));
-            }
-            // eprintln!("🎨 Grouped text into {} z-index groups", text_by_z.len());
-
-            // Prepare text rendering data before render pass
-            let mut text_groups = if !glyph_draws.is_empty() {
-                // Clear the atlas region used in the previous frame (efficient partial clear)
-                if self.prev_atlas_max_x > 0 && self.prev_atlas_max_y > 0 {
-                    let clear_width = self.prev_atlas_max_x.min(4096);
-                    let clear_height = self.prev_atlas_max_y.min(4096);
-                    let clear_size = (clear_width * clear_height * 4) as usize;
-                    let clear_data = vec![0u8; clear_size];
-                    queue.write_texture(
-                        wgpu::ImageCopyTexture {
-                            texture: &self.text_mask_atlas,
-                            mip_level: 0,
-                            origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
-                            aspect: wgpu::TextureAspect::All,
-                        },
-                        &clear_data,
-                        wgpu::ImageDataLayout {
-                            offset: 0,
-                            bytes_per_row: Some(clear_width * 4),
-                            rows_per_image: Some(clear_height),
-                        },
-                        wgpu::Extent3d {
-                            width: clear_width,
-                            height: clear_height,
-                            depth_or_array_layers: 1,
-                        },
-                    );
-                }
-
-                let mut atlas_cursor_x = 0u32;
-                let mut atlas_cursor_y = 0u32;
-                let mut next_row_height = 0u32;
-                let mut atlas_max_x = 0u32;
-                let mut atlas_max_y = 0u32;
-                let mut all_text_groups: Vec<(i32, Vec<TextQuadVtx>)> = Vec::new();
-
-                // Process each z-index group
-                for (z_index, glyphs) in text_by_z.iter() {
-                    let mut vertices: Vec<TextQuadVtx> = Vec::new();
-                    // eprintln!("      🔠 Processing z={} with {} glyphs", z_index, glyphs.len());
-
-                    let mut local_idx = 0;
-                    for (_idx, origin, glyph, color) in glyphs.iter() {
-                        let mask = &glyph.mask;
-                        let w = mask.width;
-                        let h = mask.height;
-                        if local_idx == 0 {
-                            // eprintln!("        🔤 First glyph: origin=[{:.1}, {:.1}], size=[{}, {}], color=[{:.3}, {:.3}, {:.3}, {:.3}]",
-                            //     origin[0], origin[1], w, h, color.r, color.g, color.b, color.a);
-                        }
-                        local_idx += 1;
-
-                        if atlas_cursor_x + w >= 4096 {
-                            atlas_cursor_x = 0;
-                            atlas_cursor_y += next_row_height;
-                            next_row_height = 0;
-                        }
-                        next_row_height = next_row_height.max(h);
-
-                        // Track maximum atlas region used for clearing next frame
-                        atlas_max_x = atlas_max_x.max(atlas_cursor_x + w);
-                        atlas_max_y = atlas_max_y.max(atlas_cursor_y + h);
-
-                        queue.write_texture(
-                            wgpu::ImageCopyTexture {
-                                texture: &self.text_mask_atlas,
-                                mip_level: 0,
-                                origin: wgpu::Origin3d {
-                                    x: atlas_cursor_x,
-                                    y: atlas_cursor_y,
-                                    z: 0,
-                                },
-                                aspect: wgpu::TextureAspect::All,
-                            },
-                            &mask.data,
-                            wgpu::ImageDataLayout {
-                                offset: 0,
-                                bytes_per_row: Some(w * mask.bytes_per_pixel() as u32),
-                                rows_per_image: Some(h),
-                            },
-                            wgpu::Extent3d {
-                                width: w,
-                                height: h,
-                                depth_or_array_layers: 1,
-                            },
-                        );
-
-                        let u0 = atlas_cursor_x as f32 / 4096.0;
-                        let v0 = atlas_cursor_y as f32 / 4096.0;
-                        let u1 = (atlas_cursor_x + w) as f32 / 4096.0;
-                        let v1 = (atlas_cursor_y + h) as f32 / 4096.0;
-
-                        if local_idx == 1 {
-                            // eprintln!("        📐 Atlas pos: cursor=({}, {}), uv=[{:.4}, {:.4}] to [{:.4}, {:.4}]",
-                            //     atlas_cursor_x, atlas_cursor_y, u0, v0, u1, v1);
-                        }
-
-                        vertices.extend_from_slice(&[
-                            TextQuadVtx {
-                                pos: [origin[0], origin[1]],
-                                uv: [u0, v0],
-                                color: [color.r, color.g, color.b, color.a],
-                            },
-                            TextQuadVtx {
-                                pos: [origin[0] + w as f32, origin[1]],
-                                uv: [u1, v0],
-                                color: [color.r, color.g, color.b, color.a],
-                            },
-                            TextQuadVtx {
-                                pos: [origin[0] + w as f32, origin[1] + h as f32],
-                                uv: [u1, v1],
-                                color: [color.r, color.g, color.b, color.a],
-                            },
-                            TextQuadVtx {
-                                pos: [origin[0], origin[1] + h as f32],
-                                uv: [u0, v1],
-                                color: [color.r, color.g, color.b, color.a],
-                            },
-                        ]);
-
-                        atlas_cursor_x += w;
-                    }
-
-                    // Store vertices for this z-index group
-                    if !vertices.is_empty() {
-                        all_text_groups.push((*z_index, vertices));
-                    }
-                }
-
-                // Create buffers and bind groups for each text group
-                // eprintln!("🔧 all_text_groups.len() = {}", all_text_groups.len());
-                let mut text_resources: Vec<(
-                    i32,
-                    wgpu::Buffer,
-                    wgpu::Buffer,
-                    u32,
-                    wgpu::BindGroup,
-                    wgpu::Buffer,
-                )> = Vec::new();
-                for (z_index, vertices) in all_text_groups {
-                    // eprintln!(
-                    //     "  🛠️  Creating resources for z={}, vertices={}",
-                    //     z_index,
-                    //     vertices.len()
-                    // );
-                    let quad_count = vertices.len() / 4;
-                    let mut indices: Vec<u16> = Vec::with_capacity(quad_count * 6);
-                    for i in 0..quad_count {
-                        let base = (i * 4) as u16;
-                        indices.extend_from_slice(&[
-                            base,
-                            base + 1,
-                            base + 2,
-                            base,
-                            base + 2,
-                            base + 3,
-                        ]);
-                    }
-
-                    // Create vertex buffer for this group
-                    let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                        label: Some("text-vertex-buffer-group"),
-                        size: (vertices.len() * std::mem::size_of::<TextQuadVtx>()) as u64,
-                        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                        mapped_at_creation: false,
-                    });
-
-                    // Create index buffer for this group
-                    let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                        label: Some("text-index-buffer-group"),
-                        size: (indices.len() * std::mem::size_of::<u16>()) as u64,
-                        usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-                        mapped_at_creation: false,
-                    });
-
-                    queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&vertices));
-                    queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&indices));
-
-                    // Create z bind group for this text group
-                    // Pass z_index as float directly - shader will convert to depth
-                    // eprintln!("    💎 z={} (passing as z-index to shader)", z_index);
-                    let (z_bg, z_buf) = self.create_group_z_bind_group(z_index as f32, queue);
-
-                    text_resources.push((z_index, vbuf, ibuf, indices.len() as u32, z_bg, z_buf));
-                }
-
-                // Store atlas usage for next frame's clearing
-                self.prev_atlas_max_x = atlas_max_x;
-                self.prev_atlas_max_y = atlas_max_y;
-
-                text_resources
-            } else {
-                Vec::new()
-            };
-
-            // Sort text groups by z-index (back to front)
-            text_groups.sort_by_key(|(z, _, _, _, _, _)| *z);
+            // Load image and SVG textures and place glyphs in the atlas before the render pass
+            let image_sources = self.collect_image_sources(image_draws, svg_draws, &z_range, queue);
+            let text_groups = self.prepare_text_groups(glyph_draws, &z_range, queue);
 
             // Create text bind groups before render pass so they live long enough
             let vp_bg_text = self.text.vp_bind_group(&self.device, &self.vp_buffer);
+            let vp_bg_clip = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("vp-bg-clip"),
+                layout: self.clip_stencil.viewport_bgl(),
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.vp_buffer.as_entire_binding(),
+                }],
+            });
 
-            // Prepare image resources (collect all buffers and bind groups so they live long enough)
-            let mut image_resources: Vec<(
-                wgpu::Buffer,
-                wgpu::Buffer,
-                wgpu::BindGroup,
-                wgpu::BindGroup,
-                wgpu::BindGroup,
-                wgpu::Buffer,
-            )> = Vec::new();
-            for (tex_view, origin, size, z_val) in image_views.iter() {
-                let verts = [
-                    ImageQuadVtx {
-                        pos: [origin[0], origin[1]],
-                        uv: [0.0, 0.0],
-                    },
-                    ImageQuadVtx {
-                        pos: [origin[0] + size[0], origin[1]],
-                        uv: [1.0, 0.0],
-                    },
-                    ImageQuadVtx {
-                        pos: [origin[0] + size[0], origin[1] + size[1]],
-                        uv: [1.0, 1.0],
-                    },
-                    ImageQuadVtx {
-                        pos: [origin[0], origin[1] + size[1]],
-                        uv: [0.0, 1.0],
-                    },
-                ];
-                let idx: [u16; 6] = [0, 1, 2, 0, 2, 3];
-
-                let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("image-vbuf-unified"),
-                    size: (verts.len() * std::mem::size_of::<ImageQuadVtx>()) as u64,
-                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                });
-                let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("image-ibuf-unified"),
-                    size: (idx.len() * std::mem::size_of::<u16>()) as u64,
-                    usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                });
-                queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&verts));
-                queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&idx));
-
-                let vp_bg_img = self.image.vp_bind_group(&self.device, &self.vp_buffer);
-                // Pass z_index as float directly - shader will convert to depth
-                let (z_bg_img, z_buf_img) = self.create_group_z_bind_group(*z_val as f32, queue);
-                let tex_bg = self.image.tex_bind_group(&self.device, tex_view);
-
-                image_resources.push((vbuf, ibuf, vp_bg_img, z_bg_img, tex_bg, z_buf_img));
-            }
-
-            // Prepare SVG resources
-            let mut svg_resources: Vec<(
-                wgpu::Buffer,
-                wgpu::Buffer,
-                wgpu::BindGroup,
-                wgpu::BindGroup,
-                wgpu::BindGroup,
-                wgpu::Buffer,
-            )> = Vec::new();
-            for (view_scaled, origin, size, z_val) in svg_views.iter() {
-                let verts = [
-                    ImageQuadVtx {
-                        pos: [origin[0], origin[1]],
-                        uv: [0.0, 0.0],
-                    },
-                    ImageQuadVtx {
-                        pos: [origin[0] + size[0], origin[1]],
-                        uv: [1.0, 0.0],
-                    },
-                    ImageQuadVtx {
-                        pos: [origin[0] + size[0], origin[1] + size[1]],
-                        uv: [1.0, 1.0],
-                    },
-                    ImageQuadVtx {
-                        pos: [origin[0], origin[1] + size[1]],
-                        uv: [0.0, 1.0],
-                    },
-                ];
-                let idx: [u16; 6] = [0, 1, 2, 0, 2, 3];
-
-                let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("svg-vbuf-unified"),
-                    size: (verts.len() * std::mem::size_of::<ImageQuadVtx>()) as u64,
-                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                });
-                let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("svg-ibuf-unified"),
-                    size: (idx.len() * std::mem::size_of::<u16>()) as u64,
-                    usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                });
-                queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&verts));
-                queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&idx));
-
-                let vp_bg_svg = self.image.vp_bind_group(&self.device, &self.vp_buffer);
-                // Pass z_index as float directly - shader will convert to depth
-                let (z_bg_svg, z_buf_svg) = self.create_group_z_bind_group(*z_val as f32, queue);
-                let tex_bg = self.image.tex_bind_group(&self.device, view_scaled);
-
-                svg_resources.push((vbuf, ibuf, vp_bg_svg, z_bg_svg, tex_bg, z_buf_svg));
-            }
+            // Image-pattern paths, then images and SVGs
+            let mut meshes = self.prepare_pattern_resources(pattern_draws, &z_range, false, queue);
+            meshes.extend(self.prepare_image_meshes(encoder, image_sources, false, queue));
 
             // Build depth attachment after all mutable borrows on self are finished
             let depth_attachment = Some(wgpu::RenderPassDepthStencilAttachment {
@@ -3200,7 +4039,10 @@ impl PassManager {
                     },
                     store: wgpu::StoreOp::Store,
                 }),
-                stencil_ops: None,
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: wgpu::StoreOp::Store,
+                }),
             });
 
             // Begin unified render pass (after all resource preparation)
@@ -3210,7 +4052,7 @@ impl PassManager {
                     view: surface_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: if preserve_surface {
+                        load: if preserve_surface || scissor.is_some() {
                             wgpu::LoadOp::Load
                         } else {
                             wgpu::LoadOp::Clear(clear)
@@ -3222,37 +4064,19 @@ impl PassManager {
                 occlusion_query_set: None,
                 timestamp_writes: None,
             });
-
-            // Render solids first (they're already sorted by z-index in the scene)
-            self.solid_direct.record(&mut pass, &vp_bg, scene);
-
-            // Render text glyphs within the same pass (already sorted by z-index)
-            // eprintln!("📊 text_groups.len() = {}", text_groups.len());
-            for (_z_index, vbuf, ibuf, index_count, z_bg, _z_buf) in text_groups.iter() {
-                // eprintln!("  🎯 Rendering text group at z={} with {} indices", z_index, index_count);
-                if *index_count > 0 {
-                    pass.set_pipeline(&self.text.pipeline);
-                    pass.set_bind_group(0, &vp_bg_text, &[]);
-                    pass.set_bind_group(1, z_bg, &[]);
-                    pass.set_bind_group(2, &self.text_bind_group, &[]);
-                    pass.set_vertex_buffer(0, vbuf.slice(..));
-                    pass.set_index_buffer(ibuf.slice(..), wgpu::IndexFormat::Uint16);
-                    pass.draw_indexed(0..*index_count, 0, 0..1);
-                    // eprintln!("  ✅ Drew {} indices for z={}", index_count, z_index);
-                }
-            }
-
-            // Render images within same pass
-            for (vbuf, ibuf, vp_bg_img, z_bg_img, tex_bg, _z_buf_img) in image_resources.iter() {
-                self.image
-                    .record(&mut pass, vp_bg_img, z_bg_img, tex_bg, vbuf, ibuf, 6);
+            if let Some([x, y, w, h]) = scissor {
+                pass.set_scissor_rect(x, y, w, h);
             }
 
-            // Render SVGs within same pass
-            for (vbuf, ibuf, vp_bg_svg, z_bg_svg, tex_bg, _z_buf_svg) in svg_resources.iter() {
-                self.image
-                    .record(&mut pass, vp_bg_svg, z_bg_svg, tex_bg, vbuf, ibuf, 6);
-            }
+            self.record_unified(
+                &mut pass,
+                false,
+                [&vp_bg, &vp_bg_text, &vp_bg_clip],
+                scene,
+                &text_groups,
+                &meshes,
+                z_range,
+            );
 
             // NOW drop the pass - all rendering complete
             drop(pass);
@@ -3263,359 +4087,26 @@ impl PassManager {
         // Offscreen path - unified rendering to offscreen target
         let targets = self.alloc_targets(allocator, width.max(1), height.max(1));
 
-        // Pre-fetch (and lazily load) all image views before render pass (to avoid mutable borrow conflicts)
-        let mut image_views_off: Vec<(wgpu::TextureView, [f32; 2], [f32; 2], f32)> = Vec::new();
-        // eprintln!("🔍 Pre-fetching {} images for unified offscreen render", image_draws.len());
-        for (path, origin, size, z) in image_draws.iter() {
-            // eprintln!("  📦 Image at z={}: {:?}", z, path.file_name().unwrap_or_default());
-            let tex_opt = if let Some(view) = self.try_get_image_view(std::path::Path::new(path)) {
-                Some(view)
-            } else {
-                self.load_image_to_view(std::path::Path::new(path), queue)
-            };
-            if let Some((tex_view, _w, _h)) = tex_opt {
-                image_views_off.push((tex_view, *origin, *size, *z as f32));
-            }
-        }
-
-        // Pre-rasterize all SVGs before creating render pass (to avoid mutable borrow conflicts)
-        let mut svg_views_off: Vec<(wgpu::TextureView, [f32; 2], [f32; 2], f32)> = Vec::new();
-        for (path, origin, max_size, style, _z, transform) in svg_draws.iter() {
-            if let Some((_view, w, h)) =
-                self.rasterize_svg_to_view(std::path::Path::new(path), 1.0, *style, queue)
-            {
-                let base_w = w.max(1) as f32;
-                let base_h = h.max(1) as f32;
-                let scale = (max_size[0] / base_w).min(max_size[1] / base_h).max(0.0);
-
-                if let Some((view_scaled, sw, sh)) =
-                    self.rasterize_svg_to_view(std::path::Path::new(path), scale, *style, queue)
-                {
-                    // Apply transform to origin for correct positioning (offscreen path)
-                    let transformed_origin = apply_transform_to_point(*origin, *transform);
-                    svg_views_off.push((
-                        view_scaled,
-                        transformed_origin,
-                        [sw as f32, sh as f32],
-                        *_z as f32,
-                    ));
-                }
-            }
-        }
-
-        // Group text by z-index for proper depth rendering (offscreen path)
-        let mut text_by_z_off: std::collections::HashMap<
-            i32,
-            Vec<(
-                usize,
-                [f32; 2],
-                &crate::text::RasterizedGlyph,
-                &crate::ColorLinPremul,
-            )>,
-        > = std::collections::HashMap::new();
-        for (idx, (origin, glyph, color, z)) in glyph_draws.iter().enumerate() {
-            text_by_z_off
-                .entry(*z)
-                .or_insert_with(Vec::new)
-                .push((idx, *origin, glyph, color));
-        }
-
-        // Prepare text rendering data (same as direct path)
-        let mut text_groups_off = if !glyph_draws.is_empty() {
-            // Clear the atlas region used in the previous frame (efficient partial clear)
-            // Note: Atlas is shared between direct and offscreen paths, so clear here too
-            if self.prev_atlas_max_x > 0 && self.prev_atlas_max_y > 0 {
-                let clear_width = self.prev_atlas_max_x.min(4096);
-                let clear_height = self.prev_atlas_max_y.min(4096);
-                let clear_size = (clear_width * clear_height * 4) as usize;
-                let clear_data = vec![0u8; clear_size];
-                queue.write_texture(
-                    wgpu::ImageCopyTexture {
-                        texture: &self.text_mask_atlas,
-                        mip_level: 0,
-                        origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
-                        aspect: wgpu::TextureAspect::All,
-                    },
-                    &clear_data,
-                    wgpu::ImageDataLayout {
-                        offset: 0,
-                        bytes_per_row: Some(clear_width * 4),
-                        rows_per_image: Some(clear_height),
-                    },
-                    wgpu::Extent3d {
-                        width: clear_width,
-                        height: clear_height,
-                        depth_or_array_layers: 1,
-                    },
-                );
-            }
-
-            let mut atlas_cursor_x = 0u32;
-            let mut atlas_cursor_y = 0u32;
-            let mut next_row_height = 0u32;
-            let mut atlas_max_x = 0u32;
-            let mut atlas_max_y = 0u32;
-            let mut all_text_groups: Vec<(i32, Vec<TextQuadVtx>)> = Vec::new();
-
-            // Process each z-index group
-            for (z_index, glyphs) in text_by_z_off.iter() {
-                let mut vertices: Vec<TextQuadVtx> = Vec::new();
-
-                for (_idx, origin, glyph, color) in glyphs.iter() {
-                    let mask = &glyph.mask;
-                    let w = mask.width;
-                    let h = mask.height;
-
-                    if atlas_cursor_x + w >= 4096 {
-                        atlas_cursor_x = 0;
-                        atlas_cursor_y += next_row_height;
-                        next_row_height = 0;
-                    }
-                    next_row_height = next_row_height.max(h);
-
-                    // Track maximum atlas region used for clearing next frame
-                    atlas_max_x = atlas_max_x.max(atlas_cursor_x + w);
-                    atlas_max_y = atlas_max_y.max(atlas_cursor_y + h);
-
-                    queue.write_texture(
-                        wgpu::ImageCopyTexture {
-                            texture: &self.text_mask_atlas,
-                            mip_level: 0,
-                            origin: wgpu::Origin3d {
-                                x: atlas_cursor_x,
-                                y: atlas_cursor_y,
-                                z: 0,
-                            },
-                            aspect: wgpu::TextureAspect::All,
-                        },
-                        &mask.data,
-                        wgpu::ImageDataLayout {
-                            offset: 0,
-                            bytes_per_row: Some(w * mask.bytes_per_pixel() as u32),
-                            rows_per_image: Some(h),
-                        },
-                        wgpu::Extent3d {
-                            width: w,
-                            height: h,
-                            depth_or_array_layers: 1,
-                        },
-                    );
-
-                    let u0 = atlas_cursor_x as f32 / 4096.0;
-                    let v0 = atlas_cursor_y as f32 / 4096.0;
-                    let u1 = (atlas_cursor_x + w) as f32 / 4096.0;
-                    let v1 = (atlas_cursor_y + h) as f32 / 4096.0;
-
-                    vertices.extend_from_slice(&[
-                        TextQuadVtx {
-                            pos: [origin[0], origin[1]],
-                            uv: [u0, v0],
-                            color: [color.r, color.g, color.b, color.a],
-                        },
-                        TextQuadVtx {
-                            pos: [origin[0] + w as f32, origin[1]],
-                            uv: [u1, v0],
-                            color: [color.r, color.g, color.b, color.a],
-                        },
-                        TextQuadVtx {
-                            pos: [origin[0] + w as f32, origin[1] + h as f32],
-                            uv: [u1, v1],
-                            color: [color.r, color.g, color.b, color.a],
-                        },
-                        TextQuadVtx {
-                            pos: [origin[0], origin[1] + h as f32],
-                            uv: [u0, v1],
-                            color: [color.r, color.g, color.b, color.a],
-                        },
-                    ]);
-
-                    atlas_cursor_x += w;
-                }
-
-                // Store vertices for this z-index group
-                if !vertices.is_empty() {
-                    all_text_groups.push((*z_index, vertices));
-                }
-            }
-
-            // Create buffers and bind groups for each text group
-            let mut text_resources: Vec<(
-                i32,
-                wgpu::Buffer,
-                wgpu::Buffer,
-                u32,
-                wgpu::BindGroup,
-                wgpu::Buffer,
-            )> = Vec::new();
-            for (z_index, vertices) in all_text_groups {
-                let quad_count = vertices.len() / 4;
-                let mut indices: Vec<u16> = Vec::with_capacity(quad_count * 6);
-                for i in 0..quad_count {
-                    let base = (i * 4) as u16;
-                    indices.extend_from_slice(&[
-                        base,
-                        base + 1,
-                        base + 2,
-                        base,
-                        base + 2,
-                        base + 3,
-                    ]);
-                }
-
-                // Create vertex buffer for this group
-                let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("text-vertex-buffer-group-off"),
-                    size: (vertices.len() * std::mem::size_of::<TextQuadVtx>()) as u64,
-                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                });
-
-                // Create index buffer for this group
-                let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("text-index-buffer-group-off"),
-                    size: (indices.len() * std::mem::size_of::<u16>()) as u64,
-                    usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                });
-
-                queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&vertices));
-                queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&indices));
-
-                // Create z bind group for this text group
-                // Pass z_index as float directly - shader will convert to depth
-                let (z_bg, z_buf) = self.create_group_z_bind_group(z_index as f32, queue);
-
-                text_resources.push((z_index, vbuf, ibuf, indices.len() as u32, z_bg, z_buf));
-            }
-
-            // Store atlas usage for next frame's clearing
-            self.prev_atlas_max_x = atlas_max_x;
-            self.prev_atlas_max_y = atlas_max_y;
-
-            text_resources
-        } else {
-            Vec::new()
-        };
-
-        // Sort text groups by z-index (back to front)
-        text_groups_off.sort_by_key(|(z, _, _, _, _, _)| *z);
+        // Load image and SVG textures and place glyphs in the atlas before the render pass
+        let image_sources_off = self.collect_image_sources(image_draws, svg_draws, &z_range, queue);
+        let text_groups_off = self.prepare_text_groups(glyph_draws, &z_range, queue);
 
         // Create text bind groups (use offscreen text renderer for offscreen rendering)
         let vp_bg_text_off = self
             .text_offscreen
             .vp_bind_group(&self.device, &self.vp_buffer);
+        let vp_bg_clip_off = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("vp-bg-clip-offscreen"),
+            layout: self.clip_stencil_offscreen.viewport_bgl(),
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: self.vp_buffer.as_entire_binding(),
+            }],
+        });
 
-        // Prepare image resources (offscreen: use image_offscreen to match format)
-        let mut image_resources_off: Vec<(
-            wgpu::Buffer,
-            wgpu::Buffer,
-            wgpu::BindGroup,
-            wgpu::BindGroup,
-            wgpu::BindGroup,
-            wgpu::Buffer,
-        )> = Vec::new();
-        for (tex_view, origin, size, z_val) in image_views_off.iter() {
-            let verts = [
-                ImageQuadVtx {
-                    pos: [origin[0], origin[1]],
-                    uv: [0.0, 0.0],
-                },
-                ImageQuadVtx {
-                    pos: [origin[0] + size[0], origin[1]],
-                    uv: [1.0, 0.0],
-                },
-                ImageQuadVtx {
-                    pos: [origin[0] + size[0], origin[1] + size[1]],
-                    uv: [1.0, 1.0],
-                },
-                ImageQuadVtx {
-                    pos: [origin[0], origin[1] + size[1]],
-                    uv: [0.0, 1.0],
-                },
-            ];
-            let idx: [u16; 6] = [0, 1, 2, 0, 2, 3];
-
-            let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("image-vbuf-unified-offscreen"),
-                size: (verts.len() * std::mem::size_of::<ImageQuadVtx>()) as u64,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
-            let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("image-ibuf-unified-offscreen"),
-                size: (idx.len() * std::mem::size_of::<u16>()) as u64,
-                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
-            queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&verts));
-            queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&idx));
-
-            let vp_bg_img = self
-                .image_offscreen
-                .vp_bind_group(&self.device, &self.vp_buffer);
-            // Pass z_index as float directly - shader will convert to depth
-            let (z_bg_img, z_buf_img) = self.create_group_z_bind_group(*z_val as f32, queue);
-            let tex_bg = self.image_offscreen.tex_bind_group(&self.device, tex_view);
-
-            image_resources_off.push((vbuf, ibuf, vp_bg_img, z_bg_img, tex_bg, z_buf_img));
-        }
-
-        // Prepare SVG resources (offscreen: use image_offscreen to match format)
-        let mut svg_resources_off: Vec<(
-            wgpu::Buffer,
-            wgpu::Buffer,
-            wgpu::BindGroup,
-            wgpu::BindGroup,
-            wgpu::BindGroup,
-            wgpu::Buffer,
-        )> = Vec::new();
-        for (view_scaled, origin, size, z_val) in svg_views_off.iter() {
-            let verts = [
-                ImageQuadVtx {
-                    pos: [origin[0], origin[1]],
-                    uv: [0.0, 0.0],
-                },
-                ImageQuadVtx {
-                    pos: [origin[0] + size[0], origin[1]],
-                    uv: [1.0, 0.0],
-                },
-                ImageQuadVtx {
-                    pos: [origin[0] + size[0], origin[1] + size[1]],
-                    uv: [1.0, 1.0],
-                },
-                ImageQuadVtx {
-                    pos: [origin[0], origin[1] + size[1]],
-                    uv: [0.0, 1.0],
-                },
-            ];
-            let idx: [u16; 6] = [0, 1, 2, 0, 2, 3];
-
-            let vbuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("svg-vbuf-unified-offscreen"),
-                size: (verts.len() * std::mem::size_of::<ImageQuadVtx>()) as u64,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
-            let ibuf = self.device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("svg-ibuf-unified-offscreen"),
-                size: (idx.len() * std::mem::size_of::<u16>()) as u64,
-                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
-            queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&verts));
-            queue.write_buffer(&ibuf, 0, bytemuck::cast_slice(&idx));
-
-            let vp_bg_svg = self
-                .image_offscreen
-                .vp_bind_group(&self.device, &self.vp_buffer);
-            // Pass z_index as float directly - shader will convert to depth
-            let (z_bg_svg, z_buf_svg) = self.create_group_z_bind_group(*z_val as f32, queue);
-            let tex_bg = self
-                .image_offscreen
-                .tex_bind_group(&self.device, view_scaled);
-
-            svg_resources_off.push((vbuf, ibuf, vp_bg_svg, z_bg_svg, tex_bg, z_buf_svg));
-        }
+        // Image-pattern paths, then images and SVGs
+        let mut meshes_off = self.prepare_pattern_resources(pattern_draws, &z_range, true, queue);
+        meshes_off.extend(self.prepare_image_meshes(encoder, image_sources_off, true, queue));
 
         let depth_attachment = Some(wgpu::RenderPassDepthStencilAttachment {
             view: self.depth_view(),
@@ -3623,7 +4114,10 @@ impl PassManager {
                 load: wgpu::LoadOp::Clear(1.0),
                 store: wgpu::StoreOp::Store,
             }),
-            stencil_ops: None,
+            stencil_ops: Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(0),
+                store: wgpu::StoreOp::Store,
+            }),
         });
 
         let _z_bg = self.create_z_bind_group(0.0, queue);
@@ -3643,45 +4137,16 @@ impl PassManager {
             timestamp_writes: None,
         });
 
-        // Render solids first
         // eprintln!("🟢 OFFSCREEN PATH: Rendering {} solid vertices", scene.vertices);
-        self.solid_offscreen.record(&mut pass, &vp_bg_off, scene);
-
-        // Render text glyphs within the same pass (already sorted by z-index)
-        // eprintln!("📊 text_groups_off.len() = {}", text_groups_off.len());
-        for (_z_index, vbuf, ibuf, index_count, z_bg, _z_buf) in text_groups_off.iter() {
-            // eprintln!("  🎯 Rendering text group at z={} with {} indices (OFFSCREEN)", z_index, index_count);
-            if *index_count > 0 {
-                pass.set_pipeline(&self.text_offscreen.pipeline);
-                pass.set_bind_group(0, &vp_bg_text_off, &[]);
-                pass.set_bind_group(1, z_bg, &[]);
-                pass.set_bind_group(2, &self.text_bind_group, &[]);
-                pass.set_vertex_buffer(0, vbuf.slice(..));
-                pass.set_index_buffer(ibuf.slice(..), wgpu::IndexFormat::Uint16);
-                pass.draw_indexed(0..*index_count, 0, 0..1);
-                // eprintln!("  ✅ Drew {} indices for z={} (OFFSCREEN)", index_count, z_index);
-            }
-        }
-
-        // Render images within same pass (offscreen image pipeline)
-        // eprintln!("📷 image_resources_off.len() = {}", image_resources_off.len());
-        for (_i, (vbuf, ibuf, vp_bg_img, z_bg_img, tex_bg, _z_buf_img)) in
-            image_resources_off.iter().enumerate()
-        {
-            // eprintln!("  🖼️ Rendering image {} (OFFSCREEN)", i);
-            self.image_offscreen
-                .record(&mut pass, vp_bg_img, z_bg_img, tex_bg, vbuf, ibuf, 6);
-        }
-
-        // Render SVGs within same pass (offscreen image pipeline)
-        // eprintln!("🎨 svg_resources_off.len() = {}", svg_resources_off.len());
-        for (_i, (vbuf, ibuf, vp_bg_svg, z_bg_svg, tex_bg, _z_buf_svg)) in
-            svg_resources_off.iter().enumerate()
-        {
-            // eprintln!("  🎨 Rendering SVG {} (OFFSCREEN)", i);
-            self.image_offscreen
-                .record(&mut pass, vp_bg_svg, z_bg_svg, tex_bg, vbuf, ibuf, 6);
-        }
+        self.record_unified(
+            &mut pass,
+            true,
+            [&vp_bg_off, &vp_bg_text_off, &vp_bg_clip_off],
+            scene,
+            &text_groups_off,
+            &meshes_off,
+            z_range,
+        );
 
         // Drop the pass to complete offscreen rendering
         drop(pass);
@@ -3691,3 +4156,58 @@ impl PassManager {
         allocator.release_texture(targets.color);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenes_slice_at_layer_z_indices() {
+        let z = |slices: &[SceneSlice]| -> Vec<_> {
+            slices
+                .iter()
+                .map(|slice| (slice.z, slice.layers.clone()))
+                .collect()
+        };
+        assert_eq!(
+            z(&slices_at([])),
+            vec![((Bound::Unbounded, Bound::Unbounded), 0..0)]
+        );
+        assert_eq!(
+            z(&slices_at([3, 3, 7])),
+            vec![
+                ((Bound::Unbounded, Bound::Excluded(3)), 0..2),
+                ((Bound::Included(3), Bound::Excluded(7)), 2..3),
+                ((Bound::Included(7), Bound::Unbounded), 3..3),
+            ]
+        );
+    }
+
+    #[test]
+    fn glyph_atlas_keys_prefer_the_glyph_key() {
+        let glyph = |key: u64, data: Vec<u8>| crate::text::RasterizedGlyph {
+            offset: [0.0, 0.0],
+            color: None,
+            mask: crate::text::SubpixelMask {
+                width: 1,
+                height: 1,
+                format: crate::text::MaskFormat::Rgba8,
+                data,
+            },
+            key,
+        };
+        assert_eq!(
+            AtlasKey::glyph(&glyph(7, vec![1, 2, 3, 4])),
+            AtlasKey::Glyph(7)
+        );
+        // Unkeyed glyphs fall back to their pixels.
+        assert_eq!(
+            AtlasKey::glyph(&glyph(0, vec![1, 2, 3, 4])),
+            AtlasKey::glyph(&glyph(0, vec![1, 2, 3, 4]))
+        );
+        assert_ne!(
+            AtlasKey::glyph(&glyph(0, vec![1, 2, 3, 4])),
+            AtlasKey::glyph(&glyph(0, vec![4, 3, 2, 1]))
+        );
+    }
+}
@@ -2,6 +2,24 @@ use std::sync::Arc;
 
 use crate::upload::GpuScene;
 
+/// Stencil test of the pipelines drawn in the unified pass: fragments pass
+/// where the stencil equals the reference, the depth of the clip stack
+/// being drawn (see [`ClipStencilRenderer`]).
+fn clip_stencil_test() -> wgpu::StencilState {
+    let face = wgpu::StencilFaceState {
+        compare: wgpu::CompareFunction::Equal,
+        fail_op: wgpu::StencilOperation::Keep,
+        depth_fail_op: wgpu::StencilOperation::Keep,
+        pass_op: wgpu::StencilOperation::Keep,
+    };
+    wgpu::StencilState {
+        front: face,
+        back: face,
+        read_mask: 0xFF,
+        write_mask: 0,
+    }
+}
+
 pub struct BasicSolidRenderer {
     pipeline: wgpu::RenderPipeline,
     bgl: wgpu::BindGroupLayout,
@@ -92,10 +110,10 @@ impl BasicSolidRenderer {
             }),
             primitive: wgpu::PrimitiveState::default(),
             depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
+                format: wgpu::TextureFormat::Depth24PlusStencil8,
                 depth_write_enabled: true,
                 depth_compare: wgpu::CompareFunction::LessEqual,
-                stencil: wgpu::StencilState::default(),
+                stencil: clip_stencil_test(),
                 bias: wgpu::DepthBiasState::default(),
             }),
             multisample: wgpu::MultisampleState {
@@ -118,11 +136,26 @@ impl BasicSolidRenderer {
         vp_bg: &'a wgpu::BindGroup,
         scene: &'a GpuScene,
     ) {
+        self.record_range(pass, vp_bg, scene, 0..scene.indices);
+    }
+
+    /// Like [`BasicSolidRenderer::record`], drawing only `indices` of the
+    /// scene (see [`GpuScene::index_range`]).
+    pub fn record_range<'a>(
+        &'a self,
+        pass: &mut wgpu::RenderPass<'a>,
+        vp_bg: &'a wgpu::BindGroup,
+        scene: &'a GpuScene,
+        indices: std::ops::Range<u32>,
+    ) {
+        if indices.is_empty() {
+            return;
+        }
         pass.set_pipeline(&self.pipeline);
         pass.set_bind_group(0, vp_bg, &[]);
         pass.set_vertex_buffer(0, scene.vertex.buffer.slice(..));
         pass.set_index_buffer(scene.index.buffer.slice(..), wgpu::IndexFormat::Uint16);
-        pass.draw_indexed(0..scene.indices, 0, 0..1);
+        pass.draw_indexed(indices, 0, 0..1);
     }
 
     pub fn viewport_bgl(&self) -> &wgpu::BindGroupLayout {
@@ -461,6 +494,167 @@ impl ScrimStencilMaskRenderer {
     }
 }
 
+/// Stencil-only writer for clip shapes in the unified pass (color writes
+/// disabled). Drawing the shapes of a clip stack in order with
+/// [`ClipStencilRenderer::record_intersect`] leaves the stencil equal to the
+/// stack's depth exactly where all of them overlap;
+/// [`ClipStencilRenderer::record_reset`] with the outermost shape zeroes it
+/// again.
+pub struct ClipStencilRenderer {
+    intersect: wgpu::RenderPipeline,
+    reset: wgpu::RenderPipeline,
+    bgl: wgpu::BindGroupLayout,
+}
+
+impl ClipStencilRenderer {
+    pub fn new(device: Arc<wgpu::Device>, target_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("clip-stencil-shader"),
+            source: wgpu::ShaderSource::Wgsl(engine_shaders::SOLID_WGSL.into()),
+        });
+
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("clip-stencil-vp-bgl"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: std::num::NonZeroU64::new(16),
+                },
+                count: None,
+            }],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("clip-stencil-pipeline-layout"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = |label: &str, compare, pass_op| {
+            let face = wgpu::StencilFaceState {
+                compare,
+                fail_op: wgpu::StencilOperation::Keep,
+                depth_fail_op: wgpu::StencilOperation::Keep,
+                pass_op,
+            };
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<crate::upload::Vertex>() as u64,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &[
+                            wgpu::VertexAttribute {
+                                offset: 0,
+                                shader_location: 0,
+                                format: wgpu::VertexFormat::Float32x2,
+                            },
+                            wgpu::VertexAttribute {
+                                offset: 8,
+                                shader_location: 1,
+                                format: wgpu::VertexFormat::Float32x4,
+                            },
+                            wgpu::VertexAttribute {
+                                offset: 24,
+                                shader_location: 2,
+                                format: wgpu::VertexFormat::Float32,
+                            },
+                        ],
+                    }],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: target_format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::empty(),
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth24PlusStencil8,
+                    depth_write_enabled: false,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil: wgpu::StencilState {
+                        front: face,
+                        back: face,
+                        read_mask: 0xFF,
+                        write_mask: 0xFF,
+                    },
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+        let intersect = pipeline(
+            "clip-stencil-intersect-pipeline",
+            wgpu::CompareFunction::Equal,
+            wgpu::StencilOperation::IncrementClamp,
+        );
+        let reset = pipeline(
+            "clip-stencil-reset-pipeline",
+            wgpu::CompareFunction::Always,
+            wgpu::StencilOperation::Zero,
+        );
+
+        Self {
+            intersect,
+            reset,
+            bgl,
+        }
+    }
+
+    pub fn viewport_bgl(&self) -> &wgpu::BindGroupLayout {
+        &self.bgl
+    }
+
+    /// Increment the stencil under the shape's triangles where it equals
+    /// `depth`, the number of shapes of the stack drawn before it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_intersect<'a>(
+        &'a self,
+        pass: &mut wgpu::RenderPass<'a>,
+        vp_bg: &'a wgpu::BindGroup,
+        vbuf: &'a wgpu::Buffer,
+        ibuf: &'a wgpu::Buffer,
+        indices: std::ops::Range<u32>,
+        base_vertex: i32,
+        depth: u32,
+    ) {
+        pass.set_pipeline(&self.intersect);
+        pass.set_stencil_reference(depth);
+        pass.set_bind_group(0, vp_bg, &[]);
+        pass.set_vertex_buffer(0, vbuf.slice(..));
+        pass.set_index_buffer(ibuf.slice(..), wgpu::IndexFormat::Uint16);
+        pass.draw_indexed(indices, base_vertex, 0..1);
+    }
+
+    /// Zero the stencil under the shape's triangles.
+    pub fn record_reset<'a>(
+        &'a self,
+        pass: &mut wgpu::RenderPass<'a>,
+        vp_bg: &'a wgpu::BindGroup,
+        vbuf: &'a wgpu::Buffer,
+        ibuf: &'a wgpu::Buffer,
+        indices: std::ops::Range<u32>,
+        base_vertex: i32,
+    ) {
+        pass.set_pipeline(&self.reset);
+        pass.set_bind_group(0, vp_bg, &[]);
+        pass.set_vertex_buffer(0, vbuf.slice(..));
+        pass.set_index_buffer(ibuf.slice(..), wgpu::IndexFormat::Uint16);
+        pass.draw_indexed(indices, base_vertex, 0..1);
+    }
+}
+
 /// Scrim renderer that clips against stencil (hole stays transparent).
 pub struct ScrimStencilRenderer {
     pipeline: wgpu::RenderPipeline,
@@ -586,6 +780,21 @@ pub struct Compositor {
     pipeline: wgpu::RenderPipeline,
     bgl: wgpu::BindGroupLayout,
     sampler: wgpu::Sampler,
+    /// Layer composite for `BlendMode::Normal`, blended by the hardware.
+    layer_over: wgpu::RenderPipeline,
+    /// Layer composite for the other blend modes, which read a backdrop copy.
+    layer_blend: wgpu::RenderPipeline,
+    layer_bgl: wgpu::BindGroupLayout,
+}
+
+/// Uniforms of `LAYER_COMPOSITE_WGSL`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LayerUniform {
+    pub opacity: f32,
+    pub mode: u32,
+    pub has_mask: u32,
+    pub _pad: u32,
 }
 
 impl Compositor {
@@ -657,13 +866,137 @@ impl Compositor {
             ..Default::default()
         });
 
+        let layer_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("layer-composite-shader"),
+            source: wgpu::ShaderSource::Wgsl(engine_shaders::LAYER_COMPOSITE_WGSL.into()),
+        });
+        let layer_tex_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let layer_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("layer-composite-bgl"),
+            entries: &[
+                layer_tex_entry(0),
+                layer_tex_entry(1),
+                layer_tex_entry(2),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layer_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("layer-composite-pipeline-layout"),
+            bind_group_layouts: &[&layer_bgl],
+            push_constant_ranges: &[],
+        });
+        let layer_pipeline = |label: &str, blend: wgpu::BlendState| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layer_layout),
+                vertex: wgpu::VertexState {
+                    module: &layer_shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &layer_shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: target_format,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                // Composited in paint order, between the slices of the scene
+                // the layer lands in, so no depth test.
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+        let layer_over = layer_pipeline(
+            "layer-composite-over",
+            wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        );
+        let layer_blend = layer_pipeline("layer-composite-blend", wgpu::BlendState::REPLACE);
+
         Self {
             pipeline,
             bgl,
             sampler,
+            layer_over,
+            layer_blend,
+            layer_bgl,
         }
     }
 
+    /// Bind group for [`Compositor::record_layer`]. Unused inputs (no mask,
+    /// or no backdrop for `BlendMode::Normal`) can be bound to `layer`.
+    pub fn layer_bind_group(
+        &self,
+        device: &wgpu::Device,
+        layer: &wgpu::TextureView,
+        mask: &wgpu::TextureView,
+        backdrop: &wgpu::TextureView,
+        uniform: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("layer-composite-bg"),
+            layout: &self.layer_bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(layer),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(mask),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(backdrop),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: uniform.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    /// Composite a layer over the pass target. `blend` selects the backdrop
+    /// reading pipeline; it must match the `mode` in the layer uniform.
+    pub fn record_layer<'a>(
+        &'a self,
+        pass: &mut wgpu::RenderPass<'a>,
+        bg: &'a wgpu::BindGroup,
+        blend: bool,
+    ) {
+        let pipeline = if blend {
+            &self.layer_blend
+        } else {
+            &self.layer_over
+        };
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, bg, &[]);
+        pass.draw(0..3, 0..1);
+    }
+
     pub fn bind_group(
         &self,
         device: &wgpu::Device,
@@ -692,6 +1025,182 @@ impl Compositor {
     }
 }
 
+/// Runs one `FILTER_WGSL` pass per call: a blur direction, a color matrix,
+/// a shadow tint or a plain copy, selected by [`FilterUniform::mode`].
+pub struct FilterRenderer {
+    /// Overwrites the target.
+    replace: wgpu::RenderPipeline,
+    /// Blends over the target, used to draw content over its drop shadow.
+    over: wgpu::RenderPipeline,
+    bgl: wgpu::BindGroupLayout,
+}
+
+/// Uniforms of `FILTER_WGSL`. Lengths are in physical pixels.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FilterUniform {
+    /// Color matrix rows, applied to unpremultiplied RGBA.
+    pub m: [[f32; 4]; 4],
+    pub bias: [f32; 4],
+    /// Premultiplied shadow color.
+    pub tint: [f32; 4],
+    pub dir: [f32; 2],
+    pub offset: [f32; 2],
+    pub sigma: f32,
+    /// 0 blur, 1 color matrix, 2 shadow tint, anything else copies.
+    pub mode: u32,
+    pub clamp_edges: u32,
+    pub _pad: u32,
+}
+
+impl FilterUniform {
+    pub const BLUR: u32 = 0;
+    pub const COLOR_MATRIX: u32 = 1;
+    pub const SHADOW: u32 = 2;
+    pub const COPY: u32 = 3;
+
+    pub fn blur(sigma: f32, dir: [f32; 2], clamp_edges: bool) -> Self {
+        Self {
+            dir,
+            sigma,
+            mode: Self::BLUR,
+            clamp_edges: clamp_edges as u32,
+            ..bytemuck::Zeroable::zeroed()
+        }
+    }
+
+    /// `m` is row-major 4x5, as in [`crate::Filter::ColorMatrix`].
+    pub fn color_matrix(m: &[f32; 20]) -> Self {
+        let row = |r: usize| [m[r * 5], m[r * 5 + 1], m[r * 5 + 2], m[r * 5 + 3]];
+        Self {
+            m: [row(0), row(1), row(2), row(3)],
+            bias: [m[4], m[9], m[14], m[19]],
+            mode: Self::COLOR_MATRIX,
+            ..bytemuck::Zeroable::zeroed()
+        }
+    }
+
+    pub fn shadow(tint: [f32; 4], offset: [f32; 2]) -> Self {
+        Self {
+            tint,
+            offset,
+            mode: Self::SHADOW,
+            ..bytemuck::Zeroable::zeroed()
+        }
+    }
+
+    pub fn copy() -> Self {
+        Self {
+            mode: Self::COPY,
+            ..bytemuck::Zeroable::zeroed()
+        }
+    }
+}
+
+impl FilterRenderer {
+    pub fn new(device: Arc<wgpu::Device>, target_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("filter-shader"),
+            source: wgpu::ShaderSource::Wgsl(engine_shaders::FILTER_WGSL.into()),
+        });
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("filter-bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("filter-pipeline-layout"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let filter_pipeline = |label: &str, blend: wgpu::BlendState| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: target_format,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+        let replace = filter_pipeline("filter-replace", wgpu::BlendState::REPLACE);
+        let over = filter_pipeline(
+            "filter-over",
+            wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        );
+        Self { replace, over, bgl }
+    }
+
+    pub fn bind_group(
+        &self,
+        device: &wgpu::Device,
+        src: &wgpu::TextureView,
+        uniform: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("filter-bg"),
+            layout: &self.bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(src),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: uniform.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    /// Draw the filter over the whole pass target; `over` blends instead of
+    /// replacing.
+    pub fn record<'a>(
+        &'a self,
+        pass: &mut wgpu::RenderPass<'a>,
+        bg: &'a wgpu::BindGroup,
+        over: bool,
+    ) {
+        pass.set_pipeline(if over { &self.over } else { &self.replace });
+        pass.set_bind_group(0, bg, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
 pub struct Blitter {
     pipeline: wgpu::RenderPipeline,
     bgl: wgpu::BindGroupLayout,
@@ -1166,7 +1675,14 @@ impl BackgroundRenderer {
     pub fn new(device: Arc<wgpu::Device>, target_format: wgpu::TextureFormat) -> Self {
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("background-shader"),
-            source: wgpu::ShaderSource::Wgsl(engine_shaders::BACKGROUND_WGSL.into()),
+            source: wgpu::ShaderSource::Wgsl(
+                format!(
+                    "{}{}",
+                    engine_shaders::GRADIENT_WGSL,
+                    engine_shaders::BACKGROUND_WGSL
+                )
+                .into(),
+            ),
         });
         let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("bg-bgl"),
@@ -1576,7 +2092,7 @@ impl TextRenderer {
                 module: &shader,
                 entry_point: "vs_main",
                 buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: (std::mem::size_of::<f32>() * 8) as u64, // pos(2) + uv(2) + color(4)
+                    array_stride: (std::mem::size_of::<f32>() * 9) as u64, // pos(2) + uv(2) + color(4) + color_glyph(1)
                     step_mode: wgpu::VertexStepMode::Vertex,
                     attributes: &[
                         wgpu::VertexAttribute {
@@ -1594,6 +2110,11 @@ impl TextRenderer {
                             shader_location: 2,
                             format: wgpu::VertexFormat::Float32x4,
                         },
+                        wgpu::VertexAttribute {
+                            offset: 32,
+                            shader_location: 3,
+                            format: wgpu::VertexFormat::Float32,
+                        },
                     ],
                 }],
             },
@@ -1608,10 +2129,10 @@ impl TextRenderer {
             }),
             primitive: wgpu::PrimitiveState::default(),
             depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
+                format: wgpu::TextureFormat::Depth24PlusStencil8,
                 depth_write_enabled: true,
                 depth_compare: wgpu::CompareFunction::LessEqual,
-                stencil: wgpu::StencilState::default(),
+                stencil: clip_stencil_test(),
                 bias: wgpu::DepthBiasState::default(),
             }),
             multisample: wgpu::MultisampleState::default(),
@@ -1708,6 +2229,7 @@ pub struct ImageRenderer {
     _z_bgl: wgpu::BindGroupLayout,
     tex_bgl: wgpu::BindGroupLayout,
     sampler: wgpu::Sampler,
+    pattern_sampler: wgpu::Sampler,
 }
 
 impl ImageRenderer {
@@ -1788,10 +2310,10 @@ impl ImageRenderer {
             None
         } else {
             Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
+                format: wgpu::TextureFormat::Depth24PlusStencil8,
                 depth_write_enabled: true,
                 depth_compare: wgpu::CompareFunction::LessEqual,
-                stencil: wgpu::StencilState::default(),
+                stencil: clip_stencil_test(),
                 bias: wgpu::DepthBiasState::default(),
             })
         };
@@ -1844,6 +2366,17 @@ impl ImageRenderer {
             address_mode_w: wgpu::AddressMode::ClampToEdge,
             ..Default::default()
         });
+        // Image-pattern meshes carry UVs in tile units and rely on wrapping.
+        let pattern_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("image-pattern-sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            ..Default::default()
+        });
 
         Self {
             pipeline,
@@ -1851,6 +2384,7 @@ impl ImageRenderer {
             _z_bgl: z_bgl,
             tex_bgl,
             sampler,
+            pattern_sampler,
         }
     }
 
@@ -1890,6 +2424,29 @@ impl ImageRenderer {
         })
     }
 
+    /// Like [`tex_bind_group`](Self::tex_bind_group), but sampling with repeat
+    /// addressing for image-pattern meshes.
+    pub fn pattern_tex_bind_group(
+        &self,
+        device: &wgpu::Device,
+        tex_view: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("image-pattern-tex-bg"),
+            layout: &self.tex_bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.pattern_sampler),
+                },
+            ],
+        })
+    }
+
     pub fn record<'a>(
         &'a self,
         pass: &mut wgpu::RenderPass<'a>,
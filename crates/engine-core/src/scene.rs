@@ -1,4 +1,8 @@
-#[derive(Clone, Copy, Debug, Default)]
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub struct Transform2D {
     // Affine 2D: [a, b, c, d, e, f] for matrix [[a c e],[b d f],[0 0 1]]
     pub m: [f32; 6],
@@ -56,7 +60,7 @@ impl Transform2D {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct ColorLinPremul {
     pub r: f32,
     pub g: f32,
@@ -69,23 +73,137 @@ pub type Color = ColorLinPremul;
 
 // Constructors for ColorLinPremul are defined in color.rs to keep scene.rs focused
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Brush {
     Solid(ColorLinPremul),
     LinearGradient {
         start: [f32; 2],
         end: [f32; 2],
         stops: Vec<(f32, ColorLinPremul)>,
+        spread: SpreadMode,
     },
     RadialGradient {
         center: [f32; 2],
         radius: f32,
         stops: Vec<(f32, ColorLinPremul)>,
+        spread: SpreadMode,
+    },
+    /// Sweep around `center`: stop offsets are fractions of a full turn,
+    /// clockwise (y-down) from `angle` radians off the positive x axis.
+    ConicGradient {
+        center: [f32; 2],
+        angle: f32,
+        stops: Vec<(f32, ColorLinPremul)>,
+        spread: SpreadMode,
     },
-    // Pattern, RadialGradient etc. can be added later.
+    /// Raster image tiled across the shape.
+    Image(ImagePattern),
+}
+
+/// How a gradient continues past its first and last stops.
+///
+/// `Repeat` and `Reflect` cycle over the span between the first and last
+/// stop offsets, like CSS `repeating-*-gradient()`; a ramp with stops at 0
+/// and 1 repeats once per gradient length, as SVG `spreadMethod` does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpreadMode {
+    /// Extend the end colors.
+    #[default]
+    Pad,
+    Repeat,
+    /// Repeat, mirroring every other period.
+    Reflect,
+}
+
+impl SpreadMode {
+    /// Map a ramp position onto the stop span `lo..=hi`.
+    pub fn apply(self, t: f32, lo: f32, hi: f32) -> f32 {
+        let span = hi - lo;
+        if self == SpreadMode::Pad || span.is_nan() || span <= 1e-6 || !t.is_finite() {
+            return t.clamp(lo.min(hi), hi.max(lo));
+        }
+        let u = (t - lo) / span;
+        let f = match self {
+            SpreadMode::Reflect => {
+                let m = u.rem_euclid(2.0);
+                if m > 1.0 { 2.0 - m } else { m }
+            }
+            _ => u.rem_euclid(1.0),
+        };
+        lo + f * span
+    }
+}
+
+/// Image brush: `path` stretched over `tile` and repeated from there.
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct ImagePattern {
+    /// Raster image file, interpreted like the path of `Command::DrawImage`.
+    pub path: std::path::PathBuf,
+    /// Placement of one copy of the image in pattern space.
+    pub tile: Rect,
+    pub repeat: PatternRepeat,
+    /// Maps pattern space into the local space of the painted shape.
+    pub transform: Transform2D,
+}
+
+impl ImagePattern {
+    /// Image repeated in both directions with `tile` in the shape's local space.
+    pub fn new(path: impl Into<std::path::PathBuf>, tile: Rect) -> Self {
+        Self {
+            path: path.into(),
+            tile,
+            repeat: PatternRepeat::Repeat,
+            transform: Transform2D::identity(),
+        }
+    }
+
+    pub fn with_repeat(mut self, repeat: PatternRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Transform2D) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Tile coordinates of a local-space point: `[0, 1]` on both axes covers
+    /// `tile`. `None` if `transform` is not invertible or the tile is empty.
+    pub fn uv_at(&self, p: [f32; 2]) -> Option<[f32; 2]> {
+        if self.tile.w.abs() < 1e-6 || self.tile.h.abs() < 1e-6 {
+            return None;
+        }
+        let q = self.transform.inverse_apply(p)?;
+        Some([
+            (q[0] - self.tile.x) / self.tile.w,
+            (q[1] - self.tile.y) / self.tile.h,
+        ])
+    }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// Axes along which an [`ImagePattern`] repeats its tile, as in CSS
+/// `background-repeat`. Outside a non-repeating axis the pattern is
+/// transparent.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PatternRepeat {
+    #[default]
+    Repeat,
+    RepeatX,
+    RepeatY,
+    NoRepeat,
+}
+
+impl PatternRepeat {
+    pub fn repeats_x(self) -> bool {
+        matches!(self, PatternRepeat::Repeat | PatternRepeat::RepeatX)
+    }
+
+    pub fn repeats_y(self) -> bool {
+        matches!(self, PatternRepeat::Repeat | PatternRepeat::RepeatY)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Rect {
     pub x: f32,
     pub y: f32,
@@ -93,29 +211,785 @@ pub struct Rect {
     pub h: f32,
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// Corner radii of a [`RoundedRect`], each `[horizontal, vertical]` so
+/// corners can be elliptical, as with CSS `border-radius: 40px / 20px`. A
+/// corner with either radius zero is square.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct RoundedRadii {
-    pub tl: f32,
-    pub tr: f32,
-    pub br: f32,
-    pub bl: f32,
+    pub tl: [f32; 2],
+    pub tr: [f32; 2],
+    pub br: [f32; 2],
+    pub bl: [f32; 2],
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+impl RoundedRadii {
+    /// The same circular radius at every corner.
+    pub const fn uniform(r: f32) -> Self {
+        Self::elliptical(r, r)
+    }
+
+    /// The same elliptical radii at every corner.
+    pub const fn elliptical(rx: f32, ry: f32) -> Self {
+        Self {
+            tl: [rx, ry],
+            tr: [rx, ry],
+            br: [rx, ry],
+            bl: [rx, ry],
+        }
+    }
+
+    /// Circular radii per corner, clockwise from the top left.
+    pub const fn new(tl: f32, tr: f32, br: f32, bl: f32) -> Self {
+        Self {
+            tl: [tl, tl],
+            tr: [tr, tr],
+            br: [br, br],
+            bl: [bl, bl],
+        }
+    }
+
+    /// Whether every corner is square.
+    pub fn is_zero(&self) -> bool {
+        [self.tl, self.tr, self.br, self.bl]
+            .iter()
+            .all(|r| !(r[0] > 0.0 && r[1] > 0.0))
+    }
+
+    pub fn scale(self, s: f32) -> Self {
+        self.map(|r| r * s)
+    }
+
+    /// Radii of the edge `widths` (`[top, right, bottom, left]`) inside
+    /// this one, as CSS derives the inner border edge. Negative widths grow
+    /// the radii, as a box-shadow spread does.
+    pub fn inset(self, widths: [f32; 4]) -> Self {
+        let [t, r, b, l] = widths;
+        let shrink = |c: [f32; 2], dx: f32, dy: f32| [(c[0] - dx).max(0.0), (c[1] - dy).max(0.0)];
+        Self {
+            tl: shrink(self.tl, l, t),
+            tr: shrink(self.tr, r, t),
+            br: shrink(self.br, r, b),
+            bl: shrink(self.bl, l, b),
+        }
+    }
+
+    /// Radii scaled down uniformly so adjacent corners don't overlap on a
+    /// `w`×`h` box, as CSS does. Negative and non-finite radii become zero.
+    pub fn fit(self, w: f32, h: f32) -> Self {
+        let out = self.map(|r| if r.is_finite() && r > 0.0 { r } else { 0.0 });
+        let ratio = |len: f32, a: f32, b: f32| {
+            if a + b > len.max(0.0) {
+                len.max(0.0) / (a + b)
+            } else {
+                1.0
+            }
+        };
+        let f = ratio(w, out.tl[0], out.tr[0])
+            .min(ratio(w, out.bl[0], out.br[0]))
+            .min(ratio(h, out.tl[1], out.bl[1]))
+            .min(ratio(h, out.tr[1], out.br[1]));
+        if f < 1.0 { out.scale(f) } else { out }
+    }
+
+    fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        let m = |c: [f32; 2]| [f(c[0]), f(c[1])];
+        Self {
+            tl: m(self.tl),
+            tr: m(self.tr),
+            br: m(self.br),
+            bl: m(self.bl),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Hash, Serialize, Deserialize)]
 pub struct RoundedRect {
     pub rect: Rect,
     pub radii: RoundedRadii,
 }
 
-#[derive(Clone, Copy, Debug)]
+impl RoundedRect {
+    /// Radii as drawn: fitted to the rect so adjacent corners don't overlap.
+    pub fn fitted_radii(&self) -> RoundedRadii {
+        self.radii.fit(self.rect.w, self.rect.h)
+    }
+
+    /// The rounded rect `widths` (`[top, right, bottom, left]`) inside this
+    /// one, e.g. the inner edge of a border. Negative widths grow it.
+    pub fn inset(&self, widths: [f32; 4]) -> RoundedRect {
+        let [t, r, b, l] = widths;
+        let Rect { x, y, w, h } = self.rect;
+        RoundedRect {
+            rect: Rect {
+                x: x + l,
+                y: y + t,
+                w: (w - l - r).max(0.0),
+                h: (h - t - b).max(0.0),
+            },
+            radii: self.fitted_radii().inset(widths),
+        }
+    }
+
+    /// Whether `p` is inside the rounded rect, corners included.
+    pub fn contains(&self, p: [f32; 2]) -> bool {
+        let Rect { x, y, w, h } = self.rect;
+        let (px, py) = (p[0] - x, p[1] - y);
+        if px < 0.0 || py < 0.0 || px > w || py > h {
+            return false;
+        }
+        let r = self.fitted_radii();
+        // Offsets from each corner's ellipse center towards the corner.
+        let corners = [
+            (r.tl, r.tl[0] - px, r.tl[1] - py),
+            (r.tr, px - (w - r.tr[0]), r.tr[1] - py),
+            (r.br, px - (w - r.br[0]), py - (h - r.br[1])),
+            (r.bl, r.bl[0] - px, py - (h - r.bl[1])),
+        ];
+        for (radii, dx, dy) in corners {
+            if radii[0] > 0.0 && radii[1] > 0.0 && dx > 0.0 && dy > 0.0 {
+                let (nx, ny) = (dx / radii[0], dy / radii[1]);
+                return nx * nx + ny * ny <= 1.0 + 1e-5;
+            }
+        }
+        true
+    }
+
+    /// Polygon approximating the outline with `segments` per rounded corner,
+    /// counter-clockwise on screen from the top of the top-left corner.
+    pub fn outline(&self, segments: u32) -> Vec<[f32; 2]> {
+        use std::f32::consts::{FRAC_PI_2, PI, TAU};
+        let Rect { x, y, w, h } = self.rect;
+        let r = self.fitted_radii();
+        let corners = [
+            (r.tl, [x, y], [x + r.tl[0], y + r.tl[1]], FRAC_PI_2),
+            (r.bl, [x, y + h], [x + r.bl[0], y + h - r.bl[1]], PI),
+            (
+                r.br,
+                [x + w, y + h],
+                [x + w - r.br[0], y + h - r.br[1]],
+                PI + FRAC_PI_2,
+            ),
+            (r.tr, [x + w, y], [x + w - r.tr[0], y + r.tr[1]], 0.0),
+        ];
+        let segments = segments.max(1);
+        let mut ring = Vec::new();
+        for (radii, corner, c, start) in corners {
+            if !(radii[0] > 0.0 && radii[1] > 0.0) {
+                ring.push(corner);
+                continue;
+            }
+            for i in 0..=segments {
+                let a = (start + FRAC_PI_2 * i as f32 / segments as f32).rem_euclid(TAU);
+                ring.push([c[0] + radii[0] * a.cos(), c[1] - radii[1] * a.sin()]);
+            }
+        }
+        ring
+    }
+
+    /// Outline as a closed path of lines and cubic Béziers, clockwise on
+    /// screen from the end of the top-left corner.
+    pub fn to_path(&self) -> Path {
+        // Kappa for the quarter-ellipse cubic approximation.
+        const K: f32 = 0.552_284_8;
+        let Rect { x, y, w, h } = self.rect;
+        let (x0, y0, x1, y1) = (x, y, x + w, y + h);
+        let r = self.fitted_radii();
+        let round = |c: [f32; 2]| c[0] > 0.0 && c[1] > 0.0;
+        let mut cmds = Vec::with_capacity(10);
+        let [tlx, tly] = if round(r.tl) { r.tl } else { [0.0; 2] };
+        cmds.push(PathCmd::MoveTo([x0 + tlx, y0]));
+        if round(r.tr) {
+            let [rx, ry] = r.tr;
+            cmds.push(PathCmd::LineTo([x1 - rx, y0]));
+            cmds.push(PathCmd::CubicTo(
+                [x1 - rx + K * rx, y0],
+                [x1, y0 + ry - K * ry],
+                [x1, y0 + ry],
+            ));
+        } else {
+            cmds.push(PathCmd::LineTo([x1, y0]));
+        }
+        if round(r.br) {
+            let [rx, ry] = r.br;
+            cmds.push(PathCmd::LineTo([x1, y1 - ry]));
+            cmds.push(PathCmd::CubicTo(
+                [x1, y1 - ry + K * ry],
+                [x1 - rx + K * rx, y1],
+                [x1 - rx, y1],
+            ));
+        } else {
+            cmds.push(PathCmd::LineTo([x1, y1]));
+        }
+        if round(r.bl) {
+            let [rx, ry] = r.bl;
+            cmds.push(PathCmd::LineTo([x0 + rx, y1]));
+            cmds.push(PathCmd::CubicTo(
+                [x0 + rx - K * rx, y1],
+                [x0, y1 - ry + K * ry],
+                [x0, y1 - ry],
+            ));
+        } else {
+            cmds.push(PathCmd::LineTo([x0, y1]));
+        }
+        if round(r.tl) {
+            cmds.push(PathCmd::LineTo([x0, y0 + tly]));
+            cmds.push(PathCmd::CubicTo(
+                [x0, y0 + tly - K * tly],
+                [x0 + tlx - K * tlx, y0],
+                [x0 + tlx, y0],
+            ));
+        } else {
+            cmds.push(PathCmd::LineTo([x0, y0]));
+        }
+        cmds.push(PathCmd::Close);
+        Path {
+            cmds,
+            fill_rule: FillRule::NonZero,
+        }
+    }
+}
+
+/// Per-side border of a box, in CSS order: top, right, bottom, left. The
+/// border lies inside the box's outer edge, and adjacent sides meet along
+/// the line from the outer corner towards the inner one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Border {
+    pub widths: [f32; 4],
+    pub colors: [ColorLinPremul; 4],
+}
+
+impl Border {
+    pub fn uniform(width: f32, color: ColorLinPremul) -> Self {
+        Self {
+            widths: [width; 4],
+            colors: [color; 4],
+        }
+    }
+
+    /// Whether all visible sides share one color, so the border can be
+    /// filled as a single ring.
+    pub fn is_single_color(&self) -> bool {
+        let mut visible = (0..4).filter(|&i| self.widths[i] > 0.0);
+        match visible.next() {
+            Some(first) => visible.all(|i| self.colors[i] == self.colors[first]),
+            None => true,
+        }
+    }
+
+    /// The border ring of `rrect` as an even-odd path: the outer edge and
+    /// the inner edge inset by the widths.
+    pub fn ring(&self, rrect: &RoundedRect) -> Path {
+        let widths = self.widths.map(|w| w.max(0.0));
+        let mut cmds = rrect.to_path().cmds;
+        cmds.extend(rrect.inset(widths).to_path().cmds);
+        Path {
+            cmds,
+            fill_rule: FillRule::EvenOdd,
+        }
+    }
+
+    /// Region each side paints, in CSS order, as quads from the side's two
+    /// outer corners along the join lines to where they leave the corners'
+    /// curved areas. Intersect with [`Border::ring`] to get the side's shape.
+    pub fn side_regions(&self, rrect: &RoundedRect) -> [[[f32; 2]; 4]; 4] {
+        let Rect { x, y, w, h } = rrect.rect;
+        let r = rrect.fitted_radii();
+        let [t, rt, b, l] = self.widths.map(|w| w.max(0.0));
+        // End of the join line for a corner at `o`, pointing inwards along
+        // `s`, between sides of widths `wx` (vertical side) and `wy`.
+        let join = |o: [f32; 2], s: [f32; 2], radii: [f32; 2], wx: f32, wy: f32| {
+            let (ex, ey) = (wx.max(radii[0]), wy.max(radii[1]));
+            let (dx, dy) = if wx > 0.0 || wy > 0.0 {
+                (wx, wy)
+            } else {
+                (1.0, 1.0)
+            };
+            let mut k = f32::INFINITY;
+            if dx > 0.0 {
+                k = k.min(ex / dx);
+            }
+            if dy > 0.0 {
+                k = k.min(ey / dy);
+            }
+            [o[0] + s[0] * dx * k, o[1] + s[1] * dy * k]
+        };
+        let (otl, otr, obr, obl) = ([x, y], [x + w, y], [x + w, y + h], [x, y + h]);
+        let jtl = join(otl, [1.0, 1.0], r.tl, l, t);
+        let jtr = join(otr, [-1.0, 1.0], r.tr, rt, t);
+        let jbr = join(obr, [-1.0, -1.0], r.br, rt, b);
+        let jbl = join(obl, [1.0, -1.0], r.bl, l, b);
+        [
+            [otl, otr, jtr, jtl],
+            [otr, obr, jbr, jtr],
+            [obr, obl, jbl, jbr],
+            [obl, otl, jtl, jbl],
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct ClipRect(pub Rect);
 
-#[derive(Clone, Copy, Debug)]
+/// Region pushed with `Command::PushClip`, in the local space of the
+/// transform active when it is pushed. Nested clips intersect.
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
+pub enum ClipShape {
+    Rect(Rect),
+    RoundedRect(RoundedRect),
+    /// Clip to the inside of the path according to its fill rule.
+    Path(Path),
+}
+
+impl From<ClipRect> for ClipShape {
+    fn from(clip: ClipRect) -> Self {
+        ClipShape::Rect(clip.0)
+    }
+}
+
+/// A pushed clip shape with the transform it was pushed under.
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct Clip {
+    pub shape: ClipShape,
+    pub transform: Transform2D,
+}
+
+/// Clip stacks in effect for the draws of a frame, interned so draws can
+/// refer to theirs by index. Each entry lists the clips a draw lies inside,
+/// outermost first; index 0 is the empty stack, which clips nothing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClipTable {
+    stacks: Vec<Vec<Clip>>,
+    /// Index of each stack by content hash; rebuilt when out of date.
+    #[serde(skip)]
+    index: std::collections::HashMap<u64, u32>,
+}
+
+impl Default for ClipTable {
+    fn default() -> Self {
+        Self {
+            stacks: vec![Vec::new()],
+            index: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl ClipTable {
+    /// Index of `clips`, adding them to the table if they are new.
+    pub fn intern(&mut self, clips: &[Clip]) -> u32 {
+        if self.index.len() != self.stacks.len() {
+            self.index = (self.stacks.iter().enumerate())
+                .map(|(i, stack)| (crate::content_hash(0, stack), i as u32))
+                .collect();
+        }
+        let next = self.stacks.len() as u32;
+        let id = *self
+            .index
+            .entry(crate::content_hash(0, &clips))
+            .or_insert(next);
+        if id == next {
+            self.stacks.push(clips.to_vec());
+        }
+        id
+    }
+
+    /// The clip stack at `id`; empty for an unknown index.
+    pub fn get(&self, id: u32) -> &[Clip] {
+        self.stacks.get(id as usize).map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of stacks, including the empty one.
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// Whether the table holds nothing but the empty stack.
+    pub fn is_empty(&self) -> bool {
+        self.stacks.len() <= 1
+    }
+
+    /// The stacks in index order.
+    pub fn iter(&self) -> impl Iterator<Item = &[Clip]> {
+        self.stacks.iter().map(Vec::as_slice)
+    }
+}
+
+/// Separable blend modes from the W3C Compositing spec, used when a layer is
+/// composited onto its backdrop.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+}
+
+impl BlendMode {
+    /// Every mode, in the order of their shader indices.
+    pub const ALL: [BlendMode; 12] = [
+        BlendMode::Normal,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Overlay,
+        BlendMode::Darken,
+        BlendMode::Lighten,
+        BlendMode::ColorDodge,
+        BlendMode::ColorBurn,
+        BlendMode::HardLight,
+        BlendMode::SoftLight,
+        BlendMode::Difference,
+        BlendMode::Exclusion,
+    ];
+
+    /// Index of the mode in `LAYER_COMPOSITE_WGSL`.
+    pub fn shader_index(self) -> u32 {
+        BlendMode::ALL.iter().position(|m| *m == self).unwrap_or(0) as u32
+    }
+
+    /// Blend one straight-alpha channel of the source `cs` with the backdrop
+    /// `cb`, both in `0.0..=1.0`.
+    pub fn blend_channel(self, cb: f32, cs: f32) -> f32 {
+        match self {
+            BlendMode::Normal => cs,
+            BlendMode::Multiply => cb * cs,
+            BlendMode::Screen => cb + cs - cb * cs,
+            BlendMode::Overlay => BlendMode::HardLight.blend_channel(cs, cb),
+            BlendMode::Darken => cb.min(cs),
+            BlendMode::Lighten => cb.max(cs),
+            BlendMode::ColorDodge => {
+                if cb <= 0.0 {
+                    0.0
+                } else if cs >= 1.0 {
+                    1.0
+                } else {
+                    (cb / (1.0 - cs)).min(1.0)
+                }
+            }
+            BlendMode::ColorBurn => {
+                if cb >= 1.0 {
+                    1.0
+                } else if cs <= 0.0 {
+                    0.0
+                } else {
+                    1.0 - ((1.0 - cb) / cs).min(1.0)
+                }
+            }
+            BlendMode::HardLight => {
+                if cs <= 0.5 {
+                    cb * 2.0 * cs
+                } else {
+                    BlendMode::Screen.blend_channel(cb, 2.0 * cs - 1.0)
+                }
+            }
+            BlendMode::SoftLight => {
+                if cs <= 0.5 {
+                    cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb)
+                } else {
+                    let d = if cb <= 0.25 {
+                        ((16.0 * cb - 12.0) * cb + 4.0) * cb
+                    } else {
+                        cb.sqrt()
+                    };
+                    cb + (2.0 * cs - 1.0) * (d - cb)
+                }
+            }
+            BlendMode::Difference => (cb - cs).abs(),
+            BlendMode::Exclusion => cb + cs - 2.0 * cb * cs,
+        }
+    }
+
+    /// Composite a premultiplied `source` over a premultiplied `backdrop`
+    /// with this blend mode (source-over with the blended color where both
+    /// are opaque).
+    pub fn composite(self, backdrop: [f32; 4], source: [f32; 4]) -> [f32; 4] {
+        let (ab, as_) = (backdrop[3], source[3]);
+        let unpremul = |c: f32, a: f32| {
+            if a > 1e-6 {
+                (c / a).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        let mut out = [0.0; 4];
+        for i in 0..3 {
+            let mixed = self.blend_channel(unpremul(backdrop[i], ab), unpremul(source[i], as_));
+            out[i] = source[i] * (1.0 - ab) + backdrop[i] * (1.0 - as_) + as_ * ab * mixed;
+        }
+        out[3] = as_ + ab * (1.0 - as_);
+        out
+    }
+}
+
+/// Alpha mask applied to a layer when it is composited: content is kept where
+/// `brush` is opaque inside `rect` and dropped outside `rect`.
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct LayerMask {
+    pub rect: Rect,
+    /// Only the alpha of the brush is used; gradient and pattern coordinates
+    /// are in the same space as `rect`.
+    pub brush: Brush,
+    /// Maps `rect` into world space. [`Painter::push_layer`] composes the
+    /// current transform into it.
+    ///
+    /// [`Painter::push_layer`]: crate::Painter::push_layer
+    pub transform: Transform2D,
+}
+
+impl LayerMask {
+    pub fn new(rect: Rect, brush: Brush) -> Self {
+        Self {
+            rect,
+            brush,
+            transform: Transform2D::identity(),
+        }
+    }
+}
+
+/// Image filter, as in CSS `filter` and `backdrop-filter`. Lengths are in
+/// the same logical pixels as geometry.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Filter {
+    /// Gaussian blur with standard deviation `radius`.
+    Blur { radius: f32 },
+    /// Copy of the content's alpha, tinted with `color`, moved by `offset`
+    /// and blurred by `blur` (a CSS blur radius, twice the standard
+    /// deviation), drawn under the content.
+    DropShadow {
+        offset: [f32; 2],
+        blur: f32,
+        color: ColorLinPremul,
+    },
+    /// Row-major 4x5 matrix applied to unpremultiplied linear RGBA:
+    /// `[r', g', b', a'] = M * [r, g, b, a, 1]`, clamped to `0.0..=1.0`.
+    ColorMatrix([f32; 20]),
+}
+
+impl Filter {
+    pub fn blur(radius: f32) -> Self {
+        Filter::Blur {
+            radius: radius.max(0.0),
+        }
+    }
+
+    pub fn drop_shadow(offset: [f32; 2], blur: f32, color: ColorLinPremul) -> Self {
+        Filter::DropShadow {
+            offset,
+            blur: blur.max(0.0),
+            color,
+        }
+    }
+
+    /// Desaturate by `amount` (`0.0` unchanged, `1.0` fully gray).
+    pub fn grayscale(amount: f32) -> Self {
+        let a = 1.0 - amount.clamp(0.0, 1.0);
+        Self::rgb_matrix(
+            [
+                [
+                    0.2126 + 0.7874 * a,
+                    0.7152 - 0.7152 * a,
+                    0.0722 - 0.0722 * a,
+                ],
+                [
+                    0.2126 - 0.2126 * a,
+                    0.7152 + 0.2848 * a,
+                    0.0722 - 0.0722 * a,
+                ],
+                [
+                    0.2126 - 0.2126 * a,
+                    0.7152 - 0.7152 * a,
+                    0.0722 + 0.9278 * a,
+                ],
+            ],
+            0.0,
+        )
+    }
+
+    /// Scale saturation: `0.0` is gray, `1.0` unchanged, above `1.0`
+    /// oversaturated.
+    pub fn saturate(amount: f32) -> Self {
+        let s = amount.max(0.0);
+        Self::rgb_matrix(
+            [
+                [0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s],
+                [0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s],
+                [0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s],
+            ],
+            0.0,
+        )
+    }
+
+    /// Multiply color channels by `amount` (`1.0` unchanged).
+    pub fn brightness(amount: f32) -> Self {
+        let b = amount.max(0.0);
+        Self::rgb_matrix([[b, 0.0, 0.0], [0.0, b, 0.0], [0.0, 0.0, b]], 0.0)
+    }
+
+    /// Scale contrast around mid-gray (`1.0` unchanged, `0.0` flat gray).
+    pub fn contrast(amount: f32) -> Self {
+        let c = amount.max(0.0);
+        Self::rgb_matrix([[c, 0.0, 0.0], [0.0, c, 0.0], [0.0, 0.0, c]], 0.5 - 0.5 * c)
+    }
+
+    /// Color matrix mixing RGB with `rgb` plus `offset`, keeping alpha.
+    fn rgb_matrix(rgb: [[f32; 3]; 3], offset: f32) -> Self {
+        let mut m = [0.0; 20];
+        for (row, coeffs) in rgb.iter().enumerate() {
+            m[row * 5..row * 5 + 3].copy_from_slice(coeffs);
+            m[row * 5 + 4] = offset;
+        }
+        m[18] = 1.0;
+        Filter::ColorMatrix(m)
+    }
+}
+
+/// Apply a [`Filter::ColorMatrix`] matrix to a premultiplied color.
+pub fn apply_color_matrix(m: &[f32; 20], c: [f32; 4]) -> [f32; 4] {
+    let src = if c[3] > 0.0 {
+        [c[0] / c[3], c[1] / c[3], c[2] / c[3], c[3]]
+    } else {
+        [0.0; 4]
+    };
+    let mut out = [0.0f32; 4];
+    for (row, o) in out.iter_mut().enumerate() {
+        let r = &m[row * 5..row * 5 + 5];
+        *o = (r[0] * src[0] + r[1] * src[1] + r[2] * src[2] + r[3] * src[3] + r[4]).clamp(0.0, 1.0);
+    }
+    [out[0] * out[3], out[1] * out[3], out[2] * out[3], out[3]]
+}
+
+/// `backdrop-filter` of a layer: what is drawn behind the layer inside
+/// `rrect` is replaced with its filtered copy before the layer's content is
+/// composited, e.g. frosted glass behind a modal.
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct BackdropFilter {
+    pub rrect: RoundedRect,
+    pub filters: Vec<Filter>,
+    /// Z-index at which the backdrop is sampled and replaced. The layer is
+    /// composited at this z-index if its content is higher or empty.
+    pub z: i32,
+    /// Maps `rrect` into world space. [`Painter::push_layer_with_filters`]
+    /// composes the current transform into it.
+    ///
+    /// [`Painter::push_layer_with_filters`]: crate::Painter::push_layer_with_filters
+    pub transform: Transform2D,
+}
+
+impl BackdropFilter {
+    pub fn new(rrect: RoundedRect, filters: Vec<Filter>, z: i32) -> Self {
+        Self {
+            rrect,
+            filters,
+            z,
+            transform: Transform2D::identity(),
+        }
+    }
+}
+
+/// Shape of the open ends of a stroke and of each dash.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LineCap {
+    /// Ends flush with the endpoint.
+    #[default]
+    Butt,
+    /// Ends half the width past the endpoint.
+    Square,
+    Round,
+}
+
+/// Shape of the corner where two stroked segments meet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LineJoin {
+    /// Sharp corner, beveled when the miter is longer than
+    /// [`Stroke::miter_limit`].
+    #[default]
+    Miter,
+    Bevel,
+    Round,
+}
+
+/// Stroke style, with SVG's defaults: [`Stroke::new`] draws a solid line
+/// with butt caps and miter joins limited to 4 widths.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Stroke {
     pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Longest miter, in multiples of `width`, before a miter join is
+    /// beveled. At least 1.
+    pub miter_limit: f32,
+    /// Alternating dash and gap lengths; empty for a solid line. An odd
+    /// number of lengths is repeated to make an even one.
+    pub dash: Vec<f32>,
+    /// Distance into the dash pattern at which each subpath starts.
+    pub dash_offset: f32,
 }
 
-#[derive(Clone, Copy, Debug)]
+impl Stroke {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+
+    /// Round caps and joins, e.g. for carets and freehand lines.
+    pub fn round(width: f32) -> Self {
+        Self::new(width)
+            .with_cap(LineCap::Round)
+            .with_join(LineJoin::Round)
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Dash the stroke with `dash` lengths, starting `offset` into the
+    /// pattern.
+    pub fn with_dash(mut self, dash: Vec<f32>, offset: f32) -> Self {
+        self.dash = dash;
+        self.dash_offset = offset;
+        self
+    }
+
+    /// Whether the dash pattern produces dashes; invalid patterns (negative
+    /// lengths, or all zero) draw a solid line, as in SVG.
+    pub fn is_dashed(&self) -> bool {
+        dash_pattern(&self.dash).is_some()
+    }
+
+    /// Whether a `StrokeRect` with this stroke is a plain ring with square
+    /// corners: solid, with miter joins that don't bevel at right angles.
+    pub(crate) fn has_square_corners(&self) -> bool {
+        !self.is_dashed()
+            && self.join == LineJoin::Miter
+            && self.miter_limit >= std::f32::consts::SQRT_2
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct BoxShadowSpec {
     pub offset: [f32; 2],
     pub spread: f32,
@@ -129,23 +1003,167 @@ pub enum Shape {
     RoundedRect(RoundedRect),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TextRun {
     pub text: String,
     pub pos: [f32; 2],
     pub size: f32,
     pub color: ColorLinPremul,
+    /// Styled byte ranges of `text`. Empty for a plain single-style run.
+    pub spans: Vec<TextSpan>,
+}
+
+impl TextRun {
+    /// Plain run with no attributed spans.
+    pub fn new(text: impl Into<String>, pos: [f32; 2], size: f32, color: ColorLinPremul) -> Self {
+        Self {
+            text: text.into(),
+            pos,
+            size,
+            color,
+            spans: Vec::new(),
+        }
+    }
+
+    /// Style the UTF-8 byte `range` of the text with `attrs`.
+    pub fn with_span(mut self, range: std::ops::Range<usize>, attrs: TextAttrs) -> Self {
+        self.spans.push(TextSpan { range, attrs });
+        self
+    }
+
+    pub fn is_attributed(&self) -> bool {
+        !self.spans.is_empty()
+    }
+
+    /// Resolve `spans` into contiguous segments covering the whole text.
+    ///
+    /// Bytes not covered by any span get default attributes; where spans
+    /// overlap the later one wins. Span bounds are clamped to the text and
+    /// moved back to the nearest char boundary. Adjacent segments with equal
+    /// attributes are merged.
+    pub fn segments(&self) -> Vec<(std::ops::Range<usize>, TextAttrs)> {
+        let len = self.text.len();
+        if len == 0 {
+            return Vec::new();
+        }
+        let floor = |mut i: usize| {
+            i = i.min(len);
+            while !self.text.is_char_boundary(i) {
+                i -= 1;
+            }
+            i
+        };
+        let spans: Vec<(usize, usize, &TextAttrs)> = self
+            .spans
+            .iter()
+            .map(|s| (floor(s.range.start), floor(s.range.end), &s.attrs))
+            .filter(|(start, end, _)| start < end)
+            .collect();
+
+        let mut bounds = vec![0, len];
+        for (start, end, _) in &spans {
+            bounds.push(*start);
+            bounds.push(*end);
+        }
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let default = TextAttrs::default();
+        let mut out: Vec<(std::ops::Range<usize>, TextAttrs)> = Vec::new();
+        for w in bounds.windows(2) {
+            let (start, end) = (w[0], w[1]);
+            let attrs = spans
+                .iter()
+                .rev()
+                .find(|(s, e, _)| *s <= start && end <= *e)
+                .map(|(_, _, a)| *a)
+                .unwrap_or(&default);
+            match out.last_mut() {
+                Some((range, last)) if last == attrs => range.end = end,
+                _ => out.push((start..end, attrs.clone())),
+            }
+        }
+        out
+    }
+
+    /// Copy of `spans` with explicit sizes multiplied by `scale`, for
+    /// rasterizing a run whose base `size` has been scaled the same way.
+    pub fn scaled_spans(&self, scale: f32) -> Vec<TextSpan> {
+        self.spans
+            .iter()
+            .map(|s| {
+                let mut s = s.clone();
+                s.attrs.size = s.attrs.size.map(|v| v * scale);
+                s
+            })
+            .collect()
+    }
+}
+
+/// A styled byte range within a [`TextRun`].
+#[derive(Clone, Debug, PartialEq, Hash, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub range: std::ops::Range<usize>,
+    pub attrs: TextAttrs,
+}
+
+/// Per-span text attributes. `None` fields inherit from the enclosing run
+/// (`family` falls back to the provider's primary font).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextAttrs {
+    pub family: Option<String>,
+    pub weight: FontWeight,
+    pub style: FontStyle,
+    pub size: Option<f32>,
+    pub color: Option<ColorLinPremul>,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub background: Option<ColorLinPremul>,
+    /// OpenType features such as `tnum` or `liga` off, applied to the span
+    /// and its fallback faces.
+    pub features: Vec<rune_text::FontFeature>,
+    /// Variable-font axis values such as `wght` 650. Variable faces without
+    /// an explicit `wght` take it from `weight`.
+    pub variations: Vec<rune_text::FontVariation>,
+}
+
+/// CSS-style numeric font weight (100..=900).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    pub const THIN: Self = Self(100);
+    pub const LIGHT: Self = Self(300);
+    pub const NORMAL: Self = Self(400);
+    pub const MEDIUM: Self = Self(500);
+    pub const SEMIBOLD: Self = Self(600);
+    pub const BOLD: Self = Self(700);
+    pub const BLACK: Self = Self(900);
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    Italic,
+    Oblique,
 }
 
 // --- Path geometry (for SVG import / lyon) ---
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Hash, Serialize, Deserialize)]
 pub enum FillRule {
     NonZero,
     EvenOdd,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PathCmd {
     MoveTo([f32; 2]),
     LineTo([f32; 2]),
@@ -154,16 +1172,226 @@ pub enum PathCmd {
     Close,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
 pub struct Path {
     pub cmds: Vec<PathCmd>,
     pub fill_rule: FillRule,
 }
 
+impl Path {
+    /// Bounding box of the path's points, including curve control points;
+    /// `None` for an empty path.
+    pub fn bounds(&self) -> Option<Rect> {
+        let mut minx = f32::INFINITY;
+        let mut miny = f32::INFINITY;
+        let mut maxx = f32::NEG_INFINITY;
+        let mut maxy = f32::NEG_INFINITY;
+        let mut any = false;
+        for cmd in &self.cmds {
+            match *cmd {
+                PathCmd::MoveTo(p) | PathCmd::LineTo(p) => {
+                    minx = minx.min(p[0]);
+                    miny = miny.min(p[1]);
+                    maxx = maxx.max(p[0]);
+                    maxy = maxy.max(p[1]);
+                    any = true;
+                }
+                PathCmd::QuadTo(c, p) => {
+                    for q in [c, p] {
+                        minx = minx.min(q[0]);
+                        miny = miny.min(q[1]);
+                        maxx = maxx.max(q[0]);
+                        maxy = maxy.max(q[1]);
+                    }
+                    any = true;
+                }
+                PathCmd::CubicTo(c1, c2, p) => {
+                    for q in [c1, c2, p] {
+                        minx = minx.min(q[0]);
+                        miny = miny.min(q[1]);
+                        maxx = maxx.max(q[0]);
+                        maxy = maxy.max(q[1]);
+                    }
+                    any = true;
+                }
+                PathCmd::Close => {}
+            }
+        }
+        if any {
+            Some(Rect {
+                x: minx,
+                y: miny,
+                w: (maxx - minx).max(0.0),
+                h: (maxy - miny).max(0.0),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Flatten the path into polylines, one per subpath.
+    pub(crate) fn flatten(&self) -> Vec<Contour> {
+        fn segments(ctrl_len: f32) -> usize {
+            ((ctrl_len / 4.0).ceil() as usize).clamp(2, 64)
+        }
+        fn dist(a: [f32; 2], b: [f32; 2]) -> f32 {
+            (b[0] - a[0]).hypot(b[1] - a[1])
+        }
+
+        let mut contours: Vec<Contour> = Vec::new();
+        let mut current: Vec<[f32; 2]> = Vec::new();
+        let mut pen = [0.0f32; 2];
+        let mut finish = |current: &mut Vec<[f32; 2]>, closed: bool| {
+            if !current.is_empty() {
+                contours.push(Contour {
+                    points: std::mem::take(current),
+                    closed,
+                });
+            }
+        };
+        for cmd in &self.cmds {
+            match *cmd {
+                PathCmd::MoveTo(p) => {
+                    finish(&mut current, false);
+                    current.push(p);
+                    pen = p;
+                }
+                PathCmd::LineTo(p) => {
+                    if current.is_empty() {
+                        current.push(pen);
+                    }
+                    current.push(p);
+                    pen = p;
+                }
+                PathCmd::QuadTo(c, p) => {
+                    if current.is_empty() {
+                        current.push(pen);
+                    }
+                    let n = segments(dist(pen, c) + dist(c, p));
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let mt = 1.0 - t;
+                        current.push([
+                            mt * mt * pen[0] + 2.0 * mt * t * c[0] + t * t * p[0],
+                            mt * mt * pen[1] + 2.0 * mt * t * c[1] + t * t * p[1],
+                        ]);
+                    }
+                    pen = p;
+                }
+                PathCmd::CubicTo(c1, c2, p) => {
+                    if current.is_empty() {
+                        current.push(pen);
+                    }
+                    let n = segments(dist(pen, c1) + dist(c1, c2) + dist(c2, p));
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let mt = 1.0 - t;
+                        let (a, b, c, d) =
+                            (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+                        current.push([
+                            a * pen[0] + b * c1[0] + c * c2[0] + d * p[0],
+                            a * pen[1] + b * c1[1] + c * c2[1] + d * p[1],
+                        ]);
+                    }
+                    pen = p;
+                }
+                PathCmd::Close => {
+                    if let Some(&start) = current.first() {
+                        pen = start;
+                    }
+                    finish(&mut current, true);
+                }
+            }
+        }
+        finish(&mut current, false);
+        contours
+    }
+
+    /// The path cut into open dashes along `dash`, alternating dash and gap
+    /// lengths starting `offset` into the pattern; each subpath restarts
+    /// the pattern, as in SVG. Curves are flattened. Returns the path
+    /// unchanged if the pattern is empty, negative or sums to zero.
+    pub fn dashed(&self, dash: &[f32], offset: f32) -> Path {
+        let Some(pattern) = dash_pattern(dash) else {
+            return self.clone();
+        };
+        let total: f32 = pattern.iter().sum();
+        let mut cmds = Vec::new();
+        for contour in self.flatten() {
+            let mut points = contour.points;
+            if contour.closed && points.len() > 1 {
+                points.push(points[0]);
+            }
+            let Some(&start) = points.first() else {
+                continue;
+            };
+            // Find where `offset` falls in the pattern.
+            let mut i = 0;
+            let mut phase = offset.rem_euclid(total);
+            while phase > pattern[i] {
+                phase -= pattern[i];
+                i = (i + 1) % pattern.len();
+            }
+            let mut left = pattern[i] - phase;
+            let mut on = i % 2 == 0;
+            if on {
+                cmds.push(PathCmd::MoveTo(start));
+            }
+            for seg in points.windows(2) {
+                let (a, b) = (seg[0], seg[1]);
+                let len = (b[0] - a[0]).hypot(b[1] - a[1]);
+                let mut t = 0.0;
+                while len - t > left {
+                    t += left;
+                    let f = t / len;
+                    let p = [a[0] + (b[0] - a[0]) * f, a[1] + (b[1] - a[1]) * f];
+                    cmds.push(if on {
+                        PathCmd::LineTo(p)
+                    } else {
+                        PathCmd::MoveTo(p)
+                    });
+                    on = !on;
+                    i = (i + 1) % pattern.len();
+                    left = pattern[i];
+                }
+                left -= len - t;
+                if on {
+                    cmds.push(PathCmd::LineTo(b));
+                }
+            }
+        }
+        Path {
+            cmds,
+            fill_rule: self.fill_rule,
+        }
+    }
+}
+
+/// A flattened subpath, see [`Path::flatten`].
+#[derive(Clone, Debug)]
+pub(crate) struct Contour {
+    pub(crate) points: Vec<[f32; 2]>,
+    pub(crate) closed: bool,
+}
+
+/// `dash` made even-length (an odd list repeats, as in SVG), or `None` if
+/// it can't produce dashes.
+fn dash_pattern(dash: &[f32]) -> Option<Vec<f32>> {
+    let valid = dash.iter().all(|d| d.is_finite() && *d >= 0.0);
+    if !valid || dash.iter().sum::<f32>() <= 0.0 {
+        return None;
+    }
+    Some(if dash.len() % 2 == 1 {
+        dash.repeat(2)
+    } else {
+        dash.to_vec()
+    })
+}
+
 // --- Hyperlink ---
 
 /// Hyperlink element combining text, optional underline, and a URL target.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Hyperlink {
     /// The text content to display
     pub text: String,
@@ -180,3 +1408,191 @@ pub struct Hyperlink {
     /// Underline color (if None, uses text color)
     pub underline_color: Option<ColorLinPremul>,
 }
+
+// --- Hashing ---
+//
+// Float fields hash by their bits, so values that compare equal but differ
+// in bits (0.0 and -0.0) hash differently. That is fine for change
+// detection, where a spurious difference only costs a repaint.
+
+pub(crate) fn hash_f32s<H: Hasher>(values: &[f32], state: &mut H) {
+    for v in values {
+        v.to_bits().hash(state);
+    }
+}
+
+impl Hash for Transform2D {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32s(&self.m, state);
+    }
+}
+
+impl Hash for ColorLinPremul {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32s(&[self.r, self.g, self.b, self.a], state);
+    }
+}
+
+impl Hash for Brush {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        fn hash_stops<H: Hasher>(stops: &[(f32, ColorLinPremul)], state: &mut H) {
+            stops.len().hash(state);
+            for (offset, color) in stops {
+                offset.to_bits().hash(state);
+                color.hash(state);
+            }
+        }
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Brush::Solid(color) => color.hash(state),
+            Brush::LinearGradient {
+                start,
+                end,
+                stops,
+                spread,
+            } => {
+                hash_f32s(start, state);
+                hash_f32s(end, state);
+                hash_stops(stops, state);
+                spread.hash(state);
+            }
+            Brush::RadialGradient {
+                center,
+                radius,
+                stops,
+                spread,
+            } => {
+                hash_f32s(center, state);
+                radius.to_bits().hash(state);
+                hash_stops(stops, state);
+                spread.hash(state);
+            }
+            Brush::ConicGradient {
+                center,
+                angle,
+                stops,
+                spread,
+            } => {
+                hash_f32s(center, state);
+                angle.to_bits().hash(state);
+                hash_stops(stops, state);
+                spread.hash(state);
+            }
+            Brush::Image(pattern) => pattern.hash(state),
+        }
+    }
+}
+
+impl Hash for Rect {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32s(&[self.x, self.y, self.w, self.h], state);
+    }
+}
+
+impl Hash for RoundedRadii {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for corner in [self.tl, self.tr, self.br, self.bl] {
+            hash_f32s(&corner, state);
+        }
+    }
+}
+
+impl Hash for Border {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32s(&self.widths, state);
+        self.colors.hash(state);
+    }
+}
+
+impl Hash for Filter {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Filter::Blur { radius } => radius.to_bits().hash(state),
+            Filter::DropShadow {
+                offset,
+                blur,
+                color,
+            } => {
+                hash_f32s(offset, state);
+                blur.to_bits().hash(state);
+                color.hash(state);
+            }
+            Filter::ColorMatrix(m) => hash_f32s(m, state),
+        }
+    }
+}
+
+impl Hash for Stroke {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32s(&[self.width, self.miter_limit, self.dash_offset], state);
+        self.cap.hash(state);
+        self.join.hash(state);
+        self.dash.len().hash(state);
+        hash_f32s(&self.dash, state);
+    }
+}
+
+impl Hash for BoxShadowSpec {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32s(&self.offset, state);
+        hash_f32s(&[self.spread, self.blur_radius], state);
+        self.color.hash(state);
+    }
+}
+
+impl Hash for TextRun {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+        hash_f32s(&self.pos, state);
+        self.size.to_bits().hash(state);
+        self.color.hash(state);
+        self.spans.hash(state);
+    }
+}
+
+impl Hash for TextAttrs {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.family.hash(state);
+        self.weight.hash(state);
+        self.style.hash(state);
+        self.size.map(f32::to_bits).hash(state);
+        self.color.hash(state);
+        self.underline.hash(state);
+        self.strikethrough.hash(state);
+        self.background.hash(state);
+        self.features.hash(state);
+        self.variations.hash(state);
+    }
+}
+
+impl Hash for PathCmd {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            PathCmd::MoveTo(p) | PathCmd::LineTo(p) => hash_f32s(p, state),
+            PathCmd::QuadTo(c, p) => {
+                hash_f32s(c, state);
+                hash_f32s(p, state);
+            }
+            PathCmd::CubicTo(c1, c2, p) => {
+                hash_f32s(c1, state);
+                hash_f32s(c2, state);
+                hash_f32s(p, state);
+            }
+            PathCmd::Close => {}
+        }
+    }
+}
+
+impl Hash for Hyperlink {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+        hash_f32s(&self.pos, state);
+        self.size.to_bits().hash(state);
+        self.color.hash(state);
+        self.url.hash(state);
+        self.underline.hash(state);
+        self.underline_color.hash(state);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::scene::ColorLinPremul;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Optional style overrides for SVG rendering
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SvgStyle {
     /// Override fill color (replaces all fill colors in the SVG)
     pub fill: Option<ColorLinPremul>,
@@ -45,6 +47,14 @@ impl Default for SvgStyle {
     }
 }
 
+impl Hash for SvgStyle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.fill.hash(state);
+        self.stroke.hash(state);
+        self.stroke_width.map(f32::to_bits).hash(state);
+    }
+}
+
 /// Hash-friendly version of SvgStyle for cache keys
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct SvgStyleKey {
@@ -289,7 +299,9 @@ impl SvgRasterCache {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
         });
         queue.write_texture(
@@ -328,7 +340,7 @@ impl SvgRasterCache {
 
 /// Apply style overrides by modifying the SVG XML
 /// This replaces stroke="currentColor", fill colors, and stroke-width attributes
-fn apply_style_overrides_to_xml(data: &[u8], style: SvgStyle) -> Option<Vec<u8>> {
+pub(crate) fn apply_style_overrides_to_xml(data: &[u8], style: SvgStyle) -> Option<Vec<u8>> {
     let mut svg_str = String::from_utf8(data.to_vec()).ok()?;
 
     // Replace stroke color
@@ -417,6 +429,26 @@ fn transform2d_from_usvg(t: usvg::Transform) -> crate::scene::Transform2D {
     }
 }
 
+fn stroke_from_usvg(st: &usvg::Stroke) -> crate::scene::Stroke {
+    use crate::scene::{LineCap, LineJoin, Stroke};
+    let stroke = Stroke::new(st.width().get())
+        .with_cap(match st.linecap() {
+            usvg::LineCap::Butt => LineCap::Butt,
+            usvg::LineCap::Square => LineCap::Square,
+            usvg::LineCap::Round => LineCap::Round,
+        })
+        .with_join(match st.linejoin() {
+            usvg::LineJoin::Miter | usvg::LineJoin::MiterClip => LineJoin::Miter,
+            usvg::LineJoin::Bevel => LineJoin::Bevel,
+            usvg::LineJoin::Round => LineJoin::Round,
+        })
+        .with_miter_limit(st.miterlimit().get());
+    match st.dasharray() {
+        Some(dash) => stroke.with_dash(dash.to_vec(), st.dashoffset()),
+        None => stroke,
+    }
+}
+
 fn fill_rule_from_usvg(rule: usvg::FillRule) -> crate::scene::FillRule {
     match rule {
         usvg::FillRule::NonZero => crate::scene::FillRule::NonZero,
@@ -430,7 +462,7 @@ fn import_path_fill(
     painter: &mut crate::painter::Painter,
     node_transform: usvg::Transform,
     p: &usvg::Path,
-    color: crate::scene::ColorLinPremul,
+    brush: crate::scene::Brush,
     stats: &mut SvgImportStats,
 ) {
     use crate::scene::{Path, PathCmd};
@@ -460,7 +492,7 @@ fn import_path_fill(
     let path = Path { cmds, fill_rule };
     let t = transform2d_from_usvg(node_transform);
     painter.push_transform(t);
-    painter.fill_path(path, color, 0);
+    painter.fill_path(path, brush, 0);
     painter.pop_transform();
     stats.paths += 1;
 }
@@ -542,13 +574,95 @@ fn detect_axis_aligned_rect(p: &usvg::Path) -> Option<crate::scene::Rect> {
     })
 }
 
-fn paint_from_fill(fill: &usvg::Fill) -> Option<crate::scene::Brush> {
-    match fill.paint() {
-        usvg::Paint::Color(c) => Some(crate::scene::Brush::Solid(color_from_usvg(
-            *c,
-            fill.opacity().get() as f32,
-        ))),
-        _ => None,
+/// Map a usvg paint to a brush in the painted path's local space. `None` for
+/// patterns and for gradients a `Brush` can't express: a focal point, or a
+/// radial gradient under a skew or non-uniform scale.
+fn brush_from_paint(paint: &usvg::Paint, opacity: f32) -> Option<crate::scene::Brush> {
+    use crate::scene::Brush;
+    match paint {
+        usvg::Paint::Color(c) => Some(Brush::Solid(color_from_usvg(*c, opacity))),
+        usvg::Paint::LinearGradient(g) => {
+            let t = transform2d_from_usvg(g.transform());
+            let [a, b, c, d, _, _] = t.m;
+            let v = [g.x2() - g.x1(), g.y2() - g.y1()];
+            let len2 = v[0] * v[0] + v[1] * v[1];
+            let det = a * d - b * c;
+            if len2 < 1e-12 || det.abs() < 1e-12 {
+                return None;
+            }
+            // The ramp parameter is dot(T⁻¹p - p1, v) / |v|², so in local
+            // space it grows along T⁻ᵀv / |v|². Fold the gradient transform
+            // into an equivalent untransformed start/end pair.
+            let gx = (d * v[0] - b * v[1]) / (det * len2);
+            let gy = (a * v[1] - c * v[0]) / (det * len2);
+            let g2 = gx * gx + gy * gy;
+            let start = t.apply([g.x1(), g.y1()]);
+            Some(Brush::LinearGradient {
+                start,
+                end: [start[0] + gx / g2, start[1] + gy / g2],
+                stops: stops_from_usvg(g.stops(), g.spread_method(), opacity),
+                spread: spread_from_usvg(g.spread_method()),
+            })
+        }
+        usvg::Paint::RadialGradient(g) => {
+            let focal = (g.fx() - g.cx()).abs() > 1e-3 || (g.fy() - g.cy()).abs() > 1e-3;
+            if focal {
+                return None;
+            }
+            let t = transform2d_from_usvg(g.transform());
+            let [a, b, c, d, _, _] = t.m;
+            // Only similarity transforms keep the circle a circle.
+            let (sx2, sy2) = (a * a + b * b, c * c + d * d);
+            if (sx2 - sy2).abs() > 1e-4 * sx2.max(sy2) || (a * c + b * d).abs() > 1e-4 * sx2 {
+                return None;
+            }
+            Some(Brush::RadialGradient {
+                center: t.apply([g.cx(), g.cy()]),
+                radius: g.r().get() * sx2.sqrt(),
+                stops: stops_from_usvg(g.stops(), g.spread_method(), opacity),
+                spread: spread_from_usvg(g.spread_method()),
+            })
+        }
+        usvg::Paint::Pattern(_) => None,
+    }
+}
+
+/// Gradient stops; under a repeating spread they're padded to `[0, 1]` so
+/// the ramp repeats per gradient vector, as SVG specifies, rather than over
+/// the stops' span.
+fn stops_from_usvg(
+    stops: &[usvg::Stop],
+    spread: usvg::SpreadMethod,
+    opacity: f32,
+) -> Vec<(f32, crate::scene::ColorLinPremul)> {
+    let mut out: Vec<(f32, crate::scene::ColorLinPremul)> = stops
+        .iter()
+        .map(|s| {
+            (
+                s.offset().get(),
+                color_from_usvg(s.color(), s.opacity().get() * opacity),
+            )
+        })
+        .collect();
+    if spread == usvg::SpreadMethod::Pad {
+        return out;
+    }
+    if let (Some(&(first, c0)), Some(&(last, c1))) = (out.first(), out.last()) {
+        if first > 0.0 {
+            out.insert(0, (0.0, c0));
+        }
+        if last < 1.0 {
+            out.push((1.0, c1));
+        }
+    }
+    out
+}
+
+fn spread_from_usvg(spread: usvg::SpreadMethod) -> crate::scene::SpreadMode {
+    match spread {
+        usvg::SpreadMethod::Pad => crate::scene::SpreadMode::Pad,
+        usvg::SpreadMethod::Reflect => crate::scene::SpreadMode::Reflect,
+        usvg::SpreadMethod::Repeat => crate::scene::SpreadMode::Repeat,
     }
 }
 
@@ -556,7 +670,8 @@ fn paint_from_fill(fill: &usvg::Fill) -> Option<crate::scene::Brush> {
 ///
 /// Notes:
 /// - Supports Rect/RoundedRect/Circle/Ellipse and basic filled Paths.
-/// - Only solid fills are mapped. Unsupported paints/filters/masks/text are skipped.
+/// - Solid colors and linear/radial gradients are mapped for fills and strokes.
+///   Patterns and other unsupported paints, filters, masks and text are skipped.
 pub fn import_svg_geometry_to_painter(
     painter: &mut crate::painter::Painter,
     path: &Path,
@@ -577,38 +692,38 @@ pub fn import_svg_geometry_to_painter(
             match node {
                 usvg::Node::Path(p) => {
                     if let Some(fill) = p.fill() {
-                        if let Some(crate::scene::Brush::Solid(col)) = paint_from_fill(fill) {
-                            // Try fast-path: detect simple axis-aligned rectangle and emit as a primitive
-                            if let Some(rect) = detect_axis_aligned_rect(p) {
+                        if let Some(brush) = brush_from_paint(fill.paint(), fill.opacity().get()) {
+                            // Fast path: emit a simple axis-aligned solid rectangle as a primitive.
+                            // Gradients stay paths, which place them in local coordinates.
+                            let rect = match brush {
+                                crate::scene::Brush::Solid(_) => detect_axis_aligned_rect(p),
+                                _ => None,
+                            };
+                            if let Some(rect) = rect {
                                 let t = transform2d_from_usvg(p.abs_transform());
                                 painter.push_transform(t);
-                                painter.rect(rect, crate::scene::Brush::Solid(col), 0);
+                                painter.rect(rect, brush, 0);
                                 painter.pop_transform();
                                 stats.rects += 1;
                             } else {
-                                import_path_fill(painter, p.abs_transform(), p, col, stats);
+                                import_path_fill(painter, p.abs_transform(), p, brush, stats);
                             }
                         } else {
-                            // Unsupported paint servers (gradients/patterns) are skipped for geometry import.
+                            // Unsupported paint servers (patterns, focal gradients…) are skipped.
                             stats.skipped += 1;
                         }
                     }
-                    // Stroke (solid-only for now)
                     if let Some(st) = p.stroke() {
-                        if let usvg::Paint::Color(c) = st.paint() {
-                            let col = color_from_usvg(*c, st.opacity().get() as f32);
+                        if let Some(brush) = brush_from_paint(st.paint(), st.opacity().get()) {
+                            let rect = match brush {
+                                crate::scene::Brush::Solid(_) => detect_axis_aligned_rect(p),
+                                _ => None,
+                            };
                             // If the path is a simple rect, stroke it via the rect stroke primitive
-                            if let Some(rect) = detect_axis_aligned_rect(p) {
+                            if let Some(rect) = rect {
                                 let t = transform2d_from_usvg(p.abs_transform());
                                 painter.push_transform(t);
-                                painter.stroke_rect(
-                                    rect,
-                                    crate::scene::Stroke {
-                                        width: st.width().get() as f32,
-                                    },
-                                    crate::scene::Brush::Solid(col),
-                                    0,
-                                );
+                                painter.stroke_rect(rect, stroke_from_usvg(st), brush, 0);
                                 painter.pop_transform();
                                 stats.strokes += 1;
                             } else {
@@ -644,14 +759,7 @@ pub fn import_svg_geometry_to_painter(
                                 };
                                 let t = transform2d_from_usvg(p.abs_transform());
                                 painter.push_transform(t);
-                                painter.stroke_path(
-                                    epath,
-                                    crate::scene::Stroke {
-                                        width: st.width().get() as f32,
-                                    },
-                                    col,
-                                    0,
-                                );
+                                painter.stroke_path(epath, stroke_from_usvg(st), brush, 0);
                                 painter.pop_transform();
                                 stats.strokes += 1;
                             }
@@ -695,7 +803,7 @@ pub fn svg_intrinsic_size(path: &Path) -> Option<(u32, u32)> {
 
 /// Determine if an SVG requires rasterization or can be rendered as vector geometry.
 /// Returns true if the SVG uses features that cannot be expressed analytically
-/// (filters, patterns, masks, gradients, images, text, etc.)
+/// (filters, patterns, masks, images, text, gradients a `Brush` can't express, etc.)
 pub fn svg_requires_rasterization(path: &Path) -> Option<bool> {
     let data = std::fs::read(path).ok()?;
     let mut opt = usvg::Options::default();
@@ -705,17 +813,15 @@ pub fn svg_requires_rasterization(path: &Path) -> Option<bool> {
     fn check_node(node: &usvg::Node) -> bool {
         match node {
             usvg::Node::Path(p) => {
-                // Check if fill uses non-solid paint (gradients, patterns)
+                // Check if fill or stroke uses a paint without a Brush equivalent
                 if let Some(fill) = p.fill() {
-                    if !matches!(fill.paint(), usvg::Paint::Color(_)) {
-                        return true; // Gradient or pattern fill
+                    if brush_from_paint(fill.paint(), 1.0).is_none() {
+                        return true; // Pattern or unsupported gradient fill
                     }
                 }
-
-                // Check if stroke uses non-solid paint
                 if let Some(stroke) = p.stroke() {
-                    if !matches!(stroke.paint(), usvg::Paint::Color(_)) {
-                        return true; // Gradient or pattern stroke
+                    if brush_from_paint(stroke.paint(), 1.0).is_none() {
+                        return true; // Pattern or unsupported gradient stroke
                     }
                 }
 
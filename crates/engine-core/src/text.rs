@@ -13,7 +13,7 @@
 //!
 //! # Example
 //! ```no_run
-//! use engine_core::{RuneTextProvider, SubpixelOrientation, TextRun, ColorLinPremul};
+//! use engine_core::{RuneTextProvider, SubpixelOrientation, TextProvider, TextRun, ColorLinPremul};
 //!
 //! let provider = RuneTextProvider::from_system_fonts(SubpixelOrientation::RGB)
 //!     .expect("Failed to load fonts");
@@ -23,11 +23,13 @@
 //!     pos: [0.0, 0.0],
 //!     size: 16.0,
 //!     color: ColorLinPremul::rgba(255, 255, 255, 255),
+//!     spans: Vec::new(),
 //! };
 //!
 //! let glyphs = provider.rasterize_run(&run);
 //! ```
 
+use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 
 /// LCD subpixel orientation along X axis.
@@ -38,15 +40,19 @@ pub enum SubpixelOrientation {
 }
 
 /// Storage format for a subpixel coverage mask.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MaskFormat {
     Rgba8,
     Rgba16,
+    /// Color glyph image (emoji, `COLR` glyphs): premultiplied sRGB RGBA,
+    /// 4 bytes/pixel. Drawn as is rather than tinted with the text color;
+    /// only the color's alpha applies.
+    ColorRgba8,
 }
 
 /// Subpixel mask in RGB coverage format stored in RGBA (A is unused).
 /// Supports 8-bit or 16-bit per-channel storage.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SubpixelMask {
     pub width: u32,
     pub height: u32,
@@ -58,10 +64,15 @@ pub struct SubpixelMask {
 impl SubpixelMask {
     pub fn bytes_per_pixel(&self) -> usize {
         match self.format {
-            MaskFormat::Rgba8 => 4,
+            MaskFormat::Rgba8 | MaskFormat::ColorRgba8 => 4,
             MaskFormat::Rgba16 => 8,
         }
     }
+
+    /// Whether this is a color glyph image rather than a coverage mask.
+    pub fn is_color(&self) -> bool {
+        self.format == MaskFormat::ColorRgba8
+    }
 }
 
 /// GPU-ready batch of glyph masks with positions and color.
@@ -91,34 +102,46 @@ impl GlyphBatch {
     }
 }
 
-/// Simple global cache for glyph runs keyed by (text, size, provider pointer).
-/// Used by direct text rendering paths (e.g., rune-surface Canvas) to avoid
-/// re-shaping and re-rasterizing identical text on every frame.
+/// Simple global cache for glyph runs keyed by (text, size, span attributes,
+/// provider pointer). Used by direct text rendering paths (e.g., rune-surface
+/// Canvas) to avoid re-shaping and re-rasterizing identical text on every frame.
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
 struct GlyphRunKey {
     text_hash: u64,
     size_bits: u32,
+    attrs_hash: u64,
     provider_id: usize,
+    /// Run color, for runs with color glyphs whose layers use it.
+    color_bits: Option<[u32; 4]>,
 }
 
 struct GlyphRunCache {
-    map: std::sync::Mutex<
-        std::collections::HashMap<GlyphRunKey, std::sync::Arc<Vec<RasterizedGlyph>>>,
-    >,
+    map: std::sync::Mutex<GlyphRunMap>,
     max_entries: usize,
 }
 
+/// Cached runs with the tick they were last used at.
+#[derive(Default)]
+struct GlyphRunMap {
+    runs: std::collections::HashMap<GlyphRunKey, (std::sync::Arc<Vec<RasterizedGlyph>>, u64)>,
+    tick: u64,
+}
+
 impl GlyphRunCache {
     fn new(max_entries: usize) -> Self {
         Self {
-            map: std::sync::Mutex::new(std::collections::HashMap::new()),
+            map: std::sync::Mutex::new(GlyphRunMap::default()),
             max_entries: max_entries.max(1),
         }
     }
 
     fn get(&self, key: &GlyphRunKey) -> Option<std::sync::Arc<Vec<RasterizedGlyph>>> {
-        let map = self.map.lock().unwrap();
-        map.get(key).cloned()
+        let mut map = self.map.lock().unwrap();
+        map.tick += 1;
+        let tick = map.tick;
+        let (glyphs, last_used) = map.runs.get_mut(key)?;
+        *last_used = tick;
+        Some(glyphs.clone())
     }
 
     fn insert(
@@ -127,19 +150,26 @@ impl GlyphRunCache {
         glyphs: Vec<RasterizedGlyph>,
     ) -> std::sync::Arc<Vec<RasterizedGlyph>> {
         let mut map = self.map.lock().unwrap();
+        map.tick += 1;
+        let tick = map.tick;
 
-        // Simple eviction strategy to keep memory bounded:
-        // when we grow past 2x capacity with new keys, clear everything.
-        if map.len() >= self.max_entries * 2 && !map.contains_key(&key) {
-            map.clear();
+        if let Some((existing, last_used)) = map.runs.get_mut(&key) {
+            *last_used = tick;
+            return existing.clone();
         }
 
-        if let Some(existing) = map.get(&key) {
-            return existing.clone();
+        // Evict the least recently used quarter (at least one run) at once
+        // when full, so a page with more runs than fit doesn't pay a scan
+        // per insert or drop the whole cache at once.
+        if map.runs.len() >= self.max_entries {
+            let mut ticks: Vec<u64> = map.runs.values().map(|(_, t)| *t).collect();
+            let cut = (ticks.len() / 4).max(1);
+            let (_, &mut newest_evicted, _) = ticks.select_nth_unstable(cut - 1);
+            map.runs.retain(|_, (_, t)| *t > newest_evicted);
         }
 
         let arc = std::sync::Arc::new(glyphs);
-        map.insert(key, arc.clone());
+        map.runs.insert(key, (arc.clone(), tick));
         arc
     }
 }
@@ -147,7 +177,7 @@ impl GlyphRunCache {
 static GLYPH_RUN_CACHE: std::sync::OnceLock<GlyphRunCache> = std::sync::OnceLock::new();
 
 fn global_glyph_run_cache() -> &'static GlyphRunCache {
-    GLYPH_RUN_CACHE.get_or_init(|| GlyphRunCache::new(2048))
+    GLYPH_RUN_CACHE.get_or_init(|| GlyphRunCache::new(4096))
 }
 
 /// Convert an 8-bit grayscale coverage mask to an RGB subpixel mask.
@@ -367,7 +397,9 @@ impl TextProvider for PatchedFontdueProvider {
             };
             out.push(RasterizedGlyph {
                 offset: [g.x, g.y],
+                color: None,
                 mask,
+                key: 0,
             });
         }
         out
@@ -375,10 +407,17 @@ impl TextProvider for PatchedFontdueProvider {
 }
 
 /// A glyph with its top-left offset relative to the run origin and an RGB subpixel mask.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RasterizedGlyph {
     pub offset: [f32; 2],
+    /// Span color for attributed runs; `None` means the run's color.
+    pub color: Option<crate::scene::ColorLinPremul>,
     pub mask: SubpixelMask,
+    /// Identifies the mask's pixels: glyphs with equal nonzero keys have
+    /// identical masks. [`rasterize_run_cached`] derives it from the run's
+    /// cache key and the glyph's index; `0` when unknown.
+    #[serde(default)]
+    pub key: u64,
 }
 
 /// Minimal shaped glyph information for paragraph-level wrapping.
@@ -396,7 +435,26 @@ pub struct ShapedParagraph {
     pub glyphs: Vec<ShapedGlyph>,
 }
 
+/// Horizontal extent of one glyph cluster of a shaped run, relative to the
+/// run origin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClusterBounds {
+    /// Byte range of the run's text covered by the cluster.
+    pub byte_range: std::ops::Range<usize>,
+    /// Left edge in pixels.
+    pub x: f32,
+    pub width: f32,
+    /// Whether the cluster belongs to a right-to-left run.
+    pub rtl: bool,
+}
+
 /// Text provider interface. Implementations convert a `TextRun` into positioned glyph masks.
+///
+/// Providers that understand attributed runs honor `TextRun::spans`, setting
+/// `RasterizedGlyph::color` for colored spans and emitting span backgrounds
+/// and underline/strikethrough lines as solid masks. Backgrounds come first so
+/// they composite beneath the glyphs. Other providers render the run in its
+/// base style.
 pub trait TextProvider: Send + Sync {
     fn rasterize_run(&self, run: &crate::scene::TextRun) -> Vec<RasterizedGlyph>;
 
@@ -420,6 +478,37 @@ pub trait TextProvider: Send + Sync {
         let _ = px;
         None
     }
+
+    /// Optional glyph cluster positions for hit testing, in visual order.
+    /// The default implementation returns `None`, in which case callers
+    /// estimate positions from the font size.
+    fn cluster_bounds(&self, run: &crate::scene::TextRun) -> Option<Vec<ClusterBounds>> {
+        let _ = run;
+        None
+    }
+
+    /// Optional paragraph layout with justification, hyphenation and tab
+    /// stops, for callers that draw wrapped text line by line. The default
+    /// implementation returns `None`, in which case callers wrap lines
+    /// themselves without those features.
+    fn paragraph_layout(
+        &self,
+        text: &str,
+        px: f32,
+        max_width: Option<f32>,
+        style: &rune_text::layout::ParagraphStyle,
+    ) -> Option<rune_text::layout::TextLayout> {
+        let _ = (text, px, max_width, style);
+        None
+    }
+
+    /// Optional list of the font faces `run` renders with, so a frame
+    /// capture can carry them and replay on a machine without the fonts.
+    /// The default implementation returns none.
+    fn run_faces(&self, run: &crate::scene::TextRun) -> Vec<rune_text::FontFace> {
+        let _ = run;
+        Vec::new()
+    }
 }
 
 /// Rasterize a text run using a global glyph-run cache.
@@ -429,7 +518,9 @@ pub trait TextProvider: Send + Sync {
 /// re-rasterizing glyphs every frame. The cache key is based on:
 /// - text contents
 /// - run size in pixels
+/// - span attributes, for attributed runs
 /// - the concrete text provider instance
+/// - the run color, only for runs containing color glyphs
 pub fn rasterize_run_cached(
     provider: &dyn TextProvider,
     run: &crate::scene::TextRun,
@@ -441,23 +532,59 @@ pub fn rasterize_run_cached(
     run.text.hash(&mut hasher);
     let text_hash = hasher.finish();
     let size_bits = run.size.to_bits();
+    let attrs_hash = hash_spans(&run.spans);
     // Use the concrete provider data pointer as a stable identifier for this run.
     let provider_id = (provider as *const dyn TextProvider as *const ()) as usize;
     let key = GlyphRunKey {
         text_hash,
         size_bits,
+        attrs_hash,
         provider_id,
+        color_bits: None,
     };
 
     let cache = global_glyph_run_cache();
     if let Some(hit) = cache.get(&key) {
         return hit;
     }
+    // Color glyph layers can be drawn in the text color, which is baked into
+    // their images, so runs with color glyphs are cached per run color.
+    let c = run.color;
+    let color_key = GlyphRunKey {
+        color_bits: Some([c.r, c.g, c.b, c.a].map(f32::to_bits)),
+        ..key.clone()
+    };
+    if let Some(hit) = cache.get(&color_key) {
+        return hit;
+    }
 
-    let glyphs = provider.rasterize_run(run);
+    let mut glyphs = provider.rasterize_run(run);
+    let key = if glyphs.iter().any(|g| g.mask.is_color()) {
+        color_key
+    } else {
+        key
+    };
+    for (index, glyph) in glyphs.iter_mut().enumerate() {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        index.hash(&mut hasher);
+        glyph.key = hasher.finish();
+    }
     cache.insert(key, glyphs)
 }
 
+fn hash_spans(spans: &[crate::scene::TextSpan]) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+
+    if spans.is_empty() {
+        return 0;
+    }
+    let mut hasher = DefaultHasher::new();
+    spans.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// LEGACY: Simple fontdue-based provider.
 ///
 /// **NOT RECOMMENDED**: Use [`RuneTextProvider`] (harfrust + swash) instead.
@@ -517,7 +644,9 @@ impl TextProvider for SimpleFontdueProvider {
             let oy = g.y;
             out.push(RasterizedGlyph {
                 offset: [ox, oy],
+                color: None,
                 mask,
+                key: 0,
             });
         }
         out
@@ -580,7 +709,9 @@ impl TextProvider for GrayscaleFontdueProvider {
             let oy = g.y;
             out.push(RasterizedGlyph {
                 offset: [ox, oy],
+                color: None,
                 mask,
+                key: 0,
             });
         }
         out
@@ -609,17 +740,65 @@ pub struct LineMetrics {
 
 /// Text provider backed by rune-text (HarfBuzz) for shaping and swash for rasterization.
 ///
-/// This uses a single `rune-text` `FontFace` and delegates shaping to
-/// `TextShaper::shape_ltr`, then rasterizes glyphs via swash bitmap images.
+/// This uses a primary `rune-text` `FontFace` plus a fallback chain and
+/// delegates shaping to `TextShaper::shape_with_fallback`, then rasterizes
+/// glyphs via swash bitmap images. Attributed spans that ask for another
+/// family, weight or style are resolved against a `fontdb` database of the
+/// faces known to the provider.
 pub struct RuneTextProvider {
     font: rune_text::FontFace,
+    /// Faces tried, in order, for characters the span's face can't render.
+    fallbacks: Vec<rune_text::FontFace>,
     orientation: SubpixelOrientation,
+    db: fontdb::Database,
+    /// Database id of `font`, so span queries resolving to it reuse the loaded face.
+    primary_id: Option<fontdb::ID>,
+    faces: std::sync::Mutex<std::collections::HashMap<fontdb::ID, Option<rune_text::FontFace>>>,
+}
+
+/// Vertical placement of span decorations relative to the baseline (y down).
+struct DecorationMetrics {
+    ascent: f32,
+    descent: f32,
+    underline_top: f32,
+    strikeout_top: f32,
+    thickness: f32,
 }
 
 impl RuneTextProvider {
     pub fn from_bytes(bytes: &[u8], orientation: SubpixelOrientation) -> anyhow::Result<Self> {
         let font = rune_text::FontFace::from_vec(bytes.to_vec(), 0)?;
-        Ok(Self { font, orientation })
+        let mut db = fontdb::Database::new();
+        db.load_font_data(bytes.to_vec());
+        let primary_id = db.faces().next().map(|face| face.id);
+        Ok(Self::with_database(font, orientation, db, primary_id))
+    }
+
+    /// Construct from font files and face indices: the first face is the
+    /// primary font and the rest are fallbacks. Spans asking for a family
+    /// can match any of them.
+    pub fn from_font_data(
+        faces: impl IntoIterator<Item = (Vec<u8>, u32)>,
+        orientation: SubpixelOrientation,
+    ) -> anyhow::Result<Self> {
+        let mut faces = faces.into_iter();
+        let (bytes, index) = faces
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no font faces given"))?;
+        let font = rune_text::FontFace::from_vec(bytes.clone(), index as usize)?;
+        let mut db = fontdb::Database::new();
+        db.load_font_data(bytes);
+        let primary_id = db
+            .faces()
+            .find(|face| face.index == index)
+            .map(|face| face.id);
+        let mut provider = Self::with_database(font, orientation, db, primary_id);
+        for (bytes, index) in faces {
+            let face = rune_text::FontFace::from_vec(bytes.clone(), index as usize)?;
+            provider.db.load_font_data(bytes);
+            provider.fallbacks.push(face);
+        }
+        Ok(provider)
     }
 
     /// Construct from a reasonable system sans-serif font using `fontdb`.
@@ -655,7 +834,53 @@ impl RuneTextProvider {
         };
 
         let font = rune_text::FontFace::from_vec(bytes, face.index as usize)?;
-        Ok(Self { font, orientation })
+        let mut provider = Self::with_database(font, orientation, db, Some(id));
+        provider.fallbacks = rune_text::font::fallback::fallback_faces_from_database(&provider.db);
+        Ok(provider)
+    }
+
+    fn with_database(
+        font: rune_text::FontFace,
+        orientation: SubpixelOrientation,
+        db: fontdb::Database,
+        primary_id: Option<fontdb::ID>,
+    ) -> Self {
+        Self {
+            font,
+            fallbacks: Vec::new(),
+            orientation,
+            db,
+            primary_id,
+            faces: std::sync::Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Make another font file available to attributed spans, e.g. the bold
+    /// or italic faces of the primary family.
+    pub fn register_font(&mut self, bytes: Vec<u8>) {
+        self.db.load_font_data(bytes);
+    }
+
+    /// Append a face to the fallback chain consulted for characters the
+    /// primary (or span) font doesn't cover.
+    pub fn push_fallback_font(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        self.fallbacks
+            .push(rune_text::FontFace::from_vec(bytes.to_vec(), 0)?);
+        Ok(())
+    }
+
+    /// Fallback chain for `primary`. The primary face's features and
+    /// variations carry over to the fallback faces.
+    fn fallback_chain(&self, primary: &rune_text::FontFace) -> rune_text::FontFallbackChain {
+        let chain = rune_text::FontFallbackChain::with_fallbacks(
+            primary.clone(),
+            self.fallbacks.iter().cloned(),
+        );
+        if primary.settings().is_empty() {
+            chain
+        } else {
+            chain.with_settings(primary.settings())
+        }
     }
 
     /// Layout a paragraph using rune-text's `TextLayout` with optional width-based wrapping.
@@ -668,6 +893,18 @@ impl RuneTextProvider {
         text: &str,
         size_px: f32,
         max_width: Option<f32>,
+    ) -> rune_text::layout::TextLayout {
+        self.layout_paragraph_with_style(text, size_px, max_width, &Default::default())
+    }
+
+    /// Like [`layout_paragraph`](Self::layout_paragraph), with a paragraph
+    /// style for justification, hyphenation and tab stops.
+    pub fn layout_paragraph_with_style(
+        &self,
+        text: &str,
+        size_px: f32,
+        max_width: Option<f32>,
+        style: &rune_text::layout::ParagraphStyle,
     ) -> rune_text::layout::TextLayout {
         use rune_text::layout::{TextLayout, WrapMode};
 
@@ -677,35 +914,126 @@ impl RuneTextProvider {
             WrapMode::NoWrap
         };
 
-        TextLayout::with_wrap(
+        TextLayout::with_style(
             text.to_string(),
-            &self.font,
+            &self.fallback_chain(&self.font),
             size_px.max(1.0),
             max_width,
             wrap,
+            style.clone(),
         )
     }
-}
 
-impl TextProvider for RuneTextProvider {
-    fn rasterize_run(&self, run: &crate::scene::TextRun) -> Vec<RasterizedGlyph> {
+    /// Pick the face for a span: the requested family (falling back to the
+    /// primary family) at the requested weight and style, with the span's
+    /// features and variations applied. Anything the database can't satisfy
+    /// renders with the primary font.
+    fn resolve_face(&self, attrs: &crate::scene::TextAttrs) -> rune_text::FontFace {
+        use crate::scene::FontWeight;
+        use rune_text::{FontSettings, FontVariation};
+
+        let face = self.query_face(attrs);
+        let mut settings = FontSettings {
+            features: attrs.features.clone(),
+            variations: attrs.variations.clone(),
+        };
+        // A variable face matches every weight in the database, so the
+        // requested weight has to be set on its `wght` axis.
+        if attrs.weight != FontWeight::NORMAL
+            && settings.variation(*b"wght").is_none()
+            && face.has_variation_axis(*b"wght")
+        {
+            settings.set_variation(FontVariation::weight(attrs.weight.0 as f32));
+        }
+        if settings.is_empty() {
+            face
+        } else {
+            face.with_settings(settings)
+        }
+    }
+
+    fn query_face(&self, attrs: &crate::scene::TextAttrs) -> rune_text::FontFace {
+        use crate::scene::{FontStyle, FontWeight};
+        use fontdb::{Family, Query, Stretch, Style, Weight};
+
+        if attrs.family.is_none()
+            && attrs.weight == FontWeight::NORMAL
+            && attrs.style == FontStyle::Normal
+        {
+            return self.font.clone();
+        }
+
+        let primary_family = self
+            .primary_id
+            .and_then(|id| self.db.face(id))
+            .and_then(|face| face.families.first())
+            .map(|(name, _)| name.as_str());
+        let families: Vec<Family> = attrs
+            .family
+            .as_deref()
+            .into_iter()
+            .chain(primary_family)
+            .map(Family::Name)
+            .collect();
+        let style = match attrs.style {
+            FontStyle::Normal => Style::Normal,
+            FontStyle::Italic => Style::Italic,
+            FontStyle::Oblique => Style::Oblique,
+        };
+        let Some(id) = self.db.query(&Query {
+            families: &families,
+            weight: Weight(attrs.weight.0),
+            stretch: Stretch::Normal,
+            style,
+        }) else {
+            return self.font.clone();
+        };
+        if Some(id) == self.primary_id {
+            return self.font.clone();
+        }
+
+        let mut faces = self.faces.lock().unwrap();
+        faces
+            .entry(id)
+            .or_insert_with(|| {
+                self.db
+                    .with_face_data(id, |data, index| {
+                        rune_text::FontFace::from_vec(data.to_vec(), index as usize).ok()
+                    })
+                    .flatten()
+            })
+            .clone()
+            .unwrap_or_else(|| self.font.clone())
+    }
+
+    /// Shape and rasterize `text` with `font` and the fallback chain,
+    /// starting at `pen_x` on the run baseline. Returns the advance width of
+    /// the shaped text.
+    ///
+    /// Color glyphs are rendered as color images, with `color` (or
+    /// `run_color` when unset) for layers drawn in the text color.
+    #[allow(clippy::too_many_arguments)]
+    fn rasterize_glyphs(
+        &self,
+        font: &rune_text::FontFace,
+        text: &str,
+        size: f32,
+        pen_x: f32,
+        color: Option<crate::scene::ColorLinPremul>,
+        run_color: crate::scene::ColorLinPremul,
+        out: &mut Vec<RasterizedGlyph>,
+    ) -> f32 {
+        use rune_text::bidi::BaseDirection;
         use rune_text::shaping::TextShaper;
         use swash::scale::image::Content;
         use swash::scale::{Render, ScaleContext, Source, StrikeWith};
         use swash::{FontRef, GlyphId};
 
-        let size = run.size.max(1.0);
-        let shaped = TextShaper::shape_ltr(&run.text, 0..run.text.len(), &self.font, 0, size);
+        let fonts = self.fallback_chain(font);
+        let runs =
+            TextShaper::shape_with_fallback(text, 0..text.len(), &fonts, size, BaseDirection::Auto);
 
-        // Build a swash scaler + renderer for this font/size that can rasterize
-        // outlines into coverage masks. This mirrors the docs/rune-text
-        // `GlyphRasterizer` pipeline but uses the older `Render` API from
-        // swash 0.1.x.
-        let font_bytes = self.font.as_bytes();
-        let font_ref = FontRef::from_index(&font_bytes, 0)
-            .expect("rune-text FontFace bytes should be a valid swash FontRef");
         let mut ctx = ScaleContext::new();
-        let mut scaler = ctx.builder(font_ref).size(size).hint(true).build();
         let renderer = Render::new(&[
             // Prefer scalable outlines; fall back to bitmaps when available.
             Source::Outline,
@@ -713,49 +1041,264 @@ impl TextProvider for RuneTextProvider {
             Source::ColorBitmap(StrikeWith::BestFit),
         ]);
 
-        let mut out = Vec::new();
-        for (gid, pos) in shaped.glyphs.iter().zip(shaped.positions.iter()) {
-            // Rasterize via swash scaler. This uses outlines for typical
-            // fonts and falls back to bitmaps when present, avoiding the
-            // "embedded bitmap only" issue from `glyph_bitmap`.
-            let glyph_id: GlyphId = *gid;
-            if let Some(img) = renderer.render(&mut scaler, glyph_id) {
-                let w = img.placement.width as u32;
-                let h = img.placement.height as u32;
-                if w == 0 || h == 0 {
+        let foreground = color.unwrap_or(run_color).to_srgba_u8();
+        let mut width = 0.0f32;
+        for run in &runs {
+            width += run.width;
+            let face = fonts.get(run.font_id).unwrap_or(font);
+            let has_color = face.has_color_glyphs();
+
+            // Build a swash scaler for this run's face/size that can
+            // rasterize outlines into coverage masks. This mirrors the
+            // docs/rune-text `GlyphRasterizer` pipeline but uses the older
+            // `Render` API from swash 0.1.x.
+            let font_bytes = face.as_bytes();
+            let font_ref = FontRef::from_index(&font_bytes, face.index() as usize)
+                .expect("rune-text FontFace bytes should be a valid swash FontRef");
+            let variations = face
+                .settings()
+                .variations
+                .iter()
+                .map(|v| (swash::tag_from_bytes(&v.tag), v.value));
+            let mut scaler = ctx
+                .builder(font_ref)
+                .size(size)
+                .hint(true)
+                .variations(variations)
+                .build();
+
+            for (gid, pos) in run.glyphs.iter().zip(run.positions.iter()) {
+                // Rasterize via swash scaler. This uses outlines for typical
+                // fonts and falls back to bitmaps when present, avoiding the
+                // "embedded bitmap only" issue from `glyph_bitmap`.
+                let glyph_id: GlyphId = *gid;
+                let color_image = if has_color {
+                    face.glyph_color_image(glyph_id, size, foreground)
+                } else {
+                    None
+                };
+                if let Some(image) = color_image {
+                    out.push(color_glyph(
+                        [pen_x + run.x_offset + pos.x_offset, pos.y_offset],
+                        color,
+                        image,
+                    ));
                     continue;
                 }
+                if let Some(img) = renderer.render(&mut scaler, glyph_id) {
+                    let w = img.placement.width;
+                    let h = img.placement.height;
+                    if w == 0 || h == 0 {
+                        continue;
+                    }
 
-                let mask = match img.content {
-                    Content::Mask => grayscale_to_subpixel_rgb(w, h, &img.data, self.orientation),
-                    Content::SubpixelMask => SubpixelMask {
-                        width: w,
-                        height: h,
-                        format: MaskFormat::Rgba8,
-                        data: img.data.clone(),
-                    },
-                    Content::Color => {
-                        // Derive coverage from alpha channel.
-                        let mut gray = Vec::with_capacity((w * h) as usize);
-                        let mut i = 0usize;
-                        while i + 3 < img.data.len() {
-                            gray.push(img.data[i + 3]);
-                            i += 4;
+                    let mask = match img.content {
+                        Content::Mask => {
+                            grayscale_to_subpixel_rgb(w, h, &img.data, self.orientation)
                         }
-                        grayscale_to_subpixel_rgb(w, h, &gray, self.orientation)
-                    }
-                };
+                        Content::SubpixelMask => SubpixelMask {
+                            width: w,
+                            height: h,
+                            format: MaskFormat::Rgba8,
+                            data: img.data.clone(),
+                        },
+                        Content::Color => {
+                            if let Some(image) = rune_text::ColorGlyphImage::from_bitmap(img) {
+                                out.push(color_glyph(
+                                    [pen_x + run.x_offset + pos.x_offset, pos.y_offset],
+                                    color,
+                                    image,
+                                ));
+                            }
+                            continue;
+                        }
+                    };
+
+                    let ox = pen_x + run.x_offset + pos.x_offset + img.placement.left as f32;
+                    let oy = pos.y_offset - img.placement.top as f32;
+                    out.push(RasterizedGlyph {
+                        offset: [ox, oy],
+                        color,
+                        mask,
+                        key: 0,
+                    });
+                }
+            }
+        }
 
-                let ox = pos.x_offset + img.placement.left as f32;
-                let oy = pos.y_offset - img.placement.top as f32;
-                out.push(RasterizedGlyph {
-                    offset: [ox, oy],
-                    mask,
+        width
+    }
+
+    fn decoration_metrics(font: &rune_text::FontFace, size: f32) -> DecorationMetrics {
+        let font_bytes = font.as_bytes();
+        let m = swash::FontRef::from_index(&font_bytes, font.index() as usize)
+            .expect("rune-text FontFace bytes should be a valid swash FontRef")
+            .metrics(&[])
+            .scale(size);
+        let thickness = if m.stroke_size > 0.0 {
+            m.stroke_size
+        } else {
+            size / 14.0
+        };
+        let underline_top = if m.underline_offset != 0.0 {
+            -m.underline_offset
+        } else {
+            m.descent * 0.5
+        };
+        let strikeout_top = if m.strikeout_offset != 0.0 {
+            -m.strikeout_offset
+        } else {
+            -(m.x_height + thickness) * 0.5
+        };
+        DecorationMetrics {
+            ascent: m.ascent,
+            descent: m.descent,
+            underline_top,
+            strikeout_top,
+            thickness: thickness.max(1.0),
+        }
+    }
+}
+
+/// A fully covered mask of the given logical size, used for span
+/// backgrounds and decoration lines.
+fn solid_glyph(
+    offset: [f32; 2],
+    width: f32,
+    height: f32,
+    color: Option<crate::scene::ColorLinPremul>,
+) -> Option<RasterizedGlyph> {
+    let w = width.ceil() as u32;
+    let h = height.ceil() as u32;
+    if w == 0 || h == 0 {
+        return None;
+    }
+    let coverage = vec![255u8; (w * h) as usize];
+    Some(RasterizedGlyph {
+        offset,
+        color,
+        mask: grayscale_to_rgb_equal(w, h, &coverage),
+        key: 0,
+    })
+}
+
+/// A color glyph image placed with its left edge and top at the pen
+/// position `origin` plus the image's bearings.
+fn color_glyph(
+    origin: [f32; 2],
+    color: Option<crate::scene::ColorLinPremul>,
+    image: rune_text::ColorGlyphImage,
+) -> RasterizedGlyph {
+    RasterizedGlyph {
+        offset: [origin[0] + image.left as f32, origin[1] - image.top as f32],
+        color,
+        mask: SubpixelMask {
+            width: image.width,
+            height: image.height,
+            format: MaskFormat::ColorRgba8,
+            data: image.data,
+        },
+        key: 0,
+    }
+}
+
+impl TextProvider for RuneTextProvider {
+    fn run_faces(&self, run: &crate::scene::TextRun) -> Vec<rune_text::FontFace> {
+        use rune_text::bidi::BaseDirection;
+        use rune_text::shaping::itemize;
+
+        let segments = if run.is_attributed() {
+            run.segments()
+                .into_iter()
+                .map(|(range, attrs)| (range, self.resolve_face(&attrs)))
+                .collect()
+        } else {
+            vec![(0..run.text.len(), self.font.clone())]
+        };
+        // The primary font first, then each face that renders a piece of
+        // the run, once.
+        let mut faces = vec![self.font.clone()];
+        for (range, font) in segments {
+            let fonts = self.fallback_chain(&font);
+            for item in itemize(&run.text[range], &fonts, BaseDirection::Auto) {
+                let face = fonts.get(item.font_id).unwrap_or(&font);
+                let seen = faces.iter().any(|f| {
+                    f.index() == face.index()
+                        && std::sync::Arc::ptr_eq(&f.as_bytes(), &face.as_bytes())
                 });
+                if !seen {
+                    faces.push(face.clone());
+                }
             }
         }
+        faces
+    }
 
-        out
+    fn rasterize_run(&self, run: &crate::scene::TextRun) -> Vec<RasterizedGlyph> {
+        let mut glyphs = Vec::new();
+        if !run.is_attributed() {
+            self.rasterize_glyphs(
+                &self.font,
+                &run.text,
+                run.size.max(1.0),
+                0.0,
+                None,
+                run.color,
+                &mut glyphs,
+            );
+            return glyphs;
+        }
+
+        // Each attribute segment is shaped with its own face and size, and
+        // segments continue along the shared baseline.
+        let mut backgrounds = Vec::new();
+        let mut lines = Vec::new();
+        let mut pen_x = 0.0f32;
+        for (range, attrs) in run.segments() {
+            let font = self.resolve_face(&attrs);
+            let size = attrs.size.unwrap_or(run.size).max(1.0);
+            let width = self.rasterize_glyphs(
+                &font,
+                &run.text[range],
+                size,
+                pen_x,
+                attrs.color,
+                run.color,
+                &mut glyphs,
+            );
+            if attrs.background.is_some() || attrs.underline || attrs.strikethrough {
+                let m = Self::decoration_metrics(&font, size);
+                if let Some(bg) = attrs.background {
+                    backgrounds.extend(solid_glyph(
+                        [pen_x, -m.ascent],
+                        width,
+                        m.ascent + m.descent,
+                        Some(bg),
+                    ));
+                }
+                if attrs.underline {
+                    lines.extend(solid_glyph(
+                        [pen_x, m.underline_top],
+                        width,
+                        m.thickness,
+                        attrs.color,
+                    ));
+                }
+                if attrs.strikethrough {
+                    lines.extend(solid_glyph(
+                        [pen_x, m.strikeout_top],
+                        width,
+                        m.thickness,
+                        attrs.color,
+                    ));
+                }
+            }
+            pen_x += width;
+        }
+
+        backgrounds.extend(glyphs);
+        backgrounds.extend(lines);
+        backgrounds
     }
 
     fn line_metrics(&self, px: f32) -> Option<LineMetrics> {
@@ -766,6 +1309,63 @@ impl TextProvider for RuneTextProvider {
             line_gap: m.line_gap,
         })
     }
+
+    fn cluster_bounds(&self, run: &crate::scene::TextRun) -> Option<Vec<ClusterBounds>> {
+        use rune_text::bidi::BaseDirection;
+        use rune_text::shaping::TextShaper;
+
+        let segments = if run.is_attributed() {
+            run.segments()
+        } else {
+            vec![(0..run.text.len(), crate::scene::TextAttrs::default())]
+        };
+        // Mirror `rasterize_run`: segments are shaped separately and follow
+        // each other along the baseline.
+        let mut clusters = Vec::new();
+        let mut pen_x = 0.0f32;
+        for (range, attrs) in segments {
+            let font = if run.is_attributed() {
+                self.resolve_face(&attrs)
+            } else {
+                self.font.clone()
+            };
+            let size = attrs.size.unwrap_or(run.size).max(1.0);
+            let text = &run.text[range.clone()];
+            let runs = TextShaper::shape_with_fallback(
+                text,
+                0..text.len(),
+                &self.fallback_chain(&font),
+                size,
+                BaseDirection::Auto,
+            );
+            let mut width = 0.0f32;
+            for shaped in &runs {
+                width += shaped.width;
+                for span in shaped.cluster_spans() {
+                    clusters.push(ClusterBounds {
+                        byte_range: range.start + span.text_range.start
+                            ..range.start + span.text_range.end,
+                        x: pen_x + shaped.x_offset + span.x_start,
+                        width: span.x_end - span.x_start,
+                        rtl: shaped.is_rtl(),
+                    });
+                }
+            }
+            pen_x += width;
+        }
+        clusters.sort_by(|a, b| a.x.total_cmp(&b.x));
+        Some(clusters)
+    }
+
+    fn paragraph_layout(
+        &self,
+        text: &str,
+        px: f32,
+        max_width: Option<f32>,
+        style: &rune_text::layout::ParagraphStyle,
+    ) -> Option<rune_text::layout::TextLayout> {
+        Some(self.layout_paragraph_with_style(text, px, max_width, style))
+    }
 }
 
 // Advanced shaper: integrate cosmic-text for shaping + swash rasterization (optional feature)
@@ -862,7 +1462,9 @@ mod cosmic_provider {
                                 let oy = pg.y as f32 - img.placement.top as f32;
                                 out.push(RasterizedGlyph {
                                     offset: [ox, oy],
+                                    color: None,
                                     mask,
+                                    key: 0,
                                 });
                             }
                             cosmic_text::SwashContent::Color => {
@@ -878,7 +1480,9 @@ mod cosmic_provider {
                                 let oy = pg.y as f32 - img.placement.top as f32;
                                 out.push(RasterizedGlyph {
                                     offset: [ox, oy],
+                                    color: None,
                                     mask,
+                                    key: 0,
                                 });
                             }
                             cosmic_text::SwashContent::SubpixelMask => {
@@ -889,7 +1493,9 @@ mod cosmic_provider {
                                 let oy = pg.y as f32 - img.placement.top as f32;
                                 out.push(RasterizedGlyph {
                                     offset: [ox, oy],
+                                    color: None,
                                     mask,
+                                    key: 0,
                                 });
                             }
                         }
@@ -1083,12 +1689,14 @@ mod freetype_provider {
                     if w > 0 && h > 0 {
                         out.push(RasterizedGlyph {
                             offset: [ox, oy],
+                            color: None,
                             mask: SubpixelMask {
                                 width: w,
                                 height: h,
                                 format: MaskFormat::Rgba8,
                                 data,
                             },
+                            key: 0,
                         });
                     }
                 }
@@ -1141,3 +1749,268 @@ mod freetype_provider {
 
 #[cfg(feature = "freetype_ffi")]
 pub use freetype_provider::Provider as FreeTypeProvider;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{ColorLinPremul, FontWeight, TextAttrs, TextRun};
+
+    fn geist(weight: &str) -> Vec<u8> {
+        let path = format!(
+            "{}/../../fonts/Geist/static/Geist-{weight}.ttf",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        std::fs::read(path).expect("bundled Geist font")
+    }
+
+    fn provider() -> RuneTextProvider {
+        let mut p = RuneTextProvider::from_bytes(&geist("Regular"), SubpixelOrientation::RGB)
+            .expect("load Geist Regular");
+        p.register_font(geist("Bold"));
+        p
+    }
+
+    fn white() -> ColorLinPremul {
+        ColorLinPremul::rgba(255, 255, 255, 255)
+    }
+
+    #[test]
+    fn glyph_run_cache_evicts_least_recently_used_runs() {
+        let key = |i: u64| GlyphRunKey {
+            text_hash: i,
+            size_bits: 0,
+            attrs_hash: 0,
+            provider_id: 0,
+            color_bits: None,
+        };
+        let cache = GlyphRunCache::new(8);
+        for i in 0..8 {
+            cache.insert(key(i), Vec::new());
+        }
+        // Keep the first two runs warm, then overflow the cache.
+        cache.get(&key(0)).unwrap();
+        cache.get(&key(1)).unwrap();
+        cache.insert(key(8), Vec::new());
+        assert!(cache.get(&key(0)).is_some() && cache.get(&key(1)).is_some());
+        assert!(cache.get(&key(8)).is_some());
+        assert!(cache.get(&key(2)).is_none(), "coldest run is evicted");
+        assert!(cache.get(&key(7)).is_some(), "only a quarter is evicted");
+    }
+
+    #[test]
+    fn small_glyph_run_caches_stay_within_their_limit() {
+        let key = |i: u64| GlyphRunKey {
+            text_hash: i,
+            size_bits: 0,
+            attrs_hash: 0,
+            provider_id: 0,
+            color_bits: None,
+        };
+        for max_entries in [1, 2] {
+            let cache = GlyphRunCache::new(max_entries);
+            for i in 0..5 {
+                cache.insert(key(i), Vec::new());
+                assert!(cache.map.lock().unwrap().runs.len() <= max_entries);
+            }
+            assert!(cache.get(&key(4)).is_some(), "newest run is kept");
+        }
+    }
+
+    #[test]
+    fn cached_runs_with_color_glyphs_are_keyed_by_run_color() {
+        // Stands in for a font whose color glyph layers use the text color.
+        struct TintedEmoji;
+        impl TextProvider for TintedEmoji {
+            fn rasterize_run(&self, run: &TextRun) -> Vec<RasterizedGlyph> {
+                vec![RasterizedGlyph {
+                    offset: [0.0, 0.0],
+                    color: None,
+                    mask: SubpixelMask {
+                        width: 1,
+                        height: 1,
+                        format: MaskFormat::ColorRgba8,
+                        data: run.color.to_srgba_u8().to_vec(),
+                    },
+                    key: 0,
+                }]
+            }
+        }
+        let provider = TintedEmoji;
+        let run = |c| TextRun::new("cached color glyph", [0.0, 0.0], 16.0, c);
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let a = rasterize_run_cached(&provider, &run(red));
+        let b = rasterize_run_cached(&provider, &run(blue));
+        assert_ne!(a[0].mask.data, b[0].mask.data);
+        // Their keys tell the differing pixels apart without reading them.
+        assert_ne!(a[0].key, 0);
+        assert_ne!(a[0].key, b[0].key);
+        assert!(std::sync::Arc::ptr_eq(
+            &a,
+            &rasterize_run_cached(&provider, &run(red))
+        ));
+    }
+
+    #[test]
+    fn segments_fill_gaps_and_later_spans_win() {
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let bold = TextAttrs {
+            weight: FontWeight::BOLD,
+            ..Default::default()
+        };
+        let run = TextRun::new("hello world", [0.0, 0.0], 16.0, white())
+            .with_span(0..8, bold.clone())
+            .with_span(
+                6..11,
+                TextAttrs {
+                    color: Some(red),
+                    ..Default::default()
+                },
+            );
+        let segs = run.segments();
+        let ranges: Vec<_> = segs.iter().map(|(r, _)| r.clone()).collect();
+        assert_eq!(ranges, vec![0..6, 6..11]);
+        assert_eq!(segs[0].1, bold);
+        assert_eq!(segs[1].1.color, Some(red));
+        assert_eq!(segs[1].1.weight, FontWeight::NORMAL);
+    }
+
+    #[test]
+    fn segments_clamp_to_char_boundaries() {
+        let run = TextRun::new("héllo", [0.0, 0.0], 16.0, white()).with_span(
+            2..40,
+            TextAttrs {
+                underline: true,
+                ..Default::default()
+            },
+        );
+        let ranges: Vec<_> = run.segments().into_iter().map(|(r, _)| r).collect();
+        assert_eq!(ranges, vec![0..1, 1..6]);
+    }
+
+    #[test]
+    fn bold_span_uses_registered_face() {
+        let p = provider();
+        let plain = TextRun::new("Mm", [0.0, 0.0], 32.0, white());
+        let bold = plain.clone().with_span(
+            0..2,
+            TextAttrs {
+                weight: FontWeight::BOLD,
+                ..Default::default()
+            },
+        );
+        let ink = |glyphs: &[RasterizedGlyph]| -> u64 {
+            glyphs
+                .iter()
+                .flat_map(|g| g.mask.data.iter().map(|&b| b as u64))
+                .sum()
+        };
+        assert!(ink(&p.rasterize_run(&bold)) > ink(&p.rasterize_run(&plain)));
+    }
+
+    #[test]
+    fn variable_face_takes_weight_and_variations_from_attrs() {
+        let variable = std::fs::read(format!(
+            "{}/../../fonts/Geist/Geist-VariableFont_wght.ttf",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .expect("bundled Geist variable font");
+        let p = RuneTextProvider::from_bytes(&variable, SubpixelOrientation::RGB).unwrap();
+        let ink = |run: &TextRun| -> u64 {
+            p.rasterize_run(run)
+                .iter()
+                .flat_map(|g| g.mask.data.iter().map(|&b| b as u64))
+                .sum()
+        };
+        let plain = TextRun::new("Mm", [0.0, 0.0], 32.0, white());
+        let bold = plain.clone().with_span(
+            0..2,
+            TextAttrs {
+                weight: FontWeight::BOLD,
+                ..Default::default()
+            },
+        );
+        let light = plain.clone().with_span(
+            0..2,
+            TextAttrs {
+                variations: vec![rune_text::FontVariation::weight(200.0)],
+                ..Default::default()
+            },
+        );
+        assert!(ink(&bold) > ink(&plain));
+        assert!(ink(&light) < ink(&plain));
+    }
+
+    #[test]
+    fn span_color_and_decorations_are_emitted() {
+        let p = provider();
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let plain = TextRun::new("ab cd", [0.0, 0.0], 20.0, white());
+        let styled = plain.clone().with_span(
+            3..5,
+            TextAttrs {
+                color: Some(red),
+                underline: true,
+                background: Some(blue),
+                ..Default::default()
+            },
+        );
+        let base = p.rasterize_run(&plain);
+        let glyphs = p.rasterize_run(&styled);
+        // Background first, then the glyphs, then the underline.
+        assert_eq!(glyphs.len(), base.len() + 2);
+        assert_eq!(glyphs[0].color, Some(blue));
+        assert!(glyphs[0].offset[1] < 0.0);
+        let underline = glyphs.last().unwrap();
+        assert_eq!(underline.color, Some(red));
+        assert!(underline.offset[1] > 0.0);
+        let colored = glyphs[1..glyphs.len() - 1]
+            .iter()
+            .filter(|g| g.color == Some(red))
+            .count();
+        assert_eq!(colored, 2);
+    }
+
+    #[test]
+    fn fallback_font_renders_uncovered_characters() {
+        let noto = std::fs::read(format!(
+            "{}/../../fonts/Noto_Sans/static/NotoSans-Regular.ttf",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .expect("bundled Noto Sans font");
+        let primary = rune_text::FontFace::from_vec(geist("Regular"), 0).unwrap();
+        let fallback = rune_text::FontFace::from_vec(noto.clone(), 0).unwrap();
+        let ch = ['ǅ', 'Ѩ', 'Ꞌ', 'ẞ', 'Ƣ', 'ʭ', 'Ϗ', 'Ӂ', '₸', '₼']
+            .into_iter()
+            .find(|&c| !primary.has_glyph(c) && fallback.has_glyph(c))
+            .expect("a glyph only in Noto Sans");
+        let run = TextRun::new(format!("a{ch}b"), [0.0, 0.0], 24.0, white());
+
+        let mut p = provider();
+        let without = p.rasterize_run(&run);
+        p.push_fallback_font(&noto).unwrap();
+        let with = p.rasterize_run(&run);
+        assert_eq!(with.len(), 3);
+        // Glyphs stay in pen order across the face switch.
+        assert!(with[0].offset[0] < with[1].offset[0]);
+        assert!(with[1].offset[0] < with[2].offset[0]);
+        assert_ne!(without[1].mask.data, with[1].mask.data);
+    }
+
+    #[test]
+    fn cached_runs_are_keyed_by_spans() {
+        let p = provider();
+        let plain = TextRun::new("cache me", [0.0, 0.0], 18.0, white());
+        let underlined = plain.clone().with_span(
+            0..5,
+            TextAttrs {
+                underline: true,
+                ..Default::default()
+            },
+        );
+        let a = rasterize_run_cached(&p, &plain);
+        let b = rasterize_run_cached(&p, &underlined);
+        assert_eq!(b.len(), a.len() + 1);
+    }
+}
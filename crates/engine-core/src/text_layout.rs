@@ -30,16 +30,30 @@ use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::sync::Mutex;
 
+use rune_text::layout::{EllipsisPosition, TextOverflow};
+use rune_text::unicode::grapheme_clusters;
+
+/// Shown in place of text that doesn't fit.
+const ELLIPSIS: &str = "\u{2026}";
+
 /// A cache key for wrapped text layout.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct LayoutKey {
     text_hash: u64,
     max_width_bits: u32, // f32 as bits for hashing
     size_bits: u32,      // f32 as bits for hashing
+    max_lines: Option<usize>,
+    overflow: TextOverflow,
 }
 
 impl LayoutKey {
-    fn new(text: &str, max_width: f32, size: f32) -> Self {
+    fn new(
+        text: &str,
+        max_width: f32,
+        size: f32,
+        max_lines: Option<usize>,
+        overflow: TextOverflow,
+    ) -> Self {
         // Hash the text content
         let mut hasher = std::collections::hash_map::DefaultHasher::new();
         text.hash(&mut hasher);
@@ -49,6 +63,8 @@ impl LayoutKey {
             text_hash,
             max_width_bits: max_width.to_bits(),
             size_bits: size.to_bits(),
+            max_lines,
+            overflow,
         }
     }
 }
@@ -62,6 +78,8 @@ pub struct WrappedText {
     pub line_height: f32,
     /// Total height of all lines
     pub total_height: f32,
+    /// Whether lines were hidden by a line limit
+    pub truncated: bool,
 }
 
 /// A cache for wrapped text layouts to avoid per-frame allocations.
@@ -87,7 +105,27 @@ impl TextLayoutCache {
         size: f32,
         line_height_factor: f32,
     ) -> WrappedText {
-        let key = LayoutKey::new(text, max_width, size);
+        self.get_or_wrap_clamped(
+            text,
+            max_width,
+            size,
+            line_height_factor,
+            None,
+            TextOverflow::Clip,
+        )
+    }
+
+    /// Get or compute wrapped text layout limited to `max_lines` lines.
+    pub fn get_or_wrap_clamped(
+        &self,
+        text: &str,
+        max_width: f32,
+        size: f32,
+        line_height_factor: f32,
+        max_lines: Option<usize>,
+        overflow: TextOverflow,
+    ) -> WrappedText {
+        let key = LayoutKey::new(text, max_width, size, max_lines, overflow);
 
         // Try to get from cache
         {
@@ -98,7 +136,14 @@ impl TextLayoutCache {
         }
 
         // Compute the wrapped text
-        let wrapped = wrap_text_fast(text, max_width, size, line_height_factor);
+        let wrapped = wrap_text_clamped(
+            text,
+            max_width,
+            size,
+            line_height_factor,
+            max_lines,
+            overflow,
+        );
 
         // Store in cache (with size limit)
         {
@@ -155,6 +200,7 @@ pub fn wrap_text_fast(
             lines: vec![],
             line_height,
             total_height: 0.0,
+            truncated: false,
         };
     }
 
@@ -200,9 +246,106 @@ pub fn wrap_text_fast(
         lines,
         line_height,
         total_height,
+        truncated: false,
     }
 }
 
+/// Like [`wrap_text_fast`], showing at most `max_lines` lines.
+///
+/// With an ellipsis overflow the last line shown ends in an ellipsis when
+/// lines were hidden.
+pub fn wrap_text_clamped(
+    text: &str,
+    max_width: f32,
+    size: f32,
+    line_height_factor: f32,
+    max_lines: Option<usize>,
+    overflow: TextOverflow,
+) -> WrappedText {
+    let mut wrapped = wrap_text_fast(text, max_width, size, line_height_factor);
+    wrapped.truncated = clamp_lines_fast(
+        &mut wrapped.lines,
+        max_lines,
+        Some(max_width),
+        size,
+        overflow,
+    );
+    wrapped.total_height = wrapped.lines.len() as f32 * wrapped.line_height;
+    wrapped
+}
+
+/// Drop the lines past `max_lines`; returns whether any were dropped.
+///
+/// With an ellipsis overflow the last line kept ends in an ellipsis,
+/// shortened to fit `max_width` if needed. Hidden lines always come after
+/// the kept ones, so the ellipsis goes at the end whatever its position.
+pub fn clamp_lines_fast(
+    lines: &mut Vec<String>,
+    max_lines: Option<usize>,
+    max_width: Option<f32>,
+    size: f32,
+    overflow: TextOverflow,
+) -> bool {
+    let max_lines = match max_lines {
+        Some(max_lines) => max_lines.max(1),
+        None => return false,
+    };
+    if lines.len() <= max_lines {
+        return false;
+    }
+    lines.truncate(max_lines);
+    if let (TextOverflow::Ellipsis(_), Some(last)) = (overflow, lines.last_mut()) {
+        let line = last.trim_end();
+        let chars = grapheme_clusters(line).len() + 1;
+        let max_chars = max_width.map_or(chars, |w| chars.min(fast_char_count(w, size)));
+        *last = ellipsize_fast(line, max_chars, EllipsisPosition::End);
+    }
+    true
+}
+
+/// Shorten `text` to fit `max_width` with an ellipsis at `position`,
+/// measuring it like [`wrap_text_fast`] does.
+///
+/// Cuts fall between grapheme clusters and drop the whitespace next to the
+/// ellipsis. Text that fits is returned as is.
+pub fn truncate_text_fast(
+    text: &str,
+    max_width: f32,
+    size: f32,
+    position: EllipsisPosition,
+) -> String {
+    let max_chars = fast_char_count(max_width, size);
+    if grapheme_clusters(text).len() <= max_chars {
+        return text.to_string();
+    }
+    ellipsize_fast(text, max_chars, position)
+}
+
+/// Number of average characters that fit in `width`.
+fn fast_char_count(width: f32, size: f32) -> usize {
+    (width / (size * 0.55)).max(0.0).floor() as usize
+}
+
+/// `text` cut to `max_chars` grapheme clusters including an ellipsis.
+fn ellipsize_fast(text: &str, max_chars: usize, position: EllipsisPosition) -> String {
+    let clusters = grapheme_clusters(text);
+    let keep = max_chars.saturating_sub(1).min(clusters.len());
+    let (head, tail) = match position {
+        EllipsisPosition::Start => (0, keep),
+        EllipsisPosition::Middle => (keep.div_ceil(2), keep / 2),
+        EllipsisPosition::End => (keep, 0),
+    };
+    let head_end = clusters.get(head).map_or(text.len(), |c| c.range.start);
+    let tail_start = clusters
+        .get(clusters.len() - tail)
+        .map_or(text.len(), |c| c.range.start);
+    format!(
+        "{}{ELLIPSIS}{}",
+        text[..head_end].trim_end(),
+        text[tail_start..].trim_start()
+    )
+}
+
 /// Render wrapped text to a display list or canvas.
 ///
 /// This is a helper that takes pre-wrapped text and renders it line by line.
@@ -239,4 +382,55 @@ mod tests {
         // Should be the same (from cache)
         assert_eq!(w1.lines.len(), w2.lines.len());
     }
+
+    #[test]
+    fn test_wrap_text_clamped() {
+        let text = "This is a test of the text wrapping system.";
+        let ellipsis = TextOverflow::Ellipsis(EllipsisPosition::End);
+        let wrapped = wrap_text_clamped(text, 100.0, 16.0, 1.2, Some(2), ellipsis);
+        assert_eq!(wrapped.lines.len(), 2);
+        assert!(wrapped.truncated);
+        assert!(wrapped.lines[1].ends_with(ELLIPSIS));
+        assert!(grapheme_clusters(&wrapped.lines[1]).len() <= fast_char_count(100.0, 16.0));
+        assert!((wrapped.total_height - 2.0 * 16.0 * 1.2).abs() < 0.001);
+
+        let clipped = wrap_text_clamped(text, 100.0, 16.0, 1.2, Some(2), TextOverflow::Clip);
+        assert_eq!(clipped.lines, wrapped_prefix(text, 2));
+
+        let all = wrap_text_clamped(text, 100.0, 16.0, 1.2, Some(100), ellipsis);
+        assert!(!all.truncated);
+    }
+
+    fn wrapped_prefix(text: &str, lines: usize) -> Vec<String> {
+        let mut wrapped = wrap_text_fast(text, 100.0, 16.0, 1.2).lines;
+        wrapped.truncate(lines);
+        wrapped
+    }
+
+    #[test]
+    fn test_truncate_text_fast() {
+        // 10 average characters fit in 88px at 16px.
+        let text = "a rather long file name.txt";
+        assert_eq!(
+            truncate_text_fast(text, 88.0, 16.0, EllipsisPosition::End),
+            "a rather\u{2026}"
+        );
+        assert_eq!(
+            truncate_text_fast(text, 88.0, 16.0, EllipsisPosition::Start),
+            "\u{2026}name.txt"
+        );
+        assert_eq!(
+            truncate_text_fast(text, 88.0, 16.0, EllipsisPosition::Middle),
+            "a rat\u{2026}.txt"
+        );
+        assert_eq!(
+            truncate_text_fast("short", 88.0, 16.0, EllipsisPosition::End),
+            "short"
+        );
+
+        // Combining marks stay with their base letter.
+        let accented = "e\u{301}".repeat(20);
+        let cut = truncate_text_fast(&accented, 88.0, 16.0, EllipsisPosition::End);
+        assert_eq!(cut, format!("{}\u{2026}", "e\u{301}".repeat(9)));
+    }
 }
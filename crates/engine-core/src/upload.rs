@@ -1,10 +1,14 @@
+use std::ops::{Bound, Range, RangeBounds};
+
 use anyhow::Result;
 use bytemuck::{Pod, Zeroable};
 
 use crate::allocator::{BufKey, OwnedBuffer, RenderAllocator};
 use crate::display_list::{Command, DisplayList};
 use crate::scene::{
-    Brush, FillRule, Path, PathCmd, Rect, RoundedRect, Stroke, TextRun, Transform2D,
+    BackdropFilter, BlendMode, Border, Brush, Clip, ClipShape, ClipTable, ColorLinPremul, FillRule,
+    Filter, ImagePattern, LayerMask, Path, PathCmd, Rect, RoundedRect, SpreadMode, Stroke, TextRun,
+    Transform2D,
 };
 
 #[repr(C)]
@@ -20,6 +24,83 @@ pub struct GpuScene {
     pub index: OwnedBuffer,
     pub vertices: u32,
     pub indices: u32,
+    /// Each clip (by index into the scene's [`ClipTable`]) and z-index of
+    /// the geometry with the first index drawing it, in ascending order.
+    /// Empty when the indices aren't grouped, which draws them unclipped.
+    pub z_starts: Vec<(u32, i32, u32)>,
+}
+
+impl GpuScene {
+    /// Indices drawing the geometry inside `clip` with a z-index in `z`;
+    /// all of them for clip 0 when the scene has no `z_starts`.
+    pub fn index_range(&self, clip: u32, z: impl RangeBounds<i32>) -> Range<u32> {
+        if self.z_starts.is_empty() {
+            return if clip == 0 { 0..self.indices } else { 0..0 };
+        }
+        let index_at = |i: usize| {
+            self.z_starts
+                .get(i)
+                .map_or(self.indices, |&(_, _, index)| index)
+        };
+        let start = self.z_starts.partition_point(|&(index_clip, index_z, _)| {
+            index_clip < clip
+                || index_clip == clip
+                    && match z.start_bound() {
+                        Bound::Included(&start) => index_z < start,
+                        Bound::Excluded(&start) => index_z <= start,
+                        Bound::Unbounded => false,
+                    }
+        });
+        let end = self.z_starts.partition_point(|&(index_clip, index_z, _)| {
+            index_clip < clip
+                || index_clip == clip
+                    && match z.end_bound() {
+                        Bound::Included(&end) => index_z <= end,
+                        Bound::Excluded(&end) => index_z < end,
+                        Bound::Unbounded => true,
+                    }
+        });
+        index_at(start)..index_at(end.max(start))
+    }
+}
+
+/// Stably reorder the triangles in `indices` by clip, then z-index, and
+/// return the `z_starts` of the result. `clips` holds each index from which
+/// on a new clip applies, with that clip, in ascending order.
+fn group_by_clip_and_z(
+    vertices: &[Vertex],
+    indices: &mut Vec<u16>,
+    clips: &[(usize, u32)],
+) -> Vec<(u32, i32, u32)> {
+    if !indices.len().is_multiple_of(3) {
+        return Vec::new();
+    }
+    let clip_at = |index: usize| {
+        let n = clips.partition_point(|&(start, _)| start <= index);
+        n.checked_sub(1).map_or(0, |n| clips[n].1)
+    };
+    let mut triangles: Vec<(u32, i32, [u16; 3])> = indices
+        .chunks(3)
+        .enumerate()
+        .map(|(i, tri)| {
+            let z = vertices[tri[0] as usize].z_index as i32;
+            (clip_at(i * 3), z, [tri[0], tri[1], tri[2]])
+        })
+        .collect();
+    if !triangles.is_sorted_by_key(|&(clip, z, _)| (clip, z)) {
+        triangles.sort_by_key(|&(clip, z, _)| (clip, z));
+        *indices = triangles.iter().flat_map(|&(_, _, tri)| tri).collect();
+    }
+    let mut starts: Vec<(u32, i32, u32)> = Vec::new();
+    for (i, &(clip, z, _)) in triangles.iter().enumerate() {
+        if starts
+            .last()
+            .is_none_or(|&(last_clip, last_z, _)| (last_clip, last_z) != (clip, z))
+        {
+            starts.push((clip, z, (i * 3) as u32));
+        }
+    }
+    starts
 }
 
 /// Extracted text draw from DisplayList
@@ -28,6 +109,8 @@ pub struct ExtractedTextDraw {
     pub run: TextRun,
     pub z: i32,
     pub transform: Transform2D,
+    /// Clips of the draw, by index into the scene's [`ClipTable`].
+    pub clip: u32,
 }
 
 /// Extracted image draw from DisplayList (placeholder for future)
@@ -38,6 +121,8 @@ pub struct ExtractedImageDraw {
     pub size: [f32; 2],
     pub z: i32,
     pub transform: Transform2D,
+    /// Clips of the draw, by index into the scene's [`ClipTable`].
+    pub clip: u32,
 }
 
 /// Extracted SVG draw from DisplayList (placeholder for future)
@@ -48,6 +133,29 @@ pub struct ExtractedSvgDraw {
     pub size: [f32; 2],
     pub z: i32,
     pub transform: Transform2D,
+    /// Clips of the draw, by index into the scene's [`ClipTable`].
+    pub clip: u32,
+}
+
+/// Vertex of an image pattern mesh; same layout as the image pipeline's quads.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, Zeroable)]
+pub struct PatternVertex {
+    pub pos: [f32; 2],
+    /// Texture coordinates in tile units; sampled with a repeating address mode.
+    pub uv: [f32; 2],
+}
+
+/// Path painted with a [`Brush::Image`], tessellated into textured triangles
+/// in world space.
+#[derive(Clone, Debug)]
+pub struct ExtractedPatternDraw {
+    pub path: std::path::PathBuf,
+    pub vertices: Vec<PatternVertex>,
+    pub indices: Vec<u16>,
+    pub z: i32,
+    /// Clips of the draw, by index into the scene's [`ClipTable`].
+    pub clip: u32,
 }
 
 /// Complete unified scene data extracted from DisplayList
@@ -56,6 +164,29 @@ pub struct UnifiedSceneData {
     pub text_draws: Vec<ExtractedTextDraw>,
     pub image_draws: Vec<ExtractedImageDraw>,
     pub svg_draws: Vec<ExtractedSvgDraw>,
+    pub pattern_draws: Vec<ExtractedPatternDraw>,
+    /// Offscreen layers to composite over this scene, from `PushLayer` blocks.
+    pub layers: Vec<ExtractedLayer>,
+    /// Clips the draws of this scene and of its layers refer to. Only the
+    /// outermost scene holds them; those of its layers are left empty.
+    pub clips: ClipTable,
+}
+
+/// `PushLayer` block extracted from a DisplayList, with its content uploaded
+/// as a scene of its own.
+pub struct ExtractedLayer {
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    pub mask: Option<LayerMask>,
+    pub filters: Vec<Filter>,
+    pub backdrop: Option<BackdropFilter>,
+    /// Lowest z-index of the layer content and backdrop filter; the layer is
+    /// composited there.
+    pub z: i32,
+    /// Position of the layer's `PushLayer` among all layers of the uploaded
+    /// list, counting nested layers.
+    pub index: usize,
+    pub scene: UnifiedSceneData,
 }
 
 fn apply_transform(p: [f32; 2], t: Transform2D) -> [f32; 2] {
@@ -273,21 +404,12 @@ fn push_ellipse_radial_gradient(
     }
 }
 
-fn tessellate_path_fill(
-    vertices: &mut Vec<Vertex>,
-    indices: &mut Vec<u16>,
-    path: &Path,
-    color: [f32; 4],
-    z: f32,
-    t: Transform2D,
-) {
+/// Path triangles in local space, before the command transform.
+type Geometry = lyon_tessellation::VertexBuffers<[f32; 2], u16>;
+
+fn to_lyon_path(path: &Path) -> lyon_path::Path {
     use lyon_geom::point;
-    use lyon_path::Path as LyonPath;
-    use lyon_tessellation::{
-        BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers,
-    };
 
-    // Build lyon path
     let mut builder = lyon_path::Path::builder();
     let mut started = false;
     for cmd in &path.cmds {
@@ -327,36 +449,148 @@ fn tessellate_path_fill(
     if started {
         builder.end(false);
     }
-    let lyon_path: LyonPath = builder.build();
-    let mut tess = FillTessellator::new();
-    // Configurable tessellation tolerance via LYON_TOLERANCE (default 0.1)
-    let tol = std::env::var("LYON_TOLERANCE")
+    builder.build()
+}
+
+/// Two triangles covering `rect`, for shading it like a path fill.
+fn rect_geometry(rect: Rect) -> Geometry {
+    let (x0, y0, x1, y1) = (rect.x, rect.y, rect.x + rect.w, rect.y + rect.h);
+    let mut geom = Geometry::new();
+    geom.vertices = vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]];
+    geom.indices = vec![0, 1, 2, 0, 2, 3];
+    geom
+}
+
+/// Triangle fan covering the ellipse, with the segment count `push_ellipse`
+/// uses.
+fn ellipse_geometry(center: [f32; 2], radii: [f32; 2]) -> Geometry {
+    let segs = 64u16;
+    let mut geom = Geometry::new();
+    geom.vertices.push(center);
+    for i in 0..segs {
+        let theta = (i as f32) / (segs as f32) * std::f32::consts::TAU;
+        geom.vertices.push([
+            center[0] + radii[0] * theta.cos(),
+            center[1] + radii[1] * theta.sin(),
+        ]);
+        geom.indices
+            .extend_from_slice(&[0, 1 + i, 1 + (i + 1) % segs]);
+    }
+    geom
+}
+
+/// Configurable tessellation tolerance via LYON_TOLERANCE (default 0.1)
+fn lyon_tolerance() -> f32 {
+    std::env::var("LYON_TOLERANCE")
         .ok()
         .and_then(|v| v.parse::<f32>().ok())
-        .unwrap_or(0.1);
-    let base_opts = FillOptions::default().with_tolerance(tol);
+        .unwrap_or(0.1)
+}
+
+fn fill_geometry(path: &Path) -> Option<Geometry> {
+    use lyon_tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex};
+
+    let lyon_path = to_lyon_path(path);
+    let mut tess = FillTessellator::new();
+    let base_opts = FillOptions::default().with_tolerance(lyon_tolerance());
     let options = match path.fill_rule {
         FillRule::NonZero => base_opts.with_fill_rule(lyon_tessellation::FillRule::NonZero),
         FillRule::EvenOdd => base_opts.with_fill_rule(lyon_tessellation::FillRule::EvenOdd),
     };
-    let mut geom: VertexBuffers<[f32; 2], u16> = VertexBuffers::new();
-    let result = tess.tessellate_path(
+    let mut geom = Geometry::new();
+    tess.tessellate_path(
         lyon_path.as_slice(),
         &options,
         &mut BuffersBuilder::new(&mut geom, |fv: FillVertex| {
             let p = fv.position();
             [p.x, p.y]
         }),
-    );
-    if result.is_err() {
-        return;
+    )
+    .ok()?;
+    Some(geom)
+}
+
+fn stroke_geometry(path: &Path, stroke: &Stroke) -> Option<Geometry> {
+    use lyon_tessellation::{
+        BuffersBuilder, LineCap, LineJoin, StrokeOptions, StrokeTessellator, StrokeVertex,
+    };
+
+    let lyon_path = if stroke.is_dashed() {
+        to_lyon_path(&path.dashed(&stroke.dash, stroke.dash_offset))
+    } else {
+        to_lyon_path(path)
+    };
+    let mut tess = StrokeTessellator::new();
+    let cap = match stroke.cap {
+        crate::scene::LineCap::Butt => LineCap::Butt,
+        crate::scene::LineCap::Square => LineCap::Square,
+        crate::scene::LineCap::Round => LineCap::Round,
+    };
+    let join = match stroke.join {
+        crate::scene::LineJoin::Miter => LineJoin::Miter,
+        crate::scene::LineJoin::Bevel => LineJoin::Bevel,
+        crate::scene::LineJoin::Round => LineJoin::Round,
+    };
+    let options = StrokeOptions::default()
+        .with_line_width(stroke.width.max(0.0))
+        .with_tolerance(lyon_tolerance())
+        .with_line_join(join)
+        .with_miter_limit(stroke.miter_limit.max(StrokeOptions::MINIMUM_MITER_LIMIT))
+        .with_start_cap(cap)
+        .with_end_cap(cap);
+    let mut geom = Geometry::new();
+    tess.tessellate_path(
+        lyon_path.as_slice(),
+        &options,
+        &mut BuffersBuilder::new(&mut geom, |sv: StrokeVertex| {
+            let p = sv.position();
+            [p.x, p.y]
+        }),
+    )
+    .ok()?;
+    Some(geom)
+}
+
+fn tessellate_path_fill(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    path: &Path,
+    color: [f32; 4],
+    z: f32,
+    t: Transform2D,
+) {
+    if let Some(geom) = fill_geometry(path) {
+        push_solid_geometry(vertices, indices, &geom, color, z, t);
     }
+}
+
+fn tessellate_path_stroke(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    path: &Path,
+    stroke: &Stroke,
+    color: [f32; 4],
+    z: f32,
+    t: Transform2D,
+) {
+    if let Some(geom) = stroke_geometry(path, stroke) {
+        push_solid_geometry(vertices, indices, &geom, color, z, t);
+    }
+}
+
+fn push_solid_geometry(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    geom: &Geometry,
+    color: [f32; 4],
+    z: f32,
+    t: Transform2D,
+) {
     // Transform and append
     let base = vertices.len() as u16;
     for p in &geom.vertices {
-        let tp = apply_transform(*p, t);
         vertices.push(Vertex {
-            pos: tp,
+            pos: apply_transform(*p, t),
             color,
             z_index: z,
         });
@@ -364,197 +598,466 @@ fn tessellate_path_fill(
     indices.extend(geom.indices.iter().map(|i| base + *i));
 }
 
-fn tessellate_path_stroke(
+/// Append path geometry shaded with `brush`. Gradient coordinates are in the
+/// path's local space. Image patterns need a texture and are extracted by
+/// [`pattern_mesh`] instead.
+fn push_path_brush(
     vertices: &mut Vec<Vertex>,
     indices: &mut Vec<u16>,
-    path: &Path,
-    stroke: Stroke,
-    color: [f32; 4],
+    geom: &Geometry,
+    brush: &Brush,
     z: f32,
     t: Transform2D,
 ) {
-    use lyon_geom::point;
-    use lyon_path::Path as LyonPath;
-    use lyon_tessellation::{
-        BuffersBuilder, LineCap, LineJoin, StrokeOptions, StrokeTessellator, StrokeVertex,
-        VertexBuffers,
+    match brush {
+        Brush::Solid(c) => push_solid_geometry(vertices, indices, geom, [c.r, c.g, c.b, c.a], z, t),
+        Brush::LinearGradient {
+            start,
+            end,
+            stops,
+            spread,
+        } => {
+            if let Some(stops) = ramp_stops(stops, *spread) {
+                push_linear_gradient_geometry(
+                    vertices, indices, geom, *start, *end, &stops, *spread, z, t,
+                );
+            }
+        }
+        Brush::RadialGradient {
+            center,
+            radius,
+            stops,
+            spread,
+        } => {
+            if let Some(stops) = ramp_stops(stops, *spread) {
+                push_radial_gradient_geometry(
+                    vertices, indices, geom, *center, *radius, &stops, *spread, z, t,
+                );
+            }
+        }
+        Brush::ConicGradient {
+            center,
+            angle,
+            stops,
+            spread,
+        } => {
+            if let Some(stops) = ramp_stops(stops, *spread) {
+                push_conic_gradient_geometry(
+                    vertices, indices, geom, *center, *angle, &stops, *spread, z, t,
+                );
+            }
+        }
+        Brush::Image(_) => {}
+    }
+}
+
+/// Shade triangles with a linear gradient from `start` to `end`.
+///
+/// Colors are linear in the ramp parameter between two stops, so each
+/// triangle is clipped into one polygon per stop interval (and per period of
+/// a repeating spread) and per-vertex color interpolation is exact.
+#[allow(clippy::too_many_arguments)]
+fn push_linear_gradient_geometry(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    geom: &Geometry,
+    start: [f32; 2],
+    end: [f32; 2],
+    stops: &[(f32, [f32; 4])],
+    spread: SpreadMode,
+    z: f32,
+    t: Transform2D,
+) {
+    let d = [end[0] - start[0], end[1] - start[1]];
+    let len2 = d[0] * d[0] + d[1] * d[1];
+    let param = |p: [f32; 2]| {
+        if len2 > 1e-12 {
+            ((p[0] - start[0]) * d[0] + (p[1] - start[1]) * d[1]) / len2
+        } else {
+            0.0
+        }
     };
+    let (lo, hi) = geom
+        .vertices
+        .iter()
+        .map(|p| param(*p))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    let bands = linear_bands(stops, spread, lo, hi);
 
-    // Build lyon path
-    let mut builder = lyon_path::Path::builder();
-    let mut started = false;
-    for cmd in &path.cmds {
-        match *cmd {
-            PathCmd::MoveTo(p) => {
-                if started {
-                    builder.end(false);
-                }
-                builder.begin(point(p[0], p[1]));
-                started = true;
+    for tri in geom.indices.chunks_exact(3) {
+        let poly: Vec<[f32; 4]> = tri
+            .iter()
+            .map(|&i| {
+                let p = geom.vertices[i as usize];
+                [p[0], p[1], param(p), 0.0]
+            })
+            .collect();
+        let lo = poly.iter().map(|v| v[2]).fold(f32::INFINITY, f32::min);
+        let hi = poly.iter().map(|v| v[2]).fold(f32::NEG_INFINITY, f32::max);
+        for &(t0, t1, c0, c1) in &bands {
+            if t1 < lo || t0 > hi {
+                continue;
             }
-            PathCmd::LineTo(p) => {
-                if !started {
-                    builder.begin(point(p[0], p[1]));
-                    started = true;
-                } else {
-                    builder.line_to(point(p[0], p[1]));
-                }
+            let band = clip_polygon(&poly, 2, t0, t1);
+            if band.len() < 3 {
+                continue;
             }
-            PathCmd::QuadTo(c, p) => {
-                builder.quadratic_bezier_to(point(c[0], c[1]), point(p[0], p[1]));
-            }
-            PathCmd::CubicTo(c1, c2, p) => {
-                builder.cubic_bezier_to(
-                    point(c1[0], c1[1]),
-                    point(c2[0], c2[1]),
-                    point(p[0], p[1]),
-                );
+            let base = vertices.len() as u16;
+            for v in &band {
+                let f = if t1.is_finite() && t0.is_finite() && t1 > t0 {
+                    ((v[2] - t0) / (t1 - t0)).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                vertices.push(Vertex {
+                    pos: apply_transform([v[0], v[1]], t),
+                    color: lerp4(c0, c1, f),
+                    z_index: z,
+                });
             }
-            PathCmd::Close => {
-                builder.end(true);
-                started = false;
+            for k in 1..band.len() as u16 - 1 {
+                indices.extend_from_slice(&[base, base + k, base + k + 1]);
             }
         }
     }
-    // End any open sub-path
-    if started {
-        builder.end(false);
-    }
-    let lyon_path: LyonPath = builder.build();
+}
 
-    let mut tess = StrokeTessellator::new();
-    let tol = std::env::var("LYON_TOLERANCE")
-        .ok()
-        .and_then(|v| v.parse::<f32>().ok())
-        .unwrap_or(0.1);
-    let options = StrokeOptions::default()
-        .with_line_width(stroke.width.max(0.0))
-        .with_tolerance(tol)
-        .with_line_join(LineJoin::Round)
-        .with_start_cap(LineCap::Round)
-        .with_end_cap(LineCap::Round);
-    let mut geom: VertexBuffers<[f32; 2], u16> = VertexBuffers::new();
-    let result = tess.tessellate_path(
-        lyon_path.as_slice(),
-        &options,
-        &mut BuffersBuilder::new(&mut geom, |sv: StrokeVertex| {
-            let p = sv.position();
-            [p.x, p.y]
-        }),
-    );
-    if result.is_err() {
-        return;
+/// Stop intervals of a linear ramp as `(t0, t1, c0, c1)` bands covering
+/// `lo..=hi`: the stops with a pad on either side, or for the repeating
+/// spreads one copy of them per period (at most 1024 periods).
+fn linear_bands(
+    stops: &[(f32, [f32; 4])],
+    spread: SpreadMode,
+    lo: f32,
+    hi: f32,
+) -> Vec<(f32, f32, [f32; 4], [f32; 4])> {
+    const MAX_PERIODS: i64 = 1024;
+    let (first, last) = (stops[0], stops[stops.len() - 1]);
+    let intervals = stops
+        .windows(2)
+        .filter(|pair| pair[1].0 > pair[0].0)
+        .map(|pair| (pair[0].0, pair[1].0, pair[0].1, pair[1].1));
+    let span = last.0 - first.0;
+    if spread == SpreadMode::Pad
+        || span.is_nan()
+        || span <= 1e-6
+        || !lo.is_finite()
+        || !hi.is_finite()
+    {
+        // The pad before the first stop, each stop interval, the pad after.
+        let mut bands = vec![(f32::NEG_INFINITY, first.0, first.1, first.1)];
+        bands.extend(intervals);
+        bands.push((last.0, f32::INFINITY, last.1, last.1));
+        return bands;
     }
-    let base = vertices.len() as u16;
-    for p in &geom.vertices {
-        let tp = apply_transform(*p, t);
-        vertices.push(Vertex {
-            pos: tp,
-            color,
-            z_index: z,
-        });
+    let k0 = ((lo - first.0) / span).floor() as i64;
+    let k1 = (((hi - first.0) / span).floor() as i64).min(k0 + MAX_PERIODS);
+    let mut bands = Vec::new();
+    for k in k0..=k1 {
+        let base = first.0 + k as f32 * span;
+        for (t0, t1, c0, c1) in intervals.clone() {
+            if spread == SpreadMode::Reflect && k % 2 != 0 {
+                bands.push((base + last.0 - t1, base + last.0 - t0, c1, c0));
+            } else {
+                bands.push((base + t0 - first.0, base + t1 - first.0, c0, c1));
+            }
+        }
     }
-    indices.extend(geom.indices.iter().map(|i| base + *i));
+    bands
 }
 
-/// Build a Path representing a rounded rectangle using cubic Beziers (kappa approximation).
-/// This path is then tessellated by lyon for precise coverage (avoids fan artifacts on small radii).
-fn rounded_rect_to_path(rrect: RoundedRect) -> Path {
-    let rect = rrect.rect;
-    let mut tl = rrect.radii.tl.min(rect.w * 0.5).min(rect.h * 0.5);
-    let mut tr = rrect.radii.tr.min(rect.w * 0.5).min(rect.h * 0.5);
-    let mut br = rrect.radii.br.min(rect.w * 0.5).min(rect.h * 0.5);
-    let mut bl = rrect.radii.bl.min(rect.w * 0.5).min(rect.h * 0.5);
-
-    // Clamp negative or NaN just in case
-    for r in [&mut tl, &mut tr, &mut br, &mut bl] {
-        if !r.is_finite() || *r < 0.0 {
-            *r = 0.0;
+/// Shade triangles with a circular radial gradient.
+///
+/// The ramp isn't linear across a triangle, so triangles are split along
+/// their longest edge until edges are short relative to `radius` (or to one
+/// period of a repeating spread) and colors are sampled per vertex.
+#[allow(clippy::too_many_arguments)]
+fn push_radial_gradient_geometry(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    geom: &Geometry,
+    center: [f32; 2],
+    radius: f32,
+    stops: &[(f32, [f32; 4])],
+    spread: SpreadMode,
+    z: f32,
+    t: Transform2D,
+) {
+    const MAX_PIECES: usize = 256;
+    let radius = radius.max(1e-3);
+    let period = match spread {
+        SpreadMode::Pad => 1.0,
+        _ => (stops[stops.len() - 1].0 - stops[0].0).clamp(1e-3, 1.0),
+    };
+    let max_edge = (radius * period / 12.0).max(1.0);
+    let color = |p: [f32; 2]| {
+        let (dx, dy) = (p[0] - center[0], p[1] - center[1]);
+        sample_spread(stops, spread, (dx * dx + dy * dy).sqrt() / radius)
+    };
+    let dist2 = |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2);
+
+    for tri in geom.indices.chunks_exact(3) {
+        let mut pending = vec![[
+            geom.vertices[tri[0] as usize],
+            geom.vertices[tri[1] as usize],
+            geom.vertices[tri[2] as usize],
+        ]];
+        let mut pieces = 1;
+        while let Some([a, b, c]) = pending.pop() {
+            // Rotate so that a-b is the longest edge.
+            let edges = [dist2(a, b), dist2(b, c), dist2(c, a)];
+            let longest = (0..3).fold(0, |m, i| if edges[i] > edges[m] { i } else { m });
+            let [a, b, c] = match longest {
+                0 => [a, b, c],
+                1 => [b, c, a],
+                _ => [c, a, b],
+            };
+            if edges[longest] > max_edge * max_edge && pieces < MAX_PIECES {
+                let m = [(a[0] + b[0]) * 0.5, (a[1] + b[1]) * 0.5];
+                pending.push([a, m, c]);
+                pending.push([m, b, c]);
+                pieces += 1;
+                continue;
+            }
+            let base = vertices.len() as u16;
+            for p in [a, b, c] {
+                vertices.push(Vertex {
+                    pos: apply_transform(p, t),
+                    color: color(p),
+                    z_index: z,
+                });
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2]);
         }
     }
+}
 
-    // If radii are effectively zero, fall back to a plain rect path
-    if tl <= 0.0 && tr <= 0.0 && br <= 0.0 && bl <= 0.0 {
-        return Path {
-            cmds: vec![
-                PathCmd::MoveTo([rect.x, rect.y]),
-                PathCmd::LineTo([rect.x + rect.w, rect.y]),
-                PathCmd::LineTo([rect.x + rect.w, rect.y + rect.h]),
-                PathCmd::LineTo([rect.x, rect.y + rect.h]),
-                PathCmd::Close,
-            ],
-            fill_rule: FillRule::NonZero,
-        };
+/// Shade triangles with a conic gradient around `center`.
+///
+/// Triangles are cut into thin angular sectors, split again at stop offsets
+/// and period boundaries so hard stops and repeat seams stay sharp, and
+/// colors are sampled per vertex within each sector.
+#[allow(clippy::too_many_arguments)]
+fn push_conic_gradient_geometry(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    geom: &Geometry,
+    center: [f32; 2],
+    angle: f32,
+    stops: &[(f32, [f32; 4])],
+    spread: SpreadMode,
+    z: f32,
+    t: Transform2D,
+) {
+    const SECTORS: usize = 64;
+    const MAX_EDGES: usize = 1024;
+    let tau = std::f32::consts::TAU;
+    let mut edges: Vec<f32> = (0..=SECTORS).map(|i| i as f32 / SECTORS as f32).collect();
+    let (lo, hi) = (stops[0].0, stops[stops.len() - 1].0);
+    let span = hi - lo;
+    if spread == SpreadMode::Pad || span.is_nan() || span <= 1e-6 {
+        edges.extend(stops.iter().map(|s| s.0));
+    } else {
+        let k0 = ((0.0 - lo) / span).floor() as i64;
+        let k1 = ((1.0 - lo) / span).ceil() as i64;
+        'periods: for k in k0..=k1 {
+            let base = lo + k as f32 * span;
+            for s in stops {
+                let off = match spread {
+                    SpreadMode::Reflect if k % 2 != 0 => base + hi - s.0,
+                    _ => base + s.0 - lo,
+                };
+                if edges.len() >= MAX_EDGES {
+                    break 'periods;
+                }
+                edges.push(off);
+            }
+        }
     }
+    edges.retain(|e| (0.0..=1.0).contains(e));
+    edges.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    edges.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
 
-    // Kappa for quarter circle cubic approximation
-    const K: f32 = 0.552_284_749_831;
-    let x0 = rect.x;
-    let y0 = rect.y;
-    let x1 = rect.x + rect.w;
-    let y1 = rect.y + rect.h;
+    let cross = |d: [f32; 2], p: [f32; 2]| d[0] * (p[1] - center[1]) - d[1] * (p[0] - center[0]);
+    let dir = |turn: f32| {
+        let a = angle + turn * tau;
+        [a.cos(), a.sin()]
+    };
+    for tri in geom.indices.chunks_exact(3) {
+        let tri: Vec<[f32; 2]> = tri.iter().map(|&i| geom.vertices[i as usize]).collect();
+        for sector in edges.windows(2) {
+            let (s0, s1) = (sector[0], sector[1]);
+            let (d0, d1) = (dir(s0), dir(s1));
+            // Keep the wedge between the sector's start and end rays.
+            let poly: Vec<[f32; 4]> = tri
+                .iter()
+                .map(|p| [p[0], p[1], cross(d0, *p), cross(d1, *p)])
+                .collect();
+            let poly = clip_polygon(&poly, 2, 0.0, f32::INFINITY);
+            let poly = clip_polygon(&poly, 3, f32::NEG_INFINITY, 0.0);
+            if poly.len() < 3 {
+                continue;
+            }
+            // Sample just inside the sector so seams take each side's color.
+            let eps = ((s1 - s0) * 0.25).min(1e-5);
+            let base = vertices.len() as u16;
+            for v in &poly {
+                let mut turn =
+                    ((v[1] - center[1]).atan2(v[0] - center[0]) - angle).rem_euclid(tau) / tau;
+                if turn < s0 - 0.5 {
+                    turn += 1.0;
+                } else if turn > s1 + 0.5 {
+                    turn -= 1.0;
+                }
+                vertices.push(Vertex {
+                    pos: apply_transform([v[0], v[1]], t),
+                    color: sample_spread(stops, spread, turn.clamp(s0 + eps, s1 - eps)),
+                    z_index: z,
+                });
+            }
+            for k in 1..poly.len() as u16 - 1 {
+                indices.extend_from_slice(&[base, base + k, base + k + 1]);
+            }
+        }
+    }
+}
 
-    // Start at top-left corner top edge tangent
-    let mut cmds: Vec<PathCmd> = Vec::new();
-    cmds.push(PathCmd::MoveTo([x0 + tl, y0]));
-
-    // Top edge to before TR arc
-    cmds.push(PathCmd::LineTo([x1 - tr, y0]));
-    // TR arc (clockwise): from (x1 - tr, y0) to (x1, y0 + tr)
-    if tr > 0.0 {
-        let c1 = [x1 - tr + K * tr, y0];
-        let c2 = [x1, y0 + tr - K * tr];
-        let p = [x1, y0 + tr];
-        cmds.push(PathCmd::CubicTo(c1, c2, p));
-    } else {
-        cmds.push(PathCmd::LineTo([x1, y0]));
-        cmds.push(PathCmd::LineTo([x1, y0 + tr]));
+/// Textured triangles for path geometry painted with `pattern`, with UVs in
+/// tile units and positions in world space. Non-repeating axes are clipped
+/// to the tile, so the image pipeline samples the mesh with a repeating
+/// address mode. `None` if the pattern is degenerate or the mesh overflows
+/// 16-bit indices.
+fn pattern_mesh(
+    geom: &Geometry,
+    pattern: &ImagePattern,
+    t: Transform2D,
+) -> Option<(Vec<PatternVertex>, Vec<u16>)> {
+    let uv: Vec<[f32; 2]> = geom
+        .vertices
+        .iter()
+        .map(|p| pattern.uv_at(*p))
+        .collect::<Option<_>>()?;
+    let mut vertices: Vec<PatternVertex> = Vec::new();
+    let mut indices: Vec<u16> = Vec::new();
+    for tri in geom.indices.chunks_exact(3) {
+        let mut poly: Vec<[f32; 4]> = tri
+            .iter()
+            .map(|&i| {
+                let (p, uv) = (geom.vertices[i as usize], uv[i as usize]);
+                [p[0], p[1], uv[0], uv[1]]
+            })
+            .collect();
+        if !pattern.repeat.repeats_x() {
+            poly = clip_polygon(&poly, 2, 0.0, 1.0);
+        }
+        if !pattern.repeat.repeats_y() {
+            poly = clip_polygon(&poly, 3, 0.0, 1.0);
+        }
+        if poly.len() < 3 {
+            continue;
+        }
+        if vertices.len() + poly.len() > u16::MAX as usize {
+            return None;
+        }
+        let base = vertices.len() as u16;
+        vertices.extend(poly.iter().map(|v| PatternVertex {
+            pos: apply_transform([v[0], v[1]], t),
+            uv: [v[2], v[3]],
+        }));
+        for k in 1..poly.len() as u16 - 1 {
+            indices.extend_from_slice(&[base, base + k, base + k + 1]);
+        }
     }
+    Some((vertices, indices))
+}
 
-    // Right edge down to before BR arc
-    cmds.push(PathCmd::LineTo([x1, y1 - br]));
-    // BR arc: from (x1, y1 - br) to (x1 - br, y1)
-    if br > 0.0 {
-        let c1 = [x1, y1 - br + K * br];
-        let c2 = [x1 - br + K * br, y1];
-        let p = [x1 - br, y1];
-        cmds.push(PathCmd::CubicTo(c1, c2, p));
-    } else {
-        cmds.push(PathCmd::LineTo([x1, y1]));
-        cmds.push(PathCmd::LineTo([x1 - br, y1]));
+/// Clip a convex polygon of `[x, y, a, b]` vertices to `lo <= v[key] <= hi`.
+/// Attributes must be affine in position, so crossings interpolate linearly.
+fn clip_polygon(poly: &[[f32; 4]], key: usize, lo: f32, hi: f32) -> Vec<[f32; 4]> {
+    fn clip(poly: &[[f32; 4]], inside: impl Fn(&[f32; 4]) -> f32) -> Vec<[f32; 4]> {
+        let mut out = Vec::with_capacity(poly.len() + 2);
+        for (i, a) in poly.iter().enumerate() {
+            let b = &poly[(i + 1) % poly.len()];
+            let (da, db) = (inside(a), inside(b));
+            if da >= 0.0 {
+                out.push(*a);
+            }
+            if (da >= 0.0) != (db >= 0.0) {
+                let f = da / (da - db);
+                out.push(std::array::from_fn(|k| a[k] + (b[k] - a[k]) * f));
+            }
+        }
+        out
+    }
+    let mut out = poly.to_vec();
+    if lo.is_finite() {
+        out = clip(&out, |v| v[key] - lo);
     }
+    if hi.is_finite() && out.len() >= 3 {
+        out = clip(&out, |v| hi - v[key]);
+    }
+    out
+}
 
-    // Bottom edge to before BL arc
-    cmds.push(PathCmd::LineTo([x0 + bl, y1]));
-    // BL arc: from (x0 + bl, y1) to (x0, y1 - bl)
-    if bl > 0.0 {
-        let c1 = [x0 + bl - K * bl, y1];
-        let c2 = [x0, y1 - bl + K * bl];
-        let p = [x0, y1 - bl];
-        cmds.push(PathCmd::CubicTo(c1, c2, p));
-    } else {
-        cmds.push(PathCmd::LineTo([x0, y1]));
-        cmds.push(PathCmd::LineTo([x0, y1 - bl]));
+fn lerp4(a: [f32; 4], b: [f32; 4], f: f32) -> [f32; 4] {
+    std::array::from_fn(|k| a[k] + (b[k] - a[k]) * f)
+}
+
+/// Sort stops and pad them to cover `[0, 1]`.
+pub(crate) fn pad_stops(stops: &[(f32, ColorLinPremul)]) -> Option<Vec<(f32, [f32; 4])>> {
+    let mut packed = sorted_stops(stops)?;
+    let first = packed[0];
+    let last = *packed.last()?;
+    if first.0 > 0.0 {
+        packed.insert(0, (0.0, first.1));
+    }
+    if last.0 < 1.0 {
+        packed.push((1.0, last.1));
     }
+    Some(packed)
+}
 
-    // Left edge up to before TL arc
-    cmds.push(PathCmd::LineTo([x0, y0 + tl]));
-    // TL arc: from (x0, y0 + tl) to (x0 + tl, y0)
-    if tl > 0.0 {
-        let c1 = [x0, y0 + tl - K * tl];
-        let c2 = [x0 + tl - K * tl, y0];
-        let p = [x0 + tl, y0];
-        cmds.push(PathCmd::CubicTo(c1, c2, p));
-    } else {
-        cmds.push(PathCmd::LineTo([x0, y0]));
-        cmds.push(PathCmd::LineTo([x0 + tl, y0]));
+/// Stops for a ramp with `spread`: padded to `[0, 1]` for `Pad`, as
+/// authored for the repeating modes, which cycle over the stops' own span.
+pub(crate) fn ramp_stops(
+    stops: &[(f32, ColorLinPremul)],
+    spread: SpreadMode,
+) -> Option<Vec<(f32, [f32; 4])>> {
+    match spread {
+        SpreadMode::Pad => pad_stops(stops),
+        _ => sorted_stops(stops),
     }
+}
 
-    cmds.push(PathCmd::Close);
-    Path {
-        cmds,
-        fill_rule: FillRule::NonZero,
+fn sorted_stops(stops: &[(f32, ColorLinPremul)]) -> Option<Vec<(f32, [f32; 4])>> {
+    let mut packed: Vec<(f32, [f32; 4])> = stops
+        .iter()
+        .map(|(t, c)| (*t, [c.r, c.g, c.b, c.a]))
+        .collect();
+    packed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    (!packed.is_empty()).then_some(packed)
+}
+
+/// Color of a `spread` ramp at `t`.
+pub(crate) fn sample_spread(stops: &[(f32, [f32; 4])], spread: SpreadMode, t: f32) -> [f32; 4] {
+    let (lo, hi) = (stops[0].0, stops[stops.len() - 1].0);
+    sample_stops(stops, spread.apply(t, lo, hi))
+}
+
+/// Interpolate premultiplied stop colors, clamping `t` to the ramp.
+pub(crate) fn sample_stops(stops: &[(f32, [f32; 4])], t: f32) -> [f32; 4] {
+    for pair in stops.windows(2) {
+        let (t0, c0) = pair[0];
+        let (t1, c1) = pair[1];
+        if t <= t1 {
+            let span = t1 - t0;
+            let f = if span > 1e-6 { (t - t0) / span } else { 1.0 };
+            return lerp4(c0, c1, f.clamp(0.0, 1.0));
+        }
     }
+    stops.last().map(|s| s.1).unwrap_or([0.0; 4])
 }
 
 fn push_rounded_rect(
@@ -566,7 +1069,7 @@ fn push_rounded_rect(
     t: Transform2D,
 ) {
     // Delegate to lyon's robust tessellator via our generic path fill
-    let path = rounded_rect_to_path(rrect);
+    let path = rrect.to_path();
     tessellate_path_fill(vertices, indices, &path, color, z, t);
 }
 
@@ -574,7 +1077,7 @@ fn push_rect_stroke(
     vertices: &mut Vec<Vertex>,
     indices: &mut Vec<u16>,
     rect: Rect,
-    stroke: Stroke,
+    stroke: &Stroke,
     color: [f32; 4],
     z: f32,
     t: Transform2D,
@@ -583,6 +1086,12 @@ fn push_rect_stroke(
     if w <= 0.0001 {
         return;
     }
+    if !stroke.has_square_corners() {
+        // Dashes and other joins: stroke the centerline of the ring.
+        let path = rect_stroke_centerline(rect, w);
+        tessellate_path_stroke(vertices, indices, &path, stroke, color, z, t);
+        return;
+    }
     // Outer corners
     let o0 = apply_transform([rect.x, rect.y], t);
     let o1 = apply_transform([rect.x + rect.w, rect.y], t);
@@ -656,17 +1165,147 @@ fn push_rounded_rect_stroke(
     vertices: &mut Vec<Vertex>,
     indices: &mut Vec<u16>,
     rrect: RoundedRect,
-    stroke: Stroke,
+    stroke: &Stroke,
     color: [f32; 4],
     z: f32,
     t: Transform2D,
 ) {
-    let w = stroke.width.max(0.0);
-    if w <= 0.0001 {
+    if stroke.width <= 0.0001 {
         return;
     }
-    let path = rounded_rect_to_path(rrect);
-    tessellate_path_stroke(vertices, indices, &path, Stroke { width: w }, color, z, t);
+    let path = rrect.to_path();
+    tessellate_path_stroke(vertices, indices, &path, stroke, color, z, t);
+}
+
+/// Append the border ring of `rrect`. Multi-colored borders tessellate the
+/// ring once and clip its triangles to each side's region.
+fn push_border(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    rrect: RoundedRect,
+    border: &Border,
+    z: f32,
+    t: Transform2D,
+) {
+    let Some(geom) = fill_geometry(&border.ring(&rrect)) else {
+        return;
+    };
+    let color = |c: ColorLinPremul| [c.r, c.g, c.b, c.a];
+    if border.is_single_color() {
+        push_solid_geometry(vertices, indices, &geom, color(border.colors[0]), z, t);
+        return;
+    }
+    for (side, quad) in border.side_regions(&rrect).iter().enumerate() {
+        if border.widths[side] <= 0.0 {
+            continue;
+        }
+        let mut part = Geometry::new();
+        for tri in geom.indices.chunks_exact(3) {
+            let tri: Vec<[f32; 2]> = tri.iter().map(|&i| geom.vertices[i as usize]).collect();
+            for region in convex_halves(quad) {
+                let poly = clip_to_triangle(&tri, &region);
+                if poly.len() < 3 {
+                    continue;
+                }
+                let base = part.vertices.len() as u16;
+                part.vertices.extend_from_slice(&poly);
+                for k in 1..poly.len() as u16 - 1 {
+                    part.indices
+                        .extend_from_slice(&[base, base + k, base + k + 1]);
+                }
+            }
+        }
+        push_solid_geometry(vertices, indices, &part, color(border.colors[side]), z, t);
+    }
+}
+
+/// Split a simple quad into two triangles along a diagonal that stays
+/// inside it, i.e. one from its reflex vertex if it has one.
+fn convex_halves(q: &[[f32; 2]; 4]) -> [[[f32; 2]; 3]; 2] {
+    let cross = |i: usize| {
+        let (a, b, c) = (q[(i + 3) % 4], q[i], q[(i + 1) % 4]);
+        (b[0] - a[0]) * (c[1] - b[1]) - (b[1] - a[1]) * (c[0] - b[0])
+    };
+    let winding: f32 = (0..4).map(cross).sum();
+    if cross(1) * winding < 0.0 || cross(3) * winding < 0.0 {
+        [[q[0], q[1], q[3]], [q[1], q[2], q[3]]]
+    } else {
+        [[q[0], q[1], q[2]], [q[0], q[2], q[3]]]
+    }
+}
+
+/// Clip a convex polygon to a triangle of either winding.
+fn clip_to_triangle(poly: &[[f32; 2]], tri: &[[f32; 2]; 3]) -> Vec<[f32; 2]> {
+    let [a, b, c] = *tri;
+    let orient = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+    if orient.abs() <= f32::EPSILON {
+        return Vec::new();
+    }
+    let mut out = poly.to_vec();
+    for i in 0..3 {
+        let (p, q) = (tri[i], tri[(i + 1) % 3]);
+        let side = |v: &[f32; 2]| {
+            ((q[0] - p[0]) * (v[1] - p[1]) - (q[1] - p[1]) * (v[0] - p[0])) * orient.signum()
+        };
+        let mut next = Vec::with_capacity(out.len() + 1);
+        for (j, u) in out.iter().enumerate() {
+            let v = &out[(j + 1) % out.len()];
+            let (du, dv) = (side(u), side(v));
+            if du >= 0.0 {
+                next.push(*u);
+            }
+            if (du >= 0.0) != (dv >= 0.0) {
+                let f = du / (du - dv);
+                next.push([u[0] + (v[0] - u[0]) * f, u[1] + (v[1] - u[1]) * f]);
+            }
+        }
+        out = next;
+        if out.len() < 3 {
+            return Vec::new();
+        }
+    }
+    out
+}
+
+/// Closed path along the middle of the inside stroke of `rect`, so that
+/// stroking it with `width` covers the same ring as `push_rect_stroke`.
+pub(crate) fn rect_stroke_centerline(rect: Rect, width: f32) -> Path {
+    let h = (width * 0.5).min(rect.w * 0.5).min(rect.h * 0.5).max(0.0);
+    let (x0, y0) = (rect.x + h, rect.y + h);
+    let (x1, y1) = (rect.x + rect.w - h, rect.y + rect.h - h);
+    Path {
+        cmds: vec![
+            PathCmd::MoveTo([x0, y0]),
+            PathCmd::LineTo([x1, y0]),
+            PathCmd::LineTo([x1, y1]),
+            PathCmd::LineTo([x0, y1]),
+            PathCmd::Close,
+        ],
+        fill_rule: FillRule::NonZero,
+    }
+}
+
+/// Triangles covering `clip` in display list space, for writing it to a
+/// stencil. A shape that fails to tessellate has none, clipping everything.
+pub(crate) fn clip_triangles(clip: &Clip) -> (Vec<Vertex>, Vec<u16>) {
+    let geom = match &clip.shape {
+        ClipShape::Rect(rect) => Some(rect_geometry(*rect)),
+        ClipShape::RoundedRect(rrect) => fill_geometry(&rrect.to_path()),
+        ClipShape::Path(path) => fill_geometry(path),
+    };
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    if let Some(geom) = geom {
+        push_solid_geometry(
+            &mut vertices,
+            &mut indices,
+            &geom,
+            [0.0; 4],
+            0.0,
+            clip.transform,
+        );
+    }
+    (vertices, indices)
 }
 
 pub fn upload_display_list(
@@ -701,7 +1340,11 @@ pub fn upload_display_list(
                         vertices.extend_from_slice(&v);
                         indices.extend(i.iter().map(|idx| base + idx));
                     }
-                    Brush::LinearGradient { stops, .. } => {
+                    Brush::LinearGradient {
+                        stops,
+                        spread: SpreadMode::Pad,
+                        ..
+                    } => {
                         // Only handle horizontal gradients for now: map t along x within rect
                         let mut packed: Vec<(f32, [f32; 4])> = stops
                             .iter()
@@ -728,7 +1371,15 @@ pub fn upload_display_list(
                             *z as f32,
                         );
                     }
-                    _ => {}
+                    Brush::Image(_) => {}
+                    _ => push_path_brush(
+                        &mut vertices,
+                        &mut indices,
+                        &rect_geometry(*rect),
+                        brush,
+                        *z as f32,
+                        *transform,
+                    ),
                 }
             }
             Command::DrawRoundedRect {
@@ -764,7 +1415,7 @@ pub fn upload_display_list(
                         &mut vertices,
                         &mut indices,
                         *rect,
-                        *stroke,
+                        stroke,
                         color,
                         *z as f32,
                         *transform,
@@ -785,13 +1436,28 @@ pub fn upload_display_list(
                         &mut vertices,
                         &mut indices,
                         *rrect,
-                        *stroke,
+                        stroke,
                         color,
                         *z as f32,
                         *transform,
                     );
                 }
             }
+            Command::DrawBorder {
+                rrect,
+                border,
+                z,
+                transform,
+            } => {
+                push_border(
+                    &mut vertices,
+                    &mut indices,
+                    *rrect,
+                    border,
+                    *z as f32,
+                    *transform,
+                );
+            }
             Command::DrawEllipse {
                 center,
                 radii,
@@ -816,6 +1482,7 @@ pub fn upload_display_list(
                     center: _gcenter,
                     radius: _r,
                     stops,
+                    spread: SpreadMode::Pad,
                 } => {
                     let mut packed: Vec<(f32, [f32; 4])> = stops
                         .iter()
@@ -842,43 +1509,52 @@ pub fn upload_display_list(
                         *transform,
                     );
                 }
-                _ => {}
+                Brush::Image(_) => {}
+                _ => push_path_brush(
+                    &mut vertices,
+                    &mut indices,
+                    &ellipse_geometry(*center, *radii),
+                    brush,
+                    *z as f32,
+                    *transform,
+                ),
             },
             Command::FillPath {
                 path,
-                color,
+                brush,
                 transform,
                 z,
                 ..
             } => {
-                let col = [color.r, color.g, color.b, color.a];
-                tessellate_path_fill(
-                    &mut vertices,
-                    &mut indices,
-                    path,
-                    col,
-                    *z as f32,
-                    *transform,
-                );
+                if let Some(geom) = fill_geometry(path) {
+                    push_path_brush(
+                        &mut vertices,
+                        &mut indices,
+                        &geom,
+                        brush,
+                        *z as f32,
+                        *transform,
+                    );
+                }
             }
             Command::StrokePath {
                 path,
                 stroke,
-                color,
+                brush,
                 transform,
                 z,
                 ..
             } => {
-                let col = [color.r, color.g, color.b, color.a];
-                tessellate_path_stroke(
-                    &mut vertices,
-                    &mut indices,
-                    path,
-                    *stroke,
-                    col,
-                    *z as f32,
-                    *transform,
-                );
+                if let Some(geom) = stroke_geometry(path, stroke) {
+                    push_path_brush(
+                        &mut vertices,
+                        &mut indices,
+                        &geom,
+                        brush,
+                        *z as f32,
+                        *transform,
+                    );
+                }
             }
             // BoxShadow commands are handled by PassManager as a separate pipeline.
             Command::BoxShadow { .. } => {}
@@ -890,6 +1566,8 @@ pub fn upload_display_list(
         }
     }
 
+    let z_starts = group_by_clip_and_z(&vertices, &mut indices, &[]);
+
     // Ensure index buffer size meets COPY_BUFFER_ALIGNMENT (4 bytes)
     if (indices.len() % 2) != 0 {
         if indices.len() >= 3 {
@@ -925,6 +1603,7 @@ pub fn upload_display_list(
         index: ibuf,
         vertices: vertices.len() as u32,
         indices: indices.len() as u32,
+        z_starts,
     })
 }
 
@@ -936,39 +1615,94 @@ pub fn upload_display_list(
 /// - text_draws: Text runs with their transforms and z-indices
 /// - image_draws: Image draws (currently placeholder, will be implemented)
 /// - svg_draws: SVG draws (currently placeholder, will be implemented)
+/// - pattern_draws: Textured meshes for paths painted with image patterns
+/// - layers: `PushLayer` blocks, each uploaded recursively; layers without
+///   any drawable content are dropped
 pub fn upload_display_list_unified(
     allocator: &mut RenderAllocator,
     queue: &wgpu::Queue,
     list: &DisplayList,
 ) -> Result<UnifiedSceneData> {
+    let mut clips = ClipTable::default();
+    let mut scene = upload_unified_from(allocator, queue, list, 0, &mut clips)?;
+    scene.clips = clips;
+    Ok(scene)
+}
+
+/// `upload_display_list_unified` for a list whose first layer has index
+/// `first_layer`, interning the clips of its draws into `clips`.
+fn upload_unified_from(
+    allocator: &mut RenderAllocator,
+    queue: &wgpu::Queue,
+    list: &DisplayList,
+    first_layer: usize,
+    clips: &mut ClipTable,
+) -> Result<UnifiedSceneData> {
+    let (list, blocks) = list.split_layers();
+    let mut layers: Vec<ExtractedLayer> = Vec::new();
+    for block in blocks {
+        let Some(z) = block.z() else {
+            continue;
+        };
+        let index = first_layer + block.index;
+        layers.push(ExtractedLayer {
+            opacity: block.opacity,
+            blend_mode: block.blend_mode,
+            mask: block.mask,
+            filters: block.filters,
+            backdrop: block.backdrop,
+            z,
+            index,
+            scene: upload_unified_from(allocator, queue, &block.list, index + 1, clips)?,
+        });
+    }
+
     let mut vertices: Vec<Vertex> = Vec::new();
     let mut indices: Vec<u16> = Vec::new();
     let mut text_draws: Vec<ExtractedTextDraw> = Vec::new();
     let mut image_draws: Vec<ExtractedImageDraw> = Vec::new();
     let mut svg_draws: Vec<ExtractedSvgDraw> = Vec::new();
+    let mut pattern_draws: Vec<ExtractedPatternDraw> = Vec::new();
 
-    // Track transform stack for completeness, but note that draw commands
-    // already carry fully-composed world transforms. For unified upload we
-    // treat the per-command transform as authoritative and use the stack
-    // only to mirror the current state (kept for potential future use).
+    // Draw commands already carry fully-composed world transforms; the
+    // stack only places clips, which take the transform they were pushed
+    // under.
     let mut transform_stack: Vec<Transform2D> = vec![Transform2D::identity()];
-    let mut _current_transform = Transform2D::identity();
+    let mut current_transform = Transform2D::identity();
+    // Clips pushed so far, their index in `clips`, and the index from which
+    // on each change of clip applies to the solid geometry.
+    let mut clip_stack: Vec<Clip> = Vec::new();
+    let mut clip = 0;
+    let mut clip_starts: Vec<(usize, u32)> = Vec::new();
 
     for cmd in &list.commands {
         match cmd {
             // Handle transform stack
             Command::PushTransform(t) => {
                 // `t` is already the composed world transform at this stack depth.
-                _current_transform = *t;
-                transform_stack.push(_current_transform);
+                current_transform = *t;
+                transform_stack.push(current_transform);
             }
             Command::PopTransform => {
                 transform_stack.pop();
-                _current_transform = transform_stack
+                current_transform = transform_stack
                     .last()
                     .copied()
                     .unwrap_or(Transform2D::identity());
             }
+            Command::PushClip(shape) => {
+                clip_stack.push(Clip {
+                    shape: shape.clone(),
+                    transform: current_transform,
+                });
+                clip = clips.intern(&clip_stack);
+                clip_starts.push((indices.len(), clip));
+            }
+            Command::PopClip => {
+                clip_stack.pop();
+                clip = clips.intern(&clip_stack);
+                clip_starts.push((indices.len(), clip));
+            }
 
             // Extract text commands
             Command::DrawText {
@@ -980,6 +1714,7 @@ pub fn upload_display_list_unified(
                     run: run.clone(),
                     z: *z,
                     transform: final_transform,
+                    clip,
                 });
             }
 
@@ -999,11 +1734,13 @@ pub fn upload_display_list_unified(
                     pos: hyperlink.pos,
                     size: hyperlink.size,
                     color: hyperlink.color,
+                    spans: Vec::new(),
                 };
                 text_draws.push(ExtractedTextDraw {
                     run: text_run,
                     z: *z,
                     transform: final_transform,
+                    clip,
                 });
 
                 // Draw underline if enabled
@@ -1068,7 +1805,11 @@ pub fn upload_display_list_unified(
                         vertices.extend_from_slice(&v);
                         indices.extend(i.iter().map(|idx| base + idx));
                     }
-                    Brush::LinearGradient { stops, .. } => {
+                    Brush::LinearGradient {
+                        stops,
+                        spread: SpreadMode::Pad,
+                        ..
+                    } => {
                         // Only handle horizontal gradients for now: map t along x within rect
                         let mut packed: Vec<(f32, [f32; 4])> = stops
                             .iter()
@@ -1095,7 +1836,15 @@ pub fn upload_display_list_unified(
                             *z as f32,
                         );
                     }
-                    _ => {}
+                    Brush::Image(_) => {}
+                    _ => push_path_brush(
+                        &mut vertices,
+                        &mut indices,
+                        &rect_geometry(*rect),
+                        brush,
+                        *z as f32,
+                        final_transform,
+                    ),
                 }
             }
             Command::DrawRoundedRect {
@@ -1133,7 +1882,7 @@ pub fn upload_display_list_unified(
                         &mut vertices,
                         &mut indices,
                         *rect,
-                        *stroke,
+                        stroke,
                         color,
                         *z as f32,
                         final_transform,
@@ -1155,13 +1904,28 @@ pub fn upload_display_list_unified(
                         &mut vertices,
                         &mut indices,
                         *rrect,
-                        *stroke,
+                        stroke,
                         color,
                         *z as f32,
                         final_transform,
                     );
                 }
             }
+            Command::DrawBorder {
+                rrect,
+                border,
+                z,
+                transform,
+            } => {
+                push_border(
+                    &mut vertices,
+                    &mut indices,
+                    *rrect,
+                    border,
+                    *z as f32,
+                    *transform,
+                );
+            }
             Command::DrawEllipse {
                 center,
                 radii,
@@ -1188,6 +1952,7 @@ pub fn upload_display_list_unified(
                         center: _gcenter,
                         radius: _r,
                         stops,
+                        spread: SpreadMode::Pad,
                     } => {
                         let mut packed: Vec<(f32, [f32; 4])> = stops
                             .iter()
@@ -1214,46 +1979,59 @@ pub fn upload_display_list_unified(
                             final_transform,
                         );
                     }
-                    _ => {}
+                    Brush::Image(_) => {}
+                    _ => push_path_brush(
+                        &mut vertices,
+                        &mut indices,
+                        &ellipse_geometry(*center, *radii),
+                        brush,
+                        *z as f32,
+                        final_transform,
+                    ),
                 }
             }
             Command::FillPath {
                 path,
-                color,
+                brush,
                 transform,
                 z,
                 ..
-            } => {
-                let final_transform = *transform;
-                let col = [color.r, color.g, color.b, color.a];
-                tessellate_path_fill(
-                    &mut vertices,
-                    &mut indices,
-                    path,
-                    col,
-                    *z as f32,
-                    final_transform,
-                );
             }
-            Command::StrokePath {
+            | Command::StrokePath {
                 path,
-                stroke,
-                color,
+                brush,
                 transform,
                 z,
                 ..
             } => {
-                let final_transform = *transform;
-                let col = [color.r, color.g, color.b, color.a];
-                tessellate_path_stroke(
-                    &mut vertices,
-                    &mut indices,
-                    path,
-                    *stroke,
-                    col,
-                    *z as f32,
-                    final_transform,
-                );
+                let geom = match cmd {
+                    Command::StrokePath { stroke, .. } => stroke_geometry(path, stroke),
+                    _ => fill_geometry(path),
+                };
+                let Some(geom) = geom else {
+                    continue;
+                };
+                if let Brush::Image(pattern) = brush {
+                    // Patterns sample a texture, so they go to the image pipeline.
+                    if let Some((vertices, indices)) = pattern_mesh(&geom, pattern, *transform) {
+                        pattern_draws.push(ExtractedPatternDraw {
+                            path: pattern.path.clone(),
+                            vertices,
+                            indices,
+                            z: *z,
+                            clip,
+                        });
+                    }
+                } else {
+                    push_path_brush(
+                        &mut vertices,
+                        &mut indices,
+                        &geom,
+                        brush,
+                        *z as f32,
+                        *transform,
+                    );
+                }
             }
             Command::DrawImage {
                 path,
@@ -1271,6 +2049,7 @@ pub fn upload_display_list_unified(
                     size: *size,
                     z: *z,
                     transform: final_transform,
+                    clip,
                 });
             }
             Command::DrawSvg {
@@ -1289,6 +2068,7 @@ pub fn upload_display_list_unified(
                     size: *max_size,
                     z: *z,
                     transform: final_transform,
+                    clip,
                 });
             }
             // BoxShadow commands are handled by PassManager as a separate pipeline.
@@ -1297,12 +2077,13 @@ pub fn upload_display_list_unified(
             Command::HitRegionRect { .. } => {}
             Command::HitRegionRoundedRect { .. } => {}
             Command::HitRegionEllipse { .. } => {}
-            // Clip commands would need special handling in unified rendering
-            Command::PushClip(_) => {}
-            Command::PopClip => {}
+            // Layer blocks were split off above.
+            Command::PushLayer { .. } | Command::PopLayer => {}
         }
     }
 
+    let z_starts = group_by_clip_and_z(&vertices, &mut indices, &clip_starts);
+
     // Ensure index buffer size meets COPY_BUFFER_ALIGNMENT (4 bytes)
     if (indices.len() % 2) != 0 {
         if indices.len() >= 3 {
@@ -1339,9 +2120,192 @@ pub fn upload_display_list_unified(
             index: ibuf,
             vertices: vertices.len() as u32,
             indices: indices.len() as u32,
+            z_starts,
         },
         text_draws,
         image_draws,
         svg_draws,
+        pattern_draws,
+        layers,
+        clips: ClipTable::default(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: f32) -> Path {
+        Path {
+            cmds: vec![
+                PathCmd::MoveTo([0.0, 0.0]),
+                PathCmd::LineTo([size, 0.0]),
+                PathCmd::LineTo([size, size]),
+                PathCmd::LineTo([0.0, size]),
+                PathCmd::Close,
+            ],
+            fill_rule: FillRule::NonZero,
+        }
+    }
+
+    #[test]
+    fn triangles_are_grouped_by_clip_and_z() {
+        let vertex = |z: f32| Vertex {
+            z_index: z,
+            ..Vertex::default()
+        };
+        let vertices = [vertex(2.0), vertex(0.0), vertex(1.0), vertex(0.0)];
+        let mut indices = vec![0, 0, 0, 1, 1, 1, 2, 2, 2, 3, 3, 3];
+        let starts = group_by_clip_and_z(&vertices, &mut indices, &[]);
+        assert_eq!(indices, vec![1, 1, 1, 3, 3, 3, 2, 2, 2, 0, 0, 0]);
+        assert_eq!(starts, vec![(0, 0, 0), (0, 1, 6), (0, 2, 9)]);
+
+        // Clipped triangles follow the unclipped ones, grouped by clip.
+        let mut indices = vec![0, 0, 0, 1, 1, 1, 2, 2, 2, 3, 3, 3];
+        let starts = group_by_clip_and_z(&vertices, &mut indices, &[(3, 1), (9, 0)]);
+        assert_eq!(indices, vec![3, 3, 3, 0, 0, 0, 1, 1, 1, 2, 2, 2]);
+        assert_eq!(starts, vec![(0, 0, 0), (0, 2, 3), (1, 0, 6), (1, 1, 9)]);
+    }
+
+    #[test]
+    fn linear_gradient_paths_split_at_stops() {
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let geom = fill_geometry(&square(10.0)).unwrap();
+        let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+        let brush = Brush::LinearGradient {
+            start: [2.0, 0.0],
+            end: [8.0, 0.0],
+            stops: vec![(0.0, red), (0.5, red), (1.0, blue)],
+            spread: SpreadMode::Pad,
+        };
+        let t = Transform2D::identity();
+        push_path_brush(&mut vertices, &mut indices, &geom, &brush, 0.0, t);
+        assert_eq!(indices.len() % 3, 0);
+        // Every vertex carries the exact ramp color at its position.
+        for v in &vertices {
+            let s = ((v.pos[0] - 2.0) / 6.0).clamp(0.0, 1.0);
+            let f = ((s - 0.5) / 0.5).max(0.0);
+            assert!((v.color[0] - (1.0 - f)).abs() < 1e-4, "{v:?}");
+            assert!((v.color[2] - f).abs() < 1e-4, "{v:?}");
+        }
+        // The stop boundaries became geometry edges.
+        for x in [2.0, 5.0, 8.0] {
+            assert!(vertices.iter().any(|v| (v.pos[0] - x).abs() < 1e-4));
+        }
+    }
+
+    #[test]
+    fn repeating_gradients_split_at_every_period() {
+        let red = ColorLinPremul::rgba(255, 0, 0, 255);
+        let blue = ColorLinPremul::rgba(0, 0, 255, 255);
+        let geom = fill_geometry(&square(10.0)).unwrap();
+        let t = Transform2D::identity();
+
+        let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+        let brush = Brush::LinearGradient {
+            start: [0.0, 0.0],
+            end: [2.0, 0.0],
+            stops: vec![(0.0, red), (1.0, blue)],
+            spread: SpreadMode::Reflect,
+        };
+        push_path_brush(&mut vertices, &mut indices, &geom, &brush, 0.0, t);
+        for v in &vertices {
+            // Triangle wave with a 4px period: blue at odd multiples of 2px.
+            let f = 1.0 - ((v.pos[0] / 2.0).rem_euclid(2.0) - 1.0).abs();
+            assert!((v.color[2] - f).abs() < 1e-4, "{v:?}");
+        }
+        for x in [2.0, 4.0, 6.0, 8.0] {
+            assert!(vertices.iter().any(|v| (v.pos[0] - x).abs() < 1e-4));
+        }
+
+        // Conic halves: red clockwise from +x for half a turn, then blue.
+        let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+        let brush = Brush::ConicGradient {
+            center: [5.0, 5.0],
+            angle: 0.0,
+            stops: vec![(0.0, red), (0.5, red), (0.5, blue), (1.0, blue)],
+            spread: SpreadMode::Pad,
+        };
+        push_path_brush(&mut vertices, &mut indices, &geom, &brush, 0.0, t);
+        assert!(!indices.is_empty());
+        for v in &vertices {
+            if v.pos[1] > 5.01 {
+                assert_eq!(v.color, [1.0, 0.0, 0.0, 1.0], "{v:?}");
+            } else if v.pos[1] < 4.99 {
+                assert_eq!(v.color, [0.0, 0.0, 1.0, 1.0], "{v:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn pattern_meshes_clip_non_repeating_axes() {
+        let geom = fill_geometry(&square(10.0)).unwrap();
+        let tile = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 4.0,
+            h: 4.0,
+        };
+        let t = Transform2D::translate(100.0, 0.0);
+
+        let repeat = ImagePattern::new("tile.png", tile);
+        let (vertices, _) = pattern_mesh(&geom, &repeat, t).unwrap();
+        let max_u = vertices.iter().map(|v| v.uv[0]).fold(0.0, f32::max);
+        assert!((max_u - 2.5).abs() < 1e-4);
+
+        let once = repeat
+            .clone()
+            .with_repeat(crate::scene::PatternRepeat::NoRepeat);
+        let (vertices, indices) = pattern_mesh(&geom, &once, t).unwrap();
+        assert!(!indices.is_empty());
+        for v in &vertices {
+            assert!((-1e-4..=1.0 + 1e-4).contains(&v.uv[0]), "{v:?}");
+            assert!((-1e-4..=1.0 + 1e-4).contains(&v.uv[1]), "{v:?}");
+            assert!((v.pos[0] - 100.0 - v.uv[0] * 4.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn multi_colored_borders_split_the_ring_by_side() {
+        let colors = [
+            ColorLinPremul::rgba(255, 0, 0, 255),
+            ColorLinPremul::rgba(0, 255, 0, 255),
+            ColorLinPremul::rgba(0, 0, 255, 255),
+            ColorLinPremul::rgba(255, 255, 0, 255),
+        ];
+        let rrect = RoundedRect {
+            rect: Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 10.0,
+                h: 10.0,
+            },
+            radii: crate::scene::RoundedRadii::default(),
+        };
+        let border = Border {
+            widths: [1.0; 4],
+            colors,
+        };
+        let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+        let t = Transform2D::identity();
+        push_border(&mut vertices, &mut indices, rrect, &border, 0.0, t);
+        // Each side is a trapezoid of area (10 + 8) / 2 meeting its
+        // neighbours along the corner diagonals.
+        for c in colors {
+            let area: f32 = indices
+                .chunks_exact(3)
+                .map(|tri| [0, 1, 2].map(|k| vertices[tri[k] as usize]))
+                .filter(|tri| tri[0].color == [c.r, c.g, c.b, c.a])
+                .map(|[a, b, c]| {
+                    let (u, v) = (
+                        [b.pos[0] - a.pos[0], b.pos[1] - a.pos[1]],
+                        [c.pos[0] - a.pos[0], c.pos[1] - a.pos[1]],
+                    );
+                    (u[0] * v[1] - u[1] * v[0]).abs() * 0.5
+                })
+                .sum();
+            assert!((area - 9.0).abs() < 1e-3, "{c:?}: {area}");
+        }
+    }
+}
@@ -78,18 +78,33 @@ fn fs_main(inp: VsOut) -> @location(0) vec4<f32> {
 }
 "#;
 
-/// Gradient utilities (structure only; evaluated in linear space)
+/// Gradient ramp helpers, prepended to shaders that evaluate gradients.
+/// Stop colors are premultiplied linear RGBA and are mixed as such.
 pub const GRADIENT_WGSL: &str = r#"
-struct Stop { pos: f32, color: vec4<f32> }; // premultiplied linear RGBA
+const SPREAD_PAD: u32 = 0u;
+const SPREAD_REPEAT: u32 = 1u;
+const SPREAD_REFLECT: u32 = 2u;
+
+// Map a ramp position onto the stop span [lo, hi] (SpreadMode::apply).
+fn spread_t(t: f32, lo: f32, hi: f32, mode: u32) -> f32 {
+    let span = hi - lo;
+    if (mode == SPREAD_PAD || span <= 1e-6) {
+        return clamp(t, lo, hi);
+    }
+    let u = (t - lo) / span;
+    var f = u - floor(u);
+    if (mode == SPREAD_REFLECT) {
+        let m = u - 2.0 * floor(u * 0.5);
+        f = select(m, 2.0 - m, m > 1.0);
+    }
+    return lo + f * span;
+}
 
-fn eval_linear_gradient(stops: array<Stop>, t: f32) -> vec4<f32> {
-    // Naive two-stop mix for illustration; full implementation will handle N stops.
-    let clamped = clamp(t, 0.0, 1.0);
-    // Assume two stops for now
-    let a = stops[0];
-    let b = stops[1];
-    let tt = (clamped - a.pos) / max(1e-6, (b.pos - a.pos));
-    return mix(a.color, b.color, clamp(tt, 0.0, 1.0));
+// Fraction of a clockwise (y-down) turn from `angle` radians off +x.
+fn conic_t(d: vec2<f32>, angle: f32) -> f32 {
+    let tau = 6.28318530718;
+    let a = atan2(d.y, d.x) - angle;
+    return (a - tau * floor(a / tau)) / tau;
 }
 "#;
 
@@ -130,6 +145,201 @@ fn fs_main(inp: VsOut) -> @location(0) vec4<f32> {
 }
 "#;
 
+/// Layer compositor: draws a premultiplied offscreen layer over its backdrop
+/// with group opacity, an optional alpha mask and a separable blend mode.
+/// Textures are read per pixel, so layer, mask and backdrop must match the
+/// target size.
+pub const LAYER_COMPOSITE_WGSL: &str = r#"
+struct LayerUniform {
+    opacity: f32,
+    mode: u32,     // BlendMode::shader_index; 0 = normal, blended by hardware
+    has_mask: u32,
+    _pad: u32,
+};
+
+@group(0) @binding(0) var layer_tex: texture_2d<f32>;
+@group(0) @binding(1) var mask_tex: texture_2d<f32>;
+@group(0) @binding(2) var backdrop_tex: texture_2d<f32>;
+@group(0) @binding(3) var<uniform> u: LayerUniform;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vi: u32) -> @builtin(position) vec4<f32> {
+    var pos = array<vec2<f32>, 3>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>( 3.0, -1.0),
+        vec2<f32>(-1.0,  3.0),
+    );
+    return vec4<f32>(pos[vi], 0.0, 1.0);
+}
+
+fn screen(cb: f32, cs: f32) -> f32 {
+    return cb + cs - cb * cs;
+}
+
+fn hard_light(cb: f32, cs: f32) -> f32 {
+    if (cs <= 0.5) {
+        return cb * 2.0 * cs;
+    }
+    return screen(cb, 2.0 * cs - 1.0);
+}
+
+fn blend_channel(mode: u32, cb: f32, cs: f32) -> f32 {
+    switch mode {
+        case 1u: { return cb * cs; }
+        case 2u: { return screen(cb, cs); }
+        case 3u: { return hard_light(cs, cb); }
+        case 4u: { return min(cb, cs); }
+        case 5u: { return max(cb, cs); }
+        case 6u: {
+            if (cb <= 0.0) { return 0.0; }
+            if (cs >= 1.0) { return 1.0; }
+            return min(cb / (1.0 - cs), 1.0);
+        }
+        case 7u: {
+            if (cb >= 1.0) { return 1.0; }
+            if (cs <= 0.0) { return 0.0; }
+            return 1.0 - min((1.0 - cb) / cs, 1.0);
+        }
+        case 8u: { return hard_light(cb, cs); }
+        case 9u: {
+            if (cs <= 0.5) {
+                return cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+            }
+            var d = sqrt(cb);
+            if (cb <= 0.25) {
+                d = ((16.0 * cb - 12.0) * cb + 4.0) * cb;
+            }
+            return cb + (2.0 * cs - 1.0) * (d - cb);
+        }
+        case 10u: { return abs(cb - cs); }
+        case 11u: { return cb + cs - 2.0 * cb * cs; }
+        default: { return cs; }
+    }
+}
+
+fn unpremul(c: vec3<f32>, a: f32) -> vec3<f32> {
+    if (a > 1e-6) {
+        return clamp(c / a, vec3<f32>(0.0), vec3<f32>(1.0));
+    }
+    return vec3<f32>(0.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) frag: vec4<f32>) -> @location(0) vec4<f32> {
+    let p = vec2<i32>(frag.xy);
+    var k = u.opacity;
+    if (u.has_mask != 0u) {
+        k = k * textureLoad(mask_tex, p, 0).a;
+    }
+    let src = textureLoad(layer_tex, p, 0) * k;
+    if (u.mode == 0u) {
+        return src; // premultiplied source-over via the blend state
+    }
+    // Full separable blend against a copy of the backdrop; written as-is.
+    let dst = textureLoad(backdrop_tex, p, 0);
+    let cb = unpremul(dst.rgb, dst.a);
+    let cs = unpremul(src.rgb, src.a);
+    let mixed = vec3<f32>(
+        blend_channel(u.mode, cb.r, cs.r),
+        blend_channel(u.mode, cb.g, cs.g),
+        blend_channel(u.mode, cb.b, cs.b),
+    );
+    let rgb = src.rgb * (1.0 - dst.a) + dst.rgb * (1.0 - src.a) + src.a * dst.a * mixed;
+    return vec4<f32>(rgb, src.a + dst.a * (1.0 - src.a));
+}
+"#;
+
+/// Image filter passes over a full-size premultiplied texture, read per pixel.
+/// `mode` 0 is one direction of a separable Gaussian blur, 1 a 4x5 color
+/// matrix on unpremultiplied color, 2 the source alpha tinted and offset (a
+/// drop shadow before blurring) and 3 a plain copy (to draw content back over
+/// its shadow).
+pub const FILTER_WGSL: &str = r#"
+struct FilterParams {
+    m0: vec4<f32>,
+    m1: vec4<f32>,
+    m2: vec4<f32>,
+    m3: vec4<f32>,
+    bias: vec4<f32>,
+    tint: vec4<f32>,    // premultiplied shadow color
+    dir: vec2<f32>,     // blur direction, (1,0) or (0,1)
+    offset: vec2<f32>,  // shadow offset in pixels
+    sigma: f32,         // blur standard deviation in pixels
+    mode: u32,
+    clamp_edges: u32,   // blur reads edge pixels instead of transparency
+    _pad: u32,
+};
+
+@group(0) @binding(0) var src_tex: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> u: FilterParams;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vi: u32) -> @builtin(position) vec4<f32> {
+    var pos = array<vec2<f32>, 3>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>( 3.0, -1.0),
+        vec2<f32>(-1.0,  3.0),
+    );
+    return vec4<f32>(pos[vi], 0.0, 1.0);
+}
+
+fn in_bounds(q: vec2<i32>, dims: vec2<i32>) -> bool {
+    return q.x >= 0 && q.y >= 0 && q.x < dims.x && q.y < dims.y;
+}
+
+fn blur(p: vec2<i32>, dims: vec2<i32>) -> vec4<f32> {
+    let sigma = max(u.sigma, 1e-3);
+    let r = min(i32(ceil(3.0 * sigma)), 96);
+    let dir = vec2<i32>(u.dir);
+    var acc = vec4<f32>(0.0);
+    var norm = 0.0;
+    for (var i: i32 = -r; i <= r; i = i + 1) {
+        let fi = f32(i);
+        let w = exp(-0.5 * fi * fi / (sigma * sigma));
+        norm = norm + w;
+        var q = p + dir * i;
+        if (u.clamp_edges != 0u) {
+            q = clamp(q, vec2<i32>(0), dims - vec2<i32>(1));
+        } else if (!in_bounds(q, dims)) {
+            continue;
+        }
+        acc = acc + textureLoad(src_tex, q, 0) * w;
+    }
+    return acc / max(norm, 1e-6);
+}
+
+fn color_matrix(c: vec4<f32>) -> vec4<f32> {
+    var s = vec4<f32>(0.0);
+    if (c.a > 0.0) {
+        s = vec4<f32>(c.rgb / c.a, c.a);
+    }
+    let o = clamp(
+        vec4<f32>(dot(u.m0, s), dot(u.m1, s), dot(u.m2, s), dot(u.m3, s)) + u.bias,
+        vec4<f32>(0.0),
+        vec4<f32>(1.0),
+    );
+    return vec4<f32>(o.rgb * o.a, o.a);
+}
+
+@fragment
+fn fs_main(@builtin(position) frag: vec4<f32>) -> @location(0) vec4<f32> {
+    let p = vec2<i32>(frag.xy);
+    let dims = vec2<i32>(textureDimensions(src_tex));
+    switch u.mode {
+        case 0u: { return blur(p, dims); }
+        case 1u: { return color_matrix(textureLoad(src_tex, p, 0)); }
+        case 2u: {
+            let q = p - vec2<i32>(round(u.offset));
+            if (!in_bounds(q, dims)) {
+                return vec4<f32>(0.0);
+            }
+            return u.tint * textureLoad(src_tex, q, 0).a;
+        }
+        default: { return textureLoad(src_tex, p, 0); }
+    }
+}
+"#;
+
 /// Fast blit shader for copying intermediate texture to surface (no filtering, nearest neighbor).
 /// This is optimized for the resize use case where we want the fastest possible copy.
 pub const BLIT_WGSL: &str = r#"
@@ -276,7 +486,8 @@ fn fs_resolve(inp: VsOut) -> @location(0) vec4<f32> {
 }
 "#;
 
-/// Background fill (solid or linear gradient) drawn via fullscreen triangle.
+/// Background fill (solid, linear, radial or conic gradient) drawn via
+/// fullscreen triangle. Compile with [`GRADIENT_WGSL`] prepended.
 pub const BACKGROUND_WGSL: &str = r#"
 const MAX_STOPS: u32 = 8u;
 
@@ -308,7 +519,7 @@ fn vs_main(@builtin(vertex_index) vi: u32) -> VsOut {
 struct BgUniform {
     start_end: vec4<f32>,                // start.xy, end.xy
     center_radius_stop: vec4<f32>,       // center.xy, radius, stop_count (f32)
-    flags: vec4<f32>,                    // x: mode(0/1/2), y: debug(0/1), z: aspect_ratio, w: unused
+    flags: vec4<f32>,                    // x: mode(0/1/2/3), y: debug(0/1), z: aspect_ratio, w: spread
 };
 
 struct Stop { 
@@ -333,8 +544,9 @@ fn eval_stops(t: f32) -> vec4<f32> {
         return stops[0u].color; 
     }
     
-    // Clamp t to valid range
-    let t_clamped = clamp(t, 0.0, 1.0);
+    // Wrap t into the stops' span per the spread mode
+    let spread = u32(bg.flags.w + 0.5);
+    let t_clamped = spread_t(t, stops[0u].pos, stops[stop_count - 1u].pos, spread);
     
     // Before first stop
     if (t_clamped <= stops[0u].pos) { 
@@ -384,9 +596,20 @@ fn fs_main(inp: VsOut) -> @location(0) vec4<f32> {
     if (mode == 1u) {
         let dir = end - start;
         let denom = max(1e-6, dot(dir, dir));
-        let t = clamp(dot(uv01 - start, dir) / denom, 0.0, 1.0);
+        let t = dot(uv01 - start, dir) / denom;
         return eval_stops(t);
     }
+    if (mode == 3u) {
+        // Conic: sweep around center from start_end.x radians, with the
+        // same aspect correction as the radial mode.
+        var d = uv01 - center;
+        if (aspect >= 1.0) {
+            d.x = d.x * aspect;
+        } else {
+            d.y = d.y / max(1e-6, aspect);
+        }
+        return eval_stops(conic_t(d, bg.start_end.x));
+    }
     // Radial gradient mode (mode == 2)
     // Aspect-correct radial distance so rings remain circular in screen space.
     // We normalize distances by the smaller screen dimension, so scale the
@@ -403,10 +626,11 @@ fn fs_main(inp: VsOut) -> @location(0) vec4<f32> {
         let dy = dy0 / max(1e-6, aspect);
         d = sqrt(dx0 * dx0 + dy * dy);
     }
-    let t = clamp(d / max(1e-6, radius), 0.0, 1.0);
+    let t = d / max(1e-6, radius);
     if (debug == 1u) {
         // Debug: show t value as grayscale
-        return vec4<f32>(t, t, t, 1.0);
+        let g = clamp(t, 0.0, 1.0);
+        return vec4<f32>(g, g, g, 1.0);
     }
     return eval_stops(t);
 } 
@@ -523,6 +747,8 @@ fn fs_main(inp: VsOut) -> @location(0) vec4<f32> {
 /// - @group(1) @binding(0): Mask texture (Rgba8Unorm or Rgba16Unorm)
 /// - @group(1) @binding(1): Sampler (nearest recommended)
 /// - @location(2): Per-vertex color (premultiplied linear RGBA)
+/// - @location(3): Color glyph flag; when set the texture holds a premultiplied
+///   sRGB color image (emoji) drawn as is, scaled by the color's alpha
 pub const TEXT_WGSL: &str = r#"
 struct ViewportUniform {
     scale: vec2<f32>,      // 2/W, -2/H
@@ -536,14 +762,22 @@ struct VsIn {
     @location(0) pos: vec2<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) color: vec4<f32>,
+    @location(3) color_glyph: f32,
 };
 
 struct VsOut {
     @builtin(position) pos: vec4<f32>,
     @location(0) uv: vec2<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) color_glyph: f32,
 };
 
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+    let lo = c / 12.92;
+    let hi = pow((c + vec3<f32>(0.055)) / 1.055, vec3<f32>(2.4));
+    return select(hi, lo, c <= vec3<f32>(0.04045));
+}
+
 @vertex
 fn vs_main(inp: VsIn) -> VsOut {
     var out: VsOut;
@@ -555,6 +789,7 @@ fn vs_main(inp: VsIn) -> VsOut {
     out.pos = vec4<f32>(ndc, depth, 1.0);
     out.uv = inp.uv;
     out.color = inp.color;
+    out.color_glyph = inp.color_glyph;
     return out;
 }
 
@@ -565,6 +800,15 @@ fn vs_main(inp: VsIn) -> VsOut {
 fn fs_main(inp: VsOut) -> @location(0) vec4<f32> {
     // Nearest sampling prevents color bleeding across subpixels
     let m = textureSample(mask_tex, mask_smp, inp.uv);
+    if (inp.color_glyph > 0.5) {
+        // Premultiplied sRGB color image: convert to premultiplied linear
+        // and apply only the text alpha.
+        if (m.a <= 0.0) {
+            return vec4<f32>(0.0);
+        }
+        let lin = srgb_to_linear(clamp(m.rgb / m.a, vec3<f32>(0.0), vec3<f32>(1.0)));
+        return vec4<f32>(lin * m.a, m.a) * inp.color.a;
+    }
     // Use RGB subpixel coverage directly for crisp rendering
     // The premultiplied color is modulated by each coverage channel
     let rgb = vec3<f32>(inp.color.r * m.r, inp.color.g * m.g, inp.color.b * m.b);
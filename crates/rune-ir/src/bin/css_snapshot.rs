@@ -268,11 +268,13 @@ fn to_snapshot_map(v: &ComputedStyle2, el: &ElementRef) -> Map<String, Value> {
                         }
                         v.join(", ")
                     };
+                    let prefix = if g.repeating { "repeating-" } else { "" };
                     if g.angle == 0.0 {
-                        layers.push(format!("linear-gradient({})", stops_str));
+                        layers.push(format!("{}linear-gradient({})", prefix, stops_str));
                     } else {
                         layers.push(format!(
-                            "linear-gradient({}deg, {})",
+                            "{}linear-gradient({}deg, {})",
+                            prefix,
                             trim_float(g.angle),
                             stops_str
                         ));
@@ -292,7 +294,8 @@ fn to_snapshot_map(v: &ComputedStyle2, el: &ElementRef) -> Map<String, Value> {
                         v.join(", ")
                     };
                     layers.push(format!(
-                        "radial-gradient({} {} at {} {}, {})",
+                        "{}radial-gradient({} {} at {} {}, {})",
+                        if g.repeating { "repeating-" } else { "" },
                         px(g.rx),
                         px(g.ry),
                         cx,
@@ -300,6 +303,28 @@ fn to_snapshot_map(v: &ComputedStyle2, el: &ElementRef) -> Map<String, Value> {
                         stops_str
                     ));
                 }
+                rune_ir::BackgroundLayer2::Conic(g) => {
+                    let cx = format!("{}%", trim_float(g.cx * 100.0));
+                    let cy = format!("{}%", trim_float(g.cy * 100.0));
+                    let stops_str = if g.stops.is_empty() {
+                        "#000000, #000000".to_string()
+                    } else {
+                        let mut v: Vec<String> = Vec::with_capacity(g.stops.len());
+                        for s in &g.stops {
+                            let deg = trim_float(s.offset * 360.0);
+                            v.push(format!("{} {}deg", s.color, deg));
+                        }
+                        v.join(", ")
+                    };
+                    layers.push(format!(
+                        "{}conic-gradient(from {}deg at {} {}, {})",
+                        if g.repeating { "repeating-" } else { "" },
+                        trim_float(g.angle),
+                        cx,
+                        cy,
+                        stops_str
+                    ));
+                }
             }
         }
         if !layers.is_empty() {
@@ -311,7 +336,14 @@ fn to_snapshot_map(v: &ComputedStyle2, el: &ElementRef) -> Map<String, Value> {
     }
 
     // Border/Radius/Shadow
-    if let Some(r) = v.corner_radius {
+    if let Some(r) = v.corner_radii {
+        let horizontal: Vec<String> = r.corners().iter().map(|c| px(c[0])).collect();
+        let vertical: Vec<String> = r.corners().iter().map(|c| px(c[1])).collect();
+        m.insert(
+            "border-radius".to_string(),
+            json!(format!("{} / {}", horizontal.join(" "), vertical.join(" "))),
+        );
+    } else if let Some(r) = v.corner_radius {
         m.insert("border-radius".to_string(), json!(px(r)));
     }
     // Prefer per-side if specified (CSS parity), otherwise emit uniform
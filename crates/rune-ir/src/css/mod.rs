@@ -104,12 +104,62 @@ mod tests {
         assert_eq!(g.stops[1].offset, 1.0);
     }
 
+    #[test]
+    fn background_repeating_and_conic_gradients() {
+        let v = compute_inline_only("background: repeating-linear-gradient(90deg, red, blue 10%)");
+        let g = v
+            .background_gradient
+            .expect("parsed repeating linear gradient");
+        assert!(g.repeating);
+        assert_eq!(g.stops[1].offset, 0.1);
+
+        let v = compute_inline_only(
+            "background: conic-gradient(from 90deg at 25% 75%, red 0deg 90deg, blue 0.5turn, lime)",
+        );
+        let Some(BackgroundLayer2::Conic(c)) = v.background_layers.first() else {
+            panic!("Expected conic gradient in background_layers");
+        };
+        assert!(!c.repeating);
+        assert_eq!((c.angle, c.cx, c.cy), (90.0, 0.25, 0.75));
+        let offsets: Vec<f64> = c.stops.iter().map(|s| s.offset).collect();
+        assert_eq!(offsets, vec![0.0, 0.25, 0.5, 1.0]);
+        assert_eq!(c.stops[1].color, "#ff0000");
+
+        let v =
+            compute_inline_only("background: repeating-conic-gradient(red 0 10%, blue 10% 20%)");
+        assert!(matches!(
+            v.background_layers.first(),
+            Some(BackgroundLayer2::Conic(c)) if c.repeating && c.stops.len() == 4
+        ));
+    }
+
     #[test]
     fn border_radius_parses_length() {
         let v = compute_inline_only("border-radius: 8px");
         assert_eq!(v.corner_radius, Some(8.0));
     }
 
+    #[test]
+    fn border_radius_parses_per_corner_elliptical_radii() {
+        let v = compute_inline_only("border-radius: 10px 20px / 5px");
+        assert_eq!(v.corner_radius, Some(10.0));
+        let r = v.corner_radii.unwrap();
+        assert_eq!(r.top_left, [10.0, 5.0]);
+        assert_eq!(r.top_right, [20.0, 5.0]);
+        assert_eq!(r.bottom_right, [10.0, 5.0]);
+        assert_eq!(r.bottom_left, [20.0, 5.0]);
+
+        let v = compute_inline_only("border-radius: 8px; border-bottom-right-radius: 0");
+        let r = v.corner_radii.unwrap();
+        assert_eq!((r.top_left, r.bottom_right), ([8.0, 8.0], [0.0, 0.0]));
+
+        // Uniform radii only use the single-value field.
+        let v = compute_inline_only("border-radius: 6px 6px");
+        assert_eq!((v.corner_radius, v.corner_radii), (Some(6.0), None));
+        let v = compute_inline_only("border-radius: 1px 2px 3px 4px 5px");
+        assert_eq!(v.corner_radius, None);
+    }
+
     #[test]
     fn border_and_box_shadow_parse() {
         let v =
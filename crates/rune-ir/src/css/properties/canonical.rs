@@ -1,6 +1,8 @@
 use crate::css::types::{
-    BackgroundLayer2, ComputedStyle2, Display2, LinearGradient2, RadialGradient2,
+    BackgroundLayer2, ColorStop, ComputedStyle2, ConicGradient2, Display2, LinearGradient2,
+    RadialGradient2,
 };
+use crate::view::CornerRadii;
 use csscolorparser::Color as CssColor;
 use std::str::FromStr;
 
@@ -13,6 +15,13 @@ pub fn is_whitelisted_property(name: &str) -> bool {
             | "justify-content"
             | "align-items"
             | "text-align"
+            | "hyphens"
+            | "tab-size"
+            | "text-overflow"
+            | "line-clamp"
+            | "-webkit-line-clamp"
+            | "writing-mode"
+            | "text-orientation"
             | "gap"
             | "column-gap"
             | "row-gap"
@@ -26,6 +35,8 @@ pub fn is_whitelisted_property(name: &str) -> bool {
             | "font-size"
             | "line-height"
             | "font-weight"
+            | "font-feature-settings"
+            | "font-variation-settings"
             | "background-color"
             | "background"
             | "border"            // shorthand: width style color
@@ -44,6 +55,10 @@ pub fn is_whitelisted_property(name: &str) -> bool {
             | "border-width"
             | "border-color"
             | "border-radius"
+            | "border-top-left-radius"
+            | "border-top-right-radius"
+            | "border-bottom-right-radius"
+            | "border-bottom-left-radius"
             | "box-shadow"
             | "margin"
             | "margin-top"
@@ -114,9 +129,52 @@ pub fn apply_whitelisted_property(out: &mut ComputedStyle2, name: &str, raw: &st
             out.text_align = Some(match value.to_ascii_lowercase().as_str() {
                 "center" => crate::css::types::TextAlign2::Center,
                 "right" | "end" => crate::css::types::TextAlign2::End,
+                "justify" => crate::css::types::TextAlign2::Justify,
                 _ => crate::css::types::TextAlign2::Start,
             });
         }
+        "hyphens" => {
+            out.hyphens = match value.to_ascii_lowercase().as_str() {
+                "none" => Some(crate::view::Hyphens::None),
+                "manual" => Some(crate::view::Hyphens::Manual),
+                "auto" => Some(crate::view::Hyphens::Auto),
+                _ => None,
+            }
+        }
+        // Only lengths: a bare number counts spaces, whose width isn't known here.
+        "tab-size" => {
+            out.tab_size = value
+                .strip_suffix("px")
+                .and_then(|px| px.trim().parse().ok())
+        }
+        "text-overflow" => {
+            out.text_overflow = match value.to_ascii_lowercase().as_str() {
+                "clip" => Some(crate::view::TextOverflow::Clip),
+                "ellipsis" => Some(crate::view::TextOverflow::Ellipsis),
+                "ellipsis-start" => Some(crate::view::TextOverflow::EllipsisStart),
+                "ellipsis-middle" => Some(crate::view::TextOverflow::EllipsisMiddle),
+                _ => None,
+            }
+        }
+        "line-clamp" | "-webkit-line-clamp" => {
+            out.line_clamp = value.parse::<u32>().ok().filter(|&n| n > 0)
+        }
+        "writing-mode" => {
+            out.writing_mode = match value.to_ascii_lowercase().as_str() {
+                "horizontal-tb" => Some(crate::view::WritingMode::HorizontalTb),
+                "vertical-rl" => Some(crate::view::WritingMode::VerticalRl),
+                "vertical-lr" => Some(crate::view::WritingMode::VerticalLr),
+                _ => None,
+            }
+        }
+        "text-orientation" => {
+            out.text_orientation = match value.to_ascii_lowercase().as_str() {
+                "mixed" => Some(crate::view::TextOrientation::Mixed),
+                "upright" => Some(crate::view::TextOrientation::Upright),
+                "sideways" => Some(crate::view::TextOrientation::Sideways),
+                _ => None,
+            }
+        }
         "gap" => out.gap = parse_length(value),
         "column-gap" => out.column_gap = parse_length(value),
         "row-gap" => out.row_gap = parse_length(value),
@@ -148,9 +206,19 @@ pub fn apply_whitelisted_property(out: &mut ComputedStyle2, name: &str, raw: &st
         "font-size" => out.font_size = parse_length(value),
         "line-height" => out.line_height = parse_length(value),
         "font-weight" => out.font_weight = parse_font_weight(value),
+        "font-feature-settings" => out.font_feature_settings = Some(value.to_string()),
+        "font-variation-settings" => out.font_variation_settings = Some(value.to_string()),
         "background-color" => out.background_color = canonical_color(value),
         "background" => apply_background(out, value),
-        "border-radius" => out.corner_radius = parse_border_radius(value),
+        "border-radius" => {
+            if let Some(radii) = parse_border_radius(value) {
+                set_corner_radii(out, radii);
+            }
+        }
+        "border-top-left-radius" => apply_corner_radius(out, value, 0),
+        "border-top-right-radius" => apply_corner_radius(out, value, 1),
+        "border-bottom-right-radius" => apply_corner_radius(out, value, 2),
+        "border-bottom-left-radius" => apply_corner_radius(out, value, 3),
         // Shorthands
         "border" => apply_border(out, value),
         "border-top" => apply_border_side(out, value, "top"),
@@ -411,6 +479,10 @@ fn apply_background(out: &mut ComputedStyle2, value: &str) {
     // Build ordered background_layers (first = topmost layer)
     out.background_layers.clear();
     for layer in &layers {
+        if let Some(con) = parse_conic_gradient(layer) {
+            out.background_layers.push(BackgroundLayer2::Conic(con));
+            continue;
+        }
         if let Some(rad) = parse_radial_gradient(layer) {
             out.background_layers.push(BackgroundLayer2::Radial(rad));
             continue;
@@ -568,7 +640,9 @@ fn parse_grid_template(value: &str) -> Option<crate::css::types::GridTemplate2>
 fn parse_linear_gradient(input: &str) -> Option<LinearGradient2> {
     let s = input.trim();
     let lower = s.to_ascii_lowercase();
-    if !lower.starts_with("linear-gradient(") || !s.ends_with(')') {
+    let repeating = lower.starts_with("repeating-");
+    let name = lower.strip_prefix("repeating-").unwrap_or(&lower);
+    if !name.starts_with("linear-gradient(") || !s.ends_with(')') {
         return None;
     }
     let inner = &s[s.find('(')? + 1..s.rfind(')')?];
@@ -661,63 +735,21 @@ fn parse_linear_gradient(input: &str) -> Option<LinearGradient2> {
         return None;
     }
 
-    // Resolve offsets: first=0, last=1 by default, interpolate interior
-    let n = raw_stops.len();
-    let mut offs: Vec<Option<f64>> = raw_stops.iter().map(|(_, o)| *o).collect();
-    if offs[0].is_none() {
-        offs[0] = Some(0.0);
-    }
-    if offs[n - 1].is_none() {
-        offs[n - 1] = Some(1.0);
-    }
-    let mut i = 0usize;
-    while i + 1 < n {
-        if let Some(left) = offs[i] {
-            let mut j = i + 1;
-            while j < n && offs[j].is_none() {
-                j += 1;
-            }
-            if j < n {
-                let right = offs[j].unwrap();
-                let gaps = j - i - 1;
-                if gaps > 0 {
-                    let step = (right - left) / (gaps as f64 + 1.0);
-                    for k in 1..=gaps {
-                        offs[i + k] = Some(left + step * k as f64);
-                    }
-                }
-                i = j;
-            } else {
-                break;
-            }
-        } else {
-            i += 1;
-        }
-    }
-    if offs.iter().any(|o| o.is_none()) {
-        for (idx, o) in offs.iter_mut().enumerate() {
-            if o.is_none() {
-                *o = Some(idx as f64 / (n - 1) as f64);
-            }
-        }
-    }
+    let stops = resolve_color_stops(raw_stops);
 
-    let stops = raw_stops
-        .into_iter()
-        .zip(offs.into_iter())
-        .map(|((color, _), off)| crate::css::types::ColorStop {
-            color,
-            offset: off.unwrap_or(0.0),
-        })
-        .collect::<Vec<_>>();
-
-    Some(LinearGradient2 { angle, stops })
+    Some(LinearGradient2 {
+        angle,
+        stops,
+        repeating,
+    })
 }
 
 pub fn parse_radial_gradient(input: &str) -> Option<RadialGradient2> {
     let s = input.trim();
     let lower = s.to_ascii_lowercase();
-    if !lower.starts_with("radial-gradient(") || !s.ends_with(')') {
+    let repeating = lower.starts_with("repeating-");
+    let name = lower.strip_prefix("repeating-").unwrap_or(&lower);
+    if !name.starts_with("radial-gradient(") || !s.ends_with(')') {
         return None;
     }
     let inner = &s[s.find('(')? + 1..s.rfind(')')?];
@@ -794,33 +826,7 @@ pub fn parse_radial_gradient(input: &str) -> Option<RadialGradient2> {
         }
         // Position: keywords or percentages
         if !pos_str.is_empty() {
-            let mut it = pos_str.split_whitespace();
-            if let Some(x) = it.next() {
-                cx = match x.to_ascii_lowercase().as_str() {
-                    "left" => 0.0,
-                    "center" => 0.5,
-                    "right" => 1.0,
-                    _ => x
-                        .trim_end_matches('%')
-                        .parse::<f64>()
-                        .ok()
-                        .map(|p| p / 100.0)
-                        .unwrap_or(cx),
-                };
-            }
-            if let Some(y) = it.next() {
-                cy = match y.to_ascii_lowercase().as_str() {
-                    "top" => 0.0,
-                    "center" => 0.5,
-                    "bottom" => 1.0,
-                    _ => y
-                        .trim_end_matches('%')
-                        .parse::<f64>()
-                        .ok()
-                        .map(|p| p / 100.0)
-                        .unwrap_or(cy),
-                };
-            }
+            (cx, cy) = parse_gradient_position(pos_str, cx, cy);
         }
         // Size/shape
         if !before_at.is_empty() {
@@ -873,7 +879,173 @@ pub fn parse_radial_gradient(input: &str) -> Option<RadialGradient2> {
         return None;
     }
 
-    // Resolve offsets: first=0 if missing, last=1 if missing, interpolate interior
+    let stops_vec = resolve_color_stops(raw_stops);
+
+    Some(RadialGradient2 {
+        cx,
+        cy,
+        rx,
+        ry,
+        stops: stops_vec,
+        repeating,
+    })
+}
+
+/// Parse `conic-gradient(...)` / `repeating-conic-gradient(...)`.
+///
+/// Supports an optional `from <angle>` and `at <position>` preamble; stop
+/// positions may be angles (`deg`, `turn`, `rad`, `grad`) or percentages and
+/// are stored as fractions of a turn. Double-position stops (`red 0deg 90deg`)
+/// expand to two stops.
+pub fn parse_conic_gradient(input: &str) -> Option<ConicGradient2> {
+    let s = input.trim();
+    let lower = s.to_ascii_lowercase();
+    let repeating = lower.starts_with("repeating-");
+    let name = lower.strip_prefix("repeating-").unwrap_or(&lower);
+    if !name.starts_with("conic-gradient(") || !s.ends_with(')') {
+        return None;
+    }
+    let inner = &s[s.find('(')? + 1..s.rfind(')')?];
+
+    // Split top-level by commas, respecting nested parentheses
+    let mut parts: Vec<&str> = Vec::new();
+    let mut depth = 0i32;
+    let mut start_ix = 0usize;
+    for (i, ch) in inner.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(inner[start_ix..i].trim());
+                start_ix = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(inner[start_ix..].trim());
+
+    // Angle or percentage → fraction of a turn
+    fn parse_turn(value: &str) -> Option<f64> {
+        let v = value.trim().to_ascii_lowercase();
+        let (num, scale) = if let Some(n) = v.strip_suffix('%') {
+            (n, 0.01)
+        } else if let Some(n) = v.strip_suffix("deg") {
+            (n, 1.0 / 360.0)
+        } else if let Some(n) = v.strip_suffix("grad") {
+            (n, 1.0 / 400.0)
+        } else if let Some(n) = v.strip_suffix("rad") {
+            (n, 1.0 / std::f64::consts::TAU)
+        } else if let Some(n) = v.strip_suffix("turn") {
+            (n, 1.0)
+        } else if v == "0" {
+            (v.as_str(), 0.0)
+        } else {
+            return None;
+        };
+        num.trim().parse::<f64>().ok().map(|n| n * scale)
+    }
+
+    // Color followed by zero, one or two positions
+    fn parse_conic_stop(chunk: &str) -> Option<Vec<(String, Option<f64>)>> {
+        let t = chunk.trim();
+        if t.is_empty() {
+            return None;
+        }
+        if let Some(color) = canonical_color(t) {
+            return Some(vec![(color, None)]);
+        }
+        let (rest, last) = t.rsplit_once(char::is_whitespace)?;
+        let last = parse_turn(last)?;
+        if let Some(color) = canonical_color(rest.trim()) {
+            return Some(vec![(color, Some(last))]);
+        }
+        let (color_part, first) = rest.trim().rsplit_once(char::is_whitespace)?;
+        let first = parse_turn(first)?;
+        let color = canonical_color(color_part.trim())?;
+        Some(vec![(color.clone(), Some(first)), (color, Some(last))])
+    }
+
+    let mut cx = 0.5f64;
+    let mut cy = 0.5f64;
+    let mut angle = 0.0f64;
+    let mut color_parts = &parts[..];
+    if parse_conic_stop(parts[0]).is_none() {
+        let pre = parts[0].trim();
+        let pre_lower = pre.to_ascii_lowercase();
+        let (from_str, pos_str) = match pre_lower.find("at ") {
+            Some(idx) if idx == 0 || pre_lower[..idx].ends_with(' ') => {
+                (pre[..idx].trim(), pre[idx + 3..].trim())
+            }
+            _ => (pre, ""),
+        };
+        if !from_str.is_empty() {
+            let a = from_str
+                .get(..4)
+                .filter(|kw| kw.eq_ignore_ascii_case("from"))
+                .map(|_| &from_str[4..])?;
+            angle = parse_turn(a)? * 360.0;
+        }
+        if !pos_str.is_empty() {
+            (cx, cy) = parse_gradient_position(pos_str, cx, cy);
+        }
+        color_parts = &parts[1..];
+    }
+
+    let mut raw_stops: Vec<(String, Option<f64>)> = Vec::new();
+    for p in color_parts.iter().copied() {
+        if let Some(stops) = parse_conic_stop(p) {
+            raw_stops.extend(stops);
+        }
+    }
+    if raw_stops.is_empty() {
+        return None;
+    }
+
+    Some(ConicGradient2 {
+        cx,
+        cy,
+        angle,
+        stops: resolve_color_stops(raw_stops),
+        repeating,
+    })
+}
+
+/// Parse a gradient `<position>` of keywords or percentages into fractions
+/// of the box, keeping `cx`/`cy` for components that are missing or invalid.
+fn parse_gradient_position(pos_str: &str, mut cx: f64, mut cy: f64) -> (f64, f64) {
+    let mut it = pos_str.split_whitespace();
+    if let Some(x) = it.next() {
+        cx = match x.to_ascii_lowercase().as_str() {
+            "left" => 0.0,
+            "center" => 0.5,
+            "right" => 1.0,
+            _ => x
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .ok()
+                .map(|p| p / 100.0)
+                .unwrap_or(cx),
+        };
+    }
+    if let Some(y) = it.next() {
+        cy = match y.to_ascii_lowercase().as_str() {
+            "top" => 0.0,
+            "center" => 0.5,
+            "bottom" => 1.0,
+            _ => y
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .ok()
+                .map(|p| p / 100.0)
+                .unwrap_or(cy),
+        };
+    }
+    (cx, cy)
+}
+
+/// Resolve color stop offsets: first=0 and last=1 when missing, interior
+/// gaps interpolated evenly between their positioned neighbours.
+fn resolve_color_stops(raw_stops: Vec<(String, Option<f64>)>) -> Vec<ColorStop> {
     let n = raw_stops.len();
     let mut offs: Vec<Option<f64>> = raw_stops.iter().map(|(_, o)| *o).collect();
     if offs[0].is_none() {
@@ -914,29 +1086,75 @@ pub fn parse_radial_gradient(input: &str) -> Option<RadialGradient2> {
         }
     }
 
-    let stops_vec: Vec<crate::css::types::ColorStop> = raw_stops
+    raw_stops
         .into_iter()
         .zip(offs.into_iter())
-        .map(|((color, _), off)| crate::css::types::ColorStop {
+        .map(|((color, _), off)| ColorStop {
             color,
             offset: off.unwrap_or(0.0),
         })
-        .collect();
+        .collect()
+}
 
-    Some(RadialGradient2 {
-        cx,
-        cy,
-        rx,
-        ry,
-        stops: stops_vec,
+/// Parse the `border-radius` shorthand: one to four horizontal radii,
+/// optionally followed by `/` and one to four vertical radii, in
+/// top-left, top-right, bottom-right, bottom-left order.
+fn parse_border_radius(input: &str) -> Option<CornerRadii> {
+    // Expand 1-4 values to the four corners like `margin` does.
+    fn corners(values: &str) -> Option<[f64; 4]> {
+        let v: Vec<f64> = values
+            .split_whitespace()
+            .map(parse_length)
+            .collect::<Option<_>>()?;
+        match v[..] {
+            [a] => Some([a; 4]),
+            [a, b] => Some([a, b, a, b]),
+            [a, b, c] => Some([a, b, c, b]),
+            [a, b, c, d] => Some([a, b, c, d]),
+            _ => None,
+        }
+    }
+    let mut parts = input.splitn(2, '/');
+    let h = corners(parts.next()?)?;
+    let v = match parts.next() {
+        Some(vertical) => corners(vertical)?,
+        None => h,
+    };
+    Some(CornerRadii {
+        top_left: [h[0], v[0]],
+        top_right: [h[1], v[1]],
+        bottom_right: [h[2], v[2]],
+        bottom_left: [h[3], v[3]],
     })
 }
 
-fn parse_border_radius(input: &str) -> Option<f64> {
-    // Take the first length, convert to px if provided without unit.
-    let first = input.split('/').next().unwrap_or("").trim();
-    let token = first.split_whitespace().next().unwrap_or("");
-    super::canonical::parse_length(token)
+/// Apply a `border-*-radius` longhand (`<h> [<v>]`) to corner `index`.
+fn apply_corner_radius(out: &mut ComputedStyle2, value: &str, index: usize) {
+    let values: Option<Vec<f64>> = value.split_whitespace().map(parse_length).collect();
+    let radius = match values.as_deref() {
+        Some(&[r]) => [r, r],
+        Some(&[h, v]) => [h, v],
+        _ => return,
+    };
+    let mut radii = out
+        .corner_radii
+        .or_else(|| out.corner_radius.map(CornerRadii::uniform))
+        .unwrap_or_default();
+    match index {
+        0 => radii.top_left = radius,
+        1 => radii.top_right = radius,
+        2 => radii.bottom_right = radius,
+        _ => radii.bottom_left = radius,
+    }
+    set_corner_radii(out, radii);
+}
+
+/// Record `radii`, keeping `corner_radius` as the top-left radius for
+/// consumers that only support one. Per-corner radii are only kept when
+/// they differ from it.
+fn set_corner_radii(out: &mut ComputedStyle2, radii: CornerRadii) {
+    out.corner_radius = Some(radii.top_left[0]);
+    out.corner_radii = (!radii.is_uniform()).then_some(radii);
 }
 
 /// Parse a border shorthand into (width, color). Ignores style keywords.
@@ -54,6 +54,7 @@ pub enum TextAlign2 {
     Start,
     Center,
     End,
+    Justify,
 }
 
 /// Edges container for margin/padding.
@@ -73,6 +74,12 @@ pub struct ComputedStyle2 {
     pub justify_content: Option<crate::view::LayoutJustify>,
     pub align_items: Option<crate::view::LayoutAlign>,
     pub text_align: Option<TextAlign2>,
+    pub hyphens: Option<crate::view::Hyphens>,
+    pub tab_size: Option<f64>,
+    pub text_overflow: Option<crate::view::TextOverflow>,
+    pub line_clamp: Option<u32>,
+    pub writing_mode: Option<crate::view::WritingMode>,
+    pub text_orientation: Option<crate::view::TextOrientation>,
     pub wrap: Option<bool>,
     pub gap: Option<f64>,
     // Grid-specific
@@ -90,6 +97,8 @@ pub struct ComputedStyle2 {
     pub font_size: Option<f64>,
     pub line_height: Option<f64>,
     pub font_weight: Option<f64>,
+    pub font_feature_settings: Option<String>,
+    pub font_variation_settings: Option<String>,
 
     pub background_color: Option<String>,
     pub background_gradient: Option<LinearGradient2>,
@@ -114,11 +123,14 @@ pub struct ComputedStyle2 {
     pub object_fit: Option<crate::view::ImageContentFit>,
 
     pub corner_radius: Option<f64>,
+    /// Per-corner elliptical radii, set only when they aren't all equal
+    /// to `corner_radius`.
+    pub corner_radii: Option<crate::view::CornerRadii>,
 
     // Phase 3 extras (uniform)
     pub border_width: Option<f64>,
     pub border_color: Option<String>,
-    // Per-side borders for CSS parity; renderer may approximate until full support.
+    // Per-side borders for CSS parity.
     pub border_top_width: Option<f64>,
     pub border_right_width: Option<f64>,
     pub border_bottom_width: Option<f64>,
@@ -143,6 +155,8 @@ impl Edge2 {
 pub struct LinearGradient2 {
     pub angle: f64,
     pub stops: Vec<ColorStop>,
+    /// `repeating-linear-gradient()`: the stops repeat over their span.
+    pub repeating: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -175,6 +189,21 @@ pub struct RadialGradient2 {
     pub ry: f64,
     /// Color stops in order (0.0..1.0)
     pub stops: Vec<ColorStop>,
+    /// `repeating-radial-gradient()`: the stops repeat over their span.
+    pub repeating: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConicGradient2 {
+    /// Center (normalized 0.0..1.0 range of the box)
+    pub cx: f64,
+    pub cy: f64,
+    /// `from` angle in degrees, clockwise from the top as in CSS.
+    pub angle: f64,
+    /// Color stops in order, as fractions of a turn (0.0..1.0)
+    pub stops: Vec<ColorStop>,
+    /// `repeating-conic-gradient()`: the stops repeat over their span.
+    pub repeating: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -197,4 +226,5 @@ pub enum BackgroundLayer2 {
     Solid(String),
     Linear(LinearGradient2),
     Radial(RadialGradient2),
+    Conic(ConicGradient2),
 }
@@ -1,4 +1,6 @@
+use crate::logic::IrDiffOp;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 
 pub type NodeId = String;
@@ -27,6 +29,87 @@ impl DataDocument {
             .map(|node| (node.node_id.as_str(), node))
             .collect()
     }
+
+    /// First binding whose `target` is `node_id`.
+    pub fn binding_for(&self, node_id: &str) -> Option<&DataBinding> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.target == node_id)
+    }
+
+    /// Resolve a binding path to a value.
+    ///
+    /// Paths are a data node id followed by dot-separated object keys or
+    /// array indices, e.g. `PROFILE1.address.city` or `TBLDEMO1.rows.2.1`.
+    /// Segments index into a `value` node's payload, or into the fields of
+    /// other node kinds; a bare id resolves to the whole payload of a `value`
+    /// node and to the text, label or source of text, action and image nodes.
+    pub fn resolve_path(&self, path: &str) -> Option<Value> {
+        let (node_id, segments) = split_path(path)?;
+        let node = self.node(node_id)?;
+        if let DataNodeKind::Value(data) = &node.kind {
+            return lookup(&data.value, &segments).cloned();
+        }
+        let segments = node.kind.field_path(segments)?;
+        let fields = serde_json::to_value(&node.kind).ok()?;
+        lookup(&fields, &segments).cloned()
+    }
+
+    /// Write `value` at a binding path (see [`resolve_path`](Self::resolve_path)).
+    ///
+    /// Missing object keys inside a `value` node are created, and an index one
+    /// past the end of an array appends. Returns `false` if the path doesn't
+    /// resolve or `value` doesn't fit the field, e.g. a number for a text node's
+    /// `text`; the document is left unchanged in that case.
+    pub fn set_path(&mut self, path: &str, value: Value) -> bool {
+        let Some((node_id, segments)) = split_path(path) else {
+            return false;
+        };
+        let Some(node) = self.nodes.iter_mut().find(|node| node.node_id == node_id) else {
+            return false;
+        };
+        if let DataNodeKind::Value(data) = &mut node.kind {
+            return assign(&mut data.value, &segments, value, true);
+        }
+        let Some(segments) = node.kind.field_path(segments) else {
+            return false;
+        };
+        let Ok(mut fields) = serde_json::to_value(&node.kind) else {
+            return false;
+        };
+        if !assign(&mut fields, &segments, value, false) {
+            return false;
+        }
+        match serde_json::from_value(fields) {
+            Ok(kind) => {
+                node.kind = kind;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Apply a diff operation that targets the data layer. Returns `false` for
+    /// operations addressed to widgets, which the host applies, and for
+    /// targets that don't resolve.
+    pub fn apply_diff(&mut self, op: &IrDiffOp) -> bool {
+        match op {
+            IrDiffOp::ReplaceText { .. } => false,
+            IrDiffOp::ReplaceTextByNodeId { node_id, text } => {
+                let Some(node) = self.nodes.iter_mut().find(|node| &node.node_id == node_id) else {
+                    return false;
+                };
+                match &mut node.kind {
+                    DataNodeKind::Text(data) => data.text = text.clone(),
+                    DataNodeKind::Action(data) => data.label = text.clone(),
+                    DataNodeKind::Group { label, .. } => *label = Some(text.clone()),
+                    _ => return false,
+                }
+                true
+            }
+            IrDiffOp::SetValue { path, value } => self.set_path(path, value.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +138,30 @@ pub enum DataNodeKind {
     Image(ImageNodeData),
     /// Tabular data for simple tables. Values are plain strings for now.
     Table(TableNodeData),
+    /// Structured JSON value, e.g. form state shared by bound inputs.
+    Value(ValueNodeData),
+}
+
+impl DataNodeKind {
+    /// Serialized-field path for binding `segments` on a non-`value` node.
+    /// An empty path selects the node's primary field.
+    fn field_path<'a>(&self, segments: Vec<&'a str>) -> Option<Vec<&'a str>> {
+        if segments.first() == Some(&"kind") {
+            return None;
+        }
+        if !segments.is_empty() {
+            return Some(segments);
+        }
+        let primary = match self {
+            DataNodeKind::Text(_) => "text",
+            DataNodeKind::Action(_) => "label",
+            DataNodeKind::Image(_) => "source",
+            DataNodeKind::Group { .. } | DataNodeKind::Table(_) | DataNodeKind::Value(_) => {
+                return None;
+            }
+        };
+        Some(vec![primary])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,6 +209,13 @@ pub struct TableNodeData {
     pub rows: Vec<Vec<String>>,
 }
 
+/// Structured payload addressed by binding paths.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueNodeData {
+    #[serde(default)]
+    pub value: Value,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "role", rename_all = "snake_case")]
 pub enum TextSemanticRole {
@@ -110,22 +224,37 @@ pub enum TextSemanticRole {
     Label,
 }
 
+/// Binds the widgets that reference `target` to the value at `path`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataBinding {
     pub target: NodeId,
+    /// See [`DataDocument::resolve_path`].
     pub path: String,
     #[serde(default)]
     pub mode: BindingMode,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BindingMode {
+    /// Document changes update the widget.
     Pull,
+    /// Widget edits are written to the document.
     Push,
+    /// Both directions.
     Duplex,
 }
 
+impl BindingMode {
+    pub fn pulls(self) -> bool {
+        matches!(self, BindingMode::Pull | BindingMode::Duplex)
+    }
+
+    pub fn pushes(self) -> bool {
+        matches!(self, BindingMode::Push | BindingMode::Duplex)
+    }
+}
+
 impl Default for BindingMode {
     fn default() -> Self {
         BindingMode::Pull
@@ -154,3 +283,137 @@ impl Default for ChannelKind {
         ChannelKind::Broadcast
     }
 }
+
+fn split_path(path: &str) -> Option<(&str, Vec<&str>)> {
+    let mut parts = path.trim().split('.');
+    let node_id = parts.next().filter(|id| !id.is_empty())?;
+    let segments: Vec<&str> = parts.collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return None;
+    }
+    Some((node_id, segments))
+}
+
+fn lookup<'a>(value: &'a Value, segments: &[&str]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(value, |current, segment| match current {
+            Value::Object(map) => map.get(*segment),
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+fn assign(target: &mut Value, segments: &[&str], value: Value, create: bool) -> bool {
+    let Some((segment, rest)) = segments.split_first() else {
+        *target = value;
+        return true;
+    };
+    if create && target.is_null() {
+        *target = Value::Object(Default::default());
+    }
+    match target {
+        Value::Object(map) => match map.get_mut(*segment) {
+            Some(child) => assign(child, rest, value, create),
+            None if create => {
+                let mut child = Value::Null;
+                let assigned = assign(&mut child, rest, value, create);
+                if assigned {
+                    map.insert(segment.to_string(), child);
+                }
+                assigned
+            }
+            None => false,
+        },
+        Value::Array(items) => {
+            let Ok(index) = segment.parse::<usize>() else {
+                return false;
+            };
+            if index < items.len() {
+                assign(&mut items[index], rest, value, create)
+            } else if index == items.len() && rest.is_empty() {
+                items.push(value);
+                true
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document() -> DataDocument {
+        serde_json::from_value(json!({
+            "document_id": "form",
+            "nodes": [
+                { "node_id": "PROFILE1", "kind": "value", "value": {
+                    "name": "Ada", "age": 36, "tags": ["admin"]
+                } },
+                { "node_id": "GREETING", "kind": "text", "text": "Hello" },
+                { "node_id": "TBLDEMO1", "kind": "table", "columns": ["A"], "rows": [["x"]] }
+            ],
+            "bindings": [
+                { "target": "NAMEINPT", "path": "PROFILE1.name", "mode": "duplex" }
+            ]
+        }))
+        .expect("valid data document")
+    }
+
+    #[test]
+    fn resolves_paths_into_values_and_node_fields() {
+        let doc = document();
+        assert_eq!(doc.resolve_path("PROFILE1.name"), Some(json!("Ada")));
+        assert_eq!(doc.resolve_path("PROFILE1.tags.0"), Some(json!("admin")));
+        assert_eq!(doc.resolve_path("GREETING"), Some(json!("Hello")));
+        assert_eq!(doc.resolve_path("TBLDEMO1.rows.0.0"), Some(json!("x")));
+        assert_eq!(doc.resolve_path("PROFILE1.missing"), None);
+        assert_eq!(doc.resolve_path("GREETING.kind"), None);
+        assert_eq!(doc.resolve_path("PROFILE1..name"), None);
+        assert_eq!(
+            doc.binding_for("NAMEINPT").map(|b| b.mode),
+            Some(BindingMode::Duplex)
+        );
+    }
+
+    #[test]
+    fn sets_paths_and_rejects_mismatched_fields() {
+        let mut doc = document();
+        assert!(doc.set_path("PROFILE1.address.city", json!("London")));
+        assert!(doc.set_path("PROFILE1.tags.1", json!("editor")));
+        assert!(doc.set_path("GREETING", json!("Hi")));
+        assert_eq!(
+            doc.resolve_path("PROFILE1.address.city"),
+            Some(json!("London"))
+        );
+        assert_eq!(
+            doc.resolve_path("PROFILE1.tags"),
+            Some(json!(["admin", "editor"]))
+        );
+        assert_eq!(doc.resolve_path("GREETING.text"), Some(json!("Hi")));
+
+        assert!(!doc.set_path("GREETING", json!(3)));
+        assert!(!doc.set_path("GREETING.subtitle", json!("x")));
+        assert!(!doc.set_path("PROFILE1.tags.5", json!("x")));
+        assert_eq!(doc.resolve_path("GREETING"), Some(json!("Hi")));
+    }
+
+    #[test]
+    fn applies_data_diff_ops() {
+        let mut doc = document();
+        assert!(doc.apply_diff(&IrDiffOp::SetValue {
+            path: "PROFILE1.age".into(),
+            value: json!(37),
+        }));
+        assert!(doc.apply_diff(&IrDiffOp::ReplaceTextByNodeId {
+            node_id: "GREETING".into(),
+            text: "Welcome".into(),
+        }));
+        assert_eq!(doc.resolve_path("PROFILE1.age"), Some(json!(37)));
+        assert_eq!(doc.resolve_path("GREETING"), Some(json!("Welcome")));
+    }
+}
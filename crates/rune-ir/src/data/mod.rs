@@ -3,6 +3,6 @@
 pub mod document;
 
 pub use document::{
-    ActionNodeData, DataBinding, DataChannel, DataDocument, DataNode, DataNodeKind, ImageNodeData,
-    TextNodeData, TextSemanticRole,
+    ActionNodeData, BindingMode, DataBinding, DataChannel, DataDocument, DataNode, DataNodeKind,
+    ImageNodeData, TextNodeData, TextSemanticRole, ValueNodeData,
 };
@@ -401,19 +401,22 @@ impl TranslatorState {
         if !style.backgrounds.is_empty() {
             container.backgrounds = style.backgrounds.clone();
         }
-        container.background = if let Some((start, end, cx, cy, rx, ry)) = &style.background_radial
-        {
+        container.background = if let Some(bg) = layered_only_background(&style.backgrounds) {
+            Some(bg)
+        } else if let Some((start, end, cx, cy, rx, ry)) = &style.background_radial {
             Some(ViewBackground::RadialGradient {
                 cx: *cx,
                 cy: *cy,
                 rx: *rx,
                 ry: *ry,
                 stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+                repeating: false,
             })
         } else if let Some((start, end, angle)) = &style.background_gradient {
             Some(ViewBackground::LinearGradient {
                 angle: *angle,
                 stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+                repeating: false,
             })
         } else {
             style
@@ -487,6 +490,20 @@ impl TranslatorState {
                     }
                 }
             }
+            if style.font_feature_settings.is_none() {
+                if let Some(p) = parent_style {
+                    if let Some(pf) = &p.font_feature_settings {
+                        style.font_feature_settings = Some(pf.clone());
+                    }
+                }
+            }
+            if style.font_variation_settings.is_none() {
+                if let Some(p) = parent_style {
+                    if let Some(pv) = &p.font_variation_settings {
+                        style.font_variation_settings = Some(pv.clone());
+                    }
+                }
+            }
             if style.text_align.is_none() {
                 if let Some(p) = parent_style {
                     if let Some(pa) = p.text_align {
@@ -494,6 +511,34 @@ impl TranslatorState {
                     }
                 }
             }
+            if style.hyphens.is_none() {
+                if let Some(p) = parent_style {
+                    if let Some(ph) = p.hyphens {
+                        style.hyphens = Some(ph);
+                    }
+                }
+            }
+            if style.tab_size.is_none() {
+                if let Some(p) = parent_style {
+                    if let Some(pt) = p.tab_size {
+                        style.tab_size = Some(pt);
+                    }
+                }
+            }
+            if style.writing_mode.is_none() {
+                if let Some(p) = parent_style {
+                    if let Some(pw) = p.writing_mode {
+                        style.writing_mode = Some(pw);
+                    }
+                }
+            }
+            if style.text_orientation.is_none() {
+                if let Some(p) = parent_style {
+                    if let Some(po) = p.text_orientation {
+                        style.text_orientation = Some(po);
+                    }
+                }
+            }
             if matches!(style.display, Display::None) {
                 return Ok(None);
             }
@@ -515,6 +560,7 @@ impl TranslatorState {
                             background: None,
                             backgrounds: Vec::new(),
                             corner_radius: style.corner_radius,
+                            corner_radii: None,
                             width: style.width,
                             height: style.height,
                             min_width: style.min_width,
@@ -553,7 +599,12 @@ impl TranslatorState {
                             }
                             spec.margin_left_auto = v2.margin_left_auto;
                             spec.margin_right_auto = v2.margin_right_auto;
-                            spec.background = if let Some((start, end, cx, cy, rx, ry)) =
+                            spec.corner_radii = v2.corner_radii;
+                            spec.background = if let Some(bg) =
+                                layered_only_background(&style.backgrounds)
+                            {
+                                Some(bg)
+                            } else if let Some((start, end, cx, cy, rx, ry)) =
                                 &style.background_radial
                             {
                                 Some(ViewBackground::RadialGradient {
@@ -562,11 +613,13 @@ impl TranslatorState {
                                     rx: *rx,
                                     ry: *ry,
                                     stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+                                    repeating: false,
                                 })
                             } else if let Some((start, end, angle)) = &style.background_gradient {
                                 Some(ViewBackground::LinearGradient {
                                     angle: *angle,
                                     stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+                                    repeating: false,
                                 })
                             } else {
                                 style
@@ -696,31 +749,38 @@ impl TranslatorState {
                         if !style.backgrounds.is_empty() {
                             container.backgrounds = style.backgrounds.clone();
                         }
-                        container.background =
-                            if let Some((start, end, cx, cy, rx, ry)) = &style.background_radial {
-                                Some(ViewBackground::RadialGradient {
-                                    cx: *cx,
-                                    cy: *cy,
-                                    rx: *rx,
-                                    ry: *ry,
-                                    stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
-                                })
-                            } else if let Some((start, end, angle)) = &style.background_gradient {
-                                Some(ViewBackground::LinearGradient {
-                                    angle: *angle,
-                                    stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
-                                })
-                            } else {
-                                style
-                                    .background_color
-                                    .clone()
-                                    .map(|color| ViewBackground::Solid { color })
-                            };
+                        container.background = if let Some(bg) =
+                            layered_only_background(&style.backgrounds)
+                        {
+                            Some(bg)
+                        } else if let Some((start, end, cx, cy, rx, ry)) = &style.background_radial
+                        {
+                            Some(ViewBackground::RadialGradient {
+                                cx: *cx,
+                                cy: *cy,
+                                rx: *rx,
+                                ry: *ry,
+                                stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+                                repeating: false,
+                            })
+                        } else if let Some((start, end, angle)) = &style.background_gradient {
+                            Some(ViewBackground::LinearGradient {
+                                angle: *angle,
+                                stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+                                repeating: false,
+                            })
+                        } else {
+                            style
+                                .background_color
+                                .clone()
+                                .map(|color| ViewBackground::Solid { color })
+                        };
                         // Propagate rounded corners to the container visuals
                         container.corner_radius = style.corner_radius;
                         container.border_width = style.border_width;
                         container.border_color = style.border_color.clone();
                         if let Some(v2) = &v2_style {
+                            container.corner_radii = v2.corner_radii;
                             container.border_top_width = v2.border_top_width;
                             container.border_right_width = v2.border_right_width;
                             container.border_bottom_width = v2.border_bottom_width;
@@ -827,6 +887,34 @@ impl TranslatorState {
                             }
                         }
                     }
+                    if text_style.hyphens.is_none() {
+                        if let Some(parent) = parent_style {
+                            if let Some(h) = parent.hyphens {
+                                text_style.hyphens = Some(h);
+                            }
+                        }
+                    }
+                    if text_style.tab_size.is_none() {
+                        if let Some(parent) = parent_style {
+                            if let Some(t) = parent.tab_size {
+                                text_style.tab_size = Some(t);
+                            }
+                        }
+                    }
+                    if text_style.writing_mode.is_none() {
+                        if let Some(parent) = parent_style {
+                            if let Some(w) = parent.writing_mode {
+                                text_style.writing_mode = Some(w);
+                            }
+                        }
+                    }
+                    if text_style.text_orientation.is_none() {
+                        if let Some(parent) = parent_style {
+                            if let Some(o) = parent.text_orientation {
+                                text_style.text_orientation = Some(o);
+                            }
+                        }
+                    }
                     let view_id = self.id_generator.next_view_id();
                     let view_node = ViewNode {
                         id: view_id.clone(),
@@ -1168,6 +1256,12 @@ pub(crate) struct ComputedStyle {
     justify_content: Option<LayoutJustify>,
     align_items: Option<LayoutAlign>,
     text_align: Option<crate::view::TextAlign>,
+    hyphens: Option<crate::view::Hyphens>,
+    tab_size: Option<f64>,
+    text_overflow: Option<crate::view::TextOverflow>,
+    line_clamp: Option<u32>,
+    writing_mode: Option<crate::view::WritingMode>,
+    text_orientation: Option<crate::view::TextOrientation>,
     wrap: Option<bool>,
     gap: Option<f64>,
     background_color: Option<String>,
@@ -1179,6 +1273,8 @@ pub(crate) struct ComputedStyle {
     font_size: Option<f64>,
     line_height: Option<f64>,
     font_weight: Option<f64>,
+    font_feature_settings: Option<String>,
+    font_variation_settings: Option<String>,
     margin: EdgeValues,
     padding: EdgeValues,
     width: Option<f64>,
@@ -1211,21 +1307,40 @@ fn to_edge_insets(values: &EdgeValues) -> EdgeInsets {
     }
 }
 
+/// The topmost background layer when the legacy two-stop fields can't
+/// represent it (conic or repeating gradients).
+fn layered_only_background(backgrounds: &[ViewBackground]) -> Option<ViewBackground> {
+    match backgrounds.first()? {
+        bg @ (ViewBackground::ConicGradient { .. }
+        | ViewBackground::LinearGradient {
+            repeating: true, ..
+        }
+        | ViewBackground::RadialGradient {
+            repeating: true, ..
+        }) => Some(bg.clone()),
+        _ => None,
+    }
+}
+
 fn to_surface_style(style: &ComputedStyle) -> SurfaceStyle {
     // Prefer gradients (radial → linear) when available, else treat CSS variables/inherit/currentColor as no explicit color.
     // no debug prints
-    let background = if let Some((start, end, cx, cy, rx, ry)) = &style.background_radial {
+    let background = if let Some(bg) = layered_only_background(&style.backgrounds) {
+        Some(bg)
+    } else if let Some((start, end, cx, cy, rx, ry)) = &style.background_radial {
         Some(ViewBackground::RadialGradient {
             cx: *cx,
             cy: *cy,
             rx: *rx,
             ry: *ry,
             stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+            repeating: false,
         })
     } else if let Some((start, end, angle)) = &style.background_gradient {
         Some(ViewBackground::LinearGradient {
             angle: *angle,
             stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+            repeating: false,
         })
     } else {
         style.background_color.as_ref().and_then(|c| {
@@ -1265,6 +1380,8 @@ fn to_surface_style(style: &ComputedStyle) -> SurfaceStyle {
                 color: color.clone(),
             }
         }),
+        filters: Vec::new(),
+        backdrop_filters: Vec::new(),
     }
 }
 
@@ -1287,18 +1404,24 @@ fn to_text_style(style: &ComputedStyle) -> TextStyle {
         font_size: style.font_size,
         line_height: style.line_height,
         font_weight: style.font_weight,
-        background: if let Some((start, end, cx, cy, rx, ry)) = &style.background_radial {
+        font_feature_settings: style.font_feature_settings.clone(),
+        font_variation_settings: style.font_variation_settings.clone(),
+        background: if let Some(bg) = layered_only_background(&style.backgrounds) {
+            Some(bg)
+        } else if let Some((start, end, cx, cy, rx, ry)) = &style.background_radial {
             Some(ViewBackground::RadialGradient {
                 cx: *cx,
                 cy: *cy,
                 rx: *rx,
                 ry: *ry,
                 stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+                repeating: false,
             })
         } else if let Some((start, end, angle)) = &style.background_gradient {
             Some(ViewBackground::LinearGradient {
                 angle: *angle,
                 stops: vec![(start.clone(), 0.0), (end.clone(), 1.0)],
+                repeating: false,
             })
         } else {
             style.background_color.as_ref().and_then(|c| {
@@ -1320,6 +1443,13 @@ fn to_text_style(style: &ComputedStyle) -> TextStyle {
         margin: to_edge_insets(&style.margin),
         corner_radius: style.corner_radius,
         text_align: style.text_align,
+        hyphens: style.hyphens,
+        tab_stops: None,
+        tab_size: style.tab_size,
+        text_overflow: style.text_overflow,
+        line_clamp: style.line_clamp,
+        writing_mode: style.writing_mode,
+        text_orientation: style.text_orientation,
     }
 }
 
@@ -1630,6 +1760,63 @@ impl StyleBuilder {
             }
         }
 
+        if let Some(features) = get("font-feature-settings") {
+            style.font_feature_settings = Some(features.to_string());
+        }
+
+        if let Some(variations) = get("font-variation-settings") {
+            style.font_variation_settings = Some(variations.to_string());
+        }
+
+        if let Some(hyphens) = get("hyphens") {
+            style.hyphens = match hyphens.trim().to_ascii_lowercase().as_str() {
+                "none" => Some(crate::view::Hyphens::None),
+                "manual" => Some(crate::view::Hyphens::Manual),
+                "auto" => Some(crate::view::Hyphens::Auto),
+                _ => None,
+            };
+        }
+
+        if let Some(tab_size) = get("tab-size") {
+            style.tab_size = tab_size
+                .trim()
+                .strip_suffix("px")
+                .and_then(|px| px.trim().parse().ok());
+        }
+
+        if let Some(overflow) = get("text-overflow") {
+            style.text_overflow = match overflow.trim().to_ascii_lowercase().as_str() {
+                "clip" => Some(crate::view::TextOverflow::Clip),
+                "ellipsis" => Some(crate::view::TextOverflow::Ellipsis),
+                // Not CSS; lets file names keep their extension.
+                "ellipsis-start" => Some(crate::view::TextOverflow::EllipsisStart),
+                "ellipsis-middle" => Some(crate::view::TextOverflow::EllipsisMiddle),
+                _ => None,
+            };
+        }
+
+        if let Some(mode) = get("writing-mode") {
+            style.writing_mode = match mode.trim().to_ascii_lowercase().as_str() {
+                "horizontal-tb" => Some(crate::view::WritingMode::HorizontalTb),
+                "vertical-rl" => Some(crate::view::WritingMode::VerticalRl),
+                "vertical-lr" => Some(crate::view::WritingMode::VerticalLr),
+                _ => None,
+            };
+        }
+
+        if let Some(orientation) = get("text-orientation") {
+            style.text_orientation = match orientation.trim().to_ascii_lowercase().as_str() {
+                "mixed" => Some(crate::view::TextOrientation::Mixed),
+                "upright" => Some(crate::view::TextOrientation::Upright),
+                "sideways" => Some(crate::view::TextOrientation::Sideways),
+                _ => None,
+            };
+        }
+
+        if let Some(clamp) = get("line-clamp").or_else(|| get("-webkit-line-clamp")) {
+            style.line_clamp = clamp.trim().parse::<u32>().ok().filter(|&n| n > 0);
+        }
+
         if let Some(background) = get("background-color") {
             style.background_color = Some(background.to_string());
         } else if let Some(background) = get("background") {
@@ -1719,6 +1906,8 @@ impl StyleBuilder {
                 "font-size",
                 "line-height",
                 "font-weight",
+                "font-feature-settings",
+                "font-variation-settings",
                 "background-color",
                 "background",
                 "margin",
@@ -1824,8 +2013,27 @@ pub(crate) fn apply_cssv2_inline_to_style(
             crate::css::TextAlign2::Start => crate::view::TextAlign::Start,
             crate::css::TextAlign2::Center => crate::view::TextAlign::Center,
             crate::css::TextAlign2::End => crate::view::TextAlign::End,
+            crate::css::TextAlign2::Justify => crate::view::TextAlign::Justify,
         });
     }
+    if let Some(h) = v2.hyphens {
+        style.hyphens = Some(h);
+    }
+    if let Some(t) = v2.tab_size {
+        style.tab_size = Some(t);
+    }
+    if let Some(o) = v2.text_overflow {
+        style.text_overflow = Some(o);
+    }
+    if let Some(n) = v2.line_clamp {
+        style.line_clamp = Some(n);
+    }
+    if let Some(w) = v2.writing_mode {
+        style.writing_mode = Some(w);
+    }
+    if let Some(o) = v2.text_orientation {
+        style.text_orientation = Some(o);
+    }
     if let Some(w) = v2.wrap {
         style.wrap = Some(w);
     }
@@ -1838,6 +2046,12 @@ pub(crate) fn apply_cssv2_inline_to_style(
     if let Some(f) = v2.font_family.clone() {
         style.font_family = Some(f);
     }
+    if let Some(f) = v2.font_feature_settings.clone() {
+        style.font_feature_settings = Some(f);
+    }
+    if let Some(v) = v2.font_variation_settings.clone() {
+        style.font_variation_settings = Some(v);
+    }
     if let Some(bg) = v2.background_color.clone() {
         style.background_color = Some(bg);
     }
@@ -1895,6 +2109,7 @@ pub(crate) fn apply_cssv2_inline_to_style(
                     ViewBackground::LinearGradient {
                         angle: g.angle,
                         stops,
+                        repeating: g.repeating,
                     }
                 }
                 crate::css::BackgroundLayer2::Radial(g) => {
@@ -1909,6 +2124,21 @@ pub(crate) fn apply_cssv2_inline_to_style(
                         rx: g.rx,
                         ry: g.ry,
                         stops,
+                        repeating: g.repeating,
+                    }
+                }
+                crate::css::BackgroundLayer2::Conic(g) => {
+                    let stops: Vec<(String, f64)> = g
+                        .stops
+                        .iter()
+                        .map(|s| (s.color.clone(), s.offset))
+                        .collect();
+                    ViewBackground::ConicGradient {
+                        cx: g.cx,
+                        cy: g.cy,
+                        angle: g.angle,
+                        stops,
+                        repeating: g.repeating,
                     }
                 }
             })
@@ -2267,7 +2497,11 @@ fn resolve_vars_in_style(style: &mut ComputedStyle, vars: &HashMap<String, Strin
                         new_layers.push(layer.clone());
                     }
                 }
-                ViewBackground::LinearGradient { angle, stops } => {
+                ViewBackground::LinearGradient {
+                    angle,
+                    stops,
+                    repeating,
+                } => {
                     let mut any = false;
                     let mut new_stops: Vec<(String, f64)> = Vec::with_capacity(stops.len());
                     for (c, off) in stops {
@@ -2284,6 +2518,7 @@ fn resolve_vars_in_style(style: &mut ComputedStyle, vars: &HashMap<String, Strin
                     new_layers.push(ViewBackground::LinearGradient {
                         angle: *angle,
                         stops: new_stops,
+                        repeating: *repeating,
                     });
                 }
                 ViewBackground::RadialGradient {
@@ -2292,6 +2527,7 @@ fn resolve_vars_in_style(style: &mut ComputedStyle, vars: &HashMap<String, Strin
                     rx,
                     ry,
                     stops,
+                    repeating,
                 } => {
                     let mut any = false;
                     let mut new_stops: Vec<(String, f64)> = Vec::with_capacity(stops.len());
@@ -2312,6 +2548,35 @@ fn resolve_vars_in_style(style: &mut ComputedStyle, vars: &HashMap<String, Strin
                         rx: *rx,
                         ry: *ry,
                         stops: new_stops,
+                        repeating: *repeating,
+                    });
+                }
+                ViewBackground::ConicGradient {
+                    cx,
+                    cy,
+                    angle,
+                    stops,
+                    repeating,
+                } => {
+                    let mut any = false;
+                    let mut new_stops: Vec<(String, f64)> = Vec::with_capacity(stops.len());
+                    for (c, off) in stops {
+                        if let Some(res) = resolve_var_expression(c, vars) {
+                            new_stops.push((res, *off));
+                            any = true;
+                        } else {
+                            new_stops.push((c.clone(), *off));
+                        }
+                    }
+                    if any {
+                        changed_any = true;
+                    }
+                    new_layers.push(ViewBackground::ConicGradient {
+                        cx: *cx,
+                        cy: *cy,
+                        angle: *angle,
+                        stops: new_stops,
+                        repeating: *repeating,
                     });
                 }
             }
@@ -2566,6 +2831,7 @@ fn empty_container_spec() -> FlexContainerSpec {
         background: None,
         backgrounds: Vec::new(),
         corner_radius: None,
+        corner_radii: None,
         width: None,
         height: None,
         min_width: None,
@@ -2583,6 +2849,8 @@ fn empty_container_spec() -> FlexContainerSpec {
         border_bottom_color: None,
         border_left_color: None,
         box_shadow: None,
+        filters: Vec::new(),
+        backdrop_filters: Vec::new(),
         scroll: ScrollBehavior::default(),
         children: Vec::new(),
         transition: None,
@@ -15,6 +15,12 @@ pub enum IrDiffOp {
     /// Replace text content for a bound data node by its `node_id`.
     /// Only applies to text-bearing nodes (text and label variants).
     ReplaceTextByNodeId { node_id: String, text: String },
+    /// Set the value at a data binding path, e.g. `PROFILE1.address.city`.
+    /// See `DataDocument::resolve_path` for the path syntax.
+    SetValue {
+        path: String,
+        value: serde_json::Value,
+    },
 }
 
 #[cfg(test)]
@@ -42,4 +48,15 @@ mod tests {
         let back: IrDiffOp = serde_json::from_str(&json).expect("deserialize op");
         assert_eq!(op, back);
     }
+
+    #[test]
+    fn serde_round_trip_set_value() {
+        let op = IrDiffOp::SetValue {
+            path: "PROFILE1.tags.0".into(),
+            value: serde_json::json!({ "label": "admin", "active": true }),
+        };
+        let json = serde_json::to_string(&op).expect("serialize op");
+        let back: IrDiffOp = serde_json::from_str(&json).expect("deserialize op");
+        assert_eq!(op, back);
+    }
 }
@@ -413,6 +413,19 @@ fn normalize_identifiers(data: &mut DataDocument, view: &mut ViewDocument) -> Re
             ));
         };
         binding.target = new_id.clone();
+
+        // Paths start with a data node id; keep them pointing at the renamed node.
+        let (path_node, rest) = binding
+            .path
+            .split_once('.')
+            .unwrap_or((binding.path.as_str(), ""));
+        if let Some(new_path_node) = id_map.get(path_node) {
+            binding.path = if rest.is_empty() {
+                new_path_node.clone()
+            } else {
+                format!("{new_path_node}.{rest}")
+            };
+        }
     }
 
     for node in &mut view.nodes {
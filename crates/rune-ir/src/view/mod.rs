@@ -143,6 +143,10 @@ pub struct FlexContainerSpec {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub corner_radius: Option<f64>,
+    /// Per-corner elliptical radii; overrides `corner_radius` when set.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corner_radii: Option<CornerRadii>,
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub width: Option<f64>,
@@ -196,6 +200,15 @@ pub struct FlexContainerSpec {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub box_shadow: Option<BoxShadowSpec>,
+    /// Filters applied to the element and its children, in order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<ViewFilter>,
+    /// Filters applied to what is behind the element, inside its rounded
+    /// bounds (frosted glass).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub backdrop_filters: Vec<ViewFilter>,
     #[serde(default)]
     pub scroll: ScrollBehavior,
     #[serde(default)]
@@ -243,6 +256,10 @@ pub struct GridContainerSpec {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub corner_radius: Option<f64>,
+    /// Per-corner elliptical radii; overrides `corner_radius` when set.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corner_radii: Option<CornerRadii>,
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub width: Option<f64>,
@@ -446,6 +463,15 @@ pub struct OverlayContainerSpec {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<f64>,
+    /// Filters applied to the overlay's contents.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<ViewFilter>,
+    /// Filters applied to the page behind the overlay while it is shown,
+    /// e.g. a blur behind a modal.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub backdrop_filters: Vec<ViewFilter>,
     #[serde(default)]
     pub children: Vec<ViewNodeId>,
     /// Where to place the overlay relative to viewport. Defaults differ per type
@@ -556,6 +582,47 @@ impl Default for EdgeInsets {
     }
 }
 
+/// Corner radii as `[horizontal, vertical]` pairs, as in CSS
+/// `border-radius: 10px 20px / 5px`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CornerRadii {
+    #[serde(default)]
+    pub top_left: [f64; 2],
+    #[serde(default)]
+    pub top_right: [f64; 2],
+    #[serde(default)]
+    pub bottom_right: [f64; 2],
+    #[serde(default)]
+    pub bottom_left: [f64; 2],
+}
+
+impl CornerRadii {
+    pub fn uniform(r: f64) -> Self {
+        Self {
+            top_left: [r, r],
+            top_right: [r, r],
+            bottom_right: [r, r],
+            bottom_left: [r, r],
+        }
+    }
+
+    /// The corners in top-left, top-right, bottom-right, bottom-left order.
+    pub fn corners(&self) -> [[f64; 2]; 4] {
+        [
+            self.top_left,
+            self.top_right,
+            self.bottom_right,
+            self.bottom_left,
+        ]
+    }
+
+    /// Whether every corner is the same circular radius.
+    pub fn is_uniform(&self) -> bool {
+        let r = self.top_left[0];
+        self.corners().iter().all(|c| c[0] == r && c[1] == r)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ViewBackground {
@@ -567,6 +634,10 @@ pub enum ViewBackground {
         angle: f64,
         #[serde(default)]
         stops: Vec<(String, f64)>,
+        /// Repeat the stop span along the gradient line
+        /// (`repeating-linear-gradient`).
+        #[serde(default)]
+        repeating: bool,
     },
     RadialGradient {
         /// Center position as percentages of the painted box (0..1)
@@ -581,9 +652,30 @@ pub enum ViewBackground {
         ry: f64,
         #[serde(default)]
         stops: Vec<(String, f64)>,
+        #[serde(default)]
+        repeating: bool,
+    },
+    ConicGradient {
+        /// Center position as fractions of the painted box (0..1)
+        #[serde(default = "default_center")]
+        cx: f64,
+        #[serde(default = "default_center")]
+        cy: f64,
+        /// Start angle in degrees, clockwise from the top (CSS `from`)
+        #[serde(default)]
+        angle: f64,
+        /// Stop offsets are fractions of a full turn
+        #[serde(default)]
+        stops: Vec<(String, f64)>,
+        #[serde(default)]
+        repeating: bool,
     },
 }
 
+fn default_center() -> f64 {
+    0.5
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ScrollBehavior {
     #[serde(default)]
@@ -615,6 +707,16 @@ pub struct TextStyle {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub font_weight: Option<f64>,
+    /// OpenType features in CSS `font-feature-settings` syntax, e.g.
+    /// `"tnum", "liga" 0`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_feature_settings: Option<String>,
+    /// Variable-font axis values in CSS `font-variation-settings` syntax,
+    /// e.g. `"wght" 650, "opsz" 32`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_variation_settings: Option<String>,
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub background: Option<ViewBackground>,
@@ -628,6 +730,37 @@ pub struct TextStyle {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text_align: Option<TextAlign>,
+    /// Where wrapped words may be hyphenated, like CSS `hyphens`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hyphens: Option<Hyphens>,
+    /// Tab stop positions in pixels from the start of the line, ascending.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_stops: Option<Vec<f64>>,
+    /// Distance in pixels between the tab stops after `tab_stops`. Defaults
+    /// to eight spaces.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_size: Option<f64>,
+    /// How text that doesn't fit is cut off, like CSS `text-overflow`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_overflow: Option<TextOverflow>,
+    /// Most lines shown, like CSS `line-clamp`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_clamp: Option<u32>,
+    /// Whether lines run horizontally or vertically, like CSS
+    /// `writing-mode`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub writing_mode: Option<WritingMode>,
+    /// Which glyphs stand upright in vertical text, like CSS
+    /// `text-orientation`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_orientation: Option<TextOrientation>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -636,6 +769,68 @@ pub enum TextAlign {
     Start,
     Center,
     End,
+    /// Start-aligned, with every wrapped line but a paragraph's last
+    /// stretched to the full width.
+    Justify,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Hyphens {
+    /// Never hyphenate.
+    None,
+    /// Hyphenate only at soft hyphens (U+00AD).
+    #[default]
+    Manual,
+    /// Hyphenate at soft hyphens and wherever the renderer's hyphenation
+    /// dictionary allows. Renderers without a dictionary installed treat
+    /// this as `Manual`.
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextOverflow {
+    /// Cut the text off at the edge.
+    #[default]
+    Clip,
+    /// End the text shown with an ellipsis.
+    Ellipsis,
+    /// Keep the end of the text, with an ellipsis in front.
+    EllipsisStart,
+    /// Keep both ends of the text, with an ellipsis between them.
+    EllipsisMiddle,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WritingMode {
+    /// Lines run left to right and stack top to bottom.
+    #[default]
+    HorizontalTb,
+    /// Lines run top to bottom and stack right to left, as in Japanese.
+    VerticalRl,
+    /// Lines run top to bottom and stack left to right.
+    VerticalLr,
+}
+
+impl WritingMode {
+    /// Whether lines run top to bottom.
+    pub fn is_vertical(self) -> bool {
+        self != WritingMode::HorizontalTb
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextOrientation {
+    /// CJK stands upright; Latin and the like turn sideways.
+    #[default]
+    Mixed,
+    /// Every character stands upright.
+    Upright,
+    /// Every character turns sideways with the line.
+    Sideways,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -727,6 +922,15 @@ pub struct SurfaceStyle {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub box_shadow: Option<BoxShadowSpec>,
+    /// Filters applied to the element and its children, in order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<ViewFilter>,
+    /// Filters applied to what is behind the element, inside its rounded
+    /// bounds (frosted glass).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub backdrop_filters: Vec<ViewFilter>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -737,6 +941,38 @@ pub struct BoxShadowSpec {
     pub color: String,
 }
 
+/// CSS-style filter function. Lengths are in logical pixels; `amount` is a
+/// fraction, so `1.0` leaves the content unchanged for `saturate`,
+/// `brightness` and `contrast` and fully desaturates for `grayscale`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ViewFilter {
+    Blur {
+        radius: f64,
+    },
+    DropShadow {
+        #[serde(default)]
+        offset_x: f64,
+        #[serde(default)]
+        offset_y: f64,
+        #[serde(default)]
+        blur: f64,
+        color: String,
+    },
+    Grayscale {
+        amount: f64,
+    },
+    Saturate {
+        amount: f64,
+    },
+    Brightness {
+        amount: f64,
+    },
+    Contrast {
+        amount: f64,
+    },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageSpec {
     #[serde(default)]
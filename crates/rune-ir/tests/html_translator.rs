@@ -5,7 +5,9 @@ use rune_ir::{
     data::document::DataNodeKind,
     html::{HtmlOptions, package_from_html},
     logic::LogicEngine,
-    view::ViewNodeKind,
+    view::{
+        Hyphens, TextAlign, TextOrientation, TextOverflow, TextStyle, ViewNodeKind, WritingMode,
+    },
 };
 use url::Url;
 
@@ -297,3 +299,69 @@ fn translates_form_controls_with_widget_ids() -> Result<()> {
 
     Ok(())
 }
+
+/// Translates `<p style="{css}">` and returns the style of its text node.
+fn paragraph_text_style(css: &str) -> Result<TextStyle> {
+    let options = HtmlOptions {
+        document_id: Some("styled-text".to_string()),
+        ..HtmlOptions::default()
+    };
+
+    let package = package_from_html(
+        &format!(r#"<html><body><p style="{css}">Styled paragraph</p></body></html>"#),
+        options,
+    )?;
+
+    let (_, view) = package.entrypoint_documents()?;
+    view.nodes
+        .iter()
+        .find_map(|node| match &node.kind {
+            ViewNodeKind::Text(spec) => Some(spec.style.clone()),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow::anyhow!("expected a text node"))
+}
+
+#[test]
+fn translates_font_feature_and_variation_settings() -> Result<()> {
+    let style = paragraph_text_style(
+        "font-feature-settings: 'tnum', 'liga' 0; font-variation-settings: 'wght' 650",
+    )?;
+
+    assert_eq!(
+        style.font_feature_settings.as_deref(),
+        Some("'tnum', 'liga' 0")
+    );
+    assert_eq!(style.font_variation_settings.as_deref(), Some("'wght' 650"));
+    Ok(())
+}
+
+#[test]
+fn translates_justify_hyphens_and_tab_size() -> Result<()> {
+    let style = paragraph_text_style("text-align: justify; hyphens: auto; tab-size: 32px")?;
+
+    assert_eq!(style.text_align, Some(TextAlign::Justify));
+    assert_eq!(style.hyphens, Some(Hyphens::Auto));
+    assert_eq!(style.tab_size, Some(32.0));
+    Ok(())
+}
+
+#[test]
+fn translates_text_overflow_and_line_clamp() -> Result<()> {
+    let style = paragraph_text_style("text-overflow: ellipsis; line-clamp: 3")?;
+    assert_eq!(style.text_overflow, Some(TextOverflow::Ellipsis));
+    assert_eq!(style.line_clamp, Some(3));
+
+    let style = paragraph_text_style("-webkit-line-clamp: 2")?;
+    assert_eq!(style.line_clamp, Some(2));
+    Ok(())
+}
+
+#[test]
+fn translates_writing_mode_and_text_orientation() -> Result<()> {
+    let style = paragraph_text_style("writing-mode: vertical-lr; text-orientation: sideways")?;
+
+    assert_eq!(style.writing_mode, Some(WritingMode::VerticalLr));
+    assert_eq!(style.text_orientation, Some(TextOrientation::Sideways));
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use anyhow::Result;
 use rune_ir::package::RunePackage;
+use rune_ir::view::{
+    Hyphens, TextAlign, TextOrientation, TextOverflow, TextStyle, ViewNodeKind, WritingMode,
+};
 use std::path::PathBuf;
 
 #[test]
@@ -73,3 +76,135 @@ fn loads_sample_form_package() -> Result<()> {
 
     Ok(())
 }
+
+/// Writes a one-paragraph package whose text node carries `style`, then
+/// loads it back from disk.
+fn load_text_style_package(style: serde_json::Value) -> Result<RunePackage> {
+    let temp = tempfile::tempdir()?;
+    let dir = temp.path();
+    std::fs::create_dir_all(dir.join("views/data"))?;
+    std::fs::create_dir_all(dir.join("views/layout"))?;
+
+    let manifest = serde_json::json!({
+        "schema_version": "1.0.0",
+        "entrypoint": {
+            "id": "styled_text",
+            "data": "views/data/styled.json",
+            "view": "views/layout/styled.vizr"
+        },
+        "locales": {},
+        "capabilities": [],
+        "integrity": null
+    });
+    let data = serde_json::json!({
+        "document_id": "styled-text-data",
+        "nodes": [
+            { "node_id": "PARA0001", "kind": "text", "text": "Styled paragraph" }
+        ]
+    });
+    let view = serde_json::json!({
+        "view_id": "styled_text",
+        "root": "root",
+        "nodes": [
+            { "id": "root", "type": "flex_container", "children": ["para"] },
+            { "id": "para", "node_id": "PARA0001", "type": "text", "style": style }
+        ]
+    });
+    std::fs::write(
+        dir.join("RUNE.MANIFEST.json"),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    std::fs::write(dir.join("RUNE.TOC.json"), br#"{ "entries": {} }"#)?;
+    std::fs::write(
+        dir.join("views/data/styled.json"),
+        serde_json::to_vec_pretty(&data)?,
+    )?;
+    std::fs::write(
+        dir.join("views/layout/styled.vizr"),
+        serde_json::to_vec_pretty(&view)?,
+    )?;
+
+    RunePackage::from_directory(dir)
+}
+
+fn text_style_of(package: &RunePackage) -> Result<TextStyle> {
+    let (_, view) = package.entrypoint_documents()?;
+    view.nodes
+        .iter()
+        .find_map(|node| match &node.kind {
+            ViewNodeKind::Text(spec) => Some(spec.style.clone()),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow::anyhow!("styled view has no text node"))
+}
+
+#[test]
+fn loads_font_feature_and_variation_settings() -> Result<()> {
+    let package = load_text_style_package(serde_json::json!({
+        "font_feature_settings": "\"tnum\", \"liga\" 0",
+        "font_variation_settings": "\"wght\" 650"
+    }))?;
+    let style = text_style_of(&package)?;
+
+    assert_eq!(
+        style.font_feature_settings.as_deref(),
+        Some("\"tnum\", \"liga\" 0")
+    );
+    assert_eq!(
+        style.font_variation_settings.as_deref(),
+        Some("\"wght\" 650")
+    );
+    Ok(())
+}
+
+#[test]
+fn loads_justified_hyphenated_text_with_tab_stops() -> Result<()> {
+    let package = load_text_style_package(serde_json::json!({
+        "text_align": "justify",
+        "hyphens": "auto",
+        "tab_stops": [40.0, 120.0],
+        "tab_size": 48.0
+    }))?;
+    let style = text_style_of(&package)?;
+
+    assert_eq!(style.text_align, Some(TextAlign::Justify));
+    assert_eq!(style.hyphens, Some(Hyphens::Auto));
+    assert_eq!(style.tab_stops, Some(vec![40.0, 120.0]));
+    assert_eq!(style.tab_size, Some(48.0));
+    Ok(())
+}
+
+#[test]
+fn loads_text_overflow_and_line_clamp() -> Result<()> {
+    let package = load_text_style_package(serde_json::json!({
+        "text_overflow": "ellipsis_middle",
+        "line_clamp": 2
+    }))?;
+    let style = text_style_of(&package)?;
+
+    assert_eq!(style.text_overflow, Some(TextOverflow::EllipsisMiddle));
+    assert_eq!(style.line_clamp, Some(2));
+    Ok(())
+}
+
+#[test]
+fn rejects_zero_line_clamp() {
+    let result = load_text_style_package(serde_json::json!({ "line_clamp": 0 }));
+    assert!(
+        result.is_err(),
+        "line_clamp 0 should fail schema validation"
+    );
+}
+
+#[test]
+fn loads_writing_mode_and_text_orientation() -> Result<()> {
+    let package = load_text_style_package(serde_json::json!({
+        "writing_mode": "vertical_rl",
+        "text_orientation": "upright"
+    }))?;
+    let style = text_style_of(&package)?;
+
+    assert_eq!(style.writing_mode, Some(WritingMode::VerticalRl));
+    assert_eq!(style.text_orientation, Some(TextOrientation::Upright));
+    Ok(())
+}
@@ -306,12 +306,7 @@ impl Alert {
 
         let rrect = RoundedRect {
             rect: panel,
-            radii: RoundedRadii {
-                tl: self.panel_radius,
-                tr: self.panel_radius,
-                br: self.panel_radius,
-                bl: self.panel_radius,
-            },
+            radii: RoundedRadii::uniform(self.panel_radius),
         };
 
         // Panel background and border (combined for consistent rounding)
@@ -351,12 +346,7 @@ impl Alert {
         if let (Some(label), Some(rect)) = (&self.action_label, self.action_rect_for_panel(panel)) {
             let rrect = RoundedRect {
                 rect,
-                radii: RoundedRadii {
-                    tl: rect.h * 0.5,
-                    tr: rect.h * 0.5,
-                    br: rect.h * 0.5,
-                    bl: rect.h * 0.5,
-                },
+                radii: RoundedRadii::uniform(rect.h * 0.5),
             };
 
             canvas.rounded_rect(rrect, Brush::Solid(self.action_bg), z + 3);
@@ -17,12 +17,7 @@ impl Button {
     pub fn render(&self, canvas: &mut Canvas, z: i32) {
         let rrect = RoundedRect {
             rect: self.rect,
-            radii: RoundedRadii {
-                tl: self.radius,
-                tr: self.radius,
-                br: self.radius,
-                bl: self.radius,
-            },
+            radii: RoundedRadii::uniform(self.radius),
         };
         // Draw rounded background
         canvas.rounded_rect(rrect, Brush::Solid(self.bg), z);
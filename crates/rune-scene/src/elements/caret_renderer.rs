@@ -1,5 +1,5 @@
 use crate::elements::caret::CaretBlink;
-use engine_core::{ColorLinPremul, FillRule, Path, PathCmd, Rect};
+use engine_core::{Brush, ColorLinPremul, FillRule, Path, PathCmd, Rect};
 use rune_surface::Canvas;
 use rune_text::layout::{CursorPosition, TextLayout};
 
@@ -26,10 +26,14 @@ pub struct CaretRenderConfig {
 }
 
 /// Render the text caret at the cursor position.
+///
+/// Where bidi text separates the two sides of the position, the caret is
+/// split: the half matching the position's affinity on top, the other half
+/// below it.
 pub fn render_caret(
     canvas: &mut Canvas,
     layout: &TextLayout,
-    cursor_position: usize,
+    cursor_position: CursorPosition,
     caret_blink: &CaretBlink,
     config: &CaretRenderConfig,
 ) {
@@ -37,22 +41,23 @@ pub fn render_caret(
         return;
     }
 
-    let cursor_pos = CursorPosition::new(cursor_position.min(layout.text().len()));
-    let cursor_rect = match layout.cursor_rect_at_position(cursor_pos) {
-        Some(rect) => rect,
-        None => return,
-    };
+    let cursor_pos = CursorPosition::with_affinity(
+        cursor_position.byte_offset.min(layout.text().len()),
+        cursor_position.affinity,
+    );
 
-    // Transform to screen coordinates
-    let cx = config.content_rect.x + config.align_x - config.scroll_x + cursor_rect.x;
-    let cy0 = config.text_baseline_y - config.baseline_offset + cursor_rect.y - config.scroll_y;
-    let cy1 = cy0 + cursor_rect.height;
+    for cursor_rect in layout.cursor_rects_at_position(cursor_pos) {
+        // Transform to screen coordinates
+        let cx = config.content_rect.x + config.align_x - config.scroll_x + cursor_rect.x;
+        let cy0 = config.text_baseline_y - config.baseline_offset + cursor_rect.y - config.scroll_y;
+        let cy1 = cy0 + cursor_rect.height;
 
-    let mut caret = Path {
-        cmds: Vec::new(),
-        fill_rule: FillRule::NonZero,
-    };
-    caret.cmds.push(PathCmd::MoveTo([cx, cy0]));
-    caret.cmds.push(PathCmd::LineTo([cx, cy1]));
-    canvas.stroke_path(caret, config.width, config.color, config.z);
+        let mut caret = Path {
+            cmds: Vec::new(),
+            fill_rule: FillRule::NonZero,
+        };
+        caret.cmds.push(PathCmd::MoveTo([cx, cy0]));
+        caret.cmds.push(PathCmd::LineTo([cx, cy1]));
+        canvas.stroke_path(caret, config.width, Brush::Solid(config.color), config.z);
+    }
 }
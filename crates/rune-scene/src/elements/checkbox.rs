@@ -32,12 +32,7 @@ impl Checkbox {
         if self.border_width > 0.0 {
             let border_rr = RoundedRect {
                 rect: base_rect,
-                radii: RoundedRadii {
-                    tl: 2.0,
-                    tr: 2.0,
-                    br: 2.0,
-                    bl: 2.0,
-                },
+                radii: RoundedRadii::uniform(2.0),
             };
             canvas.stroke_rounded_rect(
                 border_rr,
@@ -51,12 +46,7 @@ impl Checkbox {
             // Rounded focus outline to match demo-app ui.rs
             let focus_rr = RoundedRect {
                 rect: base_rect,
-                radii: RoundedRadii {
-                    tl: 2.0,
-                    tr: 2.0,
-                    br: 2.0,
-                    bl: 2.0,
-                },
+                radii: RoundedRadii::uniform(2.0),
             };
             let focus = Brush::Solid(Color::rgba(63, 130, 246, 255));
             canvas.stroke_rounded_rect(focus_rr, 2.0, focus, z + 2);
@@ -79,12 +69,7 @@ impl Checkbox {
             };
             let inner_rr = RoundedRect {
                 rect: inner_snapped,
-                radii: RoundedRadii {
-                    tl: 1.5,
-                    tr: 1.5,
-                    br: 1.5,
-                    bl: 1.5,
-                },
+                radii: RoundedRadii::uniform(1.5),
             };
             canvas.rounded_rect(inner_rr, Brush::Solid(self.check_color), z + 2);
 
@@ -161,12 +161,7 @@ impl ConfirmDialog {
 
         let panel_rrect = RoundedRect {
             rect: panel,
-            radii: RoundedRadii {
-                tl: self.panel_radius,
-                tr: self.panel_radius,
-                br: self.panel_radius,
-                bl: self.panel_radius,
-            },
+            radii: RoundedRadii::uniform(self.panel_radius),
         };
 
         // Panel background and border (combined for consistent rounding)
@@ -311,12 +306,7 @@ impl ConfirmDialog {
         let scrim_color = self.scrim_color();
         let panel_rrect = RoundedRect {
             rect: panel_rect,
-            radii: RoundedRadii {
-                tl: self.panel_radius,
-                tr: self.panel_radius,
-                br: self.panel_radius,
-                bl: self.panel_radius,
-            },
+            radii: RoundedRadii::uniform(self.panel_radius),
         };
         canvas.fill_scrim_with_cutout(panel_rrect, scrim_color);
 
@@ -406,12 +396,7 @@ impl ConfirmDialog {
     ) {
         let rrect = RoundedRect {
             rect,
-            radii: RoundedRadii {
-                tl: 6.0,
-                tr: 6.0,
-                br: 6.0,
-                bl: 6.0,
-            },
+            radii: RoundedRadii::uniform(6.0),
         };
         canvas.rounded_rect(rrect, Brush::Solid(bg), z);
 
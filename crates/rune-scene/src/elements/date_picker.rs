@@ -208,12 +208,7 @@ impl DatePicker {
     pub fn render(&self, canvas: &mut Canvas, z: i32) {
         let rrect = RoundedRect {
             rect: self.rect,
-            radii: RoundedRadii {
-                tl: self.radius,
-                tr: self.radius,
-                br: self.radius,
-                bl: self.radius,
-            },
+            radii: RoundedRadii::uniform(self.radius),
         };
 
         // Background from styling
@@ -304,12 +299,7 @@ impl DatePicker {
         let radius = 8.0;
         let popup_rrect = RoundedRect {
             rect: popup_rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: radius,
-                bl: radius,
-            },
+            radii: RoundedRadii::uniform(radius),
         };
 
         // Popup background - white for light theme
@@ -337,12 +327,7 @@ impl DatePicker {
         };
         let header_rrect = RoundedRect {
             rect: header_rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: 0.0,
-                bl: 0.0,
-            },
+            radii: RoundedRadii::new(radius, radius, 0.0, 0.0),
         };
         canvas.rounded_rect(header_rrect, Brush::Solid(header_bg), z + 2);
 
@@ -454,12 +439,7 @@ impl DatePicker {
                 if is_selected {
                     let cell_rrect = RoundedRect {
                         rect: cell_rect,
-                        radii: RoundedRadii {
-                            tl: 4.0,
-                            tr: 4.0,
-                            br: 4.0,
-                            bl: 4.0,
-                        },
+                        radii: RoundedRadii::uniform(4.0),
                     };
                     let selected_bg = Color::rgba(63, 130, 246, 255);
                     canvas.rounded_rect(cell_rrect, Brush::Solid(selected_bg), z + 4);
@@ -497,12 +477,7 @@ impl DatePicker {
         };
         let today_rrect = RoundedRect {
             rect: today_button_rect,
-            radii: RoundedRadii {
-                tl: 6.0,
-                tr: 6.0,
-                br: 6.0,
-                bl: 6.0,
-            },
+            radii: RoundedRadii::uniform(6.0),
         };
         let today_bg = Color::rgba(63, 130, 246, 255);
         canvas.rounded_rect(today_rrect, Brush::Solid(today_bg), z + 6);
@@ -530,12 +505,7 @@ impl DatePicker {
         };
         let clear_rrect = RoundedRect {
             rect: clear_button_rect,
-            radii: RoundedRadii {
-                tl: 6.0,
-                tr: 6.0,
-                br: 6.0,
-                bl: 6.0,
-            },
+            radii: RoundedRadii::uniform(6.0),
         };
         let clear_bg = Color::rgba(99, 104, 118, 255);
         canvas.rounded_rect(clear_rrect, Brush::Solid(clear_bg), z + 6);
@@ -574,12 +544,7 @@ impl DatePicker {
         let radius = 8.0;
         let popup_rrect = RoundedRect {
             rect: popup_rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: radius,
-                bl: radius,
-            },
+            radii: RoundedRadii::uniform(radius),
         };
 
         // Popup background - white for light theme
@@ -607,12 +572,7 @@ impl DatePicker {
         };
         let header_rrect = RoundedRect {
             rect: header_rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: 0.0,
-                bl: 0.0,
-            },
+            radii: RoundedRadii::new(radius, radius, 0.0, 0.0),
         };
         canvas.rounded_rect(header_rrect, Brush::Solid(header_bg), z + 2);
 
@@ -685,12 +645,7 @@ impl DatePicker {
             if is_selected {
                 let cell_rrect = RoundedRect {
                     rect: cell_rect,
-                    radii: RoundedRadii {
-                        tl: 4.0,
-                        tr: 4.0,
-                        br: 4.0,
-                        bl: 4.0,
-                    },
+                    radii: RoundedRadii::uniform(4.0),
                 };
                 let selected_bg = Color::rgba(63, 130, 246, 255);
                 canvas.rounded_rect(cell_rrect, Brush::Solid(selected_bg), z + 4);
@@ -736,12 +691,7 @@ impl DatePicker {
         let radius = 8.0;
         let popup_rrect = RoundedRect {
             rect: popup_rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: radius,
-                bl: radius,
-            },
+            radii: RoundedRadii::uniform(radius),
         };
 
         // Popup background - white for light theme
@@ -769,12 +719,7 @@ impl DatePicker {
         };
         let header_rrect = RoundedRect {
             rect: header_rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: 0.0,
-                bl: 0.0,
-            },
+            radii: RoundedRadii::new(radius, radius, 0.0, 0.0),
         };
         canvas.rounded_rect(header_rrect, Brush::Solid(header_bg), z + 2);
 
@@ -846,12 +791,7 @@ impl DatePicker {
                 if is_selected {
                     let cell_rrect = RoundedRect {
                         rect: cell_rect,
-                        radii: RoundedRadii {
-                            tl: 4.0,
-                            tr: 4.0,
-                            br: 4.0,
-                            bl: 4.0,
-                        },
+                        radii: RoundedRadii::uniform(4.0),
                     };
                     let selected_bg = Color::rgba(63, 130, 246, 255);
                     canvas.rounded_rect(cell_rrect, Brush::Solid(selected_bg), z + 4);
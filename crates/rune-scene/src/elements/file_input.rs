@@ -1,4 +1,7 @@
-use engine_core::{Brush, Color, ColorLinPremul, Rect, RoundedRadii, RoundedRect};
+use engine_core::{
+    Brush, Color, ColorLinPremul, EllipsisPosition, Rect, RoundedRadii, RoundedRect,
+    truncate_text_fast,
+};
 use rune_surface::Canvas;
 use std::path::PathBuf;
 
@@ -155,12 +158,7 @@ impl FileInput {
         // Render container background with full rounded corners
         let container_rrect = RoundedRect {
             rect: container_rect,
-            radii: RoundedRadii {
-                tl: self.radius,
-                tr: self.radius,
-                br: self.radius,
-                bl: self.radius,
-            },
+            radii: RoundedRadii::uniform(self.radius),
         };
 
         canvas.rounded_rect(container_rrect, Brush::Solid(self.bg_color), z + 1);
@@ -223,30 +221,14 @@ impl FileInput {
                     .to_string()
             };
 
-            // Truncate if too long. Compute an approximate character budget based on
-            // available width, then trim on Unicode scalar boundaries to avoid
-            // slicing in the middle of a UTF-8 codepoint (which would panic).
+            // Truncate if too long, keeping the extension visible.
             let available_width = container_rect.x + container_rect.w - file_name_x - 12.0;
-            let approx_max = if available_width > 0.0 {
-                (available_width / 7.0) as usize
-            } else {
-                0
-            };
-
-            let display_text = if approx_max > 3 && file_name.chars().count() > approx_max {
-                let keep = approx_max.saturating_sub(3);
-                let mut truncated = String::new();
-                for (i, ch) in file_name.chars().enumerate() {
-                    if i >= keep {
-                        break;
-                    }
-                    truncated.push(ch);
-                }
-                truncated.push_str("...");
-                truncated
-            } else {
-                file_name
-            };
+            let display_text = truncate_text_fast(
+                &file_name,
+                available_width.max(0.0),
+                14.0,
+                EllipsisPosition::Middle,
+            );
 
             canvas.draw_text_run(
                 [file_name_x, file_text_y],
@@ -268,12 +250,7 @@ impl FileInput {
 
             let focus_rrect = RoundedRect {
                 rect: focus_rect,
-                radii: RoundedRadii {
-                    tl: self.radius + 2.0,
-                    tr: self.radius + 2.0,
-                    br: self.radius + 2.0,
-                    bl: self.radius + 2.0,
-                },
+                radii: RoundedRadii::uniform(self.radius + 2.0),
             };
 
             rune_surface::shapes::draw_rounded_rectangle(
@@ -9,8 +9,8 @@ use rune_surface::Canvas;
 use rune_surface::shapes;
 use rune_text::font::load_system_default_font;
 use rune_text::layout::{
-    CursorPosition, HitTestPolicy, Point as RtPoint, Selection as RtSelection,
-    TextLayout as RtTextLayout, WrapMode as RtWrapMode,
+    CursorAffinity as RtCursorAffinity, CursorPosition, HitTestPolicy, Point as RtPoint,
+    Selection as RtSelection, TextLayout as RtTextLayout, WrapMode as RtWrapMode,
 };
 
 /// Single-line text input widget with rich editing capabilities.
@@ -57,6 +57,8 @@ pub struct InputBox {
     // Shared caret blink state (visibility + blink phase)
     caret: CaretBlink,
     pub cursor_position: usize, // Byte offset in text
+    // Which side of a bidi run boundary the caret is drawn on.
+    cursor_affinity: RtCursorAffinity,
 
     // Horizontal scrolling
     scroll_x: f32,
@@ -108,6 +110,7 @@ impl InputBox {
             border_width: 1.0,
             caret: CaretBlink::new(focused),
             cursor_position: initial_cursor,
+            cursor_affinity: RtCursorAffinity::Downstream,
             scroll_x: 0.0,
             padding_x: 12.0,
             padding_y: 10.0,
@@ -121,7 +124,7 @@ impl InputBox {
     /// Clamp selection + cursor so they stay within the current layout/text.
     fn clamp_selection_to_layout(&mut self) {
         let max = if let Some(layout) = self.rt_layout.as_ref() {
-            layout.text().len()
+            layout.text_len()
         } else {
             self.text.len()
         };
@@ -165,7 +168,7 @@ impl InputBox {
             };
 
             let (new_cursor, new_selection) = f(layout, &font, &normalized_selection, size);
-            let new_text = layout.text().to_string();
+            let new_text = layout.rope().to_string();
             (new_cursor, new_selection, new_text)
         };
 
@@ -182,7 +185,7 @@ impl InputBox {
     /// Compute cursor X (logical pixels) for current cursor position.
     fn cursor_x(&self) -> f32 {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let safe_cursor = self.cursor_position.min(layout.text().len());
+            let safe_cursor = self.cursor_position.min(layout.text_len());
             let cursor_pos = CursorPosition::new(safe_cursor);
             if let Some(cursor_rect) = layout.cursor_rect_at_position(cursor_pos) {
                 return cursor_rect.x;
@@ -206,7 +209,7 @@ impl InputBox {
         if !self.text.is_empty() && text == self.text {
             if let Some(layout) = self.rt_layout.as_ref() {
                 let layout_width = layout
-                    .measure_range_width(0, layout.text().len())
+                    .measure_range_width(0, layout.text_len())
                     .or_else(|| layout.lines().iter().map(|l| l.width).reduce(f32::max))
                     .unwrap_or(0.0);
                 if layout_width > 0.0 {
@@ -233,7 +236,8 @@ impl InputBox {
         match self.text_align {
             TextAlign::Center => (content_width - text_width).max(0.0) * 0.5,
             TextAlign::End => (content_width - text_width).max(0.0),
-            TextAlign::Start => 0.0,
+            // A single line is never stretched.
+            TextAlign::Start | TextAlign::Justify => 0.0,
         }
     }
 
@@ -243,7 +247,7 @@ impl InputBox {
             let text = ch.to_string();
             let new_cursor = if selection.is_collapsed() {
                 layout.insert_char(
-                    selection.active().min(layout.text().len()),
+                    selection.active().min(layout.text_len()),
                     ch,
                     font,
                     size,
@@ -332,7 +336,7 @@ impl InputBox {
         });
     }
 
-    /// Move cursor left by one grapheme.
+    /// Move cursor left by one grapheme on screen (visual order in bidi text).
     /// If there's a selection, collapses it to the start instead of moving.
     pub fn move_cursor_left(&mut self) {
         // If there's a selection, collapse it to the start (anchor or active, whichever is smaller)
@@ -344,25 +348,21 @@ impl InputBox {
             return;
         }
 
-        if self.cursor_position == 0 {
-            return;
-        }
-
         let new_cursor = {
             let layout = match self.rt_layout.as_ref() {
                 Some(layout) => layout,
                 None => return,
             };
-            let mut pos = self.cursor_position.min(layout.text().len());
-            pos = layout.move_cursor_left(pos);
-            pos.min(layout.text().len())
+            let pos = self.cursor_position.min(layout.text_len());
+            layout.move_cursor_visual_left(CursorPosition::with_affinity(pos, self.cursor_affinity))
         };
-        self.cursor_position = new_cursor;
+        self.cursor_position = new_cursor.byte_offset;
+        self.cursor_affinity = new_cursor.affinity;
         self.rt_selection = RtSelection::collapsed(self.cursor_position);
         self.reset_cursor_blink();
     }
 
-    /// Move cursor right by one grapheme.
+    /// Move cursor right by one grapheme on screen (visual order in bidi text).
     /// If there's a selection, collapses it to the end instead of moving.
     pub fn move_cursor_right(&mut self) {
         // If there's a selection, collapse it to the end (anchor or active, whichever is larger)
@@ -374,20 +374,17 @@ impl InputBox {
             return;
         }
 
-        if self.cursor_position >= self.text.len() {
-            return;
-        }
-
         let new_cursor = {
             let layout = match self.rt_layout.as_ref() {
                 Some(layout) => layout,
                 None => return,
             };
-            let mut pos = self.cursor_position.min(layout.text().len());
-            pos = layout.move_cursor_right(pos);
-            pos.min(layout.text().len())
+            let pos = self.cursor_position.min(layout.text_len());
+            layout
+                .move_cursor_visual_right(CursorPosition::with_affinity(pos, self.cursor_affinity))
         };
-        self.cursor_position = new_cursor;
+        self.cursor_position = new_cursor.byte_offset;
+        self.cursor_affinity = new_cursor.affinity;
         self.rt_selection = RtSelection::collapsed(self.cursor_position);
         self.reset_cursor_blink();
     }
@@ -395,7 +392,7 @@ impl InputBox {
     /// Move cursor to start of the current line (or text for single-line).
     pub fn move_cursor_line_start(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let active = self.rt_selection.active().min(layout.text().len());
+            let active = self.rt_selection.active().min(layout.text_len());
             let new = layout.move_cursor_line_start(active);
             self.cursor_position = new;
             self.rt_selection = RtSelection::collapsed(self.cursor_position);
@@ -406,7 +403,7 @@ impl InputBox {
     /// Move cursor to end of the current line (or text for single-line).
     pub fn move_cursor_line_end(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let active = self.rt_selection.active().min(layout.text().len());
+            let active = self.rt_selection.active().min(layout.text_len());
             let new = layout.move_cursor_line_end(active);
             self.cursor_position = new;
             self.rt_selection = RtSelection::collapsed(self.cursor_position);
@@ -425,9 +422,9 @@ impl InputBox {
                 Some(layout) => layout,
                 None => return,
             };
-            let mut pos = self.cursor_position.min(layout.text().len());
+            let mut pos = self.cursor_position.min(layout.text_len());
             pos = layout.move_cursor_left_word(pos);
-            pos.min(layout.text().len())
+            pos.min(layout.text_len())
         };
         self.cursor_position = new_cursor;
         self.rt_selection = RtSelection::collapsed(self.cursor_position);
@@ -445,9 +442,9 @@ impl InputBox {
                 Some(layout) => layout,
                 None => return,
             };
-            let mut pos = self.cursor_position.min(layout.text().len());
+            let mut pos = self.cursor_position.min(layout.text_len());
             pos = layout.move_cursor_right_word(pos);
-            pos.min(layout.text().len())
+            pos.min(layout.text_len())
         };
         self.cursor_position = new_cursor;
         self.rt_selection = RtSelection::collapsed(self.cursor_position);
@@ -472,7 +469,7 @@ impl InputBox {
     /// Select all text in the input box.
     pub fn select_all(&mut self) {
         let max = if let Some(layout) = self.rt_layout.as_ref() {
-            layout.text().len()
+            layout.text_len()
         } else {
             self.text.len()
         };
@@ -484,10 +481,10 @@ impl InputBox {
     /// Extend selection from current anchor to start of the current line.
     pub fn extend_selection_to_line_start(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let active = self.rt_selection.active().min(layout.text().len());
+            let active = self.rt_selection.active().min(layout.text_len());
             let new_active = layout.move_cursor_line_start(active);
 
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = self.rt_selection.anchor().min(max);
             let active = new_active.min(max);
 
@@ -500,10 +497,10 @@ impl InputBox {
     /// Extend selection from current anchor to end of the current line.
     pub fn extend_selection_to_line_end(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let active = self.rt_selection.active().min(layout.text().len());
+            let active = self.rt_selection.active().min(layout.text_len());
             let new_active = layout.move_cursor_line_end(active);
 
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = self.rt_selection.anchor().min(max);
             let active = new_active.min(max);
 
@@ -516,9 +513,12 @@ impl InputBox {
     /// Extend selection left by one grapheme (Shift+Left).
     pub fn extend_selection_left(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let new_selection = layout
-                .extend_selection(&self.rt_selection, |offset| layout.move_cursor_left(offset));
-            let max = layout.text().len();
+            let new_selection = layout.extend_selection(&self.rt_selection, |offset| {
+                layout
+                    .move_cursor_visual_left(CursorPosition::new(offset))
+                    .byte_offset
+            });
+            let max = layout.text_len();
             let anchor = new_selection.anchor().min(max);
             let active = new_selection.active().min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -531,9 +531,11 @@ impl InputBox {
     pub fn extend_selection_right(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
             let new_selection = layout.extend_selection(&self.rt_selection, |offset| {
-                layout.move_cursor_right(offset)
+                layout
+                    .move_cursor_visual_right(CursorPosition::new(offset))
+                    .byte_offset
             });
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = new_selection.anchor().min(max);
             let active = new_selection.active().min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -548,7 +550,7 @@ impl InputBox {
             let new_selection = layout.extend_selection(&self.rt_selection, |offset| {
                 layout.move_cursor_left_word(offset)
             });
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = new_selection.anchor().min(max);
             let active = new_selection.active().min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -563,7 +565,7 @@ impl InputBox {
             let new_selection = layout.extend_selection(&self.rt_selection, |offset| {
                 layout.move_cursor_right_word(offset)
             });
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = new_selection.anchor().min(max);
             let active = new_selection.active().min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -575,7 +577,7 @@ impl InputBox {
     /// Extend selection to start of text (Shift+Home).
     pub fn extend_selection_to_start(&mut self) {
         let max = if let Some(layout) = self.rt_layout.as_ref() {
-            layout.text().len()
+            layout.text_len()
         } else {
             self.text.len()
         };
@@ -590,7 +592,7 @@ impl InputBox {
     /// Extend selection to end of text (Shift+End).
     pub fn extend_selection_to_end(&mut self) {
         let max = if let Some(layout) = self.rt_layout.as_ref() {
-            layout.text().len()
+            layout.text_len()
         } else {
             self.text.len()
         };
@@ -619,13 +621,14 @@ impl InputBox {
         if let Some(layout) = self.rt_layout.as_ref() {
             // Use TextLayout's hit testing to find the byte offset at this position
             let point = RtPoint::new(local_x, 0.0);
-            let byte_offset = layout
+            let (byte_offset, affinity) = layout
                 .hit_test(point, HitTestPolicy::Clamp)
-                .map(|hit| hit.byte_offset)
-                .unwrap_or(0);
+                .map(|hit| (hit.byte_offset, hit.affinity))
+                .unwrap_or((0, RtCursorAffinity::Downstream));
 
             self.rt_selection = RtSelection::collapsed(byte_offset);
             self.cursor_position = byte_offset;
+            self.cursor_affinity = affinity;
             self.mouse_selecting = true;
             self.last_mouse_pos = Some((screen_x, screen_y));
             self.reset_cursor_blink();
@@ -736,7 +739,7 @@ impl InputBox {
             Ok(new_cursor) => {
                 // Sync text and selection after cut
                 if let Some(layout) = self.rt_layout.as_ref() {
-                    self.text = layout.text().to_string();
+                    self.text = layout.rope().to_string();
                 }
                 self.cursor_position = new_cursor.min(self.text.len());
                 self.rt_selection = RtSelection::collapsed(self.cursor_position);
@@ -783,7 +786,7 @@ impl InputBox {
             Ok(new_cursor) => {
                 // Sync text and selection after paste
                 if let Some(layout) = self.rt_layout.as_ref() {
-                    self.text = layout.text().to_string();
+                    self.text = layout.rope().to_string();
                 }
                 self.cursor_position = new_cursor.min(self.text.len());
                 self.rt_selection = RtSelection::collapsed(self.cursor_position);
@@ -822,7 +825,7 @@ impl InputBox {
         if let Some((new_cursor, new_selection)) = result {
             // Sync text, cursor, and selection after undo
             if let Some(layout) = self.rt_layout.as_ref() {
-                self.text = layout.text().to_string();
+                self.text = layout.rope().to_string();
             }
             let max = self.text.len();
             let anchor = new_selection.anchor().min(max);
@@ -860,7 +863,7 @@ impl InputBox {
         if let Some((new_cursor, new_selection)) = result {
             // Sync text, cursor, and selection after redo
             if let Some(layout) = self.rt_layout.as_ref() {
-                self.text = layout.text().to_string();
+                self.text = layout.rope().to_string();
             }
             let max = self.text.len();
             let anchor = new_selection.anchor().min(max);
@@ -1057,12 +1060,7 @@ impl InputBox {
         let radius = 6.0;
         let rrect = RoundedRect {
             rect: self.rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: radius,
-                bl: radius,
-            },
+            radii: RoundedRadii::uniform(radius),
         };
 
         // Background
@@ -1194,7 +1192,7 @@ impl InputBox {
                     caret_renderer::render_caret(
                         canvas,
                         layout,
-                        self.cursor_position,
+                        CursorPosition::with_affinity(self.cursor_position, self.cursor_affinity),
                         &self.caret,
                         &caret_config,
                     );
@@ -1226,7 +1224,12 @@ impl InputBox {
                 };
                 caret.cmds.push(PathCmd::MoveTo([cx, cy0]));
                 caret.cmds.push(PathCmd::LineTo([cx, cy1]));
-                canvas.stroke_path(caret, 1.5, Color::rgba(63, 130, 246, 255), z + 4);
+                canvas.stroke_path(
+                    caret,
+                    1.5,
+                    Brush::Solid(Color::rgba(63, 130, 246, 255)),
+                    z + 4,
+                );
             }
         }
 
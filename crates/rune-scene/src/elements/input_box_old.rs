@@ -274,7 +274,7 @@ impl InputBox {
         let radius = 6.0;
         let rrect = RoundedRect {
             rect: self.rect,
-            radii: RoundedRadii { tl: radius, tr: radius, br: radius, bl: radius },
+            radii: RoundedRadii::uniform(radius),
         };
         
         // Background
@@ -335,7 +335,12 @@ impl InputBox {
                     };
                     caret.cmds.push(PathCmd::MoveTo([cx, cy0]));
                     caret.cmds.push(PathCmd::LineTo([cx, cy1]));
-                    canvas.stroke_path(caret, 1.5, Color::rgba(63, 130, 246, 255), z + 3);
+                    canvas.stroke_path(
+                        caret,
+                        1.5,
+                        Brush::Solid(Color::rgba(63, 130, 246, 255)),
+                        z + 3,
+                    );
                 }
             }
         } else if let Some(ref placeholder) = self.placeholder {
@@ -302,12 +302,7 @@ impl Modal {
         // 2. Render centered panel (background + border in one call)
         let panel_rrect = RoundedRect {
             rect: panel,
-            radii: RoundedRadii {
-                tl: self.panel_radius,
-                tr: self.panel_radius,
-                br: self.panel_radius,
-                bl: self.panel_radius,
-            },
+            radii: RoundedRadii::uniform(self.panel_radius),
         };
 
         // Panel background and border (at base z, text renders above)
@@ -423,12 +418,7 @@ impl Modal {
         let scrim_color = self.scrim_color();
         let panel_rrect = RoundedRect {
             rect: panel_rect,
-            radii: RoundedRadii {
-                tl: self.panel_radius,
-                tr: self.panel_radius,
-                br: self.panel_radius,
-                bl: self.panel_radius,
-            },
+            radii: RoundedRadii::uniform(self.panel_radius),
         };
         canvas.fill_scrim_with_cutout(panel_rrect, scrim_color);
 
@@ -481,12 +471,7 @@ impl Modal {
         let radius = 6.0;
         let rrect = RoundedRect {
             rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: radius,
-                bl: radius,
-            },
+            radii: RoundedRadii::uniform(radius),
         };
 
         // Button colors based on primary/secondary
@@ -1,5 +1,8 @@
 use crate::ir_adapter;
-use engine_core::{Brush, Color, ColorLinPremul, Rect, RoundedRadii, RoundedRect, SvgStyle};
+use engine_core::{
+    Brush, Color, ColorLinPremul, EllipsisPosition, Rect, RoundedRadii, RoundedRect, SvgStyle,
+    truncate_text_fast,
+};
 use rune_surface::Canvas;
 use rune_surface::shapes::{self};
 
@@ -30,12 +33,7 @@ impl Select {
     pub fn render(&self, canvas: &mut Canvas, z: i32) {
         let rrect = RoundedRect {
             rect: self.rect,
-            radii: RoundedRadii {
-                tl: self.radius,
-                tr: self.radius,
-                br: self.radius,
-                bl: self.radius,
-            },
+            radii: RoundedRadii::uniform(self.radius),
         };
 
         // Background
@@ -61,7 +59,8 @@ impl Select {
             z + 1,
         );
 
-        // Label
+        // Label, cut short before the chevron
+        let icon_size = 20.0;
         let content_w = (self.rect.w - self.padding_left - self.padding_right).max(0.0);
         let content_h = (self.rect.h - self.padding_top - self.padding_bottom).max(0.0);
         let tp = [
@@ -73,16 +72,16 @@ impl Select {
         } else {
             (&self.label, self.label_color)
         };
+        let label_w = (content_w - icon_size - 4.0).max(0.0);
         canvas.draw_text_run(
             tp,
-            text.clone(),
+            truncate_text_fast(text, label_w, self.label_size, EllipsisPosition::End),
             self.label_size,
             color,
             z + 2,
         );
 
         // Chevron icon (SVG)
-        let icon_size = 20.0;
         let icon_x = (self.rect.x + self.rect.w - self.padding_right - icon_size)
             .max(self.rect.x + self.padding_left + content_w * 0.5);
         let icon_y = self.rect.y + (self.rect.h - icon_size) * 0.5;
@@ -127,12 +126,7 @@ impl Select {
         let radius = 6.0;
         let overlay_rrect = RoundedRect {
             rect: overlay_rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: radius,
-                bl: radius,
-            },
+            radii: RoundedRadii::uniform(radius),
         };
 
         // Overlay background - solid, opaque background for better visibility
@@ -199,7 +193,12 @@ impl Select {
 
             canvas.draw_text_run(
                 [text_x, text_y],
-                option.clone(),
+                truncate_text_fast(
+                    option,
+                    text_rect.w.max(0.0),
+                    self.label_size,
+                    EllipsisPosition::End,
+                ),
                 self.label_size,
                 text_color,
                 z + 3,
@@ -1,4 +1,4 @@
-use engine_core::{Brush, ColorLinPremul, Rect};
+use engine_core::{Brush, ColorLinPremul, EllipsisPosition, Rect, truncate_text_fast};
 use rune_surface::Canvas;
 
 /// A single cell in the table
@@ -258,6 +258,12 @@ impl Table {
             .collect()
     }
 
+    /// Cut `text` to the width of a column, ending it with an ellipsis
+    fn fit_text(&self, text: &str, col_width: f32, text_size: f32) -> String {
+        let available = (col_width - self.cell_padding_x * 2.0).max(0.0);
+        truncate_text_fast(text, available, text_size, EllipsisPosition::End)
+    }
+
     /// Calculate text X position based on alignment
     fn calculate_text_x(
         &self,
@@ -309,12 +315,7 @@ impl Table {
                     w: self.rect.w,
                     h: self.rect.h - (table_start_y - self.rect.y),
                 },
-                radii: engine_core::RoundedRadii {
-                    tl: self.corner_radius,
-                    tr: self.corner_radius,
-                    br: self.corner_radius,
-                    bl: self.corner_radius,
-                },
+                radii: engine_core::RoundedRadii::uniform(self.corner_radius),
             };
             rune_surface::shapes::draw_rounded_rectangle(
                 canvas,
@@ -379,12 +380,12 @@ impl Table {
 
             // Use rounded top corners for the header fill when corner radius is set.
             if self.corner_radius > 0.0 {
-                let header_radius = engine_core::RoundedRadii {
-                    tl: self.corner_radius,
-                    tr: self.corner_radius,
-                    br: 0.0,
-                    bl: 0.0,
-                };
+                let header_radius = engine_core::RoundedRadii::new(
+                    self.corner_radius,
+                    self.corner_radius,
+                    0.0,
+                    0.0,
+                );
                 rune_surface::shapes::draw_rounded_rectangle(
                     canvas,
                     engine_core::RoundedRect {
@@ -415,10 +416,11 @@ impl Table {
             let mut x_offset = self.rect.x + self.border_width;
             for (col_idx, column) in self.columns.iter().enumerate() {
                 let col_width = column_widths[col_idx];
+                let header = self.fit_text(&column.header, col_width, self.header_text_size);
                 let text_x = self.calculate_text_x(
                     x_offset,
                     col_width,
-                    &column.header,
+                    &header,
                     self.header_text_size,
                     column.alignment,
                 );
@@ -426,7 +428,7 @@ impl Table {
 
                 canvas.draw_text_run(
                     [text_x, text_y],
-                    column.header.clone(),
+                    header,
                     self.header_text_size,
                     self.header_text_color,
                     z + 3,
@@ -499,17 +501,12 @@ impl Table {
                 let text_size = cell.size.unwrap_or(self.cell_text_size);
                 let text_color = cell.color.unwrap_or(self.cell_text_color);
 
+                let text = self.fit_text(&cell.text, col_width, text_size);
                 let text_x =
-                    self.calculate_text_x(x_offset, col_width, &cell.text, text_size, alignment);
+                    self.calculate_text_x(x_offset, col_width, &text, text_size, alignment);
                 let text_y = current_y + self.row_height * 0.5 + text_size * 0.35;
 
-                canvas.draw_text_run(
-                    [text_x, text_y],
-                    cell.text.clone(),
-                    text_size,
-                    text_color,
-                    z + 3,
-                );
+                canvas.draw_text_run([text_x, text_y], text, text_size, text_color, z + 3);
 
                 // Render vertical grid line
                 if self.show_vertical_lines && col_idx < self.columns.len() - 1 {
@@ -580,17 +577,12 @@ impl Table {
                 let text_size = cell.size.unwrap_or(self.footer_text_size);
                 let text_color = cell.color.unwrap_or(self.footer_text_color);
 
+                let text = self.fit_text(&cell.text, col_width, text_size);
                 let text_x =
-                    self.calculate_text_x(x_offset, col_width, &cell.text, text_size, alignment);
+                    self.calculate_text_x(x_offset, col_width, &text, text_size, alignment);
                 let text_y = current_y + self.row_height * 0.5 + text_size * 0.35;
 
-                canvas.draw_text_run(
-                    [text_x, text_y],
-                    cell.text.clone(),
-                    text_size,
-                    text_color,
-                    z + 3,
-                );
+                canvas.draw_text_run([text_x, text_y], text, text_size, text_color, z + 3);
 
                 // Render vertical grid line
                 if self.show_vertical_lines && col_idx < self.columns.len() - 1 {
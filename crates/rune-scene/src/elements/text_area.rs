@@ -7,8 +7,8 @@ use engine_core::{
 use rune_surface::Canvas;
 use rune_surface::shapes;
 use rune_text::layout::{
-    CursorPosition, HitTestPolicy, Point as RtPoint, Selection as RtSelection,
-    TextLayout as RtTextLayout, WrapMode as RtWrapMode,
+    CursorAffinity as RtCursorAffinity, CursorPosition, HitTestPolicy, Point as RtPoint,
+    Selection as RtSelection, TextLayout as RtTextLayout, WrapMode as RtWrapMode,
 };
 // NOTE: Line height appears to be doubled somewhere in the rendering pipeline,
 // so we use 0.7 to compensate and achieve normal single-line spacing.
@@ -30,11 +30,16 @@ pub struct TextArea {
     pub border_width: f32,
     caret: CaretBlink,
     pub cursor_position: usize,
+    // Which side of a bidi run boundary the caret is drawn on.
+    cursor_affinity: RtCursorAffinity,
     scroll_y: f32,
     padding_x: f32,
     padding_y: f32,
     min_height: Option<f32>,
     max_height: Option<f32>,
+    // Font the layout was built with. Edits must pass this same face for
+    // the layout to relayout only the paragraphs they touch.
+    font: Option<rune_text::FontFace>,
     rt_layout: Option<RtTextLayout>,
     rt_selection: RtSelection,
     mouse_selecting: bool,
@@ -60,10 +65,11 @@ impl TextArea {
         let line_height_factor = DEFAULT_LINE_HEIGHT_FACTOR;
         // Use font size directly as line height for tight spacing
         let desired_line_height = text_size * line_height_factor;
-        let rt_layout = load_system_default_font().ok().map(|font| {
+        let font = load_system_default_font().ok();
+        let rt_layout = font.as_ref().map(|font| {
             RtTextLayout::with_wrap_and_line_height(
                 text.clone(),
-                &font,
+                font,
                 text_size,
                 Some(wrap_width),
                 RtWrapMode::BreakWord,
@@ -83,11 +89,13 @@ impl TextArea {
             border_width: 1.0,
             caret: CaretBlink::new(focused),
             cursor_position: initial_cursor,
+            cursor_affinity: RtCursorAffinity::Downstream,
             scroll_y: 0.0,
             padding_x,
             padding_y: 8.0,
             min_height: Some(60.0),
             max_height: None,
+            font,
             rt_layout,
             rt_selection: RtSelection::collapsed(initial_cursor),
             mouse_selecting: false,
@@ -137,19 +145,21 @@ impl TextArea {
         self.set_rect(self.rect);
     }
 
+    /// Lay the whole text out again, for when the wrap width or line height
+    /// changed. Edits keep the layout and relayout only what they touch.
     fn rewrap_layout(&mut self) {
         let desired_line_height = self.desired_line_height();
-        if let (Some(layout), Some(wrap_width)) = (self.rt_layout.as_mut(), self.wrap_width) {
-            if let Ok(font) = load_system_default_font() {
-                *layout = RtTextLayout::with_wrap_and_line_height(
-                    self.text.clone(),
-                    &font,
-                    self.text_size,
-                    Some(wrap_width),
-                    RtWrapMode::BreakWord,
-                    desired_line_height,
-                );
-            }
+        if let (Some(layout), Some(font), Some(wrap_width)) =
+            (self.rt_layout.as_mut(), self.font.as_ref(), self.wrap_width)
+        {
+            *layout = RtTextLayout::with_wrap_and_line_height(
+                self.text.clone(),
+                font,
+                self.text_size,
+                Some(wrap_width),
+                RtWrapMode::BreakWord,
+                desired_line_height,
+            );
         }
     }
 
@@ -197,7 +207,7 @@ impl TextArea {
 
         let max_scroll = (layout.total_height() - content_height).max(0.0);
 
-        let cursor_pos = CursorPosition::new(self.cursor_position.min(layout.text().len()));
+        let cursor_pos = CursorPosition::new(self.cursor_position.min(layout.text_len()));
         let cursor_rect = match layout.cursor_rect_at_position(cursor_pos) {
             Some(r) => r,
             None => return,
@@ -235,7 +245,7 @@ impl TextArea {
 
     fn clamp_selection_to_layout(&mut self) {
         let max = if let Some(layout) = self.rt_layout.as_ref() {
-            layout.text().len()
+            layout.text_len()
         } else {
             self.text.len()
         };
@@ -258,6 +268,7 @@ impl TextArea {
             &rune_text::FontFace,
             &RtSelection,
             f32,
+            Option<f32>,
         ) -> (usize, RtSelection),
     {
         self.clamp_selection_to_layout();
@@ -268,22 +279,18 @@ impl TextArea {
             RtSelection::new(a.min(b), a.max(b))
         };
         let size = self.text_size;
+        let wrap_width = self.wrap_width;
         let (new_cursor, new_selection, new_text) = {
-            let layout = match self.rt_layout.as_mut() {
-                Some(l) => l,
-                None => return,
-            };
-            let font = match load_system_default_font() {
-                Ok(f) => f,
-                Err(_) => return,
+            let (layout, font) = match (self.rt_layout.as_mut(), self.font.as_ref()) {
+                (Some(l), Some(f)) => (l, f),
+                _ => return,
             };
-            let (new_cursor, new_selection) = f(layout, &font, &normalized_selection, size);
-            let new_text = layout.text().to_string();
+            let (new_cursor, new_selection) =
+                f(layout, font, &normalized_selection, size, wrap_width);
+            let new_text = layout.rope().to_string();
             (new_cursor, new_selection, new_text)
         };
         self.text = new_text;
-        // Recreate layout with new text to ensure proper wrapping
-        self.rewrap_layout();
         let max = self.text.len();
         let anchor = new_selection.anchor().min(max);
         let active = new_selection.active().min(max);
@@ -292,17 +299,35 @@ impl TextArea {
         self.reset_cursor_blink();
     }
 
+    /// Set the entire text content, replacing any existing text.
+    /// Moves cursor to end of text and clears selection.
+    pub fn set_text(&mut self, new_text: &str) {
+        self.text = new_text.to_string();
+        if let (Some(layout), Some(font)) = (self.rt_layout.as_mut(), self.font.as_ref()) {
+            layout.set_text(
+                new_text,
+                font,
+                self.text_size,
+                self.wrap_width,
+                RtWrapMode::BreakWord,
+            );
+        }
+        self.cursor_position = self.text.len();
+        self.rt_selection = RtSelection::collapsed(self.cursor_position);
+        self.preferred_x = None;
+        self.update_scroll();
+    }
+
     pub fn insert_char(&mut self, ch: char) {
-        self.with_layout_edit(|layout, font, selection, size| {
-            let wrap_width = layout.max_line_width().max(100.0);
+        self.with_layout_edit(|layout, font, selection, size, wrap_width| {
             let text = ch.to_string();
             let new_cursor = if selection.is_collapsed() {
                 layout.insert_char(
-                    selection.active().min(layout.text().len()),
+                    selection.active().min(layout.text_len()),
                     ch,
                     font,
                     size,
-                    Some(wrap_width),
+                    wrap_width,
                     RtWrapMode::BreakWord,
                 )
             } else {
@@ -311,7 +336,7 @@ impl TextArea {
                     &text,
                     font,
                     size,
-                    Some(wrap_width),
+                    wrap_width,
                     RtWrapMode::BreakWord,
                 )
             };
@@ -325,13 +350,12 @@ impl TextArea {
     pub fn delete_before_cursor(&mut self) {
         // If there's a selection, delete it regardless of cursor position
         if !self.rt_selection.is_collapsed() {
-            self.with_layout_edit(|layout, font, selection, size| {
-                let wrap_width = layout.max_line_width().max(100.0);
+            self.with_layout_edit(|layout, font, selection, size, wrap_width| {
                 let new_cursor = layout.delete_selection(
                     selection,
                     font,
                     size,
-                    Some(wrap_width),
+                    wrap_width,
                     RtWrapMode::BreakWord,
                 );
                 (new_cursor, RtSelection::collapsed(new_cursor))
@@ -344,13 +368,12 @@ impl TextArea {
         if self.text.is_empty() || self.cursor_position == 0 {
             return;
         }
-        self.with_layout_edit(|layout, font, selection, size| {
-            let wrap_width = layout.max_line_width().max(100.0);
+        self.with_layout_edit(|layout, font, selection, size, wrap_width| {
             let new_cursor = layout.delete_backward(
                 selection.active(),
                 font,
                 size,
-                Some(wrap_width),
+                wrap_width,
                 RtWrapMode::BreakWord,
             );
             (new_cursor, RtSelection::collapsed(new_cursor))
@@ -362,13 +385,12 @@ impl TextArea {
     pub fn delete_after_cursor(&mut self) {
         // If there's a selection, delete it regardless of cursor position
         if !self.rt_selection.is_collapsed() {
-            self.with_layout_edit(|layout, font, selection, size| {
-                let wrap_width = layout.max_line_width().max(100.0);
+            self.with_layout_edit(|layout, font, selection, size, wrap_width| {
                 let new_cursor = layout.delete_selection(
                     selection,
                     font,
                     size,
-                    Some(wrap_width),
+                    wrap_width,
                     RtWrapMode::BreakWord,
                 );
                 (new_cursor, RtSelection::collapsed(new_cursor))
@@ -381,13 +403,12 @@ impl TextArea {
         if self.text.is_empty() || self.cursor_position >= self.text.len() {
             return;
         }
-        self.with_layout_edit(|layout, font, selection, size| {
-            let wrap_width = layout.max_line_width().max(100.0);
+        self.with_layout_edit(|layout, font, selection, size, wrap_width| {
             let new_cursor = layout.delete_forward(
                 selection.active(),
                 font,
                 size,
-                Some(wrap_width),
+                wrap_width,
                 RtWrapMode::BreakWord,
             );
             (new_cursor, RtSelection::collapsed(new_cursor))
@@ -406,19 +427,16 @@ impl TextArea {
             self.reset_cursor_blink();
             return;
         }
-        if self.cursor_position == 0 {
-            return;
-        }
         let new_cursor = {
             let layout = match self.rt_layout.as_ref() {
                 Some(l) => l,
                 None => return,
             };
-            let mut pos = self.cursor_position.min(layout.text().len());
-            pos = layout.move_cursor_left(pos);
-            pos.min(layout.text().len())
+            let pos = self.cursor_position.min(layout.text_len());
+            layout.move_cursor_visual_left(CursorPosition::with_affinity(pos, self.cursor_affinity))
         };
-        self.cursor_position = new_cursor;
+        self.cursor_position = new_cursor.byte_offset;
+        self.cursor_affinity = new_cursor.affinity;
         self.preferred_x = None; // Reset preferred X on horizontal movement
         self.rt_selection = RtSelection::collapsed(self.cursor_position);
         self.reset_cursor_blink();
@@ -434,19 +452,17 @@ impl TextArea {
             self.reset_cursor_blink();
             return;
         }
-        if self.cursor_position >= self.text.len() {
-            return;
-        }
         let new_cursor = {
             let layout = match self.rt_layout.as_ref() {
                 Some(l) => l,
                 None => return,
             };
-            let mut pos = self.cursor_position.min(layout.text().len());
-            pos = layout.move_cursor_right(pos);
-            pos.min(layout.text().len())
+            let pos = self.cursor_position.min(layout.text_len());
+            layout
+                .move_cursor_visual_right(CursorPosition::with_affinity(pos, self.cursor_affinity))
         };
-        self.cursor_position = new_cursor;
+        self.cursor_position = new_cursor.byte_offset;
+        self.cursor_affinity = new_cursor.affinity;
         self.preferred_x = None; // Reset preferred X on horizontal movement
         self.rt_selection = RtSelection::collapsed(self.cursor_position);
         self.reset_cursor_blink();
@@ -454,7 +470,7 @@ impl TextArea {
 
     pub fn move_cursor_up(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let pos = self.cursor_position.min(layout.text().len());
+            let pos = self.cursor_position.min(layout.text_len());
             let (new_pos, new_x) = layout.move_cursor_up(pos, self.preferred_x);
             self.cursor_position = new_pos;
             self.preferred_x = Some(new_x);
@@ -465,7 +481,7 @@ impl TextArea {
 
     pub fn move_cursor_down(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let pos = self.cursor_position.min(layout.text().len());
+            let pos = self.cursor_position.min(layout.text_len());
             let (new_pos, new_x) = layout.move_cursor_down(pos, self.preferred_x);
             self.cursor_position = new_pos;
             self.preferred_x = Some(new_x);
@@ -476,7 +492,7 @@ impl TextArea {
 
     pub fn move_cursor_line_start(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let active = self.rt_selection.active().min(layout.text().len());
+            let active = self.rt_selection.active().min(layout.text_len());
             let new = layout.move_cursor_line_start(active);
             self.cursor_position = new;
             self.preferred_x = None;
@@ -487,7 +503,7 @@ impl TextArea {
 
     pub fn move_cursor_line_end(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let active = self.rt_selection.active().min(layout.text().len());
+            let active = self.rt_selection.active().min(layout.text_len());
             let new = layout.move_cursor_line_end(active);
             self.cursor_position = new;
             self.preferred_x = None;
@@ -521,9 +537,9 @@ impl TextArea {
                 Some(l) => l,
                 None => return,
             };
-            let mut pos = self.cursor_position.min(layout.text().len());
+            let mut pos = self.cursor_position.min(layout.text_len());
             pos = layout.move_cursor_left_word(pos);
-            pos.min(layout.text().len())
+            pos.min(layout.text_len())
         };
         self.cursor_position = new_cursor;
         self.preferred_x = None;
@@ -540,9 +556,9 @@ impl TextArea {
                 Some(l) => l,
                 None => return,
             };
-            let mut pos = self.cursor_position.min(layout.text().len());
+            let mut pos = self.cursor_position.min(layout.text_len());
             pos = layout.move_cursor_right_word(pos);
-            pos.min(layout.text().len())
+            pos.min(layout.text_len())
         };
         self.cursor_position = new_cursor;
         self.preferred_x = None;
@@ -552,7 +568,7 @@ impl TextArea {
 
     pub fn select_all(&mut self) {
         let max = if let Some(layout) = self.rt_layout.as_ref() {
-            layout.text().len()
+            layout.text_len()
         } else {
             self.text.len()
         };
@@ -564,9 +580,12 @@ impl TextArea {
 
     pub fn extend_selection_left(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let new_selection = layout
-                .extend_selection(&self.rt_selection, |offset| layout.move_cursor_left(offset));
-            let max = layout.text().len();
+            let new_selection = layout.extend_selection(&self.rt_selection, |offset| {
+                layout
+                    .move_cursor_visual_left(CursorPosition::new(offset))
+                    .byte_offset
+            });
+            let max = layout.text_len();
             let anchor = new_selection.anchor().min(max);
             let active = new_selection.active().min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -580,9 +599,11 @@ impl TextArea {
     pub fn extend_selection_right(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
             let new_selection = layout.extend_selection(&self.rt_selection, |offset| {
-                layout.move_cursor_right(offset)
+                layout
+                    .move_cursor_visual_right(CursorPosition::new(offset))
+                    .byte_offset
             });
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = new_selection.anchor().min(max);
             let active = new_selection.active().min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -602,7 +623,7 @@ impl TextArea {
                 |offset, x| layout.move_cursor_up(offset, x),
                 self.preferred_x,
             );
-            let clamped = Self::clamp_selection_to_len(&new_selection, layout.text().len());
+            let clamped = Self::clamp_selection_to_len(&new_selection, layout.text_len());
             self.rt_selection = clamped;
             self.cursor_position = clamped.active();
             self.preferred_x = Some(new_x);
@@ -619,7 +640,7 @@ impl TextArea {
                 |offset, x| layout.move_cursor_down(offset, x),
                 self.preferred_x,
             );
-            let clamped = Self::clamp_selection_to_len(&new_selection, layout.text().len());
+            let clamped = Self::clamp_selection_to_len(&new_selection, layout.text_len());
             self.rt_selection = clamped;
             self.cursor_position = clamped.active();
             self.preferred_x = Some(new_x);
@@ -629,9 +650,9 @@ impl TextArea {
 
     pub fn extend_selection_to_line_start(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let active = self.rt_selection.active().min(layout.text().len());
+            let active = self.rt_selection.active().min(layout.text_len());
             let new_active = layout.move_cursor_line_start(active);
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = self.rt_selection.anchor().min(max);
             let active = new_active.min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -643,9 +664,9 @@ impl TextArea {
 
     pub fn extend_selection_to_line_end(&mut self) {
         if let Some(layout) = self.rt_layout.as_ref() {
-            let active = self.rt_selection.active().min(layout.text().len());
+            let active = self.rt_selection.active().min(layout.text_len());
             let new_active = layout.move_cursor_line_end(active);
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = self.rt_selection.anchor().min(max);
             let active = new_active.min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -660,7 +681,7 @@ impl TextArea {
             let new_selection = layout.extend_selection(&self.rt_selection, |offset| {
                 layout.move_cursor_left_word(offset)
             });
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = new_selection.anchor().min(max);
             let active = new_selection.active().min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -674,7 +695,7 @@ impl TextArea {
             let new_selection = layout.extend_selection(&self.rt_selection, |offset| {
                 layout.move_cursor_right_word(offset)
             });
-            let max = layout.text().len();
+            let max = layout.text_len();
             let anchor = new_selection.anchor().min(max);
             let active = new_selection.active().min(max);
             self.rt_selection = RtSelection::new(anchor, active);
@@ -685,7 +706,7 @@ impl TextArea {
 
     pub fn extend_selection_to_document_start(&mut self) {
         let max = if let Some(layout) = self.rt_layout.as_ref() {
-            layout.text().len()
+            layout.text_len()
         } else {
             self.text.len()
         };
@@ -698,7 +719,7 @@ impl TextArea {
 
     pub fn extend_selection_to_document_end(&mut self) {
         let max = if let Some(layout) = self.rt_layout.as_ref() {
-            layout.text().len()
+            layout.text_len()
         } else {
             self.text.len()
         };
@@ -714,12 +735,13 @@ impl TextArea {
         let local_y = screen_y - self.rect.y - self.padding_y + self.scroll_y;
         if let Some(layout) = self.rt_layout.as_ref() {
             let point = RtPoint::new(local_x, local_y);
-            let byte_offset = layout
+            let (byte_offset, affinity) = layout
                 .hit_test(point, HitTestPolicy::Clamp)
-                .map(|hit| hit.byte_offset)
-                .unwrap_or(0);
+                .map(|hit| (hit.byte_offset, hit.affinity))
+                .unwrap_or((0, RtCursorAffinity::Downstream));
             self.rt_selection = RtSelection::collapsed(byte_offset);
             self.cursor_position = byte_offset;
+            self.cursor_affinity = affinity;
             self.preferred_x = None;
             self.mouse_selecting = true;
             self.last_mouse_pos = Some((screen_x, screen_y));
@@ -848,14 +870,14 @@ impl TextArea {
                 Some(layout) => layout,
                 None => return Err("TextLayout not available".to_string()),
             };
-            let font = match load_system_default_font() {
-                Ok(font) => font,
-                Err(_) => return Err("Failed to load system font".to_string()),
+            let font = match self.font.as_ref() {
+                Some(font) => font,
+                None => return Err("Failed to load system font".to_string()),
             };
             let wrap_width = self.wrap_width.unwrap_or(100.0);
             layout.cut_to_clipboard(
                 &selection,
-                &font,
+                font,
                 size,
                 Some(wrap_width),
                 RtWrapMode::BreakWord,
@@ -864,7 +886,7 @@ impl TextArea {
         match result {
             Ok(new_cursor) => {
                 if let Some(layout) = self.rt_layout.as_ref() {
-                    self.text = layout.text().to_string();
+                    self.text = layout.rope().to_string();
                 }
                 self.cursor_position = new_cursor.min(self.text.len());
                 self.rt_selection = RtSelection::collapsed(self.cursor_position);
@@ -884,15 +906,15 @@ impl TextArea {
                 Some(layout) => layout,
                 None => return Err("TextLayout not available".to_string()),
             };
-            let font = match load_system_default_font() {
-                Ok(font) => font,
-                Err(_) => return Err("Failed to load system font".to_string()),
+            let font = match self.font.as_ref() {
+                Some(font) => font,
+                None => return Err("Failed to load system font".to_string()),
             };
             let wrap_width = self.wrap_width.unwrap_or(100.0);
             if selection.is_collapsed() {
                 layout.paste_from_clipboard(
                     selection.active(),
-                    &font,
+                    font,
                     size,
                     Some(wrap_width),
                     RtWrapMode::BreakWord,
@@ -900,7 +922,7 @@ impl TextArea {
             } else {
                 layout.paste_replace_selection(
                     &selection,
-                    &font,
+                    font,
                     size,
                     Some(wrap_width),
                     RtWrapMode::BreakWord,
@@ -910,7 +932,7 @@ impl TextArea {
         match result {
             Ok(new_cursor) => {
                 if let Some(layout) = self.rt_layout.as_ref() {
-                    self.text = layout.text().to_string();
+                    self.text = layout.rope().to_string();
                 }
                 self.cursor_position = new_cursor.min(self.text.len());
                 self.rt_selection = RtSelection::collapsed(self.cursor_position);
@@ -930,14 +952,14 @@ impl TextArea {
                 Some(layout) => layout,
                 None => return false,
             };
-            let font = match load_system_default_font() {
-                Ok(font) => font,
-                Err(_) => return false,
+            let font = match self.font.as_ref() {
+                Some(font) => font,
+                None => return false,
             };
             let wrap_width = self.wrap_width.unwrap_or(100.0);
             layout.undo(
                 &selection,
-                &font,
+                font,
                 size,
                 Some(wrap_width),
                 RtWrapMode::BreakWord,
@@ -945,7 +967,7 @@ impl TextArea {
         };
         if let Some((new_cursor, new_selection)) = result {
             if let Some(layout) = self.rt_layout.as_ref() {
-                self.text = layout.text().to_string();
+                self.text = layout.rope().to_string();
             }
             let max = self.text.len();
             let anchor = new_selection.anchor().min(max);
@@ -968,14 +990,14 @@ impl TextArea {
                 Some(layout) => layout,
                 None => return false,
             };
-            let font = match load_system_default_font() {
-                Ok(font) => font,
-                Err(_) => return false,
+            let font = match self.font.as_ref() {
+                Some(font) => font,
+                None => return false,
             };
             let wrap_width = self.wrap_width.unwrap_or(100.0);
             layout.redo(
                 &selection,
-                &font,
+                font,
                 size,
                 Some(wrap_width),
                 RtWrapMode::BreakWord,
@@ -983,7 +1005,7 @@ impl TextArea {
         };
         if let Some((new_cursor, new_selection)) = result {
             if let Some(layout) = self.rt_layout.as_ref() {
-                self.text = layout.text().to_string();
+                self.text = layout.rope().to_string();
             }
             let max = self.text.len();
             let anchor = new_selection.anchor().min(max);
@@ -1007,12 +1029,7 @@ impl TextArea {
         let radius = 6.0;
         let rrect = RoundedRect {
             rect: self.rect,
-            radii: RoundedRadii {
-                tl: radius,
-                tr: radius,
-                br: radius,
-                bl: radius,
-            },
+            radii: RoundedRadii::uniform(radius),
         };
 
         // Background
@@ -1154,7 +1171,7 @@ impl TextArea {
                     caret_renderer::render_caret(
                         canvas,
                         layout,
-                        self.cursor_position,
+                        CursorPosition::with_affinity(self.cursor_position, self.cursor_affinity),
                         &self.caret,
                         &caret_config,
                     );
@@ -1185,7 +1202,12 @@ impl TextArea {
                 };
                 caret.cmds.push(PathCmd::MoveTo([cx, cy0]));
                 caret.cmds.push(PathCmd::LineTo([cx, cy1]));
-                canvas.stroke_path(caret, 1.5, Color::rgba(63, 130, 246, 255), z + 4);
+                canvas.stroke_path(
+                    caret,
+                    1.5,
+                    Brush::Solid(Color::rgba(63, 130, 246, 255)),
+                    z + 4,
+                );
             }
         }
 
@@ -1524,3 +1546,74 @@ impl crate::event_handler::EventHandler for TextArea {
         self.contains_point(x, y)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A text area laid out with the repo's Geist font rather than
+    /// whatever the system provides.
+    fn geist_text_area(text: &str) -> TextArea {
+        let mut area = TextArea::new(
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 400.0,
+                h: 200.0,
+            },
+            text.to_string(),
+            16.0,
+            ColorLinPremul::from_srgba_u8([0, 0, 0, 255]),
+            None,
+            true,
+        );
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../fonts/Geist/static/Geist-Regular.ttf");
+        let font = rune_text::FontFace::from_path(path, 0).expect("Geist font");
+        area.rt_layout = Some(RtTextLayout::with_wrap_and_line_height(
+            text,
+            &font,
+            area.text_size,
+            area.wrap_width,
+            RtWrapMode::BreakWord,
+            area.desired_line_height(),
+        ));
+        area.font = Some(font);
+        area
+    }
+
+    #[test]
+    fn edits_relayout_only_the_edited_paragraph() {
+        let mut area = geist_text_area("first paragraph\nsecond paragraph\nthird paragraph");
+        // Lines laid out again get new run buffers; kept lines keep theirs.
+        // Newlines' empty lines have no buffer to tell apart.
+        let run_buffers = |area: &TextArea| -> Vec<_> {
+            let layout = area.rt_layout.as_ref().unwrap();
+            let lines = layout.lines().iter().filter(|l| !l.runs.is_empty());
+            lines.map(|l| l.runs.as_ptr()).collect()
+        };
+        let before = run_buffers(&area);
+
+        area.move_cursor_to_document_start();
+        area.insert_char('x');
+        let after = run_buffers(&area);
+
+        assert_eq!(
+            area.text,
+            "xfirst paragraph\nsecond paragraph\nthird paragraph"
+        );
+        assert_eq!((before.len(), after.len()), (3, 3));
+        assert_ne!(before[0], after[0]);
+        assert_eq!(before[1..], after[1..]);
+
+        // A new wrap width lays every paragraph out again.
+        area.set_rect(Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 300.0,
+            h: 200.0,
+        });
+        let rewrapped = run_buffers(&area);
+        assert!(after.iter().all(|ptr| !rewrapped.contains(ptr)));
+    }
+}
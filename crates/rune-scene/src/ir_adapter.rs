@@ -13,13 +13,45 @@
 //! This adapter focuses on converting visual specs to rendering elements.
 //! Content resolution from DataDocument should be handled by the caller.
 
-use engine_core::{ColorLinPremul, Rect};
+use std::sync::{Arc, RwLock};
+
+use engine_core::{
+    ColorLinPremul, Filter, FontFeature, FontVariation, FontWeight, Rect, TextAttrs,
+};
 use rune_ir::view::{
-    ButtonSpec, CheckboxSpec, FlexContainerSpec, RadioSpec, SurfaceStyle, TextStyle, ViewBackground,
+    ButtonSpec, CheckboxSpec, FlexContainerSpec, Hyphens, RadioSpec, SurfaceStyle, TextAlign,
+    TextOrientation, TextOverflow, TextStyle, ViewBackground, ViewFilter, WritingMode,
+};
+use rune_text::layout::{
+    EllipsisPosition, Hyphenator, ParagraphStyle, PatternHyphenator, TabStops,
 };
 
 use crate::elements;
 
+/// Dictionary used for `hyphens: auto`.
+static HYPHENATOR: RwLock<Option<Arc<dyn Hyphenator>>> = RwLock::new(None);
+
+/// Set the hyphenation dictionary used for text styled `hyphens: auto`,
+/// e.g. a [`PatternHyphenator`] loaded from the TeX patterns of the
+/// document's language.
+///
+/// No dictionary is bundled: apps that use `hyphens: auto` must install
+/// one, here or with [`load_hyphenation_patterns`]. Without one, `auto`
+/// only hyphenates at soft hyphens.
+pub fn set_hyphenator(hyphenator: Option<Arc<dyn Hyphenator>>) {
+    if let Ok(mut guard) = HYPHENATOR.write() {
+        *guard = hyphenator;
+    }
+}
+
+/// Install the TeX hyphenation patterns in `path` (e.g.
+/// `hyph-en-us.pat.txt`) as the dictionary for `hyphens: auto`.
+pub fn load_hyphenation_patterns(path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+    let patterns = std::fs::read_to_string(path)?;
+    set_hyphenator(Some(Arc::new(PatternHyphenator::new(&patterns))));
+    Ok(())
+}
+
 /// Adapter for converting rune-ir ViewNodes to rune-scene elements.
 pub struct IrAdapter;
 
@@ -177,6 +209,100 @@ impl IrAdapter {
     pub fn font_size_from_text_style(style: &TextStyle) -> f32 {
         style.font_size.unwrap_or(16.0) as f32
     }
+
+    /// Span attributes for a TextStyle's weight, OpenType features and
+    /// variation axes. Malformed feature or variation lists are ignored, as
+    /// CSS drops invalid declarations.
+    pub fn text_attrs_from_text_style(style: &TextStyle) -> TextAttrs {
+        TextAttrs {
+            weight: style
+                .font_weight
+                .map(|w| FontWeight(w.round().clamp(1.0, 1000.0) as u16))
+                .unwrap_or_default(),
+            features: style
+                .font_feature_settings
+                .as_deref()
+                .and_then(FontFeature::parse_list)
+                .unwrap_or_default(),
+            variations: style
+                .font_variation_settings
+                .as_deref()
+                .and_then(FontVariation::parse_list)
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Map the IR `text-overflow` to the layout's overflow marking.
+    pub fn text_overflow_from_text_style(style: &TextStyle) -> rune_text::layout::TextOverflow {
+        let position = match style.text_overflow {
+            None | Some(TextOverflow::Clip) => return rune_text::layout::TextOverflow::Clip,
+            Some(TextOverflow::Ellipsis) => EllipsisPosition::End,
+            Some(TextOverflow::EllipsisStart) => EllipsisPosition::Start,
+            Some(TextOverflow::EllipsisMiddle) => EllipsisPosition::Middle,
+        };
+        rune_text::layout::TextOverflow::Ellipsis(position)
+    }
+
+    /// Justification, hyphenation, tab stops, truncation and writing mode
+    /// for a TextStyle, or `None` when it uses none of them and lines can
+    /// be wrapped the simple way.
+    pub fn paragraph_style_from_text_style(style: &TextStyle) -> Option<ParagraphStyle> {
+        let justify = style.text_align == Some(TextAlign::Justify);
+        let hyphens = match style.hyphens {
+            None => None,
+            Some(Hyphens::None) => Some(rune_text::layout::Hyphens::None),
+            Some(Hyphens::Manual) => Some(rune_text::layout::Hyphens::Manual),
+            Some(Hyphens::Auto) => Some(
+                HYPHENATOR
+                    .read()
+                    .ok()
+                    .and_then(|guard| guard.clone())
+                    .map_or(rune_text::layout::Hyphens::Manual, |hyphenator| {
+                        rune_text::layout::Hyphens::Auto(hyphenator)
+                    }),
+            ),
+        };
+        let overflow = Self::text_overflow_from_text_style(style);
+        let max_lines = style.line_clamp.map(|lines| lines as usize);
+        let writing_mode = match style.writing_mode.unwrap_or_default() {
+            WritingMode::HorizontalTb => rune_text::layout::WritingMode::HorizontalTb,
+            WritingMode::VerticalRl => rune_text::layout::WritingMode::VerticalRl,
+            WritingMode::VerticalLr => rune_text::layout::WritingMode::VerticalLr,
+        };
+        let text_orientation = match style.text_orientation.unwrap_or_default() {
+            TextOrientation::Mixed => rune_text::layout::TextOrientation::Mixed,
+            TextOrientation::Upright => rune_text::layout::TextOrientation::Upright,
+            TextOrientation::Sideways => rune_text::layout::TextOrientation::Sideways,
+        };
+        if !justify
+            && hyphens.is_none()
+            && style.tab_stops.is_none()
+            && style.tab_size.is_none()
+            && max_lines.is_none()
+            && overflow == rune_text::layout::TextOverflow::Clip
+            && !writing_mode.is_vertical()
+        {
+            return None;
+        }
+
+        let tab_stops = style.tab_stops.iter().flatten().fold(
+            TabStops {
+                positions: Vec::new(),
+                interval: style.tab_size.map(|size| size as f32),
+            },
+            |stops, &stop| stops.with_stop(stop as f32),
+        );
+        Some(ParagraphStyle {
+            justify,
+            hyphens: hyphens.unwrap_or_default(),
+            tab_stops,
+            max_lines,
+            overflow,
+            writing_mode,
+            text_orientation,
+        })
+    }
 }
 
 /// Parse a ViewBackground to extract a solid color (if present).
@@ -194,6 +320,29 @@ pub fn view_background_to_color(bg: &ViewBackground) -> Option<ColorLinPremul> {
     parse_background_color(bg)
 }
 
+/// Convert view filters to engine filters, in order. Drop shadows with an
+/// unparseable color are skipped.
+pub fn view_filters(filters: &[ViewFilter]) -> Vec<Filter> {
+    filters
+        .iter()
+        .filter_map(|filter| match filter {
+            ViewFilter::Blur { radius } => Some(Filter::blur(*radius as f32)),
+            ViewFilter::DropShadow {
+                offset_x,
+                offset_y,
+                blur,
+                color,
+            } => parse_color(color).map(|color| {
+                Filter::drop_shadow([*offset_x as f32, *offset_y as f32], *blur as f32, color)
+            }),
+            ViewFilter::Grayscale { amount } => Some(Filter::grayscale(*amount as f32)),
+            ViewFilter::Saturate { amount } => Some(Filter::saturate(*amount as f32)),
+            ViewFilter::Brightness { amount } => Some(Filter::brightness(*amount as f32)),
+            ViewFilter::Contrast { amount } => Some(Filter::contrast(*amount as f32)),
+        })
+        .collect()
+}
+
 /// Parse a color string to ColorLinPremul.
 ///
 /// Supports:
@@ -326,4 +475,21 @@ mod tests {
         assert_eq!(parse_color("#ff"), None);
         assert_eq!(parse_color("#gggggg"), None);
     }
+
+    #[test]
+    fn test_view_filters() {
+        let filters = view_filters(&[
+            ViewFilter::Blur { radius: 8.0 },
+            ViewFilter::DropShadow {
+                offset_x: 0.0,
+                offset_y: 4.0,
+                blur: 12.0,
+                color: "not-a-color".to_string(),
+            },
+            ViewFilter::Grayscale { amount: 1.0 },
+        ]);
+        assert_eq!(filters.len(), 2);
+        assert!(matches!(filters[0], Filter::Blur { radius } if radius == 8.0));
+        assert!(matches!(filters[1], Filter::ColorMatrix(_)));
+    }
 }
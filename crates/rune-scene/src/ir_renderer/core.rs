@@ -247,8 +247,17 @@ impl IrRenderer {
         if debug_logging {
             eprintln!("Computing layout...");
         }
-        self.compute_layout_with_measure(root_id, available, view_doc, data_doc)
-            .context("Failed to compute layout")?;
+        let text_provider = canvas.text_provider().cloned();
+        self.compute_layout_with_measure(
+            root_id,
+            available,
+            view_doc,
+            data_doc,
+            text_provider
+                .as_deref()
+                .map(|provider| provider as &dyn engine_core::TextProvider),
+        )
+        .context("Failed to compute layout")?;
         if debug_logging {
             eprintln!("✓ Layout computed");
         }
@@ -1092,12 +1101,16 @@ impl IrRenderer {
     }
 
     /// Compute layout with custom measurement for text nodes (wrapping support).
+    ///
+    /// Text that renders with a paragraph layout is measured with one from
+    /// `text_provider`, the provider it renders with.
     pub(crate) fn compute_layout_with_measure(
         &mut self,
         root_id: NodeId,
         available: Size<AvailableSpace>,
         view_doc: &ViewDocument,
         data_doc: &DataDocument,
+        text_provider: Option<&dyn engine_core::TextProvider>,
     ) -> Result<()> {
         self.taffy
             .compute_layout_with_measure(
@@ -1120,6 +1133,7 @@ impl IrRenderer {
                                         &text,
                                         known,
                                         available_space,
+                                        text_provider,
                                     );
                                 }
                                 ViewNodeKind::Table(spec) => {
@@ -1363,11 +1377,39 @@ impl IrRenderer {
             });
         }
 
-        if has_opacity && opacity < 1.0 {
-            // Note: Canvas doesn't have direct opacity support yet
-            // For now, we'll apply opacity to individual colors during rendering
-            // TODO: Implement canvas.push_opacity() for proper opacity layers
-        }
+        // Opacity and filters render the node and its children into an
+        // offscreen layer, composited at that opacity so overlapping children
+        // don't show through each other; backdrop filters replace what is
+        // behind its rounded bounds first.
+        let has_layer = match super::style::node_filters(&view_node.kind) {
+            Some((filters, backdrop_filters, corner_radius)) => {
+                use engine_core::{BackdropFilter, BlendMode, RoundedRadii, RoundedRect};
+                let r = corner_radius as f32;
+                let backdrop = (!backdrop_filters.is_empty()).then(|| {
+                    BackdropFilter::new(
+                        RoundedRect {
+                            rect: scene_rect,
+                            radii: RoundedRadii::uniform(r),
+                        },
+                        crate::ir_adapter::view_filters(backdrop_filters),
+                        z,
+                    )
+                });
+                canvas.push_layer_with_filters(
+                    opacity as f32,
+                    BlendMode::Normal,
+                    None,
+                    crate::ir_adapter::view_filters(filters),
+                    backdrop,
+                );
+                true
+            }
+            None if has_opacity => {
+                canvas.push_layer(opacity as f32, engine_core::BlendMode::Normal, None);
+                true
+            }
+            None => false,
+        };
 
         // Track rendered bounds to compute scrollable content height.
         self.last_content_height = self.last_content_height.max(scene_rect.y + scene_rect.h);
@@ -1409,7 +1451,7 @@ impl IrRenderer {
                 elements::render_container_element(canvas, spec, scene_rect, z, view_node_id, Some(&resolver));
             }
             ViewNodeKind::GridContainer(spec) => {
-                elements::render_background_element(canvas, &spec.background, scene_rect, z, view_node_id, Some(&resolver));
+                elements::render_grid_container_element(canvas, spec, scene_rect, z, view_node_id, Some(&resolver));
             }
             ViewNodeKind::FormContainer(spec) => {
                 elements::render_background_element(canvas, &spec.background, scene_rect, z, view_node_id, Some(&resolver));
@@ -1445,6 +1487,7 @@ impl IrRenderer {
                 // Use stateful element for interactivity
                 let input_box = self.element_state.get_or_create_input_box(
                     view_node_id,
+                    view_node,
                     spec,
                     scene_rect,
                     data_doc,
@@ -1459,6 +1502,7 @@ impl IrRenderer {
                 // Use stateful element for interactivity
                 let text_area = self.element_state.get_or_create_text_area(
                     view_node_id,
+                    view_node,
                     spec,
                     scene_rect,
                     data_doc,
@@ -1499,9 +1543,13 @@ impl IrRenderer {
             }
             ViewNodeKind::Select(spec) => {
                 // Use stateful select so dropdown toggles and focus persist
-                let select =
-                    self.element_state
-                        .get_or_create_select(view_node_id, spec, scene_rect);
+                let select = self.element_state.get_or_create_select(
+                    view_node_id,
+                    view_node,
+                    spec,
+                    scene_rect,
+                    data_doc,
+                );
                 select.render(canvas, z);
 
                 // Register hit regions for the field and (when open) the dropdown overlay
@@ -1528,9 +1576,11 @@ impl IrRenderer {
                 // Use stateful date picker so popup toggles and focus persist
                 let picker = self.element_state.get_or_create_date_picker(
                     view_node_id,
+                    view_node,
                     spec,
                     scene_rect,
                     self.current_content_height,
+                    data_doc,
                 );
                 picker.render(canvas, z);
 
@@ -1644,14 +1694,27 @@ impl IrRenderer {
             ctx.push(layout); // Push transform
 
             for child_id in children {
+                let clip = view_doc.node(child_id).and_then(|child| {
+                    elements::child_clip(&view_node.kind, scene_rect, &child.kind)
+                });
+                if let Some(rrect) = clip {
+                    canvas.push_clip_rounded_rect(rrect);
+                }
                 self.render_view_node_with_elements(
                     canvas, data_doc, view_doc, child_id, ctx, provider,
                 )?;
+                if clip.is_some() {
+                    canvas.pop_clip();
+                }
             }
 
             ctx.pop(); // Pop transform
         }
 
+        if has_layer {
+            canvas.pop_layer();
+        }
+
         // Pop animation transforms
         if has_transform {
             canvas.pop_transform(); // Pop the transform matrix
@@ -1715,6 +1778,34 @@ impl IrRenderer {
                     super::state::OverlayType::Confirm => "Confirm".to_string(),
                 });
 
+            // Backdrop filters cover the whole viewport behind the overlay.
+            let has_filters = !spec.filters.is_empty() || !spec.backdrop_filters.is_empty();
+            if has_filters {
+                use engine_core::{BackdropFilter, BlendMode, Rect, RoundedRect};
+                let backdrop = (!spec.backdrop_filters.is_empty()).then(|| {
+                    BackdropFilter::new(
+                        RoundedRect {
+                            rect: Rect {
+                                x: 0.0,
+                                y: 0.0,
+                                w: viewport_width,
+                                h: viewport_height,
+                            },
+                            radii: Default::default(),
+                        },
+                        crate::ir_adapter::view_filters(&spec.backdrop_filters),
+                        overlay_z,
+                    )
+                });
+                canvas.push_layer_with_filters(
+                    1.0,
+                    BlendMode::Normal,
+                    None,
+                    crate::ir_adapter::view_filters(&spec.filters),
+                    backdrop,
+                );
+            }
+
             match active_overlay.overlay_type {
                 super::state::OverlayType::Modal => {
                     elements::render_modal_overlay(
@@ -1759,6 +1850,10 @@ impl IrRenderer {
                     );
                 }
             }
+
+            if has_filters {
+                canvas.pop_layer();
+            }
         }
 
         canvas.pop_transform();
@@ -42,13 +42,150 @@ pub(super) fn render_container_element(
     node_id: &str,
     resolver: Option<&crate::animation::resolver::AnimatedPropertyResolver>,
 ) {
-    // Render background if present
-    render_background_element(canvas, &spec.background, rect, z, node_id, resolver);
-
-    // TODO: Render border if present
+    let rrect = engine_core::RoundedRect {
+        rect,
+        radii: container_radii(spec.corner_radius, spec.corner_radii),
+    };
+    let border = container_border(
+        (spec.border_width, spec.border_color.as_deref()),
+        [
+            (spec.border_top_width, spec.border_top_color.as_deref()),
+            (spec.border_right_width, spec.border_right_color.as_deref()),
+            (
+                spec.border_bottom_width,
+                spec.border_bottom_color.as_deref(),
+            ),
+            (spec.border_left_width, spec.border_left_color.as_deref()),
+        ],
+    );
+    render_box(
+        canvas,
+        &spec.background,
+        rrect,
+        border,
+        z,
+        node_id,
+        resolver,
+    );
     // Children are rendered by recursion from the caller.
 }
 
+/// Render GridContainer using background/border (children handled recursively).
+pub(super) fn render_grid_container_element(
+    canvas: &mut rune_surface::Canvas,
+    spec: &rune_ir::view::GridContainerSpec,
+    rect: engine_core::Rect,
+    z: i32,
+    node_id: &str,
+    resolver: Option<&crate::animation::resolver::AnimatedPropertyResolver>,
+) {
+    let rrect = engine_core::RoundedRect {
+        rect,
+        radii: container_radii(spec.corner_radius, spec.corner_radii),
+    };
+    let border = container_border(
+        (spec.border_width, spec.border_color.as_deref()),
+        [
+            (spec.border_top_width, spec.border_top_color.as_deref()),
+            (spec.border_right_width, spec.border_right_color.as_deref()),
+            (
+                spec.border_bottom_width,
+                spec.border_bottom_color.as_deref(),
+            ),
+            (spec.border_left_width, spec.border_left_color.as_deref()),
+        ],
+    );
+    render_box(
+        canvas,
+        &spec.background,
+        rrect,
+        border,
+        z,
+        node_id,
+        resolver,
+    );
+}
+
+/// Rounded bounds `child` is clipped to inside a container at `rect`:
+/// everything in a scroll container, and images in a rounded card.
+pub(super) fn child_clip(
+    container: &rune_ir::view::ViewNodeKind,
+    rect: engine_core::Rect,
+    child: &rune_ir::view::ViewNodeKind,
+) -> Option<engine_core::RoundedRect> {
+    use rune_ir::view::ViewNodeKind;
+
+    let (radii, scrolls) = match container {
+        ViewNodeKind::FlexContainer(spec) => (
+            container_radii(spec.corner_radius, spec.corner_radii),
+            spec.scroll.horizontal || spec.scroll.vertical,
+        ),
+        ViewNodeKind::GridContainer(spec) => (
+            container_radii(spec.corner_radius, spec.corner_radii),
+            false,
+        ),
+        _ => return None,
+    };
+    let rounded_image = matches!(child, ViewNodeKind::Image(_)) && !radii.is_zero();
+    (scrolls || rounded_image).then_some(engine_core::RoundedRect { rect, radii })
+}
+
+/// Fill `rrect` with `background` and draw `border` inside its edge.
+fn render_box(
+    canvas: &mut rune_surface::Canvas,
+    background: &Option<rune_ir::view::ViewBackground>,
+    rrect: engine_core::RoundedRect,
+    border: Option<engine_core::Border>,
+    z: i32,
+    node_id: &str,
+    resolver: Option<&crate::animation::resolver::AnimatedPropertyResolver>,
+) {
+    if rrect.radii.is_zero() {
+        render_background_element(canvas, background, rrect.rect, z, node_id, resolver);
+    } else if let Some(bg) = background {
+        let brush = brush_from_view_background_animated(bg, rrect.rect, node_id, resolver);
+        canvas.fill_path(rrect.to_path(), brush, z);
+    }
+    if let Some(border) = border {
+        canvas.border(rrect, border, z);
+    }
+}
+
+/// Corner radii of a container; per-corner radii take precedence over the
+/// uniform `corner_radius`.
+fn container_radii(
+    corner_radius: Option<f64>,
+    corner_radii: Option<rune_ir::view::CornerRadii>,
+) -> engine_core::RoundedRadii {
+    match corner_radii {
+        Some(r) => {
+            let [tl, tr, br, bl] = r.corners().map(|c| [c[0] as f32, c[1] as f32]);
+            engine_core::RoundedRadii { tl, tr, br, bl }
+        }
+        None => engine_core::RoundedRadii::uniform(corner_radius.unwrap_or(0.0) as f32),
+    }
+}
+
+/// Border from per-side `(width, color)` pairs in CSS order, falling back to
+/// the uniform width and color for sides that don't set them. `None` when
+/// no side is visible.
+fn container_border(
+    uniform: (Option<f64>, Option<&str>),
+    sides: [(Option<f64>, Option<&str>); 4],
+) -> Option<engine_core::Border> {
+    // CSS defaults to `currentColor`; containers have no text color here.
+    let default_color = ColorLinPremul::from_srgba_u8([0, 0, 0, 255]);
+    let mut border = engine_core::Border::default();
+    for (i, (width, color)) in sides.into_iter().enumerate() {
+        border.widths[i] = width.or(uniform.0).unwrap_or(0.0).max(0.0) as f32;
+        border.colors[i] = color
+            .or(uniform.1)
+            .and_then(crate::ir_adapter::parse_color)
+            .unwrap_or(default_color);
+    }
+    border.widths.iter().any(|&w| w > 0.0).then_some(border)
+}
+
 /// Render Text element using elements::Text or elements::Label.
 pub(super) fn render_text_element(
     canvas: &mut rune_surface::Canvas,
@@ -68,6 +205,7 @@ pub(super) fn render_text_element(
     // Get text styling
     let color = crate::ir_adapter::IrAdapter::color_from_text_style(&spec.style);
     let size = crate::ir_adapter::IrAdapter::font_size_from_text_style(&spec.style);
+    let attrs = crate::ir_adapter::IrAdapter::text_attrs_from_text_style(&spec.style);
     let pad_x = spec.style.padding.left as f32;
     let pad_y = spec.style.padding.top as f32;
     let pad_right = spec.style.padding.right as f32;
@@ -91,6 +229,50 @@ pub(super) fn render_text_element(
         );
     }
 
+    // Justified, hyphenated or tabbed text needs real shaping to place its
+    // words. Without a provider that lays out paragraphs, the heuristic
+    // lines below are drawn instead.
+    if let Some(paragraph_style) =
+        crate::ir_adapter::IrAdapter::paragraph_style_from_text_style(&spec.style)
+    {
+        // Vertical lines run down the content box rather than across it.
+        let vertical = paragraph_style.writing_mode.is_vertical();
+        let line_length = if vertical {
+            (rect.h - pad_y - spec.style.padding.bottom as f32).max(0.0)
+        } else {
+            wrap_width
+        };
+        let max_width = (line_length > 0.0).then_some(line_length);
+        let layout = canvas.text_provider().and_then(|provider| {
+            provider.paragraph_layout(&text, size, max_width, &paragraph_style)
+        });
+        if let Some(layout) = layout {
+            let align = spec
+                .style
+                .text_align
+                .unwrap_or(rune_ir::view::TextAlign::Start);
+            let origin = [rect.x + pad_x, rect.y + pad_y];
+            for line in layout.lines() {
+                let turn =
+                    vertical.then(|| vertical_line_transform(&layout, line, origin, wrap_width));
+                draw_layout_line(
+                    canvas,
+                    layout.text(),
+                    line,
+                    if turn.is_some() { [0.0, 0.0] } else { origin },
+                    turn,
+                    align,
+                    line_length,
+                    size,
+                    color,
+                    &attrs,
+                    z,
+                );
+            }
+            return;
+        }
+    }
+
     // Use shared line computation so measurement and render stay in sync
     let lines = super::text_measure::compute_lines(
         spec,
@@ -119,10 +301,248 @@ pub(super) fn render_text_element(
                 base_x + (content_width - line_width).max(0.0) * 0.5
             }
             rune_ir::view::TextAlign::End => base_x + (content_width - line_width).max(0.0),
-            rune_ir::view::TextAlign::Start => base_x,
+            rune_ir::view::TextAlign::Start | rune_ir::view::TextAlign::Justify => base_x,
+        };
+
+        let origin = [aligned_x.round(), y];
+        draw_text_piece(canvas, origin, line.clone(), size, color, &attrs, z);
+    }
+}
+
+/// Draw one run of text, with span attributes when the style has any.
+fn draw_text_piece(
+    canvas: &mut rune_surface::Canvas,
+    origin: [f32; 2],
+    text: String,
+    size: f32,
+    color: ColorLinPremul,
+    attrs: &engine_core::TextAttrs,
+    z: i32,
+) {
+    if *attrs == engine_core::TextAttrs::default() {
+        canvas.draw_text_run(origin, text, size, color, z);
+    } else {
+        let len = text.len();
+        let run =
+            engine_core::TextRun::new(text, origin, size, color).with_span(0..len, attrs.clone());
+        canvas.draw_attributed_text(run, z);
+    }
+}
+
+/// Draw a line of a rune-text layout word by word at the positions the
+/// layout gave its words, so justified spaces, tab stops and hyphens end
+/// up where the layout put them. `origin` is the top-left of the content
+/// box, in line coordinates when `turn` turns a vertical line onto the
+/// screen.
+#[allow(clippy::too_many_arguments)]
+fn draw_layout_line(
+    canvas: &mut rune_surface::Canvas,
+    text: &str,
+    line: &rune_text::layout::LineBox,
+    origin: [f32; 2],
+    turn: Option<engine_core::Transform2D>,
+    align: rune_ir::view::TextAlign,
+    content_width: f32,
+    size: f32,
+    color: ColorLinPremul,
+    attrs: &engine_core::TextAttrs,
+    z: i32,
+) {
+    let x0 = origin[0]
+        + match align {
+            rune_ir::view::TextAlign::Center => (content_width - line.width).max(0.0) * 0.5,
+            rune_ir::view::TextAlign::End => (content_width - line.width).max(0.0),
+            rune_ir::view::TextAlign::Start | rune_ir::view::TextAlign::Justify => 0.0,
+        };
+    let y = (origin[1] + line.baseline_y()).round();
+    let center_y = origin[1] + line.central_baseline_y();
+    // Where the glyph at `index` of an upright run sits.
+    let upright_origin = |run: &rune_text::shaping::ShapedRun, index: usize| {
+        let pos = run
+            .positions
+            .get(index)
+            .map_or([0.0, 0.0], |p| [p.x_offset, p.y_offset]);
+        [x0 + run.x_offset + pos[0], center_y + pos[1]]
+    };
+
+    // Every glyph cluster by the byte it starts at: its left edge, and the
+    // origin of its first glyph when it stands upright in a vertical line.
+    let clusters: Vec<(usize, f32, Option<[f32; 2]>)> = line
+        .runs
+        .iter()
+        .flat_map(|run| {
+            run.cluster_spans().into_iter().map(move |span| {
+                let start = span.text_range.start;
+                let upright = run.upright.then(|| {
+                    let glyph = run
+                        .clusters
+                        .iter()
+                        .position(|&c| run.text_range.start + c as usize == start)
+                        .unwrap_or(0);
+                    upright_origin(run, glyph)
+                });
+                (start, run.x_offset + span.x_start, upright)
+            })
+        })
+        .collect();
+
+    let line_text = &text[line.text_range.clone()];
+    // Text hidden behind an ellipsis or a line clamp isn't drawn.
+    let visible = match &line.elided {
+        Some(elided) => vec![
+            0..elided.start - line.text_range.start,
+            elided.end - line.text_range.start..line_text.len(),
+        ],
+        None => std::iter::once(0..line_text.len()).collect(),
+    };
+    let mut words = Vec::new();
+    for part in visible {
+        let mut word_start = None;
+        for (i, ch) in line_text[part.clone()].char_indices() {
+            if ch.is_whitespace() {
+                if let Some(start) = word_start.take() {
+                    words.push(start..part.start + i);
+                }
+            } else if word_start.is_none() {
+                word_start = Some(part.start + i);
+            }
+        }
+        if let Some(start) = word_start {
+            words.push(start..part.end);
+        }
+    }
+
+    for word in words {
+        let bytes = (line.text_range.start + word.start)..(line.text_range.start + word.end);
+        let mut word_clusters: Vec<_> = clusters
+            .iter()
+            .filter(|(start, _, _)| bytes.contains(start))
+            .collect();
+        word_clusters.sort_by_key(|(start, _, _)| *start);
+        // Stretches of sideways clusters are drawn whole; upright clusters
+        // one by one, each where its glyph sits.
+        let mut first = 0;
+        while first < word_clusters.len() {
+            let upright = word_clusters[first].2;
+            let end = if upright.is_some() {
+                first + 1
+            } else {
+                first
+                    + word_clusters[first..]
+                        .iter()
+                        .take_while(|(_, _, upright)| upright.is_none())
+                        .count()
+            };
+            let start_byte = if first == 0 {
+                bytes.start
+            } else {
+                word_clusters[first].0
+            };
+            let end_byte = word_clusters.get(end).map_or(bytes.end, |c| c.0);
+            let origin = upright.unwrap_or_else(|| {
+                let x = word_clusters[first..end]
+                    .iter()
+                    .map(|&&(_, x, _)| x)
+                    .fold(f32::INFINITY, f32::min);
+                [(x0 + x).round(), y]
+            });
+            // Soft hyphens only show as the line-end hyphen.
+            let piece: String = text[start_byte..end_byte]
+                .chars()
+                .filter(|&c| c != '\u{AD}')
+                .collect();
+            let upright = upright.is_some();
+            draw_line_piece(canvas, turn, origin, piece, upright, size, color, attrs, z);
+            first = end;
+        }
+    }
+    let marker_origin = |run: &rune_text::shaping::ShapedRun| {
+        if run.upright {
+            upright_origin(run, 0)
+        } else {
+            [(x0 + run.x_offset).round(), y]
+        }
+    };
+    if let Some(hyphen) = &line.hyphen {
+        let origin = marker_origin(hyphen);
+        let upright = hyphen.upright;
+        let marker = "-".to_string();
+        draw_line_piece(canvas, turn, origin, marker, upright, size, color, attrs, z);
+    }
+    if let Some(ellipsis) = &line.ellipsis {
+        // Shaped from "…", or from "..." when the font lacks it.
+        let marker = if ellipsis.glyphs.len() == 1 {
+            "\u{2026}"
+        } else {
+            "..."
         };
+        let origin = marker_origin(ellipsis);
+        let upright = ellipsis.upright;
+        let marker = marker.to_string();
+        draw_line_piece(canvas, turn, origin, marker, upright, size, color, attrs, z);
+    }
+}
+
+/// Draw a piece of a layout line at `origin` in line coordinates. `turn`
+/// puts vertical lines on screen: sideways pieces turn with the line,
+/// upright ones are only placed by it and use the font's vertical
+/// alternates (`vert`).
+#[allow(clippy::too_many_arguments)]
+fn draw_line_piece(
+    canvas: &mut rune_surface::Canvas,
+    turn: Option<engine_core::Transform2D>,
+    origin: [f32; 2],
+    text: String,
+    upright: bool,
+    size: f32,
+    color: ColorLinPremul,
+    attrs: &engine_core::TextAttrs,
+    z: i32,
+) {
+    let Some(turn) = turn else {
+        draw_text_piece(canvas, origin, text, size, color, attrs, z);
+        return;
+    };
+    if upright {
+        let [a, b, c, d, e, f] = turn.m;
+        let point = [
+            (a * origin[0] + c * origin[1] + e).round(),
+            (b * origin[0] + d * origin[1] + f).round(),
+        ];
+        let mut attrs = attrs.clone();
+        attrs.features.push(engine_core::FontFeature::on(*b"vert"));
+        draw_text_piece(canvas, point, text, size, color, &attrs, z);
+    } else {
+        canvas.push_transform(turn);
+        draw_text_piece(canvas, origin, text, size, color, attrs, z);
+        canvas.pop_transform();
+    }
+}
 
-        canvas.draw_text_run([aligned_x.round(), y], line.clone(), size, color, z);
+/// Transform putting a line of vertical text on screen: a quarter turn
+/// clockwise from line coordinates, with lines stacking from the right
+/// edge of the content box for `vertical-rl` and from its left edge for
+/// `vertical-lr`. `origin` is the top-left of the content box.
+fn vertical_line_transform(
+    layout: &rune_text::layout::TextLayout,
+    line: &rune_text::layout::LineBox,
+    origin: [f32; 2],
+    content_width: f32,
+) -> engine_core::Transform2D {
+    let top = layout.to_physical(rune_text::Point::new(0.0, line.y_offset));
+    let start = match layout.writing_mode() {
+        rune_text::layout::WritingMode::VerticalRl => content_width - layout.physical_size().0,
+        _ => 0.0,
+    };
+    engine_core::Transform2D {
+        m: [
+            0.0,
+            1.0,
+            -1.0,
+            0.0,
+            origin[0] + start + top.x + line.y_offset,
+            origin[1],
+        ],
     }
 }
 
@@ -409,12 +829,7 @@ pub(super) fn render_input_box_element(
 
     let rrect = RoundedRect {
         rect,
-        radii: RoundedRadii {
-            tl: radius,
-            tr: radius,
-            br: radius,
-            bl: radius,
-        },
+        radii: RoundedRadii::uniform(radius),
     };
 
     // Background
@@ -475,7 +890,7 @@ pub(super) fn render_input_box_element(
             TextAlign::End => {
                 rect.x + padding.left as f32 + (content_width - approx_width).max(0.0)
             }
-            TextAlign::Start => rect.x + padding.left as f32,
+            TextAlign::Start | TextAlign::Justify => rect.x + padding.left as f32,
         }
     };
 
@@ -692,12 +1107,7 @@ pub(super) fn render_table_element(
             canvas,
             RoundedRect {
                 rect,
-                radii: RoundedRadii {
-                    tl: corner_radius,
-                    tr: corner_radius,
-                    br: corner_radius,
-                    bl: corner_radius,
-                },
+                radii: RoundedRadii::uniform(corner_radius),
             },
             Some(bg_brush),
             Some(stroke_width),
@@ -1131,6 +1541,13 @@ fn brush_from_view_background_animated(
     use crate::animation::types::AnimatableProperty;
     use rune_ir::view::ViewBackground;
     let default_color = engine_core::ColorLinPremul::from_srgba_u8([40, 45, 65, 255]);
+    let spread = |repeating: bool| {
+        if repeating {
+            engine_core::SpreadMode::Repeat
+        } else {
+            engine_core::SpreadMode::Pad
+        }
+    };
 
     match bg {
         ViewBackground::Solid { color } => {
@@ -1151,7 +1568,11 @@ fn brush_from_view_background_animated(
                 a: rgba[3],
             })
         }
-        ViewBackground::LinearGradient { angle, stops } => {
+        ViewBackground::LinearGradient {
+            angle,
+            stops,
+            repeating,
+        } => {
             let angle_rad = (*angle as f32).to_radians();
             let cx = rect.x + rect.w * 0.5;
             let cy = rect.y + rect.h * 0.5;
@@ -1176,6 +1597,7 @@ fn brush_from_view_background_animated(
                     start,
                     end,
                     stops: parsed_stops,
+                    spread: spread(*repeating),
                 }
             }
         }
@@ -1185,6 +1607,7 @@ fn brush_from_view_background_animated(
             rx,
             ry,
             stops,
+            repeating,
         } => {
             let center = [
                 rect.x + rect.w * (*cx as f32),
@@ -1209,6 +1632,39 @@ fn brush_from_view_background_animated(
                     center,
                     radius,
                     stops: parsed_stops,
+                    spread: spread(*repeating),
+                }
+            }
+        }
+        ViewBackground::ConicGradient {
+            cx,
+            cy,
+            angle,
+            stops,
+            repeating,
+        } => {
+            let center = [
+                rect.x + rect.w * (*cx as f32),
+                rect.y + rect.h * (*cy as f32),
+            ];
+            // CSS measures conic angles from the top; the brush from +x.
+            let angle = ((*angle - 90.0) as f32).to_radians();
+
+            let parsed_stops: Vec<(f32, engine_core::ColorLinPremul)> = stops
+                .iter()
+                .filter_map(|(color, t)| {
+                    crate::ir_adapter::parse_color(color).map(|c| (*t as f32, c))
+                })
+                .collect();
+
+            if parsed_stops.is_empty() {
+                engine_core::Brush::Solid(default_color)
+            } else {
+                engine_core::Brush::ConicGradient {
+                    center,
+                    angle,
+                    stops: parsed_stops,
+                    spread: spread(*repeating),
                 }
             }
         }
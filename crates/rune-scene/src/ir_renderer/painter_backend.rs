@@ -46,7 +46,9 @@ impl IrRenderer {
             height: AvailableSpace::Definite(viewport_height),
         };
 
-        self.compute_layout_with_measure(root_id, available, view, data)
+        // Painters have no text provider, so text is measured the way it
+        // is drawn here: with heuristic lines.
+        self.compute_layout_with_measure(root_id, available, view, data, None)
             .context("Failed to compute layout")?;
 
         // Render with transform tracking
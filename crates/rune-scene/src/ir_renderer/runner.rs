@@ -103,6 +103,7 @@ pub fn run() -> Result<()> {
         config.format,
     );
     surf.set_use_intermediate(true);
+    surf.set_partial_redraw(true);
     surf.set_logical_pixels(true);
     surf.set_dpi_scale(scale_factor);
 
@@ -110,6 +111,14 @@ pub fn run() -> Result<()> {
     let provider: std::sync::Arc<dyn engine_core::TextProvider> =
         std::sync::Arc::new(create_text_provider()?);
 
+    // `hyphens: auto` needs a dictionary; none is bundled.
+    if let Ok(path) = std::env::var("RUNE_HYPHENATION_PATTERNS") {
+        match crate::ir_adapter::load_hyphenation_patterns(&path) {
+            Ok(()) => eprintln!("✓ Loaded hyphenation patterns from {}", path),
+            Err(e) => eprintln!("✗ Failed to load hyphenation patterns '{}': {}", path, e),
+        }
+    }
+
     // Create IR renderer
     let mut ir_renderer = IrRenderer::new();
 
@@ -1058,6 +1067,12 @@ pub fn run() -> Result<()> {
                         // Update CSS-like animations (transitions and keyframes)
                         let has_active_animations = ir_renderer.update_animations(delta_ms);
 
+                        // Write edits from bound inputs back to the data document;
+                        // rendering pulls document changes into bound inputs.
+                        ir_renderer
+                            .element_state_mut()
+                            .sync_to_data_document(&mut data_doc);
+
                         // Render frame with zones
                         match render_frame_with_zones(
                             &mut surf,
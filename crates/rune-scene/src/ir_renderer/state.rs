@@ -11,6 +11,9 @@
 //! - Elements are created on-demand during rendering
 //! - State persists across frames for smooth interaction
 //! - Events are dispatched to elements via `EventHandler` trait
+//! - Elements whose data node has a `DataBinding` pick up document changes
+//!   when rendered or when `apply_mutation` applies an IR diff, and write
+//!   edits back in `sync_to_data_document`
 //!
 //! # Example
 //!
@@ -30,13 +33,15 @@ use crate::event_handler::{
     EventHandler, EventResult, KeyboardEvent, MouseClickEvent, MouseMoveEvent,
 };
 use engine_core::{ColorLinPremul, Rect};
-use rune_ir::data::document::DataDocument;
+use rune_ir::data::document::{BindingMode, DataDocument};
+use rune_ir::logic::IrMutation;
 use rune_ir::view::{
     ButtonSpec, CheckboxSpec, DatePickerSpec, FileInputSpec, InputBoxSpec, RadioSpec, SelectSpec,
     TextAlign, TextAreaSpec, ViewNode, ViewNodeId,
 };
 #[cfg(feature = "webview-cef")]
 use rune_ir::view::WebViewSpec;
+use serde_json::Value;
 use std::collections::HashMap;
 
 /// Element type identifier for focus management
@@ -75,6 +80,30 @@ pub enum OverlayType {
     Confirm,
 }
 
+/// Which element a data binding drives, with what it needs to convert values.
+#[derive(Debug, Clone, PartialEq)]
+enum BoundWidget {
+    InputBox,
+    TextArea,
+    Checkbox,
+    /// Radio and the value it submits when selected.
+    Radio(String),
+    /// Select and the submitted value of each option.
+    Select(Vec<String>),
+    DatePicker,
+}
+
+/// An element bound to a path in the data document.
+#[derive(Debug, Clone)]
+struct BoundElement {
+    widget: BoundWidget,
+    path: String,
+    mode: BindingMode,
+    /// Value last exchanged with the document, used to tell which side
+    /// changed since the previous sync.
+    synced: Option<Value>,
+}
+
 /// State container for all interactive IR elements
 ///
 /// This structure maintains the runtime state of interactive elements
@@ -115,6 +144,9 @@ pub struct IrElementState {
     #[cfg(feature = "webview-cef")]
     webviews: HashMap<ViewNodeId, elements::WebView>,
 
+    /// Elements with a `DataBinding`, refreshed each time they are rendered
+    bindings: HashMap<ViewNodeId, BoundElement>,
+
     /// Home-tab chat state: once a query has been submitted from the
     /// home_tab input, we treat the hero banner and greeting as dismissed
     /// so subsequent renders can hide them.
@@ -147,6 +179,7 @@ impl IrElementState {
             file_inputs: HashMap::new(),
             #[cfg(feature = "webview-cef")]
             webviews: HashMap::new(),
+            bindings: HashMap::new(),
             home_chat_started: false,
             focused_element: None,
             dirty: false,
@@ -168,6 +201,7 @@ impl IrElementState {
         self.file_inputs.clear();
         #[cfg(feature = "webview-cef")]
         self.webviews.clear();
+        self.bindings.clear();
         self.home_chat_started = false;
         self.focused_element = None;
         self.dirty = false;
@@ -208,21 +242,24 @@ impl IrElementState {
     ///
     /// # Arguments
     /// * `view_node_id` - Unique identifier for this element in the view document
+    /// * `view_node` - View node, whose `node_id` selects the data binding
     /// * `spec` - IR specification for this input box
     /// * `rect` - Layout rectangle (from Taffy)
     /// * `data_doc` - Data document for resolving bound values
     pub fn get_or_create_input_box(
         &mut self,
         view_node_id: &ViewNodeId,
+        view_node: &ViewNode,
         spec: &InputBoxSpec,
         rect: Rect,
-        _data_doc: &DataDocument,
+        data_doc: &DataDocument,
     ) -> &mut elements::InputBox {
+        let pulled = self.pull_binding(view_node_id, view_node, BoundWidget::InputBox, data_doc);
         let entry = self
             .input_boxes
             .entry(view_node_id.clone())
             .or_insert_with(|| {
-                // Use default value from spec (data binding would be resolved separately)
+                // Start from the spec default; a bound value is applied below.
                 let text = spec.default_value.clone().unwrap_or_default();
 
                 // Default text styling; overridden below if IR provides text_style.
@@ -244,6 +281,14 @@ impl IrElementState {
                 input
             });
 
+        if let Some(text) = pulled
+            .as_ref()
+            .map(value_to_text)
+            .filter(|text| *text != entry.text)
+        {
+            entry.set_text(&text);
+        }
+
         // CRITICAL: Update rect every frame to handle window resize and layout changes
         entry.rect = rect;
         entry.apply_surface_style(&spec.style);
@@ -289,15 +334,17 @@ impl IrElementState {
     pub fn get_or_create_text_area(
         &mut self,
         view_node_id: &ViewNodeId,
+        view_node: &ViewNode,
         spec: &TextAreaSpec,
         rect: Rect,
-        _data_doc: &DataDocument,
+        data_doc: &DataDocument,
     ) -> &mut elements::TextArea {
+        let pulled = self.pull_binding(view_node_id, view_node, BoundWidget::TextArea, data_doc);
         let entry = self
             .text_areas
             .entry(view_node_id.clone())
             .or_insert_with(|| {
-                // Use default value from spec (data binding would be resolved separately)
+                // Start from the spec default; a bound value is applied below.
                 let text = spec.default_value.clone().unwrap_or_default();
 
                 let text_color = ColorLinPremul::from_srgba_u8([26, 32, 44, 255]);
@@ -320,6 +367,13 @@ impl IrElementState {
 
         // CRITICAL: Update rect every frame to handle window resize and layout changes
         entry.set_rect(rect);
+        if let Some(text) = pulled
+            .as_ref()
+            .map(value_to_text)
+            .filter(|text| *text != entry.text)
+        {
+            entry.set_text(&text);
+        }
         entry.apply_surface_style(&spec.style);
         if spec.style.background.is_none() {
             entry.bg_color = ColorLinPremul::from_srgba_u8([0, 0, 0, 0]);
@@ -413,6 +467,8 @@ impl IrElementState {
             .unwrap_or_else(|| rect.w.min(rect.h) as f64)
             .max(0.0) as f32;
 
+        let pulled = self.pull_binding(view_node_id, view_node, BoundWidget::Checkbox, data_doc);
+
         let checkbox = self
             .checkboxes
             .entry(view_node_id.clone())
@@ -429,7 +485,11 @@ impl IrElementState {
                 )
             });
 
-        // Keep layout in sync with Taffy output; preserve user-driven checked state.
+        // Keep layout in sync with Taffy output; preserve user-driven checked state
+        // unless the bound value changed in the document.
+        if let Some(value) = pulled.as_ref() {
+            checkbox.checked = value_is_truthy(value);
+        }
         checkbox.rect.x = rect.x;
         checkbox.rect.y = rect.y;
         checkbox.rect.w = box_size;
@@ -489,6 +549,14 @@ impl IrElementState {
             .max(1.0) as f32;
         let radius = diameter * 0.5;
 
+        let radio_value = spec.value.clone().unwrap_or_else(|| "on".to_string());
+        let pulled = self.pull_binding(
+            view_node_id,
+            view_node,
+            BoundWidget::Radio(radio_value.clone()),
+            data_doc,
+        );
+
         let selected_after_creation = {
             let entry = self.radios.entry(view_node_id.clone()).or_insert_with(|| {
                 crate::ir_adapter::IrAdapter::radio_from_spec(
@@ -503,6 +571,10 @@ impl IrElementState {
                 )
             });
 
+            if let Some(value) = pulled.as_ref() {
+                entry.selected = value_to_text(value) == radio_value;
+            }
+
             // Keep layout and metadata in sync.
             entry.center = [rect.x + rect.w * 0.5, rect.y + rect.h * 0.5];
             entry.radius = radius;
@@ -579,9 +651,23 @@ impl IrElementState {
     pub fn get_or_create_select(
         &mut self,
         view_node_id: &ViewNodeId,
+        view_node: &ViewNode,
         spec: &SelectSpec,
         rect: Rect,
+        data_doc: &DataDocument,
     ) -> &mut elements::Select {
+        let option_values: Vec<String> = spec
+            .options
+            .iter()
+            .map(|opt| opt.value.clone().unwrap_or_else(|| opt.label.clone()))
+            .collect();
+        let pulled = self.pull_binding(
+            view_node_id,
+            view_node,
+            BoundWidget::Select(option_values.clone()),
+            data_doc,
+        );
+
         let entry = self.selects.entry(view_node_id.clone()).or_insert_with(|| {
             let options: Vec<String> = spec.options.iter().map(|opt| opt.label.clone()).collect();
 
@@ -634,6 +720,11 @@ impl IrElementState {
             select
         });
 
+        if let Some(value) = pulled.as_ref() {
+            let text = value_to_text(value);
+            entry.set_selected_index(option_values.iter().position(|v| *v == text));
+        }
+
         // Keep layout in sync with Taffy output each frame.
         entry.rect = rect;
         entry.apply_surface_style(&spec.style);
@@ -711,10 +802,13 @@ impl IrElementState {
     pub fn get_or_create_date_picker(
         &mut self,
         view_node_id: &ViewNodeId,
+        view_node: &ViewNode,
         spec: &DatePickerSpec,
         rect: Rect,
         viewport_height: f32,
+        data_doc: &DataDocument,
     ) -> &mut elements::DatePicker {
+        let pulled = self.pull_binding(view_node_id, view_node, BoundWidget::DatePicker, data_doc);
         let entry = self
             .date_pickers
             .entry(view_node_id.clone())
@@ -726,17 +820,7 @@ impl IrElementState {
                 let label_color =
                     crate::ir_adapter::IrAdapter::color_from_text_style(&spec.label_style);
                 // Parse initial date from spec if provided (YYYY-MM-DD format)
-                let initial_date = spec.default_value.as_ref().and_then(|s| {
-                    let parts: Vec<&str> = s.split('-').collect();
-                    if parts.len() == 3 {
-                        let year = parts[0].parse::<u32>().ok()?;
-                        let month = parts[1].parse::<u32>().ok()?;
-                        let day = parts[2].parse::<u32>().ok()?;
-                        Some((year, month, day))
-                    } else {
-                        None
-                    }
-                });
+                let initial_date = spec.default_value.as_deref().and_then(parse_date);
 
                 elements::DatePicker::new(
                     rect,
@@ -746,6 +830,10 @@ impl IrElementState {
                 )
             });
 
+        if let Some(value) = pulled.as_ref() {
+            entry.set_selected_date(value.as_str().and_then(parse_date));
+        }
+
         // Keep layout in sync with Taffy output each frame
         entry.rect = rect;
         // Set viewport height for smart popup positioning
@@ -1426,14 +1514,248 @@ impl IrElementState {
     // Data Synchronization
     // ========================================================================
 
+    /// Track the data binding of an element being rendered.
+    ///
+    /// Returns the bound value when the binding pulls and the document holds a
+    /// different value than was last exchanged with the element, e.g. after a
+    /// logic update or diff. Callers apply it to the element; `None` leaves
+    /// user-driven state alone.
+    fn pull_binding(
+        &mut self,
+        view_node_id: &ViewNodeId,
+        view_node: &ViewNode,
+        widget: BoundWidget,
+        data_doc: &DataDocument,
+    ) -> Option<Value> {
+        let Some(binding) = view_node
+            .node_id
+            .as_deref()
+            .and_then(|node_id| data_doc.binding_for(node_id))
+        else {
+            self.bindings.remove(view_node_id);
+            return None;
+        };
+
+        let bound = self
+            .bindings
+            .entry(view_node_id.clone())
+            .or_insert_with(|| BoundElement {
+                widget: widget.clone(),
+                path: binding.path.clone(),
+                mode: binding.mode,
+                synced: None,
+            });
+        if bound.path != binding.path {
+            bound.path = binding.path.clone();
+            bound.synced = None;
+        }
+        bound.widget = widget;
+        bound.mode = binding.mode;
+
+        if !binding.mode.pulls() {
+            return None;
+        }
+        let value = data_doc.resolve_path(&binding.path)?;
+        if bound.synced.as_ref() == Some(&value) {
+            return None;
+        }
+        bound.synced = Some(value.clone());
+        Some(value)
+    }
+
+    /// Current value of a bound element, in the form written to the document.
+    ///
+    /// Unselected radios have no value so only the selected member of a group
+    /// writes to the shared path.
+    fn bound_value(&self, view_node_id: &ViewNodeId, widget: &BoundWidget) -> Option<Value> {
+        match widget {
+            BoundWidget::InputBox => self
+                .input_boxes
+                .get(view_node_id)
+                .map(|input| Value::String(input.text.clone())),
+            BoundWidget::TextArea => self
+                .text_areas
+                .get(view_node_id)
+                .map(|textarea| Value::String(textarea.text.clone())),
+            BoundWidget::Checkbox => self
+                .checkboxes
+                .get(view_node_id)
+                .map(|checkbox| Value::Bool(checkbox.checked)),
+            BoundWidget::Radio(value) => self
+                .radios
+                .get(view_node_id)
+                .filter(|radio| radio.selected)
+                .map(|_| Value::String(value.clone())),
+            BoundWidget::Select(values) => self.selects.get(view_node_id).map(|select| {
+                select
+                    .selected_index
+                    .and_then(|idx| values.get(idx))
+                    .map_or(Value::Null, |v| Value::String(v.clone()))
+            }),
+            BoundWidget::DatePicker => self.date_pickers.get(view_node_id).map(|picker| {
+                picker
+                    .selected_date
+                    .map_or(Value::Null, |(year, month, day)| {
+                        Value::String(format!("{year:04}-{month:02}-{day:02}"))
+                    })
+            }),
+        }
+    }
+
     /// Synchronize element state back to data document
     ///
-    /// Updates text content in the data document when user edits input fields
-    pub fn sync_to_data_document(&self, _data_doc: &mut DataDocument) {
-        // TODO: Implement bidirectional data binding
-        // For now, elements maintain their own state
-        // Future: Update DataDocument text nodes when inputs change
+    /// Elements whose binding pushes (`push` or `duplex`) write their current
+    /// value to the binding path when it differs from the value last exchanged
+    /// with the document. Text written over a number is stored as a number when
+    /// it parses as one. Returns whether the document changed.
+    pub fn sync_to_data_document(&mut self, data_doc: &mut DataDocument) -> bool {
+        let edits: Vec<(ViewNodeId, Value)> = self
+            .bindings
+            .iter()
+            .filter(|(_, bound)| bound.mode.pushes())
+            .filter_map(|(id, bound)| Some((id.clone(), self.bound_value(id, &bound.widget)?)))
+            .collect();
+
+        let mut changed = false;
+        for (view_node_id, value) in edits {
+            let Some(bound) = self.bindings.get_mut(&view_node_id) else {
+                continue;
+            };
+            let value = match (data_doc.resolve_path(&bound.path), value) {
+                (Some(Value::Number(_)), Value::String(text)) => {
+                    match serde_json::from_str::<serde_json::Number>(text.trim()) {
+                        Ok(number) => Value::Number(number),
+                        Err(_) => Value::String(text),
+                    }
+                }
+                (_, value) => value,
+            };
+            if bound.synced.as_ref() == Some(&value) {
+                continue;
+            }
+            if data_doc.set_path(&bound.path, value.clone()) {
+                bound.synced = Some(value);
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.dirty = true;
+        }
+        changed
+    }
+
+    /// Apply a mutation from logic to the data document.
+    ///
+    /// Diff operations that target the data layer are applied with
+    /// `DataDocument::apply_diff`, after writing back pending element edits so
+    /// the diff wins where both touch the same path. Bound elements then pick
+    /// up the new values without waiting for the next render. Other mutations
+    /// are left to the host. Returns whether the document changed.
+    pub fn apply_mutation(&mut self, data_doc: &mut DataDocument, mutation: &IrMutation) -> bool {
+        let IrMutation::IrDiff { ops } = mutation else {
+            return false;
+        };
+
+        self.sync_to_data_document(data_doc);
+        let mut changed = false;
+        for op in ops {
+            changed |= data_doc.apply_diff(op);
+        }
+        if changed {
+            self.pull_from_data_document(data_doc);
+            self.dirty = true;
+        }
+        changed
+    }
+
+    /// Pull document values into every bound element whose binding pulls and
+    /// whose value changed since it was last exchanged with the document.
+    pub fn pull_from_data_document(&mut self, data_doc: &DataDocument) {
+        let pulls: Vec<(ViewNodeId, BoundWidget, Value)> = self
+            .bindings
+            .iter_mut()
+            .filter(|(_, bound)| bound.mode.pulls())
+            .filter_map(|(id, bound)| {
+                let value = data_doc.resolve_path(&bound.path)?;
+                if bound.synced.as_ref() == Some(&value) {
+                    return None;
+                }
+                bound.synced = Some(value.clone());
+                Some((id.clone(), bound.widget.clone(), value))
+            })
+            .collect();
+
+        for (view_node_id, widget, value) in pulls {
+            match widget {
+                BoundWidget::InputBox => {
+                    if let Some(input) = self.input_boxes.get_mut(&view_node_id) {
+                        input.set_text(&value_to_text(&value));
+                    }
+                }
+                BoundWidget::TextArea => {
+                    if let Some(textarea) = self.text_areas.get_mut(&view_node_id) {
+                        textarea.set_text(&value_to_text(&value));
+                    }
+                }
+                BoundWidget::Checkbox => {
+                    if let Some(checkbox) = self.checkboxes.get_mut(&view_node_id) {
+                        checkbox.checked = value_is_truthy(&value);
+                    }
+                }
+                BoundWidget::Radio(radio_value) => {
+                    if let Some(radio) = self.radios.get_mut(&view_node_id) {
+                        radio.selected = value_to_text(&value) == radio_value;
+                    }
+                }
+                BoundWidget::Select(option_values) => {
+                    if let Some(select) = self.selects.get_mut(&view_node_id) {
+                        let text = value_to_text(&value);
+                        select.set_selected_index(option_values.iter().position(|v| *v == text));
+                    }
+                }
+                BoundWidget::DatePicker => {
+                    if let Some(picker) = self.date_pickers.get_mut(&view_node_id) {
+                        picker.set_selected_date(value.as_str().and_then(parse_date));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Text shown for a bound value: strings as-is, `null` as empty, anything
+/// else as JSON.
+fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Checked state for a bound value.
+fn value_is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Null => false,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty() && s != "false",
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+/// Parse a `YYYY-MM-DD` date.
+fn parse_date(value: &str) -> Option<(u32, u32, u32)> {
+    let parts: Vec<&str> = value.split('-').collect();
+    if parts.len() != 3 {
+        return None;
     }
+    let year = parts[0].parse::<u32>().ok()?;
+    let month = parts[1].parse::<u32>().ok()?;
+    let day = parts[2].parse::<u32>().ok()?;
+    Some((year, month, day))
 }
 
 impl Default for IrElementState {
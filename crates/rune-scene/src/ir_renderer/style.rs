@@ -1,6 +1,7 @@
 //! Shared style and color helpers for the IR renderer.
 
 use engine_core::{Brush, ColorLinPremul};
+use rune_ir::view::ViewFilter;
 use taffy::prelude::{
     AlignItems, Dimension, JustifyContent, LengthPercentage, LengthPercentageAuto,
 };
@@ -95,3 +96,38 @@ pub(crate) fn layout_justify_to_taffy(
         rune_ir::view::LayoutJustify::SpaceBetween => JustifyContent::SpaceBetween,
     })
 }
+
+/// Content filters, backdrop filters and corner radius of a node that sets
+/// any filters; overlays apply theirs separately.
+pub(crate) fn node_filters(
+    kind: &rune_ir::view::ViewNodeKind,
+) -> Option<(&[ViewFilter], &[ViewFilter], f64)> {
+    use rune_ir::view::ViewNodeKind;
+
+    let style = match kind {
+        ViewNodeKind::FlexContainer(spec) => {
+            return (!spec.filters.is_empty() || !spec.backdrop_filters.is_empty()).then_some((
+                spec.filters.as_slice(),
+                spec.backdrop_filters.as_slice(),
+                spec.corner_radius.unwrap_or(0.0),
+            ));
+        }
+        ViewNodeKind::Button(spec) => &spec.style,
+        ViewNodeKind::Link(spec) => &spec.style,
+        ViewNodeKind::Table(spec) => &spec.style,
+        ViewNodeKind::InputBox(spec) => &spec.style,
+        ViewNodeKind::TextArea(spec) => &spec.style,
+        ViewNodeKind::Checkbox(spec) => &spec.style,
+        ViewNodeKind::Radio(spec) => &spec.style,
+        ViewNodeKind::Select(spec) => &spec.style,
+        ViewNodeKind::FileInput(spec) => &spec.style,
+        ViewNodeKind::DatePicker(spec) => &spec.style,
+        ViewNodeKind::WebView(spec) => &spec.style,
+        _ => return None,
+    };
+    (!style.filters.is_empty() || !style.backdrop_filters.is_empty()).then_some((
+        style.filters.as_slice(),
+        style.backdrop_filters.as_slice(),
+        style.corner_radius.unwrap_or(0.0),
+    ))
+}
@@ -53,3 +53,235 @@ fn message_label_color_matches_spec() {
     let color = IrAdapter::color_from_text_style(&spec.style);
     assert_eq!(color.to_srgba_u8(), [0xf2, 0xf2, 0xf2, 0xff]);
 }
+
+mod data_binding {
+    use crate::ir_renderer::IrElementState;
+    use engine_core::Rect;
+    use rune_ir::data::document::DataDocument;
+    use rune_ir::logic::{IrDiffOp, IrMutation};
+    use rune_ir::view::{ViewNode, ViewNodeKind};
+    use serde_json::json;
+
+    const RECT: Rect = Rect {
+        x: 0.0,
+        y: 0.0,
+        w: 200.0,
+        h: 32.0,
+    };
+
+    fn document() -> DataDocument {
+        serde_json::from_value(json!({
+            "document_id": "profile_form",
+            "nodes": [
+                { "node_id": "PROFILE1", "kind": "value", "value": {
+                    "name": "Ada", "subscribed": false, "plan": "basic"
+                } }
+            ],
+            "bindings": [
+                { "target": "NAMEINPT", "path": "PROFILE1.name", "mode": "duplex" },
+                { "target": "SUBSCRIB", "path": "PROFILE1.subscribed", "mode": "duplex" },
+                { "target": "PLANSELC", "path": "PROFILE1.plan", "mode": "duplex" }
+            ]
+        }))
+        .expect("valid data document")
+    }
+
+    fn view_nodes() -> Vec<ViewNode> {
+        serde_json::from_value(json!([
+            { "id": "name_input", "node_id": "NAMEINPT", "type": "input_box" },
+            { "id": "subscribe_box", "node_id": "SUBSCRIB", "type": "checkbox" },
+            { "id": "plan_select", "node_id": "PLANSELC", "type": "select", "options": [
+                { "label": "Basic", "value": "basic" },
+                { "label": "Pro", "value": "pro" }
+            ] }
+        ]))
+        .expect("valid view nodes")
+    }
+
+    /// Create or refresh the bound elements, as a render pass does.
+    fn render(state: &mut IrElementState, nodes: &[ViewNode], data_doc: &DataDocument) {
+        for node in nodes {
+            match &node.kind {
+                ViewNodeKind::InputBox(spec) => {
+                    state.get_or_create_input_box(&node.id, node, spec, RECT, data_doc);
+                }
+                ViewNodeKind::Checkbox(spec) => {
+                    state.get_or_create_checkbox(&node.id, node, spec, RECT, data_doc);
+                }
+                ViewNodeKind::Select(spec) => {
+                    state.get_or_create_select(&node.id, node, spec, RECT, data_doc);
+                }
+                other => panic!("unexpected view node {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn widget_edits_write_to_bound_paths() {
+        let mut data_doc = document();
+        let nodes = view_nodes();
+        let mut state = IrElementState::new();
+        render(&mut state, &nodes, &data_doc);
+
+        let (input, checkbox, select) = (&nodes[0], &nodes[1], &nodes[2]);
+        let ViewNodeKind::InputBox(input_spec) = &input.kind else {
+            unreachable!()
+        };
+        let ViewNodeKind::Checkbox(checkbox_spec) = &checkbox.kind else {
+            unreachable!()
+        };
+        let ViewNodeKind::Select(select_spec) = &select.kind else {
+            unreachable!()
+        };
+        state
+            .get_or_create_input_box(&input.id, input, input_spec, RECT, &data_doc)
+            .set_text("Grace");
+        state
+            .get_or_create_checkbox(&checkbox.id, checkbox, checkbox_spec, RECT, &data_doc)
+            .checked = true;
+        state
+            .get_or_create_select(&select.id, select, select_spec, RECT, &data_doc)
+            .set_selected_index(Some(1));
+
+        // Re-rendering before the sync keeps the edits: the document hasn't
+        // changed since the values were pulled.
+        render(&mut state, &nodes, &data_doc);
+
+        assert!(state.sync_to_data_document(&mut data_doc));
+        assert_eq!(data_doc.resolve_path("PROFILE1.name"), Some(json!("Grace")));
+        assert_eq!(
+            data_doc.resolve_path("PROFILE1.subscribed"),
+            Some(json!(true))
+        );
+        assert_eq!(data_doc.resolve_path("PROFILE1.plan"), Some(json!("pro")));
+
+        // Nothing left to write.
+        assert!(!state.sync_to_data_document(&mut data_doc));
+    }
+
+    #[test]
+    fn document_changes_update_widgets() {
+        let mut data_doc = document();
+        let nodes = view_nodes();
+        let mut state = IrElementState::new();
+        render(&mut state, &nodes, &data_doc);
+
+        let (input, checkbox, select) = (&nodes[0], &nodes[1], &nodes[2]);
+        let ViewNodeKind::InputBox(input_spec) = &input.kind else {
+            unreachable!()
+        };
+        let ViewNodeKind::Checkbox(checkbox_spec) = &checkbox.kind else {
+            unreachable!()
+        };
+        let ViewNodeKind::Select(select_spec) = &select.kind else {
+            unreachable!()
+        };
+        assert_eq!(
+            state
+                .get_or_create_input_box(&input.id, input, input_spec, RECT, &data_doc)
+                .text,
+            "Ada"
+        );
+        assert!(
+            !state
+                .get_or_create_checkbox(&checkbox.id, checkbox, checkbox_spec, RECT, &data_doc)
+                .checked
+        );
+        assert_eq!(
+            state
+                .get_or_create_select(&select.id, select, select_spec, RECT, &data_doc)
+                .selected_index,
+            Some(0)
+        );
+
+        assert!(data_doc.set_path("PROFILE1.name", json!("Linus")));
+        assert!(data_doc.set_path("PROFILE1.subscribed", json!(true)));
+        assert!(data_doc.set_path("PROFILE1.plan", json!("pro")));
+
+        assert_eq!(
+            state
+                .get_or_create_input_box(&input.id, input, input_spec, RECT, &data_doc)
+                .text,
+            "Linus"
+        );
+        assert!(
+            state
+                .get_or_create_checkbox(&checkbox.id, checkbox, checkbox_spec, RECT, &data_doc)
+                .checked
+        );
+        assert_eq!(
+            state
+                .get_or_create_select(&select.id, select, select_spec, RECT, &data_doc)
+                .selected_index,
+            Some(1)
+        );
+
+        // Values pulled from the document aren't written back.
+        assert!(!state.sync_to_data_document(&mut data_doc));
+    }
+
+    #[test]
+    fn ir_diff_mutations_update_bound_widgets() {
+        let mut data_doc = document();
+        let nodes = view_nodes();
+        let mut state = IrElementState::new();
+        render(&mut state, &nodes, &data_doc);
+
+        let (input, checkbox, select) = (&nodes[0], &nodes[1], &nodes[2]);
+        let ViewNodeKind::InputBox(input_spec) = &input.kind else {
+            unreachable!()
+        };
+        let ViewNodeKind::Checkbox(checkbox_spec) = &checkbox.kind else {
+            unreachable!()
+        };
+        let ViewNodeKind::Select(select_spec) = &select.kind else {
+            unreachable!()
+        };
+        // A pending edit on a path the diff also sets is overridden.
+        state
+            .get_or_create_input_box(&input.id, input, input_spec, RECT, &data_doc)
+            .set_text("Grace");
+        state.mark_clean();
+
+        let mutation: IrMutation = serde_json::from_value(json!({
+            "type": "ir_diff",
+            "ops": [
+                { "op": "set_value", "path": "PROFILE1.name", "value": "Linus" },
+                { "op": "set_value", "path": "PROFILE1.subscribed", "value": true },
+                { "op": "set_value", "path": "PROFILE1.plan", "value": "pro" }
+            ]
+        }))
+        .expect("valid mutation");
+        assert!(state.apply_mutation(&mut data_doc, &mutation));
+        assert!(state.is_dirty());
+        assert_eq!(data_doc.resolve_path("PROFILE1.name"), Some(json!("Linus")));
+
+        // Widgets already hold the new values, so rendering has nothing to pull.
+        assert_eq!(state.get_input_box(&input.id).unwrap().text, "Linus");
+        assert!(
+            state
+                .get_or_create_checkbox(&checkbox.id, checkbox, checkbox_spec, RECT, &data_doc)
+                .checked
+        );
+        assert_eq!(
+            state
+                .get_or_create_select(&select.id, select, select_spec, RECT, &data_doc)
+                .selected_index,
+            Some(1)
+        );
+
+        // Nothing left to write back, and rendering keeps the pulled values.
+        assert!(!state.sync_to_data_document(&mut data_doc));
+        render(&mut state, &nodes, &data_doc);
+        assert_eq!(state.get_input_box(&input.id).unwrap().text, "Linus");
+
+        // Diffs addressed to the host don't touch the document.
+        let host_only = IrMutation::IrDiff {
+            ops: vec![IrDiffOp::ReplaceText {
+                target: "widget:InputBox".into(),
+                text: "ignored".into(),
+            }],
+        };
+        assert!(!state.apply_mutation(&mut data_doc, &host_only));
+    }
+}
@@ -1,4 +1,4 @@
-use engine_core::wrap_text_fast;
+use engine_core::{TextProvider, clamp_lines_fast, wrap_text_fast};
 use rune_ir::view::TextSpec;
 use taffy::prelude::{AvailableSpace, Size};
 
@@ -51,6 +51,13 @@ pub fn compute_lines(spec: &TextSpec, text: &str, wrap_width: Option<f32>) -> He
     if out_lines.is_empty() {
         out_lines.push(String::new());
     }
+    clamp_lines_fast(
+        &mut out_lines,
+        spec.style.line_clamp.map(|lines| lines as usize),
+        wrap_width,
+        font_size,
+        crate::ir_adapter::IrAdapter::text_overflow_from_text_style(&spec.style),
+    );
 
     HeuristicLines {
         lines: out_lines,
@@ -62,18 +69,61 @@ pub fn compute_lines(spec: &TextSpec, text: &str, wrap_width: Option<f32>) -> He
 }
 
 /// Estimate wrapped text size for IR text nodes (used by Taffy measurement).
+///
+/// Text the renderer lays out as a paragraph (see
+/// [`IrAdapter::paragraph_style_from_text_style`]) is measured with the same
+/// layout from `provider`, so the box fits the lines that get drawn.
+///
+/// [`IrAdapter::paragraph_style_from_text_style`]: crate::ir_adapter::IrAdapter::paragraph_style_from_text_style
 pub fn measure_text_node(
     spec: &TextSpec,
     text: &str,
     known: Size<Option<f32>>,
     available: Size<AvailableSpace>,
+    provider: Option<&dyn TextProvider>,
 ) -> Size<f32> {
+    if let Some(size) =
+        provider.and_then(|provider| measure_paragraph(spec, text, known, available, provider))
+    {
+        return size;
+    }
+
     let font_size = spec.style.font_size.unwrap_or(16.0) as f32;
     let pad_left = spec.style.padding.left as f32;
     let pad_right = spec.style.padding.right as f32;
     let pad_top = spec.style.padding.top as f32;
     let pad_bottom = spec.style.padding.bottom as f32;
 
+    // Vertical lines run down the box and stack across it. Upright CJK
+    // characters are about an em tall each.
+    if spec
+        .style
+        .writing_mode
+        .is_some_and(|mode| mode.is_vertical())
+    {
+        let available_height = known
+            .height
+            .or(match available.height {
+                AvailableSpace::Definite(h) => Some(h),
+                _ => None,
+            })
+            .map(|h| h.max(0.0));
+        let line_length = available_height.map(|h| (h - pad_top - pad_bottom).max(0.0));
+        let lines = compute_lines(spec, text, line_length);
+        let line_count = lines.lines.len().max(1);
+        let measured_width = pad_left
+            + pad_right
+            + lines.ascent
+            + lines.descent
+            + (line_count.saturating_sub(1) as f32) * lines.line_height;
+        let measured_height = available_height
+            .unwrap_or(pad_top + pad_bottom + (lines.longest_chars as f32) * font_size);
+        return Size {
+            width: measured_width.max(0.0),
+            height: measured_height.max(0.0),
+        };
+    }
+
     let available_width = known
         .width
         .or_else(|| match available.width {
@@ -120,3 +170,51 @@ pub fn measure_text_node(
         height: measured_height.max(0.0),
     }
 }
+
+/// Size of `text` laid out as a paragraph, as `render_text_element` draws
+/// it, or `None` when its style needs no paragraph layout or the provider
+/// can't make one.
+fn measure_paragraph(
+    spec: &TextSpec,
+    text: &str,
+    known: Size<Option<f32>>,
+    available: Size<AvailableSpace>,
+    provider: &dyn TextProvider,
+) -> Option<Size<f32>> {
+    let style = crate::ir_adapter::IrAdapter::paragraph_style_from_text_style(&spec.style)?;
+    let font_size = crate::ir_adapter::IrAdapter::font_size_from_text_style(&spec.style);
+    let padding = &spec.style.padding;
+    let pad_x = (padding.left + padding.right) as f32;
+    let pad_y = (padding.top + padding.bottom) as f32;
+
+    // Lines run across the box, or down it in vertical text.
+    let vertical = style.writing_mode.is_vertical();
+    let (known_length, available_length, pad) = if vertical {
+        (known.height, available.height, pad_y)
+    } else {
+        (known.width, available.width, pad_x)
+    };
+    let box_length = known_length
+        .or(match available_length {
+            AvailableSpace::Definite(length) => Some(length),
+            _ => None,
+        })
+        .map(|length| length.max(0.0));
+    let line_length = box_length
+        .map(|length| (length - pad).max(0.0))
+        .filter(|&length| length > 0.0);
+
+    let layout = provider.paragraph_layout(text, font_size, line_length, &style)?;
+    let (width, height) = layout.physical_size();
+    Some(if vertical {
+        Size {
+            width: width + pad_x,
+            height: box_length.unwrap_or(height + pad_y),
+        }
+    } else {
+        Size {
+            width: box_length.unwrap_or(width + pad_x),
+            height: height + pad_y,
+        }
+    })
+}
@@ -18,6 +18,7 @@ pub mod layout;
 pub mod navigation;
 pub mod persistence;
 pub mod scene;
+pub mod snapshot;
 pub mod text;
 pub mod zones;
 
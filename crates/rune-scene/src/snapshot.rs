@@ -0,0 +1,354 @@
+//! Golden-image snapshot harness for IR documents.
+//!
+//! A `RunePackage` (or a `DataDocument` + `ViewDocument` pair) is laid out by
+//! [`IrRenderer`] into a headless [`Canvas`], rasterized with the CPU reference
+//! renderer and compared against a checked-in PNG with a perceptual tolerance.
+//! On mismatch the actual frame and a diff image are written next to each
+//! other so the regression can be inspected.
+//!
+//! Set `RUNE_UPDATE_SNAPSHOTS=1` to (re)write goldens instead of comparing,
+//! and `RUNE_SNAPSHOT_OUT` to choose where failure artifacts go (defaults to
+//! `<tmp>/rune-snapshots`).
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use engine_core::{ColorLinPremul, Rect, RuneTextProvider, SubpixelOrientation, TextProvider};
+use image::RgbaImage;
+use rune_ir::{data::document::DataDocument, package::RunePackage, view::ViewDocument};
+use rune_surface::Canvas;
+
+use crate::ir_renderer::IrRenderer;
+
+/// Environment variable that switches the harness into golden-writing mode.
+pub const UPDATE_ENV: &str = "RUNE_UPDATE_SNAPSHOTS";
+/// Environment variable overriding the directory for failure artifacts.
+pub const OUT_DIR_ENV: &str = "RUNE_SNAPSHOT_OUT";
+
+/// Frame setup for a snapshot render.
+#[derive(Clone, Copy, Debug)]
+pub struct SnapshotConfig {
+    /// Logical viewport width.
+    pub width: f32,
+    /// Logical viewport height.
+    pub height: f32,
+    /// Logical→device multiplier; the image is `width * scale_factor` wide.
+    pub scale_factor: f32,
+    /// Clear color behind the document (the runner's window background).
+    pub background: ColorLinPremul,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+            scale_factor: 1.0,
+            background: ColorLinPremul::from_srgba_u8([32, 32, 36, 255]),
+        }
+    }
+}
+
+/// Perceptual comparison thresholds.
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    /// Normalized YIQ distance (0..1) above which a pixel counts as changed.
+    pub pixel_threshold: f32,
+    /// Fraction of changed pixels accepted before the comparison fails.
+    pub max_diff_ratio: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            pixel_threshold: 0.1,
+            max_diff_ratio: 0.001,
+        }
+    }
+}
+
+/// Result of comparing a frame against its golden.
+pub struct Comparison {
+    pub diff_pixels: usize,
+    pub total_pixels: usize,
+    /// Largest normalized YIQ distance seen.
+    pub max_delta: f32,
+    /// Golden faded to light gray with changed pixels in red.
+    pub diff_image: RgbaImage,
+}
+
+impl Comparison {
+    pub fn diff_ratio(&self) -> f32 {
+        self.diff_pixels as f32 / self.total_pixels.max(1) as f32
+    }
+
+    pub fn passes(&self, tolerance: &Tolerance) -> bool {
+        self.diff_ratio() <= tolerance.max_diff_ratio
+    }
+}
+
+/// Text provider backed by the bundled Geist font, so snapshots do not depend
+/// on the fonts installed on the machine running them.
+pub fn bundled_text_provider() -> Result<Arc<RuneTextProvider>> {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fonts/Geist/static/Geist-Regular.ttf");
+    let bytes =
+        std::fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(Arc::new(RuneTextProvider::from_bytes(
+        &bytes,
+        SubpixelOrientation::RGB,
+    )?))
+}
+
+/// Render the entrypoint documents of a package.
+pub fn render_package(
+    package: &RunePackage,
+    config: &SnapshotConfig,
+    provider: Arc<dyn TextProvider + Send + Sync>,
+) -> Result<RgbaImage> {
+    let (data, view) = package.entrypoint_documents()?;
+    render_documents(data, view, config, provider)
+}
+
+/// Lay out and rasterize a document pair the way the runner's viewport zone
+/// does: clipped to the viewport, no scroll offset.
+pub fn render_documents(
+    data_doc: &DataDocument,
+    view_doc: &ViewDocument,
+    config: &SnapshotConfig,
+    provider: Arc<dyn TextProvider + Send + Sync>,
+) -> Result<RgbaImage> {
+    let phys_width = (config.width * config.scale_factor).round().max(1.0) as u32;
+    let phys_height = (config.height * config.scale_factor).round().max(1.0) as u32;
+
+    let mut canvas = Canvas::headless(phys_width, phys_height, config.scale_factor);
+    canvas.set_text_provider(provider.clone());
+    canvas.clear(config.background);
+    canvas.push_clip_rect(Rect {
+        x: 0.0,
+        y: 0.0,
+        w: config.width,
+        h: config.height,
+    });
+
+    let mut renderer = IrRenderer::new();
+    renderer.render_canvas_at_offset(
+        &mut canvas,
+        data_doc,
+        view_doc,
+        0.0,
+        0.0,
+        config.width,
+        config.height,
+        config.height,
+        0.0,
+        0.0,
+        provider.as_ref(),
+    )?;
+    canvas.pop_clip();
+
+    Ok(canvas.render_cpu().to_image())
+}
+
+/// Compare two frames pixel by pixel using the YIQ distance from pixelmatch.
+pub fn compare_images(
+    actual: &RgbaImage,
+    expected: &RgbaImage,
+    tolerance: &Tolerance,
+) -> Result<Comparison> {
+    if actual.dimensions() != expected.dimensions() {
+        bail!(
+            "size mismatch: actual {:?}, golden {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        );
+    }
+
+    let threshold = tolerance.pixel_threshold.clamp(0.0, 1.0);
+    let mut diff_image = RgbaImage::new(expected.width(), expected.height());
+    let mut diff_pixels = 0;
+    let mut max_delta: f32 = 0.0;
+
+    for (x, y, exp) in expected.enumerate_pixels() {
+        let act = actual.get_pixel(x, y);
+        let delta = yiq_delta(act.0, exp.0);
+        max_delta = max_delta.max(delta);
+        let out = if delta > threshold {
+            diff_pixels += 1;
+            [255, 0, 0, 255]
+        } else {
+            let [y, _, _] = yiq(blend_white(exp.0));
+            let v = (255.0 - (255.0 - y) * 0.1).round() as u8;
+            [v, v, v, 255]
+        };
+        diff_image.put_pixel(x, y, image::Rgba(out));
+    }
+
+    Ok(Comparison {
+        diff_pixels,
+        total_pixels: (expected.width() as usize) * (expected.height() as usize),
+        max_delta,
+        diff_image,
+    })
+}
+
+/// Compare `actual` against `<golden_dir>/<name>.png`.
+///
+/// In update mode the golden is (re)written and the check passes. On failure
+/// `<name>.actual.png` and `<name>.diff.png` are written to the artifact
+/// directory and the error message points at them.
+pub fn assert_snapshot(
+    name: &str,
+    actual: &RgbaImage,
+    golden_dir: &Path,
+    tolerance: &Tolerance,
+) -> Result<()> {
+    let golden_path = golden_dir.join(format!("{name}.png"));
+
+    if update_requested() {
+        std::fs::create_dir_all(golden_dir)
+            .with_context(|| format!("failed to create {}", golden_dir.display()))?;
+        actual
+            .save(&golden_path)
+            .with_context(|| format!("failed to write {}", golden_path.display()))?;
+        return Ok(());
+    }
+
+    if !golden_path.exists() {
+        bail!(
+            "missing golden {}; run with {UPDATE_ENV}=1 to create it",
+            golden_path.display()
+        );
+    }
+    let expected = image::open(&golden_path)
+        .with_context(|| format!("failed to read {}", golden_path.display()))?
+        .to_rgba8();
+
+    let out_dir = artifact_dir();
+    let comparison = match compare_images(actual, &expected, tolerance) {
+        Ok(c) => c,
+        Err(err) => {
+            let actual_path = write_artifact(&out_dir, &format!("{name}.actual.png"), actual)?;
+            bail!(
+                "snapshot '{name}': {err} (actual: {})",
+                actual_path.display()
+            );
+        }
+    };
+    if comparison.passes(tolerance) {
+        return Ok(());
+    }
+
+    let actual_path = write_artifact(&out_dir, &format!("{name}.actual.png"), actual)?;
+    let diff_path = write_artifact(
+        &out_dir,
+        &format!("{name}.diff.png"),
+        &comparison.diff_image,
+    )?;
+    bail!(
+        "snapshot '{name}' differs from {}: {} of {} pixels changed ({:.3}% > {:.3}%), max delta {:.3}\n  actual: {}\n  diff:   {}",
+        golden_path.display(),
+        comparison.diff_pixels,
+        comparison.total_pixels,
+        comparison.diff_ratio() * 100.0,
+        tolerance.max_diff_ratio * 100.0,
+        comparison.max_delta,
+        actual_path.display(),
+        diff_path.display()
+    )
+}
+
+fn update_requested() -> bool {
+    std::env::var(UPDATE_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true") || v.eq_ignore_ascii_case("yes"))
+        .unwrap_or(false)
+}
+
+fn artifact_dir() -> PathBuf {
+    std::env::var_os(OUT_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("rune-snapshots"))
+}
+
+fn write_artifact(dir: &Path, file: &str, img: &RgbaImage) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let path = dir.join(file);
+    img.save(&path)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Composite a straight-alpha pixel over white, as pixelmatch does.
+fn blend_white(p: [u8; 4]) -> [f32; 3] {
+    let a = p[3] as f32 / 255.0;
+    [
+        255.0 + (p[0] as f32 - 255.0) * a,
+        255.0 + (p[1] as f32 - 255.0) * a,
+        255.0 + (p[2] as f32 - 255.0) * a,
+    ]
+}
+
+fn yiq(c: [f32; 3]) -> [f32; 3] {
+    let [r, g, b] = c;
+    [
+        r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_2,
+        r * 0.595_978 - g * 0.274_176_1 - b * 0.321_801_9,
+        r * 0.211_470_2 - g * 0.522_617_1 + b * 0.311_146_9,
+    ]
+}
+
+/// Normalized perceptual distance in `[0, 1]`.
+fn yiq_delta(a: [u8; 4], b: [u8; 4]) -> f32 {
+    if a == b {
+        return 0.0;
+    }
+    let ya = yiq(blend_white(a));
+    let yb = yiq(blend_white(b));
+    let dy = ya[0] - yb[0];
+    let di = ya[1] - yb[1];
+    let dq = ya[2] - yb[2];
+    let delta = 0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq;
+    // 35215 is the largest possible value of `delta` (black vs. white).
+    (delta / 35215.0).sqrt().min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_images_have_no_diff() {
+        let img = RgbaImage::from_pixel(4, 4, image::Rgba([10, 20, 30, 255]));
+        let cmp = compare_images(&img, &img, &Tolerance::default()).unwrap();
+        assert_eq!(cmp.diff_pixels, 0);
+        assert_eq!(cmp.max_delta, 0.0);
+    }
+
+    #[test]
+    fn small_color_shift_is_within_threshold() {
+        let a = RgbaImage::from_pixel(2, 2, image::Rgba([100, 100, 100, 255]));
+        let b = RgbaImage::from_pixel(2, 2, image::Rgba([102, 101, 100, 255]));
+        let cmp = compare_images(&a, &b, &Tolerance::default()).unwrap();
+        assert_eq!(cmp.diff_pixels, 0);
+    }
+
+    #[test]
+    fn changed_pixels_are_counted_and_marked() {
+        let a = RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 0, 255]));
+        let mut b = a.clone();
+        b.put_pixel(1, 0, image::Rgba([255, 255, 255, 255]));
+        let cmp = compare_images(&a, &b, &Tolerance::default()).unwrap();
+        assert_eq!(cmp.diff_pixels, 1);
+        assert!(cmp.max_delta > 0.9);
+        assert_eq!(cmp.diff_image.get_pixel(1, 0).0, [255, 0, 0, 255]);
+        assert!(!cmp.passes(&Tolerance::default()));
+    }
+
+    #[test]
+    fn size_mismatch_is_an_error() {
+        let a = RgbaImage::new(2, 2);
+        let b = RgbaImage::new(3, 2);
+        assert!(compare_images(&a, &b, &Tolerance::default()).is_err());
+    }
+}
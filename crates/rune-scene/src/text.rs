@@ -31,6 +31,7 @@ pub fn measure_run(
         pos: [0.0, 0.0],
         size: size_px.max(1.0),
         color: ColorLinPremul::from_srgba_u8([255, 255, 255, 255]),
+        spans: Vec::new(),
     };
     let glyphs = provider.rasterize_run(&run);
     if glyphs.is_empty() {
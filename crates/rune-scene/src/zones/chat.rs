@@ -12,7 +12,10 @@
 
 use super::common::ZoneStyle;
 use crate::elements::InputBox;
-use engine_core::{Brush, Color, ColorLinPremul, Rect, RoundedRadii, RoundedRect, SvgStyle};
+use engine_core::{
+    Brush, Color, ColorLinPremul, EllipsisPosition, Rect, RoundedRadii, RoundedRect, SvgStyle,
+    truncate_text_fast,
+};
 
 /// A single chat message
 #[derive(Debug, Clone)]
@@ -269,12 +272,7 @@ impl ChatPanel {
                         w: bubble_width,
                         h: bubble_height,
                     },
-                    radii: RoundedRadii {
-                        tl: 12.0,
-                        tr: 12.0,
-                        br: 4.0,
-                        bl: 12.0,
-                    },
+                    radii: RoundedRadii::new(12.0, 12.0, 4.0, 12.0),
                 };
                 canvas.rounded_rect(rrect, Brush::Solid(bubble_color), z_base + 5);
 
@@ -290,7 +288,12 @@ impl ChatPanel {
                     z_base + 10,
                 );
 
-                let display_text: String = msg.content.chars().take(100).collect();
+                let display_text = truncate_text_fast(
+                    &msg.content,
+                    bubble_width - 24.0,
+                    13.0,
+                    EllipsisPosition::End,
+                );
                 canvas.draw_text_run(
                     [bubble_x + 12.0, y + 22.0],
                     display_text,
@@ -301,7 +304,12 @@ impl ChatPanel {
             } else {
                 // Assistant message: plain text, left-aligned, no bubble
                 let text_color = ColorLinPremul::from_srgba_u8([220, 225, 240, 255]);
-                let display_text: String = msg.content.chars().take(200).collect();
+                let display_text = truncate_text_fast(
+                    &msg.content,
+                    panel_rect.w - PADDING * 2.0,
+                    13.0,
+                    EllipsisPosition::End,
+                );
 
                 canvas.draw_text_run(
                     [PADDING, y + 18.0],
@@ -381,12 +389,7 @@ pub fn render_chat_fab(
             w: FAB_SIZE,
             h: FAB_SIZE,
         },
-        radii: RoundedRadii {
-            tl: FAB_SIZE / 2.0,
-            tr: FAB_SIZE / 2.0,
-            br: FAB_SIZE / 2.0,
-            bl: FAB_SIZE / 2.0,
-        },
+        radii: RoundedRadii::uniform(FAB_SIZE / 2.0),
     };
     canvas.rounded_rect(rrect, Brush::Solid(fab_bg), z_base);
 
@@ -399,12 +402,7 @@ pub fn render_chat_fab(
             w: FAB_SIZE,
             h: FAB_SIZE,
         },
-        radii: RoundedRadii {
-            tl: FAB_SIZE / 2.0,
-            tr: FAB_SIZE / 2.0,
-            br: FAB_SIZE / 2.0,
-            bl: FAB_SIZE / 2.0,
-        },
+        radii: RoundedRadii::uniform(FAB_SIZE / 2.0),
     };
     canvas.rounded_rect(shadow_rrect, Brush::Solid(shadow_color), z_base - 1);
 
@@ -442,12 +440,7 @@ pub fn render_chat_fab(
                 w: 12.0,
                 h: 12.0,
             },
-            radii: RoundedRadii {
-                tl: 6.0,
-                tr: 6.0,
-                br: 6.0,
-                bl: 6.0,
-            },
+            radii: RoundedRadii::uniform(6.0),
         };
         canvas.rounded_rect(dot_rrect, Brush::Solid(dot_color), z_base + 2);
     }
@@ -17,7 +17,10 @@
 
 use super::common::ZoneStyle;
 use crate::persistence::{BookmarkEntry, TabEntry};
-use engine_core::{Brush, Color, ColorLinPremul, Rect, RoundedRadii, RoundedRect, SvgStyle};
+use engine_core::{
+    Brush, Color, ColorLinPremul, EllipsisPosition, Rect, RoundedRadii, RoundedRect, SvgStyle,
+    truncate_text_fast,
+};
 
 /// App entry for the dock (pinned or recent)
 #[derive(Debug, Clone)]
@@ -308,12 +311,7 @@ impl Dock {
         let make_rrect = |x: f32, y: f32, w: f32, h: f32, r: f32| -> RoundedRect {
             RoundedRect {
                 rect: Rect { x, y, w, h },
-                radii: RoundedRadii {
-                    tl: r,
-                    tr: r,
-                    br: r,
-                    bl: r,
-                },
+                radii: RoundedRadii::uniform(r),
             }
         };
 
@@ -436,7 +434,8 @@ impl Dock {
 
             // App name (truncated)
             let name_color = ColorLinPremul::from_srgba_u8([220, 225, 240, alpha]);
-            let display_name: String = app.name.chars().take(10).collect();
+            let display_name =
+                truncate_text_fast(&app.name, APP_ICON_SIZE, 12.0, EllipsisPosition::End);
             canvas.draw_text_run(
                 [x + 4.0, y + APP_ICON_SIZE + 16.0],
                 display_name,
@@ -524,7 +523,8 @@ impl Dock {
 
                 // Item name (truncated)
                 let name_color = ColorLinPremul::from_srgba_u8([180, 185, 200, alpha]);
-                let display_name: String = item.name.chars().take(10).collect();
+                let display_name =
+                    truncate_text_fast(&item.name, APP_ICON_SIZE, 12.0, EllipsisPosition::End);
                 canvas.draw_text_run(
                     [x + 4.0, y + APP_ICON_SIZE + 16.0],
                     display_name,
@@ -105,7 +105,7 @@ fn render_persistent_home_button(
 
     let rrect = RoundedRect {
         rect: Rect { x, y, w: BUTTON_SIZE, h: BUTTON_SIZE },
-        radii: RoundedRadii { tl: CORNER_RADIUS, tr: CORNER_RADIUS, br: CORNER_RADIUS, bl: CORNER_RADIUS },
+        radii: RoundedRadii::uniform(CORNER_RADIUS),
     };
 
     // Background
@@ -1,6 +1,6 @@
 use super::common::ZoneStyle;
 use crate::persistence::{BookmarkEntry, BookmarksStore, TabEntry, TabsStore};
-use engine_core::{ColorLinPremul, Rect};
+use engine_core::{ColorLinPremul, EllipsisPosition, Rect, truncate_text_fast};
 use tracing::warn;
 
 // Layout constants (shared by render + hit testing)
@@ -264,7 +264,12 @@ impl Sidebar {
                 let text_x = PADDING_X + ICON_SIZE + ICON_TEXT_GAP;
                 let max_title_width =
                     sidebar_rect.w - text_x - PADDING_X - CLOSE_BUTTON_SIZE - CLOSE_HIT_PADDING * 2.0;
-                let title = truncate_text(&tab.title, max_title_width, TEXT_SIZE);
+                let title = truncate_text_fast(
+                    &tab.title,
+                    max_title_width,
+                    TEXT_SIZE,
+                    EllipsisPosition::End,
+                );
                 let text_y = row_center_y + TEXT_BASELINE_OFFSET;
                 canvas.draw_text_run(
                     [text_x, text_y],
@@ -373,7 +378,12 @@ impl Sidebar {
                 let text_x = PADDING_X + ICON_SIZE + ICON_TEXT_GAP;
                 let max_title_width =
                     sidebar_rect.w - text_x - PADDING_X - CLOSE_BUTTON_SIZE - CLOSE_HIT_PADDING * 2.0;
-                let title = truncate_text(&bookmark.title, max_title_width, TEXT_SIZE);
+                let title = truncate_text_fast(
+                    &bookmark.title,
+                    max_title_width,
+                    TEXT_SIZE,
+                    EllipsisPosition::End,
+                );
                 let text_y = row_center_y + TEXT_BASELINE_OFFSET;
                 canvas.draw_text_run(
                     [text_x, text_y],
@@ -489,21 +499,6 @@ impl Sidebar {
     }
 }
 
-/// Truncate text to fit within max_width (rough estimate based on char count)
-fn truncate_text(text: &str, max_width: f32, font_size: f32) -> String {
-    // Rough estimate: average char width is ~0.5 * font_size
-    let avg_char_width = font_size * 0.55;
-    let max_chars = (max_width / avg_char_width) as usize;
-
-    if text.len() <= max_chars {
-        text.to_string()
-    } else if max_chars > 3 {
-        format!("{}...", &text[..max_chars - 3])
-    } else {
-        text[..max_chars.min(text.len())].to_string()
-    }
-}
-
 impl Default for Sidebar {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,77 @@
+//! Golden-image regression tests for the bundled IR packages.
+//!
+//! Regenerate goldens with `RUNE_UPDATE_SNAPSHOTS=1 cargo test -p rune-scene --test ir_snapshots`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+use anyhow::Result;
+use rune_ir::package::RunePackage;
+use rune_scene::snapshot::{self, SnapshotConfig, Tolerance};
+
+fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+/// Asset paths in the packages (`images/...`) are relative to the workspace
+/// root, which is where the runner is normally launched from.
+fn enter_workspace_root() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        std::env::set_current_dir(workspace_root()).expect("failed to enter workspace root");
+    });
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots")
+}
+
+fn check_package(name: &str, package: &RunePackage) -> Result<()> {
+    enter_workspace_root();
+    let provider = snapshot::bundled_text_provider()?;
+    let image = snapshot::render_package(package, &SnapshotConfig::default(), provider)?;
+    snapshot::assert_snapshot(name, &image, &golden_dir(), &Tolerance::default())
+}
+
+fn check_example(name: &str) -> Result<()> {
+    let dir = workspace_root().join("examples").join(name);
+    let package = RunePackage::from_directory(Path::new(&dir))?;
+    check_package(name, &package)
+}
+
+#[test]
+fn home_tab() -> Result<()> {
+    check_package("home_tab", &RunePackage::sample()?)
+}
+
+#[test]
+fn sample_first_node() -> Result<()> {
+    check_example("sample_first_node")
+}
+
+#[test]
+fn sample_form() -> Result<()> {
+    check_example("sample_form")
+}
+
+#[test]
+fn sample_package() -> Result<()> {
+    check_example("sample_package")
+}
+
+#[test]
+fn sample_webview() -> Result<()> {
+    check_example("sample_webview")
+}
+
+#[test]
+fn home_tab_hidpi() -> Result<()> {
+    enter_workspace_root();
+    let config = SnapshotConfig {
+        scale_factor: 2.0,
+        ..SnapshotConfig::default()
+    };
+    let provider = snapshot::bundled_text_provider()?;
+    let image = snapshot::render_package(&RunePackage::sample()?, &config, provider)?;
+    snapshot::assert_snapshot("home_tab@2x", &image, &golden_dir(), &Tolerance::default())
+}
@@ -0,0 +1,189 @@
+//! Replay a frame saved by `RuneSurface::capture_next_frame` and write the
+//! rendered result as a PNG.
+//!
+//! Frames render with the CPU rasterizer unless `--gpu` is given, in which
+//! case they go through the same `RuneSurface` pipeline as the app, into an
+//! offscreen texture. Text renders with the fonts embedded in the capture,
+//! or with `--font` in their place.
+
+use anyhow::{Context, Result, bail};
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use engine_core::{RuneTextProvider, SubpixelOrientation, wgpu};
+use rune_surface::{Canvas, FrameCapture, RuneSurface};
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    if args.is_empty() {
+        eprintln!(
+            "Usage: cargo run -p rune-surface --features replay --bin rune_replay <capture> [--out <png>] [--gpu] [--font <file>] [--convert <capture>]"
+        );
+        bail!("missing <capture>");
+    }
+    let input = PathBuf::from(args.remove(0));
+
+    let mut out = input.with_extension("png");
+    let mut gpu = false;
+    let mut font: Option<PathBuf> = None;
+    let mut convert: Option<PathBuf> = None;
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = |name: &str| -> Result<PathBuf> {
+            match args.get(i + 1) {
+                Some(v) => Ok(PathBuf::from(v)),
+                None => bail!("{name} expects a path"),
+            }
+        };
+        match args[i].as_str() {
+            "--out" => {
+                out = value("--out")?;
+                i += 2;
+            }
+            "--font" => {
+                font = Some(value("--font")?);
+                i += 2;
+            }
+            "--convert" => {
+                // Re-encode, e.g. binary to JSON for reading by hand.
+                convert = Some(value("--convert")?);
+                i += 2;
+            }
+            "--gpu" => {
+                gpu = true;
+                i += 1;
+            }
+            other => bail!("unknown argument: {other}"),
+        }
+    }
+
+    let mut capture = FrameCapture::load(&input)?;
+    if let Some(path) = convert {
+        capture.save(&path)?;
+        println!("Wrote {}", path.display());
+    }
+
+    // Draw the embedded copies of the frame's SVGs and images. The
+    // directory is removed when `_assets` drops, on error paths as well.
+    let _assets = if capture.assets().is_empty() {
+        None
+    } else {
+        let dir = tempfile::Builder::new().prefix("rune_replay-").tempdir()?;
+        capture.unpack_assets(dir.path())?;
+        Some(dir)
+    };
+
+    let dpi_scale = capture.dpi_scale();
+    let text = capture.had_text_provider();
+    let orientation = SubpixelOrientation::RGB;
+    let provider = match font {
+        _ if !text => None,
+        Some(path) => {
+            let bytes = std::fs::read(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            Some(RuneTextProvider::from_bytes(&bytes, orientation)?)
+        }
+        None => match capture.text_provider(orientation)? {
+            Some(provider) => Some(provider),
+            None => Some(RuneTextProvider::from_system_fonts(orientation)?),
+        },
+    };
+    let mut canvas = capture.into_canvas();
+    if let Some(provider) = provider {
+        canvas.set_text_provider(Arc::new(provider));
+    }
+
+    let image = if gpu {
+        render_gpu(canvas, dpi_scale)?
+    } else {
+        canvas.render_cpu().to_image()
+    };
+    image
+        .save(&out)
+        .with_context(|| format!("failed to write {}", out.display()))?;
+    println!("Wrote {}", out.display());
+    Ok(())
+}
+
+/// Render through `RuneSurface` into an offscreen texture and read it back.
+fn render_gpu(canvas: Canvas, dpi_scale: f32) -> Result<image::RgbaImage> {
+    let viewport = canvas.viewport();
+    let (width, height) = (viewport.width.max(1), viewport.height.max(1));
+
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        compatible_surface: None,
+        force_fallback_adapter: false,
+    }))
+    .context("no GPU adapter available")?;
+    let (device, queue) =
+        pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))?;
+    let (device, queue) = (Arc::new(device), Arc::new(queue));
+
+    let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+    let mut surf = RuneSurface::new(device.clone(), queue.clone(), format);
+    surf.set_dpi_scale(dpi_scale);
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("replay-target"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    surf.render_to_texture(&target, canvas)?;
+
+    // Rows of a texture-to-buffer copy must be 256-byte aligned.
+    let row = width * 4;
+    let padded_row =
+        row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("replay-readback"),
+        size: (padded_row * height) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("replay-readback-encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        target.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row),
+                rows_per_image: Some(height),
+            },
+        },
+        target.size(),
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let (tx, rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = tx.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    rx.recv()
+        .context("readback was dropped")?
+        .context("failed to map readback buffer")?;
+    let mapped = slice.get_mapped_range();
+    let mut pixels = Vec::with_capacity((row * height) as usize);
+    for y in 0..height as usize {
+        let start = y * padded_row as usize;
+        pixels.extend_from_slice(&mapped[start..start + row as usize]);
+    }
+    drop(mapped);
+    buffer.unmap();
+    image::RgbaImage::from_raw(width, height, pixels).context("readback size mismatch")
+}
@@ -1,12 +1,18 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
 use engine_core::{
-    Brush, ColorLinPremul, Painter, Path, RasterizedGlyph, Rect, RoundedRadii, RoundedRect, Stroke,
-    TextProvider, TextRun, Transform2D, Viewport,
+    BackdropFilter, BlendMode, Border, Brush, ClipTable, ColorLinPremul, DamageItem, Filter,
+    GlyphDraw, LayerMask, MaskFormat, Painter, Path, RasterizedGlyph, Rect, RoundedRect, Stroke,
+    SvgDraw, TextProvider, TextRun, Transform2D, Viewport, content_hash,
 };
 
+use crate::surface::apply_transform_to_point;
+
 /// How an image should fit within its bounds.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ImageFitMode {
     /// Stretch to fill (may distort aspect ratio)
     Fill,
@@ -22,45 +28,60 @@ impl Default for ImageFitMode {
     }
 }
 
+/// An image drawn on a canvas, before it's fitted into its bounds:
+/// `(path, origin, size, fit, z, transform, clip)`.
+pub(crate) type CanvasImageDraw = (
+    std::path::PathBuf,
+    [f32; 2],
+    [f32; 2],
+    ImageFitMode,
+    i32,
+    Transform2D,
+    u32,
+);
+
 /// Builder for a single frame’s draw commands. Wraps `Painter` and adds canvas helpers.
 pub struct Canvas {
     pub(crate) viewport: Viewport,
     pub(crate) painter: Painter,
     pub(crate) clear_color: Option<ColorLinPremul>,
     pub(crate) text_provider: Option<Arc<dyn TextProvider + Send + Sync>>, // optional high-level text shaper
-    pub(crate) glyph_draws: Vec<([f32; 2], RasterizedGlyph, ColorLinPremul, i32)>, // low-level glyph masks with z-index
-    pub(crate) svg_draws: Vec<(
-        std::path::PathBuf,
-        [f32; 2],
-        [f32; 2],
-        Option<engine_core::SvgStyle>,
-        i32,
-        Transform2D,
-    )>, // (path, origin, max_size, style, z, transform)
-    pub(crate) image_draws: Vec<(
-        std::path::PathBuf,
-        [f32; 2],
-        [f32; 2],
-        ImageFitMode,
-        i32,
-        Transform2D,
-    )>, // (path, origin, size, fit, z, transform)
+    pub(crate) glyph_draws: Vec<GlyphDraw>, // low-level glyph masks with z-index and clip
+    pub(crate) svg_draws: Vec<SvgDraw>,
+    pub(crate) image_draws: Vec<CanvasImageDraw>,
     /// Raw pixel data draws: (pixels_rgba, src_width, src_height, origin, dst_size, z, transform)
     pub(crate) raw_image_draws: Vec<RawImageDraw>,
     pub(crate) dpi_scale: f32, // DPI scale factor for text rendering
     // Effective clip stack in device coordinates for direct text rendering.
     // Each entry is the intersection of all active clips at that depth.
     pub(crate) clip_stack: Vec<Option<Rect>>,
+    // Clip stacks the side draws above were made in, by the id they record,
+    // and the id of the current one.
+    pub(crate) clips: ClipTable,
+    clip: u32,
     // Overlay rectangles that render without depth testing (for modal scrims).
     // These are rendered in a separate pass after the main scene.
     pub(crate) overlay_draws: Vec<(Rect, ColorLinPremul)>,
     // Scrim draws that blend over content but allow z-ordered content to render on top.
     // Supports either a full-rect scrim or a scrim with a rounded-rect cutout via stencil.
     pub(crate) scrim_draws: Vec<ScrimDraw>,
+    // Side draws (glyphs, SVGs, images) of closed layers, in `push_layer`
+    // order. While a layer is open the fields above collect its draws and
+    // the enclosing ones wait on `layer_stack` with the layer's index.
+    pub(crate) layer_draws: Vec<LayerSideDraws>,
+    layer_stack: Vec<(usize, LayerSideDraws)>,
+}
+
+/// Glyph, SVG and image draws made inside one layer.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct LayerSideDraws {
+    pub(crate) glyph_draws: Vec<GlyphDraw>,
+    pub(crate) svg_draws: Vec<SvgDraw>,
+    pub(crate) image_draws: Vec<CanvasImageDraw>,
 }
 
 /// Scrim drawing modes.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum ScrimDraw {
     Rect(Rect, ColorLinPremul),
     Cutout {
@@ -91,6 +112,91 @@ pub struct RawImageDraw {
 }
 
 impl Canvas {
+    pub(crate) fn new(viewport: Viewport, dpi_scale: f32) -> Self {
+        Self {
+            viewport,
+            painter: Painter::begin_frame(viewport),
+            clear_color: None,
+            text_provider: None,
+            glyph_draws: Vec::new(),
+            svg_draws: Vec::new(),
+            image_draws: Vec::new(),
+            raw_image_draws: Vec::new(),
+            dpi_scale,
+            clip_stack: vec![None],
+            clips: ClipTable::default(),
+            clip: 0,
+            overlay_draws: Vec::new(),
+            scrim_draws: Vec::new(),
+            layer_draws: Vec::new(),
+            layer_stack: Vec::new(),
+        }
+    }
+
+    /// Stable ids and bounds of the glyph, SVG and image draws, which bypass
+    /// the display list, for partial redraws.
+    pub(crate) fn side_damage_items(&self) -> Vec<DamageItem> {
+        let padded = |origin: [f32; 2], size: [f32; 2]| Rect {
+            x: origin[0] - 1.0,
+            y: origin[1] - 1.0,
+            w: size[0] + 2.0,
+            h: size[1] + 2.0,
+        };
+        let mut items = Vec::new();
+        for (origin, glyph, color, z, clip) in &self.glyph_draws {
+            // Glyphs from the run cache carry a key for their pixels; only
+            // pre-rasterized ones without it are hashed byte by byte.
+            let pixels = if glyph.key != 0 {
+                glyph.key
+            } else {
+                let mut mask = DefaultHasher::new();
+                glyph.mask.data.hash(&mut mask);
+                mask.finish()
+            };
+            let (w, h) = (glyph.mask.width, glyph.mask.height);
+            let clip = self.clips.get(*clip);
+            items.push(DamageItem::new(
+                content_hash(pixels, &(origin.map(f32::to_bits), color, z, w, h, clip)),
+                padded(*origin, [w as f32, h as f32]),
+            ));
+        }
+        for draw in &self.svg_draws {
+            let (path, origin, max_size, style, z, transform, clip) = draw;
+            let id = content_hash(
+                1,
+                &(
+                    path,
+                    origin.map(f32::to_bits),
+                    max_size.map(f32::to_bits),
+                    style,
+                    z,
+                    transform,
+                    self.clips.get(*clip),
+                ),
+            );
+            let origin = apply_transform_to_point(*origin, *transform);
+            items.push(DamageItem::new(id, padded(origin, *max_size)));
+        }
+        for draw in &self.image_draws {
+            let (path, origin, size, fit, z, transform, clip) = draw;
+            let id = content_hash(
+                2,
+                &(
+                    path,
+                    origin.map(f32::to_bits),
+                    size.map(f32::to_bits),
+                    fit,
+                    z,
+                    transform,
+                    self.clips.get(*clip),
+                ),
+            );
+            let origin = apply_transform_to_point(*origin, *transform);
+            items.push(DamageItem::new(id, padded(origin, *size)));
+        }
+        items
+    }
+
     pub fn viewport(&self) -> Viewport {
         self.viewport
     }
@@ -228,12 +334,7 @@ impl Canvas {
                 w: (max_x - min_x).max(0.0),
                 h: (max_y - min_y).max(0.0),
             },
-            radii: RoundedRadii {
-                tl: hole.radii.tl * scale,
-                tr: hole.radii.tr * scale,
-                br: hole.radii.br * scale,
-                bl: hole.radii.bl * scale,
-            },
+            radii: hole.radii.scale(scale),
         };
 
         self.scrim_draws.push(ScrimDraw::Cutout {
@@ -242,14 +343,28 @@ impl Canvas {
         });
     }
 
-    /// Stroke a path with uniform width and solid color.
-    pub fn stroke_path(&mut self, path: Path, width: f32, color: ColorLinPremul, z: i32) {
-        self.painter.stroke_path(path, Stroke { width }, color, z);
+    /// Stroke a path with uniform width and any brush, using round joins
+    /// and caps.
+    pub fn stroke_path(&mut self, path: Path, width: f32, brush: Brush, z: i32) {
+        self.painter
+            .stroke_path(path, Stroke::round(width), brush, z);
+    }
+
+    /// Stroke a path with a full stroke style: dashes, caps, joins and miter
+    /// limit.
+    pub fn stroke_path_with(&mut self, path: Path, stroke: Stroke, brush: Brush, z: i32) {
+        self.painter.stroke_path(path, stroke, brush, z);
     }
 
-    /// Fill a path with a solid color.
-    pub fn fill_path(&mut self, path: Path, color: ColorLinPremul, z: i32) {
-        self.painter.fill_path(path, color, z);
+    /// Stroke the inside of a rectangle.
+    pub fn stroke_rect(&mut self, rect: Rect, stroke: Stroke, brush: Brush, z: i32) {
+        self.painter.stroke_rect(rect, stroke, brush, z);
+    }
+
+    /// Fill a path with any brush. Gradient and pattern coordinates are in
+    /// the path's coordinate space.
+    pub fn fill_path(&mut self, path: Path, brush: Brush, z: i32) {
+        self.painter.fill_path(path, brush, z);
     }
 
     /// Draw an ellipse (y-down coordinates).
@@ -270,7 +385,24 @@ impl Canvas {
     /// Stroke a rounded rectangle.
     pub fn stroke_rounded_rect(&mut self, rrect: RoundedRect, width: f32, brush: Brush, z: i32) {
         self.painter
-            .stroke_rounded_rect(rrect, Stroke { width }, brush, z);
+            .stroke_rounded_rect(rrect, Stroke::round(width), brush, z);
+    }
+
+    /// Draw a border inside `rrect` with per-side widths and colors.
+    pub fn border(&mut self, rrect: RoundedRect, border: Border, z: i32) {
+        self.painter.border(rrect, border, z);
+    }
+
+    /// Stroke a rounded rectangle with a full stroke style, e.g. a dashed
+    /// focus ring.
+    pub fn stroke_rounded_rect_with(
+        &mut self,
+        rrect: RoundedRect,
+        stroke: Stroke,
+        brush: Brush,
+        z: i32,
+    ) {
+        self.painter.stroke_rounded_rect(rrect, stroke, brush, z);
     }
 
     /// Draw text using direct rasterization (recommended).
@@ -312,6 +444,39 @@ impl Canvas {
         color: ColorLinPremul,
         z: i32,
     ) {
+        self.draw_attributed_text(TextRun::new(text, origin, size_px, color), z);
+    }
+
+    /// Draw a text run whose spans carry their own font family, weight, style,
+    /// size, color, underline/strikethrough and background.
+    ///
+    /// `run.pos` is the baseline origin. All spans are shaped into one line;
+    /// unstyled bytes use `run.size` and `run.color`.
+    ///
+    /// A current transform that turns by quarter turns, as vertical text
+    /// lines do, turns the glyphs with it; other rotations and scales only
+    /// move the origin.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use rune_surface::Canvas;
+    /// # use engine_core::{ColorLinPremul, FontWeight, TextAttrs, TextRun};
+    /// # let mut canvas: Canvas = todo!();
+    /// let white = ColorLinPremul::rgba(255, 255, 255, 255);
+    /// let run = TextRun::new("Hello, bold world", [10.0, 20.0], 16.0, white).with_span(
+    ///     7..11,
+    ///     TextAttrs {
+    ///         weight: FontWeight::BOLD,
+    ///         underline: true,
+    ///         ..Default::default()
+    ///     },
+    /// );
+    /// canvas.draw_attributed_text(run, 10);
+    /// ```
+    pub fn draw_attributed_text(&mut self, run: TextRun, z: i32) {
+        let origin = run.pos;
+        let size_px = run.size;
+        let color = run.color;
         // If we have a provider, rasterize immediately (simple, reliable)
         if let Some(ref provider) = self.text_provider {
             // Apply current transform to origin (handles zone positioning)
@@ -331,15 +496,21 @@ impl Canvas {
 
             // Rasterize at logical size - PassManager handles DPI scaling when rendering
             let run = TextRun {
-                text,
                 pos: [0.0, 0.0],
-                size: size_px,
-                color,
+                ..run
             };
 
             // Rasterize glyphs, using a shared cache to avoid
             // re-rasterizing identical text every frame.
-            let glyphs = engine_core::rasterize_run_cached(provider.as_ref(), &run);
+            let cached = engine_core::rasterize_run_cached(provider.as_ref(), &run);
+            let rotated: Vec<RasterizedGlyph>;
+            let glyphs: &[RasterizedGlyph] = match quarter_turns(&transform) {
+                Some(turns) => {
+                    rotated = cached.iter().map(|g| rotate_glyph(g, turns)).collect();
+                    &rotated
+                }
+                None => &cached,
+            };
             // Current effective clip rect in device coordinates, if any.
             let current_clip = self.clip_stack.last().cloned().unwrap_or(None);
 
@@ -368,9 +539,23 @@ impl Canvas {
                     if let Some((clipped_mask, clipped_origin_device)) =
                         clip_glyph_to_rect(&g.mask, glyph_origin_device, clip)
                     {
+                        let crop = [
+                            clipped_origin_device[0] - glyph_origin_device[0],
+                            clipped_origin_device[1] - glyph_origin_device[1],
+                        ];
+                        let key = derived_glyph_key(
+                            g.key,
+                            &(
+                                crop.map(f32::to_bits),
+                                clipped_mask.width,
+                                clipped_mask.height,
+                            ),
+                        );
                         let clipped = RasterizedGlyph {
                             offset: [0.0, 0.0],
+                            color: g.color,
                             mask: clipped_mask,
+                            key,
                         };
                         // Convert clipped origin back to logical coordinates
                         let mut clipped_origin_logical =
@@ -379,25 +564,27 @@ impl Canvas {
                             clipped_origin_logical[0] = snap(clipped_origin_logical[0]);
                             clipped_origin_logical[1] = snap(clipped_origin_logical[1]);
                         }
-                        self.glyph_draws
-                            .push((clipped_origin_logical, clipped, color, z));
+                        self.glyph_draws.push((
+                            clipped_origin_logical,
+                            clipped,
+                            g.color.unwrap_or(color),
+                            z,
+                            self.clip,
+                        ));
                     }
                 } else {
-                    self.glyph_draws
-                        .push((glyph_origin_logical, g.clone(), color, z));
+                    self.glyph_draws.push((
+                        glyph_origin_logical,
+                        g.clone(),
+                        g.color.unwrap_or(color),
+                        z,
+                        self.clip,
+                    ));
                 }
             }
         } else {
             // Fallback: use display list path (complex, but kept for compatibility)
-            self.painter.text(
-                TextRun {
-                    text,
-                    pos: origin,
-                    size: size_px,
-                    color,
-                },
-                z,
-            );
+            self.painter.text(run, z);
         }
     }
 
@@ -431,12 +618,7 @@ impl Canvas {
         };
 
         // Rasterize at logical size - PassManager handles DPI scaling when rendering
-        let run = TextRun {
-            text: text.to_string(),
-            pos: [0.0, 0.0],
-            size: size_px,
-            color,
-        };
+        let run = TextRun::new(text, [0.0, 0.0], size_px, color);
 
         // Rasterize glyphs, using the shared cache to avoid
         // re-rasterizing identical text every frame.
@@ -464,9 +646,23 @@ impl Canvas {
                 if let Some((clipped_mask, clipped_origin_device)) =
                     clip_glyph_to_rect(&g.mask, glyph_origin_device, clip)
                 {
+                    let crop = [
+                        clipped_origin_device[0] - glyph_origin_device[0],
+                        clipped_origin_device[1] - glyph_origin_device[1],
+                    ];
+                    let key = derived_glyph_key(
+                        g.key,
+                        &(
+                            crop.map(f32::to_bits),
+                            clipped_mask.width,
+                            clipped_mask.height,
+                        ),
+                    );
                     let clipped = RasterizedGlyph {
                         offset: [0.0, 0.0],
+                        color: g.color,
                         mask: clipped_mask,
+                        key,
                     };
                     // Convert clipped origin back to logical coordinates
                     let mut clipped_origin_logical =
@@ -475,12 +671,22 @@ impl Canvas {
                         clipped_origin_logical[0] = snap(clipped_origin_logical[0]);
                         clipped_origin_logical[1] = snap(clipped_origin_logical[1]);
                     }
-                    self.glyph_draws
-                        .push((clipped_origin_logical, clipped, color, z));
+                    self.glyph_draws.push((
+                        clipped_origin_logical,
+                        clipped,
+                        g.color.unwrap_or(color),
+                        z,
+                        self.clip,
+                    ));
                 }
             } else {
-                self.glyph_draws
-                    .push((glyph_origin_logical, g.clone(), color, z));
+                self.glyph_draws.push((
+                    glyph_origin_logical,
+                    g.clone(),
+                    g.color.unwrap_or(color),
+                    z,
+                    self.clip,
+                ));
             }
         }
     }
@@ -490,6 +696,12 @@ impl Canvas {
         self.text_provider = Some(provider);
     }
 
+    /// Text provider set for this frame, if any, e.g. to lay out
+    /// paragraphs before drawing their lines.
+    pub fn text_provider(&self) -> Option<&Arc<dyn TextProvider + Send + Sync>> {
+        self.text_provider.as_ref()
+    }
+
     /// Draw pre-rasterized glyph masks at the given origin tinted with the color.
     pub fn draw_text_glyphs(
         &mut self,
@@ -499,7 +711,8 @@ impl Canvas {
         z: i32,
     ) {
         for g in glyphs.iter().cloned() {
-            self.glyph_draws.push((origin, g, color, z));
+            let color = g.color.unwrap_or(color);
+            self.glyph_draws.push((origin, g, color, z, self.clip));
         }
     }
 
@@ -537,7 +750,7 @@ impl Canvas {
     ) {
         let transform = self.painter.current_transform();
         self.svg_draws
-            .push((path.into(), origin, max_size, None, z, transform));
+            .push((path.into(), origin, max_size, None, z, transform, self.clip));
     }
 
     /// Queue an SVG with style overrides to be rasterized and drawn.
@@ -551,8 +764,15 @@ impl Canvas {
     ) {
         let path_buf = path.into();
         let transform = self.painter.current_transform();
-        self.svg_draws
-            .push((path_buf, origin, max_size, Some(style), z, transform));
+        self.svg_draws.push((
+            path_buf,
+            origin,
+            max_size,
+            Some(style),
+            z,
+            transform,
+            self.clip,
+        ));
     }
 
     /// Queue a raster image (PNG/JPEG/GIF/WebP) to be drawn at origin with the given size.
@@ -568,7 +788,7 @@ impl Canvas {
     ) {
         let transform = self.painter.current_transform();
         self.image_draws
-            .push((path.into(), origin, size, fit, z, transform));
+            .push((path.into(), origin, size, fit, z, transform, self.clip));
     }
 
     /// Queue raw pixel data to be drawn at origin with the given size.
@@ -626,7 +846,30 @@ impl Canvas {
     pub fn push_clip_rect(&mut self, rect: Rect) {
         // Forward to Painter to keep display list behavior.
         self.painter.push_clip_rect(rect);
+        self.push_device_clip(rect);
+    }
+
+    /// Clip to a rounded rectangle. Everything drawn until the matching
+    /// `pop_clip`, text, SVGs and images included, follows the corners;
+    /// directly rasterized glyphs are also cropped to the rectangle's bounds.
+    pub fn push_clip_rounded_rect(&mut self, rrect: RoundedRect) {
+        self.painter.push_clip_rounded_rect(rrect);
+        self.push_device_clip(rrect.rect);
+    }
+
+    /// Clip to the inside of `path` according to its fill rule, like
+    /// [`Canvas::push_clip_rounded_rect`]. Directly rasterized glyphs are
+    /// also cropped to the path's bounds.
+    pub fn push_clip_path(&mut self, path: Path) {
+        let bounds = path.bounds().unwrap_or_default();
+        self.painter.push_clip_path(path);
+        self.push_device_clip(bounds);
+    }
 
+    /// Intersect the device-space clip used for direct glyph draws with
+    /// `rect`, given in local coordinates, and record the clip stack the
+    /// painter now has for side draws.
+    fn push_device_clip(&mut self, rect: Rect) {
         // Compute device-space clip rect based on current transform and dpi.
         let t = self.painter.current_transform();
         let [a, b, c, d, e, f] = t.m;
@@ -658,6 +901,7 @@ impl Canvas {
             Some(prev) => intersect_rect(prev, new_clip),
         };
         self.clip_stack.push(merged);
+        self.clip = self.clips.intern(self.painter.clips());
     }
 
     pub fn pop_clip(&mut self) {
@@ -665,6 +909,38 @@ impl Canvas {
         if self.clip_stack.len() > 1 {
             self.clip_stack.pop();
         }
+        self.clip = self.clips.intern(self.painter.clips());
+    }
+
+    /// Intern the clip stacks of the side draws into `clips`, e.g. the
+    /// table of the uploaded display list, and point the draws at them.
+    pub(crate) fn move_clips_into(&mut self, clips: &mut ClipTable) {
+        let ids: Vec<u32> = self.clips.iter().map(|stack| clips.intern(stack)).collect();
+        let remap = |clip: &mut u32| *clip = ids.get(*clip as usize).copied().unwrap_or(0);
+        let layers = &mut self.layer_draws;
+        for draw in self
+            .glyph_draws
+            .iter_mut()
+            .chain(layers.iter_mut().flat_map(|d| &mut d.glyph_draws))
+        {
+            remap(&mut draw.4);
+        }
+        for draw in self
+            .svg_draws
+            .iter_mut()
+            .chain(layers.iter_mut().flat_map(|d| &mut d.svg_draws))
+        {
+            remap(&mut draw.6);
+        }
+        for draw in self
+            .image_draws
+            .iter_mut()
+            .chain(layers.iter_mut().flat_map(|d| &mut d.image_draws))
+        {
+            remap(&mut draw.6);
+        }
+        remap(&mut self.clip);
+        self.clips = clips.clone();
     }
     pub fn push_transform(&mut self, t: Transform2D) {
         self.painter.push_transform(t);
@@ -673,6 +949,71 @@ impl Canvas {
         self.painter.pop_transform();
     }
 
+    /// Start a group that is rendered offscreen and composited as one with
+    /// `opacity`, `blend_mode` and an optional alpha mask, e.g. to fade a
+    /// whole subtree. Text, SVGs and images drawn until the matching
+    /// `pop_layer` belong to the layer; raw image draws and overlays don't.
+    pub fn push_layer(&mut self, opacity: f32, blend_mode: BlendMode, mask: Option<LayerMask>) {
+        self.push_layer_with_filters(opacity, blend_mode, mask, Vec::new(), None);
+    }
+
+    /// Start a layer whose content, side draws included, is run through
+    /// `filters` before it is composited.
+    pub fn push_filter_layer(&mut self, filters: Vec<Filter>) {
+        self.push_layer_with_filters(1.0, BlendMode::Normal, None, filters, None);
+    }
+
+    /// Start a layer drawn over a filtered copy of what is behind it inside
+    /// `rrect`, e.g. a frosted-glass panel. The backdrop is sampled at `z`.
+    pub fn push_backdrop_layer(&mut self, rrect: RoundedRect, filters: Vec<Filter>, z: i32) {
+        let backdrop = BackdropFilter::new(rrect, filters, z);
+        self.push_layer_with_filters(1.0, BlendMode::Normal, None, Vec::new(), Some(backdrop));
+    }
+
+    /// [`Canvas::push_layer`] with content `filters` and an optional
+    /// backdrop filter.
+    pub fn push_layer_with_filters(
+        &mut self,
+        opacity: f32,
+        blend_mode: BlendMode,
+        mask: Option<LayerMask>,
+        filters: Vec<Filter>,
+        backdrop: Option<BackdropFilter>,
+    ) {
+        self.painter
+            .push_layer_with_filters(opacity, blend_mode, mask, filters, backdrop);
+        let index = self.layer_draws.len();
+        self.layer_draws.push(LayerSideDraws::default());
+        let outer = self.take_side_draws();
+        self.layer_stack.push((index, outer));
+    }
+
+    pub fn pop_layer(&mut self) {
+        let Some((index, outer)) = self.layer_stack.pop() else {
+            return;
+        };
+        self.painter.pop_layer();
+        self.layer_draws[index] = self.take_side_draws();
+        self.glyph_draws = outer.glyph_draws;
+        self.svg_draws = outer.svg_draws;
+        self.image_draws = outer.image_draws;
+    }
+
+    /// Close layers left open, as the display list does at its end.
+    pub(crate) fn close_layers(&mut self) {
+        while !self.layer_stack.is_empty() {
+            self.pop_layer();
+        }
+    }
+
+    fn take_side_draws(&mut self) -> LayerSideDraws {
+        LayerSideDraws {
+            glyph_draws: std::mem::take(&mut self.glyph_draws),
+            svg_draws: std::mem::take(&mut self.svg_draws),
+            image_draws: std::mem::take(&mut self.image_draws),
+        }
+    }
+
     /// Add a hit-only region (invisible, used for interaction detection)
     pub fn hit_region_rect(&mut self, id: u32, rect: Rect, z: i32) {
         self.painter.hit_region_rect(id, rect, z);
@@ -708,6 +1049,96 @@ fn intersect_rect(a: Rect, b: Rect) -> Option<Rect> {
     }
 }
 
+/// Number of clockwise quarter turns (1 to 3) made by a transform that
+/// only rotates by a multiple of 90 degrees, or `None` for any other
+/// transform, including the identity.
+fn quarter_turns(transform: &Transform2D) -> Option<u8> {
+    let [a, b, c, d, _, _] = transform.m;
+    let is = |v: f32, want: f32| (v - want).abs() < 1e-4;
+    if is(a, 0.0) && is(b, 1.0) && is(c, -1.0) && is(d, 0.0) {
+        Some(1)
+    } else if is(a, -1.0) && is(b, 0.0) && is(c, 0.0) && is(d, -1.0) {
+        Some(2)
+    } else if is(a, 0.0) && is(b, -1.0) && is(c, 1.0) && is(d, 0.0) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+/// Turn a glyph `turns` quarter turns clockwise about its origin.
+///
+/// Subpixel coverage no longer lines up with the display's stripes once
+/// turned, so coverage masks become grayscale.
+fn rotate_glyph(glyph: &RasterizedGlyph, turns: u8) -> RasterizedGlyph {
+    let mask = &glyph.mask;
+    let (w, h) = (mask.width, mask.height);
+    let bpp = mask.bytes_per_pixel();
+    let (new_w, new_h) = if turns % 2 == 1 { (h, w) } else { (w, h) };
+    let mut data = vec![0u8; mask.data.len()];
+    for y in 0..h {
+        for x in 0..w {
+            let (nx, ny) = match turns {
+                1 => (h - 1 - y, x),
+                2 => (w - 1 - x, h - 1 - y),
+                _ => (y, w - 1 - x),
+            };
+            let src = (y * w + x) as usize * bpp;
+            let dst = (ny * new_w + nx) as usize * bpp;
+            data[dst..dst + bpp].copy_from_slice(&mask.data[src..src + bpp]);
+        }
+    }
+    match mask.format {
+        MaskFormat::Rgba8 => {
+            for px in data.chunks_exact_mut(4) {
+                let avg = ((px[0] as u16 + px[1] as u16 + px[2] as u16) / 3) as u8;
+                px[..3].fill(avg);
+            }
+        }
+        MaskFormat::Rgba16 => {
+            for px in data.chunks_exact_mut(8) {
+                let sum: u32 = (0..3)
+                    .map(|i| u16::from_le_bytes([px[2 * i], px[2 * i + 1]]) as u32)
+                    .sum();
+                let avg = ((sum / 3) as u16).to_le_bytes();
+                for i in 0..3 {
+                    px[2 * i..2 * i + 2].copy_from_slice(&avg);
+                }
+            }
+        }
+        MaskFormat::ColorRgba8 => {}
+    }
+
+    let [ox, oy] = glyph.offset;
+    let (w, h) = (w as f32, h as f32);
+    let offset = match turns {
+        1 => [-(oy + h), ox],
+        2 => [-(ox + w), -(oy + h)],
+        _ => [oy, -(ox + w)],
+    };
+    RasterizedGlyph {
+        offset,
+        color: glyph.color,
+        mask: engine_core::SubpixelMask {
+            width: new_w,
+            height: new_h,
+            format: mask.format,
+            data,
+        },
+        key: derived_glyph_key(glyph.key, &turns),
+    }
+}
+
+/// Key of a glyph made from the one keyed `key`, e.g. by clipping or
+/// turning it; `0` when `key` is unknown.
+fn derived_glyph_key(key: u64, change: &impl Hash) -> u64 {
+    if key == 0 {
+        0
+    } else {
+        content_hash(key, change)
+    }
+}
+
 /// Clip a glyph mask to a device-space rectangle, returning a new mask and origin.
 fn clip_glyph_to_rect(
     mask: &engine_core::SubpixelMask,
@@ -770,3 +1201,59 @@ fn clip_glyph_to_rect(
     let new_origin = [glyph_x0 + start_x as f32, glyph_y0 + start_y as f32];
     Some((clipped, new_origin))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarter_turn_moves_glyph_pixels_clockwise() {
+        // A 2x1 mask: left pixel lit, at offset (1, -3) from the origin.
+        let glyph = RasterizedGlyph {
+            offset: [1.0, -3.0],
+            color: None,
+            mask: engine_core::SubpixelMask {
+                width: 2,
+                height: 1,
+                format: MaskFormat::Rgba8,
+                data: vec![255, 0, 0, 255, 0, 0, 0, 0],
+            },
+            key: 0,
+        };
+        let turn = Transform2D::rotate(std::f32::consts::FRAC_PI_2);
+        let rotated = rotate_glyph(&glyph, quarter_turns(&turn).unwrap());
+
+        assert_eq!((rotated.mask.width, rotated.mask.height), (1, 2));
+        assert_eq!(rotated.offset, [2.0, 1.0]);
+        // The lit pixel ends up on top, with its coverage made gray.
+        assert_eq!(rotated.mask.data, vec![85, 85, 85, 255, 0, 0, 0, 0]);
+        assert_eq!(quarter_turns(&Transform2D::identity()), None);
+    }
+    #[test]
+    fn keyed_glyphs_damage_by_key_not_pixels() {
+        let glyph = |key, byte| RasterizedGlyph {
+            offset: [0.0, 0.0],
+            color: None,
+            mask: engine_core::SubpixelMask {
+                width: 1,
+                height: 1,
+                format: MaskFormat::Rgba8,
+                data: vec![byte; 4],
+            },
+            key,
+        };
+        let damage_id = |g: RasterizedGlyph| {
+            let mut canvas = Canvas::headless(16, 16, 1.0);
+            let color = ColorLinPremul::from_srgba_u8([0, 0, 0, 255]);
+            canvas.draw_text_glyphs([4.0, 4.0], &[g], color, 1);
+            canvas.side_damage_items()[0].id
+        };
+
+        // A key stands in for the pixels, which aren't read.
+        assert_eq!(damage_id(glyph(7, 0)), damage_id(glyph(7, 255)));
+        assert_ne!(damage_id(glyph(7, 0)), damage_id(glyph(8, 0)));
+        // Unkeyed glyphs still damage when their pixels change.
+        assert_ne!(damage_id(glyph(0, 0)), damage_id(glyph(0, 255)));
+        assert_eq!(derived_glyph_key(0, &1u8), 0);
+    }
+}
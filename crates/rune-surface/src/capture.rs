@@ -0,0 +1,384 @@
+//! Frame captures: everything a [`Canvas`] was given in one frame, in a file
+//! that can be attached to a bug report and replayed with the `rune_replay`
+//! binary (built with the `replay` feature), on the GPU or headlessly.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use engine_core::{
+    Brush, CaptureFormat, ClipTable, ColorLinPremul, Command, DisplayList, Painter, Rect,
+    RuneTextProvider, SubpixelOrientation, TextRun, Viewport,
+};
+
+use crate::canvas::{Canvas, LayerSideDraws, ScrimDraw};
+
+/// One frame of a [`Canvas`]: its display list plus the glyph, SVG, image,
+/// scrim and overlay draws made beside it.
+///
+/// The SVG and image files the frame draws are embedded, keyed by the path
+/// they were drawn from. Text runs in the display list are captured as
+/// text, along with the font faces the canvas's text provider rendered them
+/// with; glyphs the canvas rasterized itself are captured as masks. Raw
+/// image draws (WebView pixels) are not captured.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FrameCapture {
+    dpi_scale: f32,
+    clear_color: Option<ColorLinPremul>,
+    text_provider: bool,
+    list: DisplayList,
+    root: LayerSideDraws,
+    layers: Vec<LayerSideDraws>,
+    /// Clip stacks the side draws refer to.
+    clips: ClipTable,
+    scrim_draws: Vec<ScrimDraw>,
+    overlay_draws: Vec<(Rect, ColorLinPremul)>,
+    /// File contents of the SVGs, images and image brushes, by path.
+    assets: BTreeMap<PathBuf, Vec<u8>>,
+    fonts: Vec<CapturedFont>,
+}
+
+/// A font face the captured text was rendered with.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CapturedFont {
+    pub data: Vec<u8>,
+    /// Index of the face in `data`, for font collections.
+    pub index: u32,
+}
+
+impl FrameCapture {
+    /// Snapshot the frame drawn on `canvas` so far, closing any open layers.
+    pub fn from_canvas(canvas: &mut Canvas) -> Self {
+        canvas.close_layers();
+        let mut capture = Self {
+            dpi_scale: canvas.dpi_scale,
+            clear_color: canvas.clear_color,
+            text_provider: canvas.text_provider.is_some(),
+            list: canvas.painter.display_list().clone(),
+            root: LayerSideDraws {
+                glyph_draws: canvas.glyph_draws.clone(),
+                svg_draws: canvas.svg_draws.clone(),
+                image_draws: canvas.image_draws.clone(),
+            },
+            layers: canvas.layer_draws.clone(),
+            clips: canvas.clips.clone(),
+            scrim_draws: canvas.scrim_draws.clone(),
+            overlay_draws: canvas.overlay_draws.clone(),
+            assets: BTreeMap::new(),
+            fonts: Vec::new(),
+        };
+
+        let mut paths = Vec::new();
+        capture.for_each_asset_path(|path| paths.push(path.clone()));
+        for path in paths {
+            if capture.assets.contains_key(&path) {
+                continue;
+            }
+            // Missing files render as nothing, on replay as they did here.
+            if let Ok(bytes) = std::fs::read(&path) {
+                capture.assets.insert(path, bytes);
+            }
+        }
+
+        if let Some(provider) = &canvas.text_provider {
+            for run in capture.text_runs() {
+                for face in provider.run_faces(&run) {
+                    let data = face.as_bytes();
+                    let seen = capture
+                        .fonts
+                        .iter()
+                        .any(|f| f.index == face.index() && *f.data == *data);
+                    if !seen {
+                        capture.fonts.push(CapturedFont {
+                            data: data.to_vec(),
+                            index: face.index(),
+                        });
+                    }
+                }
+            }
+        }
+        capture
+    }
+
+    /// Text runs of the display list, hyperlinks included.
+    fn text_runs(&self) -> Vec<TextRun> {
+        self.list
+            .commands
+            .iter()
+            .filter_map(|cmd| match cmd {
+                Command::DrawText { run, .. } => Some(run.clone()),
+                Command::DrawHyperlink { hyperlink: h, .. } => {
+                    Some(TextRun::new(h.text.clone(), h.pos, h.size, h.color))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Call `f` on the path of every SVG, image and image brush the frame
+    /// draws.
+    fn for_each_asset_path(&mut self, mut f: impl FnMut(&mut PathBuf)) {
+        let brush_path = |brush: &mut Brush, f: &mut dyn FnMut(&mut PathBuf)| {
+            if let Brush::Image(pattern) = brush {
+                f(&mut pattern.path);
+            }
+        };
+        for cmd in &mut self.list.commands {
+            match cmd {
+                Command::DrawSvg { path, .. } | Command::DrawImage { path, .. } => f(path),
+                Command::DrawRect { brush: b, .. }
+                | Command::DrawRoundedRect { brush: b, .. }
+                | Command::StrokeRect { brush: b, .. }
+                | Command::StrokeRoundedRect { brush: b, .. }
+                | Command::DrawEllipse { brush: b, .. }
+                | Command::FillPath { brush: b, .. }
+                | Command::StrokePath { brush: b, .. } => brush_path(b, &mut f),
+                Command::PushLayer {
+                    mask: Some(mask), ..
+                } => brush_path(&mut mask.brush, &mut f),
+                _ => {}
+            }
+        }
+        for draws in std::iter::once(&mut self.root).chain(&mut self.layers) {
+            for draw in &mut draws.svg_draws {
+                f(&mut draw.0);
+            }
+            for draw in &mut draws.image_draws {
+                f(&mut draw.0);
+            }
+        }
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.list.viewport
+    }
+
+    pub fn dpi_scale(&self) -> f32 {
+        self.dpi_scale
+    }
+
+    pub fn display_list(&self) -> &DisplayList {
+        &self.list
+    }
+
+    /// Whether the captured canvas had a text provider, so the display
+    /// list's text runs were rendered.
+    pub fn had_text_provider(&self) -> bool {
+        self.text_provider
+    }
+
+    /// The embedded file contents, by the path the frame drew them from.
+    pub fn assets(&self) -> &BTreeMap<PathBuf, Vec<u8>> {
+        &self.assets
+    }
+
+    pub fn fonts(&self) -> &[CapturedFont] {
+        &self.fonts
+    }
+
+    /// Write the embedded files into `dir` and point the frame's draws at
+    /// the copies, so it replays without the original files.
+    pub fn unpack_assets(&mut self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        let mut unpacked = BTreeMap::new();
+        for (idx, (path, bytes)) in self.assets.iter().enumerate() {
+            // Keep the file name: image formats are picked by extension.
+            let name = path.file_name().map_or_else(
+                || format!("asset-{idx}"),
+                |name| format!("{idx}-{}", name.to_string_lossy()),
+            );
+            let target = dir.join(name);
+            std::fs::write(&target, bytes)
+                .with_context(|| format!("failed to write {}", target.display()))?;
+            unpacked.insert(path.clone(), target);
+        }
+        self.for_each_asset_path(|path| {
+            if let Some(target) = unpacked.get(path) {
+                *path = target.clone();
+            }
+        });
+        self.assets = std::mem::take(&mut self.assets)
+            .into_iter()
+            .map(|(path, bytes)| (unpacked[&path].clone(), bytes))
+            .collect();
+        Ok(())
+    }
+
+    /// A text provider over the captured font faces, the first being the
+    /// primary font, or `None` if no faces were captured.
+    pub fn text_provider(
+        &self,
+        orientation: SubpixelOrientation,
+    ) -> Result<Option<RuneTextProvider>> {
+        if self.fonts.is_empty() {
+            return Ok(None);
+        }
+        let faces = self
+            .fonts
+            .iter()
+            .map(|font| (font.data.clone(), font.index));
+        Ok(Some(RuneTextProvider::from_font_data(faces, orientation)?))
+    }
+
+    pub fn encode(&self, format: CaptureFormat) -> Result<Vec<u8>> {
+        format.encode(self)
+    }
+
+    /// Decode a capture in either format.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        CaptureFormat::decode(bytes)
+    }
+
+    /// Write the capture to `path`, as JSON for a `.json` extension and in
+    /// the binary form otherwise.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = self.encode(CaptureFormat::from_path(path))?;
+        std::fs::write(path, bytes).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes =
+            std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        Self::decode(&bytes).with_context(|| format!("failed to decode {}", path.display()))
+    }
+
+    /// Rebuild the canvas, to render with [`RuneSurface::end_frame`] or
+    /// [`Canvas::render_cpu`]. Set a text provider on it to render the text
+    /// runs of the display list.
+    ///
+    /// [`RuneSurface::end_frame`]: crate::RuneSurface::end_frame
+    pub fn into_canvas(self) -> Canvas {
+        let mut canvas = Canvas::new(self.list.viewport, self.dpi_scale);
+        canvas.painter = Painter::from_display_list(self.list);
+        canvas.clear_color = self.clear_color;
+        canvas.glyph_draws = self.root.glyph_draws;
+        canvas.svg_draws = self.root.svg_draws;
+        canvas.image_draws = self.root.image_draws;
+        canvas.layer_draws = self.layers;
+        canvas.clips = self.clips;
+        canvas.scrim_draws = self.scrim_draws;
+        canvas.overlay_draws = self.overlay_draws;
+        canvas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use engine_core::{BlendMode, Brush, MaskFormat, RasterizedGlyph, SubpixelMask};
+
+    fn sample_canvas() -> Canvas {
+        let mut canvas = Canvas::headless(64, 48, 2.0);
+        let blue = ColorLinPremul::from_srgba_u8([0, 0, 255, 255]);
+        canvas.clear(ColorLinPremul::from_srgba_u8([255, 255, 255, 255]));
+        canvas.fill_rect(2.0, 2.0, 20.0, 10.0, Brush::Solid(blue), 1);
+        canvas.push_layer(0.5, BlendMode::Multiply, None);
+        canvas.draw_svg("icons/check.svg", [4.0, 4.0], [12.0, 12.0], 2);
+        let glyph = RasterizedGlyph {
+            offset: [0.0, 0.0],
+            color: None,
+            mask: SubpixelMask {
+                width: 2,
+                height: 1,
+                format: MaskFormat::Rgba8,
+                data: vec![255; 8],
+            },
+            key: 0,
+        };
+        canvas.draw_text_glyphs([8.0, 8.0], &[glyph], blue, 3);
+        canvas.fill_scrim_rect(0.0, 0.0, 64.0, 48.0, blue);
+        // Left open: capturing closes it.
+        canvas
+    }
+
+    #[test]
+    fn captures_round_trip_through_both_formats() {
+        let capture = FrameCapture::from_canvas(&mut sample_canvas());
+        assert_eq!(capture.layers.len(), 1);
+        assert_eq!(capture.layers[0].glyph_draws.len(), 1);
+        for format in [CaptureFormat::Json, CaptureFormat::Binary] {
+            let decoded = FrameCapture::decode(&capture.encode(format).unwrap()).unwrap();
+            assert_eq!(
+                format!("{:?}", decoded.display_list()),
+                format!("{:?}", capture.display_list())
+            );
+            assert_eq!(decoded.dpi_scale(), 2.0);
+            assert_eq!(
+                format!("{:?}", decoded.layers[0].svg_draws),
+                format!("{:?}", capture.layers[0].svg_draws)
+            );
+            assert_eq!(
+                decoded.layers[0].glyph_draws[0].1.mask.data,
+                capture.layers[0].glyph_draws[0].1.mask.data
+            );
+            assert_eq!(decoded.scrim_draws.len(), 1);
+        }
+    }
+
+    #[test]
+    fn captures_embed_assets_and_unpack_them() {
+        let dir = std::env::temp_dir().join(format!("rune-capture-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let svg = dir.join("dot.svg");
+        let bytes = br#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><circle cx="2" cy="2" r="2"/></svg>"#;
+        std::fs::write(&svg, bytes).unwrap();
+
+        let mut canvas = Canvas::headless(16, 16, 1.0);
+        canvas.draw_svg(&svg, [0.0, 0.0], [4.0, 4.0], 1);
+        canvas.draw_svg(dir.join("missing.svg"), [4.0, 4.0], [4.0, 4.0], 1);
+        let capture = FrameCapture::from_canvas(&mut canvas);
+        assert_eq!(capture.assets().len(), 1);
+        assert_eq!(capture.assets()[&svg], bytes);
+
+        let bytes = capture.encode(CaptureFormat::Binary).unwrap();
+        let mut decoded = FrameCapture::decode(&bytes).unwrap();
+        let unpacked = dir.join("unpacked");
+        decoded.unpack_assets(&unpacked).unwrap();
+        let copy = &decoded.root.svg_draws[0].0;
+        assert!(copy.starts_with(&unpacked));
+        assert_eq!(std::fs::read(copy).unwrap(), capture.assets()[&svg]);
+        assert_eq!(decoded.root.svg_draws[1].0, dir.join("missing.svg"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn captures_record_the_fonts_text_runs_use() {
+        let font = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../fonts/Geist/static/Geist-Regular.ttf"
+        ))
+        .unwrap();
+        let provider = RuneTextProvider::from_bytes(&font, SubpixelOrientation::RGB).unwrap();
+        let mut canvas = Canvas::headless(64, 32, 1.0);
+        canvas.set_text_provider(std::sync::Arc::new(provider));
+        let black = ColorLinPremul::from_srgba_u8([0, 0, 0, 255]);
+        canvas
+            .painter
+            .text(TextRun::new("Hello", [2.0, 20.0], 16.0, black), 1);
+        let capture = FrameCapture::from_canvas(&mut canvas);
+        assert_eq!(capture.fonts().len(), 1);
+        assert_eq!(capture.fonts()[0].data, font);
+        assert!(
+            capture
+                .text_provider(SubpixelOrientation::RGB)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn replayed_canvases_render_like_the_original() {
+        let original = sample_canvas().render_cpu().to_image();
+        let capture = FrameCapture::from_canvas(&mut sample_canvas());
+        let bytes = capture.encode(CaptureFormat::Binary).unwrap();
+        let replayed = FrameCapture::decode(&bytes)
+            .unwrap()
+            .into_canvas()
+            .render_cpu()
+            .to_image();
+        assert_eq!(original.as_raw(), replayed.as_raw());
+    }
+}
@@ -0,0 +1,140 @@
+//! Headless rendering of a [`Canvas`] through engine-core's CPU rasterizer.
+//!
+//! This mirrors what `RuneSurface::end_frame` feeds the GPU so that a frame
+//! built by application code can be snapshot-tested without a device.
+
+use std::sync::Arc;
+
+use engine_core::{
+    CpuGlyphDraw, CpuImageDraw, CpuRenderer, CpuSideDraws, CpuSvgDraw, TextProvider, Viewport,
+};
+
+use crate::canvas::{Canvas, CanvasImageDraw, ScrimDraw};
+use crate::surface::{apply_transform_to_point, calculate_image_fit};
+
+impl Canvas {
+    /// Create a canvas that is not bound to a `RuneSurface`.
+    ///
+    /// `width`/`height` are device pixels and `dpi_scale` is the
+    /// logical→device multiplier, as for a surface with logical pixels on.
+    pub fn headless(width: u32, height: u32, dpi_scale: f32) -> Self {
+        let dpi_scale = if dpi_scale.is_finite() && dpi_scale > 0.0 {
+            dpi_scale
+        } else {
+            1.0
+        };
+        Canvas::new(Viewport { width, height }, dpi_scale)
+    }
+
+    /// Rasterize the frame on the CPU.
+    ///
+    /// Raw image draws (WebView pixels) are not rendered.
+    pub fn render_cpu(mut self) -> CpuRenderer {
+        let width = self.viewport.width.max(1);
+        let height = self.viewport.height.max(1);
+        let mut cpu = CpuRenderer::new(width, height);
+        cpu.set_scale_factor(self.dpi_scale);
+        if let Some(provider) = self.text_provider.clone() {
+            let provider: Arc<dyn TextProvider> = provider;
+            cpu.set_text_provider(provider);
+        }
+        if let Some(clear) = self.clear_color {
+            cpu.clear(clear);
+        }
+
+        // Keep submission order; the CPU renderer does its own z sorting.
+        self.close_layers();
+        let list = self.painter.finish();
+        cpu.set_clips(std::mem::take(&mut self.clips));
+
+        let root = prepare_side_draws(
+            &mut cpu,
+            &self.glyph_draws,
+            &self.svg_draws,
+            &self.image_draws,
+        );
+        let layers: Vec<_> = self
+            .layer_draws
+            .iter()
+            .map(|layer| {
+                prepare_side_draws(
+                    &mut cpu,
+                    &layer.glyph_draws,
+                    &layer.svg_draws,
+                    &layer.image_draws,
+                )
+            })
+            .collect();
+        let layer_sides: Vec<CpuSideDraws> = layers.iter().map(SideDraws::as_cpu).collect();
+        cpu.render_layered(&list, root.as_cpu(), &layer_sides);
+
+        for scrim in &self.scrim_draws {
+            match scrim {
+                ScrimDraw::Rect(rect, color) => cpu.fill_overlay_rect(*rect, *color),
+                ScrimDraw::Cutout { hole, color } => cpu.fill_scrim_with_cutout(*hole, *color),
+            }
+        }
+        for (rect, color) in &self.overlay_draws {
+            cpu.fill_overlay_rect(*rect, *color);
+        }
+        cpu
+    }
+}
+
+/// Side draws of the root or a layer, with asset paths resolved and images
+/// fitted like `RuneSurface::end_frame` does.
+struct SideDraws<'a> {
+    glyph_draws: &'a [CpuGlyphDraw],
+    svg_draws: Vec<CpuSvgDraw>,
+    image_draws: Vec<CpuImageDraw>,
+}
+
+impl SideDraws<'_> {
+    fn as_cpu(&self) -> CpuSideDraws<'_> {
+        CpuSideDraws {
+            glyph_draws: self.glyph_draws,
+            svg_draws: &self.svg_draws,
+            image_draws: &self.image_draws,
+        }
+    }
+}
+
+fn prepare_side_draws<'a>(
+    cpu: &mut CpuRenderer,
+    glyph_draws: &'a [CpuGlyphDraw],
+    svg_draws: &[CpuSvgDraw],
+    image_draws: &[CanvasImageDraw],
+) -> SideDraws<'a> {
+    let svg_draws = svg_draws
+        .iter()
+        .map(|(path, origin, max_size, style, z, transform, clip)| {
+            let resolved_path = crate::resolve_asset_path(path);
+            (
+                resolved_path,
+                *origin,
+                *max_size,
+                *style,
+                *z,
+                *transform,
+                *clip,
+            )
+        })
+        .collect();
+
+    let mut fitted: Vec<CpuImageDraw> = Vec::new();
+    for (path, origin, size, fit, z, transform, clip) in image_draws.iter() {
+        let resolved_path = crate::resolve_asset_path(path);
+        if let Some((img_w, img_h)) = cpu.image_size(&resolved_path) {
+            let transformed_origin = apply_transform_to_point(*origin, *transform);
+            let (render_origin, render_size) =
+                calculate_image_fit(transformed_origin, *size, img_w as f32, img_h as f32, *fit);
+            fitted.push((resolved_path, render_origin, render_size, *z, *clip));
+        }
+    }
+
+    SideDraws {
+        glyph_draws,
+        svg_draws,
+        image_draws: fitted,
+    }
+}
@@ -1,10 +1,13 @@
 //! rune-surface: Canvas-style API on top of engine-core.
 
 mod canvas;
+mod capture;
+mod headless;
 pub mod shapes;
 mod surface;
 
 pub use canvas::{Canvas, ImageFitMode, RawImageDraw, ScrimDraw};
+pub use capture::{CapturedFont, FrameCapture};
 pub use surface::{get_last_raw_image_rect, RuneSurface};
 
 /// Resolve an asset path by checking multiple locations:
@@ -70,7 +70,7 @@ pub fn draw_rounded_rectangle(
     }
 }
 
-/// Draw a circle with optional fill and stroke. Stroke supports solid color only.
+/// Draw a circle with optional fill and stroke.
 pub fn draw_circle(
     canvas: &mut Canvas,
     center: [f32; 2],
@@ -84,33 +84,30 @@ pub fn draw_circle(
         canvas.circle(center, radius, f, z);
     }
     if let (Some(w), Some(sb)) = (stroke_width, stroke_brush) {
-        // Only solid strokes are supported for path-based circle stroke
-        if let Brush::Solid(col) = sb {
-            let segs = 48u32;
-            let mut path = Path {
-                cmds: Vec::new(),
-                fill_rule: FillRule::NonZero,
-            };
-            let mut first = true;
-            for i in 0..=segs {
-                let t = (i as f32) / (segs as f32);
-                let ang = std::f32::consts::TAU * t;
-                let x = center[0] + radius * ang.cos();
-                let y = center[1] + radius * ang.sin();
-                if first {
-                    path.cmds.push(PathCmd::MoveTo([x, y]));
-                    first = false;
-                } else {
-                    path.cmds.push(PathCmd::LineTo([x, y]));
-                }
+        let segs = 48u32;
+        let mut path = Path {
+            cmds: Vec::new(),
+            fill_rule: FillRule::NonZero,
+        };
+        let mut first = true;
+        for i in 0..=segs {
+            let t = (i as f32) / (segs as f32);
+            let ang = std::f32::consts::TAU * t;
+            let x = center[0] + radius * ang.cos();
+            let y = center[1] + radius * ang.sin();
+            if first {
+                path.cmds.push(PathCmd::MoveTo([x, y]));
+                first = false;
+            } else {
+                path.cmds.push(PathCmd::LineTo([x, y]));
             }
-            path.cmds.push(PathCmd::Close);
-            canvas.stroke_path(path, w, col, z + 1);
         }
+        path.cmds.push(PathCmd::Close);
+        canvas.stroke_path(path, w, sb, z + 1);
     }
 }
 
-/// Draw an ellipse with optional fill and stroke. Stroke supports solid color only.
+/// Draw an ellipse with optional fill and stroke.
 pub fn draw_ellipse(
     canvas: &mut Canvas,
     center: [f32; 2],
@@ -124,28 +121,25 @@ pub fn draw_ellipse(
         canvas.ellipse(center, radii, f, z);
     }
     if let (Some(w), Some(sb)) = (stroke_width, stroke_brush) {
-        // Only solid strokes are supported for path-based ellipse stroke
-        if let Brush::Solid(col) = sb {
-            let segs = 64u32;
-            let mut path = Path {
-                cmds: Vec::new(),
-                fill_rule: FillRule::NonZero,
-            };
-            let mut first = true;
-            for i in 0..=segs {
-                let t = (i as f32) / (segs as f32);
-                let ang = std::f32::consts::TAU * t;
-                let x = center[0] + radii[0] * ang.cos();
-                let y = center[1] + radii[1] * ang.sin();
-                if first {
-                    path.cmds.push(PathCmd::MoveTo([x, y]));
-                    first = false;
-                } else {
-                    path.cmds.push(PathCmd::LineTo([x, y]));
-                }
+        let segs = 64u32;
+        let mut path = Path {
+            cmds: Vec::new(),
+            fill_rule: FillRule::NonZero,
+        };
+        let mut first = true;
+        for i in 0..=segs {
+            let t = (i as f32) / (segs as f32);
+            let ang = std::f32::consts::TAU * t;
+            let x = center[0] + radii[0] * ang.cos();
+            let y = center[1] + radii[1] * ang.sin();
+            if first {
+                path.cmds.push(PathCmd::MoveTo([x, y]));
+                first = false;
+            } else {
+                path.cmds.push(PathCmd::LineTo([x, y]));
             }
-            path.cmds.push(PathCmd::Close);
-            canvas.stroke_path(path, w, col, z + 1);
         }
+        path.cmds.push(PathCmd::Close);
+        canvas.stroke_path(path, w, sb, z + 1);
     }
 }
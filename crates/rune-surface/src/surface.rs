@@ -1,21 +1,27 @@
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 use anyhow::Result;
 
 use engine_core::{
+    Brush,
     ColorLinPremul,
-    Painter,
+    Command,
+    DamageItem,
     PassManager,
+    Rect,
     RenderAllocator,
+    RetainedScene,
     Transform2D,
     Viewport,
     wgpu, // import wgpu from engine-core to keep type identity
 };
 
-use crate::canvas::{Canvas, ImageFitMode};
+use crate::canvas::{Canvas, CanvasImageDraw, ImageFitMode};
+use crate::capture::FrameCapture;
 
 /// Apply a 2D affine transform to a point
-fn apply_transform_to_point(point: [f32; 2], transform: Transform2D) -> [f32; 2] {
+pub(crate) fn apply_transform_to_point(point: [f32; 2], transform: Transform2D) -> [f32; 2] {
     let [a, b, c, d, e, f] = transform.m;
     let x = point[0];
     let y = point[1];
@@ -56,7 +62,7 @@ pub type OverlayCallback = Box<
 
 /// Calculate the actual render origin and size for an image based on fit mode.
 /// Returns (origin, size) where the image should be drawn.
-fn calculate_image_fit(
+pub(crate) fn calculate_image_fit(
     origin: [f32; 2],
     bounds: [f32; 2],
     img_w: f32,
@@ -144,6 +150,13 @@ pub struct RuneSurface {
     ui_scale: f32,
     /// Optional overlay callback for post-render passes (e.g., SVG overlays)
     overlay: Option<OverlayCallback>,
+    /// When true, keep the intermediate texture between frames and repaint
+    /// only what changed.
+    partial_redraw: bool,
+    /// Drawables of the last frame, diffed against the next for partial redraws.
+    retained: RetainedScene,
+    /// Where to save a capture of the next frame, if requested.
+    capture_path: Option<PathBuf>,
 }
 
 impl RuneSurface {
@@ -169,6 +182,9 @@ impl RuneSurface {
             enable_smaa: false,
             ui_scale: 1.0,
             overlay: None,
+            partial_redraw: false,
+            retained: RetainedScene::new(),
+            capture_path: None,
         }
     }
 
@@ -234,6 +250,16 @@ impl RuneSurface {
     pub fn clear_overlay(&mut self) {
         self.overlay = None;
     }
+    /// Enable or disable partial redraws. When on, each frame is diffed
+    /// against the previous one and only the damaged region of the
+    /// intermediate texture is repainted; an unchanged frame skips scene
+    /// rendering altogether. Frames with layers, scrims, overlays, raw images
+    /// or a translucent clear color are always redrawn in full. Rendering goes
+    /// directly into the intermediate texture while this is on.
+    pub fn set_partial_redraw(&mut self, on: bool) {
+        self.partial_redraw = on;
+        self.retained.invalidate();
+    }
 
     /// Pre-allocate intermediate texture at the given size.
     /// This should be called after surface reconfiguration to avoid jitter.
@@ -244,25 +270,27 @@ impl RuneSurface {
 
     /// Begin a canvas frame of the given size (in pixels).
     pub fn begin_frame(&self, width: u32, height: u32) -> Canvas {
-        let vp = Viewport { width, height };
-        Canvas {
-            viewport: vp,
-            painter: Painter::begin_frame(vp),
-            clear_color: None,
-            text_provider: None,
-            glyph_draws: Vec::new(),
-            svg_draws: Vec::new(),
-            image_draws: Vec::new(),
-            raw_image_draws: Vec::new(),
-            dpi_scale: self.dpi_scale,
-            clip_stack: vec![None],
-            overlay_draws: Vec::new(),
-            scrim_draws: Vec::new(),
-        }
+        Canvas::new(Viewport { width, height }, self.dpi_scale)
+    }
+
+    /// Save the next frame passed to [`end_frame`](Self::end_frame) as a
+    /// [`FrameCapture`] at `path`: JSON for a `.json` extension, the compact
+    /// binary form otherwise.
+    pub fn capture_next_frame(&mut self, path: impl Into<PathBuf>) {
+        self.capture_path = Some(path.into());
     }
 
     /// Finish the frame by rendering accumulated commands to the provided surface texture.
     pub fn end_frame(&mut self, frame: wgpu::SurfaceTexture, canvas: Canvas) -> Result<()> {
+        self.render_to_texture(&frame.texture, canvas)?;
+        frame.present();
+        Ok(())
+    }
+
+    /// Render the frame into `target` instead of a swapchain texture, e.g.
+    /// to read it back. `target` must have the surface format this surface
+    /// was created with and `RENDER_ATTACHMENT` usage.
+    pub fn render_to_texture(&mut self, target: &wgpu::Texture, mut canvas: Canvas) -> Result<()> {
         // Keep passes in sync with DPI/logical settings
         self.pass.set_scale_factor(self.dpi_scale);
         self.pass.set_logical_pixels(self.logical_pixels);
@@ -272,17 +300,112 @@ impl RuneSurface {
         let use_intermediate = self.enable_smaa || self.use_intermediate;
 
         // Build final display list from painter
-        let mut list = canvas.painter.finish();
+        canvas.close_layers();
+        if let Some(path) = self.capture_path.take() {
+            // A failed capture shouldn't cost the frame.
+            if let Err(err) = FrameCapture::from_canvas(&mut canvas).save(&path) {
+                log::warn!("failed to capture frame to {}: {err:#}", path.display());
+            }
+        }
+        let side_items = if self.partial_redraw {
+            canvas.side_damage_items()
+        } else {
+            Vec::new()
+        };
+        // Take the display list, leaving the rest of the canvas whole.
+        let painter = std::mem::replace(
+            &mut canvas.painter,
+            engine_core::Painter::begin_frame(canvas.viewport),
+        );
+        let mut list = painter.finish();
         let width = canvas.viewport.width.max(1);
         let height = canvas.viewport.height.max(1);
 
-        // Sort display list by z-index to ensure proper layering
-        list.sort_by_z();
+        // Sort display list by z-index to ensure proper layering; layer side
+        // draws are looked up through the layer order from before the sort.
+        let layer_order = list.sort_by_z_tracking_layers();
+
+        // Clear color or transparent
+        let clear = canvas.clear_color.unwrap_or(ColorLinPremul {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+        });
+        let clear_wgpu = wgpu::Color {
+            r: clear.r as f64,
+            g: clear.g as f64,
+            b: clear.b as f64,
+            a: clear.a as f64,
+        };
+
+        // Partial redraw: diff against the last frame and repaint only the
+        // damaged region of the retained intermediate texture. Anything drawn
+        // outside the display list and side draws forces a full redraw.
+        let retain = self.partial_redraw
+            && use_intermediate
+            && !self.preserve_surface
+            && clear.a >= 1.0
+            && canvas.scrim_draws.is_empty()
+            && canvas.overlay_draws.is_empty()
+            && canvas.raw_image_draws.is_empty()
+            && self.overlay.is_none()
+            && !list
+                .commands
+                .iter()
+                .any(|c| matches!(c, Command::PushLayer { .. }));
+        let mut scissor = None;
+        if retain {
+            let multiplier = self.pass.logical_multiplier();
+            let screen = Rect {
+                x: 0.0,
+                y: 0.0,
+                w: width as f32 / multiplier,
+                h: height as f32 / multiplier,
+            };
+            // The clear color and scale cover the whole frame, so changing
+            // either repaints everything.
+            let background = DamageItem::new(
+                engine_core::content_hash(0, &(clear, multiplier.to_bits())),
+                screen,
+            );
+            let items = std::iter::once(background)
+                .chain(list.damage_items())
+                .chain(side_items);
+            let damage = self.retained.update(list.viewport, items);
+            match damage.bounds(screen) {
+                None => return self.present_retained(target, width, height),
+                Some(_) if damage.is_full() => {}
+                Some(r) => {
+                    let x0 = ((r.x * multiplier).floor().max(0.0) as u32).min(width);
+                    let y0 = ((r.y * multiplier).floor().max(0.0) as u32).min(height);
+                    let x1 = (((r.x + r.w) * multiplier).ceil().max(0.0) as u32).min(width);
+                    let y1 = (((r.y + r.h) * multiplier).ceil().max(0.0) as u32).min(height);
+                    scissor = Some([x0, y0, x1 - x0, y1 - y0]);
+                    // Paint the clear color under the damaged content, past
+                    // the scissor so its edges aren't antialiased.
+                    list.commands.insert(
+                        0,
+                        Command::DrawRect {
+                            rect: Rect {
+                                x: x0 as f32 / multiplier - 1.0,
+                                y: y0 as f32 / multiplier - 1.0,
+                                w: (x1 - x0) as f32 / multiplier + 2.0,
+                                h: (y1 - y0) as f32 / multiplier + 2.0,
+                            },
+                            brush: Brush::Solid(clear),
+                            z: i32::MIN,
+                            transform: Transform2D::identity(),
+                        },
+                    );
+                }
+            }
+        } else {
+            self.retained.invalidate();
+        }
 
         // Create target view
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
         let scene_view = if use_intermediate {
             self.pass
                 .ensure_intermediate_texture(&mut self.allocator, width, height);
@@ -295,9 +418,7 @@ impl RuneSurface {
                 .texture
                 .create_view(&wgpu::TextureViewDescriptor::default())
         } else {
-            frame
-                .texture
-                .create_view(&wgpu::TextureViewDescriptor::default())
+            target.create_view(&wgpu::TextureViewDescriptor::default())
         };
 
         // Command encoder
@@ -307,74 +428,40 @@ impl RuneSurface {
                 label: Some("rune-surface-encoder"),
             });
 
-        // Clear color or transparent
-        let clear = canvas.clear_color.unwrap_or(ColorLinPremul {
-            r: 0.0,
-            g: 0.0,
-            b: 0.0,
-            a: 0.0,
-        });
-        let clear_wgpu = wgpu::Color {
-            r: clear.r as f64,
-            g: clear.g as f64,
-            b: clear.b as f64,
-            a: clear.a as f64,
-        };
-
         // Ensure depth texture is allocated for z-ordering (Phase 1 of depth buffer implementation)
         self.pass
             .ensure_depth_texture(&mut self.allocator, width, height);
 
         // Extract unified scene data (solids + text/image/svg draws) from the display list.
-        let unified_scene =
+        let mut unified_scene =
             engine_core::upload_display_list_unified(&mut self.allocator, &self.queue, &list)?;
+        // Side draws clip to stacks in the same table as the display list.
+        canvas.move_clips_into(&mut unified_scene.clips);
+        self.pass.set_clips(&unified_scene.clips, &self.queue);
 
         // Sort SVG draws by z-index and resolve paths for app bundle
         let mut svg_draws: Vec<_> = canvas
             .svg_draws
             .iter()
-            .map(|(path, origin, max_size, style, z, transform)| {
+            .map(|(path, origin, max_size, style, z, transform, clip)| {
                 let resolved_path = crate::resolve_asset_path(path);
-                (resolved_path, *origin, *max_size, *style, *z, *transform)
+                (
+                    resolved_path,
+                    *origin,
+                    *max_size,
+                    *style,
+                    *z,
+                    *transform,
+                    *clip,
+                )
             })
             .collect();
-        svg_draws.sort_by_key(|(_, _, _, _, z, _)| *z);
+        svg_draws.sort_by_key(|(_, _, _, _, z, _, _)| *z);
 
         // Sort image draws by z-index and prepare simplified data (for unified pass)
         let mut image_draws = canvas.image_draws.clone();
-        image_draws.sort_by_key(|(_, _, _, _, z, _)| *z);
-
-        // Convert image draws to simplified format (path, origin, size, z)
-        // Apply transforms and fit calculations here. We synchronously load images
-        // via PassManager so that they appear on the very first frame, without
-        // requiring a scroll/resize to trigger a second redraw.
-        //
-        // NOTE: Origins in `canvas.image_draws` are already in logical coordinates;
-        // they will be scaled by PassManager via logical_pixels/dpi.
-        let mut prepared_images: Vec<(std::path::PathBuf, [f32; 2], [f32; 2], i32)> = Vec::new();
-        for (path, origin, size, fit, z, transform) in image_draws.iter() {
-            // Resolve path to check app bundle resources
-            let resolved_path = crate::resolve_asset_path(path);
-
-            // Synchronously load (or fetch from cache) to ensure the texture
-            // is available for this frame. This mirrors the demo-app unified
-            // path and avoids images only appearing after a later redraw.
-            if let Some((tex_view, img_w, img_h)) = self
-                .pass
-                .load_image_to_view(&resolved_path, &self.queue)
-            {
-                drop(tex_view); // Only need dimensions here
-                let transformed_origin = apply_transform_to_point(*origin, *transform);
-                let (render_origin, render_size) = calculate_image_fit(
-                    transformed_origin,
-                    *size,
-                    img_w as f32,
-                    img_h as f32,
-                    *fit,
-                );
-                prepared_images.push((resolved_path.clone(), render_origin, render_size, *z));
-            }
-        }
+        image_draws.sort_by_key(|(_, _, _, _, z, _, _)| *z);
+        let mut prepared_images = self.prepare_images(&image_draws);
 
         // Process raw image draws (e.g., WebView CEF pixels)
         // Optimizations:
@@ -477,93 +564,103 @@ impl RuneSurface {
                 raw_draw.dst_size[1],
             );
 
-            prepared_images.push((raw_path, transformed_origin, raw_draw.dst_size, raw_draw.z));
+            prepared_images.push((
+                raw_path,
+                transformed_origin,
+                raw_draw.dst_size,
+                raw_draw.z,
+                0,
+            ));
         }
 
+        // Image-pattern meshes reference image files like image draws do.
+        let pattern_draws: Vec<engine_core::ExtractedPatternDraw> = unified_scene
+            .pattern_draws
+            .iter()
+            .map(|draw| engine_core::ExtractedPatternDraw {
+                path: crate::resolve_asset_path(&draw.path),
+                ..draw.clone()
+            })
+            .collect();
+
         // Merge glyphs supplied explicitly via Canvas (draw_text_run/draw_text_direct)
         // with text runs extracted from the display list (e.g., hyperlinks) for
         // unified text rendering.
         let mut glyph_draws = canvas.glyph_draws.clone();
 
         if let Some(ref provider) = canvas.text_provider {
-            // Use the same snapping strategy as direct text paths so small
-            // text (e.g., 13–15px) lands cleanly on device pixels.
-            let sf = if self.dpi_scale.is_finite() && self.dpi_scale > 0.0 {
-                self.dpi_scale
-            } else {
-                1.0
-            };
-            let snap = |v: f32| -> f32 { (v * sf).round() / sf };
-
-            for text_draw in &unified_scene.text_draws {
-                let run = &text_draw.run;
-                let [a, b, c, d, e, f] = text_draw.transform.m;
-
-                // Transform the run origin (baseline-left) into world coordinates.
-                let origin_x = a * run.pos[0] + c * run.pos[1] + e;
-                let origin_y = b * run.pos[0] + d * run.pos[1] + f;
-
-                // Infer uniform scale from the linear part of the transform so
-                // text respects any explicit scaling in the display list.
-                let sx = (a * a + b * b).sqrt();
-                let sy = (c * c + d * d).sqrt();
-                let mut s = if sx.is_finite() && sy.is_finite() {
-                    if sx > 0.0 && sy > 0.0 {
-                        (sx + sy) * 0.5
-                    } else {
-                        sx.max(sy).max(1.0)
-                    }
-                } else {
-                    1.0
-                };
-                if !s.is_finite() || s <= 0.0 {
-                    s = 1.0;
-                }
-
-                // Rasterize at logical size scaled only by the display-list
-                // transform. DPI scaling is applied later by PassManager.
-                let scaled_size = (run.size * s).max(1.0);
-                let run_for_provider = engine_core::TextRun {
-                    text: run.text.clone(),
-                    pos: [0.0, 0.0],
-                    size: scaled_size,
-                    color: run.color,
-                };
-
-                // Rasterize glyphs for this run and push into glyph_draws.
-                // Origins are kept in logical coordinates; PassManager applies
-                // DPI/UI scaling centrally so geometry and text stay aligned.
-                let glyphs =
-                    engine_core::rasterize_run_cached(provider.as_ref(), &run_for_provider);
-                for g in glyphs.iter() {
-                    let mut origin = [origin_x + g.offset[0], origin_y + g.offset[1]];
-                    if scaled_size <= 15.0 {
-                        origin[0] = snap(origin[0]);
-                        origin[1] = snap(origin[1]);
-                    }
-                    glyph_draws.push((origin, g.clone(), run.color, text_draw.z));
-                }
-            }
+            glyph_draws
+                .extend(self.rasterize_text_draws(provider.as_ref(), &unified_scene.text_draws));
         }
 
-        // Unified solids + text/images/SVGs pass
-        let preserve_surface = self.preserve_surface;
-        let direct = self.direct || !use_intermediate;
-        self.pass.render_unified(
-            &mut encoder,
+        // Render layer content offscreen before the main pass, which owns the
+        // depth buffer and glyph atlas the layer passes share.
+        let layer_draws = self.layer_draws(
+            canvas.text_provider.as_deref(),
+            &canvas.layer_draws,
+            &unified_scene.layers,
+            &layer_order,
+        );
+        let rendered_layers = self.pass.render_layers(
             &mut self.allocator,
-            &scene_view,
             width,
             height,
-            &unified_scene.gpu_scene,
-            &glyph_draws,
-            &svg_draws,
-            &prepared_images,
-            clear_wgpu,
-            direct,
+            &layer_draws,
             &self.queue,
-            preserve_surface,
         );
+        drop(layer_draws);
+
+        // Unified solids + text/images/SVGs pass, drawn in slices with the
+        // layers composited between them in paint order. Slices after the
+        // first load what came before, which the offscreen path can't.
+        let preserve_surface = self.preserve_surface;
+        let direct =
+            self.direct || !use_intermediate || self.partial_redraw || !rendered_layers.is_empty();
+        for (i, slice) in engine_core::scene_slices(&rendered_layers)
+            .into_iter()
+            .enumerate()
+        {
+            self.pass.set_scissor(scissor);
+            let slice_target = engine_core::UnifiedTarget {
+                view: &scene_view,
+                width,
+                height,
+                clear: clear_wgpu,
+                direct,
+                preserve_surface: preserve_surface || i > 0,
+            };
+            let draws = engine_core::UnifiedDraws {
+                scene: &unified_scene.gpu_scene,
+                glyph_draws: &glyph_draws,
+                svg_draws: &svg_draws,
+                image_draws: &prepared_images,
+                pattern_draws: &pattern_draws,
+                z_range: slice.z,
+            };
+            self.pass.render_unified(
+                &mut encoder,
+                &mut self.allocator,
+                slice_target,
+                draws,
+                &self.queue,
+            );
+            let scene_texture = if use_intermediate {
+                &self
+                    .pass
+                    .intermediate_texture
+                    .as_ref()
+                    .expect("intermediate render target not allocated")
+                    .texture
+            } else {
+                target
+            };
+            self.pass.composite_layers(
+                &mut encoder,
+                scene_texture,
+                &rendered_layers[slice.layers],
+                &self.queue,
+            );
+        }
 
         // Render scrims; support both simple rects and stencil cutouts.
         for scrim in &canvas.scrim_draws {
@@ -641,7 +738,232 @@ impl RuneSurface {
         // Submit and present
         let cb = encoder.finish();
         self.queue.submit(std::iter::once(cb));
-        frame.present();
+        self.pass
+            .release_layers(&mut self.allocator, rendered_layers);
         Ok(())
     }
+
+    /// Resolve the retained intermediate texture to `target` unchanged, for
+    /// a frame with no damage.
+    fn present_retained(&mut self, target: &wgpu::Texture, width: u32, height: u32) -> Result<()> {
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("rune-surface-retained-encoder"),
+            });
+        if self.enable_smaa {
+            let scene_view = self
+                .pass
+                .intermediate_texture
+                .as_ref()
+                .expect("intermediate render target not allocated")
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            self.pass.apply_smaa(
+                &mut encoder,
+                &mut self.allocator,
+                &scene_view,
+                &view,
+                width,
+                height,
+                &self.queue,
+            );
+        } else {
+            self.pass.blit_to_surface(&mut encoder, &view);
+        }
+        self.queue.submit(std::iter::once(encoder.finish()));
+        Ok(())
+    }
+
+    /// Load canvas image draws (synchronously, so they show on the first
+    /// frame) and fit them into their bounds: `(path, origin, size, z, clip)`.
+    fn prepare_images(&mut self, image_draws: &[CanvasImageDraw]) -> Vec<engine_core::ImageDraw> {
+        // Apply transforms and fit calculations here. We synchronously load images
+        // via PassManager so that they appear on the very first frame, without
+        // requiring a scroll/resize to trigger a second redraw.
+        //
+        // NOTE: Origins in `canvas.image_draws` are already in logical coordinates;
+        // they will be scaled by PassManager via logical_pixels/dpi.
+        let mut prepared_images: Vec<engine_core::ImageDraw> = Vec::new();
+        for (path, origin, size, fit, z, transform, clip) in image_draws.iter() {
+            // Resolve path to check app bundle resources
+            let resolved_path = crate::resolve_asset_path(path);
+
+            // Synchronously load (or fetch from cache) to ensure the texture
+            // is available for this frame. This mirrors the demo-app unified
+            // path and avoids images only appearing after a later redraw.
+            if let Some((tex_view, img_w, img_h)) =
+                self.pass.load_image_to_view(&resolved_path, &self.queue)
+            {
+                drop(tex_view); // Only need dimensions here
+                let transformed_origin = apply_transform_to_point(*origin, *transform);
+                let (render_origin, render_size) = calculate_image_fit(
+                    transformed_origin,
+                    *size,
+                    img_w as f32,
+                    img_h as f32,
+                    *fit,
+                );
+                prepared_images.push((
+                    resolved_path.clone(),
+                    render_origin,
+                    render_size,
+                    *z,
+                    *clip,
+                ));
+            }
+        }
+        prepared_images
+    }
+
+    /// Rasterize text runs extracted from the display list (e.g., hyperlinks)
+    /// into glyph draws for the unified text pass.
+    fn rasterize_text_draws(
+        &self,
+        provider: &(dyn engine_core::TextProvider + Send + Sync),
+        text_draws: &[engine_core::ExtractedTextDraw],
+    ) -> Vec<(
+        [f32; 2],
+        engine_core::RasterizedGlyph,
+        ColorLinPremul,
+        i32,
+        u32,
+    )> {
+        let mut glyph_draws = Vec::new();
+        // Use the same snapping strategy as direct text paths so small
+        // text (e.g., 13–15px) lands cleanly on device pixels.
+        let sf = if self.dpi_scale.is_finite() && self.dpi_scale > 0.0 {
+            self.dpi_scale
+        } else {
+            1.0
+        };
+        let snap = |v: f32| -> f32 { (v * sf).round() / sf };
+
+        for text_draw in text_draws {
+            let run = &text_draw.run;
+            let [a, b, c, d, e, f] = text_draw.transform.m;
+
+            // Transform the run origin (baseline-left) into world coordinates.
+            let origin_x = a * run.pos[0] + c * run.pos[1] + e;
+            let origin_y = b * run.pos[0] + d * run.pos[1] + f;
+
+            // Infer uniform scale from the linear part of the transform so
+            // text respects any explicit scaling in the display list.
+            let sx = (a * a + b * b).sqrt();
+            let sy = (c * c + d * d).sqrt();
+            let mut s = if sx.is_finite() && sy.is_finite() {
+                if sx > 0.0 && sy > 0.0 {
+                    (sx + sy) * 0.5
+                } else {
+                    sx.max(sy).max(1.0)
+                }
+            } else {
+                1.0
+            };
+            if !s.is_finite() || s <= 0.0 {
+                s = 1.0;
+            }
+
+            // Rasterize at logical size scaled only by the display-list
+            // transform. DPI scaling is applied later by PassManager.
+            let scaled_size = (run.size * s).max(1.0);
+            let run_for_provider = engine_core::TextRun {
+                text: run.text.clone(),
+                pos: [0.0, 0.0],
+                size: scaled_size,
+                color: run.color,
+                spans: run.scaled_spans(s),
+            };
+
+            // Rasterize glyphs for this run and push into glyph_draws.
+            // Origins are kept in logical coordinates; PassManager applies
+            // DPI/UI scaling centrally so geometry and text stay aligned.
+            let glyphs = engine_core::rasterize_run_cached(provider, &run_for_provider);
+            for g in glyphs.iter() {
+                let mut origin = [origin_x + g.offset[0], origin_y + g.offset[1]];
+                if scaled_size <= 15.0 {
+                    origin[0] = snap(origin[0]);
+                    origin[1] = snap(origin[1]);
+                }
+                let color = g.color.unwrap_or(run.color);
+                glyph_draws.push((origin, g.clone(), color, text_draw.z, text_draw.clip));
+            }
+        }
+        glyph_draws
+    }
+
+    /// Per-layer draws for `PassManager::render_layers`, nested like the
+    /// layers. `layer_order` maps extracted layer indices (after sorting) to
+    /// the canvas' layer side draws (in `push_layer` order).
+    fn layer_draws<'a>(
+        &mut self,
+        text_provider: Option<&(dyn engine_core::TextProvider + Send + Sync)>,
+        side_draws: &[crate::canvas::LayerSideDraws],
+        layers: &'a [engine_core::ExtractedLayer],
+        layer_order: &[usize],
+    ) -> Vec<engine_core::LayerDraws<'a>> {
+        let mut out = Vec::with_capacity(layers.len());
+        for layer in layers {
+            let side = layer_order
+                .get(layer.index)
+                .and_then(|i| side_draws.get(*i));
+            let mut glyph_draws = side.map(|d| d.glyph_draws.clone()).unwrap_or_default();
+            if let Some(provider) = text_provider {
+                glyph_draws.extend(self.rasterize_text_draws(provider, &layer.scene.text_draws));
+            }
+            let svg_draws = side
+                .map(|d| {
+                    d.svg_draws
+                        .iter()
+                        .map(|(path, origin, max_size, style, z, transform, clip)| {
+                            let resolved_path = crate::resolve_asset_path(path);
+                            (
+                                resolved_path,
+                                *origin,
+                                *max_size,
+                                *style,
+                                *z,
+                                *transform,
+                                *clip,
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let image_draws = match side {
+                Some(d) => self.prepare_images(&d.image_draws),
+                None => Vec::new(),
+            };
+            let pattern_draws = layer
+                .scene
+                .pattern_draws
+                .iter()
+                .map(|draw| engine_core::ExtractedPatternDraw {
+                    path: crate::resolve_asset_path(&draw.path),
+                    ..draw.clone()
+                })
+                .collect();
+            out.push(engine_core::LayerDraws {
+                opacity: layer.opacity,
+                blend_mode: layer.blend_mode,
+                mask: layer.mask.as_ref(),
+                filters: &layer.filters,
+                backdrop: layer.backdrop.as_ref(),
+                z: layer.z,
+                scene: &layer.scene.gpu_scene,
+                glyph_draws,
+                svg_draws,
+                image_draws,
+                pattern_draws,
+                children: self.layer_draws(
+                    text_provider,
+                    side_draws,
+                    &layer.scene.layers,
+                    layer_order,
+                ),
+            });
+        }
+        out
+    }
 }
@@ -72,6 +72,23 @@ pub fn levels_per_byte(text: &str, base_dir: BaseDirection) -> Vec<u8> {
     info.levels.iter().map(Level::number).collect()
 }
 
+/// Resolve `Auto` to the direction of the first strong character of `text`
+/// (rules P2-P3), defaulting to left-to-right. Explicit directions are
+/// returned unchanged.
+pub fn resolve_base_direction(text: &str, base_dir: BaseDirection) -> BaseDirection {
+    if base_dir != BaseDirection::Auto {
+        return base_dir;
+    }
+    // Hebrew is the first block with strong RTL characters.
+    if !text.chars().any(|ch| ch >= '\u{0590}') {
+        return BaseDirection::Ltr;
+    }
+    match BidiInfo::new(text, None).paragraphs.first() {
+        Some(para) if para.level.is_rtl() => BaseDirection::Rtl,
+        _ => BaseDirection::Ltr,
+    }
+}
+
 fn paragraph_direction(_levels: &[Level], para_level: Level) -> ParagraphDirection {
     if para_level.is_rtl() {
         ParagraphDirection::Rtl
@@ -100,6 +117,22 @@ mod tests {
         assert_eq!(paras[0].direction, ParagraphDirection::Ltr);
     }
 
+    #[test]
+    fn resolves_auto_from_first_strong_character() {
+        assert_eq!(
+            resolve_base_direction("שלום abc", BaseDirection::Auto),
+            BaseDirection::Rtl
+        );
+        assert_eq!(
+            resolve_base_direction("123 abc שלום", BaseDirection::Auto),
+            BaseDirection::Ltr
+        );
+        assert_eq!(
+            resolve_base_direction("abc", BaseDirection::Rtl),
+            BaseDirection::Rtl
+        );
+    }
+
     #[test]
     fn levels_cover_all_bytes() {
         let text = "a אב";
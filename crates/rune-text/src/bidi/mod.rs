@@ -9,6 +9,6 @@ pub mod levels;
 pub mod mirror;
 pub mod reorder;
 
-pub use levels::{BaseDirection, ParagraphBidi, ParagraphDirection};
+pub use levels::{BaseDirection, ParagraphBidi, ParagraphDirection, resolve_base_direction};
 pub use mirror::mirrored_bracket;
-pub use reorder::{BidiRun, reorder_line, visual_runs};
+pub use reorder::{BidiRun, reorder_levels, reorder_line, visual_runs};
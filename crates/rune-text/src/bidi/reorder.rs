@@ -83,6 +83,38 @@ pub fn visual_index_map(text: &str, base_dir: BaseDirection, line: Range<usize>)
     BidiInfo::reorder_visual(&line_levels)
 }
 
+/// Visual order of a line's runs given their embedding levels (rule L2).
+///
+/// `levels` lists one level per run in logical order. The result holds the
+/// logical run indices in left-to-right display order: every maximal
+/// sequence at or above each odd level is reversed, from the highest level
+/// down to the lowest odd one.
+pub fn reorder_levels(levels: &[u8]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..levels.len()).collect();
+    let Some(&max) = levels.iter().max() else {
+        return order;
+    };
+    let min_odd = levels.iter().copied().min().unwrap_or(0) | 1;
+
+    let mut level = max;
+    while level >= min_odd {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < order.len() && levels[order[i]] >= level {
+                i += 1;
+            }
+            order[start..i].reverse();
+        }
+        level -= 1;
+    }
+    order
+}
+
 fn find_paragraph<'text>(
     info: &'text BidiInfo<'text>,
     line: &Range<usize>,
@@ -115,6 +147,16 @@ mod tests {
         assert!(!runs.is_empty());
     }
 
+    #[test]
+    fn reorder_levels_reverses_rtl_sequences() {
+        // LTR paragraph with an RTL run holding a nested LTR number.
+        assert_eq!(reorder_levels(&[0, 1, 2, 1, 0]), vec![0, 3, 2, 1, 4]);
+        // RTL paragraph with embedded LTR text.
+        assert_eq!(reorder_levels(&[1, 2, 2, 1]), vec![3, 1, 2, 0]);
+        assert_eq!(reorder_levels(&[0, 0]), vec![0, 1]);
+        assert!(reorder_levels(&[]).is_empty());
+    }
+
     #[test]
     fn visual_index_map_matches_expected_for_mixed_line() {
         // Mixed LTR + RTL in a single line with LTR base direction.
@@ -0,0 +1,902 @@
+//! Color glyph rasterization.
+//!
+//! Color fonts either embed images per glyph (`CBDT` and `sbix`, used by
+//! most emoji fonts) or describe glyphs as paint graphs over outlines
+//! (`COLR`/`CPAL`). Both are rendered to premultiplied RGBA images here; the
+//! paint graph is walked with skrifa and composited in software.
+
+use skrifa::color::{Brush, ColorPainter, ColorStop, CompositeMode, Extend, Transform};
+use skrifa::instance::{LocationRef, Size};
+use skrifa::outline::{OutlineGlyphCollection, OutlinePen};
+use skrifa::raw::types::BoundingBox;
+use skrifa::{GlyphId, MetadataProvider, Tag};
+use swash::scale::image::{Content, Image};
+use swash::zeno::{Command, Mask, PathBuilder};
+
+use crate::font::FontVariation;
+
+/// Palette index that stands for the text's foreground color.
+const FOREGROUND_INDEX: u16 = 0xFFFF;
+
+/// Largest width or height a color glyph is rendered at, in pixels.
+const MAX_COLOR_GLYPH_SIZE: u32 = 2048;
+
+/// A color glyph rendered to an RGBA image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorGlyphImage {
+    /// Offset of the image's left edge from the pen position, in pixels.
+    pub left: i32,
+    /// Distance from the baseline up to the image's top edge, in pixels.
+    pub top: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Rows of premultiplied sRGB RGBA8 pixels, top to bottom.
+    pub data: Vec<u8>,
+}
+
+impl ColorGlyphImage {
+    /// Convert a swash color bitmap (straight alpha) to a premultiplied
+    /// image. Returns `None` for coverage masks.
+    pub fn from_bitmap(image: Image) -> Option<Self> {
+        if image.content != Content::Color || image.placement.width == 0 {
+            return None;
+        }
+        let mut data = image.data;
+        for px in data.chunks_exact_mut(4) {
+            let a = px[3] as u32;
+            for c in &mut px[..3] {
+                *c = ((*c as u32 * a + 127) / 255) as u8;
+            }
+        }
+        Some(Self {
+            left: image.placement.left,
+            top: image.placement.top,
+            width: image.placement.width,
+            height: image.placement.height,
+            data,
+        })
+    }
+}
+
+/// Render the `COLR` glyph `glyph_id` at `font_size` pixels per em.
+///
+/// `palette` is the `CPAL` palette to draw with and `foreground` the
+/// straight-alpha color used for palette entry `0xFFFF`. Returns `None`
+/// when the glyph has no color layers or paints nothing.
+pub(crate) fn rasterize_colr(
+    data: &[u8],
+    index: u32,
+    variations: &[FontVariation],
+    palette: &[[u8; 4]],
+    glyph_id: u16,
+    font_size: f32,
+    foreground: [u8; 4],
+) -> Option<ColorGlyphImage> {
+    let font = skrifa::FontRef::from_index(data, index).ok()?;
+    let glyph = font.color_glyphs().get(GlyphId::new(glyph_id as u32))?;
+    let location = font
+        .axes()
+        .location(variations.iter().map(|v| (Tag::new(&v.tag), v.value)));
+    let location = LocationRef::from(&location);
+    let upem = font.metrics(Size::unscaled(), location).units_per_em;
+    if upem == 0 || font_size <= 0.0 {
+        return None;
+    }
+    let scale = font_size / upem as f32;
+    let outlines = font.outline_glyphs();
+
+    // Font units are y-up; pixels are y-down.
+    let to_pixels = Transform {
+        xx: scale,
+        yy: -scale,
+        ..Transform::default()
+    };
+    let mut bounds = BoundsPainter::new(&outlines, location, to_pixels);
+    glyph.paint(location, &mut bounds).ok()?;
+    let [x0, y0, x1, y1] = bounds.painted?;
+    let left = x0.floor() as i32;
+    let top = y0.floor() as i32;
+    let width = (x1.ceil() as i32 - left).max(0) as u32;
+    let height = (y1.ceil() as i32 - top).max(0) as u32;
+    if width == 0 || height == 0 || width > MAX_COLOR_GLYPH_SIZE || height > MAX_COLOR_GLYPH_SIZE {
+        return None;
+    }
+
+    let to_canvas = Transform {
+        dx: -left as f32,
+        dy: -top as f32,
+        ..to_pixels
+    };
+    let mut painter = ColrPainter::new(width, height, to_canvas, palette, foreground)
+        .with_outlines(&outlines, location);
+    glyph.paint(location, &mut painter).ok()?;
+    Some(ColorGlyphImage {
+        left,
+        top: -top,
+        width,
+        height,
+        data: painter.finish(),
+    })
+}
+
+fn map_point(t: &Transform, x: f32, y: f32) -> (f32, f32) {
+    (t.xx * x + t.xy * y + t.dx, t.yx * x + t.yy * y + t.dy)
+}
+
+fn invert(t: &Transform) -> Option<Transform> {
+    let det = t.xx * t.yy - t.xy * t.yx;
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv = 1.0 / det;
+    Some(Transform {
+        xx: t.yy * inv,
+        yx: -t.yx * inv,
+        xy: -t.xy * inv,
+        yy: t.xx * inv,
+        dx: (t.xy * t.dy - t.yy * t.dx) * inv,
+        dy: (t.yx * t.dx - t.xx * t.dy) * inv,
+    })
+}
+
+/// Corners of a clip box, in drawing order.
+fn box_corners(clip_box: &BoundingBox<f32>) -> [(f32, f32); 4] {
+    [
+        (clip_box.x_min, clip_box.y_min),
+        (clip_box.x_max, clip_box.y_min),
+        (clip_box.x_max, clip_box.y_max),
+        (clip_box.x_min, clip_box.y_max),
+    ]
+}
+
+/// Pen that maps outline points through a transform into a zeno path.
+struct PathPen<'a> {
+    transform: Transform,
+    path: &'a mut Vec<Command>,
+}
+
+impl OutlinePen for PathPen<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.path.move_to(map_point(&self.transform, x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.path.line_to(map_point(&self.transform, x, y));
+    }
+
+    fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+        let t = &self.transform;
+        self.path
+            .quad_to(map_point(t, cx0, cy0), map_point(t, x, y));
+    }
+
+    fn curve_to(&mut self, cx0: f32, cy0: f32, cx1: f32, cy1: f32, x: f32, y: f32) {
+        let t = &self.transform;
+        self.path.curve_to(
+            map_point(t, cx0, cy0),
+            map_point(t, cx1, cy1),
+            map_point(t, x, y),
+        );
+    }
+
+    fn close(&mut self) {
+        self.path.close();
+    }
+}
+
+/// Outline source for clip glyphs.
+struct Outlines<'a> {
+    glyphs: &'a OutlineGlyphCollection<'a>,
+    location: LocationRef<'a>,
+}
+
+impl Outlines<'_> {
+    /// Path of `glyph_id` in the space `transform` maps font units into.
+    fn path(&self, glyph_id: GlyphId, transform: Transform) -> Vec<Command> {
+        let mut path = Vec::new();
+        if let Some(glyph) = self.glyphs.get(glyph_id) {
+            let mut pen = PathPen {
+                transform,
+                path: &mut path,
+            };
+            let _ = glyph.draw((Size::unscaled(), self.location), &mut pen);
+        }
+        path
+    }
+}
+
+/// Pixel-space rectangle as `[x0, y0, x1, y1]`.
+type Rect = [f32; 4];
+
+fn intersect(a: Option<Rect>, b: Rect) -> Rect {
+    match a {
+        Some(a) => [
+            a[0].max(b[0]),
+            a[1].max(b[1]),
+            a[2].min(b[2]),
+            a[3].min(b[3]),
+        ],
+        None => b,
+    }
+}
+
+fn points_rect(points: impl IntoIterator<Item = (f32, f32)>) -> Rect {
+    let mut rect = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+    for (x, y) in points {
+        rect = [
+            rect[0].min(x),
+            rect[1].min(y),
+            rect[2].max(x),
+            rect[3].max(y),
+        ];
+    }
+    rect
+}
+
+/// First paint pass: finds the pixel area the glyph's fills cover.
+///
+/// Fills are unbounded on their own, so the painted area is the union of
+/// the clip regions active at each fill.
+struct BoundsPainter<'a> {
+    outlines: Outlines<'a>,
+    transforms: Vec<Transform>,
+    clips: Vec<Rect>,
+    painted: Option<Rect>,
+}
+
+impl<'a> BoundsPainter<'a> {
+    fn new(
+        glyphs: &'a OutlineGlyphCollection<'a>,
+        location: LocationRef<'a>,
+        base: Transform,
+    ) -> Self {
+        Self {
+            outlines: Outlines { glyphs, location },
+            transforms: vec![base],
+            clips: Vec::new(),
+            painted: None,
+        }
+    }
+
+    fn transform(&self) -> Transform {
+        *self.transforms.last().expect("base transform")
+    }
+
+    fn push_clip_rect(&mut self, rect: Rect) {
+        let clip = intersect(self.clips.last().copied(), rect);
+        self.clips.push(clip);
+    }
+}
+
+impl ColorPainter for BoundsPainter<'_> {
+    fn push_transform(&mut self, transform: Transform) {
+        let t = self.transform() * transform;
+        self.transforms.push(t);
+    }
+
+    fn pop_transform(&mut self) {
+        if self.transforms.len() > 1 {
+            self.transforms.pop();
+        }
+    }
+
+    fn push_clip_glyph(&mut self, glyph_id: GlyphId) {
+        let path = self.outlines.path(glyph_id, self.transform());
+        let rect = points_rect(path.iter().flat_map(|cmd| {
+            let points: &[_] = match cmd {
+                Command::MoveTo(p) | Command::LineTo(p) => &[*p],
+                Command::QuadTo(c, p) => &[*c, *p],
+                Command::CurveTo(c0, c1, p) => &[*c0, *c1, *p],
+                Command::Close => &[],
+            };
+            points.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>()
+        }));
+        self.push_clip_rect(rect);
+    }
+
+    fn push_clip_box(&mut self, clip_box: BoundingBox<f32>) {
+        let t = self.transform();
+        let rect = points_rect(
+            box_corners(&clip_box)
+                .into_iter()
+                .map(|(x, y)| map_point(&t, x, y)),
+        );
+        self.push_clip_rect(rect);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn fill(&mut self, _brush: Brush<'_>) {
+        let Some(&clip) = self.clips.last() else {
+            return;
+        };
+        if clip[0] >= clip[2] || clip[1] >= clip[3] {
+            return;
+        }
+        self.painted = Some(match self.painted {
+            Some(p) => [
+                p[0].min(clip[0]),
+                p[1].min(clip[1]),
+                p[2].max(clip[2]),
+                p[3].max(clip[3]),
+            ],
+            None => clip,
+        });
+    }
+
+    fn push_layer(&mut self, _composite_mode: CompositeMode) {}
+
+    fn pop_layer(&mut self) {}
+}
+
+/// Premultiplied sRGB color with channels in `0.0..=1.0`.
+type Rgba = [f32; 4];
+
+/// Second paint pass: composites the glyph onto a pixel canvas.
+struct ColrPainter<'a> {
+    width: u32,
+    height: u32,
+    outlines: Option<Outlines<'a>>,
+    palette: &'a [[u8; 4]],
+    foreground: [u8; 4],
+    /// Font-unit to canvas transforms; the last is current.
+    transforms: Vec<Transform>,
+    /// Combined clip coverage per pixel; the last is current.
+    clips: Vec<Vec<f32>>,
+    /// Layers still being drawn, with the mode each merges down with. The
+    /// first is the canvas itself.
+    layers: Vec<(Vec<Rgba>, CompositeMode)>,
+}
+
+impl<'a> ColrPainter<'a> {
+    fn new(
+        width: u32,
+        height: u32,
+        base: Transform,
+        palette: &'a [[u8; 4]],
+        foreground: [u8; 4],
+    ) -> Self {
+        let len = (width * height) as usize;
+        Self {
+            width,
+            height,
+            outlines: None,
+            palette,
+            foreground,
+            transforms: vec![base],
+            clips: Vec::new(),
+            layers: vec![(vec![[0.0; 4]; len], CompositeMode::SrcOver)],
+        }
+    }
+
+    fn with_outlines(
+        mut self,
+        glyphs: &'a OutlineGlyphCollection<'a>,
+        location: LocationRef<'a>,
+    ) -> Self {
+        self.outlines = Some(Outlines { glyphs, location });
+        self
+    }
+
+    fn transform(&self) -> Transform {
+        *self.transforms.last().expect("base transform")
+    }
+
+    /// Canvas pixels as premultiplied RGBA8, flattening unclosed layers.
+    fn finish(mut self) -> Vec<u8> {
+        while self.layers.len() > 1 {
+            self.pop_layer();
+        }
+        let (canvas, _) = self.layers.pop().expect("canvas layer");
+        canvas
+            .iter()
+            .flat_map(|px| px.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+            .collect()
+    }
+
+    /// Intersect the current clip with a pixel-space path.
+    fn push_clip_path(&mut self, path: &[Command]) {
+        let mut coverage = vec![0u8; (self.width * self.height) as usize];
+        if !path.is_empty() {
+            Mask::new(path)
+                .size(self.width, self.height)
+                .render_into(&mut coverage, None);
+        }
+        let mut clip: Vec<f32> = coverage.iter().map(|&c| c as f32 / 255.0).collect();
+        if let Some(outer) = self.clips.last() {
+            for (c, o) in clip.iter_mut().zip(outer) {
+                *c *= o;
+            }
+        }
+        self.clips.push(clip);
+    }
+
+    /// Straight-alpha palette color as premultiplied, scaled by `alpha`.
+    fn palette_color(&self, palette_index: u16, alpha: f32) -> Rgba {
+        let [r, g, b, a] = if palette_index == FOREGROUND_INDEX {
+            self.foreground
+        } else {
+            self.palette
+                .get(palette_index as usize)
+                .copied()
+                .unwrap_or([0; 4])
+        };
+        let a = a as f32 / 255.0 * alpha.clamp(0.0, 1.0);
+        [
+            r as f32 / 255.0 * a,
+            g as f32 / 255.0 * a,
+            b as f32 / 255.0 * a,
+            a,
+        ]
+    }
+
+    /// Color at gradient position `t`, after applying `extend`.
+    fn stop_color(&self, stops: &[ColorStop], t: f32, extend: Extend) -> Rgba {
+        let t = match extend {
+            Extend::Repeat => t - t.floor(),
+            Extend::Reflect => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 { 2.0 - t } else { t }
+            }
+            _ => t,
+        };
+        let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+            return [0.0; 4];
+        };
+        if t <= first.offset {
+            return self.palette_color(first.palette_index, first.alpha);
+        }
+        if t >= last.offset {
+            return self.palette_color(last.palette_index, last.alpha);
+        }
+        let i = stops.partition_point(|s| s.offset <= t).max(1);
+        let (a, b) = (&stops[i - 1], &stops[i]);
+        let span = b.offset - a.offset;
+        let f = if span > 0.0 {
+            (t - a.offset) / span
+        } else {
+            1.0
+        };
+        let ca = self.palette_color(a.palette_index, a.alpha);
+        let cb = self.palette_color(b.palette_index, b.alpha);
+        std::array::from_fn(|k| ca[k] + (cb[k] - ca[k]) * f)
+    }
+}
+
+/// Gradient position of `(x, y)` for a two-point conical gradient, or
+/// `None` where the gradient draws nothing.
+fn radial_t(x: f32, y: f32, c0: (f32, f32), r0: f32, c1: (f32, f32), r1: f32) -> Option<f32> {
+    // Solve |p - c(t)| = r(t) for the largest t with r(t) >= 0, where c and
+    // r interpolate linearly from the start circle to the end circle.
+    let (cdx, cdy) = (c1.0 - c0.0, c1.1 - c0.1);
+    let (pdx, pdy) = (x - c0.0, y - c0.1);
+    let dr = r1 - r0;
+    let a = cdx * cdx + cdy * cdy - dr * dr;
+    let b = pdx * cdx + pdy * cdy + r0 * dr;
+    let c = pdx * pdx + pdy * pdy - r0 * r0;
+    let valid = |t: f32| r0 + t * dr >= 0.0;
+    if a.abs() < 1e-6 {
+        if b.abs() < 1e-6 {
+            return None;
+        }
+        let t = c / (2.0 * b);
+        return valid(t).then_some(t);
+    }
+    let disc = b * b - a * c;
+    if disc < 0.0 {
+        return None;
+    }
+    let root = disc.sqrt();
+    let (t0, t1) = ((b + root) / a, (b - root) / a);
+    let (hi, lo) = if t0 >= t1 { (t0, t1) } else { (t1, t0) };
+    if valid(hi) {
+        Some(hi)
+    } else if valid(lo) {
+        Some(lo)
+    } else {
+        None
+    }
+}
+
+/// Blend function of a separable blend mode, on straight-alpha channels.
+fn blend_channel(mode: CompositeMode, s: f32, d: f32) -> f32 {
+    fn hard_light(s: f32, d: f32) -> f32 {
+        if s <= 0.5 {
+            d * 2.0 * s
+        } else {
+            let s = 2.0 * s - 1.0;
+            d + s - d * s
+        }
+    }
+    match mode {
+        CompositeMode::Screen => s + d - s * d,
+        CompositeMode::Overlay => hard_light(d, s),
+        CompositeMode::Darken => s.min(d),
+        CompositeMode::Lighten => s.max(d),
+        CompositeMode::ColorDodge => {
+            if d <= 0.0 {
+                0.0
+            } else if s >= 1.0 {
+                1.0
+            } else {
+                (d / (1.0 - s)).min(1.0)
+            }
+        }
+        CompositeMode::ColorBurn => {
+            if d >= 1.0 {
+                1.0
+            } else if s <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - d) / s).min(1.0)
+            }
+        }
+        CompositeMode::HardLight => hard_light(s, d),
+        CompositeMode::SoftLight => {
+            if s <= 0.5 {
+                d - (1.0 - 2.0 * s) * d * (1.0 - d)
+            } else {
+                let dd = if d <= 0.25 {
+                    ((16.0 * d - 12.0) * d + 4.0) * d
+                } else {
+                    d.sqrt()
+                };
+                d + (2.0 * s - 1.0) * (dd - d)
+            }
+        }
+        CompositeMode::Difference => (s - d).abs(),
+        CompositeMode::Exclusion => s + d - 2.0 * s * d,
+        CompositeMode::Multiply => s * d,
+        _ => s,
+    }
+}
+
+fn lum(c: [f32; 3]) -> f32 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(c);
+    let c = c.map(|v| v + d);
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    c.map(|v| {
+        let mut v = v;
+        if n < 0.0 && l - n > 0.0 {
+            v = l + (v - l) * l / (l - n);
+        }
+        if x > 1.0 && x - l > 0.0 {
+            v = l + (v - l) * (1.0 - l) / (x - l);
+        }
+        v
+    })
+}
+
+fn sat(c: [f32; 3]) -> f32 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    if x > n {
+        c.map(|v| (v - n) * s / (x - n))
+    } else {
+        [0.0; 3]
+    }
+}
+
+/// Blend function of a non-separable (HSL) blend mode.
+fn blend_hsl(mode: CompositeMode, s: [f32; 3], d: [f32; 3]) -> [f32; 3] {
+    match mode {
+        CompositeMode::HslHue => set_lum(set_sat(s, sat(d)), lum(d)),
+        CompositeMode::HslSaturation => set_lum(set_sat(d, sat(s)), lum(d)),
+        CompositeMode::HslColor => set_lum(s, lum(d)),
+        _ => set_lum(d, lum(s)),
+    }
+}
+
+/// Composite premultiplied `src` onto premultiplied `dst` with `mode`.
+fn composite(mode: CompositeMode, src: Rgba, dst: Rgba) -> Rgba {
+    let (sa, da) = (src[3], dst[3]);
+    let porter_duff =
+        |fa: f32, fb: f32| -> Rgba { std::array::from_fn(|k| src[k] * fa + dst[k] * fb) };
+    match mode {
+        CompositeMode::Clear => [0.0; 4],
+        CompositeMode::Src => src,
+        CompositeMode::Dest => dst,
+        CompositeMode::DestOver => porter_duff(1.0 - da, 1.0),
+        CompositeMode::SrcIn => porter_duff(da, 0.0),
+        CompositeMode::DestIn => porter_duff(0.0, sa),
+        CompositeMode::SrcOut => porter_duff(1.0 - da, 0.0),
+        CompositeMode::DestOut => porter_duff(0.0, 1.0 - sa),
+        CompositeMode::SrcAtop => porter_duff(da, 1.0 - sa),
+        CompositeMode::DestAtop => porter_duff(1.0 - da, sa),
+        CompositeMode::Xor => porter_duff(1.0 - da, 1.0 - sa),
+        CompositeMode::Plus => porter_duff(1.0, 1.0).map(|c| c.min(1.0)),
+        CompositeMode::SrcOver | CompositeMode::Unknown => porter_duff(1.0, 1.0 - sa),
+        _ => {
+            // Blend modes: mix the blended color where both are opaque
+            // with plain source-over elsewhere.
+            let unpremul = |c: Rgba| -> [f32; 3] {
+                if c[3] > 0.0 {
+                    [c[0] / c[3], c[1] / c[3], c[2] / c[3]]
+                } else {
+                    [0.0; 3]
+                }
+            };
+            let (cs, cd) = (unpremul(src), unpremul(dst));
+            let blended = match mode {
+                CompositeMode::HslHue
+                | CompositeMode::HslSaturation
+                | CompositeMode::HslColor
+                | CompositeMode::HslLuminosity => blend_hsl(mode, cs, cd),
+                _ => std::array::from_fn(|k| blend_channel(mode, cs[k], cd[k])),
+            };
+            let mut out = [0.0; 4];
+            for k in 0..3 {
+                out[k] = src[k] * (1.0 - da) + dst[k] * (1.0 - sa) + sa * da * blended[k];
+            }
+            out[3] = sa + da - sa * da;
+            out
+        }
+    }
+}
+
+impl ColorPainter for ColrPainter<'_> {
+    fn push_transform(&mut self, transform: Transform) {
+        let t = self.transform() * transform;
+        self.transforms.push(t);
+    }
+
+    fn pop_transform(&mut self) {
+        if self.transforms.len() > 1 {
+            self.transforms.pop();
+        }
+    }
+
+    fn push_clip_glyph(&mut self, glyph_id: GlyphId) {
+        let path = match &self.outlines {
+            Some(outlines) => outlines.path(glyph_id, self.transform()),
+            None => Vec::new(),
+        };
+        self.push_clip_path(&path);
+    }
+
+    fn push_clip_box(&mut self, clip_box: BoundingBox<f32>) {
+        let t = self.transform();
+        let mut path: Vec<Command> = Vec::new();
+        for (i, (x, y)) in box_corners(&clip_box).into_iter().enumerate() {
+            let p = map_point(&t, x, y);
+            if i == 0 {
+                path.move_to(p);
+            } else {
+                path.line_to(p);
+            }
+        }
+        path.close();
+        self.push_clip_path(&path);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn fill(&mut self, brush: Brush<'_>) {
+        // Gradients are defined in the current font-unit space, so map each
+        // pixel center back into it.
+        let Some(inverse) = invert(&self.transform()) else {
+            return;
+        };
+        let solid = match &brush {
+            Brush::Solid {
+                palette_index,
+                alpha,
+            } => Some(self.palette_color(*palette_index, *alpha)),
+            _ => None,
+        };
+        let width = self.width as usize;
+        let mut layer = std::mem::take(&mut self.layers.last_mut().expect("canvas layer").0);
+        for (i, dst) in layer.iter_mut().enumerate() {
+            let coverage = self.clips.last().map_or(1.0, |clip| clip[i]);
+            if coverage <= 0.0 {
+                continue;
+            }
+            let color = match solid {
+                Some(color) => color,
+                None => {
+                    let px = (i % width) as f32 + 0.5;
+                    let py = (i / width) as f32 + 0.5;
+                    let (x, y) = map_point(&inverse, px, py);
+                    match &brush {
+                        Brush::LinearGradient {
+                            p0,
+                            p1,
+                            color_stops,
+                            extend,
+                        } => {
+                            let (dx, dy) = (p1.x - p0.x, p1.y - p0.y);
+                            let len2 = dx * dx + dy * dy;
+                            let t = if len2 > 0.0 {
+                                ((x - p0.x) * dx + (y - p0.y) * dy) / len2
+                            } else {
+                                0.0
+                            };
+                            self.stop_color(color_stops, t, *extend)
+                        }
+                        Brush::RadialGradient {
+                            c0,
+                            r0,
+                            c1,
+                            r1,
+                            color_stops,
+                            extend,
+                        } => match radial_t(x, y, (c0.x, c0.y), *r0, (c1.x, c1.y), *r1) {
+                            Some(t) => self.stop_color(color_stops, t, *extend),
+                            None => continue,
+                        },
+                        Brush::SweepGradient {
+                            c0,
+                            start_angle,
+                            end_angle,
+                            color_stops,
+                            extend,
+                        } => {
+                            // skrifa hands sweep angles over clockwise.
+                            let ccw = (y - c0.y).atan2(x - c0.x).to_degrees();
+                            let angle = (360.0 - ccw).rem_euclid(360.0);
+                            let sector = end_angle - start_angle;
+                            let t = if sector != 0.0 {
+                                (angle - start_angle) / sector
+                            } else if angle < *start_angle {
+                                0.0
+                            } else {
+                                1.0
+                            };
+                            self.stop_color(color_stops, t, *extend)
+                        }
+                        Brush::Solid { .. } => unreachable!("solid brushes are resolved above"),
+                    }
+                }
+            };
+            let src = color.map(|c| c * coverage);
+            *dst = composite(CompositeMode::SrcOver, src, *dst);
+        }
+        self.layers.last_mut().expect("canvas layer").0 = layer;
+    }
+
+    fn push_layer(&mut self, composite_mode: CompositeMode) {
+        let len = (self.width * self.height) as usize;
+        self.layers.push((vec![[0.0; 4]; len], composite_mode));
+    }
+
+    fn pop_layer(&mut self) {
+        if self.layers.len() < 2 {
+            return;
+        }
+        let (src, mode) = self.layers.pop().expect("layer");
+        let clip = self.clips.last();
+        let dst = &mut self.layers.last_mut().expect("canvas layer").0;
+        for (i, (d, s)) in dst.iter_mut().zip(&src).enumerate() {
+            let merged = composite(mode, *s, *d);
+            let coverage = clip.map_or(1.0, |clip| clip[i]);
+            *d = std::array::from_fn(|k| d[k] + (merged[k] - d[k]) * coverage);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skrifa::raw::types::Point;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    fn clip_box(x_min: f32, y_min: f32, x_max: f32, y_max: f32) -> BoundingBox<f32> {
+        BoundingBox {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        }
+    }
+
+    fn pixel(data: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * width + x) * 4) as usize;
+        [data[i], data[i + 1], data[i + 2], data[i + 3]]
+    }
+
+    #[test]
+    fn solid_fill_is_clipped_and_premultiplied() {
+        let palette = [RED];
+        let mut painter = ColrPainter::new(4, 4, Transform::default(), &palette, BLUE);
+        painter.push_clip_box(clip_box(0.0, 0.0, 2.0, 4.0));
+        painter.fill(Brush::Solid {
+            palette_index: 0,
+            alpha: 0.5,
+        });
+        painter.pop_clip();
+        let data = painter.finish();
+        assert_eq!(pixel(&data, 4, 0, 0), [128, 0, 0, 128]);
+        assert_eq!(pixel(&data, 4, 3, 3), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn foreground_index_uses_text_color() {
+        let mut painter = ColrPainter::new(2, 2, Transform::default(), &[], BLUE);
+        painter.fill(Brush::Solid {
+            palette_index: FOREGROUND_INDEX,
+            alpha: 1.0,
+        });
+        assert_eq!(pixel(&painter.finish(), 2, 1, 1), BLUE);
+    }
+
+    #[test]
+    fn linear_gradient_interpolates_between_stops() {
+        let palette = [RED, BLUE];
+        let stops = [
+            ColorStop {
+                offset: 0.0,
+                palette_index: 0,
+                alpha: 1.0,
+            },
+            ColorStop {
+                offset: 1.0,
+                palette_index: 1,
+                alpha: 1.0,
+            },
+        ];
+        let mut painter = ColrPainter::new(8, 1, Transform::default(), &palette, BLUE);
+        painter.fill(Brush::LinearGradient {
+            p0: Point::new(0.0, 0.0),
+            p1: Point::new(8.0, 0.0),
+            color_stops: &stops,
+            extend: Extend::Pad,
+        });
+        let data = painter.finish();
+        let left = pixel(&data, 8, 0, 0);
+        let right = pixel(&data, 8, 7, 0);
+        assert!(left[0] > 200 && left[2] < 50, "{left:?}");
+        assert!(right[2] > 200 && right[0] < 50, "{right:?}");
+        assert_eq!(left[3], 255);
+    }
+
+    #[test]
+    fn layers_merge_with_their_composite_mode() {
+        let palette = [RED, BLUE];
+        let mut painter = ColrPainter::new(4, 1, Transform::default(), &palette, BLUE);
+        painter.fill(Brush::Solid {
+            palette_index: 0,
+            alpha: 1.0,
+        });
+        // Punch the right half out of the red fill.
+        painter.push_layer(CompositeMode::DestOut);
+        painter.push_clip_box(clip_box(2.0, 0.0, 4.0, 1.0));
+        painter.fill(Brush::Solid {
+            palette_index: 1,
+            alpha: 1.0,
+        });
+        painter.pop_clip();
+        painter.pop_layer();
+        let data = painter.finish();
+        assert_eq!(pixel(&data, 4, 0, 0), RED);
+        assert_eq!(pixel(&data, 4, 3, 0), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn color_bitmaps_are_premultiplied() {
+        let mut image = Image::new();
+        image.content = Content::Color;
+        image.placement.width = 1;
+        image.placement.height = 1;
+        image.placement.top = 7;
+        image.data = vec![255, 100, 0, 128];
+        let glyph = ColorGlyphImage::from_bitmap(image).unwrap();
+        assert_eq!(glyph.data, vec![128, 50, 0, 128]);
+        assert_eq!(glyph.top, 7);
+    }
+}
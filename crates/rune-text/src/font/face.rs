@@ -5,7 +5,8 @@ use swash::scale::outline::Outline;
 use swash::scale::{ScaleContext, StrikeWith};
 use swash::{FontRef, GlyphId, Metrics};
 
-use crate::font::{FontError, FontMetrics, Result, ScaledFontMetrics};
+use crate::font::color::{self, ColorGlyphImage};
+use crate::font::{FontError, FontMetrics, FontSettings, Result, ScaledFontMetrics};
 
 /// Loaded font face backed by a font file (TTF/OTF).
 ///
@@ -16,12 +17,17 @@ use crate::font::{FontError, FontMetrics, Result, ScaledFontMetrics};
 pub struct FontFace {
     /// Full font data.
     data: Arc<[u8]>,
+    /// Index of this face within the font file (for collections).
+    index: u32,
     /// Offset to the table directory for this font.
     offset: u32,
     /// Cache key used internally by swash.
     key: swash::CacheKey,
     /// Extracted font metrics in font units.
     metrics: FontMetrics,
+    /// OpenType features and variation axis values applied when shaping
+    /// and scaling glyphs.
+    settings: FontSettings,
 }
 
 impl FontFace {
@@ -32,9 +38,11 @@ impl FontFace {
         let (offset, key) = (font.offset, font.key);
         Ok(Self {
             data,
+            index: index as u32,
             offset,
             key,
             metrics,
+            settings: FontSettings::default(),
         })
     }
 
@@ -55,6 +63,54 @@ impl FontFace {
         self.data.clone()
     }
 
+    /// Index of this face within its font file. Non-zero only for faces
+    /// loaded from collections (`.ttc`/`.otc`).
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Whether `other` is this face, loaded from the same data, with the
+    /// same settings, so it shapes text identically.
+    pub fn is_same_face(&self, other: &FontFace) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+            && self.index == other.index
+            && self.settings == other.settings
+    }
+
+    /// Glyph id the font's character map assigns to `ch`, if any.
+    pub fn glyph_id(&self, ch: char) -> Option<GlyphId> {
+        match self.as_swash_ref().charmap().map(ch) {
+            0 => None,
+            id => Some(id),
+        }
+    }
+
+    /// Whether the font has a glyph for `ch`.
+    pub fn has_glyph(&self, ch: char) -> bool {
+        self.glyph_id(ch).is_some()
+    }
+
+    /// This face with OpenType features and variation axis values applied,
+    /// replacing any previous settings. Shaping uses the features and
+    /// variations; glyph outlines and bitmaps use the variations.
+    pub fn with_settings(mut self, settings: FontSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Features and variations applied to this face.
+    pub fn settings(&self) -> &FontSettings {
+        &self.settings
+    }
+
+    /// Whether the font defines the variation axis `tag` (e.g. `*b"wght"`).
+    pub fn has_variation_axis(&self, tag: [u8; 4]) -> bool {
+        let tag = swash::tag_from_bytes(&tag);
+        self.as_swash_ref()
+            .variations()
+            .any(|axis| axis.tag() == tag)
+    }
+
     /// Return a transient `FontRef` for interacting with swash APIs.
     fn as_swash_ref(&self) -> FontRef<'_> {
         FontRef {
@@ -105,7 +161,11 @@ impl FontFace {
     pub fn glyph_outline(&self, glyph_id: GlyphId, font_size: f32) -> Option<Outline> {
         let mut context = ScaleContext::new();
         let font = self.as_swash_ref();
-        let mut scaler = context.builder(font).size(font_size).build();
+        let mut scaler = context
+            .builder(font)
+            .size(font_size)
+            .variations(self.settings.variations.iter().map(|v| v.to_swash()))
+            .build();
         scaler.scale_outline(glyph_id)
     }
 
@@ -113,7 +173,11 @@ impl FontFace {
     pub fn glyph_bitmap(&self, glyph_id: GlyphId, font_size: f32) -> Option<Image> {
         let mut context = ScaleContext::new();
         let font = self.as_swash_ref();
-        let mut scaler = context.builder(font).size(font_size).build();
+        let mut scaler = context
+            .builder(font)
+            .size(font_size)
+            .variations(self.settings.variations.iter().map(|v| v.to_swash()))
+            .build();
         scaler.scale_bitmap(glyph_id, StrikeWith::BestFit)
     }
 
@@ -121,7 +185,52 @@ impl FontFace {
     pub fn glyph_color_bitmap(&self, glyph_id: GlyphId, font_size: f32) -> Option<Image> {
         let mut context = ScaleContext::new();
         let font = self.as_swash_ref();
-        let mut scaler = context.builder(font).size(font_size).build();
+        let mut scaler = context
+            .builder(font)
+            .size(font_size)
+            .variations(self.settings.variations.iter().map(|v| v.to_swash()))
+            .build();
         scaler.scale_color_bitmap(glyph_id, StrikeWith::BestFit)
     }
+
+    /// Whether the font has color glyphs: `COLR` layers or `CBDT`/`sbix`
+    /// images.
+    pub fn has_color_glyphs(&self) -> bool {
+        let font = self.as_swash_ref();
+        [b"COLR", b"CBDT", b"sbix"]
+            .iter()
+            .any(|tag| font.table(swash::tag_from_bytes(tag)).is_some())
+    }
+
+    /// Render a color glyph at the specified size as a premultiplied RGBA
+    /// image, if the glyph has color.
+    ///
+    /// `COLR` glyphs are drawn with the font's first palette; entries that
+    /// refer to the text color use `foreground` (straight-alpha RGBA).
+    pub fn glyph_color_image(
+        &self,
+        glyph_id: GlyphId,
+        font_size: f32,
+        foreground: [u8; 4],
+    ) -> Option<ColorGlyphImage> {
+        let font = self.as_swash_ref();
+        let palette: Vec<[u8; 4]> = font
+            .color_palettes()
+            .next()
+            .map(|p| (0..p.len()).map(|i| p.get(i)).collect())
+            .unwrap_or_default();
+        color::rasterize_colr(
+            &self.data,
+            self.index,
+            &self.settings.variations,
+            &palette,
+            glyph_id,
+            font_size,
+            foreground,
+        )
+        .or_else(|| {
+            self.glyph_color_bitmap(glyph_id, font_size)
+                .and_then(ColorGlyphImage::from_bitmap)
+        })
+    }
 }
@@ -0,0 +1,262 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::font::{FontFace, FontSettings};
+
+/// Families consulted, in order, when building a system fallback chain.
+///
+/// The list covers the scripts and emoji most likely to be missing from a
+/// Latin UI font on macOS, Windows and common Linux distributions. Families
+/// that aren't installed are skipped.
+pub const SYSTEM_FALLBACK_FAMILIES: &[&str] = &[
+    // Broad Latin/Greek/Cyrillic coverage
+    "Noto Sans",
+    "DejaVu Sans",
+    "Segoe UI",
+    "Helvetica Neue",
+    // Arabic
+    "Noto Sans Arabic",
+    "Geeza Pro",
+    // Hebrew
+    "Noto Sans Hebrew",
+    "Arial Hebrew",
+    // Indic
+    "Noto Sans Devanagari",
+    "Kohinoor Devanagari",
+    "Nirmala UI",
+    "Noto Sans Bengali",
+    "Noto Sans Tamil",
+    // Southeast Asian
+    "Noto Sans Thai",
+    "Thonburi",
+    "Leelawadee UI",
+    // CJK
+    "Noto Sans CJK SC",
+    "Noto Sans SC",
+    "PingFang SC",
+    "Hiragino Sans",
+    "Microsoft YaHei",
+    "Noto Sans CJK JP",
+    "Noto Sans CJK KR",
+    "Apple SD Gothic Neo",
+    "Malgun Gothic",
+    // Emoji and symbols
+    "Noto Color Emoji",
+    "Apple Color Emoji",
+    "Segoe UI Emoji",
+    "Noto Sans Symbols",
+    "Noto Sans Symbols 2",
+    "Segoe UI Symbol",
+];
+
+/// Ordered list of font faces used to render text.
+///
+/// The first face is the primary font; the rest are consulted in order for
+/// characters the earlier faces don't cover. A shaped run's `font_id` is the
+/// index of its face in the chain.
+#[derive(Debug, Clone)]
+pub struct FontFallbackChain {
+    faces: Vec<FontFace>,
+}
+
+impl FontFallbackChain {
+    /// Chain containing only `primary`.
+    pub fn new(primary: FontFace) -> Self {
+        Self {
+            faces: vec![primary],
+        }
+    }
+
+    /// Chain of `primary` followed by `fallbacks` in order.
+    pub fn with_fallbacks(
+        primary: FontFace,
+        fallbacks: impl IntoIterator<Item = FontFace>,
+    ) -> Self {
+        let mut faces = vec![primary];
+        faces.extend(fallbacks);
+        Self { faces }
+    }
+
+    /// Chain of `primary` followed by the installed faces of
+    /// [`SYSTEM_FALLBACK_FAMILIES`], discovered through `fontdb`.
+    pub fn with_system_fallbacks(primary: FontFace) -> Self {
+        let mut db = fontdb::Database::new();
+        db.load_system_fonts();
+        Self::with_fallbacks(primary, fallback_faces_from_database(&db))
+    }
+
+    /// Apply the same features and variation axis values to every face, so
+    /// fallback glyphs match the primary font's numerals, ligatures and
+    /// weight where the faces support them.
+    pub fn with_settings(mut self, settings: &FontSettings) -> Self {
+        for face in &mut self.faces {
+            *face = face.clone().with_settings(settings.clone());
+        }
+        self
+    }
+
+    /// Append a face to the end of the chain.
+    pub fn push(&mut self, face: FontFace) {
+        self.faces.push(face);
+    }
+
+    pub fn primary(&self) -> &FontFace {
+        &self.faces[0]
+    }
+
+    /// All faces, primary first.
+    pub fn faces(&self) -> &[FontFace] {
+        &self.faces
+    }
+
+    /// Faces after the primary.
+    pub fn fallbacks(&self) -> &[FontFace] {
+        &self.faces[1..]
+    }
+
+    /// Face for a shaped run's `font_id`.
+    pub fn get(&self, font_id: u32) -> Option<&FontFace> {
+        self.faces.get(font_id as usize)
+    }
+
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// Index of the face used for a grapheme cluster.
+    ///
+    /// Prefers the first face that covers every character of the cluster
+    /// (ignoring joiners and variation selectors), then the first face that
+    /// covers its base character, and otherwise the primary face so missing
+    /// glyphs render as the primary font's `.notdef`.
+    pub fn font_for_cluster(&self, cluster: &str) -> u32 {
+        let mut chars = cluster.chars().filter(|&ch| !is_default_ignorable(ch));
+        let Some(base) = chars.next() else {
+            return 0;
+        };
+        if let Some(idx) = self
+            .faces
+            .iter()
+            .position(|f| f.has_glyph(base) && chars.clone().all(|ch| f.has_glyph(ch)))
+        {
+            return idx as u32;
+        }
+        self.faces
+            .iter()
+            .position(|f| f.has_glyph(base))
+            .unwrap_or(0) as u32
+    }
+
+    /// Whether some face in the chain covers every grapheme of `text`.
+    pub fn covers(&self, text: &str) -> bool {
+        text.graphemes(true).all(|g| {
+            let id = self.font_for_cluster(g);
+            g.chars()
+                .filter(|&ch| !is_default_ignorable(ch))
+                .all(|ch| self.faces[id as usize].has_glyph(ch))
+        })
+    }
+}
+
+/// Load the installed faces of [`SYSTEM_FALLBACK_FAMILIES`] from `db`, at
+/// regular weight and upright style, skipping families that aren't present
+/// and faces that fail to parse.
+pub fn fallback_faces_from_database(db: &fontdb::Database) -> Vec<FontFace> {
+    use fontdb::{Family, Query};
+
+    let mut seen = Vec::new();
+    let mut faces = Vec::new();
+    for name in SYSTEM_FALLBACK_FAMILIES {
+        let Some(id) = db.query(&Query {
+            families: &[Family::Name(name)],
+            ..Query::default()
+        }) else {
+            continue;
+        };
+        if seen.contains(&id) {
+            continue;
+        }
+        seen.push(id);
+        if let Some(Ok(face)) = db.with_face_data(id, |data, index| {
+            FontFace::from_vec(data.to_vec(), index as usize)
+        }) {
+            faces.push(face);
+        }
+    }
+    faces
+}
+
+/// Characters that never need a glyph of their own: joiners, variation
+/// selectors and other default-ignorable code points.
+fn is_default_ignorable(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{E0000}'..='\u{E0FFF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font(path: &str) -> FontFace {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap();
+        FontFace::from_path(root.join(path), 0).expect("bundled font")
+    }
+
+    fn geist() -> FontFace {
+        font("fonts/Geist/static/Geist-Regular.ttf")
+    }
+
+    fn noto() -> FontFace {
+        font("fonts/Noto_Sans/static/NotoSans-Regular.ttf")
+    }
+
+    #[test]
+    fn primary_covers_latin() {
+        let chain = FontFallbackChain::with_fallbacks(geist(), [noto()]);
+        assert_eq!(chain.font_for_cluster("a"), 0);
+        assert!(chain.covers("Hello"));
+    }
+
+    #[test]
+    fn falls_back_for_uncovered_characters() {
+        let primary = geist();
+        let fallback = noto();
+        // Find a character Noto Sans has and Geist doesn't.
+        let ch = ['ǅ', 'Ѩ', 'Ꞌ', 'ẞ', 'Ƣ', 'ʭ', 'Ϗ', 'Ӂ', '₸', '₼']
+            .into_iter()
+            .find(|&c| !primary.has_glyph(c) && fallback.has_glyph(c))
+            .expect("a glyph only in Noto Sans");
+        let chain = FontFallbackChain::with_fallbacks(primary, [fallback]);
+        assert_eq!(chain.font_for_cluster(&ch.to_string()), 1);
+    }
+
+    #[test]
+    fn uncovered_clusters_use_primary() {
+        let chain = FontFallbackChain::new(geist());
+        assert_eq!(chain.font_for_cluster("\u{10FFFD}"), 0);
+        assert!(!chain.covers("\u{10FFFD}"));
+    }
+
+    #[test]
+    fn ignorables_do_not_force_fallback() {
+        let chain = FontFallbackChain::with_fallbacks(geist(), [noto()]);
+        assert_eq!(chain.font_for_cluster("a\u{FE0F}"), 0);
+        assert_eq!(chain.font_for_cluster("\u{200D}"), 0);
+    }
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use hashbrown::HashMap;
 
+use crate::font::fallback::{FontFallbackChain, fallback_faces_from_database};
 use crate::font::{FontError, FontFace, Result};
 
 /// Key for identifying a font within the cache.
@@ -69,10 +70,25 @@ pub fn load_font(path: impl AsRef<Path>, index: usize) -> Result<FontFace> {
 /// This mirrors the selection used by `engine-core::RuneTextProvider::from_system_fonts`
 /// so that layout metrics match the primary rendering path.
 pub fn load_system_default_font() -> Result<FontFace> {
-    use fontdb::{Database, Family, Query, Source, Stretch, Style, Weight};
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+    default_font_from_database(&db)
+}
 
-    let mut db = Database::new();
+/// Load the default system font followed by the installed faces of
+/// [`SYSTEM_FALLBACK_FAMILIES`](crate::font::fallback::SYSTEM_FALLBACK_FAMILIES).
+pub fn load_system_fallback_chain() -> Result<FontFallbackChain> {
+    let mut db = fontdb::Database::new();
     db.load_system_fonts();
+    let primary = default_font_from_database(&db)?;
+    Ok(FontFallbackChain::with_fallbacks(
+        primary,
+        fallback_faces_from_database(&db),
+    ))
+}
+
+fn default_font_from_database(db: &fontdb::Database) -> Result<FontFace> {
+    use fontdb::{Family, Query, Source, Stretch, Style, Weight};
 
     let id = db
         .query(&Query {
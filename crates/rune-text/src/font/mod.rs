@@ -1,10 +1,16 @@
+pub mod color;
 pub mod face;
+pub mod fallback;
 pub mod loader;
 pub mod metrics;
+pub mod settings;
 
+pub use color::ColorGlyphImage;
 pub use face::FontFace;
-pub use loader::{FontCache, FontKey, load_system_default_font};
+pub use fallback::{FontFallbackChain, SYSTEM_FALLBACK_FAMILIES};
+pub use loader::{FontCache, FontKey, load_system_default_font, load_system_fallback_chain};
 pub use metrics::{FontMetrics, ScaledFontMetrics};
+pub use settings::{FontFeature, FontSettings, FontVariation};
 
 use core::fmt;
 
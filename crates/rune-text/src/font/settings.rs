@@ -0,0 +1,311 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// OpenType feature setting, e.g. `liga` off or `tnum` on.
+///
+/// `value` is `1` to enable a feature and `0` to disable it; features with
+/// alternates (`salt`, `cvNN`, `aalt`) take the 1-based alternate index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FontFeature {
+    pub tag: [u8; 4],
+    pub value: u32,
+}
+
+impl FontFeature {
+    pub const fn new(tag: [u8; 4], value: u32) -> Self {
+        Self { tag, value }
+    }
+
+    /// Enable the feature `tag`.
+    pub const fn on(tag: [u8; 4]) -> Self {
+        Self::new(tag, 1)
+    }
+
+    /// Disable the feature `tag`.
+    pub const fn off(tag: [u8; 4]) -> Self {
+        Self::new(tag, 0)
+    }
+
+    /// Standard ligatures (`liga`), on by default in most shapers.
+    pub const fn ligatures(enabled: bool) -> Self {
+        Self::new(*b"liga", enabled as u32)
+    }
+
+    /// Contextual alternates (`calt`), on by default in most shapers.
+    pub const fn contextual_alternates(enabled: bool) -> Self {
+        Self::new(*b"calt", enabled as u32)
+    }
+
+    /// Kerning (`kern`), on by default in most shapers.
+    pub const fn kerning(enabled: bool) -> Self {
+        Self::new(*b"kern", enabled as u32)
+    }
+
+    /// Tabular (fixed-width) figures (`tnum`), for aligned columns of numbers.
+    pub const fn tabular_numbers() -> Self {
+        Self::on(*b"tnum")
+    }
+
+    /// Proportional figures (`pnum`).
+    pub const fn proportional_numbers() -> Self {
+        Self::on(*b"pnum")
+    }
+
+    /// Slashed zero (`zero`).
+    pub const fn slashed_zero() -> Self {
+        Self::on(*b"zero")
+    }
+
+    /// Small capitals from lowercase letters (`smcp`).
+    pub const fn small_caps() -> Self {
+        Self::on(*b"smcp")
+    }
+
+    /// Stylistic set `ss01`..=`ss20`; `None` if `set` is outside `1..=20`.
+    pub const fn stylistic_set(set: u8) -> Option<Self> {
+        if set == 0 || set > 20 {
+            return None;
+        }
+        Some(Self::on([b's', b's', b'0' + set / 10, b'0' + set % 10]))
+    }
+
+    /// Parse one entry of CSS `font-feature-settings` syntax: a tag
+    /// (optionally quoted) followed by an optional `on`, `off` or integer
+    /// value, e.g. `"tnum"`, `"liga" 0` or `ss02 on`.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let mut parts = entry.split_whitespace();
+        let tag = parse_tag(parts.next()?)?;
+        let value = match parts.next() {
+            None | Some("on") => 1,
+            Some("off") => 0,
+            Some(n) => n.parse().ok()?,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self::new(tag, value))
+    }
+
+    /// Parse a comma-separated CSS `font-feature-settings` list. `normal`
+    /// and the empty string yield no features; any malformed entry makes the
+    /// whole list invalid, as in CSS.
+    pub fn parse_list(list: &str) -> Option<Vec<Self>> {
+        parse_list(list, Self::parse)
+    }
+
+    pub(crate) fn to_harfrust(self) -> harfrust::Feature {
+        harfrust::Feature::new(harfrust::Tag::new(&self.tag), self.value, ..)
+    }
+}
+
+impl fmt::Display for FontFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" {}", tag_str(&self.tag), self.value)
+    }
+}
+
+/// Value for a variable-font design axis, e.g. `wght` 650.
+///
+/// Axes the face doesn't define are ignored; values outside an axis' range
+/// are clamped by the font.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FontVariation {
+    pub tag: [u8; 4],
+    pub value: f32,
+}
+
+impl FontVariation {
+    pub const fn new(tag: [u8; 4], value: f32) -> Self {
+        Self { tag, value }
+    }
+
+    /// Weight axis (`wght`), 1–1000 with 400 regular and 700 bold.
+    pub const fn weight(value: f32) -> Self {
+        Self::new(*b"wght", value)
+    }
+
+    /// Width axis (`wdth`), as a percentage of normal width.
+    pub const fn width(value: f32) -> Self {
+        Self::new(*b"wdth", value)
+    }
+
+    /// Optical size axis (`opsz`), usually matched to the font size in points.
+    pub const fn optical_size(value: f32) -> Self {
+        Self::new(*b"opsz", value)
+    }
+
+    /// Slant axis (`slnt`), in degrees; negative values lean right.
+    pub const fn slant(value: f32) -> Self {
+        Self::new(*b"slnt", value)
+    }
+
+    /// Parse one entry of CSS `font-variation-settings` syntax: a tag
+    /// (optionally quoted) followed by a number, e.g. `"wght" 650`.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let mut parts = entry.split_whitespace();
+        let tag = parse_tag(parts.next()?)?;
+        let value: f32 = parts.next()?.parse().ok()?;
+        if parts.next().is_some() || !value.is_finite() {
+            return None;
+        }
+        Some(Self::new(tag, value))
+    }
+
+    /// Parse a comma-separated CSS `font-variation-settings` list. `normal`
+    /// and the empty string yield no variations; any malformed entry makes
+    /// the whole list invalid, as in CSS.
+    pub fn parse_list(list: &str) -> Option<Vec<Self>> {
+        parse_list(list, Self::parse)
+    }
+
+    pub(crate) fn to_harfrust(self) -> harfrust::Variation {
+        harfrust::Variation {
+            tag: harfrust::Tag::new(&self.tag),
+            value: self.value,
+        }
+    }
+
+    pub(crate) fn to_swash(self) -> swash::Setting<f32> {
+        (swash::tag_from_bytes(&self.tag), self.value).into()
+    }
+}
+
+impl core::hash::Hash for FontVariation {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+        self.value.to_bits().hash(state);
+    }
+}
+
+impl fmt::Display for FontVariation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" {}", tag_str(&self.tag), self.value)
+    }
+}
+
+/// OpenType features and variation axis values applied when shaping and
+/// rasterizing a [`FontFace`](crate::FontFace).
+///
+/// Later entries for the same tag replace earlier ones.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontSettings {
+    pub features: Vec<FontFeature>,
+    pub variations: Vec<FontVariation>,
+}
+
+impl FontSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether no features or variations are set.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty() && self.variations.is_empty()
+    }
+
+    /// Add `feature`, replacing any earlier setting for the same tag.
+    pub fn with_feature(mut self, feature: FontFeature) -> Self {
+        self.set_feature(feature);
+        self
+    }
+
+    /// Add `variation`, replacing any earlier value for the same axis.
+    pub fn with_variation(mut self, variation: FontVariation) -> Self {
+        self.set_variation(variation);
+        self
+    }
+
+    pub fn set_feature(&mut self, feature: FontFeature) {
+        self.features.retain(|f| f.tag != feature.tag);
+        self.features.push(feature);
+    }
+
+    pub fn set_variation(&mut self, variation: FontVariation) {
+        self.variations.retain(|v| v.tag != variation.tag);
+        self.variations.push(variation);
+    }
+
+    /// Value set for the variation axis `tag`, if any.
+    pub fn variation(&self, tag: [u8; 4]) -> Option<f32> {
+        self.variations
+            .iter()
+            .rev()
+            .find(|v| v.tag == tag)
+            .map(|v| v.value)
+    }
+}
+
+fn parse_tag(token: &str) -> Option<[u8; 4]> {
+    let token = token
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .or_else(|| token.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))
+        .unwrap_or(token);
+    let bytes: [u8; 4] = token.as_bytes().try_into().ok()?;
+    bytes
+        .iter()
+        .all(|b| (0x20..=0x7e).contains(b))
+        .then_some(bytes)
+}
+
+fn parse_list<T>(list: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    let list = list.trim();
+    if list.is_empty() || list == "normal" {
+        return Some(Vec::new());
+    }
+    list.split(',').map(|entry| parse(entry.trim())).collect()
+}
+
+fn tag_str(tag: &[u8; 4]) -> &str {
+    core::str::from_utf8(tag).unwrap_or("????")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_css_feature_settings() {
+        let features = FontFeature::parse_list("\"tnum\", \"liga\" 0, ss02 on, 'salt' 3").unwrap();
+        assert_eq!(
+            features,
+            vec![
+                FontFeature::tabular_numbers(),
+                FontFeature::ligatures(false),
+                FontFeature::stylistic_set(2).unwrap(),
+                FontFeature::new(*b"salt", 3),
+            ]
+        );
+        assert_eq!(FontFeature::parse_list("normal"), Some(Vec::new()));
+        assert_eq!(FontFeature::parse_list("\"tnum\", \"toolong\""), None);
+        assert_eq!(FontFeature::stylistic_set(21), None);
+        assert_eq!(&FontFeature::stylistic_set(20).unwrap().tag, b"ss20");
+    }
+
+    #[test]
+    fn parses_css_variation_settings() {
+        let variations = FontVariation::parse_list("\"wght\" 650, \"opsz\" 32.5").unwrap();
+        assert_eq!(
+            variations,
+            vec![
+                FontVariation::weight(650.0),
+                FontVariation::optical_size(32.5)
+            ]
+        );
+        assert_eq!(FontVariation::parse_list("\"wght\""), None);
+        assert_eq!(FontVariation::parse_list("\"wght\" bold"), None);
+    }
+
+    #[test]
+    fn later_settings_replace_earlier_ones() {
+        let settings = FontSettings::new()
+            .with_feature(FontFeature::ligatures(true))
+            .with_variation(FontVariation::weight(300.0))
+            .with_feature(FontFeature::ligatures(false))
+            .with_variation(FontVariation::weight(800.0));
+        assert_eq!(settings.features, vec![FontFeature::ligatures(false)]);
+        assert_eq!(settings.variation(*b"wght"), Some(800.0));
+        assert_eq!(settings.variation(*b"wdth"), None);
+    }
+}
@@ -0,0 +1,215 @@
+//! Hyphenation: where words may be broken across lines.
+//!
+//! Soft hyphens (U+00AD) mark break points by hand. Automatic hyphenation
+//! asks a [`Hyphenator`] for the break points of each word;
+//! [`PatternHyphenator`] implements Liang's algorithm over TeX hyphenation
+//! patterns, the format the hyphenation dictionaries of most languages are
+//! published in (e.g. `hyph-en-us.pat.txt`).
+
+use std::fmt;
+use std::sync::Arc;
+
+use hashbrown::HashMap;
+
+/// Soft hyphen: an invisible break point that shows a hyphen when a line
+/// breaks there.
+pub const SOFT_HYPHEN: char = '\u{AD}';
+
+/// Finds the points where a word may be hyphenated.
+pub trait Hyphenator: Send + Sync {
+    /// Byte offsets within `word` where it may be broken with a hyphen,
+    /// ascending. `word` is a run of letters without spaces or punctuation.
+    fn hyphenate(&self, word: &str) -> Vec<usize>;
+}
+
+/// How words are hyphenated when wrapping, like CSS `hyphens`.
+#[derive(Clone, Default)]
+pub enum Hyphens {
+    /// Never break inside words; soft hyphens are ignored.
+    None,
+    /// Break only at soft hyphens.
+    #[default]
+    Manual,
+    /// Break at soft hyphens and at the points found by a hyphenator.
+    Auto(Arc<dyn Hyphenator>),
+}
+
+impl fmt::Debug for Hyphens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hyphens::None => f.write_str("None"),
+            Hyphens::Manual => f.write_str("Manual"),
+            Hyphens::Auto(_) => f.write_str("Auto(..)"),
+        }
+    }
+}
+
+/// Hyphenation by Liang's algorithm from TeX patterns.
+///
+/// Each pattern is a letter sequence interleaved with digits, such as
+/// `hy3ph` or `.ach4` (`.` marks a word edge). Every pattern matching part
+/// of a word votes its digits onto the gaps between the letters; gaps left
+/// with an odd vote are hyphenation points.
+#[derive(Debug, Clone)]
+pub struct PatternHyphenator {
+    /// Letters of each pattern, to the digit before each letter plus the
+    /// one after the last.
+    patterns: HashMap<String, Vec<u8>>,
+    /// Hyphenation points of exception words, as char indices.
+    exceptions: HashMap<String, Vec<usize>>,
+    /// Longest pattern, in chars.
+    max_pattern_len: usize,
+    /// Fewest chars kept before the first and after the last hyphen.
+    left_min: usize,
+    right_min: usize,
+}
+
+impl PatternHyphenator {
+    /// Hyphenator over whitespace-separated TeX patterns. At least two
+    /// letters stay before a hyphen and three after it, as TeX does for
+    /// English.
+    pub fn new(patterns: &str) -> Self {
+        let mut parsed = HashMap::new();
+        let mut max_pattern_len = 0;
+        for pattern in patterns.split_whitespace() {
+            let mut letters = String::new();
+            let mut values = vec![0u8];
+            for ch in pattern.chars() {
+                match ch.to_digit(10) {
+                    Some(digit) => *values.last_mut().unwrap() = digit as u8,
+                    None => {
+                        letters.push(ch);
+                        values.push(0);
+                    }
+                }
+            }
+            max_pattern_len = max_pattern_len.max(values.len() - 1);
+            parsed.insert(letters, values);
+        }
+        Self {
+            patterns: parsed,
+            exceptions: HashMap::new(),
+            max_pattern_len,
+            left_min: 2,
+            right_min: 3,
+        }
+    }
+
+    /// Add exception words, hyphenated by hand (`as-so-ciate`), that
+    /// override the patterns.
+    pub fn with_exceptions(mut self, exceptions: &str) -> Self {
+        for word in exceptions.split_whitespace() {
+            let mut points = Vec::new();
+            let mut letters = String::new();
+            for ch in word.chars() {
+                if ch == '-' {
+                    points.push(letters.chars().count());
+                } else {
+                    letters.extend(ch.to_lowercase());
+                }
+            }
+            self.exceptions.insert(letters, points);
+        }
+        self
+    }
+
+    /// Fewest chars kept before the first and after the last hyphen.
+    pub fn with_min_lengths(mut self, left: usize, right: usize) -> Self {
+        self.left_min = left.max(1);
+        self.right_min = right.max(1);
+        self
+    }
+
+    /// Hyphenation points of `word` as char indices.
+    fn points(&self, word: &[char]) -> Vec<usize> {
+        let lower: String = word.iter().collect();
+        if let Some(points) = self.exceptions.get(&lower) {
+            return points.clone();
+        }
+
+        let mut chars = Vec::with_capacity(word.len() + 2);
+        chars.push('.');
+        chars.extend_from_slice(word);
+        chars.push('.');
+
+        // values[i] is the vote for the gap before chars[i].
+        let mut values = vec![0u8; chars.len() + 1];
+        let mut key = String::new();
+        for start in 0..chars.len() {
+            key.clear();
+            for &ch in chars[start..].iter().take(self.max_pattern_len) {
+                key.push(ch);
+                if let Some(pattern) = self.patterns.get(&key) {
+                    for (offset, &value) in pattern.iter().enumerate() {
+                        let slot = &mut values[start + offset];
+                        *slot = (*slot).max(value);
+                    }
+                }
+            }
+        }
+
+        // The gap before word[i] is before chars[i + 1].
+        (self.left_min..=word.len().saturating_sub(self.right_min))
+            .filter(|&i| values[i + 1] % 2 == 1)
+            .collect()
+    }
+}
+
+impl Hyphenator for PatternHyphenator {
+    fn hyphenate(&self, word: &str) -> Vec<usize> {
+        // Lowercase char by char so indices still line up with `word`.
+        let chars: Vec<char> = word
+            .chars()
+            .map(|c| c.to_lowercase().next().unwrap_or(c))
+            .collect();
+        if chars.len() < self.left_min + self.right_min {
+            return Vec::new();
+        }
+        let offsets: Vec<usize> = word.char_indices().map(|(i, _)| i).collect();
+        self.points(&chars)
+            .into_iter()
+            .filter_map(|i| offsets.get(i).copied())
+            .filter(|&offset| offset > 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The patterns of Liang's thesis example.
+    const PATTERNS: &str = "hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n";
+
+    fn hyphenated(hyphenator: &dyn Hyphenator, word: &str) -> String {
+        let points = hyphenator.hyphenate(word);
+        let mut out = String::new();
+        for (i, ch) in word.char_indices() {
+            if points.contains(&i) {
+                out.push('-');
+            }
+            out.push(ch);
+        }
+        out
+    }
+
+    #[test]
+    fn patterns_find_hyphenation_points() {
+        let hyphenator = PatternHyphenator::new(PATTERNS);
+        assert_eq!(hyphenated(&hyphenator, "hyphenation"), "hy-phen-ation");
+        assert_eq!(hyphenated(&hyphenator, "Hyphenation"), "Hy-phen-ation");
+        assert_eq!(hyphenated(&hyphenator, "nation"), "na-tion");
+    }
+
+    #[test]
+    fn exceptions_override_patterns() {
+        let hyphenator = PatternHyphenator::new(PATTERNS).with_exceptions("hyphen-ation");
+        assert_eq!(hyphenated(&hyphenator, "hyphenation"), "hyphen-ation");
+    }
+
+    #[test]
+    fn minimum_lengths_are_respected() {
+        let hyphenator = PatternHyphenator::new(PATTERNS).with_min_lengths(3, 5);
+        assert_eq!(hyphenated(&hyphenator, "hyphenation"), "hyphen-ation");
+    }
+}
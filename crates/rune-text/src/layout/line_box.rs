@@ -21,6 +21,18 @@ pub struct LineBox {
     pub leading: f32,
     /// Shaped runs in visual order.
     pub runs: Vec<ShapedRun>,
+    /// Hyphen drawn at the end of a line broken inside a word, placed
+    /// like the runs. Its text range is empty, at the end of the line.
+    pub hyphen: Option<ShapedRun>,
+    /// Ellipsis drawn in place of hidden text, placed like the runs. Its
+    /// text range is empty, at the start of `elided`.
+    pub ellipsis: Option<ShapedRun>,
+    /// Bytes of `text_range` that aren't shown, when the line was
+    /// truncated. The runs cover the rest of the line.
+    pub elided: Option<Range<usize>>,
+    /// Embedding level of the paragraph this line belongs to
+    /// (even = LTR, odd = RTL).
+    pub bidi_level: u8,
     /// Y position of line box top (relative to paragraph) in pixels.
     pub y_offset: f32,
 }
@@ -31,11 +43,45 @@ impl LineBox {
         self.y_offset + self.baseline_offset
     }
 
+    /// Get the Y position of the central baseline, halfway between the
+    /// ascent and descent, that upright glyphs in vertical text are
+    /// centered on.
+    pub fn central_baseline_y(&self) -> f32 {
+        self.baseline_y() + (self.descent - self.ascent) / 2.0
+    }
+
     /// Get line box bottom Y position.
     pub fn bottom_y(&self) -> f32 {
         self.y_offset + self.height
     }
 
+    /// Whether the line belongs to a right-to-left paragraph.
+    pub fn is_rtl(&self) -> bool {
+        self.bidi_level % 2 == 1
+    }
+
+    /// Move the line by `bytes` in the source text and `dy` pixels down,
+    /// e.g. when an edit before it changed the text's length or height.
+    pub fn translate(&mut self, bytes: isize, dy: f32) {
+        let shift = |range: &mut Range<usize>| {
+            range.start = range.start.wrapping_add_signed(bytes);
+            range.end = range.end.wrapping_add_signed(bytes);
+        };
+        shift(&mut self.text_range);
+        for run in self
+            .runs
+            .iter_mut()
+            .chain(&mut self.hyphen)
+            .chain(&mut self.ellipsis)
+        {
+            shift(&mut run.text_range);
+        }
+        if let Some(elided) = &mut self.elided {
+            shift(elided);
+        }
+        self.y_offset += dy;
+    }
+
     /// Check if a point is within this line.
     pub fn contains_point(&self, x: f32, y: f32) -> bool {
         y >= self.y_offset && y < self.bottom_y() && x >= 0.0 && x < self.width
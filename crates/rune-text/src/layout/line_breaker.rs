@@ -3,6 +3,8 @@ use core::ops::Range;
 use unicode_linebreak::{BreakOpportunity, linebreaks};
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::layout::hyphenation::{Hyphens, SOFT_HYPHEN};
+
 /// Kind of line break at a given position.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LineBreakKind {
@@ -10,6 +12,8 @@ pub enum LineBreakKind {
     Mandatory,
     /// Optional line break opportunity.
     Opportunity,
+    /// Optional break inside a word; a line ending here shows a hyphen.
+    Hyphenation,
 }
 
 /// A line break opportunity in the text.
@@ -54,6 +58,46 @@ pub fn compute_line_breaks(text: &str) -> Vec<LineBreak> {
         .collect()
 }
 
+/// Compute line break opportunities like [`compute_line_breaks`], with
+/// the hyphenation points allowed by `hyphens`.
+///
+/// Breaks after soft hyphens become [`LineBreakKind::Hyphenation`] breaks,
+/// or are dropped for [`Hyphens::None`]. [`Hyphens::Auto`] adds the points
+/// its hyphenator finds in each word made only of letters.
+pub fn compute_hyphenated_line_breaks(text: &str, hyphens: &Hyphens) -> Vec<LineBreak> {
+    let mut breaks = compute_line_breaks(text);
+    breaks.retain_mut(|br| {
+        if !text[..br.offset].ends_with(SOFT_HYPHEN) {
+            return true;
+        }
+        br.kind = LineBreakKind::Hyphenation;
+        !matches!(hyphens, Hyphens::None)
+    });
+
+    if let Hyphens::Auto(hyphenator) = hyphens {
+        for word in compute_word_boundaries(text) {
+            if word.kind != WordBoundaryKind::Word {
+                continue;
+            }
+            // Words with manual hyphens or non-letters are left alone.
+            let segment = &text[word.range.clone()];
+            if !segment.chars().all(char::is_alphabetic) {
+                continue;
+            }
+            for point in hyphenator.hyphenate(segment) {
+                if point > 0 && point < segment.len() && segment.is_char_boundary(point) {
+                    breaks.push(LineBreak {
+                        offset: word.range.start + point,
+                        kind: LineBreakKind::Hyphenation,
+                    });
+                }
+            }
+        }
+        breaks.sort_by_key(|br| br.offset);
+    }
+    breaks
+}
+
 /// Enumerate word and non-word segments for the given text.
 ///
 /// This uses `unicode-segmentation`'s word boundary logic under the
@@ -95,6 +139,27 @@ mod tests {
         assert!(breaks.iter().any(|b| b.kind == LineBreakKind::Mandatory));
     }
 
+    #[test]
+    fn hyphenated_line_breaks() {
+        let text = "co\u{AD}operate banana";
+        let offsets = |hyphens: &Hyphens| -> Vec<(usize, LineBreakKind)> {
+            compute_hyphenated_line_breaks(text, hyphens)
+                .into_iter()
+                .map(|b| (b.offset, b.kind))
+                .collect()
+        };
+        let shy = "co\u{AD}".len();
+        let space = "co\u{AD}operate ".len();
+
+        assert!(offsets(&Hyphens::Manual).contains(&(shy, LineBreakKind::Hyphenation)));
+        assert!(!offsets(&Hyphens::None).iter().any(|&(o, _)| o == shy));
+
+        let hyphenator = crate::layout::PatternHyphenator::new("1na").with_min_lengths(1, 1);
+        let auto = offsets(&Hyphens::Auto(std::sync::Arc::new(hyphenator)));
+        assert!(auto.contains(&(space + 2, LineBreakKind::Hyphenation)));
+        assert!(auto.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
     #[test]
     fn word_boundaries_simple() {
         let text = "Hello, world!";
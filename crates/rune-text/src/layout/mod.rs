@@ -1,9 +1,12 @@
 pub mod cursor;
 pub mod cursor_movement;
 pub mod hit_test;
+pub mod hyphenation;
 pub mod line_box;
 pub mod line_breaker;
+pub mod paragraph_style;
 pub mod prefix_sums;
+pub mod rope;
 pub mod selection;
 pub mod text_layout;
 pub mod undo;
@@ -11,9 +14,14 @@ pub mod undo;
 pub use cursor::{Cursor, CursorAffinity, CursorPosition, CursorRect};
 pub use cursor_movement::{CursorMovement, MovementDirection, MovementUnit};
 pub use hit_test::{HitTestPolicy, HitTestResult, Point, Position};
+pub use hyphenation::{Hyphenator, Hyphens, PatternHyphenator};
 pub use line_box::LineBox;
 pub use line_breaker::{LineBreak, LineBreakKind, WordBoundary, WordBoundaryKind};
+pub use paragraph_style::{
+    EllipsisPosition, ParagraphStyle, TabStops, TextOrientation, TextOverflow, WritingMode,
+};
 pub use prefix_sums::PrefixSums;
+pub use rope::Rope;
 pub use selection::{Selection, SelectionRect};
 pub use text_layout::TextLayout;
 pub use undo::{TextOperation, UndoStack};
@@ -0,0 +1,210 @@
+use crate::layout::hyphenation::Hyphens;
+
+/// Paragraph-level formatting applied while laying out lines.
+#[derive(Debug, Clone, Default)]
+pub struct ParagraphStyle {
+    /// Stretch the spaces of every wrapped line except a paragraph's last
+    /// so the line fills the wrap width, like CSS `text-align: justify`.
+    /// Only applies when wrapping to a width.
+    pub justify: bool,
+    /// Where words may be hyphenated when wrapping.
+    pub hyphens: Hyphens,
+    /// Where tab characters advance to.
+    pub tab_stops: TabStops,
+    /// Most lines shown, like CSS `line-clamp`; the text after them is
+    /// hidden. `None` shows every line.
+    pub max_lines: Option<usize>,
+    /// How hidden text is marked: text cut off by `max_lines`, and lines
+    /// wider than the wrap width when not wrapping.
+    pub overflow: TextOverflow,
+    /// Whether lines run horizontally or vertically, like CSS
+    /// `writing-mode`.
+    pub writing_mode: WritingMode,
+    /// Which glyphs stand upright in vertical lines, like CSS
+    /// `text-orientation`. Ignored in horizontal text.
+    pub text_orientation: TextOrientation,
+}
+
+impl ParagraphStyle {
+    /// Style with justification turned on or off.
+    pub fn with_justify(mut self, justify: bool) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    /// Style with the given hyphenation.
+    pub fn with_hyphens(mut self, hyphens: Hyphens) -> Self {
+        self.hyphens = hyphens;
+        self
+    }
+
+    /// Style with the given tab stops.
+    pub fn with_tab_stops(mut self, tab_stops: TabStops) -> Self {
+        self.tab_stops = tab_stops;
+        self
+    }
+
+    /// Style showing at most `max_lines` lines.
+    pub fn with_max_lines(mut self, max_lines: Option<usize>) -> Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    /// Style with the given overflow marking.
+    pub fn with_overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Style with the given writing mode.
+    pub fn with_writing_mode(mut self, writing_mode: WritingMode) -> Self {
+        self.writing_mode = writing_mode;
+        self
+    }
+
+    /// Style with the given orientation for vertical text.
+    pub fn with_text_orientation(mut self, text_orientation: TextOrientation) -> Self {
+        self.text_orientation = text_orientation;
+        self
+    }
+
+    /// Whether lines may be cut short or hidden.
+    pub fn truncates(&self) -> bool {
+        self.max_lines.is_some() || self.overflow != TextOverflow::Clip
+    }
+}
+
+/// How text that doesn't fit is marked, like CSS `text-overflow`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextOverflow {
+    /// Cut the text off without a marker.
+    #[default]
+    Clip,
+    /// Replace the hidden text with an ellipsis (`…`).
+    Ellipsis(EllipsisPosition),
+}
+
+/// Which part of a truncated line the ellipsis replaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EllipsisPosition {
+    /// Keep the end of the text: `…ng file name.txt`.
+    Start,
+    /// Keep both ends: `A long f…name.txt`.
+    Middle,
+    /// Keep the start of the text: `A long file na…`.
+    #[default]
+    End,
+}
+
+/// Direction lines run in and stack in, like CSS `writing-mode`.
+///
+/// Vertical text is laid out in line coordinates, as if it were
+/// horizontal: x runs along each line, y across the lines, and the wrap
+/// width limits the length of a line. On screen the lines turn a quarter
+/// clockwise so they read top to bottom; see
+/// [`TextLayout::to_physical`](crate::layout::TextLayout::to_physical).
+/// Cursor movement stays in line coordinates as well: moving up or down a
+/// line steps between columns, which vertical text does with the left and
+/// right arrow keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WritingMode {
+    /// Lines run left to right and stack top to bottom.
+    #[default]
+    HorizontalTb,
+    /// Lines run top to bottom and stack right to left, as in Japanese
+    /// and Chinese books.
+    VerticalRl,
+    /// Lines run top to bottom and stack left to right, as in Mongolian.
+    VerticalLr,
+}
+
+impl WritingMode {
+    /// Whether lines run top to bottom.
+    pub fn is_vertical(self) -> bool {
+        self != WritingMode::HorizontalTb
+    }
+}
+
+/// How glyphs sit in vertical lines, like CSS `text-orientation`.
+///
+/// Upright glyphs are shaped with vertical metrics and the font's
+/// vertical alternates (`vert`); sideways glyphs are shaped as horizontal
+/// text and turn with the line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextOrientation {
+    /// CJK and other scripts with upright forms stand upright; Latin and
+    /// the like turn sideways.
+    #[default]
+    Mixed,
+    /// Every glyph stands upright, reading top to bottom.
+    Upright,
+    /// Every glyph turns sideways, as in rotated horizontal text.
+    Sideways,
+}
+
+/// Tab stop positions, measured in pixels from the left edge of the line.
+///
+/// A tab advances to the first explicit stop past the pen position, then
+/// to multiples of `interval` once the explicit stops run out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TabStops {
+    /// Explicit stops, ascending.
+    pub positions: Vec<f32>,
+    /// Distance between stops after the explicit ones. `None` uses the
+    /// advance of eight spaces.
+    pub interval: Option<f32>,
+}
+
+impl TabStops {
+    /// Evenly spaced stops every `interval` pixels.
+    pub fn every(interval: f32) -> Self {
+        Self {
+            positions: Vec::new(),
+            interval: Some(interval),
+        }
+    }
+
+    /// Add an explicit stop at `position` pixels.
+    pub fn with_stop(mut self, position: f32) -> Self {
+        let index = self.positions.partition_point(|&p| p < position);
+        self.positions.insert(index, position);
+        self
+    }
+
+    /// Position of the first stop after `x`. `default_interval` is used
+    /// when no interval was set.
+    pub fn next_stop(&self, x: f32, default_interval: f32) -> f32 {
+        if let Some(&stop) = self.positions.iter().find(|&&p| p > x) {
+            return stop;
+        }
+        let interval = self.interval.unwrap_or(default_interval);
+        if interval <= 0.0 {
+            return x;
+        }
+        ((x / interval).floor() + 1.0) * interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_stop_uses_explicit_stops_then_interval() {
+        let stops = TabStops::every(40.0).with_stop(100.0).with_stop(30.0);
+        assert_eq!(stops.positions, vec![30.0, 100.0]);
+        assert_eq!(stops.next_stop(0.0, 8.0), 30.0);
+        assert_eq!(stops.next_stop(30.0, 8.0), 100.0);
+        assert_eq!(stops.next_stop(100.0, 8.0), 120.0);
+        assert_eq!(TabStops::default().next_stop(17.0, 8.0), 24.0);
+    }
+
+    #[test]
+    fn only_vertical_modes_are_vertical() {
+        assert!(!WritingMode::HorizontalTb.is_vertical());
+        assert!(WritingMode::VerticalRl.is_vertical());
+        assert!(WritingMode::VerticalLr.is_vertical());
+        let style = ParagraphStyle::default().with_writing_mode(WritingMode::VerticalRl);
+        assert_eq!(style.text_orientation, TextOrientation::Mixed);
+    }
+}
@@ -1,3 +1,5 @@
+use core::ops::Range;
+
 use crate::layout::LineBox;
 
 /// Prefix sum array for O(1) line/character lookups.
@@ -30,6 +32,35 @@ impl PrefixSums {
         }
     }
 
+    /// Update after the lines in `replaced` were laid out again, without
+    /// recounting the characters of the other lines.
+    ///
+    /// `lines` is the updated layout, with the new lines starting at
+    /// `replaced.start`, and `added_chars` holds the character count of
+    /// each new line.
+    pub fn splice(&mut self, lines: &[LineBox], replaced: Range<usize>, added_chars: &[usize]) {
+        let mut cursor = self.char_offsets.get(replaced.start).copied().unwrap_or(0);
+        let mut added = Vec::with_capacity(added_chars.len());
+        for &chars in added_chars {
+            added.push(cursor);
+            cursor += chars;
+        }
+        let shift = self
+            .char_offsets
+            .get(replaced.end)
+            .map(|&old| cursor as isize - old as isize);
+        self.char_offsets.splice(replaced.clone(), added);
+        if let Some(shift) = shift {
+            for offset in &mut self.char_offsets[replaced.start + added_chars.len()..] {
+                *offset = offset.wrapping_add_signed(shift);
+            }
+        }
+
+        self.byte_offsets.clear();
+        self.byte_offsets
+            .extend(lines.iter().map(|line| line.text_range.start));
+    }
+
     /// Find line index containing character offset (layout-wide).
     pub fn line_at_char(&self, char_offset: usize) -> Option<usize> {
         if self.char_offsets.is_empty() {
@@ -0,0 +1,516 @@
+//! Rope storage for editable text.
+//!
+//! Text is kept in leaves of at most [`MAX_LEAF`] bytes under a binary tree
+//! whose nodes record the byte length and newline count of their subtree,
+//! so an edit or a paragraph lookup touches one path of the tree instead of
+//! moving or scanning the whole text. Edits split the tree at the edit
+//! point and join the pieces back; the tree is rebuilt balanced once it
+//! grows deeper than [`MAX_DEPTH`].
+
+use core::fmt;
+use core::ops::Range;
+
+/// Largest leaf built when splitting text into leaves.
+const MAX_LEAF: usize = 1024;
+
+/// Depth past which an edit rebuilds the tree balanced.
+const MAX_DEPTH: usize = 32;
+
+/// Text stored as a tree of string chunks.
+///
+/// Offsets are byte offsets and, as with `String`, edit positions must lie
+/// on `char` boundaries. Paragraphs are the `'\n'`-separated pieces of the
+/// text, so a rope always has one more paragraph than it has newlines.
+#[derive(Clone, Default)]
+pub struct Rope {
+    root: Node,
+}
+
+#[derive(Clone)]
+enum Node {
+    Leaf {
+        text: String,
+        newlines: usize,
+    },
+    Branch {
+        left: Box<Node>,
+        right: Box<Node>,
+        len: usize,
+        newlines: usize,
+        depth: usize,
+    },
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Node::leaf(String::new())
+    }
+}
+
+impl Node {
+    fn leaf(text: String) -> Self {
+        let newlines = count_newlines(&text);
+        Node::Leaf { text, newlines }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf { text, .. } => text.len(),
+            Node::Branch { len, .. } => *len,
+        }
+    }
+
+    fn newlines(&self) -> usize {
+        match self {
+            Node::Leaf { newlines, .. } | Node::Branch { newlines, .. } => *newlines,
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 0,
+            Node::Branch { depth, .. } => *depth,
+        }
+    }
+
+    /// Concatenate two nodes, merging small leaves.
+    fn join(left: Node, right: Node) -> Node {
+        if left.len() == 0 {
+            return right;
+        }
+        if right.len() == 0 {
+            return left;
+        }
+        match (left, right) {
+            (
+                Node::Leaf { mut text, newlines },
+                Node::Leaf {
+                    text: tail,
+                    newlines: more,
+                },
+            ) if text.len() + tail.len() <= MAX_LEAF => {
+                text.push_str(&tail);
+                Node::Leaf {
+                    text,
+                    newlines: newlines + more,
+                }
+            }
+            (left, right) => Node::Branch {
+                len: left.len() + right.len(),
+                newlines: left.newlines() + right.newlines(),
+                depth: left.depth().max(right.depth()) + 1,
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+        }
+    }
+
+    /// Split into the text before and after byte `at`.
+    fn split(self, at: usize) -> (Node, Node) {
+        match self {
+            Node::Leaf { mut text, .. } => {
+                let tail = text.split_off(at);
+                (Node::leaf(text), Node::leaf(tail))
+            }
+            Node::Branch { left, right, .. } => {
+                let left_len = left.len();
+                if at <= left_len {
+                    let (head, tail) = left.split(at);
+                    (head, Node::join(tail, *right))
+                } else {
+                    let (head, tail) = right.split(at - left_len);
+                    (Node::join(*left, head), tail)
+                }
+            }
+        }
+    }
+
+    /// Balanced tree over `text`, cut into leaves on `char` boundaries.
+    fn from_str(text: &str) -> Node {
+        let mut leaves = Vec::with_capacity(text.len() / MAX_LEAF + 1);
+        let mut rest = text;
+        while rest.len() > MAX_LEAF {
+            let mut cut = MAX_LEAF;
+            while !rest.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            leaves.push(Node::leaf(rest[..cut].to_string()));
+            rest = &rest[cut..];
+        }
+        leaves.push(Node::leaf(rest.to_string()));
+        Node::build(leaves)
+    }
+
+    /// Balanced tree over `leaves`, in order.
+    fn build(mut nodes: Vec<Node>) -> Node {
+        while nodes.len() > 1 {
+            let mut paired = Vec::with_capacity(nodes.len().div_ceil(2));
+            let mut iter = nodes.into_iter();
+            while let Some(left) = iter.next() {
+                paired.push(match iter.next() {
+                    Some(right) => Node::join(left, right),
+                    None => left,
+                });
+            }
+            nodes = paired;
+        }
+        nodes.pop().unwrap_or_default()
+    }
+
+    /// Move the leaves' text into `out`, packing neighbors into leaves of
+    /// up to [`MAX_LEAF`] bytes.
+    fn collect_leaves(self, out: &mut Vec<String>) {
+        match self {
+            Node::Leaf { text, .. } => match out.last_mut() {
+                Some(last) if last.len() + text.len() <= MAX_LEAF => last.push_str(&text),
+                _ if text.is_empty() => {}
+                _ => out.push(text),
+            },
+            Node::Branch { left, right, .. } => {
+                left.collect_leaves(out);
+                right.collect_leaves(out);
+            }
+        }
+    }
+
+    fn newlines_before(&self, at: usize) -> usize {
+        match self {
+            Node::Leaf { text, .. } => count_newlines(&text.as_bytes()[..at.min(text.len())]),
+            Node::Branch { left, right, .. } => {
+                if at <= left.len() {
+                    left.newlines_before(at)
+                } else {
+                    left.newlines() + right.newlines_before(at - left.len())
+                }
+            }
+        }
+    }
+
+    /// Byte offset just past newline number `n` (1-based); `n` must be at
+    /// most the node's newline count.
+    fn after_newline(&self, n: usize) -> usize {
+        match self {
+            Node::Leaf { text, .. } => text
+                .bytes()
+                .enumerate()
+                .filter(|&(_, b)| b == b'\n')
+                .nth(n - 1)
+                .map_or(text.len(), |(idx, _)| idx + 1),
+            Node::Branch { left, right, .. } => {
+                if n <= left.newlines() {
+                    left.after_newline(n)
+                } else {
+                    left.len() + right.after_newline(n - left.newlines())
+                }
+            }
+        }
+    }
+
+    /// The leaf holding byte `at` and the offset of `at` in it; `at` must
+    /// be less than the node's length.
+    fn leaf_at(&self, at: usize) -> (&str, usize) {
+        match self {
+            Node::Leaf { text, .. } => (text, at),
+            Node::Branch { left, right, .. } => {
+                let left_len = left.len();
+                if at < left_len {
+                    left.leaf_at(at)
+                } else {
+                    right.leaf_at(at - left_len)
+                }
+            }
+        }
+    }
+
+    fn push_range(&self, range: Range<usize>, out: &mut String) {
+        match self {
+            Node::Leaf { text, .. } => out.push_str(&text[range]),
+            Node::Branch { left, right, .. } => {
+                let left_len = left.len();
+                if range.start < left_len {
+                    left.push_range(range.start..range.end.min(left_len), out);
+                }
+                if range.end > left_len {
+                    right.push_range(
+                        range.start.saturating_sub(left_len)..range.end - left_len,
+                        out,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn count_newlines(text: impl AsRef<[u8]>) -> usize {
+    text.as_ref().iter().filter(|&&b| b == b'\n').count()
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Length in bytes.
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of `'\n'`-separated paragraphs; at least one.
+    pub fn paragraph_count(&self) -> usize {
+        self.root.newlines() + 1
+    }
+
+    /// Index of the paragraph containing byte `offset`. An offset just past
+    /// a newline belongs to the paragraph the newline starts.
+    pub fn paragraph_at(&self, offset: usize) -> usize {
+        self.root.newlines_before(offset)
+    }
+
+    /// Byte range of paragraph `index`, excluding its trailing newline.
+    /// Indices past the last paragraph give an empty range at the end.
+    pub fn paragraph_range(&self, index: usize) -> Range<usize> {
+        let start = self.paragraph_start(index);
+        let end = if index + 1 < self.paragraph_count() {
+            self.paragraph_start(index + 1) - 1
+        } else {
+            self.len()
+        };
+        start..end
+    }
+
+    fn paragraph_start(&self, index: usize) -> usize {
+        match index {
+            0 => 0,
+            n if n > self.root.newlines() => self.len(),
+            n => self.root.after_newline(n),
+        }
+    }
+
+    /// Whether byte `offset` starts a `char` or is the end of the text.
+    pub fn is_char_boundary(&self, offset: usize) -> bool {
+        if offset >= self.len() {
+            return offset == self.len();
+        }
+        let (leaf, at) = self.root.leaf_at(offset);
+        leaf.is_char_boundary(at)
+    }
+
+    /// The `char` starting at byte `offset`, if any.
+    pub fn char_at(&self, offset: usize) -> Option<char> {
+        if offset >= self.len() {
+            return None;
+        }
+        let (leaf, at) = self.root.leaf_at(offset);
+        leaf.get(at..)?.chars().next()
+    }
+
+    /// Start of the `char` before byte `offset`, or 0 at the start.
+    pub fn prev_char_boundary(&self, offset: usize) -> usize {
+        let offset = offset.min(self.len());
+        if offset == 0 {
+            return 0;
+        }
+        // Leaves are cut on `char` boundaries, so the char ends in the leaf
+        // holding the byte before `offset`.
+        let (leaf, at) = self.root.leaf_at(offset - 1);
+        let start = (0..=at).rev().find(|&i| leaf.is_char_boundary(i));
+        offset - 1 - at + start.unwrap_or(0)
+    }
+
+    /// End of the `char` at byte `offset`, or the text's length at the end.
+    pub fn next_char_boundary(&self, offset: usize) -> usize {
+        if offset >= self.len() {
+            return self.len();
+        }
+        let (leaf, at) = self.root.leaf_at(offset);
+        let end = (at + 1..=leaf.len()).find(|&i| leaf.is_char_boundary(i));
+        offset - at + end.unwrap_or(leaf.len())
+    }
+
+    /// Copy of the text in `range`.
+    pub fn slice(&self, range: Range<usize>) -> String {
+        let mut out = String::with_capacity(range.len());
+        if !range.is_empty() {
+            self.root.push_range(range, &mut out);
+        }
+        out
+    }
+
+    /// The rope's leaves in order.
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks {
+            stack: vec![&self.root],
+        }
+    }
+
+    /// Insert `text` at byte `offset`.
+    pub fn insert(&mut self, offset: usize, text: &str) {
+        self.replace(offset..offset, text);
+    }
+
+    /// Remove the bytes in `range`.
+    pub fn remove(&mut self, range: Range<usize>) {
+        self.replace(range, "");
+    }
+
+    /// Replace the bytes in `range` with `text`.
+    pub fn replace(&mut self, range: Range<usize>, text: &str) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {range:?} out of bounds of rope of length {}",
+            self.len()
+        );
+        let root = std::mem::take(&mut self.root);
+        let (head, rest) = root.split(range.start);
+        let (_, tail) = rest.split(range.end - range.start);
+        let root = Node::join(Node::join(head, Node::from_str(text)), tail);
+        self.root = if root.depth() > MAX_DEPTH {
+            let mut leaves = Vec::new();
+            root.collect_leaves(&mut leaves);
+            Node::build(leaves.into_iter().map(Node::leaf).collect())
+        } else {
+            root
+        };
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Self {
+            root: Node::from_str(text),
+        }
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Rope").field(&self.to_string()).finish()
+    }
+}
+
+/// Iterator over the leaves of a [`Rope`].
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while let Some(node) = self.stack.pop() {
+            match node {
+                Node::Leaf { text, .. } if text.is_empty() => {}
+                Node::Leaf { text, .. } => return Some(text),
+                Node::Branch { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_match_string_edits() {
+        let mut rope = Rope::from("");
+        let mut expected = String::new();
+        // Deterministic pseudo-random edits, long enough to need several
+        // leaves and a rebalance.
+        let mut seed = 0x2545_f491u32;
+        let mut next = |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize % bound.max(1)
+        };
+        for step in 0..2000 {
+            let at = next(expected.len() + 1);
+            let at = (0..=at)
+                .rev()
+                .find(|&i| expected.is_char_boundary(i))
+                .unwrap();
+            if step % 3 == 2 && !expected.is_empty() {
+                let end = (at + next(40)).min(expected.len());
+                let end = (end..=expected.len())
+                    .find(|&i| expected.is_char_boundary(i))
+                    .unwrap();
+                rope.remove(at..end);
+                expected.replace_range(at..end, "");
+            } else {
+                let text = ["héllo ", "\n", "wörld\nline\n", "日本語"][step % 4];
+                rope.insert(at, text);
+                expected.insert_str(at, text);
+            }
+            assert_eq!(rope.len(), expected.len());
+        }
+        assert_eq!(rope.to_string(), expected);
+        assert!(rope.root.depth() <= MAX_DEPTH);
+        assert_eq!(rope.paragraph_count(), expected.split('\n').count());
+    }
+
+    #[test]
+    fn paragraphs_are_found_by_index_and_offset() {
+        let rope = Rope::from("one\n\nthree\n");
+        assert_eq!(rope.paragraph_count(), 4);
+        assert_eq!(rope.paragraph_range(0), 0..3);
+        assert_eq!(rope.paragraph_range(1), 4..4);
+        assert_eq!(rope.paragraph_range(2), 5..10);
+        assert_eq!(rope.paragraph_range(3), 11..11);
+        assert_eq!(rope.paragraph_at(3), 0);
+        assert_eq!(rope.paragraph_at(4), 1);
+        assert_eq!(rope.paragraph_at(11), 3);
+        assert_eq!(rope.slice(5..10), "three");
+    }
+
+    #[test]
+    fn char_boundaries_cross_leaves() {
+        let text = "日本語\n".repeat(400);
+        let rope = Rope::from(text.as_str());
+        assert!(rope.chunks().count() > 1);
+        let mut offset = 0;
+        while offset < text.len() {
+            let next = rope.next_char_boundary(offset);
+            assert_eq!(rope.char_at(offset), text[offset..].chars().next());
+            assert_eq!(
+                next,
+                offset + text[offset..].chars().next().unwrap().len_utf8()
+            );
+            assert_eq!(rope.prev_char_boundary(next), offset);
+            assert!(rope.is_char_boundary(offset));
+            assert_eq!(
+                rope.is_char_boundary(offset + 1),
+                text.is_char_boundary(offset + 1)
+            );
+            offset = next;
+        }
+        assert_eq!(rope.next_char_boundary(text.len()), text.len());
+        assert_eq!(rope.prev_char_boundary(0), 0);
+        assert_eq!(rope.char_at(text.len()), None);
+    }
+
+    #[test]
+    fn large_texts_span_many_leaves() {
+        let text = "log line ✓\n".repeat(2000);
+        let mut rope = Rope::from(text.as_str());
+        assert!(rope.chunks().count() > 1);
+        assert_eq!(rope.paragraph_range(1500), 1500 * 13..1500 * 13 + 12);
+        rope.replace(13..26, "");
+        assert_eq!(rope.paragraph_count(), 2000);
+        assert_eq!(rope.slice(0..26), "log line ✓\nlog line ✓\n");
+    }
+}
@@ -1,26 +1,48 @@
+use core::ops::Range;
+use std::collections::VecDeque;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::OnceLock;
+
+use hashbrown::HashMap;
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::font::{FontFace, ScaledFontMetrics};
+use crate::bidi::{BaseDirection, resolve_base_direction};
+use crate::font::{FontFace, FontFallbackChain, ScaledFontMetrics};
 use crate::layout::{
-    LineBox, PrefixSums, WrapMode,
+    EllipsisPosition, LineBox, ParagraphStyle, PrefixSums, Rope, TabStops, TextOverflow, WrapMode,
+    WritingMode,
     cursor::{Cursor, CursorAffinity, CursorPosition, CursorRect},
     cursor_movement::CursorMovement,
     hit_test::{HitTestPolicy, HitTestResult, Point, Position},
-    line_breaker::{WordBoundaryKind, compute_line_breaks, compute_word_boundaries},
+    hyphenation::SOFT_HYPHEN,
+    line_breaker::{
+        LineBreakKind, WordBoundaryKind, compute_hyphenated_line_breaks, compute_word_boundaries,
+    },
     selection::{Selection, SelectionRect},
     undo::{TextOperation, UndoStack},
 };
-use crate::shaping::TextShaper;
+use crate::shaping::{GlyphPosition, ShapedRun, TextShaper};
+
+/// Most paragraph layouts kept for reuse after edits removed them.
+const PARAGRAPH_CACHE_LIMIT: usize = 256;
 
-/// Complete text layout with all lines for a single font.
+/// Complete text layout with all lines for a primary font.
 ///
 /// Phase 2.2 focuses on building `LineBox` instances with
 /// correct metrics and supporting basic multi-line layout
-/// driven by explicit newline characters.
+/// driven by explicit newline characters. Text the primary font
+/// can't render is shaped with the layout's fallback faces; line
+/// metrics always come from the primary font.
+///
+/// The text is stored in a [`Rope`] and laid out paragraph by paragraph
+/// (paragraphs being separated by `'\n'`). An edit only reshapes the
+/// paragraphs it touches and moves the lines after them.
 #[derive(Debug)]
 pub struct TextLayout {
     /// Source text.
-    text: String,
+    text: Rope,
+    /// Contiguous copy of `text`, built on first use after an edit.
+    flat_text: OnceLock<String>,
     /// All line boxes in visual/top-to-bottom order.
     lines: Vec<LineBox>,
     /// Prefix sums over characters and bytes for fast lookups.
@@ -29,6 +51,82 @@ pub struct TextLayout {
     undo_stack: UndoStack,
     /// Optional override for the line height (in pixels) used during layout.
     line_height_override: Option<f32>,
+    /// Faces tried after the primary font, kept so that relayout after an
+    /// edit (which only receives the primary font) uses the same chain.
+    fallback_fonts: Vec<FontFace>,
+    /// Font, size and wrapping the current lines were laid out with.
+    params: LayoutParams,
+    /// Justification, hyphenation and tab stops of every paragraph.
+    style: ParagraphStyle,
+    /// Number of line boxes of each paragraph, including the empty line
+    /// that follows a paragraph ending in a newline.
+    paragraph_lines: Vec<usize>,
+    /// Layouts of paragraphs removed by recent edits, so undoing an edit or
+    /// retyping a paragraph doesn't reshape it.
+    paragraph_cache: ParagraphCache,
+}
+
+#[derive(Debug, Clone)]
+struct LayoutParams {
+    font: FontFace,
+    font_size: f32,
+    max_width: Option<f32>,
+    wrap_mode: WrapMode,
+}
+
+impl LayoutParams {
+    fn matches(
+        &self,
+        font: &FontFace,
+        font_size: f32,
+        max_width: Option<f32>,
+        wrap_mode: WrapMode,
+    ) -> bool {
+        self.font.is_same_face(font)
+            && self.font_size == font_size
+            && self.max_width == max_width
+            && self.wrap_mode == wrap_mode
+    }
+}
+
+/// Paragraph layouts keyed by a hash and the length of the paragraph's
+/// text. Lines are stored relative to the paragraph: byte ranges start at
+/// 0 and the first line's top is at 0.
+#[derive(Debug, Default)]
+struct ParagraphCache {
+    entries: HashMap<(u64, usize), Vec<LineBox>>,
+    /// Keys oldest first, for eviction.
+    order: VecDeque<(u64, usize)>,
+}
+
+impl ParagraphCache {
+    fn key(paragraph: &str) -> (u64, usize) {
+        let mut hasher = DefaultHasher::new();
+        paragraph.hash(&mut hasher);
+        (hasher.finish(), paragraph.len())
+    }
+
+    fn insert(&mut self, key: (u64, usize), lines: Vec<LineBox>) {
+        if self.entries.insert(key, lines).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > PARAGRAPH_CACHE_LIMIT {
+            if let Some(old) = self.order.pop_front() {
+                self.entries.remove(&old);
+            }
+        }
+    }
+
+    fn take(&mut self, key: (u64, usize)) -> Option<Vec<LineBox>> {
+        let lines = self.entries.remove(&key)?;
+        self.order.retain(|k| *k != key);
+        Some(lines)
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
 }
 
 impl TextLayout {
@@ -47,6 +145,10 @@ impl TextLayout {
     ///   `max_width`.
     /// - For `WrapMode::BreakAll`, always breaks at grapheme
     ///   boundaries using a greedy algorithm.
+    ///
+    /// OpenType features and variation axes set on `font` with
+    /// [`FontFace::with_settings`] apply to shaping and line breaking, so
+    /// e.g. `tnum` or a heavier `wght` change the measured widths.
     pub fn with_wrap(
         text: impl Into<String>,
         font: &FontFace,
@@ -54,7 +156,54 @@ impl TextLayout {
         max_width: Option<f32>,
         wrap_mode: WrapMode,
     ) -> Self {
-        Self::with_wrap_internal(text, font, font_size, max_width, wrap_mode, None)
+        Self::with_wrap_internal(
+            text,
+            &FontFallbackChain::new(font.clone()),
+            font_size,
+            max_width,
+            wrap_mode,
+            None,
+            ParagraphStyle::default(),
+        )
+    }
+
+    /// Layout text with a font fallback chain, so mixed-script paragraphs
+    /// (CJK, Arabic, emoji…) shape with faces that cover them.
+    ///
+    /// Editing methods take the primary font; the fallback faces are kept
+    /// with the layout and reused on every relayout.
+    pub fn with_font_fallback(
+        text: impl Into<String>,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        max_width: Option<f32>,
+        wrap_mode: WrapMode,
+    ) -> Self {
+        Self::with_wrap_internal(
+            text,
+            fonts,
+            font_size,
+            max_width,
+            wrap_mode,
+            None,
+            ParagraphStyle::default(),
+        )
+    }
+
+    /// Layout text with a font fallback chain and a paragraph style for
+    /// justification, hyphenation and tab stops.
+    ///
+    /// Justification and hyphenation only take effect when wrapping to
+    /// `max_width`; the style is kept for every relayout after an edit.
+    pub fn with_style(
+        text: impl Into<String>,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        max_width: Option<f32>,
+        wrap_mode: WrapMode,
+        style: ParagraphStyle,
+    ) -> Self {
+        Self::with_wrap_internal(text, fonts, font_size, max_width, wrap_mode, None, style)
     }
 
     /// Layout text with an explicit line height override.
@@ -68,94 +217,45 @@ impl TextLayout {
     ) -> Self {
         Self::with_wrap_internal(
             text,
-            font,
+            &FontFallbackChain::new(font.clone()),
             font_size,
             max_width,
             wrap_mode,
             Some(line_height),
+            ParagraphStyle::default(),
         )
     }
 
     fn with_wrap_internal(
         text: impl Into<String>,
-        font: &FontFace,
+        fonts: &FontFallbackChain,
         font_size: f32,
         max_width: Option<f32>,
         wrap_mode: WrapMode,
         line_height_override: Option<f32>,
+        style: ParagraphStyle,
     ) -> Self {
         let text = text.into();
-        let mut lines = Vec::new();
-
-        // Compute scaled metrics once; reused for all lines.
-        let scaled = font.scaled_metrics(font_size);
-        let line_height = Self::resolve_line_height(line_height_override, &scaled);
-        let leading = (line_height - scaled.ascent - scaled.descent).max(0.0);
-
-        let mut y = 0.0f32;
-
-        // Split into logical paragraphs on '\n'. Wrapping decisions
-        // are made per paragraph.
-        let mut para_start = 0usize;
-        for (idx, ch) in text.char_indices() {
-            if ch == '\n' {
-                Self::layout_paragraph(
-                    &text,
-                    para_start..idx,
-                    font,
-                    font_size,
-                    line_height,
-                    scaled.ascent,
-                    scaled.descent,
-                    leading,
-                    max_width,
-                    wrap_mode,
-                    &mut y,
-                    &mut lines,
-                );
-                // Preserve empty line after newline.
-                Self::layout_paragraph(
-                    &text,
-                    idx..idx,
-                    font,
-                    font_size,
-                    line_height,
-                    scaled.ascent,
-                    scaled.descent,
-                    leading,
-                    max_width,
-                    wrap_mode,
-                    &mut y,
-                    &mut lines,
-                );
-                para_start = idx + ch.len_utf8();
-            }
-        }
-        if para_start <= text.len() {
-            Self::layout_paragraph(
-                &text,
-                para_start..text.len(),
-                font,
+        let mut layout = Self {
+            text: Rope::from(text.as_str()),
+            flat_text: OnceLock::from(text),
+            lines: Vec::new(),
+            prefix_sums: PrefixSums::new("", &[]),
+            undo_stack: UndoStack::new(),
+            line_height_override,
+            fallback_fonts: fonts.fallbacks().to_vec(),
+            params: LayoutParams {
+                font: fonts.primary().clone(),
                 font_size,
-                line_height,
-                scaled.ascent,
-                scaled.descent,
-                leading,
                 max_width,
                 wrap_mode,
-                &mut y,
-                &mut lines,
-            );
-        }
-
-        let prefix_sums = PrefixSums::new(&text, &lines);
-        Self {
-            text,
-            lines,
-            prefix_sums,
-            undo_stack: UndoStack::new(),
-            line_height_override,
-        }
+            },
+            style,
+            paragraph_lines: Vec::new(),
+            paragraph_cache: ParagraphCache::default(),
+        };
+        layout.layout_all(fonts);
+        layout
     }
 
     fn resolve_line_height(line_height_override: Option<f32>, scaled: &ScaledFontMetrics) -> f32 {
@@ -167,24 +267,85 @@ impl TextLayout {
     ///
     /// This uses `fontdb` to pick a reasonable sans-serif font from the host
     /// system (same heuristic as `engine-core::RuneTextProvider::from_system_fonts`)
-    /// so callers don't need to manage a `FontFace` directly.
+    /// so callers don't need to manage a `FontFace` directly. The system
+    /// fallback faces are attached so non-Latin text doesn't render as tofu.
     pub fn with_system_font(
         text: impl Into<String>,
         font_size: f32,
         max_width: Option<f32>,
         wrap_mode: WrapMode,
     ) -> crate::font::Result<Self> {
-        let font = crate::font::load_system_default_font()?;
-        Ok(Self::with_wrap(
-            text, &font, font_size, max_width, wrap_mode,
+        let fonts = crate::font::load_system_fallback_chain()?;
+        Ok(Self::with_font_fallback(
+            text, &fonts, font_size, max_width, wrap_mode,
         ))
     }
 
+    /// Faces consulted after the primary font.
+    pub fn fallback_fonts(&self) -> &[FontFace] {
+        &self.fallback_fonts
+    }
+
+    /// Justification, hyphenation and tab stops the text is laid out with.
+    pub fn paragraph_style(&self) -> &ParagraphStyle {
+        &self.style
+    }
+
+    /// Change the paragraph style and lay out the whole text again.
+    pub fn set_paragraph_style(&mut self, style: ParagraphStyle) {
+        self.style = style;
+        self.paragraph_cache.clear();
+        let fonts = FontFallbackChain::with_fallbacks(
+            self.params.font.clone(),
+            self.fallback_fonts.clone(),
+        );
+        self.layout_all(&fonts);
+    }
+
     /// Underlying source text.
+    ///
+    /// After an edit this copies the rope into one string, once; use
+    /// [`rope`](Self::rope) to read the text without the copy.
     pub fn text(&self) -> &str {
+        self.flat_text.get_or_init(|| self.text.to_string())
+    }
+
+    /// Underlying source text as stored.
+    pub fn rope(&self) -> &Rope {
         &self.text
     }
 
+    /// Length of the text in bytes, without copying it.
+    pub fn text_len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Byte range of paragraph `index` with its trailing newline, so a
+    /// `"\r\n"` pair stays in one piece.
+    fn paragraph_span(&self, index: usize) -> Range<usize> {
+        let range = self.text.paragraph_range(index);
+        if range.end < self.text.len() {
+            range.start..range.end + 1
+        } else {
+            range
+        }
+    }
+
+    /// Snap `position` to a grapheme boundary of the paragraph it is in.
+    fn snap_in_paragraph(&self, position: CursorPosition) -> CursorPosition {
+        let offset = position.byte_offset.min(self.text.len());
+        let span = self.paragraph_span(self.text.paragraph_at(offset));
+        let local = CursorPosition {
+            byte_offset: offset - span.start,
+            ..position
+        };
+        let snapped = local.snap_to_grapheme_boundary(&self.text.slice(span.clone()));
+        CursorPosition {
+            byte_offset: span.start + snapped.byte_offset,
+            ..snapped
+        }
+    }
+
     /// All line boxes in this layout.
     pub fn lines(&self) -> &[LineBox] {
         &self.lines
@@ -211,6 +372,8 @@ impl TextLayout {
 
     /// Calculate the visual rectangle for a cursor at a specific position.
     ///
+    /// At a boundary between runs of different direction the position's
+    /// affinity decides which of the two visual locations is used.
     /// Returns `None` if the position is invalid or outside the text bounds.
     pub fn cursor_rect_at_position(&self, position: CursorPosition) -> Option<CursorRect> {
         let byte_offset = position.byte_offset;
@@ -241,7 +404,7 @@ impl TextLayout {
         let line = &self.lines[line_idx];
 
         // Calculate X position within the line
-        let x = self.calculate_x_at_byte_offset(line, byte_offset);
+        let x = self.caret_x(line, byte_offset, position.affinity);
 
         // Use ascent + descent for cursor height to cover full text height
         let cursor_height = line.ascent + line.descent;
@@ -253,6 +416,38 @@ impl TextLayout {
         ))
     }
 
+    /// Calculate the caret rectangles for a position, splitting the caret
+    /// where bidi text puts the two sides of the position apart.
+    ///
+    /// Usually this is a single full-height rectangle. When the characters
+    /// before and after the position belong to runs of different direction
+    /// (e.g. between Latin and Hebrew text), the caret is drawn in two
+    /// halves: the top half at the location picked by the position's
+    /// affinity and the bottom half at the other one.
+    pub fn cursor_rects_at_position(&self, position: CursorPosition) -> Vec<CursorRect> {
+        let Some(primary) = self.cursor_rect_at_position(position) else {
+            return Vec::new();
+        };
+        let Some(line_idx) = self.find_line_at_byte_offset(position.byte_offset) else {
+            return vec![primary];
+        };
+
+        let other_affinity = match position.affinity {
+            CursorAffinity::Upstream => CursorAffinity::Downstream,
+            CursorAffinity::Downstream => CursorAffinity::Upstream,
+        };
+        let other_x = self.caret_x(&self.lines[line_idx], position.byte_offset, other_affinity);
+        if (other_x - primary.x).abs() < 0.5 {
+            return vec![primary];
+        }
+
+        let half = primary.height / 2.0;
+        vec![
+            CursorRect::new(primary.x, primary.y, primary.width, half),
+            CursorRect::new(other_x, primary.y + half, primary.width, half),
+        ]
+    }
+
     /// Find the line index containing the given byte offset.
     fn find_line_at_byte_offset(&self, byte_offset: usize) -> Option<usize> {
         for (idx, line) in self.lines.iter().enumerate() {
@@ -266,84 +461,76 @@ impl TextLayout {
     /// Calculate the X position for a cursor at the given byte offset within a line.
     /// Uses cluster information for accurate positioning with ligatures.
     fn calculate_x_at_byte_offset(&self, line: &LineBox, byte_offset: usize) -> f32 {
-        // If the offset is at or before the line start, cursor is at the beginning
-        if byte_offset <= line.text_range.start {
-            return 0.0;
-        }
-
-        // If the offset is at or after the line end, cursor is at the end
-        if byte_offset >= line.text_range.end {
-            return line.width;
-        }
+        self.caret_x(line, byte_offset, CursorAffinity::Downstream)
+    }
 
-        // Calculate position within the line by measuring text up to the cursor
-        let mut x = 0.0;
+    /// X position of a caret at `byte_offset` within a line.
+    ///
+    /// Inside a run the caret sits at the leading edge of the character.
+    /// At a boundary between two runs, which may be far apart visually when
+    /// their directions differ, `affinity` picks the run that ends there
+    /// (`Upstream`) or the one that starts there (`Downstream`).
+    fn caret_x(&self, line: &LineBox, byte_offset: usize, affinity: CursorAffinity) -> f32 {
+        let mut before = None;
+        let mut after = None;
         for run in &line.runs {
-            if byte_offset <= run.text_range.start {
-                // Cursor is before this run
-                break;
+            let range = &run.text_range;
+            if range.start < byte_offset && byte_offset < range.end {
+                return run.x_offset + run.caret_x(byte_offset);
             }
-
-            if byte_offset >= run.text_range.end {
-                // Cursor is after this run, add full run width
-                x += run.width;
-                continue;
+            if range.end == byte_offset {
+                before = Some(run);
             }
+            if range.start == byte_offset {
+                after = Some(run);
+            }
+        }
 
-            // Cursor is within this run - use cluster information for precise positioning
-            let offset_in_run = byte_offset - run.text_range.start;
-
-            // Find the cluster containing or just before this offset
-            let mut current_x = 0.0;
-            let mut i = 0;
-
-            while i < run.clusters.len() {
-                let cluster_start = run.clusters[i];
-
-                // If this cluster starts at or after our target offset, we're done
-                if cluster_start as usize >= offset_in_run {
-                    x += current_x;
-                    return x;
-                }
-
-                // Accumulate width of all glyphs in this cluster
-                let mut cluster_width = 0.0;
-                let mut j = i;
-                while j < run.clusters.len() && run.clusters[j] == cluster_start {
-                    cluster_width += run.advances[j];
-                    j += 1;
-                }
-
-                // Check if our offset is within this cluster
-                // Find the next cluster boundary
-                let next_cluster_start = if j < run.clusters.len() {
-                    run.clusters[j] as usize
+        let run = match affinity {
+            CursorAffinity::Upstream => before.or(after),
+            CursorAffinity::Downstream => after.or(before),
+        };
+        match run {
+            Some(run) => run.x_offset + run.caret_x(byte_offset),
+            // Empty line, or an offset outside of it: clamp to the edge
+            // where the line logically starts or ends.
+            None => {
+                let at_end = byte_offset >= line.text_range.end && !line.text_range.is_empty();
+                if at_end != line.is_rtl() {
+                    line.width
                 } else {
-                    run.text_range.len()
-                };
-
-                if offset_in_run < next_cluster_start {
-                    // Offset is within this cluster
-                    // Position at start of cluster (ligatures are indivisible)
-                    x += current_x;
-                    return x;
+                    0.0
                 }
-
-                current_x += cluster_width;
-                i = j;
             }
-
-            // Offset is after all clusters, position at end of run
-            x += run.width;
-            break;
         }
+    }
 
-        x
+    /// Visual extents of `start..end` within a line, left to right.
+    ///
+    /// A range spanning runs of different direction can be discontiguous on
+    /// screen, so it may map to several segments; adjacent ones are merged.
+    fn line_range_segments(&self, line: &LineBox, start: usize, end: usize) -> Vec<(f32, f32)> {
+        let mut segments: Vec<(f32, f32)> = Vec::new();
+        for run in &line.runs {
+            let seg_start = start.max(run.text_range.start);
+            let seg_end = end.min(run.text_range.end);
+            if seg_start >= seg_end {
+                continue;
+            }
+            let x0 = run.x_offset + run.caret_x(seg_start);
+            let x1 = run.x_offset + run.caret_x(seg_end);
+            let (left, right) = (x0.min(x1), x0.max(x1));
+            match segments.last_mut() {
+                Some(last) if (left - last.1).abs() < 0.01 => last.1 = right,
+                _ => segments.push((left, right)),
+            }
+        }
+        segments
     }
 
     /// Validate and snap a cursor position to the nearest grapheme boundary.
     pub fn snap_cursor_to_boundary(&self, position: CursorPosition) -> CursorPosition {
-        position.snap_to_grapheme_boundary(&self.text)
+        self.snap_in_paragraph(position)
     }
 
     /// Create a cursor at the start of the text.
@@ -382,11 +569,9 @@ impl TextLayout {
         let line_idx = self.find_line_at_y(point.y, policy)?;
         let line = &self.lines[line_idx];
 
-        // Hit test within the line to find X position
-        let byte_offset = self.hit_test_line(line, point.x, policy)?;
-
-        // Determine affinity based on position within character
-        let affinity = CursorAffinity::Downstream;
+        // Hit test within the line to find X position. The affinity tells
+        // which side of a direction boundary was hit.
+        let (byte_offset, affinity) = self.hit_test_line(line, point.x, policy)?;
 
         Some(HitTestResult::new(byte_offset, affinity, line_idx))
     }
@@ -476,13 +661,19 @@ impl TextLayout {
 
     /// Hit test within a specific line to find the byte offset at X coordinate.
     /// Handles BiDi text by considering visual order of runs.
-    fn hit_test_line(&self, line: &LineBox, x: f32, policy: HitTestPolicy) -> Option<usize> {
+    fn hit_test_line(
+        &self,
+        line: &LineBox,
+        x: f32,
+        policy: HitTestPolicy,
+    ) -> Option<(usize, CursorAffinity)> {
         // Before line start
         if x <= 0.0 {
             return match policy {
                 HitTestPolicy::Clamp => {
                     // For BiDi, find the visually first run
-                    self.find_visual_start_offset(line)
+                    let offset = self.find_visual_start_offset(line)?;
+                    Some((offset, Self::edge_affinity(line, line.runs.first(), offset)))
                 }
                 HitTestPolicy::Strict => None,
             };
@@ -493,7 +684,8 @@ impl TextLayout {
             return match policy {
                 HitTestPolicy::Clamp => {
                     // For BiDi, find the visually last run
-                    self.find_visual_end_offset(line)
+                    let offset = self.find_visual_end_offset(line)?;
+                    Some((offset, Self::edge_affinity(line, line.runs.last(), offset)))
                 }
                 HitTestPolicy::Strict => None,
             };
@@ -501,31 +693,31 @@ impl TextLayout {
 
         // Empty line
         if line.text_range.is_empty() {
-            return Some(line.text_range.start);
+            return Some((line.text_range.start, CursorAffinity::Downstream));
         }
 
-        // Find the position within the line
-        // Runs are already in visual order from BiDi reordering
-        let mut current_x = 0.0;
+        // Runs are in visual order with line-relative X offsets.
         for run in &line.runs {
-            let run_end_x = current_x + run.width;
-
-            if x >= current_x && x < run_end_x {
-                // Hit is within this run
-                let x_in_run = if run.direction == crate::shaping::Direction::RightToLeft {
-                    // For RTL runs, reverse the X coordinate
-                    run.width - (x - current_x)
-                } else {
-                    x - current_x
-                };
-                return self.hit_test_run(run, x_in_run);
+            if x >= run.x_offset && x < run.x_offset + run.width {
+                let offset = self.hit_test_run(run, x - run.x_offset)?;
+                return Some((offset, Self::edge_affinity(line, Some(run), offset)));
             }
-
-            current_x = run_end_x;
         }
 
         // Shouldn't reach here, but clamp to line end
-        Some(line.text_range.end)
+        Some((line.text_range.end, CursorAffinity::Downstream))
+    }
+
+    /// Affinity for a hit at `offset` inside `run`: `Upstream` when the
+    /// offset is the run's logical end and another run of the line starts
+    /// there, so the caret stays on the run that was hit.
+    fn edge_affinity(line: &LineBox, run: Option<&ShapedRun>, offset: usize) -> CursorAffinity {
+        match run {
+            Some(run) if offset == run.text_range.end && offset != line.text_range.end => {
+                CursorAffinity::Upstream
+            }
+            _ => CursorAffinity::Downstream,
+        }
     }
 
     /// Find the byte offset at the visual start of a line (handles BiDi).
@@ -536,7 +728,7 @@ impl TextLayout {
 
         // The first run in visual order
         let first_run = &line.runs[0];
-        if first_run.direction == crate::shaping::Direction::RightToLeft {
+        if first_run.is_rtl() {
             // RTL run: visual start is logical end
             Some(first_run.text_range.end)
         } else {
@@ -553,7 +745,7 @@ impl TextLayout {
 
         // The last run in visual order
         let last_run = &line.runs[line.runs.len() - 1];
-        if last_run.direction == crate::shaping::Direction::RightToLeft {
+        if last_run.is_rtl() {
             // RTL run: visual end is logical start
             Some(last_run.text_range.start)
         } else {
@@ -563,107 +755,238 @@ impl TextLayout {
     }
 
     /// Hit test within a shaped run to find the byte offset.
-    /// Uses cluster information from HarfBuzz for ligature-aware positioning.
-    fn hit_test_run(&self, run: &crate::shaping::ShapedRun, x: f32) -> Option<usize> {
-        let run_text = &self.text[run.text_range.clone()];
-
-        // Empty run
-        if run_text.is_empty() {
-            return Some(run.text_range.start);
-        }
-
-        // If no glyphs, return start
-        if run.glyphs.is_empty() {
+    /// `x` is relative to the run origin. Uses cluster information from
+    /// HarfBuzz for ligature-aware positioning.
+    fn hit_test_run(&self, run: &ShapedRun, x: f32) -> Option<usize> {
+        // Empty run, or no glyphs: return start
+        if run.text_range.is_empty() || run.glyphs.is_empty() {
             return Some(run.text_range.start);
         }
 
-        // Build cluster boundaries with their X positions
-        // This handles ligatures correctly since multiple glyphs can map to same cluster
-        let mut cluster_bounds: Vec<(u32, f32, f32)> = Vec::new(); // (cluster_byte_offset, x_start, x_end)
-
-        let mut current_x = 0.0;
-        let mut i = 0;
-
-        while i < run.clusters.len() {
-            let cluster_start = run.clusters[i];
-            let cluster_x_start = current_x;
-
-            // Find all glyphs belonging to this cluster
-            let mut cluster_width = 0.0;
-            let mut j = i;
-            while j < run.clusters.len() && run.clusters[j] == cluster_start {
-                cluster_width += run.advances[j];
-                j += 1;
-            }
-
-            let cluster_x_end = current_x + cluster_width;
-            cluster_bounds.push((cluster_start, cluster_x_start, cluster_x_end));
-
-            current_x = cluster_x_end;
-            i = j;
-        }
-
-        // Find the cluster containing the X position
-        for (cluster_offset, x_start, x_end) in cluster_bounds.iter() {
-            if x >= *x_start && x < *x_end {
-                // Check if we're closer to start or end of this cluster
-                let mid_point = (x_start + x_end) / 2.0;
-                if x < mid_point {
-                    // Closer to start of cluster
-                    return Some(run.text_range.start + *cluster_offset as usize);
+        // Find the cluster containing the X position. Clusters are in visual
+        // order, so for RTL runs the leading (logically first) half of a
+        // cluster is its right half.
+        for span in run.cluster_spans() {
+            if x >= span.x_start && x < span.x_end {
+                let mid_point = (span.x_start + span.x_end) / 2.0;
+                let leading_half = if run.is_rtl() {
+                    x >= mid_point
                 } else {
-                    // Closer to end of cluster - find next cluster boundary
-                    // This handles ligatures correctly
-                    let run_text = &self.text[run.text_range.clone()];
-                    let cluster_byte_start = *cluster_offset as usize;
-
-                    // Find the next grapheme boundary after this cluster
-                    for (idx, _) in run_text.grapheme_indices(true) {
-                        if idx > cluster_byte_start {
-                            return Some(run.text_range.start + idx);
-                        }
-                    }
+                    x < mid_point
+                };
+                if leading_half {
+                    return Some(span.text_range.start);
+                }
 
-                    // If no next boundary found, return end of run
-                    return Some(run.text_range.end);
+                // Closer to the trailing edge - find the next grapheme
+                // boundary after the cluster start. This handles ligatures
+                // correctly.
+                let run_text = self.text.slice(run.text_range.clone());
+                let cluster_byte_start = span.text_range.start - run.text_range.start;
+                for (idx, _) in run_text.grapheme_indices(true) {
+                    if idx > cluster_byte_start {
+                        return Some(run.text_range.start + idx);
+                    }
                 }
+
+                // If no next boundary found, return end of run
+                return Some(run.text_range.end);
             }
         }
 
-        // Past all clusters, return end of run
-        Some(run.text_range.end)
+        // Past all clusters, return the run's visual end
+        if run.is_rtl() {
+            Some(run.text_range.start)
+        } else {
+            Some(run.text_range.end)
+        }
     }
 
     // ========================================================================
     // Cursor Movement (Phase 6.3)
     // ========================================================================
 
-    /// Move cursor left by one grapheme cluster.
+    /// Move cursor left by one grapheme cluster in logical order.
     ///
-    /// Returns the new byte offset after moving left.
+    /// Returns the new byte offset after moving left. In right-to-left text
+    /// this moves the caret visually rightwards; see
+    /// [`TextLayout::move_cursor_visual_left`] for on-screen movement.
     pub fn move_cursor_left(&self, byte_offset: usize) -> usize {
-        CursorMovement::move_left_char(&self.text, byte_offset)
+        let offset = byte_offset.min(self.text.len());
+        if offset == 0 {
+            return 0;
+        }
+        // Graphemes never cross a paragraph's trailing newline, so the
+        // paragraph the previous byte is in is enough context.
+        let span = self.paragraph_span(self.text.paragraph_at(offset - 1));
+        let text = self.text.slice(span.clone());
+        span.start + CursorMovement::move_left_char(&text, offset - span.start)
     }
 
-    /// Move cursor right by one grapheme cluster.
+    /// Move cursor right by one grapheme cluster in logical order.
     ///
     /// Returns the new byte offset after moving right.
     pub fn move_cursor_right(&self, byte_offset: usize) -> usize {
-        CursorMovement::move_right_char(&self.text, byte_offset)
+        let offset = byte_offset.min(self.text.len());
+        let span = self.paragraph_span(self.text.paragraph_at(offset));
+        let text = self.text.slice(span.clone());
+        span.start + CursorMovement::move_right_char(&text, offset - span.start)
+    }
+
+    /// Move the caret one grapheme to the left on screen.
+    ///
+    /// Inside right-to-left runs this moves forward through the text, and
+    /// at direction boundaries the returned affinity keeps the caret on the
+    /// side it arrived from. Leaving the line through its visual edge moves
+    /// to the adjacent line.
+    pub fn move_cursor_visual_left(&self, position: CursorPosition) -> CursorPosition {
+        self.move_cursor_visual(position, false)
+    }
+
+    /// Move the caret one grapheme to the right on screen.
+    ///
+    /// See [`TextLayout::move_cursor_visual_left`].
+    pub fn move_cursor_visual_right(&self, position: CursorPosition) -> CursorPosition {
+        self.move_cursor_visual(position, true)
+    }
+
+    fn move_cursor_visual(&self, position: CursorPosition, rightward: bool) -> CursorPosition {
+        let byte_offset = position.byte_offset.min(self.text.len());
+        let Some(line_idx) = self.find_line_at_byte_offset(byte_offset) else {
+            return position;
+        };
+        let line = &self.lines[line_idx];
+
+        // Locate the caret among the line's stops by its on-screen X.
+        let stops = self.visual_caret_stops(line);
+        let x = self.caret_x(line, byte_offset, position.affinity);
+        let current = stops
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| (a.0 - x).abs().total_cmp(&(b.0 - x).abs()))
+            .map_or(0, |(idx, _)| idx);
+
+        let target = if rightward {
+            Some(current + 1).filter(|&idx| idx < stops.len())
+        } else {
+            current.checked_sub(1)
+        };
+        if let Some(target) = target {
+            // Where two stops share an X (a direction boundary), keep the
+            // one belonging to the run the caret is coming from.
+            let group = &stops[target].1;
+            return if rightward {
+                group[0]
+            } else {
+                group[group.len() - 1]
+            };
+        }
+
+        // Leaving the line: the visual edge we crossed is the logical end
+        // of the line unless moving against the paragraph direction.
+        if rightward != line.is_rtl() {
+            match self.lines.get(line_idx + 1) {
+                Some(next) if next.text_range.start > line.text_range.end => {
+                    CursorPosition::new(next.text_range.start)
+                }
+                Some(_) => CursorPosition::new(self.move_cursor_right(line.text_range.end)),
+                None => position,
+            }
+        } else {
+            match line_idx.checked_sub(1).map(|idx| &self.lines[idx]) {
+                Some(prev) => {
+                    CursorPosition::with_affinity(prev.text_range.end, CursorAffinity::Upstream)
+                }
+                None => position,
+            }
+        }
+    }
+
+    /// Caret stops of a line from left to right.
+    ///
+    /// Each entry is an X position and the cursor positions that render
+    /// there; boundaries between runs of different direction hold two.
+    fn visual_caret_stops(&self, line: &LineBox) -> Vec<(f32, Vec<CursorPosition>)> {
+        let mut stops: Vec<(f32, Vec<CursorPosition>)> = Vec::new();
+        if line.runs.is_empty() {
+            stops.push((0.0, vec![CursorPosition::new(line.text_range.start)]));
+            return stops;
+        }
+
+        for run in &line.runs {
+            let run_text = self.text.slice(run.text_range.clone());
+            let mut offsets: Vec<usize> = run_text
+                .grapheme_indices(true)
+                .map(|(idx, _)| run.text_range.start + idx)
+                .chain(core::iter::once(run.text_range.end))
+                .collect();
+            if run.is_rtl() {
+                offsets.reverse();
+            }
+
+            for offset in offsets {
+                let affinity = if offset == run.text_range.end {
+                    CursorAffinity::Upstream
+                } else {
+                    CursorAffinity::Downstream
+                };
+                let position = CursorPosition::with_affinity(offset, affinity);
+                let x = run.x_offset + run.caret_x(offset);
+                match stops.last_mut() {
+                    Some((last_x, group)) if (x - *last_x).abs() < 0.01 => group.push(position),
+                    _ => stops.push((x, vec![position])),
+                }
+            }
+        }
+        stops
     }
 
     /// Move cursor left by one word boundary.
     ///
     /// Returns the new byte offset after moving to the previous word.
     pub fn move_cursor_left_word(&self, byte_offset: usize) -> usize {
-        CursorMovement::move_left_word(&self.text, byte_offset)
+        let offset = byte_offset.min(self.text.len());
+        // Words never span paragraphs; look back one paragraph at a time
+        // for the last word start before `offset`.
+        let mut index = self.text.paragraph_at(offset);
+        loop {
+            let span = self.paragraph_span(index);
+            let local = offset.min(span.end) - span.start;
+            let start = compute_word_boundaries(&self.text.slice(span.clone()))
+                .iter()
+                .rev()
+                .find(|b| b.kind == WordBoundaryKind::Word && b.range.start < local)
+                .map(|b| b.range.start);
+            match start {
+                Some(start) => return span.start + start,
+                None if index == 0 => return 0,
+                None => index -= 1,
+            }
+        }
     }
 
     /// Move cursor right by one word boundary.
     ///
     /// Returns the new byte offset after moving to the next word.
     pub fn move_cursor_right_word(&self, byte_offset: usize) -> usize {
-        CursorMovement::move_right_word(&self.text, byte_offset)
+        let offset = byte_offset.min(self.text.len());
+        // The end of the first word ending after `offset`, searching
+        // forward one paragraph at a time.
+        let last = self.text.paragraph_count() - 1;
+        let mut index = self.text.paragraph_at(offset);
+        loop {
+            let span = self.paragraph_span(index);
+            let local = offset.max(span.start) - span.start;
+            let end = compute_word_boundaries(&self.text.slice(span.clone()))
+                .iter()
+                .find(|b| b.kind == WordBoundaryKind::Word && b.range.end > local)
+                .map(|b| b.range.end);
+            match end {
+                Some(end) => return span.start + end,
+                None if index >= last => return self.text.len(),
+                None => index += 1,
+            }
+        }
     }
 
     /// Move cursor up by one line, maintaining horizontal position.
@@ -694,7 +1017,7 @@ impl TextLayout {
             let line = &self.lines[line_idx];
             let new_offset = self
                 .hit_test_line(line, x, HitTestPolicy::Clamp)
-                .unwrap_or(line.text_range.start);
+                .map_or(line.text_range.start, |(offset, _)| offset);
 
             // If this move changes the logical position, or we've reached
             // the first line, stop. This avoids the "no-op" case when the
@@ -741,7 +1064,7 @@ impl TextLayout {
             let line = &self.lines[line_idx];
             let new_offset = self
                 .hit_test_line(line, x, HitTestPolicy::Clamp)
-                .unwrap_or(line.text_range.start);
+                .map_or(line.text_range.start, |(offset, _)| offset);
 
             // If this move changes the logical position, or we've reached
             // the last line, stop. This avoids the "no-op" case when the
@@ -759,32 +1082,25 @@ impl TextLayout {
 
     /// Move cursor to the start of the current line.
     ///
-    /// Returns the byte offset at the start of the line.
+    /// Returns the byte offset at the logical start of the line, which is
+    /// its right edge in right-to-left paragraphs.
     pub fn move_cursor_line_start(&self, byte_offset: usize) -> usize {
         let Some(line_idx) = self.find_line_at_byte_offset(byte_offset) else {
             return 0;
         };
 
-        let line = &self.lines[line_idx];
-
-        // For BiDi text, find the visual start of the line
-        self.find_visual_start_offset(line)
-            .unwrap_or(line.text_range.start)
+        self.lines[line_idx].text_range.start
     }
 
     /// Move cursor to the end of the current line.
     ///
-    /// Returns the byte offset at the end of the line.
+    /// Returns the byte offset at the logical end of the line.
     pub fn move_cursor_line_end(&self, byte_offset: usize) -> usize {
         let Some(line_idx) = self.find_line_at_byte_offset(byte_offset) else {
             return self.text.len();
         };
 
-        let line = &self.lines[line_idx];
-
-        // For BiDi text, find the visual end of the line
-        self.find_visual_end_offset(line)
-            .unwrap_or(line.text_range.end)
+        self.lines[line_idx].text_range.end
     }
 
     /// Move cursor to the start of the document.
@@ -808,6 +1124,8 @@ impl TextLayout {
     /// Calculate selection rectangles for rendering a selection.
     ///
     /// Returns a vector of rectangles, one for each line that the selection spans.
+    /// A line mixing text directions can need several rectangles, since a
+    /// logical range may be split up on screen.
     /// Empty if the selection is collapsed.
     pub fn selection_rects(&self, selection: &Selection) -> Vec<SelectionRect> {
         if selection.is_collapsed() {
@@ -831,19 +1149,17 @@ impl TextLayout {
             let line_sel_start = start.max(line.text_range.start);
             let line_sel_end = end.min(line.text_range.end);
 
-            // Calculate X positions for start and end of selection on this line
-            let x_start = self.calculate_x_at_byte_offset(line, line_sel_start);
-            let x_end = self.calculate_x_at_byte_offset(line, line_sel_end);
-
             // Use ascent + descent for selection height to cover full text height
             // even when line_height is smaller for tighter spacing
             let selection_height = line.ascent + line.descent;
-            rects.push(SelectionRect::new(
-                x_start,
-                line.y_offset,
-                x_end - x_start,
-                selection_height,
-            ));
+            for (x_start, x_end) in self.line_range_segments(line, line_sel_start, line_sel_end) {
+                rects.push(SelectionRect::new(
+                    x_start,
+                    line.y_offset,
+                    x_end - x_start,
+                    selection_height,
+                ));
+            }
         }
 
         rects
@@ -859,28 +1175,40 @@ impl TextLayout {
         }
 
         let offset = byte_offset.min(self.text.len());
-        let boundaries = compute_word_boundaries(&self.text);
+        // Words never span paragraphs.
+        let span = self.paragraph_span(self.text.paragraph_at(offset));
+        let boundaries = compute_word_boundaries(&self.text.slice(span.clone()));
+        let offset = offset - span.start;
 
         // Find the word boundary containing this offset
         // Check both inside the range and at the end boundary (for clicks at word end)
         for boundary in boundaries.iter() {
             // Offset is inside the range (exclusive end)
             if boundary.range.contains(&offset) {
-                return Selection::new(boundary.range.start, boundary.range.end);
+                return Selection::new(
+                    span.start + boundary.range.start,
+                    span.start + boundary.range.end,
+                );
             }
             // Offset is at the start of the range
             if boundary.range.start == offset {
-                return Selection::new(boundary.range.start, boundary.range.end);
+                return Selection::new(
+                    span.start + boundary.range.start,
+                    span.start + boundary.range.end,
+                );
             }
             // Offset is at the exclusive end of the range - select this word if it's an actual word
             // (not whitespace/punctuation), as clicking at the very end should select the word
             if boundary.range.end == offset && boundary.kind == WordBoundaryKind::Word {
-                return Selection::new(boundary.range.start, boundary.range.end);
+                return Selection::new(
+                    span.start + boundary.range.start,
+                    span.start + boundary.range.end,
+                );
             }
         }
 
         // If not found, return collapsed selection
-        Selection::collapsed(offset)
+        Selection::collapsed(span.start + offset)
     }
 
     /// Select the line at the given byte offset.
@@ -920,27 +1248,8 @@ impl TextLayout {
 
         let offset = byte_offset.min(self.text.len());
 
-        // Find paragraph boundaries (text between newlines)
-        let mut para_start = 0;
-        let mut para_end = self.text.len();
-
-        // Search backward for newline or start
-        for (idx, ch) in self.text[..offset].char_indices().rev() {
-            if ch == '\n' {
-                para_start = idx + ch.len_utf8();
-                break;
-            }
-        }
-
-        // Search forward for newline or end
-        for (idx, ch) in self.text[offset..].char_indices() {
-            if ch == '\n' {
-                para_end = offset + idx;
-                break;
-            }
-        }
-
-        Selection::new(para_start, para_end)
+        let range = self.text.paragraph_range(self.text.paragraph_at(offset));
+        Selection::new(range.start, range.end)
     }
 
     /// Extend a selection using a cursor movement operation.
@@ -1128,11 +1437,11 @@ impl TextLayout {
     ///
     /// Ensures both anchor and active positions are at valid grapheme cluster boundaries.
     pub fn snap_selection_to_boundaries(&self, selection: &Selection) -> Selection {
-        let anchor = CursorPosition::new(selection.anchor())
-            .snap_to_grapheme_boundary(&self.text)
+        let anchor = self
+            .snap_in_paragraph(CursorPosition::new(selection.anchor()))
             .byte_offset;
-        let active = CursorPosition::new(selection.active())
-            .snap_to_grapheme_boundary(&self.text)
+        let active = self
+            .snap_in_paragraph(CursorPosition::new(selection.active()))
             .byte_offset;
         Selection::new(anchor, active)
     }
@@ -1453,11 +1762,8 @@ impl TextLayout {
         };
         self.record_operation(operation);
 
-        // Insert the text
-        self.text.insert_str(offset, text);
-
-        // Trigger layout invalidation and re-layout
-        self.relayout(font, font_size, max_width, wrap_mode);
+        // Insert the text and re-layout
+        self.splice(offset..offset, text, font, font_size, max_width, wrap_mode);
 
         new_cursor
     }
@@ -1504,7 +1810,7 @@ impl TextLayout {
         let end = range.end.min(self.text.len());
 
         // Get the old text for undo
-        let old_text = self.text[start..end].to_string();
+        let old_text = self.text.slice(start..end);
 
         // Calculate new cursor position
         let new_cursor = start + text.len();
@@ -1520,14 +1826,8 @@ impl TextLayout {
         };
         self.record_operation(operation);
 
-        // Delete the selection
-        self.text.replace_range(start..end, "");
-
-        // Insert the new text
-        self.text.insert_str(start, text);
-
-        // Trigger layout invalidation and re-layout
-        self.relayout(font, font_size, max_width, wrap_mode);
+        // Replace the selection with the new text and re-layout
+        self.splice(start..end, text, font, font_size, max_width, wrap_mode);
 
         new_cursor
     }
@@ -1546,7 +1846,8 @@ impl TextLayout {
         self.insert_char(cursor_offset, '\n', font, font_size, max_width, wrap_mode)
     }
 
-    /// Insert a tab at the cursor position.
+    /// Insert a tab at the cursor position. The tab advances to the next
+    /// of the paragraph style's tab stops.
     ///
     /// Returns the new cursor position after insertion.
     pub fn insert_tab(
@@ -1591,16 +1892,17 @@ impl TextLayout {
         }
 
         // Find the previous grapheme boundary
-        let mut prev_boundary = 0;
-        for (idx, _) in self.text.grapheme_indices(true) {
-            if idx >= cursor_offset {
+        let (window_start, window) = self.paragraphs_around(cursor_offset);
+        let mut prev_boundary = window_start;
+        for (idx, _) in window.grapheme_indices(true) {
+            if window_start + idx >= cursor_offset {
                 break;
             }
-            prev_boundary = idx;
+            prev_boundary = window_start + idx;
         }
 
         // Get the deleted text for undo
-        let deleted_text = self.text[prev_boundary..cursor_offset].to_string();
+        let deleted_text = self.text.slice(prev_boundary..cursor_offset);
 
         // Record the operation for undo
         let operation = TextOperation::Delete {
@@ -1612,10 +1914,14 @@ impl TextLayout {
         self.record_operation(operation);
 
         // Delete from prev_boundary to cursor_offset
-        self.text.replace_range(prev_boundary..cursor_offset, "");
-
-        // Trigger layout invalidation and re-layout
-        self.relayout(font, font_size, max_width, wrap_mode);
+        self.splice(
+            prev_boundary..cursor_offset,
+            "",
+            font,
+            font_size,
+            max_width,
+            wrap_mode,
+        );
 
         prev_boundary
     }
@@ -1647,19 +1953,24 @@ impl TextLayout {
         }
 
         // Find the next grapheme boundary
-        let mut next_boundary = self.text.len();
-        for (idx, _) in self.text.grapheme_indices(true) {
-            if idx > cursor_offset {
-                next_boundary = idx;
+        let (window_start, window) = self.paragraphs_around(cursor_offset);
+        let mut next_boundary = window_start + window.len();
+        for (idx, _) in window.grapheme_indices(true) {
+            if window_start + idx > cursor_offset {
+                next_boundary = window_start + idx;
                 break;
             }
         }
 
         // Delete from cursor_offset to next_boundary
-        self.text.replace_range(cursor_offset..next_boundary, "");
-
-        // Trigger layout invalidation and re-layout
-        self.relayout(font, font_size, max_width, wrap_mode);
+        self.splice(
+            cursor_offset..next_boundary,
+            "",
+            font,
+            font_size,
+            max_width,
+            wrap_mode,
+        );
 
         cursor_offset
     }
@@ -1680,13 +1991,17 @@ impl TextLayout {
         }
 
         // Find the previous word boundary
-        let prev_word_offset = CursorMovement::move_left_word(&self.text, cursor_offset);
+        let prev_word_offset = self.move_cursor_left_word(cursor_offset);
 
         // Delete from prev_word_offset to cursor_offset
-        self.text.replace_range(prev_word_offset..cursor_offset, "");
-
-        // Trigger layout invalidation and re-layout
-        self.relayout(font, font_size, max_width, wrap_mode);
+        self.splice(
+            prev_word_offset..cursor_offset,
+            "",
+            font,
+            font_size,
+            max_width,
+            wrap_mode,
+        );
 
         prev_word_offset
     }
@@ -1707,13 +2022,17 @@ impl TextLayout {
         }
 
         // Find the next word boundary
-        let next_word_offset = CursorMovement::move_right_word(&self.text, cursor_offset);
+        let next_word_offset = self.move_cursor_right_word(cursor_offset);
 
         // Delete from cursor_offset to next_word_offset
-        self.text.replace_range(cursor_offset..next_word_offset, "");
-
-        // Trigger layout invalidation and re-layout
-        self.relayout(font, font_size, max_width, wrap_mode);
+        self.splice(
+            cursor_offset..next_word_offset,
+            "",
+            font,
+            font_size,
+            max_width,
+            wrap_mode,
+        );
 
         cursor_offset
     }
@@ -1748,7 +2067,7 @@ impl TextLayout {
         let end = range.end.min(self.text.len());
 
         // Get the deleted text for undo
-        let deleted_text = self.text[start..end].to_string();
+        let deleted_text = self.text.slice(start..end);
 
         // Record the operation for undo
         let operation = TextOperation::Delete {
@@ -1760,10 +2079,7 @@ impl TextLayout {
         self.record_operation(operation);
 
         // Delete the range
-        self.text.replace_range(start..end, "");
-
-        // Trigger layout invalidation and re-layout
-        self.relayout(font, font_size, max_width, wrap_mode);
+        self.splice(start..end, "", font, font_size, max_width, wrap_mode);
 
         start
     }
@@ -1785,31 +2101,25 @@ impl TextLayout {
 
         let offset = cursor_offset.min(self.text.len());
 
-        // Find line boundaries (between newlines)
-        let mut line_start = 0;
-        let mut line_end = self.text.len();
-
-        // Search backward for newline or start
-        for (idx, ch) in self.text[..offset].char_indices().rev() {
-            if ch == '\n' {
-                line_start = idx + ch.len_utf8();
-                break;
-            }
-        }
-
-        // Search forward for newline or end
-        for (idx, ch) in self.text[offset..].char_indices() {
-            if ch == '\n' {
-                line_end = offset + idx + ch.len_utf8(); // Include the newline
-                break;
-            }
-        }
+        // Find line boundaries (between newlines), including the newline
+        let index = self.text.paragraph_at(offset);
+        let line = self.text.paragraph_range(index);
+        let line_start = line.start;
+        let line_end = if index + 1 < self.text.paragraph_count() {
+            line.end + 1
+        } else {
+            line.end
+        };
 
         // Delete the line
-        self.text.replace_range(line_start..line_end, "");
-
-        // Trigger layout invalidation and re-layout
-        self.relayout(font, font_size, max_width, wrap_mode);
+        self.splice(
+            line_start..line_end,
+            "",
+            font,
+            font_size,
+            max_width,
+            wrap_mode,
+        );
 
         line_start.min(self.text.len())
     }
@@ -1837,14 +2147,14 @@ impl TextLayout {
         let start = range.start.min(self.text.len());
         let end = range.end.min(self.text.len());
 
-        let selected_text = &self.text[start..end];
+        let selected_text = self.text.slice(start..end);
 
         // Access clipboard and set text
         let mut clipboard =
             arboard::Clipboard::new().map_err(|e| format!("Failed to access clipboard: {}", e))?;
 
         clipboard
-            .set_text(selected_text.to_string())
+            .set_text(selected_text)
             .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
 
         Ok(())
@@ -2035,11 +2345,18 @@ impl TextLayout {
                 TextOperation::Insert { offset, text, .. } => {
                     // Undo insert by deleting
                     let end = offset + text.len();
-                    self.text.replace_range(*offset..end, "");
+                    self.splice(*offset..end, "", font, font_size, max_width, wrap_mode);
                 }
                 TextOperation::Delete { offset, text, .. } => {
                     // Undo delete by inserting
-                    self.text.insert_str(*offset, text);
+                    self.splice(
+                        *offset..*offset,
+                        text,
+                        font,
+                        font_size,
+                        max_width,
+                        wrap_mode,
+                    );
                 }
                 TextOperation::Replace {
                     offset,
@@ -2049,14 +2366,18 @@ impl TextLayout {
                 } => {
                     // Undo replace by replacing back
                     let end = offset + new_text.len();
-                    self.text.replace_range(*offset..end, old_text);
+                    self.splice(
+                        *offset..end,
+                        old_text,
+                        font,
+                        font_size,
+                        max_width,
+                        wrap_mode,
+                    );
                 }
             }
         }
 
-        // Re-layout after undo
-        self.relayout(font, font_size, max_width, wrap_mode);
-
         // Return the selection from before the operation
         if let Some(first_op) = operations.first() {
             let selection = first_op.selection_before().clone();
@@ -2093,11 +2414,18 @@ impl TextLayout {
         for operation in operations.iter() {
             match operation {
                 TextOperation::Insert { offset, text, .. } => {
-                    self.text.insert_str(*offset, text);
+                    self.splice(
+                        *offset..*offset,
+                        text,
+                        font,
+                        font_size,
+                        max_width,
+                        wrap_mode,
+                    );
                 }
                 TextOperation::Delete { offset, text, .. } => {
                     let end = offset + text.len();
-                    self.text.replace_range(*offset..end, "");
+                    self.splice(*offset..end, "", font, font_size, max_width, wrap_mode);
                 }
                 TextOperation::Replace {
                     offset,
@@ -2106,14 +2434,18 @@ impl TextLayout {
                     ..
                 } => {
                     let end = offset + old_text.len();
-                    self.text.replace_range(*offset..end, new_text);
+                    self.splice(
+                        *offset..end,
+                        new_text,
+                        font,
+                        font_size,
+                        max_width,
+                        wrap_mode,
+                    );
                 }
             }
         }
 
-        // Re-layout after redo
-        self.relayout(font, font_size, max_width, wrap_mode);
-
         // Return the selection from after the operation
         if let Some(last_op) = operations.last() {
             let selection = last_op.selection_after().clone();
@@ -2161,6 +2493,18 @@ impl TextLayout {
         self.undo_stack.set_grouping(enabled);
     }
 
+    /// Text of the paragraphs around `offset`, from the start of the one
+    /// before it to the end of the one after it, and the offset it starts
+    /// at. Grapheme clusters never span the start of a paragraph, so the
+    /// cluster boundaries near `offset` can be found in it without
+    /// segmenting the whole text.
+    fn paragraphs_around(&self, offset: usize) -> (usize, String) {
+        let index = self.text.paragraph_at(offset);
+        let start = self.text.paragraph_range(index.saturating_sub(1)).start;
+        let end = self.text.paragraph_range(index + 1).end;
+        (start, self.text.slice(start..end))
+    }
+
     /// Record a text operation in the undo stack.
     ///
     /// This is called internally by text modification methods.
@@ -2236,56 +2580,13 @@ impl TextLayout {
         let line_idx = self.find_line_at_byte_offset(byte_offset)?;
         let line = &self.lines[line_idx];
 
-        // Find the run containing this offset
-        for run in &line.runs {
-            if byte_offset < run.text_range.start || byte_offset >= run.text_range.end {
-                continue;
-            }
-
-            let offset_in_run = byte_offset - run.text_range.start;
-
-            // Find the cluster containing this offset
-            let mut i = 0;
-            while i < run.clusters.len() {
-                let cluster_start = run.clusters[i] as usize;
-
-                // Find the next cluster boundary
-                let next_cluster_start = if i + 1 < run.clusters.len() {
-                    // Find next different cluster value
-                    let mut j = i + 1;
-                    while j < run.clusters.len() && run.clusters[j] == run.clusters[i] {
-                        j += 1;
-                    }
-                    if j < run.clusters.len() {
-                        run.clusters[j] as usize
-                    } else {
-                        run.text_range.len()
-                    }
-                } else {
-                    run.text_range.len()
-                };
-
-                // Check if our offset is within this cluster
-                if offset_in_run >= cluster_start && offset_in_run < next_cluster_start {
-                    // Calculate the width of this cluster
-                    let mut cluster_width = 0.0;
-                    let mut j = i;
-                    while j < run.clusters.len() && run.clusters[j] == run.clusters[i] {
-                        cluster_width += run.advances[j];
-                        j += 1;
-                    }
-                    return Some(cluster_width);
-                }
-
-                // Move to next cluster
-                i += 1;
-                while i < run.clusters.len() && run.clusters[i] == run.clusters[i - 1] {
-                    i += 1;
-                }
-            }
-        }
-
-        None
+        // Find the cluster containing this offset
+        line.runs
+            .iter()
+            .filter(|run| run.text_range.contains(&byte_offset))
+            .flat_map(|run| run.cluster_spans())
+            .find(|span| span.text_range.contains(&byte_offset))
+            .map(|span| span.x_end - span.x_start)
     }
 
     /// Get the line height used in this layout.
@@ -2388,11 +2689,12 @@ impl TextLayout {
             let line_start = start_offset.max(line.text_range.start);
             let line_end = end_offset.min(line.text_range.end);
 
-            // Calculate X positions for start and end
-            let x_start = self.calculate_x_at_byte_offset(line, line_start);
-            let x_end = self.calculate_x_at_byte_offset(line, line_end);
-
-            let line_width = x_end - x_start;
+            // Sum the visual segments; mixed-direction text may split them
+            let line_width: f32 = self
+                .line_range_segments(line, line_start, line_end)
+                .iter()
+                .map(|(x_start, x_end)| x_end - x_start)
+                .sum();
             max_width = max_width.max(line_width);
         }
 
@@ -2425,104 +2727,673 @@ impl TextLayout {
             .unwrap_or(0.0)
     }
 
+    // ========================================================================
+    // Writing Modes
+    // ========================================================================
+
+    /// Direction the lines run and stack in.
+    pub fn writing_mode(&self) -> WritingMode {
+        self.style.writing_mode
+    }
+
+    /// Size of the text on screen as `(width, height)`: the
+    /// [`text_bounds`](Self::text_bounds), turned around for vertical text.
+    pub fn physical_size(&self) -> (f32, f32) {
+        let (width, height) = self.text_bounds();
+        if self.style.writing_mode.is_vertical() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Map a point in line coordinates (x along a line, y across the lines)
+    /// to on-screen coordinates relative to the layout origin.
+    ///
+    /// Horizontal text is unchanged. Vertical lines turn a quarter
+    /// clockwise, so x runs down the screen, and stack right to left
+    /// (`VerticalRl`) or left to right (`VerticalLr`).
+    pub fn to_physical(&self, point: Point) -> Point {
+        if !self.style.writing_mode.is_vertical() {
+            return point;
+        }
+        Point::new(self.flip_cross_axis(point.y, point.y), point.x)
+    }
+
+    /// Map an on-screen point relative to the layout origin to line
+    /// coordinates; the inverse of [`to_physical`](Self::to_physical).
+    pub fn to_line_coordinates(&self, point: Point) -> Point {
+        if !self.style.writing_mode.is_vertical() {
+            return point;
+        }
+        Point::new(point.y, self.flip_cross_axis(point.x, point.x))
+    }
+
+    /// [`cursor_rect_at_position`](Self::cursor_rect_at_position) on
+    /// screen. In vertical text the caret lies across the line.
+    pub fn physical_cursor_rect(&self, position: CursorPosition) -> Option<CursorRect> {
+        let rect = self.cursor_rect_at_position(position)?;
+        let (x, y, width, height) = self.physical_rect(rect.x, rect.y, rect.width, rect.height);
+        Some(CursorRect::new(x, y, width, height))
+    }
+
+    /// [`selection_rects`](Self::selection_rects) on screen.
+    pub fn physical_selection_rects(&self, selection: &Selection) -> Vec<SelectionRect> {
+        self.selection_rects(selection)
+            .into_iter()
+            .map(|rect| {
+                let (x, y, width, height) =
+                    self.physical_rect(rect.x, rect.y, rect.width, rect.height);
+                SelectionRect::new(x, y, width, height)
+            })
+            .collect()
+    }
+
+    /// [`hit_test`](Self::hit_test) at an on-screen point.
+    pub fn hit_test_physical(&self, point: Point, policy: HitTestPolicy) -> Option<HitTestResult> {
+        self.hit_test(self.to_line_coordinates(point), policy)
+    }
+
+    /// Screen position across the lines of `cross`, a y in line coordinates
+    /// of vertical text, or the reverse: the mapping is its own inverse.
+    /// `probe` picks the line whose columns are mirrored in `VerticalLr`
+    /// text.
+    fn flip_cross_axis(&self, cross: f32, probe: f32) -> f32 {
+        match self.style.writing_mode {
+            WritingMode::HorizontalTb => cross,
+            WritingMode::VerticalRl => self.total_height() - cross,
+            WritingMode::VerticalLr => match self.find_line_at_y(probe, HitTestPolicy::Clamp) {
+                Some(index) => {
+                    let line = &self.lines[index];
+                    2.0 * line.y_offset + line.height - cross
+                }
+                None => cross,
+            },
+        }
+    }
+
+    /// Turn a rectangle inside one line from line coordinates to the
+    /// screen, as `(x, y, width, height)`.
+    fn physical_rect(&self, x: f32, y: f32, width: f32, height: f32) -> (f32, f32, f32, f32) {
+        if !self.style.writing_mode.is_vertical() {
+            return (x, y, width, height);
+        }
+        let probe = y + height / 2.0;
+        let near = self.flip_cross_axis(y, probe);
+        let far = self.flip_cross_axis(y + height, probe);
+        (near.min(far), x, height, width)
+    }
+
     // ========================================================================
     // Helper Methods for Text Modification
     // ========================================================================
 
-    /// Re-layout the text after modification.
-    ///
-    /// This is called internally after any text insertion or deletion.
-    fn relayout(
-        &mut self,
-        font: &FontFace,
-        font_size: f32,
-        max_width: Option<f32>,
-        wrap_mode: WrapMode,
-    ) {
-        // Clear existing layout
-        self.lines.clear();
+    /// Replace the bytes in `range` with `text` and update the layout.
+    ///
+    /// This is called internally by every edit. Only the paragraphs the
+    /// edit touches are laid out again, reusing cached layouts of
+    /// paragraphs with the same text; the lines after them are moved. A
+    /// change of font, size or wrapping lays out everything, as does any
+    /// edit when lines may be truncated.
+    fn splice(
+        &mut self,
+        range: Range<usize>,
+        text: &str,
+        font: &FontFace,
+        font_size: f32,
+        max_width: Option<f32>,
+        wrap_mode: WrapMode,
+    ) {
+        let fonts = FontFallbackChain::with_fallbacks(font.clone(), self.fallback_fonts.clone());
+        if !self.params.matches(font, font_size, max_width, wrap_mode) || self.style.truncates() {
+            self.text.replace(range, text);
+            self.flat_text = OnceLock::new();
+            self.params = LayoutParams {
+                font: font.clone(),
+                font_size,
+                max_width,
+                wrap_mode,
+            };
+            self.paragraph_cache.clear();
+            self.layout_all(&fonts);
+            return;
+        }
+
+        let first = self.text.paragraph_at(range.start);
+        let last = self.text.paragraph_at(range.end);
+        let first_line: usize = self.paragraph_lines[..first].iter().sum();
+        let old_lines =
+            first_line..first_line + self.paragraph_lines[first..=last].iter().sum::<usize>();
+        let y = self.lines[first_line].y_offset;
+
+        // Keep the layouts of the replaced paragraphs, minus the empty line
+        // of their newline, for reuse.
+        let paragraph_count = self.text.paragraph_count();
+        let mut removed = self.lines.drain(old_lines.clone());
+        for index in first..=last {
+            let para = self.text.paragraph_range(index);
+            let has_newline = index + 1 < paragraph_count;
+            let count = self.paragraph_lines[index] - usize::from(has_newline);
+            let mut lines: Vec<LineBox> = removed.by_ref().take(count).collect();
+            if has_newline {
+                removed.next();
+            }
+            let top = lines.first().map_or(0.0, |l| l.y_offset);
+            for line in &mut lines {
+                line.translate(-(para.start as isize), -top);
+            }
+            let key = ParagraphCache::key(&self.text.slice(para));
+            self.paragraph_cache.insert(key, lines);
+        }
+        drop(removed);
+
+        let byte_shift = text.len() as isize - range.len() as isize;
+        self.text.replace(range, text);
+        self.flat_text = OnceLock::new();
+
+        let new_last = first + text.bytes().filter(|&b| b == b'\n').count();
+        let (lines, line_counts, line_chars) =
+            self.layout_paragraphs(first..new_last + 1, y, &fonts);
+        let mut y = lines.last().map_or(y, LineBox::bottom_y);
+        let added = lines.len();
+        self.lines.splice(first_line..first_line, lines);
+        // Stack the following lines the way a full layout would, so their
+        // offsets don't drift from it over many edits.
+        for line in &mut self.lines[first_line + added..] {
+            line.translate(byte_shift, 0.0);
+            line.y_offset = y;
+            y += line.height;
+        }
+        self.paragraph_lines.splice(first..=last, line_counts);
+        self.prefix_sums.splice(&self.lines, old_lines, &line_chars);
+    }
+
+    /// Lay out every paragraph from scratch.
+    fn layout_all(&mut self, fonts: &FontFallbackChain) {
+        let (lines, line_counts, _) =
+            self.layout_paragraphs(0..self.text.paragraph_count(), 0.0, fonts);
+        self.lines = lines;
+        self.paragraph_lines = line_counts;
+        if self.style.truncates() {
+            self.truncate_lines(fonts);
+        }
+        self.prefix_sums = PrefixSums::new(self.text(), &self.lines);
+    }
+
+    /// Hide the lines past the style's `max_lines`, and put an ellipsis in
+    /// the last line shown and in lines wider than the wrap width.
+    ///
+    /// The last line shown takes over the hidden text's bytes, so the lines
+    /// still cover the whole text.
+    fn truncate_lines(&mut self, fonts: &FontFallbackChain) {
+        let text_len = self.text.len();
+        let text = self.flat_text.get_or_init(|| self.text.to_string());
+        let max_lines = self.style.max_lines.map(|max| max.max(1));
+        let clamped = max_lines.is_some_and(|max| self.lines.len() > max);
+        if let Some(max) = max_lines {
+            self.lines.truncate(max);
+        }
+
+        let last = self.lines.len() - 1;
+        for (index, line) in self.lines.iter_mut().enumerate() {
+            let hides_rest = clamped && index == last;
+            let overflows = self
+                .params
+                .max_width
+                .is_some_and(|max_width| line.width > max_width + 0.01);
+            match self.style.overflow {
+                TextOverflow::Ellipsis(position) if hides_rest || overflows => {
+                    let content = if hides_rest {
+                        line.text_range.start..text_len
+                    } else {
+                        line.text_range.clone()
+                    };
+                    Self::ellipsize(
+                        text,
+                        content,
+                        line,
+                        fonts,
+                        self.params.font_size,
+                        self.params.max_width,
+                        position,
+                        &self.style,
+                    );
+                }
+                _ if hides_rest => {
+                    line.elided = Some(line.text_range.end..text_len);
+                    line.text_range.end = text_len;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether any text is hidden by `max_lines` or an ellipsis.
+    pub fn is_truncated(&self) -> bool {
+        self.lines.iter().any(|line| line.elided.is_some())
+    }
+
+    /// Lay out the paragraphs in `paragraphs` with the first line's top at
+    /// `y`. Returns the lines, the number of lines of each paragraph and
+    /// the number of characters in each line.
+    fn layout_paragraphs(
+        &mut self,
+        paragraphs: Range<usize>,
+        mut y: f32,
+        fonts: &FontFallbackChain,
+    ) -> (Vec<LineBox>, Vec<usize>, Vec<usize>) {
+        let paragraph_count = self.text.paragraph_count();
+        let mut lines = Vec::new();
+        let mut line_counts = Vec::with_capacity(paragraphs.len());
+        let mut line_chars = Vec::new();
+        for index in paragraphs {
+            let para = self.text.paragraph_range(index);
+            let text = self.text.slice(para.clone());
+            let mut para_lines = match self.paragraph_cache.take(ParagraphCache::key(&text)) {
+                Some(lines) => lines,
+                None => self.layout_paragraph_text(&text, fonts),
+            };
+            line_chars.extend(
+                para_lines
+                    .iter()
+                    .map(|l| text[l.text_range.clone()].chars().count()),
+            );
+            if index + 1 < paragraph_count {
+                // Preserve empty line after newline.
+                let mut newline = self.layout_paragraph_text("", fonts);
+                for line in &mut newline {
+                    line.translate(para.len() as isize, 0.0);
+                }
+                line_chars.push(0);
+                para_lines.append(&mut newline);
+            }
+
+            line_counts.push(para_lines.len());
+            for mut line in para_lines {
+                line.translate(para.start as isize, 0.0);
+                line.y_offset = y;
+                y += line.height;
+                lines.push(line);
+            }
+        }
+        (lines, line_counts, line_chars)
+    }
+
+    /// Lay out a single paragraph on its own, with byte ranges relative to
+    /// its start and the first line's top at 0.
+    fn layout_paragraph_text(&self, paragraph: &str, fonts: &FontFallbackChain) -> Vec<LineBox> {
+        let params = &self.params;
+        let scaled = fonts.primary().scaled_metrics(params.font_size);
+        let line_height = Self::resolve_line_height(self.line_height_override, &scaled);
+        let leading = (line_height - scaled.ascent - scaled.descent).max(0.0);
+        let mut y = 0.0f32;
+        let mut lines = Vec::new();
+        Self::layout_paragraph(
+            paragraph,
+            0..paragraph.len(),
+            fonts,
+            params.font_size,
+            line_height,
+            scaled.ascent,
+            scaled.descent,
+            leading,
+            params.max_width,
+            params.wrap_mode,
+            &self.style,
+            &mut y,
+            &mut lines,
+        );
+        lines
+    }
+
+    /// Replace the whole text, e.g. when it's bound to a model that changed
+    /// outside the layout.
+    ///
+    /// Only the paragraphs that differ from the current text are laid out
+    /// again. The change isn't recorded for undo.
+    pub fn set_text(
+        &mut self,
+        text: &str,
+        font: &FontFace,
+        font_size: f32,
+        max_width: Option<f32>,
+        wrap_mode: WrapMode,
+    ) {
+        let old = self.text();
+        let mut prefix = old
+            .bytes()
+            .zip(text.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(prefix) || !text.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let mut suffix = old.as_bytes()[prefix..]
+            .iter()
+            .rev()
+            .zip(text.as_bytes()[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(old.len() - suffix)
+            || !text.is_char_boundary(text.len() - suffix)
+        {
+            suffix -= 1;
+        }
+        let range = prefix..old.len() - suffix;
+        self.splice(
+            range,
+            &text[prefix..text.len() - suffix],
+            font,
+            font_size,
+            max_width,
+            wrap_mode,
+        );
+    }
+}
+
+impl TextLayout {
+    /// Shape one line's worth of text through the fallback chain, returning
+    /// its runs in visual order and their total advance.
+    fn shape_line(
+        text: &str,
+        range: core::ops::Range<usize>,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        base_dir: BaseDirection,
+        style: &ParagraphStyle,
+    ) -> (Vec<ShapedRun>, f32) {
+        let range_start = range.start;
+        let mut runs = Self::shape_text(text, range, fonts, font_size, base_dir, style);
+        runs.sort_by(|a, b| a.x_offset.total_cmp(&b.x_offset));
+        if text.contains(['\t', SOFT_HYPHEN]) {
+            let tab_stops = &style.tab_stops;
+            Self::expand_tabs(text, range_start, &mut runs, fonts, font_size, tab_stops);
+        }
+        let width = runs.iter().map(|run| run.width).sum();
+        (runs, width)
+    }
+
+    /// Shape text through the fallback chain in the style's writing mode.
+    fn shape_text(
+        text: &str,
+        range: Range<usize>,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        base_dir: BaseDirection,
+        style: &ParagraphStyle,
+    ) -> Vec<ShapedRun> {
+        if style.writing_mode.is_vertical() {
+            let orientation = style.text_orientation;
+            TextShaper::shape_with_fallback_vertical(
+                text,
+                range,
+                fonts,
+                font_size,
+                base_dir,
+                orientation,
+            )
+        } else {
+            TextShaper::shape_with_fallback(text, range, fonts, font_size, base_dir)
+        }
+    }
+
+    /// Advance tabs to the next tab stop and make soft hyphens invisible,
+    /// then place the runs side by side again. `runs` are in visual order
+    /// and `text` starts at `range_start` in the source.
+    fn expand_tabs(
+        text: &str,
+        range_start: usize,
+        runs: &mut [ShapedRun],
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        tab_stops: &TabStops,
+    ) {
+        let space_width: f32 =
+            TextShaper::shape_with_fallback(" ", 0..1, fonts, font_size, BaseDirection::Ltr)
+                .iter()
+                .map(|run| run.width)
+                .sum();
+        let mut pen = 0.0f32;
+        for run in runs.iter_mut() {
+            let space = fonts
+                .get(run.font_id)
+                .and_then(|font| font.glyph_id(' '))
+                .unwrap_or(0);
+            for i in 0..run.glyphs.len() {
+                let offset = run.text_range.start - range_start + run.clusters[i] as usize;
+                let advance = match text[offset..].chars().next() {
+                    Some('\t') => tab_stops.next_stop(pen, space_width * 8.0) - pen,
+                    Some(SOFT_HYPHEN) => 0.0,
+                    _ => {
+                        pen += run.advances[i];
+                        continue;
+                    }
+                };
+                run.glyphs[i] = space;
+                run.positions[i] = GlyphPosition {
+                    x_offset: 0.0,
+                    y_offset: 0.0,
+                };
+                run.advances[i] = advance;
+                pen += advance;
+            }
+        }
+        Self::reflow_runs(runs);
+    }
+
+    /// Recompute run widths from their advances and place the runs side by
+    /// side from the line start.
+    fn reflow_runs(runs: &mut [ShapedRun]) {
+        let mut x = 0.0f32;
+        for run in runs {
+            run.width = run.advances.iter().sum();
+            run.x_offset = x;
+            x += run.width;
+        }
+    }
+
+    /// Shape a hyphen or ellipsis drawn at a line break.
+    fn shape_marker(
+        marker: &str,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        base_dir: BaseDirection,
+        style: &ParagraphStyle,
+    ) -> Option<ShapedRun> {
+        Self::shape_text(marker, 0..marker.len(), fonts, font_size, base_dir, style)
+            .into_iter()
+            .next()
+    }
+
+    /// Place the line's hyphen after its last character (left of the runs
+    /// in right-to-left lines) and include it in the line width.
+    fn place_hyphen(line: &mut LineBox) {
+        let content: f32 = line.runs.iter().map(|run| run.width).sum();
+        if let Some(hyphen) = &mut line.hyphen {
+            if line.bidi_level % 2 == 1 {
+                hyphen.x_offset = 0.0;
+                for run in &mut line.runs {
+                    run.x_offset += hyphen.width;
+                }
+            } else {
+                hyphen.x_offset = content;
+            }
+            line.width = content + hyphen.width;
+        }
+    }
+
+    /// Lay `line` out again as the part of `full_text[content]` that fits
+    /// in `max_width` with an ellipsis at `position`; without a width the
+    /// ellipsis is added to the line as it is.
+    ///
+    /// Cuts fall on grapheme boundaries and drop the whitespace next to the
+    /// ellipsis. Kept text comes from the first and last paragraph of
+    /// `content` only. The pieces are placed in the line's direction, so a
+    /// trailing ellipsis sits at the left of a right-to-left line.
+    #[allow(clippy::too_many_arguments)]
+    fn ellipsize(
+        full_text: &str,
+        content: Range<usize>,
+        line: &mut LineBox,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        max_width: Option<f32>,
+        position: EllipsisPosition,
+        style: &ParagraphStyle,
+    ) {
+        let base_dir = if line.is_rtl() {
+            BaseDirection::Rtl
+        } else {
+            BaseDirection::Ltr
+        };
+        let marker = if fonts.primary().has_glyph('\u{2026}') {
+            "\u{2026}"
+        } else {
+            "..."
+        };
+        let Some(mut ellipsis) = Self::shape_marker(marker, fonts, font_size, base_dir, style)
+        else {
+            return;
+        };
+        let available = max_width.map_or(line.width, |max_width| {
+            (max_width - ellipsis.width).max(0.0)
+        });
+        let shape = |range: Range<usize>| {
+            if range.is_empty() {
+                return (Vec::new(), 0.0);
+            }
+            Self::shape_line(
+                &full_text[range.clone()],
+                range,
+                fonts,
+                font_size,
+                base_dir,
+                style,
+            )
+        };
+        // Kept text without the whitespace that would touch the ellipsis.
+        let head = |end: usize| {
+            content.start..content.start + full_text[content.start..end].trim_end().len()
+        };
+        let tail = |start: usize| {
+            content.end - full_text[start..content.end].trim_start().len()..content.end
+        };
+
+        let text = &full_text[content.clone()];
+        let head_limit = content.start + text.find('\n').unwrap_or(text.len());
+        let tail_limit = content.start + text.rfind('\n').map_or(0, |i| i + 1);
+        let mut bounds: Vec<usize> = text
+            .grapheme_indices(true)
+            .map(|(i, _)| content.start + i)
+            .collect();
+        bounds.push(content.end);
+        let heads: Vec<usize> = bounds
+            .iter()
+            .copied()
+            .filter(|&b| b <= head_limit)
+            .collect();
+        let tails: Vec<usize> = bounds
+            .iter()
+            .copied()
+            .filter(|&b| b >= tail_limit)
+            .collect();
+
+        // Widths grow with the kept text, so the longest piece that fits
+        // can be found by bisection.
+        let fit_head = |width: f32| {
+            let fits = heads.partition_point(|&end| shape(head(end)).1 <= width);
+            heads[fits.max(1) - 1]
+        };
+        let fit_tail = |width: f32| {
+            let too_wide = tails.partition_point(|&start| shape(tail(start)).1 > width);
+            tails[too_wide.min(tails.len() - 1)]
+        };
+        let (head_end, tail_start) = match position {
+            EllipsisPosition::End => (fit_head(available), content.end),
+            EllipsisPosition::Start => (content.start, fit_tail(available)),
+            EllipsisPosition::Middle => {
+                let head_end = fit_head(available / 2.0);
+                let used = shape(head(head_end)).1;
+                (head_end, fit_tail(available - used).max(head_end))
+            }
+        };
+
+        let head = head(head_end);
+        let tail = tail(tail_start);
+        let elided = head.end..tail.start;
+        let (head_runs, head_width) = shape(head);
+        let (tail_runs, tail_width) = shape(tail);
+        let ((mut runs, left_width), (right, right_width)) = if line.is_rtl() {
+            ((tail_runs, tail_width), (head_runs, head_width))
+        } else {
+            ((head_runs, head_width), (tail_runs, tail_width))
+        };
+        ellipsis.text_range = elided.start..elided.start;
+        ellipsis.x_offset = left_width;
+        for mut run in right {
+            run.x_offset += left_width + ellipsis.width;
+            runs.push(run);
+        }
 
-        // Compute scaled metrics once; reused for all lines.
-        let scaled = font.scaled_metrics(font_size);
-        let line_height = Self::resolve_line_height(self.line_height_override, &scaled);
-        let leading = (line_height - scaled.ascent - scaled.descent).max(0.0);
+        line.width = left_width + ellipsis.width + right_width;
+        line.text_range = content;
+        line.runs = runs;
+        line.hyphen = None;
+        line.ellipsis = Some(ellipsis);
+        line.elided = Some(elided);
+    }
 
-        let mut y = 0.0f32;
+    /// Widen the spaces of `line` so that its text, without trailing
+    /// whitespace, fills `max_width`. Lines with tabs keep their spacing so
+    /// tab columns stay aligned.
+    fn justify_line(full_text: &str, line: &mut LineBox, max_width: f32) {
+        let text = &full_text[line.text_range.clone()];
+        if text.contains('\t') {
+            return;
+        }
+        let content_end = line.text_range.start + text.trim_end().len();
+        let is_space = |offset: usize| {
+            offset < content_end
+                && matches!(full_text[offset..].chars().next(), Some(' ' | '\u{A0}'))
+        };
 
-        // Split into logical paragraphs on '\n'. Wrapping decisions
-        // are made per paragraph.
-        let mut para_start = 0usize;
-        for (idx, ch) in self.text.char_indices() {
-            if ch == '\n' {
-                Self::layout_paragraph(
-                    &self.text,
-                    para_start..idx,
-                    font,
-                    font_size,
-                    line_height,
-                    scaled.ascent,
-                    scaled.descent,
-                    leading,
-                    max_width,
-                    wrap_mode,
-                    &mut y,
-                    &mut self.lines,
-                );
-                // Preserve empty line after newline.
-                Self::layout_paragraph(
-                    &self.text,
-                    idx..idx,
-                    font,
-                    font_size,
-                    line_height,
-                    scaled.ascent,
-                    scaled.descent,
-                    leading,
-                    max_width,
-                    wrap_mode,
-                    &mut y,
-                    &mut self.lines,
-                );
-                para_start = idx + ch.len_utf8();
+        let mut spaces = 0usize;
+        let mut content_width = line.hyphen.as_ref().map_or(0.0, |hyphen| hyphen.width);
+        for run in &line.runs {
+            for (&cluster, &advance) in run.clusters.iter().zip(&run.advances) {
+                let offset = run.text_range.start + cluster as usize;
+                if offset < content_end {
+                    content_width += advance;
+                }
+                if is_space(offset) {
+                    spaces += 1;
+                }
             }
         }
-        if para_start <= self.text.len() {
-            Self::layout_paragraph(
-                &self.text,
-                para_start..self.text.len(),
-                font,
-                font_size,
-                line_height,
-                scaled.ascent,
-                scaled.descent,
-                leading,
-                max_width,
-                wrap_mode,
-                &mut y,
-                &mut self.lines,
-            );
+        let extra = max_width - content_width;
+        if spaces == 0 || extra <= 0.0 {
+            return;
         }
 
-        // Rebuild prefix sums
-        self.prefix_sums = PrefixSums::new(&self.text, &self.lines);
-    }
-
-    /// Get a mutable reference to the underlying text.
-    ///
-    /// **Warning**: Modifying the text directly will invalidate the layout.
-    /// You must call `relayout()` after making changes, or use the provided
-    /// insertion/deletion methods instead.
-    pub fn text_mut(&mut self) -> &mut String {
-        &mut self.text
+        let per_space = extra / spaces as f32;
+        for run in &mut line.runs {
+            let start = run.text_range.start;
+            for (&cluster, advance) in run.clusters.iter().zip(&mut run.advances) {
+                if is_space(start + cluster as usize) {
+                    *advance += per_space;
+                }
+            }
+        }
+        Self::reflow_runs(&mut line.runs);
+        line.width = line.runs.iter().map(|run| run.width).sum();
+        Self::place_hyphen(line);
     }
-}
 
-impl TextLayout {
     #[allow(clippy::too_many_arguments)]
     fn layout_paragraph(
         full_text: &str,
         range: core::ops::Range<usize>,
-        font: &FontFace,
+        fonts: &FontFallbackChain,
         font_size: f32,
         line_height: f32,
         ascent: f32,
@@ -2530,6 +3401,7 @@ impl TextLayout {
         leading: f32,
         max_width: Option<f32>,
         wrap_mode: WrapMode,
+        style: &ParagraphStyle,
         y: &mut f32,
         out_lines: &mut Vec<LineBox>,
     ) {
@@ -2546,6 +3418,10 @@ impl TextLayout {
                 descent,
                 leading,
                 runs: Vec::new(),
+                hyphen: None,
+                ellipsis: None,
+                elided: None,
+                bidi_level: 0,
                 y_offset: *y,
             };
             *y += line_height;
@@ -2553,18 +3429,27 @@ impl TextLayout {
             return;
         }
 
+        // Lines of a paragraph share its base direction, so a wrapped line
+        // starting with Latin text inside a Hebrew paragraph stays RTL.
+        let base_dir = resolve_base_direction(paragraph, BaseDirection::Auto);
+
         // No wrapping requested or no width constraint: single line.
         if max_width.is_none() || matches!(wrap_mode, WrapMode::NoWrap) {
-            let run = TextShaper::shape_ltr(paragraph, range.clone(), font, 0, font_size);
+            let (runs, width) =
+                Self::shape_line(paragraph, range.clone(), fonts, font_size, base_dir, style);
             let line = LineBox {
                 text_range: range,
-                width: run.width,
+                width,
                 height: line_height,
                 baseline_offset: ascent,
                 ascent,
                 descent,
                 leading,
-                runs: vec![run],
+                runs,
+                hyphen: None,
+                ellipsis: None,
+                elided: None,
+                bidi_level: base_level(base_dir),
                 y_offset: *y,
             };
             *y += line_height;
@@ -2573,19 +3458,22 @@ impl TextLayout {
         }
 
         let max_width = max_width.unwrap();
+        let first_line = out_lines.len();
 
         match wrap_mode {
             WrapMode::BreakWord => {
                 Self::layout_with_word_wrap(
                     full_text,
                     range,
-                    font,
+                    fonts,
                     font_size,
+                    base_dir,
                     line_height,
                     ascent,
                     descent,
                     leading,
                     max_width,
+                    style,
                     y,
                     out_lines,
                 );
@@ -2594,32 +3482,44 @@ impl TextLayout {
                 Self::layout_with_break_all(
                     full_text,
                     range,
-                    font,
+                    fonts,
                     font_size,
+                    base_dir,
                     line_height,
                     ascent,
                     descent,
                     leading,
                     max_width,
+                    style,
                     y,
                     out_lines,
                 );
             }
             WrapMode::NoWrap => unreachable!(),
         }
+
+        // The last line of a paragraph keeps its natural spacing.
+        if style.justify {
+            let last_line = out_lines.len().saturating_sub(1);
+            for line in &mut out_lines[first_line..last_line.max(first_line)] {
+                Self::justify_line(full_text, line, max_width);
+            }
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
     fn layout_with_word_wrap(
         full_text: &str,
         range: core::ops::Range<usize>,
-        font: &FontFace,
+        fonts: &FontFallbackChain,
         font_size: f32,
+        base_dir: BaseDirection,
         line_height: f32,
         ascent: f32,
         descent: f32,
         leading: f32,
         max_width: f32,
+        style: &ParagraphStyle,
         y: &mut f32,
         out_lines: &mut Vec<LineBox>,
     ) {
@@ -2628,43 +3528,64 @@ impl TextLayout {
         let para_len = paragraph.len();
 
         // Precompute line break opportunities within the paragraph.
-        let breaks = compute_line_breaks(paragraph);
+        let breaks = compute_hyphenated_line_breaks(paragraph, &style.hyphens);
+        let hyphen = breaks
+            .iter()
+            .any(|b| b.kind == LineBreakKind::Hyphenation)
+            .then(|| Self::shape_marker("-", fonts, font_size, base_dir, style))
+            .flatten();
 
         while local_start < para_len {
             let mut best_end = None;
 
             // Try all break opportunities after local_start, greedily
-            // picking the last that fits.
+            // picking the last that fits. A line ending at a hyphenation
+            // point needs room for the hyphen.
             for br in breaks.iter().filter(|b| b.offset > local_start) {
                 let local_end = br.offset.min(para_len);
+                let hyphenated = br.kind == LineBreakKind::Hyphenation && local_end < para_len;
+                let hyphen_width = match (&hyphen, hyphenated) {
+                    (Some(hyphen), true) => hyphen.width,
+                    _ => 0.0,
+                };
                 let segment = &paragraph[local_start..local_end];
-                let run = TextShaper::shape_ltr(
+                let (runs, width) = Self::shape_line(
                     segment,
                     (range.start + local_start)..(range.start + local_end),
-                    font,
-                    0,
+                    fonts,
                     font_size,
+                    base_dir,
+                    style,
                 );
-                if run.width <= max_width {
-                    best_end = Some((local_end, run));
+                if width + hyphen_width <= max_width {
+                    best_end = Some((local_end, runs, width, hyphenated));
                 } else {
                     break;
                 }
             }
 
             // If we found a suitable break at a word boundary, use it.
-            if let Some((local_end, run)) = best_end {
-                let line = LineBox {
-                    text_range: (range.start + local_start)..(range.start + local_end),
-                    width: run.width,
+            if let Some((local_end, runs, width, hyphenated)) = best_end {
+                let end = range.start + local_end;
+                let mut line = LineBox {
+                    text_range: (range.start + local_start)..end,
+                    width,
                     height: line_height,
                     baseline_offset: ascent,
                     ascent,
                     descent,
                     leading,
-                    runs: vec![run],
+                    runs,
+                    hyphen: hyphen.clone().filter(|_| hyphenated).map(|mut hyphen| {
+                        hyphen.text_range = end..end;
+                        hyphen
+                    }),
+                    ellipsis: None,
+                    elided: None,
+                    bidi_level: base_level(base_dir),
                     y_offset: *y,
                 };
+                Self::place_hyphen(&mut line);
                 *y += line_height;
                 out_lines.push(line);
                 local_start = local_end;
@@ -2677,30 +3598,35 @@ impl TextLayout {
             for (idx, g) in paragraph[local_start..].grapheme_indices(true) {
                 let local_end = local_start + idx + g.len();
                 let segment = &paragraph[local_start..local_end];
-                let run = TextShaper::shape_ltr(
+                let (runs, width) = Self::shape_line(
                     segment,
                     (range.start + local_start)..(range.start + local_end),
-                    font,
-                    0,
+                    fonts,
                     font_size,
+                    base_dir,
+                    style,
                 );
-                if run.width <= max_width {
-                    best_end = Some((local_end, run));
+                if width <= max_width {
+                    best_end = Some((local_end, runs, width));
                 } else {
                     break;
                 }
             }
 
-            if let Some((local_end, run)) = best_end {
+            if let Some((local_end, runs, width)) = best_end {
                 let line = LineBox {
                     text_range: (range.start + local_start)..(range.start + local_end),
-                    width: run.width,
+                    width,
                     height: line_height,
                     baseline_offset: ascent,
                     ascent,
                     descent,
                     leading,
-                    runs: vec![run],
+                    runs,
+                    hyphen: None,
+                    ellipsis: None,
+                    elided: None,
+                    bidi_level: base_level(base_dir),
                     y_offset: *y,
                 };
                 *y += line_height;
@@ -2712,22 +3638,27 @@ impl TextLayout {
                 if let Some((idx, g)) = iter.next() {
                     let local_end = local_start + idx + g.len();
                     let segment = &paragraph[local_start..local_end];
-                    let run = TextShaper::shape_ltr(
+                    let (runs, width) = Self::shape_line(
                         segment,
                         (range.start + local_start)..(range.start + local_end),
-                        font,
-                        0,
+                        fonts,
                         font_size,
+                        base_dir,
+                        style,
                     );
                     let line = LineBox {
                         text_range: (range.start + local_start)..(range.start + local_end),
-                        width: run.width,
+                        width,
                         height: line_height,
                         baseline_offset: ascent,
                         ascent,
                         descent,
                         leading,
-                        runs: vec![run],
+                        runs,
+                        hyphen: None,
+                        ellipsis: None,
+                        elided: None,
+                        bidi_level: base_level(base_dir),
                         y_offset: *y,
                     };
                     *y += line_height;
@@ -2744,13 +3675,15 @@ impl TextLayout {
     fn layout_with_break_all(
         full_text: &str,
         range: core::ops::Range<usize>,
-        font: &FontFace,
+        fonts: &FontFallbackChain,
         font_size: f32,
+        base_dir: BaseDirection,
         line_height: f32,
         ascent: f32,
         descent: f32,
         leading: f32,
         max_width: f32,
+        style: &ParagraphStyle,
         y: &mut f32,
         out_lines: &mut Vec<LineBox>,
     ) {
@@ -2763,30 +3696,35 @@ impl TextLayout {
             for (idx, g) in paragraph[local_start..].grapheme_indices(true) {
                 let local_end = local_start + idx + g.len();
                 let segment = &paragraph[local_start..local_end];
-                let run = TextShaper::shape_ltr(
+                let (runs, width) = Self::shape_line(
                     segment,
                     (range.start + local_start)..(range.start + local_end),
-                    font,
-                    0,
+                    fonts,
                     font_size,
+                    base_dir,
+                    style,
                 );
-                if run.width <= max_width {
-                    best_end = Some((local_end, run));
+                if width <= max_width {
+                    best_end = Some((local_end, runs, width));
                 } else {
                     break;
                 }
             }
 
-            if let Some((local_end, run)) = best_end {
+            if let Some((local_end, runs, width)) = best_end {
                 let line = LineBox {
                     text_range: (range.start + local_start)..(range.start + local_end),
-                    width: run.width,
+                    width,
                     height: line_height,
                     baseline_offset: ascent,
                     ascent,
                     descent,
                     leading,
-                    runs: vec![run],
+                    runs,
+                    hyphen: None,
+                    ellipsis: None,
+                    elided: None,
+                    bidi_level: base_level(base_dir),
                     y_offset: *y,
                 };
                 *y += line_height;
@@ -2798,22 +3736,27 @@ impl TextLayout {
                 if let Some((idx, g)) = iter.next() {
                     let local_end = local_start + idx + g.len();
                     let segment = &paragraph[local_start..local_end];
-                    let run = TextShaper::shape_ltr(
+                    let (runs, width) = Self::shape_line(
                         segment,
                         (range.start + local_start)..(range.start + local_end),
-                        font,
-                        0,
+                        fonts,
                         font_size,
+                        base_dir,
+                        style,
                     );
                     let line = LineBox {
                         text_range: (range.start + local_start)..(range.start + local_end),
-                        width: run.width,
+                        width,
                         height: line_height,
                         baseline_offset: ascent,
                         ascent,
                         descent,
                         leading,
-                        runs: vec![run],
+                        runs,
+                        hyphen: None,
+                        ellipsis: None,
+                        elided: None,
+                        bidi_level: base_level(base_dir),
                         y_offset: *y,
                     };
                     *y += line_height;
@@ -2827,9 +3770,18 @@ impl TextLayout {
     }
 }
 
+/// Embedding level for a resolved paragraph direction.
+fn base_level(base_dir: BaseDirection) -> u8 {
+    match base_dir {
+        BaseDirection::Rtl => 1,
+        _ => 0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::layout::TextOrientation;
 
     fn create_test_font() -> FontFace {
         // Load a test font - using the Geist font from the fonts directory
@@ -4125,4 +5077,655 @@ mod tests {
         assert_eq!(first, 0);
         assert!(last >= first);
     }
+
+    fn create_fallback_chain() -> (FontFallbackChain, char) {
+        let fonts_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("fonts");
+        let primary = create_test_font();
+        let fallback =
+            FontFace::from_path(fonts_dir.join("Noto_Sans/static/NotoSans-Regular.ttf"), 0)
+                .expect("Failed to load fallback font");
+        let ch = ['ǅ', 'Ѩ', 'Ꞌ', 'ẞ', 'Ƣ', 'ʭ', 'Ϗ', 'Ӂ', '₸', '₼']
+            .into_iter()
+            .find(|&c| !primary.has_glyph(c) && fallback.has_glyph(c))
+            .expect("a glyph only in the fallback font");
+        (FontFallbackChain::with_fallbacks(primary, [fallback]), ch)
+    }
+
+    #[test]
+    fn test_fallback_font_runs() {
+        let (fonts, ch) = create_fallback_chain();
+        let text = format!("ab{ch}cd");
+        let layout = TextLayout::with_font_fallback(&*text, &fonts, 16.0, None, WrapMode::NoWrap);
+
+        let line = &layout.lines()[0];
+        let ids: Vec<u32> = line.runs.iter().map(|run| run.font_id).collect();
+        assert_eq!(ids, vec![0, 1, 0]);
+        let width: f32 = line.runs.iter().map(|run| run.width).sum();
+        assert!((line.width - width).abs() < 0.001);
+        // The fallback glyph is a real glyph, not the primary font's .notdef.
+        assert!(line.runs[1].glyphs.iter().all(|&gid| gid != 0));
+    }
+
+    #[test]
+    fn test_fallback_fonts_survive_edits() {
+        let (fonts, ch) = create_fallback_chain();
+        let mut layout = TextLayout::with_font_fallback("ab", &fonts, 16.0, None, WrapMode::NoWrap);
+        layout.insert_char(2, ch, fonts.primary(), 16.0, None, WrapMode::NoWrap);
+
+        let line = &layout.lines()[0];
+        assert_eq!(line.runs.len(), 2);
+        assert_eq!(line.runs[1].font_id, 1);
+    }
+
+    #[test]
+    fn test_cursor_after_fallback_run() {
+        let (fonts, ch) = create_fallback_chain();
+        let text = format!("{ch}x");
+        let layout = TextLayout::with_font_fallback(&*text, &fonts, 16.0, None, WrapMode::NoWrap);
+
+        let first = &layout.lines()[0].runs[0];
+        let rect = layout
+            .cursor_rect_at_position(CursorPosition::new(ch.len_utf8()))
+            .unwrap();
+        assert!((rect.x - first.width).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_rtl_paragraph_reorders_runs() {
+        let font = create_test_font();
+        let text = "שלום abc";
+        let layout = TextLayout::new(text, &font, 16.0);
+
+        let line = &layout.lines()[0];
+        assert!(line.is_rtl());
+        // The embedded Latin run is displayed first, on the left.
+        assert_eq!(&text[line.runs[0].text_range.clone()], "abc");
+        assert_eq!(line.runs[0].x_offset, 0.0);
+        assert!(line.runs[1].is_rtl());
+
+        // The logical start of an RTL line is its right edge.
+        let start = layout
+            .cursor_rect_at_position(CursorPosition::new(0))
+            .unwrap();
+        assert!((start.x - line.width).abs() < 0.001);
+        let end = layout
+            .cursor_rect_at_position(CursorPosition::new(text.len()))
+            .unwrap();
+        assert!((end.x - line.runs[0].width).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_hit_test_rtl_paragraph() {
+        let font = create_test_font();
+        let text = "שלום abc";
+        let layout = TextLayout::new(text, &font, 16.0);
+        let line = &layout.lines()[0];
+        let y = line.y_offset + 1.0;
+
+        let right = layout
+            .hit_test(Point::new(line.width - 0.1, y), HitTestPolicy::Clamp)
+            .unwrap();
+        assert_eq!(right.byte_offset, 0);
+
+        let left = layout
+            .hit_test(Point::new(0.1, y), HitTestPolicy::Clamp)
+            .unwrap();
+        assert_eq!(left.byte_offset, text.find('a').unwrap());
+
+        let beyond = layout
+            .hit_test(Point::new(line.width + 10.0, y), HitTestPolicy::Clamp)
+            .unwrap();
+        assert_eq!(beyond.byte_offset, 0);
+    }
+
+    #[test]
+    fn test_split_caret_at_direction_boundary() {
+        let font = create_test_font();
+        let text = "abc אב";
+        let layout = TextLayout::new(text, &font, 16.0);
+        let line = &layout.lines()[0];
+        let boundary = 4;
+
+        let upstream = layout
+            .cursor_rect_at_position(CursorPosition::with_affinity(
+                boundary,
+                CursorAffinity::Upstream,
+            ))
+            .unwrap();
+        assert!((upstream.x - line.runs[0].width).abs() < 0.001);
+        let downstream = layout
+            .cursor_rect_at_position(CursorPosition::new(boundary))
+            .unwrap();
+        assert!((downstream.x - line.width).abs() < 0.001);
+
+        let rects = layout.cursor_rects_at_position(CursorPosition::new(boundary));
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[0].x, downstream.x);
+        assert_eq!(rects[1].x, upstream.x);
+        assert!((rects[0].height + rects[1].height - downstream.height).abs() < 0.001);
+
+        // Inside a single-direction run the caret is not split.
+        assert_eq!(
+            layout
+                .cursor_rects_at_position(CursorPosition::new(1))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_visual_cursor_movement_mixed_line() {
+        let font = create_test_font();
+        let layout = TextLayout::new("abc אב", &font, 16.0);
+
+        let mut position = CursorPosition::new(0);
+        let mut rightward = Vec::new();
+        for _ in 0..7 {
+            position = layout.move_cursor_visual_right(position);
+            rightward.push(position.byte_offset);
+        }
+        // Through "abc ", across the Hebrew run from its left edge (the
+        // logical end) to its right edge, then stop at the end of the text.
+        assert_eq!(rightward, vec![1, 2, 3, 4, 6, 4, 4]);
+        assert_eq!(position.affinity, CursorAffinity::Downstream);
+
+        let mut leftward = Vec::new();
+        for _ in 0..3 {
+            position = layout.move_cursor_visual_left(position);
+            leftward.push(position.byte_offset);
+        }
+        assert_eq!(leftward, vec![6, 8, 3]);
+    }
+
+    #[test]
+    fn test_selection_rects_split_across_directions() {
+        let font = create_test_font();
+        let text = "abc אב";
+        let layout = TextLayout::new(text, &font, 16.0);
+
+        // "c", " " and "א": the Hebrew letter sits at the far right.
+        let rects = layout.selection_rects(&Selection::new(2, 6));
+        assert_eq!(rects.len(), 2);
+        let line = &layout.lines()[0];
+        assert!((rects[0].x + rects[0].width - line.runs[0].width).abs() < 0.001);
+        assert!((rects[1].x + rects[1].width - line.width).abs() < 0.001);
+
+        // A selection within one direction stays a single rectangle.
+        assert_eq!(layout.selection_rects(&Selection::new(0, 3)).len(), 1);
+    }
+
+    #[test]
+    fn test_wrapped_lines_keep_paragraph_direction() {
+        let font = create_test_font();
+        let text = "שלום עולם abc def";
+        let width = TextLayout::new("שלום עולם", &font, 16.0).max_line_width();
+        let layout =
+            TextLayout::with_wrap(text, &font, 16.0, Some(width + 1.0), WrapMode::BreakWord);
+
+        assert!(layout.line_count() > 1);
+        assert!(layout.lines().iter().all(|line| line.is_rtl()));
+    }
+
+    #[test]
+    fn test_variation_settings_change_widths() {
+        use crate::font::{FontSettings, FontVariation};
+
+        let font = create_test_font();
+        assert!(font.has_variation_axis(*b"wght"));
+        let bold = font
+            .clone()
+            .with_settings(FontSettings::new().with_variation(FontVariation::weight(900.0)));
+
+        let regular = TextLayout::new("Heading", &font, 32.0).max_line_width();
+        let heavy = TextLayout::new("Heading", &bold, 32.0).max_line_width();
+        assert!(heavy > regular, "{heavy} should exceed {regular}");
+    }
+
+    #[test]
+    fn test_tabular_numbers_equalize_digit_widths() {
+        use crate::font::{FontFeature, FontSettings};
+
+        let font = create_test_font();
+        let tnum = font
+            .clone()
+            .with_settings(FontSettings::new().with_feature(FontFeature::tabular_numbers()));
+
+        let ones = TextLayout::new("1111", &tnum, 16.0).max_line_width();
+        let eights = TextLayout::new("8888", &tnum, 16.0).max_line_width();
+        assert!((ones - eights).abs() < 0.001, "{ones} != {eights}");
+
+        let proportional_ones = TextLayout::new("1111", &font, 16.0).max_line_width();
+        assert!((proportional_ones - ones).abs() > 0.001);
+    }
+
+    fn assert_same_layout(edited: &TextLayout, font: &FontFace, font_size: f32) {
+        let fresh = TextLayout::with_wrap(
+            edited.text(),
+            font,
+            font_size,
+            Some(120.0),
+            WrapMode::BreakWord,
+        );
+        assert_eq!(
+            edited.lines().len(),
+            fresh.lines().len(),
+            "{:?}",
+            edited.text()
+        );
+        for (index, (a, b)) in edited.lines().iter().zip(fresh.lines()).enumerate() {
+            assert_eq!(a.text_range, b.text_range, "line {index}");
+            assert_eq!(a.y_offset, b.y_offset, "line {index}");
+            assert!((a.width - b.width).abs() < 0.001, "line {index}");
+            let runs = |line: &LineBox| -> Vec<_> {
+                line.runs.iter().map(|r| r.text_range.clone()).collect()
+            };
+            assert_eq!(runs(a), runs(b), "line {index}");
+            assert_eq!(
+                edited.char_offset_at_line(index),
+                fresh.char_offset_at_line(index),
+                "line {index}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_incremental_relayout_matches_full_layout() {
+        let font = create_test_font();
+        let wrap = (Some(120.0), WrapMode::BreakWord);
+        let mut layout = TextLayout::with_wrap(
+            "First paragraph that wraps onto a few lines\n\nthird\nfourth héllo wörld",
+            &font,
+            16.0,
+            wrap.0,
+            wrap.1,
+        );
+
+        let cursor = layout.insert_str(5, " extra words", &font, 16.0, wrap.0, wrap.1);
+        assert_same_layout(&layout, &font, 16.0);
+        layout.insert_newline(cursor, &font, 16.0, wrap.0, wrap.1);
+        assert_same_layout(&layout, &font, 16.0);
+
+        let third = layout.text().find("third").unwrap();
+        layout.delete_backward(third, &font, 16.0, wrap.0, wrap.1);
+        assert_same_layout(&layout, &font, 16.0);
+
+        let selection = Selection::new(10, layout.text().find("fourth").unwrap() + 3);
+        layout.replace_selection(&selection, "a\nb\nc", &font, 16.0, wrap.0, wrap.1);
+        assert_same_layout(&layout, &font, 16.0);
+
+        layout.undo(&Selection::collapsed(0), &font, 16.0, wrap.0, wrap.1);
+        assert_same_layout(&layout, &font, 16.0);
+        layout.redo(&Selection::collapsed(0), &font, 16.0, wrap.0, wrap.1);
+        assert_same_layout(&layout, &font, 16.0);
+
+        let end = layout.text().len();
+        layout.delete_line(end, &font, 16.0, wrap.0, wrap.1);
+        assert_same_layout(&layout, &font, 16.0);
+
+        // A new font size lays out every paragraph again.
+        layout.insert_char(0, 'x', &font, 20.0, wrap.0, wrap.1);
+        assert_same_layout(&layout, &font, 20.0);
+    }
+
+    #[test]
+    fn test_undo_reuses_cached_paragraph_layout() {
+        let font = create_test_font();
+        let text = "alpha\nbeta gamma\ndelta";
+        let mut layout = TextLayout::new(text, &font, 16.0);
+
+        let cursor = layout.insert_char(10, 'X', &font, 16.0, None, WrapMode::NoWrap);
+        let old_key = ParagraphCache::key("beta gamma");
+        assert!(layout.paragraph_cache.entries.contains_key(&old_key));
+        assert!(
+            !layout
+                .paragraph_cache
+                .entries
+                .contains_key(&ParagraphCache::key("alpha"))
+        );
+
+        layout.undo(
+            &Selection::collapsed(cursor),
+            &font,
+            16.0,
+            None,
+            WrapMode::NoWrap,
+        );
+        assert_eq!(layout.text(), text);
+        assert!(!layout.paragraph_cache.entries.contains_key(&old_key));
+        assert!(
+            layout
+                .paragraph_cache
+                .entries
+                .contains_key(&ParagraphCache::key("betaX gamma"))
+        );
+    }
+
+    #[test]
+    fn test_set_text_relayouts_changed_paragraphs() {
+        let font = create_test_font();
+        let mut layout = TextLayout::with_wrap(
+            "one\ntwo\nthree",
+            &font,
+            16.0,
+            Some(120.0),
+            WrapMode::BreakWord,
+        );
+        layout.set_text(
+            "one\ntwo and a half, long enough to wrap\nthree",
+            &font,
+            16.0,
+            Some(120.0),
+            WrapMode::BreakWord,
+        );
+        assert_same_layout(&layout, &font, 16.0);
+        assert_eq!(layout.rope().paragraph_count(), 3);
+
+        layout.set_text("", &font, 16.0, Some(120.0), WrapMode::BreakWord);
+        assert_eq!(layout.line_count(), 1);
+        assert!(!layout.can_undo());
+    }
+
+    #[test]
+    fn test_cursor_moves_match_whole_text_moves() {
+        let font = create_test_font();
+        let text = "one two\r\n\n  ,three é\nfour";
+        let layout = TextLayout::new(text, &font, 16.0);
+        for offset in (0..=text.len()).filter(|&i| text.is_char_boundary(i)) {
+            assert_eq!(
+                layout.move_cursor_left(offset),
+                CursorMovement::move_left_char(text, offset),
+                "left from {offset}"
+            );
+            assert_eq!(
+                layout.move_cursor_right(offset),
+                CursorMovement::move_right_char(text, offset),
+                "right from {offset}"
+            );
+            assert_eq!(
+                layout.move_cursor_left_word(offset),
+                CursorMovement::move_left_word(text, offset),
+                "word left from {offset}"
+            );
+            assert_eq!(
+                layout.move_cursor_right_word(offset),
+                CursorMovement::move_right_word(text, offset),
+                "word right from {offset}"
+            );
+        }
+        assert_eq!(layout.text_len(), text.len());
+    }
+
+    #[test]
+    fn test_justified_lines_fill_width() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font.clone());
+        let text = "the quick brown fox jumps over the lazy dog and keeps running";
+        let style = ParagraphStyle::default().with_justify(true);
+        let layout =
+            TextLayout::with_style(text, &fonts, 16.0, Some(150.0), WrapMode::BreakWord, style);
+        let lines = layout.lines();
+        assert!(lines.len() > 2);
+
+        for line in &lines[..lines.len() - 1] {
+            // Trailing spaces hang past the edge.
+            let trailing: f32 = line
+                .runs
+                .iter()
+                .flat_map(|run| run.cluster_spans())
+                .filter(|span| text[span.text_range.clone()].trim().is_empty())
+                .filter(|span| {
+                    span.text_range.start >= text[..line.text_range.end].trim_end().len()
+                })
+                .map(|span| span.x_end - span.x_start)
+                .sum();
+            assert!((line.width - trailing - 150.0).abs() < 0.01, "{line:?}");
+        }
+        let natural = TextLayout::with_wrap(text, &font, 16.0, Some(150.0), WrapMode::BreakWord);
+        assert_eq!(
+            lines.last().unwrap().width,
+            natural.lines().last().unwrap().width
+        );
+    }
+
+    #[test]
+    fn test_hyphenation_breaks_words() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font.clone());
+        let text = "hyphenation hyphenation";
+        let hyphenator =
+            crate::layout::PatternHyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n");
+        let style = ParagraphStyle::default().with_hyphens(crate::layout::Hyphens::Auto(
+            std::sync::Arc::new(hyphenator),
+        ));
+        let width = TextLayout::measure_single_line_width("hyphenation hyphen-", &font, 16.0);
+        let layout = TextLayout::with_style(
+            text,
+            &fonts,
+            16.0,
+            Some(width + 1.0),
+            WrapMode::BreakWord,
+            style,
+        );
+
+        let first = &layout.lines()[0];
+        assert_eq!(&text[first.text_range.clone()], "hyphenation hyphen");
+        let hyphen = first.hyphen.as_ref().expect("line ends with a hyphen");
+        assert_eq!(
+            hyphen.text_range,
+            first.text_range.end..first.text_range.end
+        );
+        assert!(first.width <= width + 1.0);
+        assert!(layout.lines()[1].hyphen.is_none());
+
+        // Manual hyphenation only breaks at soft hyphens.
+        let manual =
+            TextLayout::with_wrap(text, &font, 16.0, Some(width + 1.0), WrapMode::BreakWord);
+        assert_eq!(&text[manual.lines()[0].text_range.clone()], "hyphenation ");
+    }
+
+    #[test]
+    fn test_soft_hyphen_is_invisible_unless_breaking() {
+        let font = create_test_font();
+        let text = "co\u{AD}operate";
+        let layout = TextLayout::new(text, &font, 16.0);
+        let plain = TextLayout::measure_single_line_width("cooperate", &font, 16.0);
+        assert!((layout.lines()[0].width - plain).abs() < 0.01);
+        assert!(layout.lines()[0].hyphen.is_none());
+
+        let width = TextLayout::measure_single_line_width("co-", &font, 16.0);
+        let wrapped =
+            TextLayout::with_wrap(text, &font, 16.0, Some(width + 1.0), WrapMode::BreakWord);
+        assert_eq!(wrapped.lines()[0].text_range, 0.."co\u{AD}".len());
+        assert!(wrapped.lines()[0].hyphen.is_some());
+    }
+
+    #[test]
+    fn test_tabs_advance_to_tab_stops() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font.clone());
+        let style =
+            ParagraphStyle::default().with_tab_stops(TabStops::every(100.0).with_stop(60.0));
+        let layout = TextLayout::with_style("a\tb\tc", &fonts, 16.0, None, WrapMode::NoWrap, style);
+        assert!((layout.offset_to_position(2).unwrap().x - 60.0).abs() < 0.01);
+        assert!((layout.offset_to_position(4).unwrap().x - 100.0).abs() < 0.01);
+
+        // Without stops, tabs advance to multiples of eight spaces.
+        let layout = TextLayout::new("\tx", &font, 16.0);
+        let eight_spaces = TextLayout::measure_single_line_width("        ", &font, 16.0);
+        assert!((layout.offset_to_position(1).unwrap().x - eight_spaces).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_max_lines_ends_with_ellipsis() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font.clone());
+        let text = "the quick brown fox jumps over the lazy dog\nsecond paragraph";
+        let style = ParagraphStyle::default()
+            .with_max_lines(Some(2))
+            .with_overflow(TextOverflow::Ellipsis(EllipsisPosition::End));
+        let layout =
+            TextLayout::with_style(text, &fonts, 16.0, Some(120.0), WrapMode::BreakWord, style);
+
+        assert_eq!(layout.line_count(), 2);
+        assert!(layout.is_truncated());
+        let last = &layout.lines()[1];
+        assert_eq!(last.text_range.end, text.len());
+        let elided = last.elided.clone().unwrap();
+        assert_eq!(elided.end, text.len());
+        let ellipsis = last.ellipsis.as_ref().expect("last line has an ellipsis");
+        assert_eq!(ellipsis.text_range, elided.start..elided.start);
+        assert!(last.width <= 120.0);
+        assert!(!text[..elided.start].ends_with(' '));
+
+        // Without a limit every line is shown.
+        let full = TextLayout::with_wrap(text, &font, 16.0, Some(120.0), WrapMode::BreakWord);
+        assert!(full.line_count() > 2);
+        assert!(!full.is_truncated());
+    }
+
+    #[test]
+    fn test_middle_ellipsis_keeps_both_ends() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font.clone());
+        let text = "a rather long file name.txt";
+        let style = ParagraphStyle::default()
+            .with_overflow(TextOverflow::Ellipsis(EllipsisPosition::Middle));
+        let layout =
+            TextLayout::with_style(text, &fonts, 16.0, Some(100.0), WrapMode::NoWrap, style);
+
+        let line = &layout.lines()[0];
+        let elided = line.elided.clone().unwrap();
+        assert!(elided.start > 0 && elided.end < text.len());
+        assert!(line.width <= 100.0);
+        let ellipsis = line.ellipsis.as_ref().unwrap();
+        let head: f32 = line
+            .runs
+            .iter()
+            .filter(|run| run.text_range.end <= elided.start)
+            .map(|run| run.width)
+            .sum();
+        assert!((ellipsis.x_offset - head).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_clip_hides_lines_without_marker() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font);
+        let text = "one\ntwo\nthree";
+        let style = ParagraphStyle::default().with_max_lines(Some(1));
+        let layout = TextLayout::with_style(text, &fonts, 16.0, None, WrapMode::NoWrap, style);
+
+        assert_eq!(layout.line_count(), 1);
+        let line = &layout.lines()[0];
+        assert!(line.ellipsis.is_none());
+        assert_eq!(line.elided, Some("one".len()..text.len()));
+        assert_eq!(line.text_range, 0..text.len());
+    }
+
+    #[test]
+    fn test_ellipsis_cuts_at_grapheme_boundaries() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font.clone());
+        let text = "e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}";
+        let width = TextLayout::measure_single_line_width(text, &font, 16.0);
+        for position in [
+            EllipsisPosition::Start,
+            EllipsisPosition::Middle,
+            EllipsisPosition::End,
+        ] {
+            let style = ParagraphStyle::default().with_overflow(TextOverflow::Ellipsis(position));
+            let layout = TextLayout::with_style(
+                text,
+                &fonts,
+                16.0,
+                Some(width / 2.0),
+                WrapMode::NoWrap,
+                style,
+            );
+            let elided = layout.lines()[0].elided.clone().unwrap();
+            assert!(!elided.is_empty());
+            for offset in [elided.start, elided.end] {
+                assert!(
+                    offset == text.len() || text[offset..].starts_with('e'),
+                    "{position:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_vertical_upright_runs_advance_down_the_line() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font);
+        let style = ParagraphStyle::default()
+            .with_writing_mode(WritingMode::VerticalRl)
+            .with_text_orientation(TextOrientation::Upright);
+        let layout = TextLayout::with_style("abc", &fonts, 16.0, None, WrapMode::NoWrap, style);
+
+        let line = &layout.lines()[0];
+        assert!(line.runs.iter().all(|run| run.upright));
+        let advances: Vec<f32> = line.runs.iter().flat_map(|r| r.advances.clone()).collect();
+        assert_eq!(advances.len(), 3);
+        assert!(advances.iter().all(|&a| a > 0.0));
+        assert!((line.width - advances.iter().sum::<f32>()).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_vertical_sideways_text_keeps_horizontal_metrics() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font.clone());
+        let style = ParagraphStyle::default().with_writing_mode(WritingMode::VerticalRl);
+        let text = "the quick brown fox";
+        let vertical =
+            TextLayout::with_style(text, &fonts, 16.0, Some(80.0), WrapMode::BreakWord, style);
+        let horizontal = TextLayout::with_wrap(text, &font, 16.0, Some(80.0), WrapMode::BreakWord);
+
+        assert_eq!(vertical.line_count(), horizontal.line_count());
+        for (v, h) in vertical.lines().iter().zip(horizontal.lines()) {
+            assert_eq!(v.text_range, h.text_range);
+            assert!(v.runs.iter().all(|run| !run.upright));
+            assert!((v.width - h.width).abs() < 0.01);
+        }
+        let (width, height) = vertical.physical_size();
+        assert_eq!((height, width), vertical.text_bounds());
+    }
+
+    #[test]
+    fn test_vertical_lines_stack_in_writing_mode_direction() {
+        let font = create_test_font();
+        let fonts = FontFallbackChain::new(font);
+        let text = "one\ntwo";
+        for mode in [WritingMode::VerticalRl, WritingMode::VerticalLr] {
+            let style = ParagraphStyle::default().with_writing_mode(mode);
+            let layout = TextLayout::with_style(text, &fonts, 16.0, None, WrapMode::NoWrap, style);
+            let first = layout.physical_cursor_rect(CursorPosition::new(0)).unwrap();
+            let second = layout.physical_cursor_rect(CursorPosition::new(4)).unwrap();
+            // The caret lies across the line, at the top of its column.
+            assert!(first.width > first.height);
+            assert!(first.y.abs() < 0.01 && second.y.abs() < 0.01);
+            match mode {
+                WritingMode::VerticalRl => assert!(first.x > second.x, "{mode:?}"),
+                _ => assert!(first.x < second.x, "{mode:?}"),
+            }
+
+            for point in [Point::new(5.0, 3.0), Point::new(20.0, 30.0)] {
+                let back = layout.to_line_coordinates(layout.to_physical(point));
+                assert!((back.x - point.x).abs() < 0.01 && (back.y - point.y).abs() < 0.01);
+            }
+
+            // Hitting the last column finds the last line.
+            let last = layout.line_count() - 1;
+            let line = &layout.lines()[last];
+            let point = layout.to_physical(Point::new(1.0, line.y_offset + line.height / 2.0));
+            let hit = layout
+                .hit_test_physical(point, HitTestPolicy::Strict)
+                .unwrap();
+            assert_eq!((hit.line_index, hit.byte_offset), (last, 4));
+        }
+    }
 }
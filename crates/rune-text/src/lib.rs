@@ -13,9 +13,12 @@ pub mod unicode;
 
 pub use font::{
     FontError,
+    color::ColorGlyphImage,
     face::FontFace,
+    fallback::FontFallbackChain,
     loader::{FontCache, FontKey},
     metrics::{FontMetrics, ScaledFontMetrics},
+    settings::{FontFeature, FontSettings, FontVariation},
 };
 
 pub use layout::{
@@ -0,0 +1,247 @@
+use core::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::bidi::BaseDirection;
+use crate::font::FontFallbackChain;
+use crate::layout::TextOrientation;
+use crate::unicode::properties::{Script, is_upright_in_vertical, script_runs};
+
+use super::Direction;
+
+/// A maximal span of text that shapes as a single run: one script, one
+/// bidi embedding level and one face of the fallback chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextItem {
+    /// Byte range in the itemized text.
+    pub range: Range<usize>,
+    pub script: Script,
+    /// BiDi embedding level (even = LTR, odd = RTL).
+    pub bidi_level: u8,
+    /// Index of the face in the fallback chain.
+    pub font_id: u32,
+    /// Whether the item stands upright in a vertical line.
+    pub upright: bool,
+}
+
+impl TextItem {
+    pub fn direction(&self) -> Direction {
+        if self.bidi_level % 2 == 1 {
+            Direction::RightToLeft
+        } else {
+            Direction::LeftToRight
+        }
+    }
+}
+
+/// Split `text` into shaping items by script, bidi level and font coverage.
+///
+/// Items are returned in logical order. Font selection happens per grapheme
+/// cluster, so combining marks and emoji sequences stay with their base
+/// character.
+pub fn itemize(text: &str, fonts: &FontFallbackChain, base_dir: BaseDirection) -> Vec<TextItem> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let scripts = script_runs(text);
+    let levels = bidi_levels(text, base_dir);
+    let single_font = fonts.len() == 1;
+
+    let mut items: Vec<TextItem> = Vec::new();
+    let mut script_idx = 0usize;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        while scripts[script_idx].0.end <= idx {
+            script_idx += 1;
+        }
+        let script = scripts[script_idx].1;
+        let bidi_level = levels.as_ref().map_or(0, |l| l[idx]);
+        let font_id = if single_font {
+            0
+        } else {
+            fonts.font_for_cluster(grapheme)
+        };
+        let end = idx + grapheme.len();
+
+        match items.last_mut() {
+            Some(last)
+                if last.script == script
+                    && last.bidi_level == bidi_level
+                    && last.font_id == font_id =>
+            {
+                last.range.end = end;
+            }
+            _ => items.push(TextItem {
+                range: idx..end,
+                script,
+                bidi_level,
+                font_id,
+                upright: false,
+            }),
+        }
+    }
+    items
+}
+
+/// Split `text` into shaping items for a vertical line.
+///
+/// Like [`itemize`], with items also split where glyphs turn between
+/// upright and sideways under `orientation`. Upright items always read top
+/// to bottom, so right-to-left levels are raised to the next left-to-right
+/// one.
+pub fn itemize_vertical(
+    text: &str,
+    fonts: &FontFallbackChain,
+    base_dir: BaseDirection,
+    orientation: TextOrientation,
+) -> Vec<TextItem> {
+    let mut items = Vec::new();
+    for item in itemize(text, fonts, base_dir) {
+        let mut start = item.range.start;
+        let mut upright = None;
+        for (idx, grapheme) in text[item.range.clone()].grapheme_indices(true) {
+            let idx = item.range.start + idx;
+            let is_upright = match orientation {
+                TextOrientation::Mixed => {
+                    grapheme.chars().next().is_some_and(is_upright_in_vertical)
+                }
+                TextOrientation::Upright => true,
+                TextOrientation::Sideways => false,
+            };
+            if let Some(prev) = upright.filter(|&prev| prev != is_upright) {
+                items.push(vertical_item(&item, start..idx, prev));
+                start = idx;
+            }
+            upright = Some(is_upright);
+        }
+        items.push(vertical_item(
+            &item,
+            start..item.range.end,
+            upright.unwrap_or(false),
+        ));
+    }
+    items
+}
+
+fn vertical_item(item: &TextItem, range: Range<usize>, upright: bool) -> TextItem {
+    let bidi_level = if upright {
+        item.bidi_level + item.bidi_level % 2
+    } else {
+        item.bidi_level
+    };
+    TextItem {
+        range,
+        bidi_level,
+        upright,
+        ..item.clone()
+    }
+}
+
+/// Per-byte embedding levels, or `None` when the whole text is level 0
+/// (no right-to-left characters and a non-RTL base direction).
+fn bidi_levels(text: &str, base_dir: BaseDirection) -> Option<Vec<u8>> {
+    // Hebrew is the first block with strong RTL characters.
+    let may_have_rtl = text.chars().any(|ch| ch >= '\u{0590}');
+    if !may_have_rtl && base_dir != BaseDirection::Rtl {
+        return None;
+    }
+    Some(crate::bidi::levels::levels_per_byte(text, base_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::FontFace;
+
+    fn font(path: &str) -> FontFace {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap();
+        FontFace::from_path(root.join(path), 0).expect("bundled font")
+    }
+
+    fn chain() -> FontFallbackChain {
+        FontFallbackChain::new(font("fonts/Geist/static/Geist-Regular.ttf"))
+    }
+
+    #[test]
+    fn latin_text_is_one_item() {
+        let items = itemize("Hello, world", &chain(), BaseDirection::Auto);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].range, 0..12);
+        assert_eq!(items[0].script, Script::Latin);
+        assert_eq!(items[0].direction(), Direction::LeftToRight);
+    }
+
+    #[test]
+    fn splits_on_script_and_direction() {
+        let text = "abc שלום def";
+        let items = itemize(text, &chain(), BaseDirection::Ltr);
+        let hebrew = items
+            .iter()
+            .find(|i| i.script == Script::Hebrew)
+            .expect("hebrew item");
+        assert_eq!(hebrew.direction(), Direction::RightToLeft);
+        assert!(text[hebrew.range.clone()].starts_with('ש'));
+        // Items tile the text in logical order.
+        assert_eq!(items.first().unwrap().range.start, 0);
+        assert_eq!(items.last().unwrap().range.end, text.len());
+        for pair in items.windows(2) {
+            assert_eq!(pair[0].range.end, pair[1].range.start);
+        }
+    }
+
+    #[test]
+    fn splits_on_font_coverage() {
+        let primary = font("fonts/Geist/static/Geist-Regular.ttf");
+        let fallback = font("fonts/Noto_Sans/static/NotoSans-Regular.ttf");
+        let ch = ['ǅ', 'Ѩ', 'Ꞌ', 'ẞ', 'Ƣ', 'ʭ', 'Ϗ', 'Ӂ', '₸', '₼']
+            .into_iter()
+            .find(|&c| !primary.has_glyph(c) && fallback.has_glyph(c))
+            .expect("a glyph only in Noto Sans");
+        let text = format!("ab{ch}cd");
+        let fonts = FontFallbackChain::with_fallbacks(primary, [fallback]);
+        let items = itemize(&text, &fonts, BaseDirection::Auto);
+        let ids: Vec<u32> = items.iter().map(|i| i.font_id).collect();
+        assert_eq!(ids, vec![0, 1, 0]);
+        assert_eq!(&text[items[1].range.clone()], ch.to_string());
+    }
+
+    #[test]
+    fn vertical_items_split_on_orientation() {
+        let text = "日本abc語";
+        let items = itemize_vertical(text, &chain(), BaseDirection::Ltr, TextOrientation::Mixed);
+        let spans: Vec<(&str, bool)> = items
+            .iter()
+            .map(|i| (&text[i.range.clone()], i.upright))
+            .collect();
+        assert_eq!(spans, vec![("日本", true), ("abc", false), ("語", true)]);
+
+        let items = itemize_vertical(text, &chain(), BaseDirection::Ltr, TextOrientation::Upright);
+        assert!(items.iter().all(|i| i.upright));
+        let items = itemize_vertical(
+            text,
+            &chain(),
+            BaseDirection::Ltr,
+            TextOrientation::Sideways,
+        );
+        assert!(items.iter().all(|i| !i.upright));
+    }
+
+    #[test]
+    fn upright_items_read_left_to_right() {
+        let items = itemize_vertical(
+            "שלום",
+            &chain(),
+            BaseDirection::Rtl,
+            TextOrientation::Upright,
+        );
+        assert!(
+            items
+                .iter()
+                .all(|i| i.direction() == Direction::LeftToRight)
+        );
+    }
+}
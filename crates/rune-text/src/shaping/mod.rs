@@ -1,5 +1,7 @@
+mod itemize;
 mod shaped_run;
 mod shaper;
 
-pub use shaped_run::{Direction, GlyphPosition, Script, ShapedRun};
+pub use itemize::{TextItem, itemize, itemize_vertical};
+pub use shaped_run::{ClusterSpan, Direction, GlyphPosition, Script, ShapedRun};
 pub use shaper::TextShaper;
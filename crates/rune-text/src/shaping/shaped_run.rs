@@ -9,12 +9,8 @@ pub enum Direction {
     RightToLeft,
 }
 
-/// Unicode script tag for the run.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Script {
-    Latin,
-    // Future: add more scripts or use Unicode script property directly.
-}
+/// Unicode script of the run.
+pub use crate::unicode::properties::Script;
 
 /// A run of text shaped with a single font.
 #[derive(Debug, Clone)]
@@ -39,12 +35,17 @@ pub struct ShapedRun {
     pub width: f32,
     /// X offset within line (for alignment).
     pub x_offset: f32,
-    /// BiDi embedding level. For Phase 1.3 we only handle simple LTR (level 0).
+    /// BiDi embedding level (even = LTR, odd = RTL).
     pub bidi_level: u8,
     /// Logical direction of this run.
     pub direction: Direction,
     /// Script of this run.
     pub script: Script,
+    /// Whether the glyphs stand upright in a vertical line. Upright runs
+    /// are shaped top to bottom: advances and `x_offset`s run down the
+    /// line, and `y_offset`s are measured across it from the line's
+    /// central baseline rather than its alphabetic one.
+    pub upright: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -52,3 +53,85 @@ pub struct GlyphPosition {
     pub x_offset: f32,
     pub y_offset: f32,
 }
+
+/// One glyph cluster of a run: the source text it covers and where its
+/// glyphs sit horizontally.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterSpan {
+    /// Byte range in source text covered by the cluster.
+    pub text_range: Range<usize>,
+    /// Left edge relative to the run origin, in pixels.
+    pub x_start: f32,
+    /// Right edge relative to the run origin, in pixels.
+    pub x_end: f32,
+}
+
+impl ShapedRun {
+    /// Whether this run is laid out right-to-left.
+    pub fn is_rtl(&self) -> bool {
+        self.direction == Direction::RightToLeft
+    }
+
+    /// Glyph clusters in visual (left-to-right) order.
+    ///
+    /// Each cluster's text range ends where the logically next cluster
+    /// starts, so ligatures cover all the characters they were formed from.
+    pub fn cluster_spans(&self) -> Vec<ClusterSpan> {
+        let mut starts: Vec<u32> = self.clusters.clone();
+        starts.sort_unstable();
+        starts.dedup();
+
+        let mut spans = Vec::with_capacity(starts.len());
+        let mut x = 0.0f32;
+        let mut i = 0;
+        while i < self.clusters.len() {
+            let cluster = self.clusters[i];
+            let mut width = 0.0;
+            while i < self.clusters.len() && self.clusters[i] == cluster {
+                width += self.advances[i];
+                i += 1;
+            }
+            let end = match starts.binary_search(&cluster) {
+                Ok(pos) if pos + 1 < starts.len() => starts[pos + 1] as usize,
+                _ => self.text_range.len(),
+            };
+            spans.push(ClusterSpan {
+                text_range: (self.text_range.start + cluster as usize)
+                    ..(self.text_range.start + end),
+                x_start: x,
+                x_end: x + width,
+            });
+            x += width;
+        }
+        spans
+    }
+
+    /// X position of a caret placed before the character at `byte_offset`,
+    /// relative to the run origin.
+    ///
+    /// Offsets inside a cluster snap to the cluster's leading edge, which is
+    /// its left side for left-to-right runs and its right side otherwise.
+    pub fn caret_x(&self, byte_offset: usize) -> f32 {
+        let (start_x, end_x) = if self.is_rtl() {
+            (self.width, 0.0)
+        } else {
+            (0.0, self.width)
+        };
+        if byte_offset <= self.text_range.start {
+            return start_x;
+        }
+        if byte_offset >= self.text_range.end {
+            return end_x;
+        }
+        self.cluster_spans()
+            .into_iter()
+            .find(|span| span.text_range.contains(&byte_offset))
+            .map_or(end_x, |span| {
+                if self.is_rtl() {
+                    span.x_end
+                } else {
+                    span.x_start
+                }
+            })
+    }
+}
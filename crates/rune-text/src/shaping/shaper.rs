@@ -6,15 +6,21 @@ use harfrust::{
 };
 use swash::GlyphId;
 
-use crate::font::FontFace;
+use crate::bidi::{BaseDirection, reorder_levels};
+use crate::font::{FontFace, FontFallbackChain};
+use crate::layout::TextOrientation;
+use crate::unicode::properties::is_neutral_script;
 
+use super::itemize::{TextItem, itemize, itemize_vertical};
 use super::{Direction, GlyphPosition, Script, ShapedRun};
 
-/// Simple text shaper for Phase 1.3 built on harfrust (pure-Rust HarfBuzz port).
+/// Text shaper built on harfrust (pure-Rust HarfBuzz port).
 ///
-/// For now this focuses on:
-/// - Single-font runs
-/// - Simple LTR text
+/// - Single-font runs via [`TextShaper::shape`] / [`TextShaper::shape_ltr`]
+/// - Mixed-script text via [`TextShaper::shape_with_fallback`], which
+///   itemizes by script, direction and font coverage
+/// - Vertical lines via [`TextShaper::shape_upright`] and
+///   [`TextShaper::shape_with_fallback_vertical`]
 /// - Kerning and ligatures via HarfBuzz semantics
 pub struct TextShaper;
 
@@ -27,35 +33,116 @@ impl TextShaper {
         font: &FontFace,
         font_id: u32,
         font_size: f32,
+    ) -> ShapedRun {
+        Self::shape(
+            text,
+            text_range,
+            font,
+            font_id,
+            font_size,
+            Script::Latin,
+            Direction::LeftToRight,
+        )
+    }
+
+    /// Shape `text` with one font, script and direction.
+    ///
+    /// Glyphs are returned in visual order, so right-to-left runs list their
+    /// glyphs (and clusters) from the end of the text backwards. Neutral
+    /// scripts (`Common`, `Inherited`) let harfrust guess from the text.
+    /// The face's [`FontSettings`](crate::FontSettings) select the variation
+    /// instance and the OpenType features applied on top of the defaults.
+    pub fn shape(
+        text: &str,
+        text_range: Range<usize>,
+        font: &FontFace,
+        font_id: u32,
+        font_size: f32,
+        script: Script,
+        direction: Direction,
+    ) -> ShapedRun {
+        Self::shape_run(
+            text, text_range, font, font_id, font_size, script, direction, false,
+        )
+    }
+
+    /// Shape `text` with one font and script to stand upright in a vertical
+    /// line.
+    ///
+    /// Glyphs are shaped top to bottom with the font's vertical advances
+    /// and alternates (`vert`). The run's advances and `x_offset`s measure
+    /// down the line; `y_offset`s measure across it from the central
+    /// baseline, growing toward the line's left edge on screen. Fonts
+    /// without vertical metrics advance each glyph by one em.
+    pub fn shape_upright(
+        text: &str,
+        text_range: Range<usize>,
+        font: &FontFace,
+        font_id: u32,
+        font_size: f32,
+        script: Script,
+    ) -> ShapedRun {
+        Self::shape_run(
+            text,
+            text_range,
+            font,
+            font_id,
+            font_size,
+            script,
+            Direction::LeftToRight,
+            true,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn shape_run(
+        text: &str,
+        text_range: Range<usize>,
+        font: &FontFace,
+        font_id: u32,
+        font_size: f32,
+        script: Script,
+        direction: Direction,
+        upright: bool,
     ) -> ShapedRun {
         // Build a harfrust FontRef from the font bytes.
         let font_data = font.as_bytes();
-        let font_ref = HbFontRef::from_index(&font_data, 0).expect("valid font data for harfrust");
+        let font_ref =
+            HbFontRef::from_index(&font_data, font.index()).expect("valid font data for harfrust");
 
-        // Shaper configuration with default (no variations) instance.
+        // Shaper configuration with the face's variation instance.
+        let settings = font.settings();
         let data = ShaperData::new(&font_ref);
-        let instance =
-            ShaperInstance::from_variations(&font_ref, core::iter::empty::<harfrust::Variation>());
+        let instance = ShaperInstance::from_variations(
+            &font_ref,
+            settings.variations.iter().map(|v| v.to_harfrust()),
+        );
         let shaper = data
             .shaper(&font_ref)
             .instance(Some(&instance))
             .point_size(None)
             .build();
 
-        // Build Unicode buffer; this will handle complex scripts, combining
-        // marks, ligatures, etc., though Phase 1.3 focuses on simple LTR usage.
+        // Build Unicode buffer; this handles complex scripts, combining
+        // marks, ligatures, etc.
         let mut buffer = HbUnicodeBuffer::new();
         buffer.push_str(text);
-        buffer.set_direction(HbDirection::LeftToRight);
-        // Latin script for now; later phases can infer script from text.
-        let latin_tag = HbTag::new(b"Latn");
-        if let Some(script) = HbScript::from_iso15924_tag(latin_tag) {
-            buffer.set_script(script);
+        buffer.set_direction(match direction {
+            _ if upright => HbDirection::TopToBottom,
+            Direction::LeftToRight => HbDirection::LeftToRight,
+            Direction::RightToLeft => HbDirection::RightToLeft,
+        });
+        if !is_neutral_script(script)
+            && let Ok(tag) = <[u8; 4]>::try_from(script.short_name().as_bytes())
+            && let Some(hb_script) = HbScript::from_iso15924_tag(HbTag::new(&tag))
+        {
+            buffer.set_script(hb_script);
         }
         // Let harfrust fill in any remaining segment properties.
         buffer.guess_segment_properties();
 
-        let glyph_buffer = shaper.shape(buffer, &[]);
+        let features: Vec<_> = settings.features.iter().map(|f| f.to_harfrust()).collect();
+        let glyph_buffer = shaper.shape(buffer, &features);
         let infos = glyph_buffer.glyph_infos();
         let positions = glyph_buffer.glyph_positions();
 
@@ -78,9 +165,22 @@ impl TextShaper {
 
         for (info, pos) in infos.iter().zip(positions.iter()) {
             let gid = info.glyph_id as GlyphId;
-            let x_advance = pos.x_advance as f32 * scale;
-            let x_offset = pos.x_offset as f32 * scale;
-            let y_offset = -(pos.y_offset as f32) * scale;
+            // Vertical advances and offsets point up in font space; turned
+            // into line coordinates, down the line is +x and the line's
+            // left edge on screen is +y.
+            let (x_advance, x_offset, y_offset) = if upright {
+                (
+                    -(pos.y_advance as f32) * scale,
+                    -(pos.y_offset as f32) * scale,
+                    -(pos.x_offset as f32) * scale,
+                )
+            } else {
+                (
+                    pos.x_advance as f32 * scale,
+                    pos.x_offset as f32 * scale,
+                    -(pos.y_offset as f32) * scale,
+                )
+            };
 
             glyphs.push(gid);
             glyph_positions.push(GlyphPosition {
@@ -105,9 +205,86 @@ impl TextShaper {
             clusters,
             width,
             x_offset: 0.0,
-            bidi_level: 0,
-            direction: Direction::LeftToRight,
-            script: Script::Latin,
+            bidi_level: match direction {
+                Direction::LeftToRight => 0,
+                Direction::RightToLeft => 1,
+            },
+            direction,
+            script,
+            upright,
+        }
+    }
+
+    /// Shape mixed-script text with a font fallback chain.
+    ///
+    /// The text is itemized by script, bidi level and font coverage, and
+    /// each item is shaped with its face. Runs come back in logical order
+    /// with `font_id` indexing into `fonts`; `x_offset` holds the pen
+    /// position where the run starts once the runs are reordered for
+    /// display (UAX-9 rule L2), so callers can draw them as-is. `text` is
+    /// treated as a single line. `text_range` is the range of `text` in the
+    /// caller's source string; run ranges are offset accordingly.
+    pub fn shape_with_fallback(
+        text: &str,
+        text_range: Range<usize>,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        base_dir: BaseDirection,
+    ) -> Vec<ShapedRun> {
+        let items = itemize(text, fonts, base_dir);
+        Self::shape_items(text, text_range, fonts, font_size, items)
+    }
+
+    /// Shape mixed-script text for a vertical line.
+    ///
+    /// Like [`TextShaper::shape_with_fallback`], with the text also split
+    /// where glyphs turn between upright and sideways under `orientation`.
+    /// Upright runs are shaped with [`TextShaper::shape_upright`]; sideways
+    /// runs are shaped as horizontal text that turns with the line.
+    pub fn shape_with_fallback_vertical(
+        text: &str,
+        text_range: Range<usize>,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        base_dir: BaseDirection,
+        orientation: TextOrientation,
+    ) -> Vec<ShapedRun> {
+        let items = itemize_vertical(text, fonts, base_dir, orientation);
+        Self::shape_items(text, text_range, fonts, font_size, items)
+    }
+
+    fn shape_items(
+        text: &str,
+        text_range: Range<usize>,
+        fonts: &FontFallbackChain,
+        font_size: f32,
+        items: Vec<TextItem>,
+    ) -> Vec<ShapedRun> {
+        let mut runs: Vec<ShapedRun> = items
+            .into_iter()
+            .map(|item| {
+                let font = fonts.get(item.font_id).unwrap_or(fonts.primary());
+                let mut run = Self::shape_run(
+                    &text[item.range.clone()],
+                    (text_range.start + item.range.start)..(text_range.start + item.range.end),
+                    font,
+                    item.font_id,
+                    font_size,
+                    item.script,
+                    item.direction(),
+                    item.upright,
+                );
+                run.bidi_level = item.bidi_level;
+                run
+            })
+            .collect();
+
+        let levels: Vec<u8> = runs.iter().map(|run| run.bidi_level).collect();
+        let mut pen_x = 0.0f32;
+        for idx in reorder_levels(&levels) {
+            runs[idx].x_offset = pen_x;
+            pen_x += runs[idx].width;
         }
+        runs
     }
 }
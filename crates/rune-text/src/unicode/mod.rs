@@ -11,3 +11,4 @@ pub use graphemes::{
     GraphemeCluster, grapheme_clusters, is_grapheme_boundary, next_grapheme_boundary,
     prev_grapheme_boundary,
 };
+pub use properties::{Script, char_script, script_runs};
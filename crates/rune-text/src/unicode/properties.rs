@@ -1,5 +1,114 @@
-//! Placeholder for richer Unicode property helpers.
+//! Unicode character property helpers.
 //!
-//! Later phases can add APIs here for things like character
-//! width, script detection, and classification of control
-//! and zero-width characters.
+//! Currently covers script detection for shaping itemization and the
+//! vertical orientation of characters. Later phases can add character width
+//! and classification of control and zero-width characters here.
+
+use core::ops::Range;
+
+pub use unicode_script::Script;
+use unicode_script::UnicodeScript;
+use unicode_vo::{Orientation, char_orientation};
+
+/// Unicode `Script` property of a character.
+pub fn char_script(ch: char) -> Script {
+    ch.script()
+}
+
+/// Whether `script` is shared across scripts (`Common`, `Inherited`,
+/// `Unknown`) and takes on the script of the surrounding text.
+pub fn is_neutral_script(script: Script) -> bool {
+    matches!(script, Script::Common | Script::Inherited | Script::Unknown)
+}
+
+/// Whether `ch` stands upright in vertical text, per its Unicode
+/// `Vertical_Orientation`. CJK ideographs, kana and the brackets and marks
+/// that have vertical forms stand upright; Latin letters and most
+/// punctuation turn sideways.
+pub fn is_upright_in_vertical(ch: char) -> bool {
+    char_orientation(ch) != Orientation::Rotated
+}
+
+/// Split `text` into maximal runs of a single script.
+///
+/// Neutral characters (spaces, punctuation, digits, combining marks) join
+/// the preceding run, or the following one at the start of the text. Text
+/// made only of neutral characters is a single `Common` run. Ranges are
+/// byte offsets into `text`.
+pub fn script_runs(text: &str) -> Vec<(Range<usize>, Script)> {
+    let mut runs: Vec<(Range<usize>, Script)> = Vec::new();
+    let mut current = Script::Common;
+    let mut start = 0usize;
+
+    for (idx, ch) in text.char_indices() {
+        let script = char_script(ch);
+        if is_neutral_script(script) || script == current {
+            continue;
+        }
+        if is_neutral_script(current) {
+            // Leading neutrals adopt the first real script.
+            current = script;
+            continue;
+        }
+        runs.push((start..idx, current));
+        start = idx;
+        current = script;
+    }
+    if start < text.len() {
+        let script = if is_neutral_script(current) {
+            Script::Common
+        } else {
+            current
+        };
+        runs.push((start..text.len(), script));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_script_is_one_run() {
+        assert_eq!(script_runs("Hello, world!"), vec![(0..13, Script::Latin)]);
+    }
+
+    #[test]
+    fn neutrals_join_preceding_run() {
+        let text = "abc 日本語 def";
+        let runs = script_runs(text);
+        let han = text.find('日').unwrap();
+        let def = text.find('d').unwrap();
+        assert_eq!(
+            runs,
+            vec![
+                (0..han, Script::Latin),
+                (han..def, Script::Han),
+                (def..text.len(), Script::Latin),
+            ]
+        );
+    }
+
+    #[test]
+    fn leading_neutrals_join_first_script() {
+        let text = "123 שלום";
+        assert_eq!(script_runs(text), vec![(0..text.len(), Script::Hebrew)]);
+    }
+
+    #[test]
+    fn only_neutrals_is_common() {
+        assert_eq!(script_runs("12 - 34"), vec![(0..7, Script::Common)]);
+        assert!(script_runs("").is_empty());
+    }
+
+    #[test]
+    fn vertical_orientation() {
+        assert!(is_upright_in_vertical('日'));
+        assert!(is_upright_in_vertical('か'));
+        assert!(is_upright_in_vertical('「'));
+        assert!(is_upright_in_vertical('ー'));
+        assert!(!is_upright_in_vertical('A'));
+        assert!(!is_upright_in_vertical('-'));
+    }
+}
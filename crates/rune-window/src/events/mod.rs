@@ -1,9 +1,12 @@
 pub mod types;
 
-pub use types::RuneWindowEvent;
+pub use types::{
+    ImeEvent, Key, KeyEvent, Modifiers, NamedKey, RuneWindowEvent, ScrollDelta, TouchEvent,
+    TouchPhase,
+};
 
 pub fn translate_window_event(event: &winit::event::WindowEvent) -> Option<RuneWindowEvent> {
-    use winit::event::{ElementState, WindowEvent};
+    use winit::event::{ElementState, Ime, MouseScrollDelta, WindowEvent};
     match event {
         WindowEvent::Resized(sz) => Some(RuneWindowEvent::Resized(*sz)),
         WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
@@ -16,8 +19,175 @@ pub fn translate_window_event(event: &winit::event::WindowEvent) -> Option<RuneW
             ElementState::Pressed => Some(RuneWindowEvent::MousePressed(*button)),
             ElementState::Released => Some(RuneWindowEvent::MouseReleased(*button)),
         },
+        WindowEvent::MouseWheel { delta, phase, .. } => Some(RuneWindowEvent::MouseWheel {
+            delta: match delta {
+                MouseScrollDelta::LineDelta(x, y) => ScrollDelta::Lines { x: *x, y: *y },
+                MouseScrollDelta::PixelDelta(p) => ScrollDelta::Pixels {
+                    x: p.x as f32,
+                    y: p.y as f32,
+                },
+            },
+            phase: translate_touch_phase(*phase),
+        }),
+        WindowEvent::KeyboardInput { event, .. } => {
+            let key = KeyEvent {
+                key: translate_key(&event.logical_key),
+                text: event.text.as_ref().map(|t| t.to_string()),
+                repeat: event.repeat,
+            };
+            match event.state {
+                ElementState::Pressed => Some(RuneWindowEvent::KeyPressed(key)),
+                ElementState::Released => Some(RuneWindowEvent::KeyReleased(key)),
+            }
+        }
+        WindowEvent::ModifiersChanged(modifiers) => Some(RuneWindowEvent::ModifiersChanged(
+            translate_modifiers(modifiers.state()),
+        )),
+        WindowEvent::Touch(touch) => Some(RuneWindowEvent::Touch(TouchEvent {
+            id: touch.id,
+            phase: translate_touch_phase(touch.phase),
+            position: [touch.location.x as f32, touch.location.y as f32],
+            force: touch.force.map(|f| f.normalized() as f32),
+        })),
+        WindowEvent::Ime(ime) => Some(RuneWindowEvent::Ime(match ime {
+            Ime::Enabled => ImeEvent::Enabled,
+            Ime::Preedit(text, cursor) => ImeEvent::Preedit {
+                text: text.clone(),
+                cursor: *cursor,
+            },
+            Ime::Commit(text) => ImeEvent::Commit(text.clone()),
+            Ime::Disabled => ImeEvent::Disabled,
+        })),
+        WindowEvent::Focused(focused) => Some(RuneWindowEvent::Focused(*focused)),
+        WindowEvent::HoveredFile(path) => Some(RuneWindowEvent::FileHovered(path.clone())),
+        WindowEvent::HoveredFileCancelled => Some(RuneWindowEvent::FileHoverCancelled),
+        WindowEvent::DroppedFile(path) => Some(RuneWindowEvent::FileDropped(path.clone())),
         WindowEvent::RedrawRequested => Some(RuneWindowEvent::RedrawRequested),
         WindowEvent::CloseRequested => Some(RuneWindowEvent::CloseRequested),
         _ => None,
     }
 }
+
+pub(crate) fn translate_modifiers(state: winit::keyboard::ModifiersState) -> Modifiers {
+    Modifiers {
+        shift: state.shift_key(),
+        ctrl: state.control_key(),
+        alt: state.alt_key(),
+        logo: state.super_key(),
+    }
+}
+
+fn translate_touch_phase(phase: winit::event::TouchPhase) -> TouchPhase {
+    use winit::event::TouchPhase as W;
+    match phase {
+        W::Started => TouchPhase::Started,
+        W::Moved => TouchPhase::Moved,
+        W::Ended => TouchPhase::Ended,
+        W::Cancelled => TouchPhase::Cancelled,
+    }
+}
+
+fn translate_key(key: &winit::keyboard::Key) -> Key {
+    use winit::keyboard::{Key as W, NamedKey as N};
+    let named = match key {
+        W::Character(ch) => return Key::Character(ch.to_string()),
+        W::Named(named) => named,
+        W::Unidentified(_) | W::Dead(_) => return Key::Unidentified,
+    };
+    Key::Named(match named {
+        N::Enter => NamedKey::Enter,
+        N::Tab => NamedKey::Tab,
+        N::Space => NamedKey::Space,
+        N::Backspace => NamedKey::Backspace,
+        N::Delete => NamedKey::Delete,
+        N::Escape => NamedKey::Escape,
+        N::Insert => NamedKey::Insert,
+        N::ArrowLeft => NamedKey::ArrowLeft,
+        N::ArrowRight => NamedKey::ArrowRight,
+        N::ArrowUp => NamedKey::ArrowUp,
+        N::ArrowDown => NamedKey::ArrowDown,
+        N::Home => NamedKey::Home,
+        N::End => NamedKey::End,
+        N::PageUp => NamedKey::PageUp,
+        N::PageDown => NamedKey::PageDown,
+        N::Shift => NamedKey::Shift,
+        N::Control => NamedKey::Control,
+        N::Alt | N::AltGraph => NamedKey::Alt,
+        N::Super | N::Meta | N::Hyper => NamedKey::Logo,
+        N::CapsLock => NamedKey::CapsLock,
+        N::ContextMenu => NamedKey::ContextMenu,
+        N::F1 => NamedKey::F(1),
+        N::F2 => NamedKey::F(2),
+        N::F3 => NamedKey::F(3),
+        N::F4 => NamedKey::F(4),
+        N::F5 => NamedKey::F(5),
+        N::F6 => NamedKey::F(6),
+        N::F7 => NamedKey::F(7),
+        N::F8 => NamedKey::F(8),
+        N::F9 => NamedKey::F(9),
+        N::F10 => NamedKey::F(10),
+        N::F11 => NamedKey::F(11),
+        N::F12 => NamedKey::F(12),
+        N::F13 => NamedKey::F(13),
+        N::F14 => NamedKey::F(14),
+        N::F15 => NamedKey::F(15),
+        N::F16 => NamedKey::F(16),
+        N::F17 => NamedKey::F(17),
+        N::F18 => NamedKey::F(18),
+        N::F19 => NamedKey::F(19),
+        N::F20 => NamedKey::F(20),
+        N::F21 => NamedKey::F(21),
+        N::F22 => NamedKey::F(22),
+        N::F23 => NamedKey::F(23),
+        N::F24 => NamedKey::F(24),
+        _ => return Key::Unidentified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::keyboard::{Key as W, ModifiersState, NamedKey as N};
+
+    #[test]
+    fn keys_map_to_platform_neutral_values() {
+        assert_eq!(
+            translate_key(&W::Character("é".into())),
+            Key::Character("é".to_string())
+        );
+        assert_eq!(
+            translate_key(&W::Named(N::ArrowLeft)),
+            Key::Named(NamedKey::ArrowLeft)
+        );
+        assert_eq!(
+            translate_key(&W::Named(N::F12)),
+            Key::Named(NamedKey::F(12))
+        );
+        assert_eq!(translate_key(&W::Dead(Some('`'))), Key::Unidentified);
+    }
+
+    #[test]
+    fn modifiers_map_from_winit_state() {
+        let m = translate_modifiers(ModifiersState::SHIFT | ModifiersState::SUPER);
+        assert!(m.shift && m.logo && !m.ctrl && !m.alt);
+        assert!(translate_modifiers(ModifiersState::empty()).is_empty());
+    }
+
+    #[test]
+    fn file_drop_and_focus_events_translate() {
+        use winit::event::WindowEvent;
+        let path = std::path::PathBuf::from("/tmp/photo.png");
+        assert!(matches!(
+            translate_window_event(&WindowEvent::DroppedFile(path.clone())),
+            Some(RuneWindowEvent::FileDropped(p)) if p == path
+        ));
+        assert!(matches!(
+            translate_window_event(&WindowEvent::Focused(false)),
+            Some(RuneWindowEvent::Focused(false))
+        ));
+        assert!(matches!(
+            translate_window_event(&WindowEvent::Ime(winit::event::Ime::Commit("漢".into()))),
+            Some(RuneWindowEvent::Ime(ImeEvent::Commit(t))) if t == "漢"
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use winit::dpi::PhysicalSize;
 use winit::event::MouseButton;
 
@@ -5,9 +7,160 @@ use winit::event::MouseButton;
 pub enum RuneWindowEvent {
     Resized(PhysicalSize<u32>),
     ScaleFactorChanged(f64),
-    CursorMoved { position: [f32; 2] },
+    CursorMoved {
+        position: [f32; 2],
+    },
     MousePressed(MouseButton),
     MouseReleased(MouseButton),
+    /// Mouse wheel or trackpad scroll.
+    MouseWheel {
+        delta: ScrollDelta,
+        phase: TouchPhase,
+    },
+    KeyPressed(KeyEvent),
+    KeyReleased(KeyEvent),
+    /// The set of held modifier keys changed.
+    ModifiersChanged(Modifiers),
+    Touch(TouchEvent),
+    /// Input method composition; see [`ImeEvent`].
+    Ime(ImeEvent),
+    /// The window gained (`true`) or lost (`false`) keyboard focus.
+    Focused(bool),
+    /// A file is being dragged over the window.
+    FileHovered(PathBuf),
+    /// A hovered file left the window without being dropped.
+    FileHoverCancelled,
+    /// A file was dropped on the window. Multiple files arrive as one event
+    /// each.
+    FileDropped(PathBuf),
     RedrawRequested,
     CloseRequested,
 }
+
+/// Modifier keys held during an event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// Command on macOS, the Windows key elsewhere.
+    pub logo: bool,
+}
+
+impl Modifiers {
+    /// The platform's shortcut modifier: Command on macOS, Ctrl elsewhere.
+    pub fn command(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.logo
+        } else {
+            self.ctrl
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.shift || self.ctrl || self.alt || self.logo)
+    }
+}
+
+/// A key press or release. Held modifiers arrive separately as
+/// [`RuneWindowEvent::ModifiersChanged`] and are tracked by
+/// [`WindowCtx::modifiers`](crate::WindowCtx::modifiers).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEvent {
+    /// Logical key, after the keyboard layout is applied.
+    pub key: Key,
+    /// Text the key produces, if any. Text editors should insert this rather
+    /// than `key` so dead keys and layouts compose correctly; IME input
+    /// arrives separately as [`ImeEvent::Commit`].
+    pub text: Option<String>,
+    /// Whether the event is an auto-repeat of a held key.
+    pub repeat: bool,
+}
+
+/// Logical key value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A key that produces a character, e.g. `"a"` or `"A"` with Shift.
+    Character(String),
+    Named(NamedKey),
+    /// A key the platform couldn't identify, or a dead key.
+    Unidentified,
+}
+
+/// Non-character keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamedKey {
+    Enter,
+    Tab,
+    Space,
+    Backspace,
+    Delete,
+    Escape,
+    Insert,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Shift,
+    Control,
+    Alt,
+    Logo,
+    CapsLock,
+    ContextMenu,
+    /// Function key `F1`..=`F24`.
+    F(u8),
+}
+
+/// Scroll amount reported by a mouse wheel or trackpad.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollDelta {
+    /// Lines (or rows and columns) to scroll; typical of mouse wheels.
+    /// Positive `y` scrolls up, positive `x` scrolls left.
+    Lines { x: f32, y: f32 },
+    /// Physical pixels to scroll; typical of trackpads.
+    Pixels { x: f32, y: f32 },
+}
+
+/// Phase of a touch or gesture-driven scroll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+/// A single finger touching the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchEvent {
+    /// Identifier stable for the lifetime of one finger's contact.
+    pub id: u64,
+    pub phase: TouchPhase,
+    /// Position in physical pixels.
+    pub position: [f32; 2],
+    /// Normalized pressure in `0.0..=1.0`, if the platform reports it.
+    pub force: Option<f32>,
+}
+
+/// Input method (IME) composition events, used for CJK and other complex
+/// text entry. Text inputs should call
+/// [`WindowCtx::set_ime_allowed`](crate::WindowCtx::set_ime_allowed) to
+/// receive them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+    Enabled,
+    /// Composition text to display inline at the caret, replacing any
+    /// previous preedit. `cursor` is the byte range of the IME's cursor or
+    /// selection within `text`; an empty `text` clears the preedit.
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// Final text to insert at the caret.
+    Commit(String),
+    Disabled,
+}
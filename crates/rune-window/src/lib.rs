@@ -3,7 +3,7 @@
 //! Responsibilities:
 //! - Create window + surface + device/queue.
 //! - Manage surface configuration and resizing.
-//! - Dispatch basic events (redraw, resize, mouse move/click).
+//! - Dispatch window events (redraw, resize, mouse, keyboard, IME, file drops).
 //! - Expose helpers to acquire a frame for drawing and to request redraws.
 
 use anyhow::Result;
@@ -40,6 +40,7 @@ pub struct WindowCtx<'a> {
     size: PhysicalSize<u32>,
     scale_factor: f64,
     last_cursor_pos: [f32; 2],
+    modifiers: events::Modifiers,
     elwt: &'a EventLoopWindowTarget<()>,
 }
 
@@ -77,6 +78,23 @@ impl<'a> WindowCtx<'a> {
     pub fn mouse_pos(&self) -> [f32; 2] {
         self.last_cursor_pos
     }
+    /// Modifier keys currently held.
+    pub fn modifiers(&self) -> events::Modifiers {
+        self.modifiers
+    }
+    /// Enable or disable input method composition. Text inputs should enable
+    /// it while focused to receive [`events::ImeEvent`]s.
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.window.set_ime_allowed(allowed);
+    }
+    /// Tell the input method where the caret is, in physical pixels, so its
+    /// candidate window appears next to the text being composed.
+    pub fn set_ime_cursor_area(&self, position: [f32; 2], size: [f32; 2]) {
+        self.window.set_ime_cursor_area(
+            winit::dpi::PhysicalPosition::new(position[0], position[1]),
+            PhysicalSize::new(size[0], size[1]),
+        );
+    }
     pub fn request_redraw(&self) {
         self.window.request_redraw();
     }
@@ -161,6 +179,7 @@ impl RuneWindow {
 
     pub fn run(mut self, mut handler: impl EventHandler + 'static) -> Result<()> {
         let mut last_cursor_pos: [f32; 2] = [0.0, 0.0];
+        let mut modifiers = events::Modifiers::default();
         let mut needs_init = true;
 
         Ok(self.event_loop.run(move |event, elwt| {
@@ -176,6 +195,7 @@ impl RuneWindow {
                             size: self.size,
                             scale_factor: self.scale_factor,
                             last_cursor_pos,
+                            modifiers,
                             elwt,
                         };
                         let _ = handler.init(&mut ctx);
@@ -183,161 +203,33 @@ impl RuneWindow {
                     }
                 }
                 Event::WindowEvent { window_id, event } if window_id == self.window.id() => {
-                    match event {
-                        WindowEvent::CloseRequested => elwt.exit(),
+                    match &event {
+                        WindowEvent::CloseRequested => {
+                            elwt.exit();
+                            return;
+                        }
                         WindowEvent::Resized(new_size) => {
-                            self.size = new_size;
+                            self.size = *new_size;
                             if new_size.width > 0 && new_size.height > 0 {
                                 self.config.width = new_size.width;
                                 self.config.height = new_size.height;
                                 self.surface.configure(&self.device, &self.config);
                             }
-                            // synthesized event
-                            let mut ctx_for_event = WindowCtx {
-                                window: self.window,
-                                device: &self.device,
-                                queue: &self.queue,
-                                surface: &self.surface,
-                                config: &mut self.config,
-                                size: self.size,
-                                scale_factor: self.scale_factor,
-                                last_cursor_pos,
-                                elwt,
-                            };
-                            let _ = handler.on_event(
-                                &mut ctx_for_event,
-                                crate::events::RuneWindowEvent::Resized(new_size),
-                            );
-                            let mut ctx = WindowCtx {
-                                window: self.window,
-                                device: &self.device,
-                                queue: &self.queue,
-                                surface: &self.surface,
-                                config: &mut self.config,
-                                size: self.size,
-                                scale_factor: self.scale_factor,
-                                last_cursor_pos,
-                                elwt,
-                            };
-                            let _ = handler.on_resize(&mut ctx, new_size);
                         }
                         WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
-                            self.scale_factor = scale_factor;
-                            // synthesized event
-                            let mut ctx = WindowCtx {
-                                window: self.window,
-                                device: &self.device,
-                                queue: &self.queue,
-                                surface: &self.surface,
-                                config: &mut self.config,
-                                size: self.size,
-                                scale_factor: self.scale_factor,
-                                last_cursor_pos,
-                                elwt,
-                            };
-                            let _ = handler.on_event(
-                                &mut ctx,
-                                crate::events::RuneWindowEvent::ScaleFactorChanged(scale_factor),
-                            );
+                            self.scale_factor = *scale_factor;
                         }
                         WindowEvent::CursorMoved { position, .. } => {
                             last_cursor_pos = [position.x as f32, position.y as f32];
-                            // synthesized event
-                            let mut ctx_for_event = WindowCtx {
-                                window: self.window,
-                                device: &self.device,
-                                queue: &self.queue,
-                                surface: &self.surface,
-                                config: &mut self.config,
-                                size: self.size,
-                                scale_factor: self.scale_factor,
-                                last_cursor_pos,
-                                elwt,
-                            };
-                            let _ = handler.on_event(
-                                &mut ctx_for_event,
-                                crate::events::RuneWindowEvent::CursorMoved {
-                                    position: last_cursor_pos,
-                                },
-                            );
-                            let mut ctx = WindowCtx {
-                                window: self.window,
-                                device: &self.device,
-                                queue: &self.queue,
-                                surface: &self.surface,
-                                config: &mut self.config,
-                                size: self.size,
-                                scale_factor: self.scale_factor,
-                                last_cursor_pos,
-                                elwt,
-                            };
-                            let _ = handler.on_mouse_move(&mut ctx, last_cursor_pos);
                         }
-                        WindowEvent::MouseInput { state, button, .. } => {
-                            // synthesized event
-                            let mut ctx_for_event = WindowCtx {
-                                window: self.window,
-                                device: &self.device,
-                                queue: &self.queue,
-                                surface: &self.surface,
-                                config: &mut self.config,
-                                size: self.size,
-                                scale_factor: self.scale_factor,
-                                last_cursor_pos,
-                                elwt,
-                            };
-                            let _ = handler.on_event(
-                                &mut ctx_for_event,
-                                match state {
-                                    ElementState::Pressed => {
-                                        crate::events::RuneWindowEvent::MousePressed(button)
-                                    }
-                                    ElementState::Released => {
-                                        crate::events::RuneWindowEvent::MouseReleased(button)
-                                    }
-                                },
-                            );
-                            let mut ctx = WindowCtx {
-                                window: self.window,
-                                device: &self.device,
-                                queue: &self.queue,
-                                surface: &self.surface,
-                                config: &mut self.config,
-                                size: self.size,
-                                scale_factor: self.scale_factor,
-                                last_cursor_pos,
-                                elwt,
-                            };
-                            let _ = handler.on_mouse_input(&mut ctx, state, button);
+                        WindowEvent::ModifiersChanged(m) => {
+                            modifiers = events::translate_modifiers(m.state());
                         }
                         _ => {}
                     }
-                }
-                Event::AboutToWait => {
-                    // Ensure at least one redraw after init on platforms where
-                    // request_redraw during init may be deferred.
-                    self.window.request_redraw();
-                }
-                Event::WindowEvent {
-                    window_id,
-                    event: WindowEvent::RedrawRequested,
-                } if window_id == self.window.id() => {
-                    // synthesized event
-                    let mut ctx_for_event = WindowCtx {
-                        window: self.window,
-                        device: &self.device,
-                        queue: &self.queue,
-                        surface: &self.surface,
-                        config: &mut self.config,
-                        size: self.size,
-                        scale_factor: self.scale_factor,
-                        last_cursor_pos,
-                        elwt,
+                    let Some(ev) = events::translate_window_event(&event) else {
+                        return;
                     };
-                    let _ = handler.on_event(
-                        &mut ctx_for_event,
-                        crate::events::RuneWindowEvent::RedrawRequested,
-                    );
                     let mut ctx = WindowCtx {
                         window: self.window,
                         device: &self.device,
@@ -347,9 +239,25 @@ impl RuneWindow {
                         size: self.size,
                         scale_factor: self.scale_factor,
                         last_cursor_pos,
+                        modifiers,
                         elwt,
                     };
-                    let _ = handler.on_redraw(&mut ctx);
+                    // synthesized event first, then the typed callback
+                    let _ = handler.on_event(&mut ctx, ev);
+                    let _ = match typed_callback(&event) {
+                        Some(Callback::Redraw) => handler.on_redraw(&mut ctx),
+                        Some(Callback::Resize(size)) => handler.on_resize(&mut ctx, size),
+                        Some(Callback::MouseMove(pos)) => handler.on_mouse_move(&mut ctx, pos),
+                        Some(Callback::MouseInput(state, button)) => {
+                            handler.on_mouse_input(&mut ctx, state, button)
+                        }
+                        None => Ok(()),
+                    };
+                }
+                Event::AboutToWait => {
+                    // Ensure at least one redraw after init on platforms where
+                    // request_redraw during init may be deferred.
+                    self.window.request_redraw();
                 }
                 Event::WindowEvent { .. } => {}
                 Event::DeviceEvent { .. } => {}
@@ -366,3 +274,53 @@ impl RuneWindow {
         self.window
     }
 }
+
+/// Typed [`EventHandler`] callback that follows `on_event` for a window event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Callback {
+    Redraw,
+    Resize(PhysicalSize<u32>),
+    MouseMove([f32; 2]),
+    MouseInput(ElementState, MouseButton),
+}
+
+fn typed_callback(event: &WindowEvent) -> Option<Callback> {
+    match event {
+        WindowEvent::RedrawRequested => Some(Callback::Redraw),
+        WindowEvent::Resized(size) => Some(Callback::Resize(*size)),
+        WindowEvent::CursorMoved { position, .. } => {
+            Some(Callback::MouseMove([position.x as f32, position.y as f32]))
+        }
+        WindowEvent::MouseInput { state, button, .. } => {
+            Some(Callback::MouseInput(*state, *button))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use events::RuneWindowEvent;
+
+    #[test]
+    fn redraw_requested_reaches_on_event_then_on_redraw() {
+        let event = WindowEvent::RedrawRequested;
+        assert!(matches!(
+            events::translate_window_event(&event),
+            Some(RuneWindowEvent::RedrawRequested)
+        ));
+        assert_eq!(typed_callback(&event), Some(Callback::Redraw));
+    }
+
+    #[test]
+    fn events_without_typed_callback_only_reach_on_event() {
+        let event = WindowEvent::Focused(true);
+        assert!(events::translate_window_event(&event).is_some());
+        assert_eq!(typed_callback(&event), None);
+        assert_eq!(
+            typed_callback(&WindowEvent::Resized(PhysicalSize::new(640, 480))),
+            Some(Callback::Resize(PhysicalSize::new(640, 480)))
+        );
+    }
+}